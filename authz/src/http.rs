@@ -1,5 +1,6 @@
 //! HTTP authorisation helpers.
 
+use base64::{prelude::BASE64_STANDARD, Engine};
 use http::HeaderValue;
 
 /// We strip off the "authorization" header from the request, to prevent it from being accidentally logged
@@ -14,6 +15,35 @@ impl AuthorizationHeaderExtension {
     }
 }
 
+impl AuthorizationHeaderExtension {
+    /// Extract the request token from the wrapped authorization header value,
+    /// if any.
+    ///
+    /// The `Token` and `Bearer` schemes carry the token as-is, while the
+    /// `Basic` scheme carries the token as the password.
+    pub fn token(&self) -> Option<Vec<u8>> {
+        self.0.as_ref().and_then(extract_header_token)
+    }
+}
+
+fn extract_header_token(header_value: &'_ HeaderValue) -> Option<Vec<u8>> {
+    let mut parts = header_value.as_bytes().splitn(2, |&v| v == b' ');
+    let token = match parts.next()? {
+        b"Token" | b"Bearer" => parts.next()?.to_vec(),
+        b"Basic" => parts
+            .next()
+            .and_then(|v| BASE64_STANDARD.decode(v).ok())?
+            .splitn(2, |&v| v == b':')
+            .nth(1)?
+            .to_vec(),
+        _ => return None,
+    };
+    if token.is_empty() {
+        return None;
+    }
+    Some(token)
+}
+
 impl std::fmt::Debug for AuthorizationHeaderExtension {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("AuthorizationHeaderExtension(...)")
@@ -1,7 +1,8 @@
 //! Backend that supports custom removal / expiry of keys
+use iox_time::Time;
 use metric::U64Counter;
 use parking_lot::Mutex;
-use std::{fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
+use std::{collections::HashSet, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
 
 use crate::{
     backend::policy::{CacheBackend, CallbackHandle, ChangeRequest, Subscriber},
@@ -15,8 +16,9 @@ where
     K: Clone + Eq + Debug + Hash + Ord + Send + 'static,
     V: Clone + Debug + Send + 'static,
 {
-    // the policy itself doesn't do anything, the handles will do all the work
-    _phantom: PhantomData<(K, V)>,
+    // the policy only tracks the cached keys, the handles will do all the work
+    keys: Arc<Mutex<HashSet<K>>>,
+    _phantom: PhantomData<V>,
 }
 
 impl<K, V> RemoveIfPolicy<K, V>
@@ -49,6 +51,7 @@ where
 
         let handle = RemoveIfHandle {
             callback_handle: Arc::new(Mutex::new(None)),
+            keys: Default::default(),
             metric_removed_by_predicate,
         };
         let handle_captured = handle.clone();
@@ -56,6 +59,7 @@ where
         let policy_constructor = move |callback_handle| {
             *handle_captured.callback_handle.lock() = Some(callback_handle);
             Self {
+                keys: handle_captured.keys,
                 _phantom: PhantomData::default(),
             }
         };
@@ -71,6 +75,16 @@ where
 {
     type K = K;
     type V = V;
+
+    fn set(&mut self, k: &K, _v: &V, _now: Time) -> Vec<ChangeRequest<'static, K, V>> {
+        self.keys.lock().insert(k.clone());
+        vec![]
+    }
+
+    fn remove(&mut self, k: &K, _now: Time) -> Vec<ChangeRequest<'static, K, V>> {
+        self.keys.lock().remove(k);
+        vec![]
+    }
 }
 
 /// Handle created by [`RemoveIfPolicy`] that can be used to evict data from caches.
@@ -83,6 +97,7 @@ where
     V: Clone + Debug + Send + 'static,
 {
     callback_handle: Arc<Mutex<Option<CallbackHandle<K, V>>>>,
+    keys: Arc<Mutex<HashSet<K>>>,
     metric_removed_by_predicate: U64Counter,
 }
 
@@ -124,6 +139,42 @@ where
         removed
     }
 
+    /// "remove" all keys (aka remove them from the shared backend) for
+    /// which the specified predicate is true. Returns the number of
+    /// removed keys.
+    ///
+    /// Note that the predicate function is called while the lock is
+    /// held (and thus the inner backend can't be concurrently accessed
+    pub fn remove_all_if<P>(&self, mut predicate: P) -> usize
+    where
+        P: FnMut(V) -> bool,
+    {
+        let mut guard = self.callback_handle.lock();
+        let handle = match guard.as_mut() {
+            Some(handle) => handle,
+            None => return 0,
+        };
+
+        let metric_removed_by_predicate = self.metric_removed_by_predicate.clone();
+        let keys = Arc::clone(&self.keys);
+        let mut removed = 0;
+        let removed_captured = &mut removed;
+        handle.execute_requests(vec![ChangeRequest::from_fn(move |backend| {
+            let keys = keys.lock().iter().cloned().collect::<Vec<_>>();
+            for k in keys {
+                if let Some(v) = backend.get_untracked(&k) {
+                    if predicate(v) {
+                        metric_removed_by_predicate.inc(1);
+                        backend.remove(&k);
+                        *removed_captured += 1;
+                    }
+                }
+            }
+        })]);
+
+        removed
+    }
+
     /// Performs [`remove_if`](Self::remove_if) and [`GET`](Cache::get) in one go.
     ///
     /// Ensures that these two actions interact correctly.
@@ -237,6 +288,38 @@ mod tests {
         assert_eq!(get_removed_metric(&metric_registry), 1);
     }
 
+    #[test]
+    fn test_remove_all_if() {
+        let metric_registry = metric::Registry::new();
+        let time_provider = Arc::new(MockProvider::new(Time::MIN));
+        let mut backend: PolicyBackend<u8, String> = PolicyBackend::hashmap_backed(time_provider);
+        let (policy_constructor, handle) =
+            RemoveIfPolicy::create_constructor_and_handle("my_cache", &metric_registry);
+        backend.add_policy(policy_constructor);
+        backend.set(1, "foo".into());
+        backend.set(2, "bar".into());
+        backend.set(3, "baz".into());
+        backend.remove(&3);
+
+        assert_eq!(handle.remove_all_if(|v| v == "zzz"), 0);
+        assert_eq!(backend.get(&1), Some("foo".into()));
+        assert_eq!(backend.get(&2), Some("bar".into()));
+        assert_eq!(get_removed_metric(&metric_registry), 0);
+
+        assert_eq!(handle.remove_all_if(|v| v == "foo"), 1);
+        assert_eq!(backend.get(&1), None);
+        assert_eq!(backend.get(&2), Some("bar".into()));
+        assert_eq!(get_removed_metric(&metric_registry), 1);
+
+        backend.set(1, "foo".into());
+        assert_eq!(handle.remove_all_if(|_| true), 2);
+        assert_eq!(backend.get(&1), None);
+        assert_eq!(backend.get(&2), None);
+        assert_eq!(get_removed_metric(&metric_registry), 3);
+
+        assert_eq!(handle.remove_all_if(|_| true), 0);
+    }
+
     #[test]
     fn test_not_linked() {
         let metric_registry = metric::Registry::new();
@@ -249,6 +332,7 @@ mod tests {
         assert_eq!(get_removed_metric(&metric_registry), 0);
 
         assert!(!handle.remove_if(&1, |v| v == "zzz"));
+        assert_eq!(handle.remove_all_if(|_| true), 0);
         assert_eq!(get_removed_metric(&metric_registry), 0);
     }
 
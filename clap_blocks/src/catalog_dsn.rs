@@ -47,6 +47,12 @@ fn default_hotswap_poll_interval_timeout() -> &'static str {
     Box::leak(Box::new(s))
 }
 
+fn default_max_replica_lag() -> &'static str {
+    let s =
+        humantime::format_duration(PostgresConnectionOptions::DEFAULT_MAX_REPLICA_LAG).to_string();
+    Box::leak(Box::new(s))
+}
+
 /// CLI config for catalog DSN.
 #[derive(Debug, Clone, Default, clap::Parser)]
 pub struct CatalogDsnConfig {
@@ -117,6 +123,30 @@ pub struct CatalogDsnConfig {
         value_parser = humantime::parse_duration,
     )]
     pub hotswap_poll_interval: Duration,
+
+    /// Connection strings of read replicas of a PostgreSQL-based catalog, separated by commas.
+    ///
+    /// Reads that tolerate stale results are spread over the replicas, all other operations use
+    /// the primary catalog. Like the catalog DSN, a replica DSN may point to a file (i.e. start
+    /// with `dsn-file://`).
+    #[clap(
+        long = "catalog-replica-dsns",
+        env = "INFLUXDB_IOX_CATALOG_REPLICA_DSNS",
+        value_delimiter = ',',
+        action
+    )]
+    pub replica_dsns: Vec<String>,
+
+    /// Maximum replication lag of a catalog read replica that serves reads.
+    ///
+    /// Reads fall back to the primary catalog while a replica lags behind by more than this.
+    #[clap(
+        long = "catalog-max-replica-lag",
+        env = "INFLUXDB_IOX_CATALOG_MAX_REPLICA_LAG",
+        default_value = default_max_replica_lag(),
+        value_parser = humantime::parse_duration,
+    )]
+    pub max_replica_lag: Duration,
 }
 
 /// Catalog type.
@@ -167,6 +197,8 @@ impl CatalogDsnConfig {
             connect_timeout: PostgresConnectionOptions::DEFAULT_CONNECT_TIMEOUT,
             idle_timeout: PostgresConnectionOptions::DEFAULT_IDLE_TIMEOUT,
             hotswap_poll_interval: PostgresConnectionOptions::DEFAULT_HOTSWAP_POLL_INTERVAL,
+            replica_dsns: vec![],
+            max_replica_lag: PostgresConnectionOptions::DEFAULT_MAX_REPLICA_LAG,
         }
     }
 
@@ -202,6 +234,8 @@ impl CatalogDsnConfig {
                     connect_timeout: self.connect_timeout,
                     idle_timeout: self.idle_timeout,
                     hotswap_poll_interval: self.hotswap_poll_interval,
+                    replica_dsns: self.replica_dsns.clone(),
+                    max_replica_lag: self.max_replica_lag,
                 };
                 Arc::new(
                     PostgresCatalog::connect(options, metrics)
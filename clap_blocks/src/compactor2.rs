@@ -1,6 +1,6 @@
 //! CLI config for compactor2-related commands
 
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 /// Compaction type.
 #[derive(Debug, Default, Clone, Copy, PartialEq, clap::ValueEnum)]
@@ -254,6 +254,36 @@ pub struct Compactor2Config {
     )]
     pub shard_id: Option<usize>,
 
+    /// Claim partitions through time-limited leases in the catalog
+    /// and use this lease duration in seconds.
+    ///
+    /// With leases, any number of compactors can share the work
+    /// without static sharding. Leases of partitions that are being
+    /// compacted are renewed periodically. If a compactor stops, its
+    /// partitions are taken over by the other compactors once the
+    /// leases expired.
+    ///
+    /// This cannot be combined with the shard count and shard ID. If
+    /// not provided, leases are disabled.
+    #[clap(
+        long = "compaction-partition-lease-secs",
+        env = "INFLUXDB_IOX_COMPACTION_PARTITION_LEASE_SECS",
+        action
+    )]
+    pub partition_lease_secs: Option<NonZeroU64>,
+
+    /// Name of this compactor, used as the owner of partition leases.
+    ///
+    /// Must be unique across all compactors sharing the same
+    /// catalog. If not provided, a random name is generated at
+    /// startup.
+    #[clap(
+        long = "compaction-partition-lease-owner",
+        env = "INFLUXDB_IOX_COMPACTION_PARTITION_LEASE_OWNER",
+        action
+    )]
+    pub partition_lease_owner: Option<String>,
+
     /// Minimum number of L1 files to compact to L2.
     ///
     /// If there are more than this many L1 (by definition non
@@ -343,4 +373,27 @@ mod tests {
         );
         assert_contains!(&error, "[possible values: hot, cold]");
     }
+
+    #[test]
+    fn partition_lease_secs_must_not_be_zero() {
+        let config = Compactor2Config::try_parse_from([
+            "my_binary",
+            "--compaction-partition-lease-secs",
+            "30",
+        ])
+        .unwrap();
+        assert_eq!(config.partition_lease_secs, NonZeroU64::new(30));
+
+        let error = Compactor2Config::try_parse_from([
+            "my_binary",
+            "--compaction-partition-lease-secs",
+            "0",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(
+            &error,
+            "invalid value '0' for '--compaction-partition-lease-secs"
+        );
+    }
 }
@@ -71,4 +71,28 @@ pub struct GarbageCollectorConfig {
         env = "INFLUXDB_IOX_GC_RETENTION_SLEEP_INTERVAL_MINUTES"
     )]
     pub retention_sleep_interval_minutes: u64,
+
+    /// Soft-deleted namespaces can be undeleted for this duration. Afterwards, their parquet
+    /// files are flagged for deletion, and the namespaces are removed from the catalog once the
+    /// parquet file deleter has removed their files.
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    ///
+    /// Shares its environment variable with the router, which rejects undeletes after this
+    /// duration. If not specified, defaults to 14 days.
+    #[clap(
+        long,
+        default_value = "14d",
+        value_parser = parse_duration,
+        env = "INFLUXDB_IOX_NAMESPACE_DELETE_GRACE_PERIOD"
+    )]
+    pub namespace_delete_grace_period: Duration,
+
+    /// Number of minutes to sleep between iterations of the namespace purger.
+    /// Defaults to 60 minutes.
+    #[clap(
+        long,
+        default_value_t = 60,
+        env = "INFLUXDB_IOX_GC_NAMESPACE_PURGE_SLEEP_INTERVAL_MINUTES"
+    )]
+    pub namespace_purge_sleep_interval_minutes: u64,
 }
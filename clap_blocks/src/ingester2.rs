@@ -69,4 +69,27 @@ pub struct Ingester2Config {
         action
     )]
     pub persist_hot_partition_cost: usize,
+
+    /// Maintain a cache of the most recent value of each field for every
+    /// series, seeded from persisted Parquet files at startup, and use it to
+    /// answer qualifying `last()` selector queries.
+    #[clap(
+        long = "last-value-cache",
+        env = "INFLUXDB_IOX_LAST_VALUE_CACHE",
+        action
+    )]
+    pub last_value_cache: bool,
+
+    /// The maximum number of series the last value cache tracks for a single
+    /// table.
+    ///
+    /// Tables exceeding this limit stop being cached, and queries against
+    /// them are answered without the cache.
+    #[clap(
+        long = "last-value-cache-max-series",
+        env = "INFLUXDB_IOX_LAST_VALUE_CACHE_MAX_SERIES",
+        default_value = "100000",
+        action
+    )]
+    pub last_value_cache_max_series: usize,
 }
@@ -4,7 +4,8 @@ use crate::{
     ingester_address::IngesterAddress,
     single_tenant::{CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG},
 };
-use std::{collections::HashMap, num::NonZeroUsize};
+use data_types::QueryPriority;
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
 
 /// CLI config for querier configuration
 #[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
@@ -23,6 +24,28 @@ pub struct QuerierConfig {
     )]
     pub num_query_threads: Option<NonZeroUsize>,
 
+    /// The number of threads of a dedicated pool for interactive queries.
+    ///
+    /// If not specified, interactive queries run on the pool of
+    /// `--num-query-threads`.
+    #[clap(
+        long = "num-interactive-query-threads",
+        env = "INFLUXDB_IOX_NUM_INTERACTIVE_QUERY_THREADS",
+        action
+    )]
+    pub num_interactive_query_threads: Option<NonZeroUsize>,
+
+    /// The number of threads of a dedicated pool for batch queries.
+    ///
+    /// If not specified, batch queries run on the pool of
+    /// `--num-query-threads`.
+    #[clap(
+        long = "num-batch-query-threads",
+        env = "INFLUXDB_IOX_NUM_BATCH_QUERY_THREADS",
+        action
+    )]
+    pub num_batch_query_threads: Option<NonZeroUsize>,
+
     /// Size of memory pool used during query exec, in bytes.
     ///
     /// If queries attempt to allocate more than this many bytes
@@ -81,6 +104,39 @@ pub struct QuerierConfig {
     )]
     pub max_concurrent_queries: usize,
 
+    /// Limit the number of concurrent interactive queries.
+    ///
+    /// Queries beyond this limit are rejected. If not specified, interactive
+    /// queries are only limited by `--max-concurrent-queries`.
+    #[clap(
+        long = "max-concurrent-interactive-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_INTERACTIVE_QUERIES",
+        action
+    )]
+    pub max_concurrent_interactive_queries: Option<usize>,
+
+    /// Limit the number of concurrent normal priority queries.
+    ///
+    /// Queries beyond this limit are rejected. If not specified, normal
+    /// priority queries are only limited by `--max-concurrent-queries`.
+    #[clap(
+        long = "max-concurrent-normal-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_NORMAL_QUERIES",
+        action
+    )]
+    pub max_concurrent_normal_queries: Option<usize>,
+
+    /// Limit the number of concurrent batch queries.
+    ///
+    /// Queries beyond this limit are rejected. If not specified, batch queries
+    /// are only limited by `--max-concurrent-queries`.
+    #[clap(
+        long = "max-concurrent-batch-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_BATCH_QUERIES",
+        action
+    )]
+    pub max_concurrent_batch_queries: Option<usize>,
+
     /// After how many ingester query errors should the querier enter circuit breaker mode?
     ///
     /// The querier normally contacts the ingester for any unpersisted data during query planning.
@@ -104,6 +160,31 @@ pub struct QuerierConfig {
     )]
     pub ingester_circuit_breaker_threshold: u64,
 
+    /// Cache query results.
+    ///
+    /// Results of SQL queries are cached in the RAM pool for data (see `--ram-pool-data-bytes`)
+    /// and reused as long as the query reads exactly the same chunks. Queries with relative time
+    /// ranges (e.g. `now() - INTERVAL '1 hour'`) that bin their output via `date_bin` are cached
+    /// per time bucket, see `--query-result-cache-bucket-width`.
+    #[clap(
+        long = "query-result-cache",
+        env = "INFLUXDB_IOX_QUERY_RESULT_CACHE",
+        action
+    )]
+    pub query_result_cache: bool,
+
+    /// Width of the time buckets used to cache results of queries with relative time ranges.
+    ///
+    /// Must be a multiple of the `date_bin` stride of a query for the query to be cached. Set to
+    /// `0s` to only cache queries that do not depend on the query time.
+    #[clap(
+        long = "query-result-cache-bucket-width",
+        env = "INFLUXDB_IOX_QUERY_RESULT_CACHE_BUCKET_WIDTH",
+        default_value = "1h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub query_result_cache_bucket_width: Duration,
+
     /// DataFusion config.
     #[clap(
         long = "datafusion-config",
@@ -136,6 +217,46 @@ impl QuerierConfig {
     pub fn max_concurrent_queries(&self) -> usize {
         self.max_concurrent_queries
     }
+
+    /// Number of threads of the dedicated pools of query priority classes.
+    ///
+    /// Classes without an entry run on the shared query pool.
+    pub fn query_priority_threads(&self) -> HashMap<QueryPriority, NonZeroUsize> {
+        [
+            (
+                QueryPriority::Interactive,
+                self.num_interactive_query_threads,
+            ),
+            (QueryPriority::Batch, self.num_batch_query_threads),
+        ]
+        .into_iter()
+        .filter_map(|(priority, threads)| Some((priority, threads?)))
+        .collect()
+    }
+
+    /// Number of queries of each priority class allowed to run concurrently.
+    ///
+    /// Classes without an entry are only limited by
+    /// [`max_concurrent_queries`](Self::max_concurrent_queries).
+    pub fn max_concurrent_queries_by_priority(&self) -> HashMap<QueryPriority, usize> {
+        [
+            (
+                QueryPriority::Interactive,
+                self.max_concurrent_interactive_queries,
+            ),
+            (QueryPriority::Normal, self.max_concurrent_normal_queries),
+            (QueryPriority::Batch, self.max_concurrent_batch_queries),
+        ]
+        .into_iter()
+        .filter_map(|(priority, max)| Some((priority, max?)))
+        .collect()
+    }
+
+    /// Bucket width of the query result cache, or `None` if the cache is disabled.
+    pub fn query_result_cache_bucket_width(&self) -> Option<Duration> {
+        self.query_result_cache
+            .then_some(self.query_result_cache_bucket_width)
+    }
 }
 
 fn parse_datafusion_config(
@@ -182,6 +303,48 @@ mod tests {
         assert_eq!(actual.num_query_threads(), None);
         assert!(actual.ingester_addresses.is_empty());
         assert!(actual.datafusion_config.is_empty());
+        assert_eq!(actual.query_result_cache_bucket_width(), None);
+        assert!(actual.query_priority_threads().is_empty());
+        assert!(actual.max_concurrent_queries_by_priority().is_empty());
+    }
+
+    #[test]
+    fn test_query_priorities() {
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--num-interactive-query-threads",
+            "2",
+            "--max-concurrent-interactive-queries",
+            "20",
+            "--max-concurrent-batch-queries",
+            "3",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            actual.query_priority_threads(),
+            HashMap::from([(QueryPriority::Interactive, NonZeroUsize::new(2).unwrap())]),
+        );
+        assert_eq!(
+            actual.max_concurrent_queries_by_priority(),
+            HashMap::from([(QueryPriority::Interactive, 20), (QueryPriority::Batch, 3)]),
+        );
+    }
+
+    #[test]
+    fn test_query_result_cache() {
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--query-result-cache",
+            "--query-result-cache-bucket-width",
+            "10m",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            actual.query_result_cache_bucket_width(),
+            Some(Duration::from_secs(600))
+        );
     }
 
     #[test]
@@ -52,6 +52,22 @@ pub struct Router2Config {
     )]
     pub http_request_limit: usize,
 
+    /// Parse line protocol write requests as their body is read, writing
+    /// them in chunks of roughly this many bytes of decoded line protocol
+    /// instead of buffering the whole request.
+    ///
+    /// This bounds the memory used by large write requests, which are then
+    /// no longer written atomically. The maximum HTTP request size only limits
+    /// the length of a single line of a streamed request.
+    ///
+    /// Disabled by default.
+    #[clap(
+        long = "http-write-stream-chunk-bytes",
+        env = "INFLUXDB_IOX_HTTP_WRITE_STREAM_CHUNK_BYTES",
+        action
+    )]
+    pub http_write_stream_chunk_bytes: Option<usize>,
+
     /// gRPC address for the router to talk with the ingesters. For
     /// example:
     ///
@@ -112,6 +128,19 @@ pub struct Router2Config {
     )]
     pub namespace_autocreation_enabled: bool,
 
+    /// How long a soft-deleted namespace can be undeleted. Afterwards, the garbage collector
+    /// purges it.
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    ///
+    /// Shares its environment variable with the garbage collector.
+    #[clap(
+        long = "namespace-delete-grace-period",
+        env = "INFLUXDB_IOX_NAMESPACE_DELETE_GRACE_PERIOD",
+        default_value = "14d",
+        value_parser = humantime::parse_duration
+    )]
+    pub namespace_delete_grace_period: Duration,
+
     /// A "strftime" format string used to derive the partition key from the row
     /// timestamps.
     ///
@@ -0,0 +1,433 @@
+//! Runtime administration of the compactor.
+//!
+//! The [`CompactionAdmin`] is shared between the compactor and an external control surface (e.g. the gRPC API). It
+//! allows operators to request the immediate compaction of specific partitions, to inspect the partitions that are
+//! currently being compacted and to cancel running compaction jobs.
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use data_types::PartitionId;
+use iox_time::{Time, TimeProvider};
+use tokio_util::sync::CancellationToken;
+
+/// Number of finished jobs that are kept so that their outcome can be inspected.
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// State of a compaction job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactionJobState {
+    /// Requested via the admin API but not picked up yet.
+    Queued,
+
+    /// Currently being compacted.
+    Running,
+
+    /// Finished without error.
+    Succeeded,
+
+    /// Finished with an error, or skipped by the compactor after it was requested (e.g. because another compactor
+    /// holds the lease of the partition), see [`CompactionJob::last_error`].
+    Failed,
+
+    /// Cancelled via the admin API.
+    Cancelled,
+}
+
+/// Status of a compaction job for a single partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionJob {
+    /// The partition that is compacted.
+    pub partition_id: PartitionId,
+
+    /// Current state.
+    pub state: CompactionJobState,
+
+    /// Whether the job was requested via the admin API.
+    pub requested: bool,
+
+    /// When the job was requested via the admin API.
+    pub enqueued_at: Option<Time>,
+
+    /// When the compactor started working on the partition.
+    pub started_at: Option<Time>,
+
+    /// When the compactor stopped working on the partition.
+    pub finished_at: Option<Time>,
+
+    /// Number of compaction rounds that were completed so far.
+    pub rounds_completed: usize,
+
+    /// Number of files that the current (or last) round started with.
+    pub files_in_round: usize,
+
+    /// Error message of the last failed attempt, if any.
+    pub last_error: Option<String>,
+}
+
+impl CompactionJob {
+    fn new(partition_id: PartitionId) -> Self {
+        Self {
+            partition_id,
+            state: CompactionJobState::Queued,
+            requested: false,
+            enqueued_at: None,
+            started_at: None,
+            finished_at: None,
+            rounds_completed: 0,
+            files_in_round: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// Outcome of a compaction job, reported by the driver.
+#[derive(Debug)]
+pub(crate) enum JobOutcome {
+    Succeeded,
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Partitions requested via [`CompactionAdmin::enqueue`] that were not yet handed out.
+    queue: VecDeque<PartitionId>,
+
+    /// Jobs, keyed by partition.
+    jobs: HashMap<PartitionId, CompactionJob>,
+
+    /// Cancellation tokens of running jobs.
+    cancel_tokens: HashMap<PartitionId, CancellationToken>,
+
+    /// Finished jobs, oldest first. Used to bound the size of `jobs`.
+    finished: VecDeque<PartitionId>,
+}
+
+/// Shared admin state of a compactor.
+///
+/// See [module-level docs](self).
+#[derive(Debug)]
+pub struct CompactionAdmin {
+    state: Mutex<State>,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl CompactionAdmin {
+    /// Create new, empty admin state.
+    pub fn new(time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            state: Default::default(),
+            time_provider,
+        }
+    }
+
+    /// Request compaction of the given partitions.
+    ///
+    /// Partitions that are already queued are ignored. Returns the number of newly queued partitions.
+    pub fn enqueue(&self, partition_ids: impl IntoIterator<Item = PartitionId>) -> usize {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock().expect("not poisoned");
+
+        let mut n_queued = 0;
+        for partition_id in partition_ids {
+            if state.queue.contains(&partition_id) {
+                continue;
+            }
+            state.queue.push_back(partition_id);
+            n_queued += 1;
+
+            let job = state
+                .jobs
+                .entry(partition_id)
+                .or_insert_with(|| CompactionJob::new(partition_id));
+            job.requested = true;
+            job.enqueued_at = Some(now);
+            if job.state != CompactionJobState::Running {
+                job.state = CompactionJobState::Queued;
+            }
+        }
+
+        n_queued
+    }
+
+    /// List all known jobs: queued, running and recently finished ones, ordered by partition ID.
+    pub fn jobs(&self) -> Vec<CompactionJob> {
+        let state = self.state.lock().expect("not poisoned");
+        let mut jobs: Vec<_> = state.jobs.values().cloned().collect();
+        jobs.sort_by_key(|job| job.partition_id);
+        jobs
+    }
+
+    /// Cancel the compaction of the given partition.
+    ///
+    /// Queued partitions are removed from the queue, running jobs are aborted. Returns `false` if the partition is
+    /// neither queued nor running.
+    pub fn cancel(&self, partition_id: PartitionId) -> bool {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock().expect("not poisoned");
+
+        if let Some(token) = state.cancel_tokens.get(&partition_id) {
+            // the driver reports the outcome once the job has stopped
+            token.cancel();
+            return true;
+        }
+
+        let len_before = state.queue.len();
+        state.queue.retain(|p| *p != partition_id);
+        if state.queue.len() == len_before {
+            return false;
+        }
+
+        if let Some(job) = state.jobs.get_mut(&partition_id) {
+            job.state = CompactionJobState::Cancelled;
+            job.finished_at = Some(now);
+        }
+        Self::push_finished(&mut state, partition_id);
+        true
+    }
+
+    /// Take all queued partitions.
+    pub(crate) fn take_queued(&self) -> Vec<PartitionId> {
+        let mut state = self.state.lock().expect("not poisoned");
+        state.queue.drain(..).collect()
+    }
+
+    /// Report that the compactor started to work on the given partition.
+    ///
+    /// The returned token is cancelled when the job shall be aborted.
+    pub(crate) fn job_started(&self, partition_id: PartitionId) -> CancellationToken {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock().expect("not poisoned");
+
+        state.finished.retain(|p| *p != partition_id);
+        let job = state
+            .jobs
+            .entry(partition_id)
+            .or_insert_with(|| CompactionJob::new(partition_id));
+        job.state = CompactionJobState::Running;
+        job.started_at = Some(now);
+        job.finished_at = None;
+        job.rounds_completed = 0;
+        job.files_in_round = 0;
+
+        let token = CancellationToken::new();
+        state.cancel_tokens.insert(partition_id, token.clone());
+        token
+    }
+
+    /// Report the start of a new compaction round.
+    pub(crate) fn round_started(
+        &self,
+        partition_id: PartitionId,
+        rounds_completed: usize,
+        n_files: usize,
+    ) {
+        let mut state = self.state.lock().expect("not poisoned");
+        if let Some(job) = state.jobs.get_mut(&partition_id) {
+            job.rounds_completed = rounds_completed;
+            job.files_in_round = n_files;
+        }
+    }
+
+    /// Report that the compactor stopped working on the given partition.
+    pub(crate) fn job_finished(&self, partition_id: PartitionId, outcome: JobOutcome) {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock().expect("not poisoned");
+
+        state.cancel_tokens.remove(&partition_id);
+        if let Some(job) = state.jobs.get_mut(&partition_id) {
+            // a job that was re-queued while running stays queued
+            if !state.queue.contains(&partition_id) {
+                job.state = match &outcome {
+                    JobOutcome::Succeeded => CompactionJobState::Succeeded,
+                    JobOutcome::Failed(_) => CompactionJobState::Failed,
+                    JobOutcome::Cancelled => CompactionJobState::Cancelled,
+                };
+            } else {
+                job.state = CompactionJobState::Queued;
+            }
+            job.finished_at = Some(now);
+            if let JobOutcome::Failed(e) = outcome {
+                job.last_error = Some(e);
+            }
+        }
+
+        if !state.queue.contains(&partition_id) {
+            Self::push_finished(&mut state, partition_id);
+        }
+    }
+
+    /// Report that the compactor is done with the given partition, whether it worked on it or not.
+    ///
+    /// A requested job that was taken from the queue but never started was skipped, e.g. because another compactor
+    /// holds the lease of the partition. It is marked as failed with the given reason so that it does not stay queued
+    /// forever.
+    pub(crate) fn partition_done(
+        &self,
+        partition_id: PartitionId,
+        skip_reason: impl FnOnce() -> String,
+    ) {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock().expect("not poisoned");
+
+        if state.queue.contains(&partition_id) || state.cancel_tokens.contains_key(&partition_id) {
+            return;
+        }
+        let Some(job) = state.jobs.get_mut(&partition_id) else {
+            return;
+        };
+        if job.state != CompactionJobState::Queued {
+            return;
+        }
+
+        job.state = CompactionJobState::Failed;
+        job.finished_at = Some(now);
+        job.last_error = Some(skip_reason());
+        Self::push_finished(&mut state, partition_id);
+    }
+
+    fn push_finished(state: &mut State, partition_id: PartitionId) {
+        state.finished.retain(|p| *p != partition_id);
+        state.finished.push_back(partition_id);
+        while state.finished.len() > MAX_FINISHED_JOBS {
+            if let Some(p) = state.finished.pop_front() {
+                state.jobs.remove(&p);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_time::MockProvider;
+
+    use super::*;
+
+    fn admin() -> CompactionAdmin {
+        CompactionAdmin::new(Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))))
+    }
+
+    #[test]
+    fn test_enqueue_and_take() {
+        let admin = admin();
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+
+        assert_eq!(admin.enqueue([p_1, p_2, p_1]), 2);
+        assert_eq!(admin.enqueue([p_2]), 0);
+
+        let jobs = admin.jobs();
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs
+            .iter()
+            .all(|job| job.state == CompactionJobState::Queued && job.requested));
+
+        assert_eq!(admin.take_queued(), vec![p_1, p_2]);
+        assert_eq!(admin.take_queued(), vec![]);
+    }
+
+    #[test]
+    fn test_job_lifecycle() {
+        let admin = admin();
+        let p = PartitionId::new(1);
+
+        admin.job_started(p);
+        admin.round_started(p, 0, 10);
+        admin.round_started(p, 1, 3);
+
+        let job = admin.jobs().remove(0);
+        assert_eq!(job.state, CompactionJobState::Running);
+        assert!(!job.requested);
+        assert_eq!(job.rounds_completed, 1);
+        assert_eq!(job.files_in_round, 3);
+
+        admin.job_finished(p, JobOutcome::Failed(String::from("foo")));
+        let job = admin.jobs().remove(0);
+        assert_eq!(job.state, CompactionJobState::Failed);
+        assert_eq!(job.last_error.as_deref(), Some("foo"));
+
+        // the last error is kept for successful retries
+        admin.job_started(p);
+        admin.job_finished(p, JobOutcome::Succeeded);
+        let job = admin.jobs().remove(0);
+        assert_eq!(job.state, CompactionJobState::Succeeded);
+        assert_eq!(job.last_error.as_deref(), Some("foo"));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let admin = admin();
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+
+        assert!(!admin.cancel(p_1));
+
+        // queued
+        admin.enqueue([p_1]);
+        assert!(admin.cancel(p_1));
+        assert_eq!(admin.take_queued(), vec![]);
+        assert_eq!(admin.jobs()[0].state, CompactionJobState::Cancelled);
+
+        // running
+        let token = admin.job_started(p_2);
+        assert!(!token.is_cancelled());
+        assert!(admin.cancel(p_2));
+        assert!(token.is_cancelled());
+
+        admin.job_finished(p_2, JobOutcome::Cancelled);
+        assert!(!admin.cancel(p_2));
+        assert_eq!(admin.jobs()[1].state, CompactionJobState::Cancelled);
+    }
+
+    #[test]
+    fn test_skipped() {
+        let admin = admin();
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+        let skipped = || String::from("skipped");
+
+        admin.enqueue([p_1, p_2]);
+        assert_eq!(admin.take_queued(), vec![p_1, p_2]);
+
+        // p_1 is skipped, p_2 is compacted
+        admin.partition_done(p_1, skipped);
+        admin.job_started(p_2);
+        admin.job_finished(p_2, JobOutcome::Succeeded);
+        admin.partition_done(p_2, skipped);
+
+        // p_3 is still queued
+        admin.enqueue([p_3]);
+        admin.partition_done(p_3, skipped);
+
+        let jobs = admin.jobs();
+        assert_eq!(jobs[0].state, CompactionJobState::Failed);
+        assert_eq!(jobs[0].last_error.as_deref(), Some("skipped"));
+        assert!(jobs[0].finished_at.is_some());
+        assert_eq!(jobs[1].state, CompactionJobState::Succeeded);
+        assert_eq!(jobs[1].last_error, None);
+        assert_eq!(jobs[2].state, CompactionJobState::Queued);
+        assert_eq!(jobs[2].last_error, None);
+
+        // re-requested partitions are queued again
+        admin.enqueue([p_1]);
+        assert_eq!(admin.jobs()[0].state, CompactionJobState::Queued);
+    }
+
+    #[test]
+    fn test_finished_jobs_are_bounded() {
+        let admin = admin();
+
+        for i in 0..(MAX_FINISHED_JOBS as i64 + 10) {
+            let p = PartitionId::new(i);
+            admin.job_started(p);
+            admin.job_finished(p, JobOutcome::Succeeded);
+        }
+
+        let jobs = admin.jobs();
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS);
+        assert_eq!(jobs[0].partition_id, PartitionId::new(10));
+    }
+}
@@ -60,6 +60,7 @@ impl Compactor2 {
                         config.partition_concurrency,
                         config.partition_timeout,
                         Arc::clone(&job_semaphore),
+                        Arc::clone(&config.admin),
                         &components
                     ).await;
 
@@ -0,0 +1,473 @@
+//! Ensure that partitions flowing through the pipeline are leased by this compactor.
+
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+use futures::StreamExt;
+use observability_deps::tracing::warn;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    components::{
+        partition_done_sink::PartitionDoneSink, partition_leases::PartitionLeases,
+        partitions_source::PartitionsSource,
+    },
+    error::DynError,
+};
+
+/// Ensures that only partitions that are leased by this compactor are processed, so that multiple compactors can share
+/// the same partition source without any static sharding.
+///
+/// This should be used as a wrapper around the actual [`PartitionsSource`] and [`PartitionDoneSink`] and will setup of
+/// the following stream layout:
+///
+/// ```text
+///          +---------------------------------------------------+
+///          |                                                   |
+///          |                                                   |
+///          |                                                   V
+/// (1)====>(2)====>[concurrent processing]---->(3)---->(4)---->(5)
+///          ^            ^                              :
+///          :            :                              :
+///          :           (6)                             :
+///          +...........................................+
+/// ```
+///
+/// | Step |  Name                 | Type                                                        | Description |
+/// | ---- | --------------------- | ----------------------------------------------------------- | ----------- |
+/// | 1    | **Actual source**     | `inner_source`/`T1`/[`PartitionsSource`], wrapped           | This is the actual source, e.g. a [catalog](crate::components::partitions_source::catalog_to_compact::CatalogToCompactPartitionsSource) |
+/// | 2    | **Lease source**      | [`LeasePartitionsSourceWrapper`], wraps `inner_source`/`T1` | Tries to acquire a lease for every [`PartitionId`] from the `inner_source` and filters out partitions that are leased by other compactors. |
+/// | 3    | **Critical section**  | --                                                          | Here it is ensured that no other compactor works on the same [`PartitionId`], as long as the lease is renewed in time. |
+/// | 4    | **Lease sink**        | [`LeasePartitionDoneSinkWrapper`], wraps `inner_sink`/`T2`  | Releases the lease so that the partition can immediately be picked up by any compactor. |
+/// | 5    | **Actual sink**       | `inner_sink`/`T2`/[`PartitionDoneSink`], wrapped            | The actual sink. Directly receives all partitions filtered out at step 2. |
+/// | 6    | **Heartbeat**         | background task                                             | Renews the leases of all partitions between step 2 and step 4 every `heartbeat_interval`. |
+///
+/// If a compactor dies, it stops renewing its leases and the partitions are taken over by other compactors once the
+/// leases expired. A compactor that loses a lease (e.g. because the catalog was unreachable for too long) will notice
+/// this at the next heartbeat. Use
+/// [`HasLeasePartitionFilter`](crate::components::partition_filter::has_lease::HasLeasePartitionFilter) to stop
+/// processing such partitions.
+///
+/// At most `max_leases` partitions are leased at the same time, so that a single compactor does not claim more work
+/// than it can process and starves the others. Partitions that exceed this budget are treated like partitions that are
+/// leased by other compactors.
+///
+/// Note that partitions filtered out by [`LeasePartitionsSourceWrapper`] will directly be forwarded to `inner_sink`. No
+/// partition is ever lost. This means that `inner_source` and `inner_sink` can perform proper accounting. The
+/// concurrency of the lease acquisition and of this bypass can be controlled via `concurrency`.
+///
+/// This setup relies on a fact that it does not process duplicate [`PartitionId`]. You may use
+/// [`unique_partitions`](crate::components::combos::unique_partitions::unique_partitions) to achieve that.
+///
+/// # Panic
+/// The heartbeat is a tokio task, so this function must be called within a tokio runtime.
+pub fn lease_partitions<T1, T2, T3>(
+    inner_source: T1,
+    inner_sink: T2,
+    leases: T3,
+    heartbeat_interval: Duration,
+    max_leases: usize,
+    concurrency: usize,
+) -> (
+    LeasePartitionsSourceWrapper<T1, T2, T3>,
+    LeasePartitionDoneSinkWrapper<T2, T3>,
+)
+where
+    T1: PartitionsSource,
+    T2: PartitionDoneSink,
+    T3: PartitionLeases + 'static,
+{
+    let inner_sink = Arc::new(inner_sink);
+    let leases = Arc::new(leases);
+    let held = Arc::new(Mutex::new(HashSet::default()));
+    let heartbeat = HeartbeatHandle(tokio::spawn(heartbeat(
+        Arc::clone(&leases),
+        Arc::clone(&held),
+        heartbeat_interval,
+    )));
+    let source = LeasePartitionsSourceWrapper {
+        inner_source,
+        inner_sink: Arc::clone(&inner_sink),
+        leases: Arc::clone(&leases),
+        held: Arc::clone(&held),
+        max_leases,
+        concurrency,
+        _heartbeat: heartbeat,
+    };
+    let sink = LeasePartitionDoneSinkWrapper {
+        inner: inner_sink,
+        leases,
+        held,
+    };
+    (source, sink)
+}
+
+type Held = Arc<Mutex<HashSet<PartitionId>>>;
+
+/// Aborts the heartbeat task when dropped.
+#[derive(Debug)]
+struct HeartbeatHandle(JoinHandle<()>);
+
+impl Drop for HeartbeatHandle {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+async fn heartbeat<T>(leases: Arc<T>, held: Held, heartbeat_interval: Duration)
+where
+    T: PartitionLeases,
+{
+    let mut interval = tokio::time::interval(heartbeat_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let partitions = held
+            .lock()
+            .expect("not poisoned")
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+
+        for partition in partitions {
+            if !leases.acquire(partition).await {
+                warn!(
+                    partition_id = partition.get(),
+                    "lost partition lease, another compactor took over",
+                );
+                continue;
+            }
+
+            // The partition may have finished while the lease was renewed. Do not leave a stale lease behind.
+            let still_held = held.lock().expect("not poisoned").contains(&partition);
+            if !still_held {
+                leases.release(partition).await;
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LeasePartitionsSourceWrapper<T1, T2, T3>
+where
+    T1: PartitionsSource,
+    T2: PartitionDoneSink,
+    T3: PartitionLeases,
+{
+    inner_source: T1,
+    inner_sink: Arc<T2>,
+    leases: Arc<T3>,
+    held: Held,
+    max_leases: usize,
+    concurrency: usize,
+    _heartbeat: HeartbeatHandle,
+}
+
+impl<T1, T2, T3> Display for LeasePartitionsSourceWrapper<T1, T2, T3>
+where
+    T1: PartitionsSource,
+    T2: PartitionDoneSink,
+    T3: PartitionLeases,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "lease({}, {}, {})",
+            self.inner_source, self.inner_sink, self.leases
+        )
+    }
+}
+
+#[async_trait]
+impl<T1, T2, T3> PartitionsSource for LeasePartitionsSourceWrapper<T1, T2, T3>
+where
+    T1: PartitionsSource,
+    T2: PartitionDoneSink,
+    T3: PartitionLeases,
+{
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let res = self.inner_source.fetch().await;
+
+        let n_held = self.held.lock().expect("not poisoned").len();
+        let budget = self.max_leases.saturating_sub(n_held);
+
+        let mut leased = Vec::with_capacity(budget);
+        let mut rejected = Vec::with_capacity(res.len());
+        let mut candidates = res.into_iter();
+
+        // only try as many partitions as we still need, but keep going if some are leased by other compactors
+        while leased.len() < budget {
+            let batch = candidates
+                .by_ref()
+                .take(budget - leased.len())
+                .collect::<Vec<_>>();
+            if batch.is_empty() {
+                break;
+            }
+
+            let acquired = futures::stream::iter(batch)
+                .map(|id| async move { (id, self.leases.acquire(id).await) })
+                .buffered(self.concurrency)
+                .collect::<Vec<_>>()
+                .await;
+
+            for (id, acquired) in acquired {
+                if acquired {
+                    leased.push(id);
+                } else {
+                    rejected.push(id);
+                }
+            }
+        }
+        rejected.extend(candidates);
+
+        self.held
+            .lock()
+            .expect("not poisoned")
+            .extend(leased.iter().copied());
+
+        futures::stream::iter(rejected)
+            .map(|id| self.inner_sink.record(id, Ok(())))
+            .buffer_unordered(self.concurrency)
+            .collect::<()>()
+            .await;
+
+        leased
+    }
+}
+
+#[derive(Debug)]
+pub struct LeasePartitionDoneSinkWrapper<T1, T2>
+where
+    T1: PartitionDoneSink,
+    T2: PartitionLeases,
+{
+    inner: Arc<T1>,
+    leases: Arc<T2>,
+    held: Held,
+}
+
+impl<T1, T2> Display for LeasePartitionDoneSinkWrapper<T1, T2>
+where
+    T1: PartitionDoneSink,
+    T2: PartitionLeases,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lease({}, {})", self.inner, self.leases)
+    }
+}
+
+#[async_trait]
+impl<T1, T2> PartitionDoneSink for LeasePartitionDoneSinkWrapper<T1, T2>
+where
+    T1: PartitionDoneSink,
+    T2: PartitionLeases,
+{
+    async fn record(&self, partition: PartitionId, res: Result<(), DynError>) {
+        let existing = {
+            let mut guard = self.held.lock().expect("not poisoned");
+            guard.remove(&partition)
+        };
+        // perform check when NOT holding the mutex to not poison it
+        assert!(
+            existing,
+            "Unknown or already done partition in sink: {partition}"
+        );
+
+        // release first, so the partition is free to be picked up again as soon as the inner sink is done with it
+        self.leases.release(partition).await;
+        self.inner.record(partition, res).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::components::{
+        partition_done_sink::mock::MockPartitionDoneSink,
+        partition_leases::mock::MockPartitionLeases, partitions_source::mock::MockPartitionsSource,
+    };
+
+    use super::*;
+
+    const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(1);
+
+    #[tokio::test]
+    async fn test_display() {
+        let (source, sink) = lease_partitions(
+            MockPartitionsSource::new(vec![]),
+            MockPartitionDoneSink::new(),
+            MockPartitionLeases::new(),
+            HEARTBEAT_INTERVAL,
+            10,
+            1,
+        );
+        assert_eq!(source.to_string(), "lease(mock, mock, mock)");
+        assert_eq!(sink.to_string(), "lease(mock, mock)");
+    }
+
+    #[tokio::test]
+    async fn test_lease() {
+        let inner_source = Arc::new(MockPartitionsSource::new(vec![
+            PartitionId::new(1),
+            PartitionId::new(2),
+            PartitionId::new(3),
+        ]));
+        let inner_sink = Arc::new(MockPartitionDoneSink::new());
+        let leases = Arc::new(MockPartitionLeases::new());
+        leases.take(PartitionId::new(2));
+
+        let (source, sink) = lease_partitions(
+            Arc::clone(&inner_source),
+            Arc::clone(&inner_sink),
+            Arc::clone(&leases),
+            HEARTBEAT_INTERVAL,
+            10,
+            1,
+        );
+
+        // ========== Round 1 ==========
+        assert_eq!(
+            source.fetch().await,
+            vec![PartitionId::new(1), PartitionId::new(3)],
+        );
+        assert_eq!(
+            leases.held(),
+            HashSet::from([PartitionId::new(1), PartitionId::new(3)]),
+        );
+        // partitions leased by other compactors bypass the critical section
+        assert_eq!(
+            inner_sink.results(),
+            HashMap::from([(PartitionId::new(2), Ok(()))]),
+        );
+
+        sink.record(PartitionId::new(1), Err(String::from("foo").into()))
+            .await;
+        assert_eq!(leases.held(), HashSet::from([PartitionId::new(3)]));
+        assert_eq!(
+            inner_sink.results(),
+            HashMap::from([
+                (PartitionId::new(1), Err(String::from("foo"))),
+                (PartitionId::new(2), Ok(())),
+            ]),
+        );
+
+        // ========== Round 2 ==========
+        inner_source.set(vec![PartitionId::new(1), PartitionId::new(4)]);
+        assert_eq!(
+            source.fetch().await,
+            vec![PartitionId::new(1), PartitionId::new(4)],
+        );
+        assert_eq!(
+            leases.held(),
+            HashSet::from([
+                PartitionId::new(1),
+                PartitionId::new(3),
+                PartitionId::new(4)
+            ]),
+        );
+
+        sink.record(PartitionId::new(1), Ok(())).await;
+        sink.record(PartitionId::new(3), Ok(())).await;
+        sink.record(PartitionId::new(4), Ok(())).await;
+        assert_eq!(leases.held(), HashSet::new());
+    }
+
+    #[tokio::test]
+    async fn test_max_leases() {
+        let inner_source = Arc::new(MockPartitionsSource::new(vec![
+            PartitionId::new(1),
+            PartitionId::new(2),
+            PartitionId::new(3),
+            PartitionId::new(4),
+        ]));
+        let inner_sink = Arc::new(MockPartitionDoneSink::new());
+        let leases = Arc::new(MockPartitionLeases::new());
+        leases.take(PartitionId::new(1));
+
+        let (source, sink) = lease_partitions(
+            Arc::clone(&inner_source),
+            Arc::clone(&inner_sink),
+            Arc::clone(&leases),
+            HEARTBEAT_INTERVAL,
+            2,
+            1,
+        );
+
+        // skips the partition leased by another compactor and stops once the budget is used up
+        assert_eq!(
+            source.fetch().await,
+            vec![PartitionId::new(2), PartitionId::new(3)],
+        );
+        assert_eq!(
+            inner_sink.results(),
+            HashMap::from([(PartitionId::new(1), Ok(())), (PartitionId::new(4), Ok(()))]),
+        );
+
+        // no budget left
+        inner_source.set(vec![PartitionId::new(5)]);
+        assert_eq!(source.fetch().await, vec![]);
+
+        // finishing a partition frees up budget
+        sink.record(PartitionId::new(2), Ok(())).await;
+        assert_eq!(source.fetch().await, vec![PartitionId::new(5)]);
+        assert_eq!(
+            leases.held(),
+            HashSet::from([PartitionId::new(3), PartitionId::new(5)]),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_renews_leases() {
+        let leases = Arc::new(MockPartitionLeases::new());
+        let (source, sink) = lease_partitions(
+            MockPartitionsSource::new(vec![PartitionId::new(1)]),
+            MockPartitionDoneSink::new(),
+            Arc::clone(&leases),
+            HEARTBEAT_INTERVAL,
+            10,
+            1,
+        );
+        assert_eq!(source.fetch().await, vec![PartitionId::new(1)]);
+
+        // simulate an expired lease, the heartbeat should pick it up again
+        leases.release(PartitionId::new(1)).await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !leases.held().contains(&PartitionId::new(1)) {
+                tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+            }
+        })
+        .await
+        .expect("heartbeat renewed lease");
+
+        sink.record(PartitionId::new(1), Ok(())).await;
+        assert_eq!(leases.held(), HashSet::new());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Unknown or already done partition in sink: 1")]
+    async fn test_panic_sink_unknown() {
+        let (source, sink) = lease_partitions(
+            MockPartitionsSource::new(vec![PartitionId::new(1)]),
+            MockPartitionDoneSink::new(),
+            MockPartitionLeases::new(),
+            HEARTBEAT_INTERVAL,
+            10,
+            1,
+        );
+        let ids = source.fetch().await;
+        assert_eq!(ids.len(), 1);
+        let id = ids[0];
+        sink.record(id, Ok(())).await;
+        sink.record(id, Ok(())).await;
+    }
+}
@@ -1,5 +1,8 @@
 //! Combinations of multiple components that together can achieve one goal.
 
+pub mod lease_partitions;
+pub mod rollup_only;
+pub mod rollup_partitions;
 pub mod throttle_partition;
 pub mod unique_partitions;
 
@@ -0,0 +1,327 @@
+//! Only modify the catalog for partitions that are rolled up.
+
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
+
+use crate::{
+    components::{
+        commit::Commit, partition_done_sink::PartitionDoneSink,
+        partition_info_source::PartitionInfoSource, rollup_rules::RollupRules,
+    },
+    error::DynError,
+    partition_info::PartitionInfo,
+};
+
+/// Ensures that only partitions with a matching [rollup rule](RollupRules) are committed to the
+/// catalog, all other partitions are compacted in shadow mode.
+///
+/// Cold compaction runs in shadow mode until its partition selection is checked, but rollups
+/// must be committed to have any effect.
+///
+/// This should be used as a wrapper around the actual [`PartitionInfoSource`], [`Commit`] &
+/// [`PartitionDoneSink`] and will set up the following layout:
+///
+/// | Step |  Name                 | Type                                                                 | Description |
+/// | ---- | --------------------- | -------------------------------------------------------------------- | ----------- |
+/// | 1    | **Info source**       | [`RollupOnlyPartitionInfoSourceWrapper`], wraps `inner_source`/`T1`  | Marks partitions with a matching rollup rule when their compaction starts. |
+/// | 2    | **Commit**            | [`RollupOnlyCommitWrapper`], wraps `inner_commit`/`T2`               | Commits marked partitions via `inner_commit`, all others via `shadow_commit`. |
+/// | 3    | **Sink**              | [`RollupOnlyPartitionDoneSinkWrapper`], wraps `inner_sink`/`T4`      | Records marked partitions via `inner_sink`, all others via `shadow_sink`, and unmarks them. |
+///
+/// Whether a partition is committed is decided once per compaction job, so all rounds of a job
+/// are either committed or not.
+pub fn rollup_only<T1, T2, T3, T4, T5, R>(
+    inner_source: T1,
+    inner_commit: T2,
+    shadow_commit: T3,
+    inner_sink: T4,
+    shadow_sink: T5,
+    rollup_rules: R,
+) -> (
+    RollupOnlyPartitionInfoSourceWrapper<T1, R>,
+    RollupOnlyCommitWrapper<T2, T3>,
+    RollupOnlyPartitionDoneSinkWrapper<T4, T5>,
+)
+where
+    T1: PartitionInfoSource,
+    T2: Commit,
+    T3: Commit,
+    T4: PartitionDoneSink,
+    T5: PartitionDoneSink,
+    R: RollupRules,
+{
+    let rolled_up = RolledUp::default();
+    let source = RollupOnlyPartitionInfoSourceWrapper {
+        inner: inner_source,
+        rollup_rules,
+        rolled_up: Arc::clone(&rolled_up),
+    };
+    let commit = RollupOnlyCommitWrapper {
+        inner: inner_commit,
+        shadow: shadow_commit,
+        rolled_up: Arc::clone(&rolled_up),
+    };
+    let sink = RollupOnlyPartitionDoneSinkWrapper {
+        inner: inner_sink,
+        shadow: shadow_sink,
+        rolled_up,
+    };
+    (source, commit, sink)
+}
+
+type RolledUp = Arc<Mutex<HashSet<PartitionId>>>;
+
+#[derive(Debug)]
+pub struct RollupOnlyPartitionInfoSourceWrapper<T, R>
+where
+    T: PartitionInfoSource,
+    R: RollupRules,
+{
+    inner: T,
+    rollup_rules: R,
+    rolled_up: RolledUp,
+}
+
+impl<T, R> Display for RollupOnlyPartitionInfoSourceWrapper<T, R>
+where
+    T: PartitionInfoSource,
+    R: RollupRules,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup_only({}, {})", self.inner, self.rollup_rules)
+    }
+}
+
+#[async_trait]
+impl<T, R> PartitionInfoSource for RollupOnlyPartitionInfoSourceWrapper<T, R>
+where
+    T: PartitionInfoSource,
+    R: RollupRules,
+{
+    async fn fetch(&self, partition_id: PartitionId) -> Result<Arc<PartitionInfo>, DynError> {
+        let partition = self.inner.fetch(partition_id).await?;
+
+        if self
+            .rollup_rules
+            .fetch(partition.namespace_id, partition.table.id)
+            .await
+            .is_some()
+        {
+            self.rolled_up
+                .lock()
+                .expect("not poisoned")
+                .insert(partition_id);
+        }
+
+        Ok(partition)
+    }
+}
+
+#[derive(Debug)]
+pub struct RollupOnlyCommitWrapper<T1, T2>
+where
+    T1: Commit,
+    T2: Commit,
+{
+    inner: T1,
+    shadow: T2,
+    rolled_up: RolledUp,
+}
+
+impl<T1, T2> Display for RollupOnlyCommitWrapper<T1, T2>
+where
+    T1: Commit,
+    T2: Commit,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup_only({}, shadow={})", self.inner, self.shadow)
+    }
+}
+
+#[async_trait]
+impl<T1, T2> Commit for RollupOnlyCommitWrapper<T1, T2>
+where
+    T1: Commit,
+    T2: Commit,
+{
+    async fn commit(
+        &self,
+        partition_id: PartitionId,
+        delete: &[ParquetFile],
+        upgrade: &[ParquetFile],
+        create: &[ParquetFileParams],
+        target_level: CompactionLevel,
+    ) -> Vec<ParquetFileId> {
+        let rolled_up = self
+            .rolled_up
+            .lock()
+            .expect("not poisoned")
+            .contains(&partition_id);
+
+        if rolled_up {
+            self.inner
+                .commit(partition_id, delete, upgrade, create, target_level)
+                .await
+        } else {
+            self.shadow
+                .commit(partition_id, delete, upgrade, create, target_level)
+                .await
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RollupOnlyPartitionDoneSinkWrapper<T1, T2>
+where
+    T1: PartitionDoneSink,
+    T2: PartitionDoneSink,
+{
+    inner: T1,
+    shadow: T2,
+    rolled_up: RolledUp,
+}
+
+impl<T1, T2> Display for RollupOnlyPartitionDoneSinkWrapper<T1, T2>
+where
+    T1: PartitionDoneSink,
+    T2: PartitionDoneSink,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup_only({}, shadow={})", self.inner, self.shadow)
+    }
+}
+
+#[async_trait]
+impl<T1, T2> PartitionDoneSink for RollupOnlyPartitionDoneSinkWrapper<T1, T2>
+where
+    T1: PartitionDoneSink,
+    T2: PartitionDoneSink,
+{
+    async fn record(&self, partition: PartitionId, res: Result<(), DynError>) {
+        let rolled_up = self
+            .rolled_up
+            .lock()
+            .expect("not poisoned")
+            .remove(&partition);
+
+        if rolled_up {
+            self.inner.record(partition, res).await;
+        } else {
+            self.shadow.record(partition, res).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        components::{
+            commit::mock::MockCommit,
+            partition_done_sink::mock::MockPartitionDoneSink,
+            partition_info_source::mock::MockPartitionInfoSource,
+            rollup_rules::mock::{tests::rule, MockRollupRules},
+        },
+        test_utils::PartitionInfoBuilder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let (source, commit, sink) = rollup_only(
+            MockPartitionInfoSource::new(vec![]),
+            MockCommit::new(),
+            MockCommit::new(),
+            MockPartitionDoneSink::new(),
+            MockPartitionDoneSink::new(),
+            MockRollupRules::new(vec![]),
+        );
+        assert_eq!(source.to_string(), "rollup_only(mock, mock)");
+        assert_eq!(commit.to_string(), "rollup_only(mock, shadow=mock)");
+        assert_eq!(sink.to_string(), "rollup_only(mock, shadow=mock)");
+    }
+
+    #[tokio::test]
+    async fn test_only_rolled_up_partitions_are_committed() {
+        // partition 1 is in table 3 of namespace 2, see `PartitionInfoBuilder`
+        let p1 = PartitionId::new(1);
+        let p2 = PartitionId::new(2);
+        let source = MockPartitionInfoSource::new(vec![
+            Arc::new(PartitionInfoBuilder::new().with_partition_id(1).build()),
+            Arc::new(PartitionInfoBuilder::new().with_partition_id(2).build()),
+        ]);
+        let inner_commit = Arc::new(MockCommit::new());
+        let shadow_commit = Arc::new(MockCommit::new());
+        let inner_sink = Arc::new(MockPartitionDoneSink::new());
+        let shadow_sink = Arc::new(MockPartitionDoneSink::new());
+        let (source, commit, sink) = rollup_only(
+            source,
+            Arc::clone(&inner_commit),
+            Arc::clone(&shadow_commit),
+            Arc::clone(&inner_sink),
+            Arc::clone(&shadow_sink),
+            MockRollupRules::new(vec![rule(1, 2, Some(3))]),
+        );
+
+        // a partition that was never fetched is not committed
+        commit
+            .commit(p2, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        assert_eq!(inner_commit.history().len(), 0);
+        assert_eq!(shadow_commit.history().len(), 1);
+        sink.record(p2, Ok(())).await;
+
+        // a partition with a matching rule is committed
+        source.fetch(p1).await.unwrap();
+        commit
+            .commit(p1, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        assert_eq!(inner_commit.history().len(), 1);
+        assert_eq!(shadow_commit.history().len(), 1);
+        sink.record(p1, Ok(())).await;
+        assert_eq!(inner_sink.results(), HashMap::from([(p1, Ok(()))]));
+        assert_eq!(shadow_sink.results(), HashMap::from([(p2, Ok(()))]));
+
+        // done partitions are forgotten
+        commit
+            .commit(p1, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        assert_eq!(inner_commit.history().len(), 1);
+        assert_eq!(shadow_commit.history().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_partitions_without_rule_are_not_committed() {
+        let p1 = PartitionId::new(1);
+        let source = MockPartitionInfoSource::new(vec![Arc::new(
+            PartitionInfoBuilder::new().with_partition_id(1).build(),
+        )]);
+        let inner_commit = Arc::new(MockCommit::new());
+        let inner_sink = Arc::new(MockPartitionDoneSink::new());
+        let (source, commit, sink) = rollup_only(
+            source,
+            Arc::clone(&inner_commit),
+            MockCommit::new(),
+            Arc::clone(&inner_sink),
+            MockPartitionDoneSink::new(),
+            // rule for another namespace
+            MockRollupRules::new(vec![rule(1, 1, None)]),
+        );
+
+        source.fetch(p1).await.unwrap();
+        commit
+            .commit(p1, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        sink.record(p1, Err("fail".into())).await;
+
+        assert_eq!(inner_commit.history().len(), 0);
+        assert!(inner_sink.results().is_empty());
+    }
+}
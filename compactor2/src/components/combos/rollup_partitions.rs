@@ -0,0 +1,259 @@
+//! Record rollups of partitions once they are committed.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
+
+use crate::{
+    components::{
+        commit::Commit, partition_done_sink::PartitionDoneSink, rollup_rules::RollupRules,
+    },
+    error::DynError,
+};
+
+/// Ensures that a rollup is only recorded in the catalog after the rolled-up data was committed.
+///
+/// The [`RollupDataFusionPlanner`](crate::components::df_planner::rollup::RollupDataFusionPlanner)
+/// registers planned rollups in the returned [`PendingRollups`]. This should be used as a wrapper
+/// around the actual [`Commit`] & [`PartitionDoneSink`] and will set up the following layout:
+///
+/// | Step |  Name                 | Type                                                        | Description |
+/// | ---- | --------------------- | ----------------------------------------------------------- | ----------- |
+/// | 1    | **Planner**           | [`PendingRollups`]                                          | Registers planned rollups. |
+/// | 2    | **Rollup commit**     | [`RollupCommitWrapper`], wraps `inner_commit`/`T1`          | Records planned rollups of the partition once the commit succeeded. |
+/// | 3    | **Actual commit**     | `inner_commit`/`T1`/[`Commit`]                              | The actual commit implementation. |
+/// | 4    | **Rollup sink**       | [`RollupPartitionDoneSinkWrapper`], wraps `inner_sink`/`T2` | Forgets rollups of the partition, including the ones that were never committed. |
+/// | 5    | **Actual sink**       | `inner_sink`/`T2`/[`PartitionDoneSink`]                     | The actual sink. |
+///
+/// A failed or timed-out compaction job therefore never marks its partition as rolled up.
+pub fn rollup_partitions<T1, T2, R>(
+    commit: T1,
+    sink: T2,
+    rollup_rules: R,
+) -> (
+    RollupCommitWrapper<T1, R>,
+    RollupPartitionDoneSinkWrapper<T2>,
+    Arc<PendingRollups>,
+)
+where
+    T1: Commit,
+    T2: PartitionDoneSink,
+    R: RollupRules,
+{
+    let pending = Arc::new(PendingRollups::default());
+    let commit = RollupCommitWrapper {
+        inner: commit,
+        rollup_rules,
+        pending: Arc::clone(&pending),
+    };
+    let sink = RollupPartitionDoneSinkWrapper {
+        inner: sink,
+        pending: Arc::clone(&pending),
+    };
+    (commit, sink, pending)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rollup {
+    interval_ns: i64,
+    committed: bool,
+}
+
+/// Rollups of the partitions that are currently compacted.
+#[derive(Debug, Default)]
+pub struct PendingRollups {
+    state: Mutex<HashMap<PartitionId, Rollup>>,
+}
+
+impl PendingRollups {
+    /// Register that the output of a plan for the given partition is rolled up.
+    pub fn planned(&self, partition_id: PartitionId, interval_ns: i64) {
+        self.state
+            .lock()
+            .expect("not poisoned")
+            .entry(partition_id)
+            .or_insert(Rollup {
+                interval_ns,
+                committed: false,
+            });
+    }
+
+    /// Returns true if rolled-up data of the given partition was committed during the current
+    /// compaction of the partition.
+    ///
+    /// Later rounds must not roll up this data again.
+    pub fn committed(&self, partition_id: PartitionId) -> bool {
+        self.state
+            .lock()
+            .expect("not poisoned")
+            .get(&partition_id)
+            .map(|rollup| rollup.committed)
+            .unwrap_or_default()
+    }
+
+    /// Mark the planned rollup of the partition as committed.
+    ///
+    /// Returns the rollup interval if the rollup was not committed before.
+    fn commit(&self, partition_id: PartitionId) -> Option<i64> {
+        let mut guard = self.state.lock().expect("not poisoned");
+        let rollup = guard.get_mut(&partition_id)?;
+        if rollup.committed {
+            return None;
+        }
+        rollup.committed = true;
+        Some(rollup.interval_ns)
+    }
+
+    fn forget(&self, partition_id: PartitionId) {
+        self.state
+            .lock()
+            .expect("not poisoned")
+            .remove(&partition_id);
+    }
+}
+
+#[derive(Debug)]
+pub struct RollupCommitWrapper<T, R>
+where
+    T: Commit,
+    R: RollupRules,
+{
+    inner: T,
+    rollup_rules: R,
+    pending: Arc<PendingRollups>,
+}
+
+impl<T, R> Display for RollupCommitWrapper<T, R>
+where
+    T: Commit,
+    R: RollupRules,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup({}, {})", self.inner, self.rollup_rules)
+    }
+}
+
+#[async_trait]
+impl<T, R> Commit for RollupCommitWrapper<T, R>
+where
+    T: Commit,
+    R: RollupRules,
+{
+    async fn commit(
+        &self,
+        partition_id: PartitionId,
+        delete: &[ParquetFile],
+        upgrade: &[ParquetFile],
+        create: &[ParquetFileParams],
+        target_level: CompactionLevel,
+    ) -> Vec<ParquetFileId> {
+        let created = self
+            .inner
+            .commit(partition_id, delete, upgrade, create, target_level)
+            .await;
+
+        if let Some(interval_ns) = self.pending.commit(partition_id) {
+            self.rollup_rules.record(partition_id, interval_ns).await;
+        }
+
+        created
+    }
+}
+
+#[derive(Debug)]
+pub struct RollupPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    inner: T,
+    pending: Arc<PendingRollups>,
+}
+
+impl<T> Display for RollupPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionDoneSink for RollupPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    async fn record(&self, partition: PartitionId, res: Result<(), DynError>) {
+        self.pending.forget(partition);
+        self.inner.record(partition, res).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::{
+        commit::mock::MockCommit, partition_done_sink::mock::MockPartitionDoneSink,
+        rollup_rules::mock::MockRollupRules,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let (commit, sink, _pending) = rollup_partitions(
+            MockCommit::new(),
+            MockPartitionDoneSink::new(),
+            MockRollupRules::new(vec![]),
+        );
+        assert_eq!(commit.to_string(), "rollup(mock, mock)");
+        assert_eq!(sink.to_string(), "rollup(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_record_after_commit() {
+        let rules = Arc::new(MockRollupRules::new(vec![]));
+        let (commit, sink, pending) = rollup_partitions(
+            MockCommit::new(),
+            MockPartitionDoneSink::new(),
+            Arc::clone(&rules),
+        );
+        let p1 = PartitionId::new(1);
+        let p2 = PartitionId::new(2);
+
+        // commits without planned rollups record nothing
+        commit
+            .commit(p1, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        assert!(rules.recorded().is_empty());
+        assert!(!pending.committed(p1));
+
+        // planned rollups are only recorded once committed
+        pending.planned(p1, 10);
+        pending.planned(p2, 20);
+        assert!(rules.recorded().is_empty());
+        assert!(!pending.committed(p1));
+
+        commit
+            .commit(p1, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        assert_eq!(rules.recorded(), HashMap::from([(p1, 10)]));
+        assert!(pending.committed(p1));
+        assert!(!pending.committed(p2));
+
+        // the job for p2 failed, its rollup is never recorded
+        sink.record(p2, Err("fail".into())).await;
+        commit
+            .commit(p2, &[], &[], &[], CompactionLevel::Final)
+            .await;
+        assert_eq!(rules.recorded(), HashMap::from([(p1, 10)]));
+
+        // finished partitions are forgotten
+        sink.record(p1, Ok(())).await;
+        assert!(!pending.committed(p1));
+    }
+}
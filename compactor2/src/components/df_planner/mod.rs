@@ -9,6 +9,7 @@ use datafusion::{error::DataFusionError, physical_plan::ExecutionPlan};
 pub mod panic;
 pub mod planner_v1;
 mod query_chunk;
+pub mod rollup;
 
 use crate::{partition_info::PartitionInfo, plan_ir::PlanIR};
 
@@ -0,0 +1,418 @@
+use std::{any::Any, fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::CompactionLevel;
+use datafusion::{
+    arrow::{compute::cast, datatypes::SchemaRef, record_batch::RecordBatch},
+    error::DataFusionError,
+    execution::context::TaskContext,
+    physical_expr::PhysicalSortExpr,
+    physical_plan::{
+        stream::RecordBatchStreamAdapter, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+        Statistics,
+    },
+};
+use futures::StreamExt;
+use iox_query::{
+    exec::{Executor, ExecutorType},
+    frontend::reorg::ReorgPlanner,
+};
+use iox_time::TimeProvider;
+use observability_deps::tracing::info;
+use parquet_file::storage::ParquetStorage;
+
+use crate::{
+    components::{
+        combos::rollup_partitions::PendingRollups,
+        df_planner::query_chunk::{to_query_chunks, QueryableParquetChunk},
+        rollup_rules::RollupRules,
+    },
+    partition_info::PartitionInfo,
+    plan_ir::PlanIR,
+};
+
+use super::DataFusionPlanner;
+
+/// Downsamples the output of final-level compactions according to the [`RollupRules`] of the
+/// table.
+///
+/// A plan is only rolled up if a rule applies to the table and all input files are older than
+/// the rule's minimum age. All other plans are passed to the inner planner unchanged.
+///
+/// Partitions are rolled up at most once. Aggregating already rolled-up rows again, e.g. together
+/// with late arriving raw points, would produce wrong results for aggregates like the mean, so
+/// late points of a rolled-up partition are kept at their original resolution.
+///
+/// Planned rollups are registered in [`PendingRollups`] and only recorded in the catalog once
+/// their output is committed. The recorded interval is not used by the querier, see
+/// [`Partition::rollup_interval_ns`](data_types::Partition::rollup_interval_ns).
+#[derive(Debug)]
+pub struct RollupDataFusionPlanner<T, R>
+where
+    T: DataFusionPlanner,
+    R: RollupRules,
+{
+    inner: T,
+    rollup_rules: R,
+    pending: Arc<PendingRollups>,
+    store: ParquetStorage,
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl<T, R> RollupDataFusionPlanner<T, R>
+where
+    T: DataFusionPlanner,
+    R: RollupRules,
+{
+    pub fn new(
+        inner: T,
+        rollup_rules: R,
+        pending: Arc<PendingRollups>,
+        store: ParquetStorage,
+        exec: Arc<Executor>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            inner,
+            rollup_rules,
+            pending,
+            store,
+            exec,
+            time_provider,
+        }
+    }
+}
+
+impl<T, R> Display for RollupDataFusionPlanner<T, R>
+where
+    T: DataFusionPlanner,
+    R: RollupRules,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup({}, {})", self.inner, self.rollup_rules)
+    }
+}
+
+#[async_trait]
+impl<T, R> DataFusionPlanner for RollupDataFusionPlanner<T, R>
+where
+    T: DataFusionPlanner,
+    R: RollupRules,
+{
+    async fn plan(
+        &self,
+        ir: &PlanIR,
+        partition: Arc<PartitionInfo>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let files = match ir {
+            PlanIR::Compact {
+                files,
+                target_level: CompactionLevel::Final,
+                ..
+            } if !files.is_empty() => files,
+            _ => return self.inner.plan(ir, partition).await,
+        };
+
+        if partition.rollup_interval_ns.is_some() || self.pending.committed(partition.partition_id)
+        {
+            return self.inner.plan(ir, partition).await;
+        }
+
+        let rule = match self
+            .rollup_rules
+            .fetch(partition.namespace_id, partition.table.id)
+            .await
+        {
+            Some(rule) => rule,
+            None => return self.inner.plan(ir, partition).await,
+        };
+
+        let now = self.time_provider.now().timestamp_nanos();
+        let cutoff = now.saturating_sub(rule.min_age_ns);
+        if files.iter().any(|f| f.file.max_time.get() >= cutoff) {
+            return self.inner.plan(ir, partition).await;
+        }
+
+        let query_chunks = to_query_chunks(files, &partition, self.store.clone());
+        let merged_schema = QueryableParquetChunk::merge_schemas(&query_chunks);
+        let sort_key = partition
+            .sort_key
+            .as_ref()
+            .expect("no partition sort key in catalog")
+            .filter_to(&merged_schema.primary_key(), partition.partition_id.get());
+
+        let plan = ReorgPlanner::new()
+            .rollup_plan(
+                Arc::from(partition.table.name.clone()),
+                &merged_schema,
+                query_chunks,
+                sort_key,
+                &rule,
+            )
+            .map_err(|e| {
+                DataFusionError::Context(
+                    String::from("planner"),
+                    Box::new(DataFusionError::External(Box::new(e))),
+                )
+            })?;
+
+        let ctx = self.exec.new_context(ExecutorType::Reorg);
+        let plan = ctx.create_physical_plan(&plan).await.map_err(|e| {
+            DataFusionError::Context(
+                String::from("planner"),
+                Box::new(DataFusionError::External(Box::new(e))),
+            )
+        })?;
+
+        info!(
+            partition_id = partition.partition_id.get(),
+            rollup_interval_ns = rule.interval_ns,
+            "rolling up partition",
+        );
+        self.pending
+            .planned(partition.partition_id, rule.interval_ns);
+
+        Ok(Arc::new(SchemaRestoringExec {
+            input: plan,
+            schema: merged_schema.as_arrow(),
+        }))
+    }
+}
+
+/// Re-attaches the IOx schema to the output of an aggregation.
+///
+/// Aggregations drop the field metadata that the parquet writer requires to recover the IOx
+/// column types.
+#[derive(Debug)]
+struct SchemaRestoringExec {
+    input: Arc<dyn ExecutionPlan>,
+    schema: SchemaRef,
+}
+
+impl ExecutionPlan for SchemaRestoringExec {
+    fn as_any(&self) -> &dyn Any {
+        self as _
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> datafusion::error::Result<Arc<dyn ExecutionPlan>> {
+        assert_eq!(children.len(), 1);
+        Ok(Arc::new(Self {
+            input: children.remove(0),
+            schema: Arc::clone(&self.schema),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> datafusion::error::Result<SendableRecordBatchStream> {
+        let schema = Arc::clone(&self.schema);
+        let stream = self.input.execute(partition, context)?.map({
+            let schema = Arc::clone(&schema);
+            move |batch| restore_schema(batch?, &schema)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+fn restore_schema(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+) -> datafusion::error::Result<RecordBatch> {
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(array, field)| {
+            if array.data_type() == field.data_type() {
+                Ok(Arc::clone(array))
+            } else {
+                cast(array, field.data_type())
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::ChunkOrder;
+    use iox_tests::ParquetFileBuilder;
+    use iox_time::{MockProvider, Time};
+    use object_store::memory::InMemory;
+    use parquet_file::storage::StorageId;
+
+    use crate::{
+        components::{
+            combos::rollup_partitions::rollup_partitions,
+            commit::{mock::MockCommit, Commit},
+            df_planner::panic::{PanicDataFusionPlanner, PanicPlan},
+            partition_done_sink::mock::MockPartitionDoneSink,
+            rollup_rules::mock::{tests::rule, MockRollupRules},
+        },
+        file_classification::CompactReason,
+        plan_ir::FileIR,
+        test_utils::PartitionInfoBuilder,
+    };
+
+    use super::*;
+
+    fn planner(
+        rules: Vec<data_types::RollupRule>,
+        now: i64,
+    ) -> RollupDataFusionPlanner<PanicDataFusionPlanner, Arc<MockRollupRules>> {
+        planner_with_pending(rules, now, Arc::new(PendingRollups::default()))
+    }
+
+    fn planner_with_pending(
+        rules: Vec<data_types::RollupRule>,
+        now: i64,
+        pending: Arc<PendingRollups>,
+    ) -> RollupDataFusionPlanner<PanicDataFusionPlanner, Arc<MockRollupRules>> {
+        RollupDataFusionPlanner::new(
+            PanicDataFusionPlanner::new(),
+            Arc::new(MockRollupRules::new(rules)),
+            pending,
+            ParquetStorage::new(Arc::new(InMemory::new()), StorageId::from("iox")),
+            Arc::new(Executor::new_testing()),
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(now))),
+        )
+    }
+
+    fn compact_ir(max_time: i64, target_level: CompactionLevel) -> PlanIR {
+        let file = ParquetFileBuilder::new(1)
+            .with_time_range(0, max_time)
+            .with_compaction_level(CompactionLevel::FileNonOverlapped)
+            .build();
+        PlanIR::Compact {
+            files: vec![FileIR {
+                file,
+                order: ChunkOrder::new(1),
+            }],
+            target_level,
+            // This reason is arbitrary
+            reason: CompactReason::ManySmallFiles,
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(planner(vec![], 0).to_string(), "rollup(panic, mock)");
+    }
+
+    #[tokio::test]
+    async fn test_delegates_without_rule() {
+        let planner = planner(vec![], 1_000_000);
+        let partition = Arc::new(PartitionInfoBuilder::new().build());
+
+        let plan = planner
+            .plan(&compact_ir(10, CompactionLevel::Final), partition)
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<PanicPlan>().is_some());
+        assert!(planner.rollup_rules.recorded().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delegates_for_young_files() {
+        let partition = Arc::new(PartitionInfoBuilder::new().build());
+        // rule applies to the namespace of the partition and requires an age of 1_000ns
+        let planner = planner(vec![rule(1, partition.namespace_id.get(), None)], 1_000);
+
+        let plan = planner
+            .plan(&compact_ir(10, CompactionLevel::Final), partition)
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<PanicPlan>().is_some());
+        assert!(planner.rollup_rules.recorded().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delegates_for_non_final_level() {
+        let partition = Arc::new(PartitionInfoBuilder::new().build());
+        let planner = planner(vec![rule(1, partition.namespace_id.get(), None)], 1_000_000);
+
+        let plan = planner
+            .plan(
+                &compact_ir(10, CompactionLevel::FileNonOverlapped),
+                partition,
+            )
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<PanicPlan>().is_some());
+        assert!(planner.rollup_rules.recorded().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delegates_for_rolled_up_partition() {
+        let partition = Arc::new(
+            PartitionInfoBuilder::new()
+                .with_rollup_interval_ns(10)
+                .build(),
+        );
+        let planner = planner(vec![rule(1, partition.namespace_id.get(), None)], 1_000_000);
+
+        let plan = planner
+            .plan(&compact_ir(10, CompactionLevel::Final), partition)
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<PanicPlan>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_delegates_after_committed_rollup() {
+        let partition = Arc::new(PartitionInfoBuilder::new().build());
+        let (commit, _sink, pending) = rollup_partitions(
+            MockCommit::new(),
+            MockPartitionDoneSink::new(),
+            MockRollupRules::new(vec![]),
+        );
+        pending.planned(partition.partition_id, 10);
+        commit
+            .commit(
+                partition.partition_id,
+                &[],
+                &[],
+                &[],
+                CompactionLevel::Final,
+            )
+            .await;
+
+        let planner = planner_with_pending(
+            vec![rule(1, partition.namespace_id.get(), None)],
+            1_000_000,
+            pending,
+        );
+        let plan = planner
+            .plan(&compact_ir(10, CompactionLevel::Final), partition)
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<PanicPlan>().is_some());
+    }
+}
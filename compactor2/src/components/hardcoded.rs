@@ -8,14 +8,20 @@ use data_types::CompactionLevel;
 use object_store::memory::InMemory;
 
 use crate::{
-    config::{CompactionType, Config, PartitionsSourceConfig},
+    config::{CompactionType, Config, LeaseConfig, PartitionsSourceConfig},
     error::ErrorKind,
     object_store::ignore_writes::IgnoreWrites,
 };
 
 use super::{
     changed_files_filter::logging::LoggingChangedFiles,
-    combos::{throttle_partition::throttle_partition, unique_partitions::unique_partitions},
+    combos::{
+        lease_partitions::lease_partitions,
+        rollup_only::rollup_only,
+        rollup_partitions::{rollup_partitions, PendingRollups},
+        throttle_partition::throttle_partition,
+        unique_partitions::unique_partitions,
+    },
     commit::{
         catalog::CatalogCommit, logging::LoggingCommitWrapper, metrics::MetricsCommitWrapper,
         mock::MockCommit, Commit,
@@ -23,7 +29,9 @@ use super::{
     df_plan_exec::{
         dedicated::DedicatedDataFusionPlanExec, noop::NoopDataFusionPlanExec, DataFusionPlanExec,
     },
-    df_planner::{planner_v1::V1DataFusionPlanner, DataFusionPlanner},
+    df_planner::{
+        planner_v1::V1DataFusionPlanner, rollup::RollupDataFusionPlanner, DataFusionPlanner,
+    },
     divide_initial::multiple_branches::MultipleBranchesDivideInitial,
     file_classifier::{
         logging::LoggingFileClassifierWrapper, split_based::SplitBasedFileClassifier,
@@ -45,20 +53,21 @@ use super::{
     },
     parquet_files_sink::{dispatch::DispatchParquetFilesSink, ParquetFilesSink},
     partition_done_sink::{
-        catalog::CatalogPartitionDoneSink, error_kind::ErrorKindPartitionDoneSinkWrapper,
-        logging::LoggingPartitionDoneSinkWrapper, metrics::MetricsPartitionDoneSinkWrapper,
-        mock::MockPartitionDoneSink, PartitionDoneSink,
+        admin::AdminPartitionDoneSinkWrapper, catalog::CatalogPartitionDoneSink,
+        error_kind::ErrorKindPartitionDoneSinkWrapper, logging::LoggingPartitionDoneSinkWrapper,
+        metrics::MetricsPartitionDoneSinkWrapper, mock::MockPartitionDoneSink, PartitionDoneSink,
     },
     partition_files_source::{catalog::CatalogPartitionFilesSource, PartitionFilesSource},
     partition_filter::{
         and::AndPartitionFilter, greater_matching_files::GreaterMatchingFilesPartitionFilter,
         greater_size_matching_files::GreaterSizeMatchingFilesPartitionFilter,
-        has_files::HasFilesPartitionFilter, has_matching_file::HasMatchingFilePartitionFilter,
-        logging::LoggingPartitionFilterWrapper, max_num_columns::MaxNumColumnsPartitionFilter,
-        metrics::MetricsPartitionFilterWrapper, never_skipped::NeverSkippedPartitionFilter,
-        or::OrPartitionFilter, PartitionFilter,
+        has_files::HasFilesPartitionFilter, has_lease::HasLeasePartitionFilter,
+        has_matching_file::HasMatchingFilePartitionFilter, logging::LoggingPartitionFilterWrapper,
+        max_num_columns::MaxNumColumnsPartitionFilter, metrics::MetricsPartitionFilterWrapper,
+        never_skipped::NeverSkippedPartitionFilter, or::OrPartitionFilter, PartitionFilter,
     },
     partition_info_source::{sub_sources::SubSourcePartitionInfoSource, PartitionInfoSource},
+    partition_leases::catalog::CatalogPartitionLeases,
     partition_source::{
         catalog::CatalogPartitionSource, logging::LoggingPartitionSourceWrapper,
         metrics::MetricsPartitionSourceWrapper,
@@ -67,7 +76,7 @@ use super::{
         endless::EndlessPartititionStream, once::OncePartititionStream, PartitionStream,
     },
     partitions_source::{
-        catalog_all::CatalogAllPartitionsSource,
+        admin_queue::AdminQueuePartitionsSourceWrapper, catalog_all::CatalogAllPartitionsSource,
         catalog_to_compact::CatalogToCompactPartitionsSource,
         filter::FilterPartitionsSourceWrapper, logging::LoggingPartitionsSourceWrapper,
         metrics::MetricsPartitionsSourceWrapper, mock::MockPartitionsSource,
@@ -79,6 +88,9 @@ use super::{
         metrics::MetricsPostClassificationFilterWrapper, possible_progress::PossibleProgressFilter,
         PostClassificationPartitionFilter,
     },
+    rollup_rules::{
+        catalog::CatalogRollupRules, read_only::ReadOnlyRollupRulesWrapper, RollupRules,
+    },
     round_info_source::{LevelBasedRoundInfo, LoggingRoundInfoWrapper, RoundInfoSource},
     round_split::many_files::ManyFilesRoundSplit,
     scratchpad::{noop::NoopScratchpadGen, prod::ProdScratchpadGen, ScratchpadGen},
@@ -93,19 +105,20 @@ use super::{
 
 /// Get hardcoded components.
 pub fn hardcoded_components(config: &Config) -> Arc<Components> {
-    let (partitions_source, commit, partition_done_sink) =
-        make_partitions_source_commit_partition_sink(config);
+    let rollup_rules = make_rollup_rules(config);
+    let (partitions_source, partition_info_source, commit, partition_done_sink, pending_rollups) =
+        make_partitions_source_commit_partition_sink(config, rollup_rules.clone());
 
     Arc::new(Components {
         partition_stream: make_partition_stream(config, partitions_source),
-        partition_info_source: make_partition_info_source(config),
+        partition_info_source,
         partition_files_source: make_partition_files_source(config),
         round_info_source: make_round_info_source(config),
         partition_filter: make_partition_filter(config),
         partition_done_sink,
         commit,
         ir_planner: make_ir_planner(config),
-        df_planner: make_df_planner(config),
+        df_planner: make_df_planner(config, rollup_rules.zip(pending_rollups)),
         df_plan_exec: make_df_plan_exec(config),
         parquet_files_sink: make_parquet_files_sink(config),
         round_split: Arc::new(ManyFilesRoundSplit::new()),
@@ -119,10 +132,13 @@ pub fn hardcoded_components(config: &Config) -> Arc<Components> {
 
 fn make_partitions_source_commit_partition_sink(
     config: &Config,
+    rollup_rules: Option<Arc<dyn RollupRules>>,
 ) -> (
     Arc<dyn PartitionsSource>,
+    Arc<dyn PartitionInfoSource>,
     Arc<dyn Commit>,
     Arc<dyn PartitionDoneSink>,
+    Option<Arc<PendingRollups>>,
 ) {
     let partitions_source: Arc<dyn PartitionsSource> = match &config.partitions_source {
         PartitionsSourceConfig::CatalogRecentWrites { threshold } => {
@@ -165,11 +181,11 @@ fn make_partitions_source_commit_partition_sink(
         AndIdOnlyPartitionFilter::new(id_only_partition_filters),
         partitions_source,
     );
+    // explicitly requested partitions bypass the filters above
+    let partitions_source =
+        AdminQueuePartitionsSourceWrapper::new(Arc::clone(&config.admin), partitions_source);
 
-    // Temporarily do nothing for cold compaction until we check the cold compaction selection.
-    let shadow_mode = config.shadow_mode || config.compaction_type == CompactionType::Cold;
-
-    let partition_done_sink: Arc<dyn PartitionDoneSink> = if shadow_mode {
+    let partition_done_sink: Arc<dyn PartitionDoneSink> = if config.shadow_mode {
         Arc::new(MockPartitionDoneSink::new())
     } else {
         Arc::new(CatalogPartitionDoneSink::new(
@@ -178,7 +194,7 @@ fn make_partitions_source_commit_partition_sink(
         ))
     };
 
-    let commit: Arc<dyn Commit> = if shadow_mode {
+    let commit: Arc<dyn Commit> = if config.shadow_mode {
         Arc::new(MockCommit::new())
     } else {
         Arc::new(CatalogCommit::new(
@@ -187,15 +203,79 @@ fn make_partitions_source_commit_partition_sink(
         ))
     };
 
+    // Temporarily do not modify the catalog for cold compaction until we check the cold compaction
+    // selection, except for partitions that are rolled up.
+    let partition_info_source = make_partition_info_source(config);
+    let (partition_info_source, commit, partition_done_sink): (
+        Arc<dyn PartitionInfoSource>,
+        Arc<dyn Commit>,
+        Arc<dyn PartitionDoneSink>,
+    ) = match &rollup_rules {
+        Some(rollup_rules) if !config.shadow_mode => {
+            let (partition_info_source, commit, partition_done_sink) = rollup_only(
+                partition_info_source,
+                commit,
+                MockCommit::new(),
+                partition_done_sink,
+                MockPartitionDoneSink::new(),
+                Arc::clone(rollup_rules),
+            );
+            (
+                Arc::new(partition_info_source),
+                Arc::new(commit),
+                Arc::new(partition_done_sink),
+            )
+        }
+        _ => (partition_info_source, commit, partition_done_sink),
+    };
+
+    // partitions skipped by the wrappers below are forwarded to this sink directly
+    let partition_done_sink: Arc<dyn PartitionDoneSink> = Arc::new(
+        AdminPartitionDoneSinkWrapper::new(Arc::clone(&config.admin), partition_done_sink),
+    );
+
     let commit = if let Some(commit_wrapper) = config.commit_wrapper.as_ref() {
         commit_wrapper.wrap(commit)
     } else {
         commit
     };
 
+    let (commit, partition_done_sink, pending_rollups): (
+        Arc<dyn Commit>,
+        Arc<dyn PartitionDoneSink>,
+        _,
+    ) = if let Some(rollup_rules) = rollup_rules {
+        let (commit, partition_done_sink, pending_rollups) =
+            rollup_partitions(commit, partition_done_sink, rollup_rules);
+        (
+            Arc::new(commit),
+            Arc::new(partition_done_sink),
+            Some(pending_rollups),
+        )
+    } else {
+        (commit, partition_done_sink, None)
+    };
+
     let (partitions_source, partition_done_sink) =
         unique_partitions(partitions_source, partition_done_sink, 1);
 
+    let (partitions_source, partition_done_sink): (
+        Arc<dyn PartitionsSource>,
+        Arc<dyn PartitionDoneSink>,
+    ) = if let Some(lease_config) = &config.lease_config {
+        let (partitions_source, partition_done_sink) = lease_partitions(
+            partitions_source,
+            partition_done_sink,
+            make_partition_leases(config, lease_config),
+            lease_config.heartbeat_interval,
+            config.partition_concurrency.get(),
+            10,
+        );
+        (Arc::new(partitions_source), Arc::new(partition_done_sink))
+    } else {
+        (Arc::new(partitions_source), Arc::new(partition_done_sink))
+    };
+
     let (partitions_source, commit, partition_done_sink) = throttle_partition(
         partitions_source,
         commit,
@@ -253,7 +333,22 @@ fn make_partitions_source_commit_partition_sink(
         ))
     };
 
-    (partitions_source, commit, partition_done_sink)
+    (
+        partitions_source,
+        partition_info_source,
+        commit,
+        partition_done_sink,
+        pending_rollups,
+    )
+}
+
+fn make_partition_leases(config: &Config, lease_config: &LeaseConfig) -> CatalogPartitionLeases {
+    CatalogPartitionLeases::new(
+        config.backoff_config.clone(),
+        Arc::clone(&config.catalog),
+        lease_config.owner.clone(),
+        lease_config.lease_duration,
+    )
 }
 
 fn make_partition_stream(
@@ -329,6 +424,13 @@ fn exceptional_cases_partition_filters(config: &Config) -> Vec<Arc<dyn Partition
         config.max_num_columns_per_table,
     )));
 
+    if let Some(lease_config) = &config.lease_config {
+        // renews the lease, so only check it for partitions that passed the filters above
+        partition_filters.push(Arc::new(HasLeasePartitionFilter::new(
+            make_partition_leases(config, lease_config),
+        )));
+    }
+
     partition_filters
 }
 
@@ -363,10 +465,40 @@ fn make_ir_planner(config: &Config) -> Arc<dyn IRPlanner> {
     )))
 }
 
-fn make_df_planner(config: &Config) -> Arc<dyn DataFusionPlanner> {
-    Arc::new(V1DataFusionPlanner::new(
+/// Rollup rules are only applied by cold compaction.
+fn make_rollup_rules(config: &Config) -> Option<Arc<dyn RollupRules>> {
+    if config.compaction_type != CompactionType::Cold {
+        return None;
+    }
+
+    let rules = CatalogRollupRules::new(config.backoff_config.clone(), Arc::clone(&config.catalog));
+    if config.shadow_mode {
+        Some(Arc::new(ReadOnlyRollupRulesWrapper::new(rules)))
+    } else {
+        Some(Arc::new(rules))
+    }
+}
+
+fn make_df_planner(
+    config: &Config,
+    rollups: Option<(Arc<dyn RollupRules>, Arc<PendingRollups>)>,
+) -> Arc<dyn DataFusionPlanner> {
+    let planner = V1DataFusionPlanner::new(
+        config.parquet_store_scratchpad.clone(),
+        Arc::clone(&config.exec),
+    );
+
+    let Some((rollup_rules, pending_rollups)) = rollups else {
+        return Arc::new(planner);
+    };
+
+    Arc::new(RollupDataFusionPlanner::new(
+        planner,
+        rollup_rules,
+        pending_rollups,
         config.parquet_store_scratchpad.clone(),
         Arc::clone(&config.exec),
+        Arc::clone(&config.time_provider),
     ))
 }
 
@@ -30,11 +30,13 @@ pub mod partition_done_sink;
 pub mod partition_files_source;
 pub mod partition_filter;
 pub mod partition_info_source;
+pub mod partition_leases;
 pub mod partition_source;
 pub mod partition_stream;
 pub mod partitions_source;
 pub mod post_classification_partition_filter;
 pub mod report;
+pub mod rollup_rules;
 pub mod round_info_source;
 pub mod round_split;
 pub mod scratchpad;
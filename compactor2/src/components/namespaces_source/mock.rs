@@ -148,6 +148,7 @@ mod tests {
                                 },
                             ),
                         ]),
+                        primary_key: vec![],
                     },
                 ),
                 (
@@ -177,6 +178,7 @@ mod tests {
                                 },
                             ),
                         ]),
+                        primary_key: vec![],
                     },
                 ),
             ]);
@@ -194,6 +196,12 @@ mod tests {
                         max_tables: 10,
                         max_columns_per_table: 10,
                         retention_period_ns: None,
+                        max_lines_per_second: None,
+                        max_batch_bytes_per_second: None,
+                        max_concurrent_queries: None,
+                        max_query_memory_bytes: None,
+                        type_coercion_policy: Default::default(),
+                        default_query_priority: Default::default(),
                         deleted_at: None,
                     },
                     schema: NamespaceSchema {
@@ -204,6 +212,10 @@ mod tests {
                         max_columns_per_table: 10,
                         max_tables: 42,
                         retention_period_ns: None,
+                        write_rate_limit: Default::default(),
+                        query_quota: Default::default(),
+                        type_coercion_policy: Default::default(),
+                        default_query_priority: Default::default(),
                     },
                 },
             }
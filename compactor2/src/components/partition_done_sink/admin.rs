@@ -0,0 +1,120 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+
+use crate::{admin::CompactionAdmin, error::DynError};
+
+use super::PartitionDoneSink;
+
+/// Reports done partitions to the [admin API](CompactionAdmin), so that requested partitions which were skipped
+/// (e.g. because another compactor holds their lease) are not shown as queued forever.
+///
+/// This must wrap the innermost sink, because partitions that are skipped by other wrappers are forwarded to their
+/// inner sink directly.
+#[derive(Debug)]
+pub struct AdminPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    admin: Arc<CompactionAdmin>,
+    inner: T,
+}
+
+impl<T> AdminPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    pub fn new(admin: Arc<CompactionAdmin>, inner: T) -> Self {
+        Self { admin, inner }
+    }
+}
+
+impl<T> Display for AdminPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "admin({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionDoneSink for AdminPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    async fn record(&self, partition: PartitionId, res: Result<(), DynError>) {
+        self.admin.partition_done(partition, || match &res {
+            Ok(()) => String::from(
+                "skipped by this compactor, e.g. because the partition is leased by another compactor",
+            ),
+            Err(e) => e.to_string(),
+        });
+        self.inner.record(partition, res).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use iox_time::{MockProvider, Time};
+
+    use crate::{
+        admin::CompactionJobState, components::partition_done_sink::mock::MockPartitionDoneSink,
+    };
+
+    use super::*;
+
+    fn admin() -> Arc<CompactionAdmin> {
+        Arc::new(CompactionAdmin::new(Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(0),
+        ))))
+    }
+
+    #[test]
+    fn test_display() {
+        let sink = AdminPartitionDoneSinkWrapper::new(admin(), MockPartitionDoneSink::new());
+        assert_eq!(sink.to_string(), "admin(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let admin = admin();
+        let inner = Arc::new(MockPartitionDoneSink::new());
+        let sink = AdminPartitionDoneSinkWrapper::new(Arc::clone(&admin), Arc::clone(&inner));
+
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+        admin.enqueue([p_1, p_2]);
+        admin.take_queued();
+
+        sink.record(p_1, Ok(())).await;
+        sink.record(p_2, Err("foo".into())).await;
+        // not requested via the admin API
+        sink.record(p_3, Ok(())).await;
+
+        let jobs = admin.jobs();
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs
+            .iter()
+            .all(|job| job.state == CompactionJobState::Failed));
+        assert!(jobs[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .starts_with("skipped"));
+        assert_eq!(jobs[1].last_error.as_deref(), Some("foo"));
+
+        assert_eq!(
+            inner.results(),
+            HashMap::from([
+                (p_1, Ok(())),
+                (p_2, Err(String::from("foo"))),
+                (p_3, Ok(())),
+            ]),
+        );
+    }
+}
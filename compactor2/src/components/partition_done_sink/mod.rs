@@ -8,6 +8,7 @@ use data_types::PartitionId;
 
 use crate::error::DynError;
 
+pub mod admin;
 pub mod catalog;
 pub mod error_kind;
 pub mod logging;
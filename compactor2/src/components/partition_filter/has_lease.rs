@@ -0,0 +1,81 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use data_types::ParquetFile;
+
+use crate::{components::partition_leases::PartitionLeases, error::DynError, PartitionInfo};
+
+use super::PartitionFilter;
+
+/// Only continues to compact a partition while its lease is held by this compactor.
+///
+/// Every application renews the lease, so a partition that was taken over by another compactor (e.g. because this
+/// one failed to renew the lease in time) is not compacted any further.
+#[derive(Debug)]
+pub struct HasLeasePartitionFilter<T>
+where
+    T: PartitionLeases,
+{
+    leases: T,
+}
+
+impl<T> HasLeasePartitionFilter<T>
+where
+    T: PartitionLeases,
+{
+    pub fn new(leases: T) -> Self {
+        Self { leases }
+    }
+}
+
+impl<T> Display for HasLeasePartitionFilter<T>
+where
+    T: PartitionLeases,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "has_lease({})", self.leases)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionFilter for HasLeasePartitionFilter<T>
+where
+    T: PartitionLeases,
+{
+    async fn apply(
+        &self,
+        partition_info: &PartitionInfo,
+        _files: &[ParquetFile],
+    ) -> Result<bool, DynError> {
+        Ok(self.leases.acquire(partition_info.partition_id).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        components::partition_leases::mock::MockPartitionLeases, test_utils::PartitionInfoBuilder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let filter = HasLeasePartitionFilter::new(MockPartitionLeases::new());
+        assert_eq!(filter.to_string(), "has_lease(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_apply() {
+        let leases = Arc::new(MockPartitionLeases::new());
+        let filter = HasLeasePartitionFilter::new(Arc::clone(&leases));
+        let p_info = Arc::new(PartitionInfoBuilder::new().build());
+
+        assert!(filter.apply(&p_info, &[]).await.unwrap());
+
+        leases.take(p_info.partition_id);
+        assert!(!filter.apply(&p_info, &[]).await.unwrap());
+    }
+}
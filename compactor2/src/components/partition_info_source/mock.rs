@@ -0,0 +1,62 @@
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+
+use crate::{error::DynError, partition_info::PartitionInfo};
+
+use super::PartitionInfoSource;
+
+#[derive(Debug)]
+pub struct MockPartitionInfoSource {
+    partitions: HashMap<PartitionId, Arc<PartitionInfo>>,
+}
+
+impl MockPartitionInfoSource {
+    #[allow(dead_code)] // not used anywhere
+    pub fn new(partitions: Vec<Arc<PartitionInfo>>) -> Self {
+        Self {
+            partitions: partitions
+                .into_iter()
+                .map(|p| (p.partition_id, p))
+                .collect(),
+        }
+    }
+}
+
+impl Display for MockPartitionInfoSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "mock")
+    }
+}
+
+#[async_trait]
+impl PartitionInfoSource for MockPartitionInfoSource {
+    async fn fetch(&self, partition_id: PartitionId) -> Result<Arc<PartitionInfo>, DynError> {
+        self.partitions
+            .get(&partition_id)
+            .cloned()
+            .ok_or_else(|| String::from("Cannot find partition info").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::PartitionInfoBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MockPartitionInfoSource::new(vec![]).to_string(), "mock");
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let p1 = Arc::new(PartitionInfoBuilder::new().with_partition_id(1).build());
+        let source = MockPartitionInfoSource::new(vec![Arc::clone(&p1)]);
+
+        assert_eq!(source.fetch(PartitionId::new(1)).await.unwrap(), p1);
+        assert!(source.fetch(PartitionId::new(2)).await.is_err());
+    }
+}
@@ -8,6 +8,7 @@ use data_types::PartitionId;
 
 use crate::{error::DynError, partition_info::PartitionInfo};
 
+pub mod mock;
 pub mod sub_sources;
 
 /// Fetches the subset of information about a partition neededed for compaction
@@ -15,3 +16,13 @@ pub mod sub_sources;
 pub trait PartitionInfoSource: Debug + Display + Send + Sync {
     async fn fetch(&self, partition_id: PartitionId) -> Result<Arc<PartitionInfo>, DynError>;
 }
+
+#[async_trait]
+impl<T> PartitionInfoSource for Arc<T>
+where
+    T: PartitionInfoSource + ?Sized,
+{
+    async fn fetch(&self, partition_id: PartitionId) -> Result<Arc<PartitionInfo>, DynError> {
+        self.as_ref().fetch(partition_id).await
+    }
+}
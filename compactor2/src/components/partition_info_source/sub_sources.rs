@@ -104,6 +104,7 @@ where
             table_schema: Arc::new(table_schema.clone()),
             sort_key: partition.sort_key(),
             partition_key: partition.partition_key,
+            rollup_interval_ns: partition.rollup_interval_ns,
         }))
     }
 }
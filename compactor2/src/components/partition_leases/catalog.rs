@@ -0,0 +1,112 @@
+use std::{fmt::Display, ops::ControlFlow, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::PartitionId;
+use iox_catalog::interface::{Catalog, Error};
+use observability_deps::tracing::warn;
+
+use super::PartitionLeases;
+
+#[derive(Debug)]
+pub struct CatalogPartitionLeases {
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+    owner: String,
+    lease_duration: Duration,
+}
+
+impl CatalogPartitionLeases {
+    pub fn new(
+        backoff_config: BackoffConfig,
+        catalog: Arc<dyn Catalog>,
+        owner: String,
+        lease_duration: Duration,
+    ) -> Self {
+        Self {
+            backoff_config,
+            catalog,
+            owner,
+            lease_duration,
+        }
+    }
+}
+
+impl Display for CatalogPartitionLeases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "catalog({})", self.owner)
+    }
+}
+
+#[async_trait]
+impl PartitionLeases for CatalogPartitionLeases {
+    async fn acquire(&self, partition: PartitionId) -> bool {
+        Backoff::new(&self.backoff_config)
+            .retry_with_backoff("acquire_partition_lease", || async {
+                match self
+                    .catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .try_acquire_lease(partition, &self.owner, self.lease_duration)
+                    .await
+                {
+                    Ok(acquired) => ControlFlow::Break(acquired),
+                    // the partition was deleted, there is nothing left to lease
+                    Err(Error::PartitionNotFound { .. }) => {
+                        warn!(
+                            partition_id = partition.get(),
+                            "partition not found, cannot lease it"
+                        );
+                        ControlFlow::Break(false)
+                    }
+                    Err(e) => ControlFlow::Continue(e),
+                }
+            })
+            .await
+            .expect("retry forever")
+    }
+
+    async fn release(&self, partition: PartitionId) {
+        Backoff::new(&self.backoff_config)
+            .retry_with_backoff("release_partition_lease", || async {
+                match self
+                    .catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .release_lease(partition, &self.owner)
+                    .await
+                {
+                    // a deleted partition has no lease left
+                    Ok(()) | Err(Error::PartitionNotFound { .. }) => ControlFlow::Break(()),
+                    Err(e) => ControlFlow::Continue(e),
+                }
+            })
+            .await
+            .expect("retry forever")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_catalog::mem::MemCatalog;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deleted_partition() {
+        let catalog = Arc::new(MemCatalog::new(Arc::new(metric::Registry::new())));
+        let leases = CatalogPartitionLeases::new(
+            BackoffConfig::default(),
+            catalog,
+            String::from("c1"),
+            Duration::from_secs(60),
+        );
+
+        // does not retry forever
+        let partition = PartitionId::new(1);
+        assert!(!leases.acquire(partition).await);
+        leases.release(partition).await;
+    }
+}
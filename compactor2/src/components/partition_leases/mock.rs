@@ -0,0 +1,86 @@
+use std::{collections::HashSet, fmt::Display, sync::Mutex};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+
+use super::PartitionLeases;
+
+/// Mock leases where a fixed set of partitions is held by other compactors.
+#[derive(Debug, Default)]
+pub struct MockPartitionLeases {
+    taken: Mutex<HashSet<PartitionId>>,
+    held: Mutex<HashSet<PartitionId>>,
+}
+
+impl MockPartitionLeases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretend that another compactor holds the lease for the given partition.
+    #[allow(dead_code)] // not used anywhere
+    pub fn take(&self, partition: PartitionId) {
+        self.held.lock().expect("not poisoned").remove(&partition);
+        self.taken.lock().expect("not poisoned").insert(partition);
+    }
+
+    /// Partitions for which the lease is currently held.
+    #[allow(dead_code)] // not used anywhere
+    pub fn held(&self) -> HashSet<PartitionId> {
+        self.held.lock().expect("not poisoned").clone()
+    }
+}
+
+impl Display for MockPartitionLeases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "mock")
+    }
+}
+
+#[async_trait]
+impl PartitionLeases for MockPartitionLeases {
+    async fn acquire(&self, partition: PartitionId) -> bool {
+        if self
+            .taken
+            .lock()
+            .expect("not poisoned")
+            .contains(&partition)
+        {
+            return false;
+        }
+        self.held.lock().expect("not poisoned").insert(partition);
+        true
+    }
+
+    async fn release(&self, partition: PartitionId) {
+        self.held.lock().expect("not poisoned").remove(&partition);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MockPartitionLeases::new().to_string(), "mock");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_release() {
+        let leases = MockPartitionLeases::new();
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+
+        assert!(leases.acquire(p_1).await);
+        assert!(leases.acquire(p_2).await);
+        assert_eq!(leases.held(), HashSet::from([p_1, p_2]));
+
+        leases.release(p_1).await;
+        assert_eq!(leases.held(), HashSet::from([p_2]));
+
+        leases.take(p_2);
+        assert!(!leases.acquire(p_2).await);
+        assert_eq!(leases.held(), HashSet::new());
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+
+pub mod catalog;
+pub mod mock;
+
+/// Time-limited, exclusive claims on partitions that are shared between multiple compactors.
+#[async_trait]
+pub trait PartitionLeases: Debug + Display + Send + Sync {
+    /// Acquire or renew the lease for the given partition.
+    ///
+    /// Returns `true` if this compactor holds the lease afterwards.
+    ///
+    /// This method performs retries.
+    async fn acquire(&self, partition: PartitionId) -> bool;
+
+    /// Release the lease for the given partition.
+    ///
+    /// This is a no-op if the lease is not held by this compactor.
+    ///
+    /// This method performs retries.
+    async fn release(&self, partition: PartitionId);
+}
+
+#[async_trait]
+impl<T> PartitionLeases for Arc<T>
+where
+    T: PartitionLeases + ?Sized,
+{
+    async fn acquire(&self, partition: PartitionId) -> bool {
+        self.as_ref().acquire(partition).await
+    }
+
+    async fn release(&self, partition: PartitionId) {
+        self.as_ref().release(partition).await
+    }
+}
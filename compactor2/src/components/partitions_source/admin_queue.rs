@@ -0,0 +1,101 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+use observability_deps::tracing::info;
+
+use crate::admin::CompactionAdmin;
+
+use super::PartitionsSource;
+
+/// Merges partitions requested via the [admin API](CompactionAdmin) with the ones of the inner source.
+///
+/// Requested partitions take priority: if there are any, only those are returned and the inner source is consulted
+/// on the next fetch.
+#[derive(Debug)]
+pub struct AdminQueuePartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    admin: Arc<CompactionAdmin>,
+    inner: T,
+}
+
+impl<T> AdminQueuePartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    pub fn new(admin: Arc<CompactionAdmin>, inner: T) -> Self {
+        Self { admin, inner }
+    }
+}
+
+impl<T> Display for AdminQueuePartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "admin_queue({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionsSource for AdminQueuePartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let queued = self.admin.take_queued();
+        if queued.is_empty() {
+            return self.inner.fetch().await;
+        }
+
+        info!(
+            n_partitions = queued.len(),
+            "fetched partitions from admin queue"
+        );
+        queued
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_time::{MockProvider, Time};
+
+    use crate::components::partitions_source::mock::MockPartitionsSource;
+
+    use super::*;
+
+    fn admin() -> Arc<CompactionAdmin> {
+        Arc::new(CompactionAdmin::new(Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(0),
+        ))))
+    }
+
+    #[test]
+    fn test_display() {
+        let source =
+            AdminQueuePartitionsSourceWrapper::new(admin(), MockPartitionsSource::new(vec![]));
+        assert_eq!(source.to_string(), "admin_queue(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+
+        let admin = admin();
+        let source = AdminQueuePartitionsSourceWrapper::new(
+            Arc::clone(&admin),
+            MockPartitionsSource::new(vec![p_1, p_2]),
+        );
+        assert_eq!(source.fetch().await, vec![p_1, p_2]);
+
+        admin.enqueue([p_3, p_1]);
+        assert_eq!(source.fetch().await, vec![p_3, p_1]);
+
+        // queue is drained
+        assert_eq!(source.fetch().await, vec![p_1, p_2]);
+    }
+}
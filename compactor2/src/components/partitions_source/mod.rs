@@ -6,6 +6,7 @@ use std::{
 use async_trait::async_trait;
 use data_types::PartitionId;
 
+pub mod admin_queue;
 pub mod catalog_all;
 pub mod catalog_to_compact;
 pub mod filter;
@@ -2,7 +2,7 @@
 
 use observability_deps::tracing::info;
 
-use crate::config::{Config, ShardConfig};
+use crate::config::{Config, LeaseConfig, ShardConfig};
 
 use super::Components;
 
@@ -31,6 +31,7 @@ pub fn log_config(config: &Config) {
         shadow_mode,
         ignore_partition_skip_marker,
         shard_config,
+        lease_config,
         min_num_l1_files_to_compact,
         process_once,
         parquet_files_sink_override,
@@ -39,6 +40,8 @@ pub fn log_config(config: &Config) {
         all_errors_are_fatal,
         max_num_columns_per_table,
         max_num_files_per_plan,
+        // no need to print the runtime state
+        admin: _,
     } = &config;
 
     let (shard_cfg_n_shards, shard_cfg_shard_id) = match shard_config {
@@ -50,6 +53,24 @@ pub fn log_config(config: &Config) {
         }
     };
 
+    let (lease_cfg_owner, lease_cfg_lease_duration_secs, lease_cfg_heartbeat_interval_secs) =
+        match lease_config {
+            None => (None, None, None),
+            Some(lease_config) => {
+                // use struct unpack so we don't forget any members
+                let LeaseConfig {
+                    owner,
+                    lease_duration,
+                    heartbeat_interval,
+                } = lease_config;
+                (
+                    Some(owner),
+                    Some(lease_duration.as_secs_f32()),
+                    Some(heartbeat_interval.as_secs_f32()),
+                )
+            }
+        };
+
     let parquet_files_sink_override = parquet_files_sink_override
         .as_ref()
         .map(|_| "Some")
@@ -78,6 +99,9 @@ pub fn log_config(config: &Config) {
         ignore_partition_skip_marker,
         ?shard_cfg_n_shards,
         ?shard_cfg_shard_id,
+        ?lease_cfg_owner,
+        ?lease_cfg_lease_duration_secs,
+        ?lease_cfg_heartbeat_interval_secs,
         min_num_l1_files_to_compact,
         process_once,
         simulate_without_object_store,
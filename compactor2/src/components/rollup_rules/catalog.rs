@@ -0,0 +1,60 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::{NamespaceId, PartitionId, RollupRule, TableId};
+use iox_catalog::interface::Catalog;
+
+use super::RollupRules;
+
+#[derive(Debug)]
+pub struct CatalogRollupRules {
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+}
+
+impl CatalogRollupRules {
+    pub fn new(backoff_config: BackoffConfig, catalog: Arc<dyn Catalog>) -> Self {
+        Self {
+            backoff_config,
+            catalog,
+        }
+    }
+}
+
+impl Display for CatalogRollupRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "catalog")
+    }
+}
+
+#[async_trait]
+impl RollupRules for CatalogRollupRules {
+    async fn fetch(&self, namespace_id: NamespaceId, table_id: TableId) -> Option<RollupRule> {
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("rollup_rule_for_table", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .rollup_rules()
+                    .get_for_table(namespace_id, table_id)
+                    .await
+            })
+            .await
+            .expect("retry forever")
+    }
+
+    async fn record(&self, partition_id: PartitionId, rollup_interval_ns: i64) {
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("set_rollup_interval", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .set_rollup_interval(partition_id, rollup_interval_ns)
+                    .await
+            })
+            .await
+            .expect("retry forever");
+    }
+}
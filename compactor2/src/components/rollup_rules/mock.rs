@@ -0,0 +1,120 @@
+use std::{collections::HashMap, fmt::Display, sync::Mutex};
+
+use async_trait::async_trait;
+use data_types::{NamespaceId, PartitionId, RollupRule, TableId};
+
+use super::RollupRules;
+
+#[derive(Debug, Default)]
+pub struct MockRollupRules {
+    rules: Vec<RollupRule>,
+    recorded: Mutex<HashMap<PartitionId, i64>>,
+}
+
+impl MockRollupRules {
+    pub fn new(rules: Vec<RollupRule>) -> Self {
+        Self {
+            rules,
+            recorded: Default::default(),
+        }
+    }
+
+    #[allow(dead_code)] // not used anywhere
+    pub fn recorded(&self) -> HashMap<PartitionId, i64> {
+        self.recorded.lock().expect("not poisoned").clone()
+    }
+}
+
+impl Display for MockRollupRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "mock")
+    }
+}
+
+#[async_trait]
+impl RollupRules for MockRollupRules {
+    async fn fetch(&self, namespace_id: NamespaceId, table_id: TableId) -> Option<RollupRule> {
+        let mut rules = self.rules.iter().filter(|r| r.namespace_id == namespace_id);
+        let table_rule = rules.clone().find(|r| r.table_id == Some(table_id));
+        let namespace_rule = rules.find(|r| r.table_id.is_none());
+        table_rule.or(namespace_rule).cloned()
+    }
+
+    async fn record(&self, partition_id: PartitionId, rollup_interval_ns: i64) {
+        self.recorded
+            .lock()
+            .expect("not poisoned")
+            .insert(partition_id, rollup_interval_ns);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use data_types::{RollupAggregate, RollupRuleId};
+
+    use super::*;
+
+    pub(crate) fn rule(id: i64, namespace_id: i64, table_id: Option<i64>) -> RollupRule {
+        RollupRule {
+            id: RollupRuleId::new(id),
+            namespace_id: NamespaceId::new(namespace_id),
+            table_id: table_id.map(TableId::new),
+            interval_ns: 300_000_000_000,
+            min_age_ns: 1_000,
+            float_aggregate: RollupAggregate::Mean,
+            integer_aggregate: RollupAggregate::Sum,
+            unsigned_aggregate: RollupAggregate::Sum,
+            boolean_aggregate: RollupAggregate::Last,
+            string_aggregate: RollupAggregate::Last,
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MockRollupRules::new(vec![]).to_string(), "mock");
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let ns_rule = rule(1, 1, None);
+        let table_rule = rule(2, 1, Some(2));
+        let other_ns_rule = rule(3, 2, None);
+        let rules = MockRollupRules::new(vec![
+            ns_rule.clone(),
+            table_rule.clone(),
+            other_ns_rule.clone(),
+        ]);
+
+        assert_eq!(
+            rules.fetch(NamespaceId::new(1), TableId::new(2)).await,
+            Some(table_rule)
+        );
+        assert_eq!(
+            rules.fetch(NamespaceId::new(1), TableId::new(3)).await,
+            Some(ns_rule)
+        );
+        assert_eq!(
+            rules.fetch(NamespaceId::new(2), TableId::new(4)).await,
+            Some(other_ns_rule)
+        );
+        assert_eq!(
+            rules.fetch(NamespaceId::new(3), TableId::new(5)).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let rules = MockRollupRules::new(vec![]);
+        assert!(rules.recorded().is_empty());
+
+        rules.record(PartitionId::new(1), 10).await;
+        rules.record(PartitionId::new(2), 20).await;
+        rules.record(PartitionId::new(1), 30).await;
+
+        assert_eq!(
+            rules.recorded(),
+            HashMap::from([(PartitionId::new(1), 30), (PartitionId::new(2), 20)]),
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use data_types::{NamespaceId, PartitionId, RollupRule, TableId};
+
+pub mod catalog;
+pub mod mock;
+pub mod read_only;
+
+/// Rules that describe how aged data is downsampled, and the record of which partitions were
+/// rolled up.
+#[async_trait]
+pub trait RollupRules: Debug + Display + Send + Sync {
+    /// Get the rule that applies to the given table, if any.
+    ///
+    /// This method performs retries.
+    async fn fetch(&self, namespace_id: NamespaceId, table_id: TableId) -> Option<RollupRule>;
+
+    /// Record that the data of the given partition is rolled up to windows of
+    /// `rollup_interval_ns` nanoseconds.
+    ///
+    /// This method performs retries.
+    async fn record(&self, partition_id: PartitionId, rollup_interval_ns: i64);
+}
+
+#[async_trait]
+impl<T> RollupRules for Arc<T>
+where
+    T: RollupRules + ?Sized,
+{
+    async fn fetch(&self, namespace_id: NamespaceId, table_id: TableId) -> Option<RollupRule> {
+        self.as_ref().fetch(namespace_id, table_id).await
+    }
+
+    async fn record(&self, partition_id: PartitionId, rollup_interval_ns: i64) {
+        self.as_ref().record(partition_id, rollup_interval_ns).await
+    }
+}
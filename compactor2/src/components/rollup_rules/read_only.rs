@@ -0,0 +1,83 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use data_types::{NamespaceId, PartitionId, RollupRule, TableId};
+use observability_deps::tracing::info;
+
+use super::RollupRules;
+
+/// Reads rules from the inner source but only logs rolled up partitions instead of recording
+/// them.
+///
+/// Used in shadow mode, where the output of the compaction is never committed.
+#[derive(Debug)]
+pub struct ReadOnlyRollupRulesWrapper<T>
+where
+    T: RollupRules,
+{
+    inner: T,
+}
+
+impl<T> ReadOnlyRollupRulesWrapper<T>
+where
+    T: RollupRules,
+{
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> Display for ReadOnlyRollupRulesWrapper<T>
+where
+    T: RollupRules,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "read_only({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> RollupRules for ReadOnlyRollupRulesWrapper<T>
+where
+    T: RollupRules,
+{
+    async fn fetch(&self, namespace_id: NamespaceId, table_id: TableId) -> Option<RollupRule> {
+        self.inner.fetch(namespace_id, table_id).await
+    }
+
+    async fn record(&self, partition_id: PartitionId, rollup_interval_ns: i64) {
+        info!(
+            partition_id = partition_id.get(),
+            rollup_interval_ns, "not recording rollup in read-only mode",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::components::rollup_rules::mock::{tests::rule, MockRollupRules};
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let rules = ReadOnlyRollupRulesWrapper::new(MockRollupRules::new(vec![]));
+        assert_eq!(rules.to_string(), "read_only(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_record() {
+        let inner = Arc::new(MockRollupRules::new(vec![rule(1, 1, None)]));
+        let rules = ReadOnlyRollupRulesWrapper::new(Arc::clone(&inner));
+
+        assert_eq!(
+            rules.fetch(NamespaceId::new(1), TableId::new(1)).await,
+            Some(rule(1, 1, None))
+        );
+
+        rules.record(PartitionId::new(1), 10).await;
+        assert!(inner.recorded().is_empty());
+    }
+}
@@ -8,7 +8,10 @@ use iox_query::exec::Executor;
 use iox_time::TimeProvider;
 use parquet_file::storage::ParquetStorage;
 
-use crate::components::{commit::CommitWrapper, parquet_files_sink::ParquetFilesSink};
+use crate::{
+    admin::CompactionAdmin,
+    components::{commit::CommitWrapper, parquet_files_sink::ParquetFilesSink},
+};
 
 /// Multiple from `max_desired_file_size_bytes` to compute the minimum value for
 /// `max_compact_size_bytes`. Since `max_desired_file_size_bytes` is softly enforced, actual file
@@ -102,6 +105,9 @@ pub struct Config {
     /// Shard config (if sharding should be enabled).
     pub shard_config: Option<ShardConfig>,
 
+    /// Partition lease config (if partitions should be claimed dynamically via catalog leases).
+    pub lease_config: Option<LeaseConfig>,
+
     /// Minimum number of L1 files to compact to L2
     /// This is to prevent too many small files
     pub min_num_l1_files_to_compact: usize,
@@ -138,6 +144,9 @@ pub struct Config {
 
     /// max number of files per compaction plan
     pub max_num_files_per_plan: usize,
+
+    /// Admin state used to request, inspect and cancel compaction jobs at runtime.
+    pub admin: Arc<CompactionAdmin>,
 }
 
 impl Config {
@@ -210,6 +219,26 @@ pub struct ShardConfig {
     pub shard_id: usize,
 }
 
+/// Partition lease config.
+///
+/// Compactors that use leases claim partitions in the catalog for a limited time and renew these claims while they
+/// work on them. Partitions of compactors that stop renewing their leases are taken over by the remaining ones.
+#[derive(Debug, Clone)]
+pub struct LeaseConfig {
+    /// Name of this compactor, used as the lease owner.
+    ///
+    /// Must be unique across all compactors that share the same catalog.
+    pub owner: String,
+
+    /// Time after which a lease that was not renewed expires.
+    pub lease_duration: Duration,
+
+    /// Interval in which the leases of all partitions that are currently processed are renewed.
+    ///
+    /// Must be smaller than the lease duration.
+    pub heartbeat_interval: Duration,
+}
+
 /// Compaction type.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum CompactionType {
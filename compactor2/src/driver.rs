@@ -8,6 +8,7 @@ use tokio::sync::watch::Sender;
 use tracker::InstrumentedAsyncSemaphore;
 
 use crate::{
+    admin::{CompactionAdmin, JobOutcome},
     components::{
         changed_files_filter::SavedParquetFileState,
         scratchpad::Scratchpad,
@@ -26,6 +27,7 @@ pub async fn compact(
     partition_concurrency: NonZeroUsize,
     partition_timeout: Duration,
     job_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    admin: Arc<CompactionAdmin>,
     components: &Arc<Components>,
 ) {
     components
@@ -38,6 +40,7 @@ pub async fn compact(
                 partition_id,
                 partition_timeout,
                 Arc::clone(&job_semaphore),
+                Arc::clone(&admin),
                 components,
             )
         })
@@ -50,27 +53,65 @@ async fn compact_partition(
     partition_id: PartitionId,
     partition_timeout: Duration,
     job_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    admin: Arc<CompactionAdmin>,
     components: Arc<Components>,
 ) {
     info!(partition_id = partition_id.get(), "compact partition",);
     let mut scratchpad = components.scratchpad_gen.pad();
+    let cancel_token = admin.job_started(partition_id);
+
+    let res = tokio::select! {
+        res = timeout_with_progress_checking(partition_timeout, |transmit_progress_signal| {
+            let components = Arc::clone(&components);
+            let admin = Arc::clone(&admin);
+            async {
+                try_compact_partition(
+                    partition_id,
+                    job_semaphore,
+                    admin,
+                    components,
+                    scratchpad.as_mut(),
+                    transmit_progress_signal,
+                )
+                .await
+            }
+        }) => Some(res),
+        _ = cancel_token.cancelled() => None,
+    };
 
-    let res = timeout_with_progress_checking(partition_timeout, |transmit_progress_signal| {
-        let components = Arc::clone(&components);
-        async {
-            try_compact_partition(
+    let res = match res {
+        // The job was cancelled via the admin API. This is not an error of the partition, so it must not be marked as
+        // skipped.
+        None => {
+            info!(partition_id = partition_id.get(), "compaction cancelled",);
+            admin.job_finished(partition_id, JobOutcome::Cancelled);
+            Ok(())
+        }
+        Some(res) => {
+            let res = match_timeout_result(res);
+            admin.job_finished(
                 partition_id,
-                job_semaphore,
-                components,
-                scratchpad.as_mut(),
-                transmit_progress_signal,
-            )
-            .await
+                match &res {
+                    Ok(()) => JobOutcome::Succeeded,
+                    Err(e) => JobOutcome::Failed(e.to_string()),
+                },
+            );
+            res
         }
-    })
-    .await;
+    };
+    components
+        .partition_done_sink
+        .record(partition_id, res)
+        .await;
 
-    let res = match res {
+    scratchpad.clean().await;
+    info!(partition_id = partition_id.get(), "compacted partition",);
+}
+
+/// Convert the outcome of [`timeout_with_progress_checking`] into the result that is reported to the
+/// `partition_done_sink`.
+fn match_timeout_result(res: TimeoutWithProgress<Result<(), DynError>>) -> Result<(), DynError> {
+    match res {
         // If `try_compact_partition` timed out and didn't make any progress, something is wrong
         // with this partition and it should get added to the `skipped_compactions` table by
         // sending a timeout error to the `partition_done_sink`.
@@ -86,14 +127,7 @@ async fn compact_partition(
         // let the `partition_done_sink` decide if the error means the partition should be added
         // to the `skipped_compactions` table or not.
         TimeoutWithProgress::Completed(res) => res,
-    };
-    components
-        .partition_done_sink
-        .record(partition_id, res)
-        .await;
-
-    scratchpad.clean().await;
-    info!(partition_id = partition_id.get(), "compacted partition",);
+    }
 }
 
 /// Main function to compact files of a single partition.
@@ -185,6 +219,7 @@ async fn compact_partition(
 async fn try_compact_partition(
     partition_id: PartitionId,
     job_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    admin: Arc<CompactionAdmin>,
     components: Arc<Components>,
     scratchpad_ctx: &mut dyn Scratchpad,
     transmit_progress_signal: Sender<bool>,
@@ -193,7 +228,10 @@ async fn try_compact_partition(
     let partition_info = components.partition_info_source.fetch(partition_id).await?;
 
     // loop for each "Round", consider each file in the partition
+    let mut rounds_completed = 0;
     loop {
+        admin.round_started(partition_id, rounds_completed, files.len());
+
         let round_info = components
             .round_info_source
             .calculate(&partition_info, &files)
@@ -304,6 +342,7 @@ async fn try_compact_partition(
         }
 
         files = files_next;
+        rounds_completed += 1;
     }
 }
 
@@ -203,6 +203,7 @@
 )]
 #![allow(rustdoc::private_intra_doc_links)]
 
+pub mod admin;
 pub mod compactor;
 mod components;
 pub mod config;
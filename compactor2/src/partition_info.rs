@@ -28,6 +28,9 @@ pub struct PartitionInfo {
 
     /// partition_key
     pub partition_key: PartitionKey,
+
+    /// Window size the data of the partition was rolled up to, if any
+    pub rollup_interval_ns: Option<i64>,
 }
 
 impl PartitionInfo {
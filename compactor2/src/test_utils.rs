@@ -26,13 +26,17 @@ impl PartitionInfoBuilder {
                     id: TableId::new(3),
                     namespace_id,
                     name: String::from("table"),
+                    primary_key: vec![],
+                    deleted_at: None,
                 }),
                 table_schema: Arc::new(TableSchema {
                     id: table_id,
                     columns: BTreeMap::new(),
+                    primary_key: vec![],
                 }),
                 sort_key: None,
                 partition_key: PartitionKey::from("key"),
+                rollup_interval_ns: None,
             },
         }
     }
@@ -42,6 +46,11 @@ impl PartitionInfoBuilder {
         self
     }
 
+    pub fn with_rollup_interval_ns(mut self, interval_ns: i64) -> Self {
+        self.inner.rollup_interval_ns = Some(interval_ns);
+        self
+    }
+
     pub fn with_num_columns(mut self, num_cols: usize) -> Self {
         let mut columns = BTreeMap::new();
         for i in 0..num_cols {
@@ -55,6 +64,7 @@ impl PartitionInfoBuilder {
         let table_schema = Arc::new(TableSchema {
             id: self.inner.table.id,
             columns,
+            primary_key: vec![],
         });
         self.inner.table_schema = table_schema;
 
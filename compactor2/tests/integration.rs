@@ -1,6 +1,6 @@
 use arrow_util::assert_batches_sorted_eq;
 use compactor2_test_utils::{format_files, list_object_store, TestSetup, TestSetupBuilder};
-use data_types::{CompactionLevel, ParquetFile, PartitionId};
+use data_types::{CompactionLevel, ParquetFile, PartitionId, RollupAggregate, RollupRuleParams};
 use iox_tests::TestParquetFileBuilder;
 use test_helpers::{assert_contains, tracing::TracingCapture};
 
@@ -666,6 +666,103 @@ async fn cold_compaction_doesnt_find_hot_partitions() {
     );
 }
 
+#[tokio::test]
+async fn cold_compaction_rolls_up() {
+    test_helpers::maybe_start_logging();
+
+    let setup = TestSetup::builder()
+        .await
+        .for_cold_compaction()
+        .build()
+        .await;
+
+    setup
+        .catalog
+        .catalog
+        .repositories()
+        .await
+        .rollup_rules()
+        .create_or_update(RollupRuleParams {
+            namespace_id: setup.partition_info.namespace_id,
+            table_id: None,
+            interval_ns: 10_000,
+            min_age_ns: 0,
+            float_aggregate: RollupAggregate::Mean,
+            integer_aggregate: RollupAggregate::Sum,
+            unsigned_aggregate: RollupAggregate::Sum,
+            boolean_aggregate: RollupAggregate::Last,
+            string_aggregate: RollupAggregate::Last,
+        })
+        .await
+        .unwrap();
+
+    // Creating Parquet files with creation times around 2 hours ago is what makes this partition
+    // selected for cold compaction
+    let time_provider = setup.catalog.time_provider();
+    let parquet_builder = TestParquetFileBuilder::default()
+        .with_compaction_level(CompactionLevel::FileNonOverlapped)
+        .with_creation_time(time_provider.minutes_ago(2 * 60));
+    for (lp, min_time, max_time) in [
+        (
+            "table,tag1=A field_int=1i 1000\ntable,tag1=B field_int=10i 2000",
+            1000,
+            2000,
+        ),
+        ("table,tag1=A field_int=2i 5000", 5000, 5000),
+        ("table,tag1=A field_int=4i 12000", 12000, 12000),
+    ] {
+        setup
+            .partition
+            .create_parquet_file(
+                parquet_builder
+                    .clone()
+                    .with_line_protocol(lp)
+                    .with_min_time(min_time)
+                    .with_max_time(max_time),
+            )
+            .await;
+    }
+
+    setup.run_compact().await;
+
+    // the L1 files are compacted into L2 and rolled up into windows of 10us
+    let files = setup.list_by_table_not_to_delete().await;
+    assert!(!files.is_empty());
+    assert!(files
+        .iter()
+        .all(|f| f.compaction_level == CompactionLevel::Final));
+
+    let mut batches = vec![];
+    for file in files {
+        batches.extend(setup.read_parquet_file(file).await);
+    }
+    assert_batches_sorted_eq!(
+        &[
+            "+-----------+------+------+------+-----------------------------+",
+            "| field_int | tag1 | tag2 | tag3 | time                        |",
+            "+-----------+------+------+------+-----------------------------+",
+            "| 10        | B    |      |      | 1970-01-01T00:00:00Z        |",
+            "| 3         | A    |      |      | 1970-01-01T00:00:00Z        |",
+            "| 4         | A    |      |      | 1970-01-01T00:00:00.000010Z |",
+            "+-----------+------+------+------+-----------------------------+",
+        ],
+        &batches
+    );
+
+    // the rollup is recorded once it was committed
+    let partition = setup
+        .catalog
+        .catalog
+        .repositories()
+        .await
+        .partitions()
+        .get_by_id(setup.partition_info.partition_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(partition.rollup_interval_ns, Some(10_000));
+}
+
 #[track_caller]
 fn assert_levels<'a>(
     files: impl IntoIterator<Item = &'a ParquetFile>,
@@ -33,6 +33,7 @@ use crate::{
 use async_trait::async_trait;
 use backoff::BackoffConfig;
 use compactor2::{
+    admin::CompactionAdmin,
     compact,
     config::{CompactionType, Config, PartitionsSourceConfig},
     hardcoded_components, Components, PanicDataFusionPlanner, PartitionInfo,
@@ -146,6 +147,7 @@ impl TestSetupBuilder<false> {
             shadow_mode: false,
             ignore_partition_skip_marker: false,
             shard_config: None,
+            lease_config: None,
             min_num_l1_files_to_compact: MIN_NUM_L1_FILES_TO_COMPACT,
             process_once: true,
             simulate_without_object_store: false,
@@ -154,6 +156,7 @@ impl TestSetupBuilder<false> {
             all_errors_are_fatal: true,
             max_num_columns_per_table: 200,
             max_num_files_per_plan: 200,
+            admin: Arc::new(CompactionAdmin::new(catalog.time_provider())),
         };
 
         let bytes_written = Arc::new(AtomicUsize::new(0));
@@ -589,6 +592,7 @@ impl<const WITH_FILES: bool> TestSetupBuilder<WITH_FILES> {
             table_schema: Arc::new(self.table.catalog_schema().await),
             sort_key: self.partition.partition.sort_key(),
             partition_key: self.partition.partition.partition_key.clone(),
+            rollup_interval_ns: self.partition.partition.rollup_interval_ns,
         });
 
         TestSetup {
@@ -702,6 +706,7 @@ impl TestSetup {
             NonZeroUsize::new(10).unwrap(),
             config.partition_timeout,
             job_semaphore,
+            Arc::clone(&config.admin),
             &components,
         )
         .await;
@@ -209,6 +209,12 @@ impl ColumnId {
     }
 }
 
+impl std::fmt::Display for ColumnId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl PgHasArrayType for ColumnId {
     fn array_type_info() -> sqlx::postgres::PgTypeInfo {
         <i64 as PgHasArrayType>::array_type_info()
@@ -404,6 +410,27 @@ impl std::fmt::Display for ParquetFileId {
     }
 }
 
+/// Unique ID for a `RollupRule`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct RollupRuleId(i64);
+
+#[allow(missing_docs)]
+impl RollupRuleId {
+    pub fn new(v: i64) -> Self {
+        Self(v)
+    }
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for RollupRuleId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Data object for a topic. When Kafka is used as the write buffer, this is the Kafka topic name
 /// plus a catalog-assigned ID.
 #[derive(Debug, Clone, Eq, PartialEq, sqlx::FromRow)]
@@ -441,10 +468,214 @@ pub struct Namespace {
     pub max_tables: i32,
     /// The maximum number of columns per table in this namespace
     pub max_columns_per_table: i32,
+    /// The maximum sustained rate of line protocol lines per second accepted
+    /// for this namespace. None means unlimited.
+    #[sqlx(default)]
+    pub max_lines_per_second: Option<i64>,
+    /// The maximum sustained rate of write batch bytes per second accepted
+    /// for this namespace, measured as the in-memory size of the decoded
+    /// writes rather than the size of the request body. None means
+    /// unlimited.
+    #[sqlx(default)]
+    pub max_batch_bytes_per_second: Option<i64>,
+    /// The maximum number of queries the querier runs concurrently for this
+    /// namespace. None means unlimited.
+    #[sqlx(default)]
+    pub max_concurrent_queries: Option<i32>,
+    /// The maximum number of bytes of the querier memory pool the queries of
+    /// this namespace may hold at once. None means unlimited.
+    #[sqlx(default)]
+    pub max_query_memory_bytes: Option<i64>,
+    /// How writes with a field type that conflicts with the existing column
+    /// type are handled.
+    #[sqlx(default)]
+    pub type_coercion_policy: TypeCoercionPolicy,
+    /// The priority class of queries against this namespace that do not
+    /// request one.
+    #[sqlx(default)]
+    pub default_query_priority: QueryPriority,
     /// When this file was marked for deletion.
     pub deleted_at: Option<Timestamp>,
 }
 
+/// The per-namespace write rate limits enforced by the router.
+///
+/// A [`None`] value for either limit means the rate is unbounded.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct WriteRateLimit {
+    /// The maximum number of lines per second.
+    pub lines_per_second: Option<i64>,
+    /// The maximum number of bytes of decoded, in-memory write batches per
+    /// second.
+    pub batch_bytes_per_second: Option<i64>,
+}
+
+impl WriteRateLimit {
+    /// Returns true if neither rate is bounded.
+    pub fn is_unlimited(&self) -> bool {
+        self.lines_per_second.is_none() && self.batch_bytes_per_second.is_none()
+    }
+}
+
+impl From<&Namespace> for WriteRateLimit {
+    fn from(ns: &Namespace) -> Self {
+        Self {
+            lines_per_second: ns.max_lines_per_second,
+            batch_bytes_per_second: ns.max_batch_bytes_per_second,
+        }
+    }
+}
+
+/// The per-namespace query quotas enforced by the querier.
+///
+/// A [`None`] value for either quota means the resource is only bounded by the
+/// global querier limits.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct QueryQuota {
+    /// The maximum number of concurrently running queries.
+    pub max_concurrent_queries: Option<i32>,
+    /// The maximum number of bytes reserved from the query memory pool.
+    pub max_memory_bytes: Option<i64>,
+}
+
+impl QueryQuota {
+    /// Returns true if neither quota is bounded.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_concurrent_queries.is_none() && self.max_memory_bytes.is_none()
+    }
+}
+
+impl From<&Namespace> for QueryQuota {
+    fn from(ns: &Namespace) -> Self {
+        Self {
+            max_concurrent_queries: ns.max_concurrent_queries,
+            max_memory_bytes: ns.max_query_memory_bytes,
+        }
+    }
+}
+
+/// How the router handles a write with a field whose type differs from the
+/// type of the existing column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[repr(i16)]
+pub enum TypeCoercionPolicy {
+    /// Reject the write.
+    #[default]
+    Strict = 0,
+    /// Convert integer and unsigned integer values written to a float column
+    /// to floats, rejecting any other conflict.
+    Widen = 1,
+    /// Convert numeric values to the type of the existing numeric column if
+    /// every value converts without loss, rejecting the write otherwise.
+    LosslessCast = 2,
+}
+
+impl TypeCoercionPolicy {
+    /// The short string description of the policy.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Widen => "widen",
+            Self::LosslessCast => "lossless_cast",
+        }
+    }
+
+    /// Returns true if this policy may convert values of type `from` to the
+    /// type `to` of an existing column.
+    ///
+    /// Whether each individual value can be converted without loss is only
+    /// known once it is converted.
+    pub fn allows(&self, from: ColumnType, to: ColumnType) -> bool {
+        use ColumnType::*;
+
+        match self {
+            Self::Strict => false,
+            Self::Widen => matches!((from, to), (I64 | U64, F64)),
+            Self::LosslessCast => {
+                from != to && matches!(from, I64 | U64 | F64) && matches!(to, I64 | U64 | F64)
+            }
+        }
+    }
+}
+
+impl Display for TypeCoercionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<i32> for TypeCoercionPolicy {
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            x if x == Self::Strict as i32 => Ok(Self::Strict),
+            x if x == Self::Widen as i32 => Ok(Self::Widen),
+            x if x == Self::LosslessCast as i32 => Ok(Self::LosslessCast),
+            _ => Err("invalid type coercion policy value".into()),
+        }
+    }
+}
+
+/// The priority class of a query, which determines the querier thread pool it
+/// runs on and the concurrency limit it counts against.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[repr(i16)]
+pub enum QueryPriority {
+    /// Regular queries.
+    #[default]
+    Normal = 0,
+    /// Latency sensitive queries such as dashboards and metadata lookups.
+    Interactive = 1,
+    /// Expensive queries, such as ad-hoc analytics, that may wait for
+    /// resources.
+    Batch = 2,
+}
+
+impl QueryPriority {
+    /// All priority classes.
+    pub const ALL: [Self; 3] = [Self::Interactive, Self::Normal, Self::Batch];
+
+    /// The short string description of the priority class.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Interactive => "interactive",
+            Self::Batch => "batch",
+        }
+    }
+}
+
+impl Display for QueryPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for QueryPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("invalid query priority '{s}'"))
+    }
+}
+
+impl TryFrom<i32> for QueryPriority {
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            x if x == Self::Normal as i32 => Ok(Self::Normal),
+            x if x == Self::Interactive as i32 => Ok(Self::Interactive),
+            x if x == Self::Batch as i32 => Ok(Self::Batch),
+            _ => Err("invalid query priority value".into()),
+        }
+    }
+}
+
 /// Schema collection for a namespace. This is an in-memory object useful for a schema
 /// cache.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -464,6 +695,14 @@ pub struct NamespaceSchema {
     /// The retention period in ns.
     /// None represents infinite duration (i.e. never drop data).
     pub retention_period_ns: Option<i64>,
+    /// The write rate limits applied to this namespace.
+    pub write_rate_limit: WriteRateLimit,
+    /// The query quotas applied to this namespace.
+    pub query_quota: QueryQuota,
+    /// How field type conflicts of writes to this namespace are handled.
+    pub type_coercion_policy: TypeCoercionPolicy,
+    /// The priority class of queries that do not request one.
+    pub default_query_priority: QueryPriority,
 }
 
 impl NamespaceSchema {
@@ -484,9 +723,37 @@ impl NamespaceSchema {
             max_columns_per_table: max_columns_per_table as usize,
             max_tables: max_tables as usize,
             retention_period_ns,
+            write_rate_limit: WriteRateLimit::default(),
+            query_quota: QueryQuota::default(),
+            type_coercion_policy: TypeCoercionPolicy::default(),
+            default_query_priority: QueryPriority::default(),
         }
     }
 
+    /// Set the [`WriteRateLimit`] of this schema.
+    pub fn with_write_rate_limit(mut self, write_rate_limit: WriteRateLimit) -> Self {
+        self.write_rate_limit = write_rate_limit;
+        self
+    }
+
+    /// Set the [`QueryQuota`] of this schema.
+    pub fn with_query_quota(mut self, query_quota: QueryQuota) -> Self {
+        self.query_quota = query_quota;
+        self
+    }
+
+    /// Set the [`TypeCoercionPolicy`] of this schema.
+    pub fn with_type_coercion_policy(mut self, policy: TypeCoercionPolicy) -> Self {
+        self.type_coercion_policy = policy;
+        self
+    }
+
+    /// Set the default [`QueryPriority`] of this schema.
+    pub fn with_default_query_priority(mut self, priority: QueryPriority) -> Self {
+        self.default_query_priority = priority;
+        self
+    }
+
     /// Estimated Size in bytes including `self`.
     pub fn size(&self) -> usize {
         std::mem::size_of_val(self)
@@ -507,6 +774,15 @@ pub struct Table {
     pub namespace_id: NamespaceId,
     /// The name of the table, which is unique within the associated namespace
     pub name: String,
+    /// The primary key declared when the table was created: its tag columns in
+    /// sort order followed by the time column.
+    ///
+    /// Empty if the sort order of the table is derived from its data.
+    pub primary_key: Vec<String>,
+    /// When this table was marked for deletion.
+    ///
+    /// The name of a deleted table stays reserved within its namespace.
+    pub deleted_at: Option<Timestamp>,
 }
 
 /// Column definitions for a table
@@ -516,6 +792,8 @@ pub struct TableSchema {
     pub id: TableId,
     /// the table's columns by their name
     pub columns: BTreeMap<String, ColumnSchema>,
+    /// the declared primary key of the table, see [`Table::primary_key`]
+    pub primary_key: Vec<String>,
 }
 
 impl TableSchema {
@@ -524,9 +802,25 @@ impl TableSchema {
         Self {
             id,
             columns: BTreeMap::new(),
+            primary_key: vec![],
+        }
+    }
+
+    /// Initialize a new, empty `TableSchema` for `table`.
+    pub fn new_for_table(table: &Table) -> Self {
+        Self {
+            primary_key: table.primary_key.clone(),
+            ..Self::new(table.id)
         }
     }
 
+    /// Returns true if `column` may be written as a tag of this table.
+    ///
+    /// All tags are permitted unless the table declares a primary key.
+    pub fn permits_tag(&self, column: &str) -> bool {
+        self.primary_key.is_empty() || self.primary_key.iter().any(|c| c == column)
+    }
+
     /// Add `col` to this table schema.
     ///
     /// # Panics
@@ -548,6 +842,11 @@ impl TableSchema {
                 .iter()
                 .map(|(k, v)| size_of_val(k) + k.capacity() + size_of_val(v))
                 .sum::<usize>()
+            + self
+                .primary_key
+                .iter()
+                .map(|c| size_of_val(c) + c.capacity())
+                .sum::<usize>()
     }
 
     /// Create `ID->name` map for columns.
@@ -581,6 +880,10 @@ pub struct Column {
     pub name: String,
     /// the logical type of the column
     pub column_type: ColumnType,
+    /// When this column was marked for deletion.
+    ///
+    /// The name of a deleted column stays reserved within its table.
+    pub deleted_at: Option<Timestamp>,
 }
 
 impl Column {
@@ -932,6 +1235,18 @@ pub struct Partition {
 
     /// The time at which the newest file of the partition is created
     pub new_file_at: Option<Timestamp>,
+
+    /// The window size in nanoseconds the data of this partition was
+    /// rolled up to by the compactor.
+    ///
+    /// If [`None`] the partition holds data at its original resolution.
+    ///
+    /// This is only used by the compactor to avoid rolling up a partition
+    /// twice. The querier does not read it, so queries over rolled-up
+    /// partitions, e.g. with `GROUP BY time()` windows finer than this
+    /// interval, are answered from the rolled-up rows as if they were raw
+    /// data.
+    pub rollup_interval_ns: Option<i64>,
 }
 
 impl Partition {
@@ -966,6 +1281,135 @@ pub struct SkippedCompaction {
     pub limit_num_files_first_in_partition: i64,
 }
 
+/// Aggregate used to combine the values of a field within one window of a
+/// [`RollupRule`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, sqlx::Type)]
+#[repr(i16)]
+pub enum RollupAggregate {
+    /// Arithmetic mean, only valid for float fields
+    Mean = 1,
+    /// Sum of all values, only valid for numeric fields
+    Sum = 2,
+    /// Smallest value, only valid for numeric fields
+    Min = 3,
+    /// Largest value, only valid for numeric fields
+    Max = 4,
+    /// Value with the earliest timestamp
+    First = 5,
+    /// Value with the latest timestamp
+    Last = 6,
+}
+
+impl RollupAggregate {
+    /// Returns true if this aggregate can be applied to fields of type
+    /// `field_type` without changing the type of the field.
+    pub fn is_valid_for(&self, field_type: InfluxFieldType) -> bool {
+        match self {
+            Self::Mean => field_type == InfluxFieldType::Float,
+            Self::Sum | Self::Min | Self::Max => matches!(
+                field_type,
+                InfluxFieldType::Float | InfluxFieldType::Integer | InfluxFieldType::UInteger
+            ),
+            Self::First | Self::Last => true,
+        }
+    }
+}
+
+impl Display for RollupAggregate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mean => write!(f, "mean"),
+            Self::Sum => write!(f, "sum"),
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+            Self::First => write!(f, "first"),
+            Self::Last => write!(f, "last"),
+        }
+    }
+}
+
+/// Describes how the compactor downsamples data once it has aged out of
+/// the recent, full-resolution window.
+///
+/// A rule either applies to all tables of a namespace (`table_id` is
+/// [`None`]) or to a single table, in which case it takes precedence over
+/// the namespace-wide rule.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct RollupRule {
+    /// the id of the rule
+    pub id: RollupRuleId,
+    /// the namespace the rule applies to
+    pub namespace_id: NamespaceId,
+    /// the table the rule applies to, or [`None`] for all tables of the namespace
+    pub table_id: Option<TableId>,
+    /// width of the windows the data is aggregated into, in nanoseconds
+    pub interval_ns: i64,
+    /// minimum age of data, in nanoseconds, before it is rolled up
+    pub min_age_ns: i64,
+    /// aggregate for float fields
+    pub float_aggregate: RollupAggregate,
+    /// aggregate for integer fields
+    pub integer_aggregate: RollupAggregate,
+    /// aggregate for unsigned integer fields
+    pub unsigned_aggregate: RollupAggregate,
+    /// aggregate for boolean fields
+    pub boolean_aggregate: RollupAggregate,
+    /// aggregate for string fields
+    pub string_aggregate: RollupAggregate,
+}
+
+impl RollupRule {
+    /// The aggregate used for fields of type `field_type`.
+    pub fn aggregate(&self, field_type: InfluxFieldType) -> RollupAggregate {
+        match field_type {
+            InfluxFieldType::Float => self.float_aggregate,
+            InfluxFieldType::Integer => self.integer_aggregate,
+            InfluxFieldType::UInteger => self.unsigned_aggregate,
+            InfluxFieldType::Boolean => self.boolean_aggregate,
+            InfluxFieldType::String => self.string_aggregate,
+        }
+    }
+}
+
+/// Data for a rollup rule to be created or updated in the catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollupRuleParams {
+    /// the namespace the rule applies to
+    pub namespace_id: NamespaceId,
+    /// the table the rule applies to, or [`None`] for all tables of the namespace
+    pub table_id: Option<TableId>,
+    /// width of the windows the data is aggregated into, in nanoseconds
+    pub interval_ns: i64,
+    /// minimum age of data, in nanoseconds, before it is rolled up
+    pub min_age_ns: i64,
+    /// aggregate for float fields
+    pub float_aggregate: RollupAggregate,
+    /// aggregate for integer fields
+    pub integer_aggregate: RollupAggregate,
+    /// aggregate for unsigned integer fields
+    pub unsigned_aggregate: RollupAggregate,
+    /// aggregate for boolean fields
+    pub boolean_aggregate: RollupAggregate,
+    /// aggregate for string fields
+    pub string_aggregate: RollupAggregate,
+}
+
+impl RollupRuleParams {
+    /// Returns the first field type whose aggregate would change the
+    /// type of the field, if any.
+    pub fn invalid_field_type(&self) -> Option<(InfluxFieldType, RollupAggregate)> {
+        [
+            (InfluxFieldType::Float, self.float_aggregate),
+            (InfluxFieldType::Integer, self.integer_aggregate),
+            (InfluxFieldType::UInteger, self.unsigned_aggregate),
+            (InfluxFieldType::Boolean, self.boolean_aggregate),
+            (InfluxFieldType::String, self.string_aggregate),
+        ]
+        .into_iter()
+        .find(|(field_type, agg)| !agg.is_valid_for(*field_type))
+    }
+}
+
 /// Set of columns.
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
 #[sqlx(transparent)]
@@ -3100,6 +3544,7 @@ mod tests {
         let schema1 = TableSchema {
             id: TableId::new(1),
             columns: BTreeMap::from([]),
+            primary_key: vec![],
         };
         let schema2 = TableSchema {
             id: TableId::new(2),
@@ -3110,8 +3555,69 @@ mod tests {
                     column_type: ColumnType::Bool,
                 },
             )]),
+            primary_key: vec![],
         };
         assert!(schema1.size() < schema2.size());
+
+        let schema3 = TableSchema {
+            primary_key: vec![String::from("foo"), String::from("time")],
+            ..schema2.clone()
+        };
+        assert!(schema2.size() < schema3.size());
+    }
+
+    #[test]
+    fn test_table_schema_permits_tag() {
+        let mut schema = TableSchema::new(TableId::new(1));
+        assert!(schema.permits_tag("region"));
+
+        schema.primary_key = vec![String::from("region"), String::from("time")];
+        assert!(schema.permits_tag("region"));
+        assert!(!schema.permits_tag("host"));
+    }
+
+    #[test]
+    fn test_type_coercion_policy_allows() {
+        use ColumnType::*;
+
+        for (from, to) in [
+            (I64, F64),
+            (U64, F64),
+            (F64, I64),
+            (I64, Tag),
+            (String, Bool),
+        ] {
+            assert!(!TypeCoercionPolicy::Strict.allows(from, to));
+        }
+
+        assert!(TypeCoercionPolicy::Widen.allows(I64, F64));
+        assert!(TypeCoercionPolicy::Widen.allows(U64, F64));
+        assert!(!TypeCoercionPolicy::Widen.allows(F64, I64));
+        assert!(!TypeCoercionPolicy::Widen.allows(I64, U64));
+
+        assert!(TypeCoercionPolicy::LosslessCast.allows(I64, F64));
+        assert!(TypeCoercionPolicy::LosslessCast.allows(F64, U64));
+        assert!(TypeCoercionPolicy::LosslessCast.allows(U64, I64));
+        assert!(!TypeCoercionPolicy::LosslessCast.allows(I64, I64));
+        assert!(!TypeCoercionPolicy::LosslessCast.allows(I64, Tag));
+        assert!(!TypeCoercionPolicy::LosslessCast.allows(Bool, I64));
+        assert!(!TypeCoercionPolicy::LosslessCast.allows(String, F64));
+    }
+
+    #[test]
+    fn test_query_priority_from_str() {
+        for priority in QueryPriority::ALL {
+            assert_eq!(priority.as_str().parse::<QueryPriority>(), Ok(priority));
+            assert_eq!(QueryPriority::try_from(priority as i32).unwrap(), priority);
+        }
+        assert_eq!(
+            "Interactive".parse::<QueryPriority>(),
+            Ok(QueryPriority::Interactive)
+        );
+        assert_eq!(
+            "urgent".parse::<QueryPriority>(),
+            Err("invalid query priority 'urgent'".to_string())
+        );
     }
 
     #[test]
@@ -3124,6 +3630,10 @@ mod tests {
             max_columns_per_table: 4,
             max_tables: 42,
             retention_period_ns: None,
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
         let schema2 = NamespaceSchema {
             id: NamespaceId::new(1),
@@ -3133,6 +3643,10 @@ mod tests {
             max_columns_per_table: 4,
             max_tables: 42,
             retention_period_ns: None,
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
         assert!(schema1.size() < schema2.size());
     }
@@ -3173,4 +3687,40 @@ mod tests {
         assert_eq!(tr.start(), 1);
         assert_eq!(tr.end(), 1);
     }
+
+    #[test]
+    fn test_rollup_aggregate_is_valid_for() {
+        assert!(RollupAggregate::Mean.is_valid_for(InfluxFieldType::Float));
+        assert!(!RollupAggregate::Mean.is_valid_for(InfluxFieldType::Integer));
+        assert!(RollupAggregate::Sum.is_valid_for(InfluxFieldType::UInteger));
+        assert!(!RollupAggregate::Max.is_valid_for(InfluxFieldType::String));
+        assert!(!RollupAggregate::Min.is_valid_for(InfluxFieldType::Boolean));
+        assert!(RollupAggregate::Last.is_valid_for(InfluxFieldType::Boolean));
+        assert!(RollupAggregate::First.is_valid_for(InfluxFieldType::String));
+    }
+
+    #[test]
+    fn test_rollup_rule_params_invalid_field_type() {
+        let params = RollupRuleParams {
+            namespace_id: NamespaceId::new(1),
+            table_id: None,
+            interval_ns: 300_000_000_000,
+            min_age_ns: 0,
+            float_aggregate: RollupAggregate::Mean,
+            integer_aggregate: RollupAggregate::Sum,
+            unsigned_aggregate: RollupAggregate::Max,
+            boolean_aggregate: RollupAggregate::Last,
+            string_aggregate: RollupAggregate::First,
+        };
+        assert_eq!(params.invalid_field_type(), None);
+
+        let params = RollupRuleParams {
+            integer_aggregate: RollupAggregate::Mean,
+            ..params
+        };
+        assert_eq!(
+            params.invalid_field_type(),
+            Some((InfluxFieldType::Integer, RollupAggregate::Mean))
+        );
+    }
 }
@@ -20,7 +20,7 @@ use arrow_flight::{
 use arrow_util::flight::prepare_schema_for_flight;
 use bytes::Bytes;
 use datafusion::{logical_expr::LogicalPlan, physical_plan::ExecutionPlan};
-use iox_query::{exec::IOxSessionContext, QueryNamespace};
+use iox_query::{exec::IOxSessionContext, frontend::sql::SqlQueryPlanner, QueryNamespace};
 use observability_deps::tracing::debug;
 use once_cell::sync::Lazy;
 use prost::Message;
@@ -98,7 +98,7 @@ impl FlightSQLPlanner {
     /// Returns a plan that computes results requested in msg
     pub async fn do_get(
         namespace_name: impl Into<String>,
-        _database: Arc<dyn QueryNamespace>,
+        database: Arc<dyn QueryNamespace>,
         cmd: FlightSQLCommand,
         ctx: &IOxSessionContext,
     ) -> Result<Arc<dyn ExecutionPlan>> {
@@ -108,12 +108,12 @@ impl FlightSQLPlanner {
         match cmd {
             FlightSQLCommand::CommandStatementQuery(CommandStatementQuery { query }) => {
                 debug!(%query, "Planning FlightSQL query");
-                Ok(ctx.sql_to_physical_plan(&query).await?)
+                Ok(plan_sql_query(&query, database.as_ref(), ctx).await?)
             }
             FlightSQLCommand::CommandPreparedStatementQuery(handle) => {
                 let query = handle.query();
                 debug!(%query, "Planning FlightSQL prepared query");
-                Ok(ctx.sql_to_physical_plan(query).await?)
+                Ok(plan_sql_query(query, database.as_ref(), ctx).await?)
             }
             FlightSQLCommand::CommandGetSqlInfo(CommandGetSqlInfo { info }) => {
                 debug!("Planning GetSqlInfo query");
@@ -298,6 +298,20 @@ impl FlightSQLPlanner {
     }
 }
 
+/// Plan a SQL query, using the result cache of `database` if enabled
+async fn plan_sql_query(
+    query: &str,
+    database: &dyn QueryNamespace,
+    ctx: &IOxSessionContext,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let planner = SqlQueryPlanner::new();
+    let plan = match database.result_cache() {
+        Some(cache) => planner.query_cached(query, cache, ctx).await?,
+        None => planner.query(query, ctx).await?,
+    };
+    Ok(plan)
+}
+
 /// Return the schema for the specified query
 ///
 /// returns: IPC encoded (schema_bytes) for this query
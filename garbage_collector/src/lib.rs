@@ -18,6 +18,7 @@
 #![allow(clippy::missing_docs_in_private_items)]
 
 use crate::{
+    namespace::purger as namespace_purger,
     objectstore::{checker as os_checker, deleter as os_deleter, lister as os_lister},
     parquetfile::deleter as pf_deleter,
     retention::flagger as retention_flagger,
@@ -33,6 +34,8 @@ use std::{fmt::Debug, sync::Arc};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
+/// Logic for purging soft-deleted namespaces
+mod namespace;
 /// Logic for listing, checking and deleting files in object storage
 mod objectstore;
 /// Logic for deleting parquet files from the catalog
@@ -55,6 +58,7 @@ pub struct GarbageCollector {
     os_deleter: tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
     pf_deleter: tokio::task::JoinHandle<Result<(), pf_deleter::Error>>,
     retention_flagger: tokio::task::JoinHandle<Result<(), retention_flagger::Error>>,
+    namespace_purger: tokio::task::JoinHandle<Result<(), namespace_purger::Error>>,
 }
 
 impl Debug for GarbageCollector {
@@ -79,6 +83,8 @@ impl GarbageCollector {
             objectstore_sleep_interval_minutes = %sub_config.objectstore_sleep_interval_minutes,
             parquetfile_sleep_interval_minutes = %sub_config.parquetfile_sleep_interval_minutes,
             retention_sleep_interval_minutes = %sub_config.retention_sleep_interval_minutes,
+            namespace_delete_grace_period = %format_duration(sub_config.namespace_delete_grace_period).to_string(),
+            namespace_purge_sleep_interval_minutes = %sub_config.namespace_purge_sleep_interval_minutes,
             "GarbageCollector starting"
         );
 
@@ -131,14 +137,28 @@ impl GarbageCollector {
         ));
 
         // Initialise the retention code, which is just one thread that calls
-        // flag_for_delete_by_retention() on the catalog then sleeps.
+        // flag_for_delete_by_retention() and flag_for_delete_by_table_deletion() on the catalog
+        // then sleeps.
         let retention_flagger = tokio::spawn(retention_flagger::perform(
             shutdown.clone(),
-            catalog,
+            Arc::clone(&catalog),
             sub_config.retention_sleep_interval_minutes,
             sub_config.dry_run,
         ));
 
+        // Initialise the namespace purger, which is just one thread that flags the parquet files
+        // of namespaces soft-deleted longer than the grace period ago for deletion, removes the
+        // namespaces from the catalog once the parquet file deleter has removed their files, then
+        // sleeps.
+        let namespace_purger = tokio::spawn(namespace_purger::perform(
+            shutdown.clone(),
+            catalog,
+            sub_config.namespace_delete_grace_period,
+            sub_config.parquetfile_cutoff,
+            sub_config.namespace_purge_sleep_interval_minutes,
+            sub_config.dry_run,
+        ));
+
         Ok(Self {
             shutdown,
             os_lister,
@@ -146,6 +166,7 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            namespace_purger,
         })
     }
 
@@ -165,17 +186,20 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            namespace_purger,
             shutdown: _,
         } = self;
 
-        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger) = futures::join!(
+        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger, namespace_purger) = futures::join!(
             os_lister,
             os_checker,
             os_deleter,
             pf_deleter,
-            retention_flagger
+            retention_flagger,
+            namespace_purger
         );
 
+        namespace_purger.context(NamespacePurgerPanicSnafu)??;
         retention_flagger.context(ParquetFileDeleterPanicSnafu)??;
         pf_deleter.context(ParquetFileDeleterPanicSnafu)??;
         os_deleter.context(ObjectStoreDeleterPanicSnafu)??;
@@ -242,6 +266,12 @@ pub enum Error {
     ParquetFileRetentionFlagger { source: retention_flagger::Error },
     #[snafu(display("The parquet file retention flagger task panicked"))]
     ParquetFileRetentionFlaggerPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The namespace purger task failed"))]
+    #[snafu(context(false))]
+    NamespacePurger { source: namespace_purger::Error },
+    #[snafu(display("The namespace purger task panicked"))]
+    NamespacePurgerPanic { source: tokio::task::JoinError },
 }
 
 #[allow(missing_docs)]
@@ -0,0 +1,89 @@
+use data_types::Timestamp;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::*;
+use snafu::prelude::*;
+use std::{sync::Arc, time::Duration};
+use tokio::{select, time::sleep};
+use tokio_util::sync::CancellationToken;
+
+pub(crate) async fn perform(
+    shutdown: CancellationToken,
+    catalog: Arc<dyn Catalog>,
+    grace_period: Duration,
+    parquetfile_cutoff: Duration,
+    sleep_interval_minutes: u64,
+    dry_run: bool,
+) -> Result<()> {
+    loop {
+        if !dry_run {
+            let now = catalog.time_provider().now();
+            let mut repos = catalog.repositories().await;
+
+            let flagged = repos
+                .parquet_files()
+                .flag_for_delete_by_namespace_deletion(Timestamp::from(now - grace_period))
+                .await
+                .context(FlaggingSnafu)?;
+            info!(flagged_count = %flagged.len(), "iox_catalog::flag_for_delete_by_namespace_deletion()");
+
+            // The parquet file deleter removes the flagged files from the catalog once they have
+            // been flagged for longer than its cutoff, so by then only the namespace's tables,
+            // columns and partitions are left to remove.
+            let purge_before = Timestamp::from(now - grace_period - parquetfile_cutoff);
+            let namespaces = repos
+                .namespaces()
+                .list(SoftDeletedRows::OnlyDeleted)
+                .await
+                .context(ListingSnafu)?;
+            for namespace in namespaces
+                .into_iter()
+                .filter(|n| n.deleted_at.map_or(false, |t| t < purge_before))
+            {
+                repos
+                    .namespaces()
+                    .purge(&namespace.name)
+                    .await
+                    .context(PurgingSnafu {
+                        name: &namespace.name,
+                    })?;
+                info!(
+                    namespace_id = %namespace.id,
+                    namespace_name = %namespace.name,
+                    "purged soft-deleted namespace"
+                );
+            }
+        } else {
+            debug!("dry run enabled for namespace purger");
+        };
+
+        select! {
+            _ = shutdown.cancelled() => {
+                break
+            },
+            _ = sleep(Duration::from_secs(60 * sleep_interval_minutes)) => (),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Failed to flag parquet files of deleted namespaces for deletion"))]
+    Flagging {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to list deleted namespaces"))]
+    Listing {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to purge deleted namespace {name}"))]
+    Purging {
+        name: String,
+        source: iox_catalog::interface::Error,
+    },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
@@ -13,14 +13,20 @@ pub(crate) async fn perform(
 ) -> Result<()> {
     loop {
         if !dry_run {
-            let flagged = catalog
-                .repositories()
-                .await
+            let mut repos = catalog.repositories().await;
+            let flagged = repos
                 .parquet_files()
                 .flag_for_delete_by_retention()
                 .await
                 .context(FlaggingSnafu)?;
             info!(flagged_count = %flagged.len(), "iox_catalog::flag_for_delete_by_retention()");
+
+            let flagged = repos
+                .parquet_files()
+                .flag_for_delete_by_table_deletion()
+                .await
+                .context(FlaggingDeletedTablesSnafu)?;
+            info!(flagged_count = %flagged.len(), "iox_catalog::flag_for_delete_by_table_deletion()");
         } else {
             debug!("dry run enabled for parquet retention flagger");
         };
@@ -42,6 +48,11 @@ pub enum Error {
     Flagging {
         source: iox_catalog::interface::Error,
     },
+
+    #[snafu(display("Failed to flag parquet files of deleted tables for deletion"))]
+    FlaggingDeletedTables {
+        source: iox_catalog::interface::Error,
+    },
 }
 
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
@@ -33,6 +33,11 @@ fn main() -> Result<()> {
 /// - `influxdata.iox.write.v1.rs`
 /// - `influxdata.iox.write_buffer.v1.rs`
 /// - `influxdata.platform.storage.rs`
+/// - `opentelemetry.proto.collector.metrics.v1.rs`
+/// - `opentelemetry.proto.common.v1.rs`
+/// - `opentelemetry.proto.metrics.v1.rs`
+/// - `opentelemetry.proto.resource.v1.rs`
+/// - `prometheus.rs`
 fn generate_grpc_types(root: &Path) -> Result<()> {
     let authz_path = root.join("influxdata/iox/authz/v1");
     let catalog_path = root.join("influxdata/iox/catalog/v1");
@@ -49,6 +54,8 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
     let write_buffer_path = root.join("influxdata/iox/write_buffer/v1");
     let storage_path = root.join("influxdata/platform/storage");
     let storage_errors_path = root.join("influxdata/platform/errors");
+    let prometheus_path = root.join("prometheus/prompb");
+    let otlp_path = root.join("opentelemetry/proto");
 
     let proto_files = vec![
         authz_path.join("authz.proto"),
@@ -80,6 +87,12 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         storage_path.join("storage_common.proto"),
         storage_path.join("test.proto"),
         storage_errors_path.join("errors.proto"),
+        prometheus_path.join("remote.proto"),
+        prometheus_path.join("types.proto"),
+        otlp_path.join("collector/metrics/v1/metrics_service.proto"),
+        otlp_path.join("common/v1/common.proto"),
+        otlp_path.join("metrics/v1/metrics.proto"),
+        otlp_path.join("resource/v1/resource.proto"),
     ];
 
     // Tell cargo to recompile if any of these proto files are changed
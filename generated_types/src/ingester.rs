@@ -35,6 +35,10 @@ pub struct IngesterQueryRequest {
 
     /// Predicate for filtering
     pub predicate: Option<Predicate>,
+
+    /// Answer the query from the ingester's last value cache, returning only
+    /// the most recent value of each field for each series.
+    pub last_values: bool,
 }
 
 impl IngesterQueryRequest {
@@ -51,6 +55,16 @@ impl IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            last_values: false,
+        }
+    }
+
+    /// Request the most recent value per series from the ingester's last value
+    /// cache instead of all buffered data.
+    pub fn with_last_values(self, last_values: bool) -> Self {
+        Self {
+            last_values,
+            ..self
         }
     }
 }
@@ -64,13 +78,14 @@ impl TryFrom<proto::IngesterQueryRequest> for IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            last_values,
         } = proto;
 
         let namespace_id = NamespaceId::new(namespace_id);
         let table_id = TableId::new(table_id);
         let predicate = predicate.map(TryInto::try_into).transpose()?;
 
-        Ok(Self::new(namespace_id, table_id, columns, predicate))
+        Ok(Self::new(namespace_id, table_id, columns, predicate).with_last_values(last_values))
     }
 }
 
@@ -83,6 +98,7 @@ impl TryFrom<IngesterQueryRequest> for proto::IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            last_values,
         } = query;
 
         Ok(Self {
@@ -90,6 +106,7 @@ impl TryFrom<IngesterQueryRequest> for proto::IngesterQueryRequest {
             table_id: table_id.get(),
             columns,
             predicate: predicate.map(TryInto::try_into).transpose()?,
+            last_values,
         })
     }
 }
@@ -248,7 +265,8 @@ mod tests {
             TableId::new(1337),
             vec!["usage".into(), "time".into()],
             Some(rust_predicate),
-        );
+        )
+        .with_last_values(true);
 
         let proto_query: proto::IngesterQueryRequest = rust_query.clone().try_into().unwrap();
 
@@ -102,6 +102,56 @@ pub mod influxdata {
                     env!("OUT_DIR"),
                     "/influxdata.iox.namespace.v1.serde.rs"
                 ));
+
+                impl From<data_types::TypeCoercionPolicy> for TypeCoercionPolicy {
+                    fn from(value: data_types::TypeCoercionPolicy) -> Self {
+                        match value {
+                            data_types::TypeCoercionPolicy::Strict => Self::Strict,
+                            data_types::TypeCoercionPolicy::Widen => Self::Widen,
+                            data_types::TypeCoercionPolicy::LosslessCast => Self::LosslessCast,
+                        }
+                    }
+                }
+
+                impl TryFrom<TypeCoercionPolicy> for data_types::TypeCoercionPolicy {
+                    type Error = Box<dyn std::error::Error>;
+
+                    fn try_from(value: TypeCoercionPolicy) -> Result<Self, Self::Error> {
+                        Ok(match value {
+                            TypeCoercionPolicy::Strict => Self::Strict,
+                            TypeCoercionPolicy::Widen => Self::Widen,
+                            TypeCoercionPolicy::LosslessCast => Self::LosslessCast,
+                            TypeCoercionPolicy::Unspecified => {
+                                return Err("unknown type coercion policy".into())
+                            }
+                        })
+                    }
+                }
+
+                impl From<data_types::QueryPriority> for QueryPriority {
+                    fn from(value: data_types::QueryPriority) -> Self {
+                        match value {
+                            data_types::QueryPriority::Normal => Self::Normal,
+                            data_types::QueryPriority::Interactive => Self::Interactive,
+                            data_types::QueryPriority::Batch => Self::Batch,
+                        }
+                    }
+                }
+
+                impl TryFrom<QueryPriority> for data_types::QueryPriority {
+                    type Error = Box<dyn std::error::Error>;
+
+                    fn try_from(value: QueryPriority) -> Result<Self, Self::Error> {
+                        Ok(match value {
+                            QueryPriority::Normal => Self::Normal,
+                            QueryPriority::Interactive => Self::Interactive,
+                            QueryPriority::Batch => Self::Batch,
+                            QueryPriority::Unspecified => {
+                                return Err("unknown query priority".into())
+                            }
+                        })
+                    }
+                }
             }
         }
 
@@ -206,6 +256,51 @@ pub mod influxdata {
     }
 }
 
+/// The Prometheus remote storage protocol types.
+pub mod prometheus {
+    include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
+}
+
+/// The OpenTelemetry protocol (OTLP) types.
+pub mod opentelemetry {
+    pub mod proto {
+        pub mod collector {
+            pub mod metrics {
+                pub mod v1 {
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/opentelemetry.proto.collector.metrics.v1.rs"
+                    ));
+                }
+            }
+        }
+        pub mod common {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.common.v1.rs"
+                ));
+            }
+        }
+        pub mod metrics {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.metrics.v1.rs"
+                ));
+            }
+        }
+        pub mod resource {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.resource.v1.rs"
+                ));
+            }
+        }
+    }
+}
+
 // Needed because of https://github.com/hyperium/tonic/issues/471
 pub mod grpc {
     pub mod health {
@@ -326,4 +421,36 @@ mod tests {
 
         assert!(data_types::ColumnType::try_from(column_schema::ColumnType::Unspecified).is_err());
     }
+
+    #[test]
+    fn test_type_coercion_policy() {
+        use influxdata::iox::namespace::v1::TypeCoercionPolicy;
+
+        for policy in [
+            data_types::TypeCoercionPolicy::Strict,
+            data_types::TypeCoercionPolicy::Widen,
+            data_types::TypeCoercionPolicy::LosslessCast,
+        ] {
+            assert_eq!(
+                data_types::TypeCoercionPolicy::try_from(TypeCoercionPolicy::from(policy)).unwrap(),
+                policy,
+            );
+        }
+
+        assert!(data_types::TypeCoercionPolicy::try_from(TypeCoercionPolicy::Unspecified).is_err());
+    }
+
+    #[test]
+    fn test_query_priority() {
+        use influxdata::iox::namespace::v1::QueryPriority;
+
+        for priority in data_types::QueryPriority::ALL {
+            assert_eq!(
+                data_types::QueryPriority::try_from(QueryPriority::from(priority)).unwrap(),
+                priority,
+            );
+        }
+
+        assert!(data_types::QueryPriority::try_from(QueryPriority::Unspecified).is_err());
+    }
 }
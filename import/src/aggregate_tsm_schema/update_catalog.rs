@@ -1236,6 +1236,7 @@ mod tests {
             partition_key: PartitionKey::from("2022-06-21"),
             sort_key: Vec::new(),
             new_file_at: None,
+            rollup_interval_ns: None,
         };
         let sort_key = get_sort_key(&partition, &m).1.unwrap();
         let sort_key = sort_key.to_columns().collect::<Vec<_>>();
@@ -1285,6 +1286,7 @@ mod tests {
             // N.B. sort key is already what it will computed to; here we're testing the `adjust_sort_key_columns` code path
             sort_key: vec!["host".to_string(), "arch".to_string(), "time".to_string()],
             new_file_at: None,
+            rollup_interval_ns: None,
         };
         // ensure sort key is unchanged
         let _maybe_updated_sk = get_sort_key(&partition, &m).1;
@@ -1333,6 +1335,7 @@ mod tests {
             // N.B. is missing host so will need updating
             sort_key: vec!["arch".to_string(), "time".to_string()],
             new_file_at: None,
+            rollup_interval_ns: None,
         };
         let sort_key = get_sort_key(&partition, &m).1.unwrap();
         let sort_key = sort_key.to_columns().collect::<Vec<_>>();
@@ -1383,6 +1386,7 @@ mod tests {
             // N.B. is missing arch so will need updating
             sort_key: vec!["host".to_string(), "time".to_string()],
             new_file_at: None,
+            rollup_interval_ns: None,
         };
         let sort_key = get_sort_key(&partition, &m).1.unwrap();
         let sort_key = sort_key.to_columns().collect::<Vec<_>>();
@@ -0,0 +1,68 @@
+//! Types and parsers for the [`KILL QUERY`][sql] statement.
+//!
+//! [sql]: https://docs.influxdata.com/influxdb/v1.8/troubleshooting/query_management/#kill-query
+
+use crate::common::ws1;
+use crate::internal::{expect, ParseResult};
+use crate::keywords::keyword;
+use crate::literal::unsigned_integer;
+use nom::combinator::map;
+use nom::sequence::{pair, preceded};
+use std::fmt::{Display, Formatter};
+
+/// Represents a `KILL QUERY` statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KillQueryStatement {
+    /// The identifier of the query to terminate.
+    pub id: u64,
+}
+
+impl Display for KillQueryStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KILL QUERY {}", self.id)
+    }
+}
+
+pub(crate) fn kill_statement(i: &str) -> ParseResult<&str, KillQueryStatement> {
+    preceded(
+        pair(keyword("KILL"), ws1),
+        expect("invalid KILL statement, expected QUERY", kill_query),
+    )(i)
+}
+
+fn kill_query(i: &str) -> ParseResult<&str, KillQueryStatement> {
+    preceded(
+        pair(keyword("QUERY"), ws1),
+        map(
+            expect(
+                "invalid KILL QUERY statement, expected query identifier",
+                unsigned_integer,
+            ),
+            |id| KillQueryStatement { id },
+        ),
+    )(i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assert_expect_error;
+
+    #[test]
+    fn test_kill_statement() {
+        let (_, got) = kill_statement("KILL QUERY 36").unwrap();
+        assert_eq!(got, KillQueryStatement { id: 36 });
+        // validate Display
+        assert_eq!(got.to_string(), "KILL QUERY 36");
+
+        // Fallible cases
+        assert_expect_error!(
+            kill_statement("KILL foo"),
+            "invalid KILL statement, expected QUERY"
+        );
+        assert_expect_error!(
+            kill_statement("KILL QUERY foo"),
+            "invalid KILL QUERY statement, expected query identifier"
+        );
+    }
+}
@@ -34,6 +34,7 @@ pub mod functions;
 pub mod identifier;
 mod internal;
 mod keywords;
+pub mod kill;
 pub mod literal;
 pub mod parameter;
 pub mod select;
@@ -5,6 +5,7 @@ use crate::delete::{delete_statement, DeleteStatement};
 use crate::drop::{drop_statement, DropMeasurementStatement};
 use crate::explain::{explain_statement, ExplainStatement};
 use crate::internal::ParseResult;
+use crate::kill::{kill_statement, KillQueryStatement};
 use crate::select::{select_statement, SelectStatement};
 use crate::show::{show_statement, ShowDatabasesStatement};
 use crate::show_field_keys::ShowFieldKeysStatement;
@@ -27,6 +28,8 @@ pub enum Statement {
     DropMeasurement(Box<DropMeasurementStatement>),
     /// Represents an `EXPLAIN` statement.
     Explain(Box<ExplainStatement>),
+    /// Represents a `KILL QUERY` statement.
+    KillQuery(Box<KillQueryStatement>),
     /// Represents a `SELECT` statement.
     Select(Box<SelectStatement>),
     /// Represents a `SHOW DATABASES` statement.
@@ -50,6 +53,7 @@ impl Display for Statement {
             Self::Delete(s) => Display::fmt(s, f),
             Self::DropMeasurement(s) => Display::fmt(s, f),
             Self::Explain(s) => Display::fmt(s, f),
+            Self::KillQuery(s) => Display::fmt(s, f),
             Self::Select(s) => Display::fmt(s, f),
             Self::ShowDatabases(s) => Display::fmt(s, f),
             Self::ShowMeasurements(s) => Display::fmt(s, f),
@@ -67,6 +71,7 @@ pub fn statement(i: &str) -> ParseResult<&str, Statement> {
         map(delete_statement, |s| Statement::Delete(Box::new(s))),
         map(drop_statement, |s| Statement::DropMeasurement(Box::new(s))),
         map(explain_statement, |s| Statement::Explain(Box::new(s))),
+        map(kill_statement, |s| Statement::KillQuery(Box::new(s))),
         map(select_statement, |s| Statement::Select(Box::new(s))),
         create_statement,
         show_statement,
@@ -97,6 +102,10 @@ mod test {
         let (got, _) = statement("EXPLAIN SELECT * FROM cpu").unwrap();
         assert_eq!(got, "");
 
+        // kill_statement combinator
+        let (got, _) = statement("KILL QUERY 1").unwrap();
+        assert_eq!(got, "");
+
         let (got, _) = statement("SELECT * FROM foo WHERE time > now() - 5m AND host = 'bar' GROUP BY TIME(5m) FILL(previous) ORDER BY time DESC").unwrap();
         assert_eq!(got, "");
 
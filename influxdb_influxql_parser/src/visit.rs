@@ -35,6 +35,7 @@ use crate::explain::ExplainStatement;
 use crate::expression::arithmetic::Expr;
 use crate::expression::conditional::ConditionalExpression;
 use crate::expression::{Binary, Call, ConditionalBinary, VarRef};
+use crate::kill::KillQueryStatement;
 use crate::literal::Literal;
 use crate::select::{
     Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause,
@@ -150,6 +151,19 @@ pub trait Visitor: Sized {
         Ok(self)
     }
 
+    /// Invoked before any children of the `KILL QUERY` statement are visited.
+    fn pre_visit_kill_query_statement(
+        self,
+        _n: &KillQueryStatement,
+    ) -> Result<Recursion<Self>, Self::Error> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `KILL QUERY` statement are visited.
+    fn post_visit_kill_query_statement(self, _n: &KillQueryStatement) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
     /// Invoked before any children of the `EXPLAIN` statement are visited.
     fn pre_visit_explain_statement(
         self,
@@ -616,6 +630,7 @@ impl Visitable for Statement {
             Self::Delete(s) => s.accept(visitor),
             Self::DropMeasurement(s) => s.accept(visitor),
             Self::Explain(s) => s.accept(visitor),
+            Self::KillQuery(s) => s.accept(visitor),
             Self::Select(s) => s.accept(visitor),
             Self::ShowDatabases(s) => s.accept(visitor),
             Self::ShowMeasurements(s) => s.accept(visitor),
@@ -712,6 +727,17 @@ impl Visitable for DropMeasurementStatement {
     }
 }
 
+impl Visitable for KillQueryStatement {
+    fn accept<V: Visitor>(&self, visitor: V) -> Result<V, V::Error> {
+        let visitor = match visitor.pre_visit_kill_query_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_kill_query_statement(self)
+    }
+}
+
 impl Visitable for ExplainStatement {
     fn accept<V: Visitor>(&self, visitor: V) -> Result<V, V::Error> {
         let visitor = match visitor.pre_visit_explain_statement(self)? {
@@ -1352,6 +1378,7 @@ mod test {
     use crate::expression::arithmetic::Expr;
     use crate::expression::conditional::ConditionalExpression;
     use crate::expression::{Binary, Call, ConditionalBinary, VarRef};
+    use crate::kill::KillQueryStatement;
     use crate::literal::Literal;
     use crate::select::{
         Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause,
@@ -1412,6 +1439,7 @@ mod test {
         trace_visit!(measurement_name, MeasurementName);
         trace_visit!(drop_measurement_statement, DropMeasurementStatement);
         trace_visit!(explain_statement, ExplainStatement);
+        trace_visit!(kill_query_statement, KillQueryStatement);
         trace_visit!(select_statement, SelectStatement);
         trace_visit!(show_databases_statement, ShowDatabasesStatement);
         trace_visit!(show_measurements_statement, ShowMeasurementsStatement);
@@ -35,6 +35,7 @@ use crate::explain::ExplainStatement;
 use crate::expression::arithmetic::Expr;
 use crate::expression::conditional::ConditionalExpression;
 use crate::expression::{Binary, Call, ConditionalBinary, VarRef};
+use crate::kill::KillQueryStatement;
 use crate::literal::Literal;
 use crate::select::{
     Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause,
@@ -154,6 +155,22 @@ pub trait VisitorMut: Sized {
         Ok(())
     }
 
+    /// Invoked before any children of the `KILL QUERY` statement are visited.
+    fn pre_visit_kill_query_statement(
+        &mut self,
+        _n: &mut KillQueryStatement,
+    ) -> Result<Recursion, Self::Error> {
+        Ok(Continue)
+    }
+
+    /// Invoked after all children of the `KILL QUERY` statement are visited.
+    fn post_visit_kill_query_statement(
+        &mut self,
+        _n: &mut KillQueryStatement,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     /// Invoked before any children of the `EXPLAIN` statement are visited.
     fn pre_visit_explain_statement(
         &mut self,
@@ -646,6 +663,7 @@ impl VisitableMut for Statement {
             Self::Delete(s) => s.accept(visitor),
             Self::DropMeasurement(s) => s.accept(visitor),
             Self::Explain(s) => s.accept(visitor),
+            Self::KillQuery(s) => s.accept(visitor),
             Self::Select(s) => s.accept(visitor),
             Self::ShowDatabases(s) => s.accept(visitor),
             Self::ShowMeasurements(s) => s.accept(visitor),
@@ -736,6 +754,16 @@ impl VisitableMut for DropMeasurementStatement {
     }
 }
 
+impl VisitableMut for KillQueryStatement {
+    fn accept<V: VisitorMut>(&mut self, visitor: &mut V) -> Result<(), V::Error> {
+        if let Stop = visitor.pre_visit_kill_query_statement(self)? {
+            return Ok(());
+        };
+
+        visitor.post_visit_kill_query_statement(self)
+    }
+}
+
 impl VisitableMut for ExplainStatement {
     fn accept<V: VisitorMut>(&mut self, visitor: &mut V) -> Result<(), V::Error> {
         if let Stop = visitor.pre_visit_explain_statement(self)? {
@@ -1289,6 +1317,7 @@ mod test {
     use crate::expression::arithmetic::Expr;
     use crate::expression::conditional::ConditionalExpression;
     use crate::expression::{Binary, Call, ConditionalBinary, VarRef};
+    use crate::kill::KillQueryStatement;
     use crate::literal::Literal;
     use crate::parse_statements;
     use crate::select::{
@@ -1348,6 +1377,7 @@ mod test {
         trace_visit!(measurement_name, MeasurementName);
         trace_visit!(drop_measurement_statement, DropMeasurementStatement);
         trace_visit!(explain_statement, ExplainStatement);
+        trace_visit!(kill_query_statement, KillQueryStatement);
         trace_visit!(select_statement, SelectStatement);
         trace_visit!(show_databases_statement, ShowDatabasesStatement);
         trace_visit!(show_measurements_statement, ShowMeasurementsStatement);
@@ -0,0 +1,39 @@
+use influxdb_iox_client::{connection::Connection, flight};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error cancelling query: {0}")]
+    Cancel(#[from] influxdb_iox_client::flight::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Cancel a running query.
+///
+/// Hint: the IDs of running queries are listed in the `id` column of
+/// the `system.queries` table
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The IOx namespace the query runs in
+    #[clap(action)]
+    namespace: String,
+
+    /// The ID of the query to cancel
+    #[clap(action)]
+    query_id: u64,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = flight::Client::new(connection);
+
+    let Config {
+        namespace,
+        query_id,
+    } = config;
+
+    client.cancel_query(&namespace, query_id).await?;
+    println!("Cancelled query {query_id} in namespace {namespace}");
+
+    Ok(())
+}
@@ -5,11 +5,15 @@ use thiserror::Error;
 
 use crate::process_info::setup_metric_registry;
 
+mod backup;
 mod topic;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("Error in backup subcommand: {0}")]
+    Backup(#[from] backup::Error),
+
     #[error("Error in topic subcommand: {0}")]
     Topic(#[from] topic::Error),
 
@@ -42,6 +46,12 @@ enum Command {
 
     /// Manage topic
     Topic(topic::Config),
+
+    /// Write a snapshot of the catalog to the object store
+    Backup(Box<backup::Backup>),
+
+    /// Restore a snapshot from the object store into an empty catalog
+    Restore(Box<backup::Restore>),
 }
 
 pub async fn command(config: Config) -> Result<(), Error> {
@@ -55,6 +65,12 @@ pub async fn command(config: Config) -> Result<(), Error> {
         Command::Topic(config) => {
             topic::command(config).await?;
         }
+        Command::Backup(config) => {
+            backup::backup(*config).await?;
+        }
+        Command::Restore(config) => {
+            backup::restore(*config).await?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,129 @@
+//! This module implements the `catalog backup` and `catalog restore` CLI subcommands
+
+use bytes::Bytes;
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig, ObjectStoreType},
+};
+use iox_catalog::backup::CatalogSnapshot;
+use object_store::path::Path;
+use thiserror::Error;
+
+use crate::process_info::setup_metric_registry;
+
+/// Directory in the object store that backups are written to by default.
+const BACKUP_DIR: &str = "catalog_backups";
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Backup error: {0}")]
+    Backup(#[from] iox_catalog::backup::Error),
+
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Cannot parse object store config: {0}")]
+    ObjectStoreParsing(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error("Cannot (de)serialize snapshot: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error(
+        "The object store is configured to store files in memory which is \
+        unlikely to be useful - try passing --object-store=file"
+    )]
+    SillyObjectStoreConfig,
+}
+
+/// Write a snapshot of the catalog to the object store
+#[derive(Debug, clap::Parser)]
+pub struct Backup {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store: ObjectStoreConfig,
+
+    /// Path of the snapshot in the object store.
+    ///
+    /// Defaults to `catalog_backups/<nanoseconds since epoch>.json`.
+    #[clap(long = "path", action)]
+    path: Option<String>,
+}
+
+/// Restore a snapshot from the object store into an empty catalog
+#[derive(Debug, clap::Parser)]
+pub struct Restore {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store: ObjectStoreConfig,
+
+    /// Path of the snapshot in the object store
+    #[clap(action)]
+    path: String,
+}
+
+pub async fn backup(config: Backup) -> Result<(), Error> {
+    check_object_store(&config.object_store)?;
+    let object_store = make_object_store(&config.object_store)?;
+
+    let metrics = setup_metric_registry();
+    let catalog = config.catalog_dsn.get_catalog("cli", metrics).await?;
+    let snapshot = iox_catalog::backup::backup(catalog.as_ref()).await?;
+
+    let path = match config.path {
+        Some(path) => Path::from(path),
+        None => Path::from(format!("{BACKUP_DIR}/{}.json", snapshot.created_at)),
+    };
+    let data = serde_json::to_vec(&snapshot)?;
+    object_store.put(&path, Bytes::from(data)).await?;
+
+    println!("{}", summary(&snapshot));
+    println!("{path}");
+    Ok(())
+}
+
+pub async fn restore(config: Restore) -> Result<(), Error> {
+    check_object_store(&config.object_store)?;
+    let object_store = make_object_store(&config.object_store)?;
+
+    let data = object_store
+        .get(&Path::from(config.path))
+        .await?
+        .bytes()
+        .await?;
+    let snapshot: CatalogSnapshot = serde_json::from_slice(&data)?;
+
+    let metrics = setup_metric_registry();
+    let catalog = config.catalog_dsn.get_catalog("cli", metrics).await?;
+    iox_catalog::backup::restore(catalog.as_ref(), &snapshot).await?;
+
+    println!("{}", summary(&snapshot));
+    Ok(())
+}
+
+fn check_object_store(config: &ObjectStoreConfig) -> Result<(), Error> {
+    match &config.object_store {
+        None | Some(ObjectStoreType::Memory | ObjectStoreType::MemoryThrottled) => {
+            Err(Error::SillyObjectStoreConfig)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn summary(snapshot: &CatalogSnapshot) -> String {
+    format!(
+        "{} namespaces, {} tables, {} columns, {} partitions, {} parquet files",
+        snapshot.namespaces.len(),
+        snapshot.tables.len(),
+        snapshot.columns.len(),
+        snapshot.partitions.len(),
+        snapshot.parquet_files.len(),
+    )
+}
@@ -0,0 +1,125 @@
+//! This module implements the `compaction` CLI command
+
+use comfy_table::{Cell, Table};
+use influxdb_iox_client::{
+    compactor::{self, generated_types::CompactionJob},
+    connection::Connection,
+};
+use iox_time::Time;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Request, inspect and cancel compaction jobs of a compactor
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for compaction jobs
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Request immediate compaction of the given partitions
+    Partitions {
+        #[clap(required = true)]
+        partition_ids: Vec<i64>,
+    },
+
+    /// Request immediate compaction of all partitions of a table
+    Table {
+        namespace_name: String,
+        table_name: String,
+    },
+
+    /// List queued, running and recently finished compaction jobs
+    Jobs,
+
+    /// Cancel the queued or running compaction job of a partition
+    Cancel { partition_id: i64 },
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = compactor::Client::new(connection);
+    match config.command {
+        Command::Partitions { partition_ids } => {
+            let num_queued = client.compact_partitions(partition_ids).await?;
+            println!("Queued {num_queued} partition(s) for compaction");
+        }
+
+        Command::Table {
+            namespace_name,
+            table_name,
+        } => {
+            let response = client.compact_table(namespace_name, table_name).await?;
+            println!(
+                "Queued {} of {} partition(s) for compaction",
+                response.num_queued,
+                response.partition_ids.len()
+            );
+        }
+
+        Command::Jobs => {
+            let jobs = client.compaction_jobs().await?;
+            println!("{}", create_table(&jobs));
+        }
+
+        Command::Cancel { partition_id } => {
+            if client.cancel_compaction_job(partition_id).await? {
+                println!("Cancelled compaction of partition {partition_id}");
+            } else {
+                println!("Partition {partition_id} is neither queued nor being compacted");
+            }
+        } // Deliberately not adding _ => so the compiler will direct people here to impl new
+          // commands
+    }
+
+    Ok(())
+}
+
+/// Turn compaction jobs into a table
+fn create_table(jobs: &[CompactionJob]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = [
+        "partition_id",
+        "state",
+        "requested",
+        "enqueued_at",
+        "started_at",
+        "finished_at",
+        "rounds_completed",
+        "files_in_round",
+        "last_error",
+    ]
+    .into_iter()
+    .map(Cell::new)
+    .collect();
+    table.set_header(headers);
+
+    for job in jobs {
+        table.add_row(vec![
+            Cell::new(job.partition_id.to_string()),
+            Cell::new(job.state().as_str_name()),
+            Cell::new(job.requested.to_string()),
+            Cell::new(format_timestamp(job.enqueued_at)),
+            Cell::new(format_timestamp(job.started_at)),
+            Cell::new(format_timestamp(job.finished_at)),
+            Cell::new(job.rounds_completed.to_string()),
+            Cell::new(job.files_in_round.to_string()),
+            Cell::new(job.last_error.as_deref().unwrap_or_default()),
+        ]);
+    }
+
+    table
+}
+
+fn format_timestamp(ts: Option<i64>) -> String {
+    ts.map(|ts| Time::from_timestamp_nanos(ts).to_rfc3339())
+        .unwrap_or_default()
+}
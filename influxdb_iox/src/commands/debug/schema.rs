@@ -28,11 +28,67 @@ struct Get {
     namespace: String,
 }
 
+/// Create a table with a declared primary key
+#[derive(Debug, clap::Parser)]
+struct CreateTable {
+    /// The namespace of the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table to create
+    #[clap(action)]
+    table: String,
+
+    /// The tags of the primary key in sort order, separated by commas.
+    ///
+    /// The time column is always the last column of the primary key.
+    #[clap(long, value_delimiter = ',', required = true, action)]
+    primary_key: Vec<String>,
+}
+
+/// Soft-delete a table
+#[derive(Debug, clap::Parser)]
+struct DeleteTable {
+    /// The namespace of the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table to delete
+    #[clap(action)]
+    table: String,
+}
+
+/// Soft-delete a column of a table
+#[derive(Debug, clap::Parser)]
+struct DeleteColumn {
+    /// The namespace of the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The table of the column
+    #[clap(action)]
+    table: String,
+
+    /// The name of the column to delete
+    #[clap(action)]
+    column: String,
+}
+
 /// All possible subcommands for catalog
 #[derive(Debug, clap::Parser)]
 enum Command {
     /// Fetch schema for a namespace
     Get(Get),
+
+    /// Create a table sorted and deduplicated by a declared primary key. Writes with other tags
+    /// are rejected.
+    CreateTable(CreateTable),
+
+    /// Soft-delete a table and all of its data. The table name stays reserved.
+    DeleteTable(DeleteTable),
+
+    /// Soft-delete a column of a table. The column name stays reserved.
+    DeleteColumn(DeleteColumn),
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
@@ -41,6 +97,30 @@ pub async fn command(connection: Connection, config: Config) -> Result<(), Error
             let mut client = schema::Client::new(connection);
             let schema = client.get_schema(&command.namespace).await?;
             println!("{}", serde_json::to_string_pretty(&schema)?);
+        }
+        Command::CreateTable(command) => {
+            let mut client = schema::Client::new(connection);
+            let table = client
+                .create_table(&command.namespace, &command.table, command.primary_key)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&table)?);
+        }
+        Command::DeleteTable(command) => {
+            let mut client = schema::Client::new(connection);
+            client
+                .delete_table(&command.namespace, &command.table)
+                .await?;
+            println!("Deleted table {}", command.table);
+        }
+        Command::DeleteColumn(command) => {
+            let mut client = schema::Client::new(connection);
+            client
+                .delete_column(&command.namespace, &command.table, &command.column)
+                .await?;
+            println!(
+                "Deleted column {} of table {}",
+                command.column, command.table
+            );
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
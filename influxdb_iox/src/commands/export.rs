@@ -0,0 +1,422 @@
+//! This module implements the `export` CLI command
+
+use arrow::record_batch::RecordBatch;
+use datafusion::parquet::{arrow::ArrowWriter, errors::ParquetError};
+use flate2::{write::GzEncoder, Compression};
+use futures_util::TryStreamExt;
+use influxdb_iox_client::{
+    catalog::{self, generated_types::ParquetFile},
+    connection::Connection,
+    store,
+};
+use schema::{merge::SchemaMerger, Schema};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use thiserror::Error;
+use tokio::{
+    fs,
+    io::{self, AsyncWriteExt},
+};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+use self::{
+    manifest::{ExportParams, Manifest},
+    merge::{merge_partition, read_schema, ExportFile, TimeRange},
+};
+
+mod manifest;
+mod merge;
+
+/// Name of the manifest file within the output directory.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Directory within the output directory that holds the downloaded parquet files.
+const DOWNLOAD_DIRECTORY: &str = ".download";
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+
+    #[error("IO error: {0}")]
+    FileError(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Manifest(#[from] manifest::Error),
+
+    #[error("{0}")]
+    Merge(#[from] merge::Error),
+
+    #[error("Cannot merge schemas of table {table}: {source}")]
+    SchemaMerge {
+        table: String,
+        source: schema::merge::Error,
+    },
+
+    #[error("Cannot write parquet: {0}")]
+    Parquet(#[from] ParquetError),
+
+    #[error("Cannot convert to line protocol: {0}")]
+    LineProtocol(String),
+
+    #[error("Table not found: {0}")]
+    TableNotFound(String),
+
+    #[error("--gzip is only supported for line protocol exports")]
+    GzipParquet,
+}
+
+/// Export a namespace, or some of its tables, as parquet or line protocol
+///
+/// Parquet files are downloaded through the catalog and object store APIs and overlapping files
+/// are deduplicated the same way a query would. One file per table is written to the output
+/// directory. An interrupted export can be resumed by running the same command again.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to export
+    #[clap(action)]
+    namespace: String,
+
+    /// The tables to export. Can be repeated. Exports all tables if not specified.
+    #[clap(long = "table", short = 't', action)]
+    tables: Vec<String>,
+
+    /// Only export data at or after this time, as RFC3339 timestamp or nanoseconds since the
+    /// epoch
+    #[clap(long, value_parser = parse_timestamp)]
+    start: Option<i64>,
+
+    /// Only export data before this time, as RFC3339 timestamp or nanoseconds since the epoch
+    #[clap(long, value_parser = parse_timestamp)]
+    end: Option<i64>,
+
+    /// The output format
+    #[clap(long, short, value_enum, default_value_t = Format::Parquet, action)]
+    format: Format,
+
+    /// Compress line protocol output with gzip
+    #[clap(long, action)]
+    gzip: bool,
+
+    /// The output directory to use. If not specified, files will be placed in a directory named
+    /// after the namespace in the current working directory.
+    #[clap(action, short)]
+    output_directory: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Format {
+    /// One parquet file per table
+    Parquet,
+
+    /// One line protocol file per table
+    LineProtocol,
+}
+
+impl Format {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::LineProtocol => "line-protocol",
+        }
+    }
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    if config.gzip && config.format == Format::Parquet {
+        return Err(Error::GzipParquet);
+    }
+
+    let directory = config
+        .output_directory
+        .unwrap_or_else(|| PathBuf::from(&config.namespace));
+    fs::create_dir_all(&directory).await?;
+
+    let params = ExportParams {
+        namespace: config.namespace.clone(),
+        start: config.start,
+        end: config.end,
+        format: config.format.name().to_owned(),
+        gzip: config.gzip,
+    };
+    let mut manifest = Manifest::load_or_create(&directory.join(MANIFEST_FILE), params)?;
+
+    let mut schema_client = influxdb_iox_client::schema::Client::new(connection.clone());
+    let namespace_schema = schema_client.get_schema(&config.namespace).await?;
+    let tables = if config.tables.is_empty() {
+        let mut tables = namespace_schema.tables.into_keys().collect::<Vec<_>>();
+        tables.sort_unstable();
+        tables
+    } else {
+        if let Some(table) = config
+            .tables
+            .iter()
+            .find(|table| !namespace_schema.tables.contains_key(*table))
+        {
+            return Err(Error::TableNotFound(table.clone()));
+        }
+        config.tables
+    };
+
+    let mut exporter = TableExporter {
+        catalog_client: catalog::Client::new(connection.clone()),
+        store_client: store::Client::new(connection),
+        namespace: config.namespace,
+        directory,
+        format: config.format,
+        gzip: config.gzip,
+        range: TimeRange {
+            start: config.start,
+            end: config.end,
+        },
+    };
+
+    for table in tables {
+        if let Some(rows) = manifest.completed(&table) {
+            println!("skipping table {table} ({rows} rows already exported)");
+            continue;
+        }
+
+        let rows = exporter.export(&table).await?;
+        manifest.complete_table(&table, rows)?;
+        println!("exported {rows} rows of table {table}");
+    }
+    println!("Done.");
+
+    Ok(())
+}
+
+/// Exports a single table at a time.
+struct TableExporter {
+    catalog_client: catalog::Client,
+    store_client: store::Client,
+    namespace: String,
+    directory: PathBuf,
+    format: Format,
+    gzip: bool,
+    range: TimeRange,
+}
+
+impl TableExporter {
+    /// Export `table` and return the number of exported rows.
+    ///
+    /// Partitions are merged one at a time, so memory usage is bounded by the size of the largest
+    /// partition.
+    async fn export(&mut self, table: &str) -> Result<u64, Error> {
+        let files = self
+            .catalog_client
+            .get_parquet_files_by_namespace_table(self.namespace.clone(), table.to_owned())
+            .await?
+            .into_iter()
+            .filter(|file| file.to_delete == 0 && self.range.overlaps(file.min_time, file.max_time))
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        let download_directory = self.directory.join(DOWNLOAD_DIRECTORY).join(table);
+        fs::create_dir_all(&download_directory).await?;
+        let local_path = |file: &ParquetFile| {
+            download_directory.join(format!("{}.parquet", file.object_store_id))
+        };
+
+        let num_files = files.len();
+        println!("found {num_files} Parquet files for table {table}, downloading...");
+        let mut merger = SchemaMerger::new();
+        for file in &files {
+            let path = local_path(file);
+            self.download(file, &path).await?;
+            merger =
+                merger
+                    .merge(&read_schema(&path).await?)
+                    .map_err(|source| Error::SchemaMerge {
+                        table: table.to_owned(),
+                        source,
+                    })?;
+        }
+        let schema = merger.build();
+
+        // merge files in the order the querier would see them, oldest first
+        let mut partitions = BTreeMap::<i64, Vec<ParquetFile>>::new();
+        for file in files {
+            partitions.entry(file.partition_id).or_default().push(file);
+        }
+        for files in partitions.values_mut() {
+            files.sort_by_key(|file| (file.max_l0_created_at, file.id));
+        }
+
+        let output_path = self.directory.join(self.output_file_name(table));
+        let tmp_path = output_path.with_extension("tmp");
+        let mut writer = TableWriter::create(&tmp_path, self.format, self.gzip, table, &schema)?;
+
+        let mut rows = 0;
+        for files in partitions.into_values() {
+            let mut partition_files = Vec::with_capacity(files.len());
+            for file in &files {
+                partition_files.push(ExportFile::read(&local_path(file)).await?);
+            }
+
+            for batch in merge_partition(partition_files, &schema, self.range).await? {
+                rows += batch.num_rows() as u64;
+                writer.write(&batch)?;
+            }
+        }
+
+        writer.finish()?;
+        fs::rename(&tmp_path, &output_path).await?;
+
+        Ok(rows)
+    }
+
+    /// Download `file` to `path` unless it was downloaded before.
+    async fn download(&mut self, file: &ParquetFile, path: &Path) -> Result<(), Error> {
+        if fs::metadata(path).await.map_or(false, |metadata| {
+            metadata.len() == file.file_size_bytes as u64
+        }) {
+            return Ok(());
+        }
+
+        let mut response = self
+            .store_client
+            .get_parquet_file_by_object_store_id(file.object_store_id.clone())
+            .await?
+            .map_ok(|res| res.data)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+            .into_async_read()
+            .compat();
+        let mut local_file = fs::File::create(path).await?;
+        io::copy(&mut response, &mut local_file).await?;
+        local_file.flush().await?;
+
+        Ok(())
+    }
+
+    fn output_file_name(&self, table: &str) -> String {
+        match (self.format, self.gzip) {
+            (Format::Parquet, _) => format!("{table}.parquet"),
+            (Format::LineProtocol, false) => format!("{table}.lp"),
+            (Format::LineProtocol, true) => format!("{table}.lp.gz"),
+        }
+    }
+}
+
+/// Writes the output file of a table.
+enum TableWriter {
+    Parquet(ArrowWriter<File>),
+    LineProtocol {
+        table: String,
+        schema: Schema,
+        out: LineProtocolOutput,
+    },
+}
+
+enum LineProtocolOutput {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl TableWriter {
+    fn create(
+        path: &Path,
+        format: Format,
+        gzip: bool,
+        table: &str,
+        schema: &Schema,
+    ) -> Result<Self, Error> {
+        let file = File::create(path)?;
+        let writer = match format {
+            Format::Parquet => Self::Parquet(ArrowWriter::try_new(file, schema.as_arrow(), None)?),
+            Format::LineProtocol => {
+                let file = BufWriter::new(file);
+                let out = if gzip {
+                    LineProtocolOutput::Gzip(GzEncoder::new(file, Compression::default()))
+                } else {
+                    LineProtocolOutput::Plain(file)
+                };
+                Self::LineProtocol {
+                    table: table.to_owned(),
+                    schema: schema.clone(),
+                    out,
+                }
+            }
+        };
+
+        Ok(writer)
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        match self {
+            Self::Parquet(writer) => writer.write(batch)?,
+            Self::LineProtocol { table, schema, out } => {
+                let lines = parquet_to_line_protocol::convert_to_lines(table, schema, batch)
+                    .map_err(Error::LineProtocol)?;
+                match out {
+                    LineProtocolOutput::Plain(w) => w.write_all(&lines)?,
+                    LineProtocolOutput::Gzip(w) => w.write_all(&lines)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        let file = match self {
+            Self::Parquet(writer) => {
+                writer.close()?;
+                return Ok(());
+            }
+            Self::LineProtocol {
+                out: LineProtocolOutput::Plain(w),
+                ..
+            } => w,
+            Self::LineProtocol {
+                out: LineProtocolOutput::Gzip(w),
+                ..
+            } => w.finish()?,
+        };
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+
+        Ok(())
+    }
+}
+
+/// Parse a timestamp given as RFC3339 string or as nanoseconds since the epoch.
+fn parse_timestamp(s: &str) -> Result<i64, String> {
+    if let Ok(nanos) = s.parse::<i64>() {
+        return Ok(nanos);
+    }
+
+    let time = humantime::parse_rfc3339_weak(s).map_err(|e| e.to_string())?;
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|d| i64::try_from(d.as_nanos()).ok())
+        .ok_or_else(|| format!("timestamp out of range: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("42").unwrap(), 42);
+        assert_eq!(parse_timestamp("-1").unwrap(), -1);
+        assert_eq!(
+            parse_timestamp("1970-01-01T00:00:01Z").unwrap(),
+            1_000_000_000
+        );
+        assert_eq!(
+            parse_timestamp("2023-01-01 00:00:00").unwrap(),
+            1_672_531_200_000_000_000
+        );
+        assert!(parse_timestamp("yesterday").is_err());
+        assert!(parse_timestamp("1900-01-01T00:00:00Z").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! The manifest that makes an export resumable.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Cannot read manifest {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Cannot write manifest {path:?}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Cannot parse manifest {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error(
+        "Manifest {path:?} belongs to an export with different options, \
+        use a different output directory or delete the manifest"
+    )]
+    Mismatch { path: PathBuf },
+}
+
+/// The options of an export that determine its output.
+///
+/// Resuming an export is only possible with the same options.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportParams {
+    pub namespace: String,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub format: String,
+    pub gzip: bool,
+}
+
+/// Progress of an export, persisted as JSON in the output directory.
+///
+/// Tables are the unit of progress: a table is only recorded once its output file is complete,
+/// so an interrupted export redoes at most one table. Downloaded parquet files are kept in the
+/// output directory and are not fetched again.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(skip)]
+    path: PathBuf,
+
+    params: ExportParams,
+
+    /// Completed tables and the number of rows exported for each.
+    completed_tables: BTreeMap<String, u64>,
+}
+
+impl Manifest {
+    /// Load the manifest at `path`, or create a new one if it does not exist.
+    ///
+    /// Fails if an existing manifest was written for different `params`.
+    pub fn load_or_create(path: &Path, params: ExportParams) -> Result<Self, Error> {
+        let manifest = match std::fs::read(path) {
+            Ok(data) => {
+                let mut manifest: Self =
+                    serde_json::from_slice(&data).map_err(|source| Error::Parse {
+                        path: path.to_owned(),
+                        source,
+                    })?;
+                if manifest.params != params {
+                    return Err(Error::Mismatch {
+                        path: path.to_owned(),
+                    });
+                }
+                manifest.path = path.to_owned();
+                manifest
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self {
+                path: path.to_owned(),
+                params,
+                completed_tables: BTreeMap::new(),
+            },
+            Err(source) => {
+                return Err(Error::Read {
+                    path: path.to_owned(),
+                    source,
+                })
+            }
+        };
+
+        Ok(manifest)
+    }
+
+    /// Returns the number of exported rows if the table was already exported.
+    pub fn completed(&self, table: &str) -> Option<u64> {
+        self.completed_tables.get(table).copied()
+    }
+
+    /// Record that `table` was exported and persist the manifest.
+    pub fn complete_table(&mut self, table: &str, rows: u64) -> Result<(), Error> {
+        self.completed_tables.insert(table.to_owned(), rows);
+        self.save()
+    }
+
+    /// Atomically write the manifest to disk.
+    fn save(&self) -> Result<(), Error> {
+        let data = serde_json::to_vec_pretty(self).expect("manifest serializes");
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, data)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|source| Error::Write {
+                path: self.path.clone(),
+                source,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn test_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+
+        let mut manifest = Manifest::load_or_create(&path, params()).unwrap();
+        assert_eq!(manifest.completed("cpu"), None);
+        manifest.complete_table("cpu", 42).unwrap();
+
+        let manifest = Manifest::load_or_create(&path, params()).unwrap();
+        assert_eq!(manifest.completed("cpu"), Some(42));
+        assert_eq!(manifest.completed("mem"), None);
+
+        let other = ExportParams {
+            gzip: true,
+            ..params()
+        };
+        assert_matches!(
+            Manifest::load_or_create(&path, other),
+            Err(Error::Mismatch { .. })
+        );
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+        std::fs::write(&path, "{").unwrap();
+
+        assert_matches!(
+            Manifest::load_or_create(&path, params()),
+            Err(Error::Parse { .. })
+        );
+    }
+
+    fn params() -> ExportParams {
+        ExportParams {
+            namespace: "ns".to_owned(),
+            start: Some(1),
+            end: None,
+            format: "line-protocol".to_owned(),
+            gzip: false,
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Reading and deduplicating the parquet files of a partition.
+
+use arrow::{
+    array::{new_null_array, ArrayRef, Int64Array, TimestampNanosecondArray},
+    compute::{and, filter_record_batch, kernels::comparison},
+    datatypes::{Fields, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    datasource::object_store::ObjectStoreUrl,
+    error::DataFusionError,
+    execution::context::SessionContext,
+    physical_plan::{
+        collect,
+        expressions::{Column, PhysicalSortExpr},
+        memory::MemoryExec,
+        sorts::sort::SortExec,
+        ExecutionPlan,
+    },
+};
+use futures::TryStreamExt;
+use iox_query::{chunk_order_field, provider::DeduplicateExec, CHUNK_ORDER_COLUMN_NAME};
+use object_store::{local::LocalFileSystem, path::Path as ObjectStorePath, ObjectStore};
+use parquet_to_line_protocol::ParquetFileReader;
+use schema::{Schema, TIME_COLUMN_NAME};
+use std::{path::Path, sync::Arc};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid path: {0}")]
+    Path(#[from] object_store::path::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error("Cannot read parquet file: {0}")]
+    Reader(#[from] parquet_to_line_protocol::Error),
+
+    #[error("Invalid IOx schema: {0}")]
+    Schema(#[from] schema::Error),
+
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    #[error("DataFusion error: {0}")]
+    DataFusion(#[from] DataFusionError),
+}
+
+/// Time range of an export, `start` inclusive and `end` exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl TimeRange {
+    /// Returns true if any timestamp between `min` and `max` (both inclusive) is in the range.
+    pub fn overlaps(&self, min: i64, max: i64) -> bool {
+        self.start.map_or(true, |start| max >= start) && self.end.map_or(true, |end| min < end)
+    }
+
+    /// Remove the rows of `batch` that are outside of the range.
+    fn filter(&self, batch: RecordBatch) -> Result<RecordBatch, Error> {
+        if self.start.is_none() && self.end.is_none() {
+            return Ok(batch);
+        }
+
+        let time = batch
+            .column_by_name(TIME_COLUMN_NAME)
+            .and_then(|col| col.as_any().downcast_ref::<TimestampNanosecondArray>())
+            .ok_or_else(|| {
+                ArrowError::SchemaError(format!("missing column '{TIME_COLUMN_NAME}'"))
+            })?;
+
+        let mask = match (self.start, self.end) {
+            (Some(start), Some(end)) => and(
+                &comparison::gt_eq_scalar(time, start)?,
+                &comparison::lt_scalar(time, end)?,
+            )?,
+            (Some(start), None) => comparison::gt_eq_scalar(time, start)?,
+            (None, Some(end)) => comparison::lt_scalar(time, end)?,
+            (None, None) => unreachable!("checked above"),
+        };
+
+        Ok(filter_record_batch(&batch, &mask)?)
+    }
+}
+
+/// A parquet file written by IOx, read into memory.
+#[derive(Debug)]
+pub struct ExportFile {
+    pub batches: Vec<RecordBatch>,
+}
+
+impl ExportFile {
+    /// Read the local parquet file at `path`.
+    pub async fn read(path: &Path) -> Result<Self, Error> {
+        let reader = open(path).await?;
+        let batches = reader
+            .read()
+            .await?
+            .try_collect()
+            .await
+            .map_err(DataFusionError::from)?;
+
+        Ok(Self { batches })
+    }
+}
+
+/// Read the IOx schema of the local parquet file at `path`.
+pub async fn read_schema(path: &Path) -> Result<Schema, Error> {
+    let reader = open(path).await?;
+    Ok(Schema::try_from(reader.schema())?)
+}
+
+async fn open(path: &Path) -> Result<ParquetFileReader, Error> {
+    let object_store = Arc::new(LocalFileSystem::new()) as Arc<dyn ObjectStore>;
+    let object_store_path = ObjectStorePath::from_filesystem_path(path)?;
+    let object_meta = object_store.head(&object_store_path).await?;
+
+    Ok(ParquetFileReader::try_new(
+        object_store,
+        ObjectStoreUrl::local_filesystem(),
+        object_meta,
+    )
+    .await?)
+}
+
+/// Merge the files of a single partition into one deduplicated set of rows with the given table
+/// `schema`, keeping only the rows within `range`.
+///
+/// `files` must be ordered from oldest to newest: for rows with the same primary key, the
+/// newest non-null value of each field wins, as it does when querying.
+pub async fn merge_partition(
+    files: Vec<ExportFile>,
+    schema: &Schema,
+    range: TimeRange,
+) -> Result<Vec<RecordBatch>, Error> {
+    let arrow_schema = schema.as_arrow();
+
+    let batches = if files.len() == 1 {
+        // files are deduplicated when they are persisted or compacted
+        files
+            .into_iter()
+            .flat_map(|file| file.batches)
+            .map(|batch| pad_batch(&batch, &arrow_schema, &[]))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        deduplicate(files, schema).await?
+    };
+
+    batches
+        .into_iter()
+        .map(|batch| range.filter(batch))
+        .filter(|batch| !matches!(batch, Ok(batch) if batch.num_rows() == 0))
+        .collect()
+}
+
+async fn deduplicate(files: Vec<ExportFile>, schema: &Schema) -> Result<Vec<RecordBatch>, Error> {
+    let arrow_schema = schema.as_arrow();
+    let schema_with_chunk_order = Arc::new(ArrowSchema::new(
+        arrow_schema
+            .fields
+            .iter()
+            .cloned()
+            .chain(std::iter::once(chunk_order_field()))
+            .collect::<Fields>(),
+    ));
+
+    let mut batches = vec![];
+    for (order, file) in files.into_iter().enumerate() {
+        for batch in file.batches {
+            let chunk_order = Arc::new(Int64Array::from_value(order as i64, batch.num_rows()));
+            batches.push(pad_batch(&batch, &schema_with_chunk_order, &[chunk_order])?);
+        }
+    }
+
+    let sort_exprs = |names: &mut dyn Iterator<Item = &str>| {
+        names
+            .map(|name| {
+                Ok(PhysicalSortExpr {
+                    expr: Arc::new(Column::new_with_schema(name, &schema_with_chunk_order)?),
+                    options: Default::default(),
+                })
+            })
+            .collect::<Result<Vec<_>, DataFusionError>>()
+    };
+    let dedup_key = sort_exprs(&mut schema.primary_key().into_iter())?;
+    let input_order = sort_exprs(
+        &mut schema
+            .primary_key()
+            .into_iter()
+            .chain(std::iter::once(CHUNK_ORDER_COLUMN_NAME)),
+    )?;
+
+    let plan: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+        &[batches],
+        Arc::clone(&schema_with_chunk_order),
+        None,
+    )?);
+    let plan = Arc::new(SortExec::new(input_order, plan));
+    let plan = Arc::new(DeduplicateExec::new(plan, dedup_key, true));
+
+    let ctx = SessionContext::new();
+    let projection = (0..arrow_schema.fields().len()).collect::<Vec<_>>();
+    collect(plan, ctx.task_ctx())
+        .await?
+        .into_iter()
+        .map(|batch| Ok(batch.project(&projection)?))
+        .collect()
+}
+
+/// Convert `batch` to `schema`, filling columns that the batch lacks with NULLs.
+///
+/// The last `extra.len()` columns of `schema` are taken from `extra`.
+fn pad_batch(
+    batch: &RecordBatch,
+    schema: &ArrowSchemaRef,
+    extra: &[ArrayRef],
+) -> Result<RecordBatch, ArrowError> {
+    let n_fields = schema.fields().len() - extra.len();
+    let columns = schema
+        .fields()
+        .iter()
+        .take(n_fields)
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(column) => Arc::clone(column),
+            None => new_null_array(field.data_type(), batch.num_rows()),
+        })
+        .chain(extra.iter().cloned())
+        .collect();
+
+    RecordBatch::try_new(Arc::clone(schema), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use schema::builder::SchemaBuilder;
+
+    #[test]
+    fn test_time_range_overlaps() {
+        let range = TimeRange {
+            start: Some(10),
+            end: Some(20),
+        };
+        assert!(range.overlaps(0, 10));
+        assert!(range.overlaps(19, 30));
+        assert!(!range.overlaps(0, 9));
+        assert!(!range.overlaps(20, 30));
+        assert!(TimeRange::default().overlaps(i64::MIN, i64::MIN));
+    }
+
+    #[tokio::test]
+    async fn test_merge_partition() {
+        let schema = SchemaBuilder::new()
+            .tag("tag")
+            .influx_field("a", schema::InfluxFieldType::Integer)
+            .influx_field("b", schema::InfluxFieldType::Integer)
+            .timestamp()
+            .build()
+            .unwrap();
+
+        // older file without field `b`
+        let old = file(
+            &schema,
+            &["tag", "a", "time"],
+            vec![
+                tags(&["x", "x", "y"]),
+                ints(&[Some(1), Some(2), Some(3)]),
+                times(&[1, 2, 3]),
+            ],
+        );
+        // newer file overwriting `a` of one row and adding `b` to another
+        let new = file(
+            &schema,
+            &["tag", "a", "b", "time"],
+            vec![
+                tags(&["x", "y"]),
+                ints(&[Some(10), None]),
+                ints(&[None, Some(30)]),
+                times(&[1, 3]),
+            ],
+        );
+
+        let batches = merge_partition(vec![old, new], &schema, TimeRange::default())
+            .await
+            .unwrap();
+        assert_batches_eq!(
+            &[
+                "+-----+----+----+--------------------------------+",
+                "| tag | a  | b  | time                           |",
+                "+-----+----+----+--------------------------------+",
+                "| x   | 10 |    | 1970-01-01T00:00:00.000000001Z |",
+                "| x   | 2  |    | 1970-01-01T00:00:00.000000002Z |",
+                "| y   | 3  | 30 | 1970-01-01T00:00:00.000000003Z |",
+                "+-----+----+----+--------------------------------+",
+            ],
+            &batches
+        );
+
+        let old = file(
+            &schema,
+            &["tag", "a", "time"],
+            vec![
+                tags(&["x", "x", "y"]),
+                ints(&[Some(1), Some(2), Some(3)]),
+                times(&[1, 2, 3]),
+            ],
+        );
+        let range = TimeRange {
+            start: Some(2),
+            end: Some(3),
+        };
+        let batches = merge_partition(vec![old], &schema, range).await.unwrap();
+        assert_batches_eq!(
+            &[
+                "+-----+---+---+--------------------------------+",
+                "| tag | a | b | time                           |",
+                "+-----+---+---+--------------------------------+",
+                "| x   | 2 |   | 1970-01-01T00:00:00.000000002Z |",
+                "+-----+---+---+--------------------------------+",
+            ],
+            &batches
+        );
+    }
+
+    /// Build a file with a subset of the columns of `schema`.
+    fn file(schema: &Schema, columns: &[&str], arrays: Vec<ArrayRef>) -> ExportFile {
+        let schema = schema.select_by_names(columns).unwrap();
+        let batch = RecordBatch::try_new(schema.as_arrow(), arrays).unwrap();
+        ExportFile {
+            batches: vec![batch],
+        }
+    }
+
+    fn tags(values: &[&str]) -> ArrayRef {
+        Arc::new(
+            values
+                .iter()
+                .copied()
+                .collect::<arrow::array::DictionaryArray<arrow::datatypes::Int32Type>>(),
+        )
+    }
+
+    fn ints(values: &[Option<i64>]) -> ArrayRef {
+        Arc::new(Int64Array::from(values.to_vec()))
+    }
+
+    fn times(values: &[i64]) -> ArrayRef {
+        Arc::new(TimestampNanosecondArray::from(values.to_vec()))
+    }
+}
@@ -5,7 +5,11 @@ use thiserror::Error;
 
 mod create;
 mod delete;
+mod purge;
+mod query_priority;
 mod retention;
+mod type_coercion;
+mod undelete;
 mod update_limit;
 
 #[allow(clippy::enum_variant_names)]
@@ -42,8 +46,22 @@ enum Command {
     /// Update one of the service protection limits for an existing namespace
     UpdateLimit(update_limit::Config),
 
+    /// Update how writes to an existing namespace with conflicting field
+    /// types are handled
+    TypeCoercion(type_coercion::Config),
+
+    /// Update the priority class of queries against an existing namespace
+    /// that do not request one
+    QueryPriority(query_priority::Config),
+
     /// Delete a namespace
     Delete(delete::Config),
+
+    /// Restore a deleted namespace within its delete grace period
+    Undelete(undelete::Config),
+
+    /// Permanently remove a deleted namespace and all its data
+    Purge(purge::Config),
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
@@ -62,8 +80,20 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         Command::UpdateLimit(config) => {
             update_limit::command(connection, config).await?;
         }
+        Command::TypeCoercion(config) => {
+            type_coercion::command(connection, config).await?;
+        }
+        Command::QueryPriority(config) => {
+            query_priority::command(connection, config).await?;
+        }
         Command::Delete(config) => {
             delete::command(connection, config).await?;
+        }
+        Command::Undelete(config) => {
+            undelete::command(connection, config).await?;
+        }
+        Command::Purge(config) => {
+            purge::command(connection, config).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
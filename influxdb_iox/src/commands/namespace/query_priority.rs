@@ -0,0 +1,49 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::namespace::generated_types::QueryPriority;
+
+use crate::commands::namespace::Result;
+
+/// Update the priority class of queries against a namespace that do not
+/// request one
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the default query priority for
+    #[clap(action)]
+    namespace: String,
+
+    /// The default query priority to apply
+    #[clap(value_enum, action)]
+    priority: Priority,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Priority {
+    /// Regular queries
+    Normal,
+    /// Latency sensitive queries such as dashboards and metadata lookups
+    Interactive,
+    /// Expensive queries, such as ad-hoc analytics, that may wait for
+    /// resources
+    Batch,
+}
+
+impl From<Priority> for QueryPriority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::Normal => Self::Normal,
+            Priority::Interactive => Self::Interactive,
+            Priority::Batch => Self::Batch,
+        }
+    }
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client
+        .update_namespace_default_query_priority(&config.namespace, config.priority.into())
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
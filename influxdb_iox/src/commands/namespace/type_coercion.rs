@@ -0,0 +1,54 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::namespace::generated_types::TypeCoercionPolicy;
+
+use crate::commands::namespace::Result;
+
+/// Update how writes with a field type that conflicts with the existing column
+/// type are handled
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the type coercion policy for
+    #[clap(action)]
+    namespace: String,
+
+    /// The type coercion policy to apply
+    #[clap(value_enum, action)]
+    policy: Policy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Policy {
+    /// Reject writes with a conflicting field type
+    Strict,
+    /// Convert integer and unsigned integer values written to a float column
+    /// to floats
+    Widen,
+    /// Convert numeric values to the type of the existing numeric column if
+    /// every value converts without loss
+    LosslessCast,
+}
+
+impl From<Policy> for TypeCoercionPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Strict => Self::Strict,
+            Policy::Widen => Self::Widen,
+            Policy::LosslessCast => Self::LosslessCast,
+        }
+    }
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client
+        .update_namespace_type_coercion_policy(&config.namespace, config.policy.into())
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    println!(
+        r"
+NOTE: This change will NOT take effect until all router instances have been restarted!"
+    );
+    Ok(())
+}
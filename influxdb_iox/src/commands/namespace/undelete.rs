@@ -0,0 +1,21 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::namespace::Result;
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The deleted namespace to be restored
+    #[clap(action)]
+    namespace: String,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config { namespace } = config;
+
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client.undelete_namespace(&namespace).await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
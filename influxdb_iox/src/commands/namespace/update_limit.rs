@@ -16,14 +16,21 @@ pub struct Config {
 #[derive(Debug, clap::Args)]
 #[clap(group(
             // This arg group "limit" links the members of the below struct 
-            // named "max_tables" and "max_columns_per_table" together as 
+            // named "max_tables", "max_columns_per_table", etc. together as 
             // mutually exclusive flags. As we specify all flags & commands
             // using clap-derive rather than the imperative builder, v3 only
             // properly supports this kind of behaviour in a macro code block.
             // NOTE: It takes the variable names and not the flag long names.
             clap::ArgGroup::new("limit")
                 .required(true)
-                .args(&["max_tables", "max_columns_per_table"])
+                .args(&[
+                    "max_tables",
+                    "max_columns_per_table",
+                    "max_lines_per_second",
+                    "max_batch_bytes_per_second",
+                    "max_concurrent_queries",
+                    "max_query_memory_bytes",
+                ])
         ))]
 struct Args {
     /// The maximum number of tables to allow for this namespace
@@ -33,6 +40,27 @@ struct Args {
     /// The maximum number of columns to allow per table for this namespace
     #[clap(action, long = "max-columns-per-table", short = 'c', group = "limit")]
     max_columns_per_table: Option<i32>,
+
+    /// The maximum number of line protocol lines per second to accept for
+    /// this namespace (0 removes the limit)
+    #[clap(action, long = "max-lines-per-second", group = "limit")]
+    max_lines_per_second: Option<i64>,
+
+    /// The maximum number of bytes of write data per second to accept for
+    /// this namespace, measured as the in-memory size of the decoded writes
+    /// (0 removes the limit)
+    #[clap(action, long = "max-batch-bytes-per-second", group = "limit")]
+    max_batch_bytes_per_second: Option<i64>,
+
+    /// The maximum number of queries the querier runs concurrently for this
+    /// namespace (0 removes the limit)
+    #[clap(action, long = "max-concurrent-queries", group = "limit")]
+    max_concurrent_queries: Option<i32>,
+
+    /// The maximum number of bytes of querier memory the queries of this
+    /// namespace may hold at once (0 removes the limit)
+    #[clap(action, long = "max-query-memory-bytes", group = "limit")]
+    max_query_memory_bytes: Option<i64>,
 }
 
 impl From<Args> for LimitUpdate {
@@ -40,6 +68,10 @@ impl From<Args> for LimitUpdate {
         let Args {
             max_tables,
             max_columns_per_table,
+            max_lines_per_second,
+            max_batch_bytes_per_second,
+            max_concurrent_queries,
+            max_query_memory_bytes,
         } = args;
 
         if let Some(n) = max_tables {
@@ -48,6 +80,18 @@ impl From<Args> for LimitUpdate {
         if let Some(n) = max_columns_per_table {
             return Self::MaxColumnsPerTable(n);
         }
+        if let Some(n) = max_lines_per_second {
+            return Self::MaxLinesPerSecond(n);
+        }
+        if let Some(n) = max_batch_bytes_per_second {
+            return Self::MaxBatchBytesPerSecond(n);
+        }
+        if let Some(n) = max_concurrent_queries {
+            return Self::MaxConcurrentQueries(n);
+        }
+        if let Some(n) = max_query_memory_bytes {
+            return Self::MaxQueryMemoryBytes(n);
+        }
         unreachable!();
     }
 }
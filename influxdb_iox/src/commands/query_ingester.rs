@@ -78,6 +78,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         columns,
         predicate,
         namespace_id,
+        last_values: false,
     };
 
     // send the message directly encoded as bytes to the ingester.
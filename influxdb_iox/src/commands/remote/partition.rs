@@ -264,10 +264,27 @@ async fn load_schema(
     println!("namespace {} loaded into local catalog", &namespace.name);
 
     for (table_name, table_schema) in &schema.tables {
-        let table = repos
-            .tables()
-            .create_or_get(table_name, namespace.id)
-            .await?;
+        let table = if table_schema.primary_key.is_empty() {
+            repos
+                .tables()
+                .create_or_get(table_name, namespace.id)
+                .await?
+        } else {
+            match repos
+                .tables()
+                .create(table_name, namespace.id, &table_schema.primary_key)
+                .await
+            {
+                Ok(t) => t,
+                Err(iox_catalog::interface::Error::NameExists { .. }) => {
+                    repos
+                        .tables()
+                        .create_or_get(table_name, namespace.id)
+                        .await?
+                }
+                e => e?,
+            }
+        };
         for (column_name, column_schema) in &table_schema.columns {
             let column_type: ColumnType = column_schema
                 .column_type()
@@ -411,6 +428,7 @@ mod tests {
                 "table1".to_string(),
                 TableSchema {
                     id: 1,
+                    primary_key: vec![],
                     columns: HashMap::from([(
                         "col1".to_string(),
                         ColumnSchema {
@@ -420,6 +438,10 @@ mod tests {
                     )]),
                 },
             )]),
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
         let res = load_schema(&catalog, "foo", &schema).await.unwrap();
 
@@ -444,6 +466,7 @@ mod tests {
                 "table1".to_string(),
                 TableSchema {
                     id: 1,
+                    primary_key: vec![],
                     columns: HashMap::from([(
                         "col1".to_string(),
                         ColumnSchema {
@@ -453,6 +476,10 @@ mod tests {
                     )]),
                 },
             )]),
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
         assert!(load_schema(&catalog, "foo", &schema).await.is_ok());
 
@@ -465,6 +492,7 @@ mod tests {
                     "newtable".to_string(),
                     TableSchema {
                         id: 2,
+                        primary_key: vec![],
                         columns: HashMap::from([(
                             "col1".to_string(),
                             ColumnSchema {
@@ -478,6 +506,7 @@ mod tests {
                     "table1".to_string(),
                     TableSchema {
                         id: 1,
+                        primary_key: vec![],
                         columns: HashMap::from([
                             (
                                 "col1".to_string(),
@@ -497,6 +526,10 @@ mod tests {
                     },
                 ),
             ]),
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
 
         let res = load_schema(&catalog, "foo", &schema).await.unwrap();
@@ -467,6 +467,8 @@ impl Config {
             persist_queue_depth,
             persist_hot_partition_cost,
             rpc_write_max_incoming_bytes: 1024 * 1024 * 1024, // 1GiB
+            last_value_cache: false,
+            last_value_cache_max_series: 100_000,
         };
 
         let router_config = Router2Config {
@@ -474,9 +476,11 @@ impl Config {
             single_tenant_deployment,
             query_pool_name: QUERY_POOL_NAME.to_string(),
             http_request_limit: 1_000,
+            http_write_stream_chunk_bytes: None,
             ingester_addresses: ingester_addresses.clone(),
             new_namespace_retention_hours: None, // infinite retention
             namespace_autocreation_enabled: true,
+            namespace_delete_grace_period: Duration::from_secs(14 * 24 * 60 * 60),
             partition_key_pattern: "%Y-%m-%d".to_string(),
             topic: QUERY_POOL_NAME.to_string(),
             rpc_write_timeout_seconds: Duration::new(3, 0),
@@ -505,6 +509,8 @@ impl Config {
             ignore_partition_skip_marker: false,
             shard_count: None,
             shard_id: None,
+            partition_lease_secs: None,
+            partition_lease_owner: None,
             min_num_l1_files_to_compact: 1,
             process_once: false,
             process_all_partitions: false,
@@ -515,12 +521,19 @@ impl Config {
         let querier_config = QuerierConfig {
             authz_address,
             num_query_threads: None, // will be ignored
+            num_interactive_query_threads: None,
+            num_batch_query_threads: None,
             ingester_addresses,
             ram_pool_metadata_bytes: querier_ram_pool_metadata_bytes,
             ram_pool_data_bytes: querier_ram_pool_data_bytes,
             max_concurrent_queries: querier_max_concurrent_queries,
+            max_concurrent_interactive_queries: None,
+            max_concurrent_normal_queries: None,
+            max_concurrent_batch_queries: None,
             exec_mem_pool_bytes,
             ingester_circuit_breaker_threshold: u64::MAX, // never for all-in-one-mode
+            query_result_cache: false,
+            query_result_cache_bucket_width: Default::default(),
             datafusion_config: Default::default(),
         };
 
@@ -621,6 +634,7 @@ pub async fn command(config: Config) -> Result<()> {
             Arc::clone(parquet_store_real.object_store()),
         )]),
         mem_pool_size: querier_config.exec_mem_pool_bytes,
+        query_priority_threads: querier_config.query_priority_threads(),
     }));
 
     info!("starting router");
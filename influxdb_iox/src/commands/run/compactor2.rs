@@ -18,6 +18,7 @@ use object_store::DynObjectStore;
 use object_store_metrics::ObjectStoreMetrics;
 use observability_deps::tracing::*;
 use parquet_file::storage::{ParquetStorage, StorageId};
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use thiserror::Error;
@@ -113,6 +114,7 @@ pub async fn command(config: Config) -> Result<(), Error> {
             .map(|store| (store.id(), Arc::clone(store.object_store())))
             .collect(),
         mem_pool_size: config.compactor_config.exec_mem_pool_bytes,
+        query_priority_threads: HashMap::default(),
     }));
     let time_provider = Arc::new(SystemProvider::new());
 
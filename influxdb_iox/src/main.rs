@@ -33,8 +33,10 @@ use std::{
 use tokio::runtime::Runtime;
 
 mod commands {
+    pub mod cancel_query;
     pub mod catalog;
     pub mod debug;
+    pub mod export;
     pub mod import;
     pub mod namespace;
     pub mod query;
@@ -197,6 +199,9 @@ enum Command {
     /// Interrogate internal data
     Debug(commands::debug::Config),
 
+    /// Export a namespace as parquet or line protocol files
+    Export(commands::export::Config),
+
     /// Initiate a read request to the gRPC storage service.
     Storage(commands::storage::Config),
 
@@ -209,6 +214,9 @@ enum Command {
     /// Query the ingester only
     QueryIngester(commands::query_ingester::Config),
 
+    /// Cancel a running query
+    CancelQuery(commands::cancel_query::Config),
+
     /// Commands related to the bulk ingest of data
     Import(commands::import::Config),
 
@@ -349,6 +357,14 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Export(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::export::command(connection, config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Write(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(http_host).await;
@@ -373,6 +389,14 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::CancelQuery(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::cancel_query::command(connection, config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Import(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(grpc_host).await;
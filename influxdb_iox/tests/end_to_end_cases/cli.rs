@@ -372,6 +372,28 @@ async fn schema_cli() {
                                     .and(predicate::str::contains("val")),
                             );
                     }
+
+                    // Create a table with a declared primary key
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(state.cluster().router().router_grpc_base().to_string())
+                        .arg("debug")
+                        .arg("schema")
+                        .arg("create-table")
+                        .arg(state.cluster().namespace())
+                        .arg("declared_table")
+                        .arg("--primary-key")
+                        .arg("region,host")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(
+                            r#""primaryKey": [
+    "region",
+    "host",
+    "time"
+  ]"#,
+                        ));
                 }
                 .boxed()
             })),
@@ -913,6 +935,78 @@ async fn namespace_deletion() {
                 }
                 .boxed()
             })),
+            // undelete the namespace within its grace period
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+                    let namespace = "bananas_namespace";
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("undelete")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(namespace));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("list")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(namespace));
+                }
+                .boxed()
+            })),
+            // delete it again and purge it for good
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+                    let namespace = "bananas_namespace";
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("delete")
+                        .arg(namespace)
+                        .assert()
+                        .success();
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("purge")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains("Purged namespace")
+                                .and(predicate::str::contains(namespace)),
+                        );
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("undelete")
+                        .arg(namespace)
+                        .assert()
+                        .failure()
+                        .stderr(predicate::str::contains("no soft-deleted namespace"));
+                }
+                .boxed()
+            })),
         ],
     )
     .run()
@@ -1145,6 +1239,54 @@ async fn namespace_update_service_limit() {
                 }
                 .boxed()
             })),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let namespace = "service_limiter_namespace";
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("type-coercion")
+                        .arg(namespace)
+                        .arg("widen")
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(namespace)
+                                .and(predicate::str::contains(r#""maxColumnsPerTable": 42"#))
+                                .and(predicate::str::contains(
+                                    r#""typeCoercionPolicy": "TYPE_COERCION_POLICY_WIDEN""#,
+                                )),
+                        );
+                }
+                .boxed()
+            })),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let namespace = "service_limiter_namespace";
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("query-priority")
+                        .arg(namespace)
+                        .arg("interactive")
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(namespace).and(predicate::str::contains(
+                                r#""defaultQueryPriority": "QUERY_PRIORITY_INTERACTIVE""#,
+                            )),
+                        );
+                }
+                .boxed()
+            })),
         ],
     )
     .run()
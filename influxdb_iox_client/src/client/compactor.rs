@@ -43,4 +43,55 @@ impl Client {
 
         Ok(response.into_inner().skipped_compaction)
     }
+
+    /// Request immediate compaction of the given partitions.
+    ///
+    /// Returns the number of newly queued partitions.
+    pub async fn compact_partitions(&mut self, partition_ids: Vec<i64>) -> Result<i64, Error> {
+        let response = self
+            .inner
+            .compact_partitions(CompactPartitionsRequest { partition_ids })
+            .await?;
+
+        Ok(response.into_inner().num_queued)
+    }
+
+    /// Request immediate compaction of all partitions of the given table
+    pub async fn compact_table(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        table_name: impl Into<String> + Send,
+    ) -> Result<CompactTableResponse, Error> {
+        let response = self
+            .inner
+            .compact_table(CompactTableRequest {
+                namespace_name: namespace_name.into(),
+                table_name: table_name.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    /// List queued, running and recently finished compaction jobs
+    pub async fn compaction_jobs(&mut self) -> Result<Vec<CompactionJob>, Error> {
+        let response = self
+            .inner
+            .list_compaction_jobs(ListCompactionJobsRequest {})
+            .await?;
+
+        Ok(response.into_inner().jobs)
+    }
+
+    /// Cancel the compaction job of the given partition.
+    ///
+    /// Returns `false` if the partition was neither queued nor running.
+    pub async fn cancel_compaction_job(&mut self, partition_id: i64) -> Result<bool, Error> {
+        let response = self
+            .inner
+            .cancel_compaction_job(CancelCompactionJobRequest { partition_id })
+            .await?;
+
+        Ok(response.into_inner().cancelled)
+    }
 }
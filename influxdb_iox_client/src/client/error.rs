@@ -157,6 +157,22 @@ impl Error {
         })
     }
 
+    /// Return a `Error::ResourceExhausted` variant with the specified message
+    pub(crate) fn resource_exhausted(message: impl Into<String>) -> Self {
+        Self::ResourceExhausted(ServerError {
+            message: message.into(),
+            details: None,
+        })
+    }
+
+    /// Return a `Error::Unavailable` variant with the specified message
+    pub(crate) fn unavailable(message: impl Into<String>) -> Self {
+        Self::Unavailable(ServerError {
+            message: message.into(),
+            details: None,
+        })
+    }
+
     /// Return a `Error::Client` variant with the specified message
     pub(crate) fn client<E: std::error::Error + Send + Sync + 'static>(e: E) -> Self {
         Self::Client(Box::new(e))
@@ -186,6 +202,12 @@ pub(crate) async fn translate_response(response: reqwest::Response) -> Result<()
 
     if status.is_success() {
         Ok(())
+    } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Err(Error::resource_exhausted(
+            response_description(response).await,
+        ))
+    } else if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        Err(Error::unavailable(response_description(response).await))
     } else if status.is_server_error() {
         Err(Error::internal(response_description(response).await))
     } else {
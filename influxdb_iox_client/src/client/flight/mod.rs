@@ -2,8 +2,10 @@
 
 use std::{pin::Pin, task::Poll};
 
-use ::generated_types::influxdata::iox::querier::v1::{read_info::QueryType, ReadInfo};
-use futures_util::{Stream, StreamExt};
+use ::generated_types::influxdata::iox::querier::v1::{
+    read_info::QueryType, CancelQuery, ReadInfo,
+};
+use futures_util::{Stream, StreamExt, TryStreamExt};
 use prost::Message;
 use thiserror::Error;
 use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
@@ -15,7 +17,9 @@ use arrow::{
 
 use rand::Rng;
 
-use arrow_flight::{decode::FlightRecordBatchStream, error::FlightError, FlightClient, Ticket};
+use arrow_flight::{
+    decode::FlightRecordBatchStream, error::FlightError, Action, FlightClient, Ticket,
+};
 
 use crate::connection::Connection;
 
@@ -27,6 +31,9 @@ pub mod generated_types {
     };
 }
 
+/// Type of the Flight `DoAction` request that cancels a running query.
+pub const CANCEL_QUERY_ACTION_TYPE: &str = "CancelQuery";
+
 /// Error responses when querying an IOx namespace using the IOx Flight API.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -222,6 +229,35 @@ impl Client {
         self.do_get_with_read_info(request).await
     }
 
+    /// Cancel the running query with the given ID in the given namespace.
+    ///
+    /// Query IDs are listed in the `system.queries` table.
+    pub async fn cancel_query(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        query_id: u64,
+    ) -> Result<(), Error> {
+        let request = CancelQuery {
+            namespace_name: namespace_name.into(),
+            query_id,
+        };
+
+        let action = Action {
+            r#type: CANCEL_QUERY_ACTION_TYPE.into(),
+            body: request.encode_to_vec().into(),
+        };
+
+        // the server does not return any results, but drain the
+        // stream to see any errors
+        self.inner
+            .do_action(action)
+            .await?
+            .try_for_each(|_| async { Ok(()) })
+            .await?;
+
+        Ok(())
+    }
+
     /// Perform a lower level client read with the `ReadInfo`
     async fn do_get_with_read_info(
         &mut self,
@@ -103,6 +103,42 @@ impl Client {
         Ok(response.into_inner().namespace.unwrap_field("namespace")?)
     }
 
+    /// Update how writes to `namespace` with a field type that conflicts with
+    /// the existing column type are handled.
+    pub async fn update_namespace_type_coercion_policy(
+        &mut self,
+        namespace: &str,
+        policy: TypeCoercionPolicy,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_type_coercion_policy(UpdateNamespaceTypeCoercionPolicyRequest {
+                name: namespace.to_string(),
+                policy: policy.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Update the priority class of queries against `namespace` that do not
+    /// request one.
+    pub async fn update_namespace_default_query_priority(
+        &mut self,
+        namespace: &str,
+        priority: QueryPriority,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_default_query_priority(UpdateNamespaceDefaultQueryPriorityRequest {
+                name: namespace.to_string(),
+                priority: priority.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
     /// Delete a namespace
     pub async fn delete_namespace(&mut self, namespace: &str) -> Result<(), Error> {
         self.inner
@@ -113,4 +149,27 @@ impl Client {
 
         Ok(())
     }
+
+    /// Undo the deletion of a namespace within its delete grace period
+    pub async fn undelete_namespace(&mut self, namespace: &str) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .undelete_namespace(UndeleteNamespaceRequest {
+                name: namespace.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Permanently remove a deleted namespace and all its data
+    pub async fn purge_namespace(&mut self, namespace: &str) -> Result<(), Error> {
+        self.inner
+            .purge_namespace(PurgeNamespaceRequest {
+                name: namespace.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
 }
@@ -35,4 +35,56 @@ impl Client {
 
         Ok(response.into_inner().schema.unwrap_field("schema")?)
     }
+
+    /// Create a table with a declared primary key.
+    ///
+    /// `primary_key` lists the tags of the primary key in sort order, the time column is appended
+    /// by the server.
+    pub async fn create_table(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        primary_key: Vec<String>,
+    ) -> Result<TableSchema, Error> {
+        let response = self
+            .inner
+            .create_table(CreateTableRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                primary_key,
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
+
+    /// Soft-delete a table of a namespace.
+    pub async fn delete_table(&mut self, namespace: &str, table: &str) -> Result<(), Error> {
+        self.inner
+            .delete_table(DeleteTableRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Soft-delete a column of a table.
+    pub async fn delete_column(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        column: &str,
+    ) -> Result<(), Error> {
+        self.inner
+            .delete_column(DeleteColumnRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                column: column.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
 }
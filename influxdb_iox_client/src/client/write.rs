@@ -9,6 +9,9 @@ use crate::{
 };
 use reqwest::{Body, Method};
 
+/// A writer that batches, retries and spools writes.
+pub mod buffered;
+
 /// The default value for the maximum size of each request, in bytes
 pub const DEFAULT_MAX_REQUEST_PAYLOAD_SIZE_BYTES: Option<usize> = Some(1024 * 1024);
 
@@ -0,0 +1,766 @@
+//! A writer that batches line protocol, retries failed writes and spools pending batches to disk.
+
+use std::{
+    collections::VecDeque,
+    io,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
+
+use backoff::{Backoff, BackoffConfig};
+use influxdb_line_protocol::LineProtocolBuilder;
+use tokio::{sync::Notify, task::JoinHandle, time::Instant};
+
+use super::Client;
+use crate::error::Error;
+
+/// File name extension of spooled batches.
+const SPOOL_FILE_EXTENSION: &str = "lp";
+
+/// Configuration of a [`BufferedWriter`].
+#[derive(Debug, Clone)]
+pub struct BufferedWriterConfig {
+    /// Send a batch once it holds at least this many bytes of line protocol.
+    pub max_batch_bytes: usize,
+
+    /// Send a batch once its first line was written this long ago.
+    pub max_batch_age: Duration,
+
+    /// Maximum number of bytes in batches waiting to be sent.
+    ///
+    /// Once exceeded, the oldest batches are dropped.
+    pub max_queued_bytes: usize,
+
+    /// Directory that holds batches until they are sent, so that they survive restarts.
+    ///
+    /// If `None`, batches are only held in memory.
+    pub spool_dir: Option<PathBuf>,
+
+    /// Backoff between attempts to send a batch.
+    ///
+    /// A batch is given up on once the [deadline](BackoffConfig::deadline) passes.
+    pub backoff: BackoffConfig,
+}
+
+impl BufferedWriterConfig {
+    /// Default value for [`max_batch_bytes`](Self::max_batch_bytes).
+    pub const DEFAULT_MAX_BATCH_BYTES: usize = 1024 * 1024;
+
+    /// Default value for [`max_batch_age`](Self::max_batch_age).
+    pub const DEFAULT_MAX_BATCH_AGE: Duration = Duration::from_secs(1);
+
+    /// Default value for [`max_queued_bytes`](Self::max_queued_bytes).
+    pub const DEFAULT_MAX_QUEUED_BYTES: usize = 64 * 1024 * 1024;
+}
+
+impl Default for BufferedWriterConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_bytes: Self::DEFAULT_MAX_BATCH_BYTES,
+            max_batch_age: Self::DEFAULT_MAX_BATCH_AGE,
+            max_queued_bytes: Self::DEFAULT_MAX_QUEUED_BYTES,
+            spool_dir: None,
+            backoff: BackoffConfig::default(),
+        }
+    }
+}
+
+/// Delivery metrics of a [`BufferedWriter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryMetrics {
+    /// Batches that were written.
+    pub batches_delivered: u64,
+
+    /// Bytes of line protocol that were written.
+    pub bytes_delivered: u64,
+
+    /// Requests that failed with an error worth retrying.
+    pub retries: u64,
+
+    /// Batches given up on, because the server rejected them or the retry deadline passed.
+    pub batches_failed: u64,
+
+    /// Batches dropped because too many bytes were waiting to be sent.
+    pub batches_dropped: u64,
+
+    /// Batches that could not be spooled to disk and are only held in memory.
+    pub spool_errors: u64,
+
+    /// Batches waiting to be sent.
+    pub queued_batches: u64,
+
+    /// Bytes of line protocol waiting to be sent, including the batch being filled.
+    pub queued_bytes: u64,
+}
+
+/// A writer that collects points into batches and sends them to a namespace in the background.
+///
+/// A batch is sent once it reaches [`max_batch_bytes`](BufferedWriterConfig::max_batch_bytes) or
+/// [`max_batch_age`](BufferedWriterConfig::max_batch_age). Batches are sent one at a time in the
+/// order they were written. Requests that fail because the server is overloaded or unavailable
+/// are retried with exponential backoff and jitter, batches the server rejects are dropped.
+///
+/// Batches waiting to be sent are kept in the
+/// [`spool_dir`](BufferedWriterConfig::spool_dir), if configured, and sent by the next writer
+/// for that directory if the process stops. Points that are not part of a batch yet are lost
+/// when the writer is dropped without a [`flush`](Self::flush).
+///
+/// ```no_run
+/// #[tokio::main]
+/// # async fn main() {
+/// use influxdb_iox_client::{
+///     connection::Builder,
+///     write::{
+///         buffered::{BufferedWriter, BufferedWriterConfig},
+///         Client,
+///     },
+/// };
+/// use influxdb_line_protocol::LineProtocolBuilder;
+///
+/// let connection = Builder::default()
+///     .build("http://127.0.0.1:8080")
+///     .await
+///     .unwrap();
+///
+/// let writer = BufferedWriter::new(
+///     Client::new(connection),
+///     "fruit_bananas",
+///     BufferedWriterConfig::default(),
+/// )
+/// .expect("failed to start writer");
+///
+/// writer.write(
+///     LineProtocolBuilder::new()
+///         .measurement("cpu")
+///         .tag("region", "west")
+///         .field("user", 23.2)
+///         .close_line(),
+/// );
+///
+/// // wait until the point is written
+/// writer.flush().await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BufferedWriter {
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl BufferedWriter {
+    /// Start writing to `namespace` through `client`.
+    ///
+    /// Batches left in the spool directory by a previous writer are sent first.
+    pub fn new(
+        client: Client,
+        namespace: impl Into<String>,
+        config: BufferedWriterConfig,
+    ) -> Result<Self, Error> {
+        let queue = match &config.spool_dir {
+            Some(dir) => recover_spool(dir).map_err(Error::client)?,
+            None => VecDeque::new(),
+        };
+
+        let state = State {
+            current: vec![],
+            current_since: None,
+            next_seq: queue.back().map_or(0, |batch| batch.seq + 1),
+            queued_bytes: queue.iter().map(|batch| batch.size).sum(),
+            queue,
+            in_flight: None,
+        };
+        let shared = Arc::new(Shared {
+            config,
+            state: Mutex::new(state),
+            batch_ready: Notify::new(),
+            batch_done: Notify::new(),
+            counters: Counters::default(),
+        });
+        shared.drop_excess(&mut shared.state());
+
+        let task = tokio::spawn(deliver_batches(
+            Arc::clone(&shared),
+            client,
+            namespace.into(),
+        ));
+
+        Ok(Self { shared, task })
+    }
+
+    /// Add the lines built by `lines` to the current batch.
+    pub fn write(&self, lines: LineProtocolBuilder<Vec<u8>>) {
+        let lines = lines.build();
+        if lines.is_empty() {
+            return;
+        }
+
+        let max_batch_bytes = self.shared.config.max_batch_bytes;
+        let mut state = self.shared.state();
+        if !state.current.is_empty() && state.current.len() + lines.len() > max_batch_bytes {
+            self.shared.seal(&mut state);
+        }
+
+        if state.current.is_empty() {
+            state.current_since = Some(Instant::now());
+            // start the age timer of the batch
+            self.shared.batch_ready.notify_one();
+        }
+        state.current.extend_from_slice(&lines);
+
+        if state.current.len() >= max_batch_bytes {
+            self.shared.seal(&mut state);
+        }
+    }
+
+    /// Send the current batch and wait until it and all batches before it were delivered or
+    /// given up on.
+    pub async fn flush(&self) {
+        let end_seq = {
+            let mut state = self.shared.state();
+            self.shared.seal(&mut state);
+            state.next_seq
+        };
+
+        loop {
+            let done = self.shared.batch_done.notified();
+            let pending = self
+                .shared
+                .state()
+                .queue
+                .front()
+                .map_or(false, |batch| batch.seq < end_seq);
+            if !pending {
+                return;
+            }
+            done.await;
+        }
+    }
+
+    /// Current delivery metrics.
+    pub fn metrics(&self) -> DeliveryMetrics {
+        let state = self.shared.state();
+        let counters = &self.shared.counters;
+
+        DeliveryMetrics {
+            batches_delivered: counters.batches_delivered.load(Ordering::Relaxed),
+            bytes_delivered: counters.bytes_delivered.load(Ordering::Relaxed),
+            retries: counters.retries.load(Ordering::Relaxed),
+            batches_failed: counters.batches_failed.load(Ordering::Relaxed),
+            batches_dropped: counters.batches_dropped.load(Ordering::Relaxed),
+            spool_errors: counters.spool_errors.load(Ordering::Relaxed),
+            queued_batches: state.queue.len() as u64,
+            queued_bytes: (state.queued_bytes + state.current.len()) as u64,
+        }
+    }
+}
+
+impl Drop for BufferedWriter {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// State shared between a [`BufferedWriter`] and its delivery task.
+#[derive(Debug)]
+struct Shared {
+    config: BufferedWriterConfig,
+    state: Mutex<State>,
+
+    /// Wakes the delivery task when a batch was sealed or the current batch was started.
+    batch_ready: Notify,
+
+    /// Wakes flushes when a batch was delivered or given up on.
+    batch_done: Notify,
+
+    counters: Counters,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    batches_delivered: AtomicU64,
+    bytes_delivered: AtomicU64,
+    retries: AtomicU64,
+    batches_failed: AtomicU64,
+    batches_dropped: AtomicU64,
+    spool_errors: AtomicU64,
+}
+
+#[derive(Debug)]
+struct State {
+    /// The batch being filled.
+    current: Vec<u8>,
+
+    /// When the first line of `current` was written.
+    current_since: Option<Instant>,
+
+    /// Sealed batches, in the order they are sent.
+    queue: VecDeque<Batch>,
+
+    /// Total size of the batches in `queue`.
+    queued_bytes: usize,
+
+    /// Sequence number of the next sealed batch.
+    next_seq: u64,
+
+    /// Sequence number of the batch being sent.
+    in_flight: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Batch {
+    seq: u64,
+    size: usize,
+    data: BatchData,
+}
+
+#[derive(Debug, Clone)]
+enum BatchData {
+    Memory(Arc<Vec<u8>>),
+    Spooled(PathBuf),
+}
+
+impl Batch {
+    /// The line protocol of the batch.
+    fn load(&self) -> Result<String, Error> {
+        let data = match &self.data {
+            BatchData::Memory(data) => data.to_vec(),
+            BatchData::Spooled(path) => std::fs::read(path).map_err(Error::client)?,
+        };
+        String::from_utf8(data).map_err(Error::client)
+    }
+
+    /// Remove the batch from the spool directory, if it is spooled.
+    fn discard(&self) {
+        if let BatchData::Spooled(path) = &self.data {
+            // a batch left behind is sent again by the next writer
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("buffered writer state poisoned")
+    }
+
+    /// Move the current batch to the queue.
+    fn seal(&self, state: &mut State) {
+        if state.current.is_empty() {
+            return;
+        }
+
+        let data = std::mem::take(&mut state.current);
+        let seq = state.next_seq;
+        let size = data.len();
+        state.current_since = None;
+        state.next_seq += 1;
+
+        let data = match &self.config.spool_dir {
+            Some(dir) => match spool(dir, seq, &data) {
+                Ok(path) => BatchData::Spooled(path),
+                Err(_) => {
+                    self.counters.spool_errors.fetch_add(1, Ordering::Relaxed);
+                    BatchData::Memory(Arc::new(data))
+                }
+            },
+            None => BatchData::Memory(Arc::new(data)),
+        };
+
+        state.queue.push_back(Batch { seq, size, data });
+        state.queued_bytes += size;
+        self.drop_excess(state);
+        self.batch_ready.notify_one();
+    }
+
+    /// Drop the oldest batches, except for the one being sent, until the queue fits into
+    /// [`max_queued_bytes`](BufferedWriterConfig::max_queued_bytes).
+    fn drop_excess(&self, state: &mut State) {
+        while state.queued_bytes > self.config.max_queued_bytes {
+            let skip_in_flight = state.queue.front().map(|batch| batch.seq) == state.in_flight;
+            let Some(batch) = state.queue.remove(usize::from(skip_in_flight)) else {
+                break;
+            };
+
+            state.queued_bytes -= batch.size;
+            batch.discard();
+            self.counters
+                .batches_dropped
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Send `batch`, retrying errors worth retrying.
+    async fn deliver(&self, client: &Client, namespace: &str, batch: &Batch) {
+        let lp = match batch.load() {
+            Ok(lp) => lp,
+            Err(_) => {
+                self.counters.batches_failed.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        let result = Backoff::new(&self.config.backoff)
+            .retry_with_backoff("write buffered batch", || {
+                let mut client = client.clone();
+                let namespace = namespace.to_owned();
+                let lp = lp.clone();
+
+                async move {
+                    match client.write_lp(namespace, lp).await {
+                        Ok(_) => ControlFlow::Break(Ok(())),
+                        Err(e) if is_retryable(&e) => {
+                            self.counters.retries.fetch_add(1, Ordering::Relaxed);
+                            ControlFlow::Continue(e)
+                        }
+                        Err(e) => ControlFlow::Break(Err(e)),
+                    }
+                }
+            })
+            .await;
+
+        match result {
+            Ok(Ok(())) => {
+                self.counters
+                    .batches_delivered
+                    .fetch_add(1, Ordering::Relaxed);
+                self.counters
+                    .bytes_delivered
+                    .fetch_add(batch.size as u64, Ordering::Relaxed);
+            }
+            Ok(Err(_)) | Err(_) => {
+                self.counters.batches_failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Remove the batch with sequence number `seq` after it was sent or given up on.
+    fn finish(&self, seq: u64) {
+        let mut state = self.state();
+        state.in_flight = None;
+        if let Some(pos) = state.queue.iter().position(|batch| batch.seq == seq) {
+            let batch = state.queue.remove(pos).expect("position is in the queue");
+            state.queued_bytes -= batch.size;
+            batch.discard();
+        }
+        drop(state);
+
+        self.batch_done.notify_waiters();
+    }
+}
+
+/// Send the batches of a [`BufferedWriter`], sealing the current batch once it is too old.
+async fn deliver_batches(shared: Arc<Shared>, client: Client, namespace: String) {
+    loop {
+        let ready = shared.batch_ready.notified();
+
+        let (batch, batch_deadline) = {
+            let mut state = shared.state();
+            let max_batch_age = shared.config.max_batch_age;
+            if matches!(state.current_since, Some(since) if since.elapsed() >= max_batch_age) {
+                shared.seal(&mut state);
+            }
+
+            let batch = state.queue.front().cloned();
+            state.in_flight = batch.as_ref().map(|batch| batch.seq);
+            (
+                batch,
+                state.current_since.map(|since| since + max_batch_age),
+            )
+        };
+
+        match (batch, batch_deadline) {
+            (Some(batch), _) => {
+                shared.deliver(&client, &namespace, &batch).await;
+                shared.finish(batch.seq);
+            }
+            (None, Some(deadline)) => {
+                tokio::select! {
+                    _ = ready => {}
+                    _ = tokio::time::sleep_until(deadline) => {}
+                }
+            }
+            (None, None) => ready.await,
+        }
+    }
+}
+
+/// Whether a write that failed with `e` may succeed later, i.e. the server is overloaded or
+/// unavailable, failed internally or could not be reached.
+fn is_retryable(e: &Error) -> bool {
+    matches!(
+        e,
+        Error::ResourceExhausted(_)
+            | Error::Unavailable(_)
+            | Error::DeadlineExceeded(_)
+            | Error::Internal(_)
+            | Error::Client(_)
+    )
+}
+
+fn spool_path(dir: &Path, seq: u64) -> PathBuf {
+    dir.join(format!("{seq:020}.{SPOOL_FILE_EXTENSION}"))
+}
+
+/// Write the batch `seq` to the spool directory, returning its path.
+fn spool(dir: &Path, seq: u64, data: &[u8]) -> io::Result<PathBuf> {
+    // Write to a temporary file first, so that a crash never leaves a partial batch behind.
+    let path = spool_path(dir, seq);
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, data)?;
+    std::fs::rename(&tmp_path, &path)?;
+    Ok(path)
+}
+
+/// Read the batches left in the spool directory `dir`, creating it if it does not exist.
+fn recover_spool(dir: &Path) -> io::Result<VecDeque<Batch>> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut batches = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(true, |ext| ext != SPOOL_FILE_EXTENSION)
+        {
+            continue;
+        }
+        let Some(seq) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse().ok())
+        else {
+            continue;
+        };
+
+        batches.push(Batch {
+            seq,
+            size: std::fs::metadata(&path)?.len() as usize,
+            data: BatchData::Spooled(path),
+        });
+    }
+    batches.sort_by_key(|batch| batch.seq);
+
+    Ok(batches.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use futures_util::{future::BoxFuture, FutureExt};
+
+    use super::*;
+    use crate::client::write::RequestMaker;
+
+    const NAMESPACE: &str = "orgname_bucketname";
+
+    #[tokio::test]
+    async fn test_batch_by_size() {
+        let mock = Arc::new(MockRequestMaker::default());
+        let writer = new_writer(&mock, |config| config.max_batch_bytes = 14);
+
+        writer.write(line(1));
+        writer.write(line(2));
+        writer.write(line(3));
+        writer.flush().await;
+
+        assert_eq!(mock.bodies(), ["m v=1i\nm v=2i", "m v=3i"]);
+        assert_eq!(
+            writer.metrics(),
+            DeliveryMetrics {
+                batches_delivered: 2,
+                bytes_delivered: 21,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_by_age() {
+        let mock = Arc::new(MockRequestMaker::default());
+        let writer = new_writer(&mock, |config| {
+            config.max_batch_age = Duration::from_millis(10)
+        });
+
+        writer.write(line(1));
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while mock.bodies().is_empty() {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("batch was not sent");
+
+        assert_eq!(mock.bodies(), ["m v=1i"]);
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let mock = Arc::new(MockRequestMaker::default());
+        mock.respond(Err(Error::unavailable("down")));
+        mock.respond(Err(Error::resource_exhausted("slow down")));
+        let writer = new_writer(&mock, |_| {});
+
+        writer.write(line(1));
+        writer.flush().await;
+
+        assert_eq!(mock.bodies(), ["m v=1i"]);
+        assert_eq!(
+            writer.metrics(),
+            DeliveryMetrics {
+                batches_delivered: 1,
+                bytes_delivered: 7,
+                retries: 2,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_batch_is_dropped() {
+        let mock = Arc::new(MockRequestMaker::default());
+        mock.respond(Err(Error::invalid_argument("lp", "bad line")));
+        let writer = new_writer(&mock, |_| {});
+
+        writer.write(line(1));
+        writer.flush().await;
+        writer.write(line(2));
+        writer.flush().await;
+
+        assert_eq!(mock.bodies(), ["m v=2i"]);
+        assert_eq!(
+            writer.metrics(),
+            DeliveryMetrics {
+                batches_delivered: 1,
+                bytes_delivered: 7,
+                batches_failed: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_batches() {
+        let mock = Arc::new(MockRequestMaker::default());
+        mock.fail.store(true, Ordering::Relaxed);
+        let writer = new_writer(&mock, |config| {
+            config.max_batch_bytes = 1;
+            config.max_queued_bytes = 14;
+        });
+
+        for v in 1..=4 {
+            writer.write(line(v));
+        }
+
+        let metrics = writer.metrics();
+        assert_eq!(metrics.batches_dropped, 2);
+        assert_eq!(metrics.queued_batches, 2);
+        assert_eq!(metrics.queued_bytes, 14);
+    }
+
+    #[tokio::test]
+    async fn test_spool_survives_restart() {
+        let spool_dir = tempfile::tempdir().unwrap();
+        let with_spool = |config: &mut BufferedWriterConfig| {
+            config.max_batch_bytes = 1;
+            config.spool_dir = Some(spool_dir.path().to_owned());
+        };
+
+        // the server is down, so the batches stay in the spool
+        let mock = Arc::new(MockRequestMaker::default());
+        mock.fail.store(true, Ordering::Relaxed);
+        let writer = new_writer(&mock, with_spool);
+        writer.write(line(1));
+        writer.write(line(2));
+        assert_eq!(writer.metrics().queued_batches, 2);
+        drop(writer);
+
+        let mock = Arc::new(MockRequestMaker::default());
+        let writer = new_writer(&mock, with_spool);
+        assert_eq!(writer.metrics().queued_batches, 2);
+        writer.write(line(3));
+        writer.flush().await;
+
+        assert_eq!(mock.bodies(), ["m v=1i", "m v=2i", "m v=3i"]);
+        assert_eq!(std::fs::read_dir(spool_dir.path()).unwrap().count(), 0);
+    }
+
+    fn line(v: i64) -> LineProtocolBuilder<Vec<u8>> {
+        LineProtocolBuilder::new()
+            .measurement("m")
+            .field("v", v)
+            .close_line()
+    }
+
+    fn new_writer(
+        mock: &Arc<MockRequestMaker>,
+        configure: impl FnOnce(&mut BufferedWriterConfig),
+    ) -> BufferedWriter {
+        let mut config = BufferedWriterConfig {
+            max_batch_age: Duration::from_secs(3600),
+            backoff: BackoffConfig {
+                init_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        configure(&mut config);
+
+        let client = Client::new_with_maker(Arc::clone(mock) as _);
+        BufferedWriter::new(client, NAMESPACE, config).unwrap()
+    }
+
+    #[derive(Debug, Default)]
+    struct MockRequestMaker {
+        /// Responses to the next requests, which succeed once these are used up
+        responses: Mutex<VecDeque<Result<(), Error>>>,
+        /// Fail all requests as unavailable
+        fail: AtomicBool,
+        /// Bodies of the successful requests
+        bodies: Mutex<Vec<String>>,
+    }
+
+    impl MockRequestMaker {
+        fn respond(&self, response: Result<(), Error>) {
+            self.responses.lock().unwrap().push_back(response);
+        }
+
+        /// Bodies of the successful requests, without trailing newlines
+        fn bodies(&self) -> Vec<String> {
+            self.bodies
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|body| body.trim_end().to_owned())
+                .collect()
+        }
+    }
+
+    impl RequestMaker for MockRequestMaker {
+        fn write_source(
+            &self,
+            _org_id: String,
+            _bucket_id: String,
+            body: String,
+        ) -> BoxFuture<'_, Result<usize, Error>> {
+            let response = if self.fail.load(Ordering::Relaxed) {
+                Err(Error::unavailable("down"))
+            } else {
+                self.responses.lock().unwrap().pop_front().unwrap_or(Ok(()))
+            };
+
+            let len = body.len();
+            if response.is_ok() {
+                self.bodies.lock().unwrap().push(body);
+            }
+
+            async move { response.map(|()| len) }.boxed()
+        }
+    }
+}
@@ -14,7 +14,7 @@ use trace::span::Span;
 use super::{
     partition::resolver::PartitionProvider,
     post_write::PostWriteObserver,
-    table::{name_resolver::TableNameProvider, TableData},
+    table::{last_value::LastValueCacheProvider, name_resolver::TableNameProvider, TableData},
 };
 use crate::{
     arcmap::ArcMap,
@@ -76,6 +76,10 @@ pub(crate) struct NamespaceData<O> {
     /// [`PartitionData`]: super::partition::PartitionData
     partition_provider: Arc<dyn PartitionProvider>,
 
+    /// The constructor of the last value cache for new [`TableData`], if
+    /// enabled.
+    last_value_cache: Option<Arc<dyn LastValueCacheProvider>>,
+
     post_write_observer: Arc<O>,
 
     transition_shard_id: ShardId,
@@ -106,11 +110,21 @@ impl<O> NamespaceData<O> {
             table_name_resolver,
             table_count,
             partition_provider,
+            last_value_cache: None,
             post_write_observer,
             transition_shard_id,
         }
     }
 
+    /// Maintain a last value cache for each table in this namespace, built by
+    /// `provider`.
+    pub(super) fn with_last_value_cache(self, provider: Arc<dyn LastValueCacheProvider>) -> Self {
+        Self {
+            last_value_cache: Some(provider),
+            ..self
+        }
+    }
+
     /// Return the table data by ID.
     pub(crate) fn table(&self, table_id: TableId) -> Option<Arc<TableData<O>>> {
         self.tables.get(&table_id)
@@ -159,7 +173,7 @@ where
                     // TableData for it.
                     let table_data = self.tables.get_or_insert_with(&table_id, || {
                         self.table_count.inc(1);
-                        Arc::new(TableData::new(
+                        let table = TableData::new(
                             table_id,
                             self.table_name_resolver.for_table(table_id),
                             self.namespace_id,
@@ -167,7 +181,12 @@ where
                             Arc::clone(&self.partition_provider),
                             Arc::clone(&self.post_write_observer),
                             self.transition_shard_id,
-                        ))
+                        );
+                        Arc::new(match &self.last_value_cache {
+                            Some(p) => table
+                                .with_last_value_cache(p.for_table(self.namespace_id, table_id)),
+                            None => table,
+                        })
                     });
 
                     table_data
@@ -305,6 +305,7 @@ mod tests {
             sort_key: vec!["dos".to_string(), "bananas".to_string()],
             persisted_sequence_number: Default::default(),
             new_file_at: Default::default(),
+            rollup_interval_ns: Default::default(),
         };
 
         let cache = new_cache(inner, [partition]);
@@ -373,6 +374,7 @@ mod tests {
             sort_key: Default::default(),
             persisted_sequence_number: Default::default(),
             new_file_at: Default::default(),
+            rollup_interval_ns: Default::default(),
         };
 
         let cache = new_cache(inner, [partition]);
@@ -422,6 +424,7 @@ mod tests {
             sort_key: Default::default(),
             persisted_sequence_number: Default::default(),
             new_file_at: Default::default(),
+            rollup_interval_ns: Default::default(),
         };
 
         let cache = new_cache(inner, [partition]);
@@ -179,4 +179,62 @@ mod tests {
         assert_eq!(got.table_id, table_id);
         assert_eq!(got.partition_key, PartitionKey::from(PARTITION_KEY));
     }
+
+    #[tokio::test]
+    async fn test_resolver_declared_primary_key() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(iox_catalog::mem::MemCatalog::new(Arc::clone(&metrics)));
+
+        let (shard_id, namespace_id, table_id) = {
+            let mut repos = catalog.repositories().await;
+            let t = repos.topics().create_or_get("platanos").await.unwrap();
+            let q = repos.query_pools().create_or_get("platanos").await.unwrap();
+            let ns = repos
+                .namespaces()
+                .create(TABLE_NAME, None, t.id, q.id)
+                .await
+                .unwrap();
+
+            let shard = repos
+                .shards()
+                .create_or_get(&t, ShardIndex::new(0))
+                .await
+                .unwrap();
+
+            let table = repos
+                .tables()
+                .create(
+                    TABLE_NAME,
+                    ns.id,
+                    &["region".to_string(), "time".to_string()],
+                )
+                .await
+                .unwrap();
+
+            (shard.id, ns.id, table.id)
+        };
+
+        let resolver = CatalogPartitionResolver::new(Arc::clone(&catalog));
+        let got = resolver
+            .get_partition(
+                PartitionKey::from(PARTITION_KEY),
+                namespace_id,
+                Arc::new(DeferredLoad::new(Duration::from_secs(1), async {
+                    NamespaceName::from(NAMESPACE_NAME)
+                })),
+                table_id,
+                Arc::new(DeferredLoad::new(Duration::from_secs(1), async {
+                    TableName::from(TABLE_NAME)
+                })),
+                shard_id,
+            )
+            .await;
+
+        // The new partition is sorted by the declared primary key of the
+        // table, rather than a sort key derived from the data when persisting.
+        assert_matches!(got.lock().sort_key(), SortKeyState::Provided(Some(sort_key)) => {
+            assert_eq!(sort_key.to_columns().collect::<Vec<_>>(), ["region", "time"]);
+        });
+    }
 }
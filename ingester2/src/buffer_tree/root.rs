@@ -11,13 +11,18 @@ use super::{
     namespace::{name_resolver::NamespaceNameProvider, NamespaceData},
     partition::{resolver::PartitionProvider, PartitionData},
     post_write::PostWriteObserver,
-    table::name_resolver::TableNameProvider,
+    table::{last_value::LastValueCacheProvider, name_resolver::TableNameProvider},
 };
 use crate::{
     arcmap::ArcMap,
     dml_sink::DmlSink,
     partition_iter::PartitionIter,
-    query::{response::QueryResponse, tracing::QueryExecTracing, QueryError, QueryExec},
+    query::{
+        last_value::{LastValueError, LastValueQuery},
+        response::QueryResponse,
+        tracing::QueryExecTracing,
+        QueryError, QueryExec,
+    },
 };
 
 /// A [`BufferTree`] is the root of an in-memory tree of many [`NamespaceData`]
@@ -99,6 +104,9 @@ pub(crate) struct BufferTree<O> {
     /// [`TableData`]: crate::buffer_tree::table::TableData
     table_name_resolver: Arc<dyn TableNameProvider>,
 
+    /// The constructor of per-table last value caches, if enabled.
+    last_value_cache: Option<Arc<dyn LastValueCacheProvider>>,
+
     metrics: Arc<metric::Registry>,
     namespace_count: U64Counter,
 
@@ -130,6 +138,7 @@ where
             namespaces: Default::default(),
             namespace_name_resolver,
             table_name_resolver,
+            last_value_cache: None,
             metrics,
             partition_provider,
             post_write_observer,
@@ -138,6 +147,15 @@ where
         }
     }
 
+    /// Maintain a last value cache for every table in the tree, using
+    /// `provider` to initialise each cache.
+    pub(crate) fn with_last_value_cache(self, provider: Arc<dyn LastValueCacheProvider>) -> Self {
+        Self {
+            last_value_cache: Some(provider),
+            ..self
+        }
+    }
+
     /// Gets the namespace data out of the map
     pub(crate) fn namespace(&self, namespace_id: NamespaceId) -> Option<Arc<NamespaceData<O>>> {
         self.namespaces.get(&namespace_id)
@@ -178,7 +196,7 @@ where
             // buffered in this ingester instance.
             self.namespace_count.inc(1);
 
-            Arc::new(NamespaceData::new(
+            let namespace = NamespaceData::new(
                 namespace_id,
                 self.namespace_name_resolver.for_namespace(namespace_id),
                 Arc::clone(&self.table_name_resolver),
@@ -186,7 +204,11 @@ where
                 Arc::clone(&self.post_write_observer),
                 &self.metrics,
                 self.transition_shard_id,
-            ))
+            );
+            Arc::new(match &self.last_value_cache {
+                Some(p) => namespace.with_last_value_cache(Arc::clone(p)),
+                None => namespace,
+            })
         });
 
         namespace_data.apply(op).await
@@ -220,6 +242,29 @@ where
     }
 }
 
+impl<O> LastValueQuery for BufferTree<O>
+where
+    O: Send + Sync + Debug,
+{
+    fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        columns: Vec<String>,
+    ) -> Result<QueryResponse, LastValueError> {
+        let table = self
+            .namespace(namespace_id)
+            .ok_or(QueryError::NamespaceNotFound(namespace_id))?
+            .table(table_id)
+            .ok_or(QueryError::TableNotFound(namespace_id, table_id))?;
+
+        table
+            .last_values(&columns)
+            .map(QueryResponse::new)
+            .ok_or(LastValueError::NotCovered(namespace_id, table_id))
+    }
+}
+
 impl<O> PartitionIter for crate::buffer_tree::BufferTree<O>
 where
     O: Send + Sync + Debug + 'static,
@@ -247,7 +292,10 @@ mod tests {
             },
             partition::{resolver::mock::MockPartitionProvider, PartitionData, SortKeyState},
             post_write::mock::MockPostWriteObserver,
-            table::{name_resolver::mock::MockTableNameProvider, TableName},
+            table::{
+                last_value::mock::MockLastValueCacheProvider,
+                name_resolver::mock::MockTableNameProvider, TableName,
+            },
         },
         deferred_load::{self, DeferredLoad},
         query::partition_response::PartitionResponse,
@@ -1076,4 +1124,74 @@ mod tests {
             &batches
         );
     }
+
+    /// Ensure the last value cache of each table reflects the most recent
+    /// write to each series.
+    #[tokio::test]
+    async fn test_last_values() {
+        let partition_provider = Arc::new(MockPartitionProvider::default().with_partition(
+            PartitionData::new(
+                PartitionId::new(0),
+                PartitionKey::from("p1"),
+                NAMESPACE_ID,
+                Arc::new(DeferredLoad::new(Duration::from_secs(1), async {
+                    NamespaceName::from(NAMESPACE_NAME)
+                })),
+                TABLE_ID,
+                Arc::new(DeferredLoad::new(Duration::from_secs(1), async {
+                    TableName::from(TABLE_NAME)
+                })),
+                SortKeyState::Provided(None),
+                TRANSITION_SHARD_ID,
+            ),
+        ));
+
+        let buf = BufferTree::new(
+            Arc::new(MockNamespaceNameProvider::default()),
+            Arc::new(MockTableNameProvider::new(TABLE_NAME)),
+            partition_provider,
+            Arc::new(MockPostWriteObserver::default()),
+            Arc::new(metric::Registry::default()),
+            TRANSITION_SHARD_ID,
+        )
+        .with_last_value_cache(Arc::new(MockLastValueCacheProvider::default()));
+
+        assert_matches!(
+            buf.query_last_values(NAMESPACE_ID, TABLE_ID, vec![]),
+            Err(LastValueError::Query(QueryError::NamespaceNotFound(_)))
+        );
+
+        buf.apply(DmlOperation::Write(make_write_op(
+            &PartitionKey::from("p1"),
+            NAMESPACE_ID,
+            TABLE_NAME,
+            TABLE_ID,
+            0,
+            "bananas,region=Madrid temp=35 4242424242\n\
+             bananas,region=Madrid temp=30 4242424243\n\
+             bananas,region=Murcia temp=20 4242424242",
+        )))
+        .await
+        .expect("failed to write data");
+
+        let batches = buf
+            .query_last_values(NAMESPACE_ID, TABLE_ID, vec![])
+            .expect("query should succeed")
+            .into_record_batches()
+            .try_collect::<Vec<_>>()
+            .await
+            .expect("query failed");
+
+        assert_batches_sorted_eq!(
+            [
+                "+--------+------+-------------------------------+",
+                "| region | temp | time                          |",
+                "+--------+------+-------------------------------+",
+                "| Madrid | 30.0 | 1970-01-01T00:00:04.242424243 |",
+                "| Murcia | 20.0 | 1970-01-01T00:00:04.242424242 |",
+                "+--------+------+-------------------------------+",
+            ],
+            &batches
+        );
+    }
 }
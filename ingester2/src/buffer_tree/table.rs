@@ -1,5 +1,6 @@
 //! Table level data buffer structures.
 
+pub(crate) mod last_value;
 pub(crate) mod name_resolver;
 
 use std::{fmt::Debug, sync::Arc};
@@ -12,6 +13,7 @@ use parking_lot::Mutex;
 use schema::Projection;
 use trace::span::{Span, SpanRecorder};
 
+use self::last_value::LastValueCache;
 use super::{
     namespace::NamespaceName,
     partition::{resolver::PartitionProvider, PartitionData},
@@ -83,6 +85,9 @@ pub(crate) struct TableData<O> {
     // Map of partition key to its data
     partition_data: ArcMap<PartitionKey, Mutex<PartitionData>>,
 
+    /// The most recent value of each field per series, if enabled.
+    last_value_cache: Option<Arc<LastValueCache>>,
+
     post_write_observer: Arc<O>,
     transition_shard_id: ShardId,
 }
@@ -109,11 +114,21 @@ impl<O> TableData<O> {
             namespace_name,
             partition_data: Default::default(),
             partition_provider,
+            last_value_cache: None,
             post_write_observer,
             transition_shard_id,
         }
     }
 
+    /// Maintain the most recent value of each field per series in `cache` as
+    /// writes are buffered.
+    pub(super) fn with_last_value_cache(self, cache: Arc<LastValueCache>) -> Self {
+        Self {
+            last_value_cache: Some(cache),
+            ..self
+        }
+    }
+
     /// Return a mutable reference to all partitions buffered for this table.
     ///
     /// # Ordering
@@ -145,6 +160,24 @@ impl<O> TableData<O> {
     pub(crate) fn namespace_id(&self) -> NamespaceId {
         self.namespace_id
     }
+
+    /// Return the most recent value of each field for each series in this
+    /// table, projected to `columns` (or all columns if empty).
+    ///
+    /// Returns [`None`] if the last value cache is disabled, or does not (yet)
+    /// cover all the data in the table.
+    pub(crate) fn last_values(&self, columns: &[String]) -> Option<PartitionStream> {
+        let partitions = self.last_value_cache.as_ref()?.snapshot(columns)?;
+
+        // The querier does not use the persistence count of these responses
+        // to select Parquet files, as the cached values already cover them.
+        let partitions = partitions.into_iter().map(|(id, batch)| {
+            let data = Box::pin(MemoryStream::new(vec![batch]));
+            PartitionResponse::new(Some(data), id, 0)
+        });
+
+        Some(PartitionStream::new(futures::stream::iter(partitions)))
+    }
 }
 
 impl<O> TableData<O>
@@ -182,12 +215,27 @@ where
             }
         };
 
+        // Convert the write before it is moved into the partition buffer, so
+        // that the last value cache can be updated once it is applied.
+        let last_value_update = match &self.last_value_cache {
+            Some(cache) => Some((
+                cache,
+                batch.schema(Projection::All)?,
+                batch.to_arrow(Projection::All)?,
+            )),
+            None => None,
+        };
+
         // Obtain the partition lock.
         let mut p = partition_data.lock();
 
         // Enqueue the write, returning any error.
         p.buffer_write(batch, sequence_number)?;
 
+        if let Some((cache, schema, batch)) = last_value_update {
+            cache.observe_write(p.partition_id(), &schema, &batch);
+        }
+
         // If successful, allow the observer to inspect the partition.
         self.post_write_observer
             .observe(Arc::clone(&partition_data), p);
@@ -0,0 +1,508 @@
+//! A per-table cache of the most recent value of each field, for each series.
+
+pub(crate) mod seeder;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    sync::Arc,
+};
+
+use arrow::{
+    array::{as_string_array, Array, ArrayRef, StringArray, TimestampNanosecondArray},
+    compute::cast,
+    datatypes::DataType,
+    record_batch::RecordBatch,
+};
+use data_types::{NamespaceId, PartitionId, TableId};
+use datafusion::{error::DataFusionError, scalar::ScalarValue};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use schema::{builder::SchemaBuilder, InfluxColumnType, Schema, TIME_COLUMN_NAME};
+
+/// The default upper bound on the number of series tracked by a single
+/// [`LastValueCache`].
+pub(crate) const DEFAULT_MAX_SERIES: usize = 100_000;
+
+/// An abstract constructor of [`LastValueCache`] instances for a given table.
+pub(crate) trait LastValueCacheProvider: Send + Sync + Debug {
+    fn for_table(&self, namespace_id: NamespaceId, table_id: TableId) -> Arc<LastValueCache>;
+}
+
+/// The ordered set of `(tag name, tag value)` pairs identifying a series.
+type SeriesKey = Vec<(Arc<str>, Arc<str>)>;
+
+/// The most recent observed value of a single field in a series.
+#[derive(Debug)]
+struct LastValue {
+    time: i64,
+    partition_id: PartitionId,
+    value: ScalarValue,
+}
+
+/// How to resolve two values for the same field with the same timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ties {
+    /// The value being observed replaces the cached value (later writes win).
+    Replace,
+    /// The cached value is kept (values seeded from older data never replace
+    /// data observed in the write path).
+    Keep,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The type of every column observed for this table.
+    columns: BTreeMap<Arc<str>, InfluxColumnType>,
+
+    /// The most recent value of each field, keyed by series.
+    series: HashMap<SeriesKey, HashMap<Arc<str>, LastValue>>,
+
+    /// True once all data persisted for the table before this cache was
+    /// created has been observed.
+    seeded: bool,
+
+    /// True if the cache stopped tracking values, either because it reached
+    /// the configured series limit or failed to process an observation.
+    disabled: bool,
+}
+
+/// A cache of the most recent value of each field, for each series in a table.
+///
+/// The cache is populated from two sources: writes buffered by the
+/// [`TableData`] that owns it, and the Parquet files persisted for the table
+/// prior to the cache being initialised (see [`seeder`]).
+///
+/// A snapshot of the cache is only made available once seeding has completed,
+/// as until then it may be missing series or hold stale values. If the number
+/// of series exceeds the configured limit, the cache is cleared and disabled
+/// for the lifetime of the table buffer - it never returns partial results.
+///
+/// Values are never evicted, including values that have since aged out of the
+/// namespace retention period - callers are expected to apply the retention
+/// bound to the returned rows.
+///
+/// [`TableData`]: super::TableData
+#[derive(Debug)]
+pub(crate) struct LastValueCache {
+    max_series: usize,
+    state: Mutex<State>,
+}
+
+impl LastValueCache {
+    /// Initialise an empty, unseeded cache tracking at most `max_series`.
+    pub(crate) fn new(max_series: usize) -> Self {
+        Self {
+            max_series,
+            state: Default::default(),
+        }
+    }
+
+    /// Update the cache with the rows in `batch`, which was buffered in the
+    /// write path for `partition_id`.
+    ///
+    /// Values in `batch` replace cached values with the same timestamp.
+    pub(crate) fn observe_write(
+        &self,
+        partition_id: PartitionId,
+        schema: &Schema,
+        batch: &RecordBatch,
+    ) {
+        self.observe(partition_id, schema, batch, Ties::Replace)
+    }
+
+    /// Update the cache with the rows in `batch`, which was read from a Parquet
+    /// file persisted for `partition_id`.
+    ///
+    /// Values in `batch` never replace cached values with the same timestamp.
+    pub(crate) fn observe_persisted(
+        &self,
+        partition_id: PartitionId,
+        schema: &Schema,
+        batch: &RecordBatch,
+    ) {
+        self.observe(partition_id, schema, batch, Ties::Keep)
+    }
+
+    /// Mark all data persisted prior to the cache initialisation as observed.
+    pub(crate) fn mark_seeded(&self) {
+        self.state.lock().seeded = true;
+    }
+
+    /// Returns true if the cache holds the most recent value of every series
+    /// in the table.
+    pub(crate) fn is_complete(&self) -> bool {
+        let state = self.state.lock();
+        state.seeded && !state.disabled
+    }
+
+    fn observe(&self, partition_id: PartitionId, schema: &Schema, batch: &RecordBatch, ties: Ties) {
+        let mut state = self.state.lock();
+        if state.disabled {
+            return;
+        }
+
+        if let Err(e) = state.observe(partition_id, schema, batch, ties, self.max_series) {
+            warn!(error=%e, "failed to update last value cache, disabling cache");
+            state.disable();
+        }
+    }
+
+    /// Return the cached values as one [`RecordBatch`] per partition, projected
+    /// to `columns` (or all columns, if empty).
+    ///
+    /// Each series produces a row for every distinct timestamp of its cached
+    /// field values, with each field set in the row carrying its most recent
+    /// timestamp (and NULL in all others).
+    ///
+    /// Returns [`None`] if the cache is not complete.
+    pub(crate) fn snapshot(&self, columns: &[String]) -> Option<Vec<(PartitionId, RecordBatch)>> {
+        let state = self.state.lock();
+        if !state.seeded || state.disabled {
+            return None;
+        }
+
+        match state.snapshot(columns) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                warn!(error=%e, "failed to build last value cache snapshot");
+                None
+            }
+        }
+    }
+}
+
+impl State {
+    fn observe(
+        &mut self,
+        partition_id: PartitionId,
+        schema: &Schema,
+        batch: &RecordBatch,
+        ties: Ties,
+        max_series: usize,
+    ) -> Result<(), DataFusionError> {
+        let Some(time) = batch.column_by_name(TIME_COLUMN_NAME) else {
+            return Ok(());
+        };
+        let time = time
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .ok_or_else(|| DataFusionError::Internal("unexpected time column type".to_string()))?;
+
+        let mut tags = Vec::new();
+        let mut fields = Vec::new();
+        for (column_type, field) in schema.iter() {
+            let Some(array) = batch.column_by_name(field.name()) else {
+                continue;
+            };
+            let name: Arc<str> = Arc::from(field.name().as_str());
+
+            match column_type {
+                InfluxColumnType::Tag => {
+                    tags.push((Arc::clone(&name), cast(array, &DataType::Utf8)?));
+                }
+                InfluxColumnType::Field(_) => fields.push((Arc::clone(&name), Arc::clone(array))),
+                InfluxColumnType::Timestamp => {}
+            }
+            self.columns.entry(name).or_insert(column_type);
+        }
+
+        // Series keys are ordered by tag name.
+        tags.sort_by(|a, b| a.0.cmp(&b.0));
+        let tags: Vec<(&Arc<str>, &StringArray)> = tags
+            .iter()
+            .map(|(name, array)| (name, as_string_array(array)))
+            .collect();
+
+        for row in 0..batch.num_rows() {
+            let key: SeriesKey = tags
+                .iter()
+                .filter(|(_, values)| values.is_valid(row))
+                .map(|(name, values)| (Arc::clone(name), Arc::from(values.value(row))))
+                .collect();
+            let t = time.value(row);
+
+            if !self.series.contains_key(&key) && self.series.len() >= max_series {
+                warn!(
+                    max_series,
+                    "last value cache series limit reached, disabling cache"
+                );
+                self.disable();
+                return Ok(());
+            }
+
+            let values = self.series.entry(key).or_default();
+            for (name, array) in &fields {
+                if array.is_null(row) {
+                    continue;
+                }
+
+                match values.get(name) {
+                    Some(v) if v.time > t || (v.time == t && ties == Ties::Keep) => continue,
+                    _ => {}
+                }
+
+                values.insert(
+                    Arc::clone(name),
+                    LastValue {
+                        time: t,
+                        partition_id,
+                        value: ScalarValue::try_from_array(array, row)?,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn disable(&mut self) {
+        self.disabled = true;
+        self.series = Default::default();
+    }
+
+    fn snapshot(
+        &self,
+        columns: &[String],
+    ) -> Result<Vec<(PartitionId, RecordBatch)>, DataFusionError> {
+        let mut builder = SchemaBuilder::new();
+        for (name, column_type) in &self.columns {
+            if *column_type == InfluxColumnType::Timestamp
+                || columns.is_empty()
+                || columns.iter().any(|c| c == name.as_ref())
+            {
+                builder.influx_column(name, *column_type);
+            }
+        }
+        let schema = builder
+            .build()
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        // Group the fields of each series by timestamp, and the resulting rows
+        // by partition.
+        let mut rows: BTreeMap<PartitionId, Vec<Row<'_>>> = BTreeMap::new();
+        for (key, values) in &self.series {
+            let mut by_time: BTreeMap<i64, Row<'_>> = BTreeMap::new();
+            for (name, v) in values {
+                by_time
+                    .entry(v.time)
+                    .or_insert_with(|| Row {
+                        key,
+                        time: v.time,
+                        partition_id: v.partition_id,
+                        fields: HashMap::new(),
+                    })
+                    .fields
+                    .insert(name.as_ref(), &v.value);
+            }
+            for row in by_time.into_values() {
+                rows.entry(row.partition_id).or_default().push(row);
+            }
+        }
+
+        rows.into_iter()
+            .map(|(partition_id, rows)| Ok((partition_id, build_batch(&schema, &rows)?)))
+            .collect()
+    }
+}
+
+/// A single output row of a cache snapshot.
+#[derive(Debug)]
+struct Row<'a> {
+    key: &'a SeriesKey,
+    time: i64,
+    partition_id: PartitionId,
+    fields: HashMap<&'a str, &'a ScalarValue>,
+}
+
+impl<'a> Row<'a> {
+    fn tag(&self, name: &str) -> Option<&'a str> {
+        self.key
+            .binary_search_by(|(k, _)| k.as_ref().cmp(name))
+            .ok()
+            .map(|idx| self.key[idx].1.as_ref())
+    }
+}
+
+fn build_batch(schema: &Schema, rows: &[Row<'_>]) -> Result<RecordBatch, DataFusionError> {
+    let arrays = schema
+        .iter()
+        .map(|(column_type, field)| {
+            let name = field.name().as_str();
+            let array: ArrayRef = match column_type {
+                InfluxColumnType::Tag => {
+                    let values: StringArray = rows.iter().map(|r| r.tag(name)).collect();
+                    cast(&(Arc::new(values) as ArrayRef), field.data_type())?
+                }
+                InfluxColumnType::Field(_) => {
+                    let null = ScalarValue::try_from(field.data_type())?;
+                    ScalarValue::iter_to_array(rows.iter().map(|r| {
+                        r.fields
+                            .get(name)
+                            .map(|v| (*v).clone())
+                            .unwrap_or_else(|| null.clone())
+                    }))?
+                }
+                InfluxColumnType::Timestamp => Arc::new(TimestampNanosecondArray::from(
+                    rows.iter().map(|r| r.time).collect::<Vec<_>>(),
+                )),
+            };
+            Ok(array)
+        })
+        .collect::<Result<Vec<_>, DataFusionError>>()?;
+
+    Ok(RecordBatch::try_new(schema.as_arrow(), arrays)?)
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+
+    /// A [`LastValueCacheProvider`] of caches that require no seeding.
+    #[derive(Debug, Default)]
+    pub(crate) struct MockLastValueCacheProvider;
+
+    impl LastValueCacheProvider for MockLastValueCacheProvider {
+        fn for_table(&self, _namespace_id: NamespaceId, _table_id: TableId) -> Arc<LastValueCache> {
+            let cache = LastValueCache::new(DEFAULT_MAX_SERIES);
+            cache.mark_seeded();
+            Arc::new(cache)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_sorted_eq;
+    use mutable_batch_lp::lines_to_batches;
+    use schema::Projection;
+
+    use super::*;
+
+    const PARTITION_ID: PartitionId = PartitionId::new(1);
+
+    fn observe(cache: &LastValueCache, lp: &str, ties: Ties) {
+        let batch = lines_to_batches(lp, 0)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .1;
+        let schema = batch.schema(Projection::All).unwrap();
+        let batch = batch.to_arrow(Projection::All).unwrap();
+        match ties {
+            Ties::Replace => cache.observe_write(PARTITION_ID, &schema, &batch),
+            Ties::Keep => cache.observe_persisted(PARTITION_ID, &schema, &batch),
+        }
+    }
+
+    fn snapshot(cache: &LastValueCache) -> Vec<RecordBatch> {
+        cache
+            .snapshot(&[])
+            .expect("cache should be complete")
+            .into_iter()
+            .map(|(_, b)| b)
+            .collect()
+    }
+
+    #[test]
+    fn test_last_value_per_series() {
+        let cache = LastValueCache::new(DEFAULT_MAX_SERIES);
+        observe(
+            &cache,
+            "cpu,host=a usage=1,idle=9 1\n\
+             cpu,host=a usage=2 2\n\
+             cpu,host=b usage=3,idle=7 1\n\
+             cpu,host=b,region=eu usage=4 5",
+            Ties::Replace,
+        );
+
+        // Not yet seeded.
+        assert!(cache.snapshot(&[]).is_none());
+        assert!(!cache.is_complete());
+
+        cache.mark_seeded();
+        assert!(cache.is_complete());
+
+        assert_batches_sorted_eq!(
+            [
+                "+------+------+--------+-------------------------------+-------+",
+                "| host | idle | region | time                          | usage |",
+                "+------+------+--------+-------------------------------+-------+",
+                "| a    |      |        | 1970-01-01T00:00:00.000000002 | 2.0   |",
+                "| a    | 9.0  |        | 1970-01-01T00:00:00.000000001 |       |",
+                "| b    | 7.0  |        | 1970-01-01T00:00:00.000000001 | 3.0   |",
+                "| b    |      | eu     | 1970-01-01T00:00:00.000000005 | 4.0   |",
+                "+------+------+--------+-------------------------------+-------+",
+            ],
+            &snapshot(&cache)
+        );
+    }
+
+    #[test]
+    fn test_seeded_values_do_not_replace_writes() {
+        let cache = LastValueCache::new(DEFAULT_MAX_SERIES);
+        observe(&cache, "cpu,host=a usage=2 10", Ties::Replace);
+
+        // Older and same-timestamp persisted values are ignored, newer values
+        // are applied.
+        observe(
+            &cache,
+            "cpu,host=a usage=1 5\n\
+             cpu,host=a usage=42 10\n\
+             cpu,host=b usage=3 1",
+            Ties::Keep,
+        );
+        cache.mark_seeded();
+
+        // A subsequent write with the same timestamp wins.
+        observe(&cache, "cpu,host=b usage=4 1", Ties::Replace);
+
+        assert_batches_sorted_eq!(
+            [
+                "+------+-------------------------------+-------+",
+                "| host | time                          | usage |",
+                "+------+-------------------------------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000010 | 2.0   |",
+                "| b    | 1970-01-01T00:00:00.000000001 | 4.0   |",
+                "+------+-------------------------------+-------+",
+            ],
+            &snapshot(&cache)
+        );
+
+        // Projection keeps the timestamp column.
+        let batches = cache.snapshot(&["usage".to_string()]).unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+-------------------------------+-------+",
+                "| time                          | usage |",
+                "+-------------------------------+-------+",
+                "| 1970-01-01T00:00:00.000000001 | 4.0   |",
+                "| 1970-01-01T00:00:00.000000010 | 2.0   |",
+                "+-------------------------------+-------+",
+            ],
+            &batches.into_iter().map(|(_, b)| b).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_series_limit() {
+        let cache = LastValueCache::new(2);
+        cache.mark_seeded();
+
+        observe(
+            &cache,
+            "cpu,host=a usage=1 1\ncpu,host=b usage=1 1",
+            Ties::Replace,
+        );
+        assert!(cache.is_complete());
+
+        observe(&cache, "cpu,host=c usage=1 1", Ties::Replace);
+        assert!(!cache.is_complete());
+        assert!(cache.snapshot(&[]).is_none());
+
+        // The cache stays disabled.
+        observe(&cache, "cpu,host=a usage=1 2", Ties::Replace);
+        assert!(cache.snapshot(&[]).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+//! Seeding of [`LastValueCache`] instances from persisted Parquet files.
+
+use std::{collections::HashMap, sync::Arc};
+
+use backoff::{Backoff, BackoffConfig};
+use data_types::{ColumnId, NamespaceId, TableId};
+use datafusion::{error::DataFusionError, prelude::SessionContext};
+use datafusion_util::config::{iox_session_config, register_iox_object_store};
+use iox_catalog::interface::{get_table_schema_by_id, Catalog};
+use observability_deps::tracing::*;
+use parquet_file::{storage::ParquetStorage, ParquetFilePath};
+use schema::{Projection, Schema};
+use thiserror::Error;
+
+use super::{LastValueCache, LastValueCacheProvider};
+
+/// Errors encountered while seeding a [`LastValueCache`].
+#[derive(Debug, Error)]
+enum SeedError {
+    #[error("catalog error: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[error("invalid table schema: {0}")]
+    Schema(#[from] schema::builder::Error),
+
+    #[error("invalid parquet file schema: {0}")]
+    FileSchema(#[from] schema::Error),
+
+    #[error("failed to read parquet file: {0}")]
+    Read(#[from] DataFusionError),
+}
+
+/// A [`LastValueCacheProvider`] that initialises caches with the most recent
+/// values found in the Parquet files persisted for the table.
+///
+/// Seeding happens asynchronously in a background task, and the returned
+/// cache does not report itself as complete until it has finished. Writes
+/// observed by the cache while seeding is in progress always take precedence
+/// over values read from Parquet files.
+#[derive(Debug)]
+pub(crate) struct ParquetLastValueSeeder {
+    catalog: Arc<dyn Catalog>,
+    store: ParquetStorage,
+    max_series: usize,
+    backoff_config: BackoffConfig,
+}
+
+impl ParquetLastValueSeeder {
+    pub(crate) fn new(
+        catalog: Arc<dyn Catalog>,
+        store: ParquetStorage,
+        max_series: usize,
+        backoff_config: BackoffConfig,
+    ) -> Self {
+        Self {
+            catalog,
+            store,
+            max_series,
+            backoff_config,
+        }
+    }
+}
+
+impl LastValueCacheProvider for ParquetLastValueSeeder {
+    fn for_table(&self, namespace_id: NamespaceId, table_id: TableId) -> Arc<LastValueCache> {
+        let cache = Arc::new(LastValueCache::new(self.max_series));
+
+        tokio::spawn(seed(
+            table_id,
+            Arc::clone(&cache),
+            Arc::clone(&self.catalog),
+            self.store.clone(),
+            self.backoff_config.clone(),
+        ));
+
+        debug!(%namespace_id, %table_id, "seeding last value cache");
+
+        cache
+    }
+}
+
+/// Populate `cache` from all the Parquet files of `table_id`, retrying until
+/// successful.
+async fn seed(
+    table_id: TableId,
+    cache: Arc<LastValueCache>,
+    catalog: Arc<dyn Catalog>,
+    store: ParquetStorage,
+    backoff_config: BackoffConfig,
+) {
+    // Seeding is idempotent - values already observed are never replaced by
+    // the same value, so a failed attempt (for example, because a file was
+    // compacted away after listing it) is simply started over.
+    let n_files = Backoff::new(&backoff_config)
+        .retry_all_errors("seed last value cache", || {
+            seed_once(table_id, &cache, Arc::clone(&catalog), &store)
+        })
+        .await
+        .expect("retry forever");
+
+    cache.mark_seeded();
+
+    info!(%table_id, n_files, "last value cache seeded");
+}
+
+async fn seed_once(
+    table_id: TableId,
+    cache: &LastValueCache,
+    catalog: Arc<dyn Catalog>,
+    store: &ParquetStorage,
+) -> Result<usize, SeedError> {
+    let (table_schema, mut files) = {
+        let mut repos = catalog.repositories().await;
+        let table_schema = get_table_schema_by_id(table_id, repos.as_mut()).await?;
+        let files = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table_id)
+            .await?;
+        (table_schema, files)
+    };
+
+    let column_names: HashMap<ColumnId, String> = table_schema
+        .columns
+        .iter()
+        .map(|(name, c)| (c.id, name.clone()))
+        .collect();
+    let schema = Schema::try_from(table_schema)?;
+
+    // Newest files first, so that the most recently written of two values
+    // with the same timestamp is the one retained.
+    files.sort_by(|a, b| b.max_l0_created_at.cmp(&a.max_l0_created_at));
+
+    let session_ctx = SessionContext::with_config(iox_session_config());
+    register_iox_object_store(
+        session_ctx.runtime_env(),
+        store.id(),
+        Arc::clone(store.object_store()),
+    );
+
+    for file in &files {
+        let names: Vec<&str> = file
+            .column_set
+            .iter()
+            .filter_map(|id| column_names.get(id).map(String::as_str))
+            .collect();
+        let file_schema = schema.select_by_names(&names)?;
+
+        let batches = store
+            .parquet_exec_input(&ParquetFilePath::from(file), file.file_size_bytes as usize)
+            .read_to_batches(file_schema.as_arrow(), Projection::All, &session_ctx)
+            .await?;
+
+        for batch in &batches {
+            cache.observe_persisted(file.partition_id, &file_schema, batch);
+        }
+    }
+
+    Ok(files.len())
+}
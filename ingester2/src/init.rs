@@ -29,7 +29,10 @@ use crate::{
         partition::resolver::{
             CatalogPartitionResolver, CoalescePartitionResolver, PartitionCache, PartitionProvider,
         },
-        table::name_resolver::{TableNameProvider, TableNameResolver},
+        table::{
+            last_value::seeder::ParquetLastValueSeeder,
+            name_resolver::{TableNameProvider, TableNameResolver},
+        },
         BufferTree,
     },
     dml_sink::{instrumentation::DmlSinkInstrumentation, tracing::DmlSinkTracing},
@@ -217,6 +220,17 @@ pub enum InitError {
 /// Decreasing this value increases the frequency of persist operations, and
 /// usually decreases the size of the resulting parquet files.
 ///
+/// ## Last Value Cache
+///
+/// If `last_value_cache_max_series` is provided, each table maintains a cache
+/// of the most recent value of every field for each series (tracking at most
+/// the specified number of series per table). The cache is populated by the
+/// write path (including WAL replay) and seeded from the Parquet files
+/// persisted for the table in a background task.
+///
+/// Once seeded, the cache is used to answer "last value" queries from the
+/// querier without scanning the buffered data or object storage.
+///
 /// [`MutableBatch::size_data()`]: mutable_batch::MutableBatch::size_data
 #[allow(clippy::too_many_arguments)]
 pub async fn new<F>(
@@ -230,6 +244,7 @@ pub async fn new<F>(
     persist_queue_depth: usize,
     persist_hot_partition_cost: usize,
     object_store: ParquetStorage,
+    last_value_cache_max_series: Option<usize>,
     shutdown: F,
 ) -> Result<IngesterGuard<impl IngesterRpcInterface>, InitError>
 where
@@ -308,7 +323,7 @@ where
         persist_queue_depth,
         Arc::clone(&ingest_state),
         persist_executor,
-        object_store,
+        object_store.clone(),
         Arc::clone(&catalog),
         NopObserver::default(),
         &metrics,
@@ -330,14 +345,27 @@ where
         &metrics,
     );
 
-    let buffer = Arc::new(BufferTree::new(
+    let buffer = BufferTree::new(
         namespace_name_provider,
         table_name_provider,
         partition_provider,
         Arc::new(hot_partition_persister),
         Arc::clone(&metrics),
         transition_shard.id,
-    ));
+    );
+
+    // Optionally maintain a per-table cache of the most recent values, seeded
+    // from the persisted Parquet files.
+    let buffer = match last_value_cache_max_series {
+        Some(max_series) => buffer.with_last_value_cache(Arc::new(ParquetLastValueSeeder::new(
+            Arc::clone(&catalog),
+            object_store,
+            max_series,
+            BackoffConfig::default(),
+        ))),
+        None => buffer,
+    };
+    let buffer = Arc::new(buffer);
 
     // Initialise the WAL
     let wal = Wal::new(wal_directory).await.map_err(InitError::WalInit)?;
@@ -0,0 +1,42 @@
+//! Queries answered from per-table last value caches.
+
+use std::{fmt::Debug, sync::Arc};
+
+use data_types::{NamespaceId, TableId};
+use thiserror::Error;
+
+use super::{response::QueryResponse, QueryError};
+
+#[derive(Debug, Error)]
+pub(crate) enum LastValueError {
+    #[error(transparent)]
+    Query(#[from] QueryError),
+
+    #[error("last value cache does not cover table id {1} in namespace id {0}")]
+    NotCovered(NamespaceId, TableId),
+}
+
+/// Return the most recent value of each field for each series in a table,
+/// answered from the table's last value cache.
+pub(crate) trait LastValueQuery: Send + Sync + Debug {
+    fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        columns: Vec<String>,
+    ) -> Result<QueryResponse, LastValueError>;
+}
+
+impl<T> LastValueQuery for Arc<T>
+where
+    T: LastValueQuery,
+{
+    fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        columns: Vec<String>,
+    ) -> Result<QueryResponse, LastValueError> {
+        (**self).query_last_values(namespace_id, table_id, columns)
+    }
+}
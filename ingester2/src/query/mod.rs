@@ -3,6 +3,9 @@
 mod r#trait;
 pub(crate) use r#trait::*;
 
+// Last value cache queries
+pub(crate) mod last_value;
+
 // Response types
 pub(crate) mod partition_response;
 pub(crate) mod response;
@@ -16,7 +16,7 @@ use crate::{
     init::IngesterRpcInterface,
     partition_iter::PartitionIter,
     persist::queue::PersistQueue,
-    query::{response::QueryResponse, QueryExec},
+    query::{last_value::LastValueQuery, response::QueryResponse, QueryExec},
     timestamp_oracle::TimestampOracle,
 };
 
@@ -44,7 +44,7 @@ impl<D, Q, T, P> GrpcDelegate<D, Q, T, P>
 where
     D: DmlSink + 'static,
     Q: QueryExec<Response = QueryResponse> + 'static,
-    T: PartitionIter + Sync + 'static,
+    T: PartitionIter + LastValueQuery + 'static,
     P: PersistQueue + Sync + 'static,
 {
     /// Initialise a new [`GrpcDelegate`].
@@ -80,7 +80,7 @@ impl<D, Q, T, P> IngesterRpcInterface for GrpcDelegate<D, Q, T, P>
 where
     D: DmlSink + 'static,
     Q: QueryExec<Response = QueryResponse> + 'static,
-    T: PartitionIter + Sync + 'static,
+    T: PartitionIter + LastValueQuery + 'static,
     P: PersistQueue + Sync + 'static,
 {
     type CatalogHandler = CatalogService;
@@ -127,5 +127,6 @@ where
             max_simultaneous_requests,
             &self.metrics,
         )
+        .with_last_values(Arc::clone(&self.buffer) as _)
     }
 }
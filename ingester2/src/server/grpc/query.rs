@@ -1,4 +1,4 @@
-use std::pin::Pin;
+use std::{pin::Pin, sync::Arc};
 
 use arrow_flight::{
     encode::FlightDataEncoderBuilder, error::FlightError,
@@ -20,7 +20,11 @@ use trace::{ctx::SpanContext, span::SpanExt};
 
 use crate::{
     ingester_id::IngesterId,
-    query::{response::QueryResponse, QueryError, QueryExec},
+    query::{
+        last_value::{LastValueError, LastValueQuery},
+        response::QueryResponse,
+        QueryError, QueryExec,
+    },
 };
 
 /// Error states for the query RPC handler.
@@ -57,6 +61,16 @@ impl From<QueryError> for tonic::Status {
     }
 }
 
+/// Map a last value query error into a [`tonic::Status`].
+impl From<LastValueError> for tonic::Status {
+    fn from(e: LastValueError) -> Self {
+        match e {
+            LastValueError::Query(e) => e.into(),
+            LastValueError::NotCovered(_, _) => Self::failed_precondition(e.to_string()),
+        }
+    }
+}
+
 /// Map a gRPC handler error to a [`tonic::Status`].
 impl From<Error> for tonic::Status {
     fn from(e: Error) -> Self {
@@ -82,6 +96,10 @@ impl From<Error> for tonic::Status {
 pub(crate) struct FlightService<Q> {
     query_handler: Q,
 
+    /// The handler of requests for the most recent values of a table, if
+    /// supported.
+    last_value_handler: Option<Arc<dyn LastValueQuery>>,
+
     /// A request limiter to restrict the number of simultaneous requests this
     /// ingester services.
     ///
@@ -112,11 +130,20 @@ impl<Q> FlightService<Q> {
 
         Self {
             query_handler,
+            last_value_handler: None,
             request_sem: Semaphore::new(max_simultaneous_requests),
             query_request_limit_rejected,
             ingester_id,
         }
     }
+
+    /// Answer requests for the most recent values of a table using `handler`.
+    pub(super) fn with_last_values(self, handler: Arc<dyn LastValueQuery>) -> Self {
+        Self {
+            last_value_handler: Some(handler),
+            ..self
+        }
+    }
 }
 
 type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send + 'static>>;
@@ -177,6 +204,27 @@ where
             warn!(predicate=?p, "ignoring query predicate (unsupported)");
         }
 
+        if request.last_values {
+            let response = self
+                .last_value_handler
+                .as_ref()
+                .ok_or(LastValueError::NotCovered(namespace_id, table_id))
+                .and_then(|h| h.query_last_values(namespace_id, table_id, request.columns))
+                .map_err(|e| {
+                    debug!(
+                        error=%e,
+                        %namespace_id,
+                        %table_id,
+                        "last value query error"
+                    );
+                    e
+                })?;
+
+            let output = encode_response(response, self.ingester_id).map_err(tonic::Status::from);
+
+            return Ok(Response::new(Box::pin(output) as Self::DoGetStream));
+        }
+
         let response = match self
             .query_handler
             .query_exec(namespace_id, table_id, request.columns, span)
@@ -375,4 +423,32 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn last_values_without_cache() {
+        let flight = FlightService::new(
+            MockQueryExec::default(),
+            IngesterId::new(),
+            100,
+            &metric::Registry::default(),
+        );
+
+        let req = tonic::Request::new(Ticket {
+            ticket: proto::IngesterQueryRequest {
+                namespace_id: 42,
+                table_id: 24,
+                columns: vec![],
+                predicate: None,
+                last_values: true,
+            }
+            .encode_to_vec()
+            .into(),
+        });
+        match flight.do_get(req).await {
+            Ok(_) => panic!("expected error because no last value cache exists"),
+            Err(s) => {
+                assert_eq!(s.code(), Code::FailedPrecondition);
+            }
+        }
+    }
 }
@@ -50,6 +50,7 @@ async fn write_query() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -144,6 +145,7 @@ async fn wal_replay() {
                 table_id: ctx.table_id(namespace_name, "bananas").await.get(),
                 columns: vec![],
                 predicate: None,
+                last_values: false,
             })
             .await
             .expect("query request failed");
@@ -239,6 +241,7 @@ async fn graceful_shutdown() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -160,6 +160,7 @@ impl TestContextBuilder {
             max_persist_queue_depth,
             persist_hot_partition_cost,
             storage.clone(),
+            None,
             shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
         )
         .await
@@ -0,0 +1,7 @@
+-- Time-limited leases that allow multiple compactors to claim partitions without static sharding.
+CREATE TABLE IF NOT EXISTS partition_lease (
+    partition_id BIGINT REFERENCES partition (id) ON DELETE CASCADE,
+    owner TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    PRIMARY KEY (partition_id)
+);
@@ -0,0 +1,26 @@
+-- Rules for downsampling aged data during cold compaction.
+CREATE TABLE IF NOT EXISTS rollup_rule (
+    id BIGINT GENERATED ALWAYS AS IDENTITY,
+    namespace_id BIGINT NOT NULL REFERENCES namespace (id) ON DELETE CASCADE,
+    table_id BIGINT NULL REFERENCES table_name (id) ON DELETE CASCADE,
+    interval_ns BIGINT NOT NULL,
+    min_age_ns BIGINT NOT NULL,
+    float_aggregate SMALLINT NOT NULL,
+    integer_aggregate SMALLINT NOT NULL,
+    unsigned_aggregate SMALLINT NOT NULL,
+    boolean_aggregate SMALLINT NOT NULL,
+    string_aggregate SMALLINT NOT NULL,
+    PRIMARY KEY (id)
+);
+
+-- At most one namespace-wide rule and one rule per table.
+CREATE UNIQUE INDEX IF NOT EXISTS rollup_rule_namespace_unique
+    ON rollup_rule (namespace_id) WHERE table_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS rollup_rule_table_unique
+    ON rollup_rule (namespace_id, table_id) WHERE table_id IS NOT NULL;
+
+-- Window size the data of a partition was rolled up to, NULL for full resolution.
+ALTER TABLE
+    partition
+ADD
+    COLUMN rollup_interval_ns BIGINT DEFAULT NULL;
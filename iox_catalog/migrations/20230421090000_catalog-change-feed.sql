@@ -0,0 +1,138 @@
+-- Publish changes of catalog objects on the "<schema>_changes" channel (i.e.
+-- iox_catalog_changes by default), for services that cache them (see
+-- iox_catalog::changes).
+--
+-- Payloads are "<kind>:<id>:<id>":
+--   namespace:<namespace id>
+--   table:<namespace id>:<table id>
+--   partition:<table id>:<partition id>
+--   parquet_file:<table id>:<partition id>
+--
+-- Notifications are only delivered once the transaction commits and identical
+-- payloads within one transaction are delivered once. Updates that change
+-- nothing (e.g. the upserts of create_or_get) are not published.
+
+CREATE OR REPLACE FUNCTION notify_namespace_change()
+RETURNS TRIGGER
+LANGUAGE PLPGSQL
+AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_changes', 'namespace:' || OLD.id);
+    ELSIF TG_OP = 'INSERT' OR OLD IS DISTINCT FROM NEW THEN
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_changes', 'namespace:' || NEW.id);
+    END IF;
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER namespace_change
+    AFTER INSERT OR UPDATE OR DELETE ON namespace
+    FOR EACH ROW
+    EXECUTE PROCEDURE notify_namespace_change();
+
+CREATE OR REPLACE FUNCTION notify_table_change()
+RETURNS TRIGGER
+LANGUAGE PLPGSQL
+AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_changes', 'table:' || OLD.namespace_id || ':' || OLD.id);
+    ELSIF TG_OP = 'INSERT' OR OLD IS DISTINCT FROM NEW THEN
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_changes', 'table:' || NEW.namespace_id || ':' || NEW.id);
+    END IF;
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER table_change
+    AFTER INSERT OR UPDATE OR DELETE ON table_name
+    FOR EACH ROW
+    EXECUTE PROCEDURE notify_table_change();
+
+-- Column changes are published as changes of their table. Columns removed
+-- together with their table (or namespace) are covered by that change.
+CREATE OR REPLACE FUNCTION notify_column_change()
+RETURNS TRIGGER
+LANGUAGE PLPGSQL
+AS $$
+DECLARE
+    changed_table_id BIGINT;
+    changed_namespace_id BIGINT;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed_table_id := OLD.table_id;
+    ELSIF TG_OP = 'INSERT' OR OLD IS DISTINCT FROM NEW THEN
+        changed_table_id := NEW.table_id;
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    SELECT namespace_id INTO changed_namespace_id FROM table_name WHERE id = changed_table_id;
+    IF changed_namespace_id IS NOT NULL THEN
+        PERFORM pg_notify(
+            TG_TABLE_SCHEMA || '_changes',
+            'table:' || changed_namespace_id || ':' || changed_table_id
+        );
+    END IF;
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER column_change
+    AFTER INSERT OR UPDATE OR DELETE ON column_name
+    FOR EACH ROW
+    EXECUTE PROCEDURE notify_column_change();
+
+-- Only the attributes other services cache are published for partitions; the
+-- compaction bookkeeping (e.g. new_file_at) changes with every new file.
+CREATE OR REPLACE FUNCTION notify_partition_change()
+RETURNS TRIGGER
+LANGUAGE PLPGSQL
+AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_changes', 'partition:' || OLD.table_id || ':' || OLD.id);
+    ELSIF TG_OP = 'INSERT'
+        OR OLD.sort_key IS DISTINCT FROM NEW.sort_key
+        OR OLD.rollup_interval_ns IS DISTINCT FROM NEW.rollup_interval_ns THEN
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_changes', 'partition:' || NEW.table_id || ':' || NEW.id);
+    END IF;
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER partition_change
+    AFTER INSERT OR UPDATE OR DELETE ON partition
+    FOR EACH ROW
+    EXECUTE PROCEDURE notify_partition_change();
+
+CREATE OR REPLACE FUNCTION notify_parquet_file_change()
+RETURNS TRIGGER
+LANGUAGE PLPGSQL
+AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify(
+            TG_TABLE_SCHEMA || '_changes',
+            'parquet_file:' || OLD.table_id || ':' || OLD.partition_id
+        );
+    ELSIF TG_OP = 'INSERT' OR OLD IS DISTINCT FROM NEW THEN
+        PERFORM pg_notify(
+            TG_TABLE_SCHEMA || '_changes',
+            'parquet_file:' || NEW.table_id || ':' || NEW.partition_id
+        );
+    END IF;
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER parquet_file_change
+    AFTER INSERT OR UPDATE OR DELETE ON parquet_file
+    FOR EACH ROW
+    EXECUTE PROCEDURE notify_parquet_file_change();
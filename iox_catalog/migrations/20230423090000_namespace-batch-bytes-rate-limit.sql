@@ -0,0 +1,6 @@
+-- The write rate limit in bytes is enforced on the in-memory size of the
+-- decoded write batches, not on the bytes sent by clients. Name it accordingly.
+ALTER TABLE
+    namespace
+RENAME COLUMN
+    max_bytes_per_second TO max_batch_bytes_per_second;
@@ -0,0 +1,11 @@
+-- Time-limited leases that allow multiple compactors to claim partitions without static sharding.
+create table if not exists partition_lease
+(
+    partition_id INTEGER not null
+        constraint partition_lease_pkey
+            primary key
+        references partition
+            on delete cascade,
+    owner        text    not null,
+    expires_at   numeric not null
+);
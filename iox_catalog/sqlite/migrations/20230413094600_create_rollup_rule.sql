@@ -0,0 +1,32 @@
+-- Rules for downsampling aged data during cold compaction.
+create table if not exists rollup_rule
+(
+    id                 INTEGER
+        constraint rollup_rule_pkey
+            primary key autoincrement,
+    namespace_id       numeric not null
+        references namespace
+            on delete cascade,
+    table_id           numeric
+        references table_name
+            on delete cascade,
+    interval_ns        numeric not null,
+    min_age_ns         numeric not null,
+    float_aggregate    smallint not null,
+    integer_aggregate  smallint not null,
+    unsigned_aggregate smallint not null,
+    boolean_aggregate  smallint not null,
+    string_aggregate   smallint not null
+);
+
+-- At most one namespace-wide rule and one rule per table.
+create unique index if not exists rollup_rule_namespace_unique
+    on rollup_rule (namespace_id) where table_id is null;
+create unique index if not exists rollup_rule_table_unique
+    on rollup_rule (namespace_id, table_id) where table_id is not null;
+
+-- Window size the data of a partition was rolled up to, NULL for full resolution.
+ALTER TABLE
+    partition
+ADD
+    COLUMN rollup_interval_ns numeric DEFAULT NULL;
@@ -0,0 +1,16 @@
+-- Add soft-deletion timestamps to the "table_name" and "column_name" tables.
+--
+-- The name of a deleted table or column stays reserved.
+ALTER TABLE
+    table_name
+ADD
+    COLUMN deleted_at numeric DEFAULT NULL;
+
+CREATE INDEX table_name_deleted_at_idx ON table_name (deleted_at);
+
+ALTER TABLE
+    column_name
+ADD
+    COLUMN deleted_at numeric DEFAULT NULL;
+
+CREATE INDEX column_name_deleted_at_idx ON column_name (deleted_at);
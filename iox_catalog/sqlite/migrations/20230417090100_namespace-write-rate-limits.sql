@@ -0,0 +1,12 @@
+-- Add per-namespace write rate limits enforced by the router.
+--
+-- NULL means the rate is unlimited.
+ALTER TABLE
+    namespace
+ADD
+    COLUMN max_lines_per_second numeric DEFAULT NULL;
+
+ALTER TABLE
+    namespace
+ADD
+    COLUMN max_bytes_per_second numeric DEFAULT NULL;
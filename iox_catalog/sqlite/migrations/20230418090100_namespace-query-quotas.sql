@@ -0,0 +1,12 @@
+-- Add per-namespace query quotas enforced by the querier.
+--
+-- NULL means the namespace is only bound by the global querier limits.
+ALTER TABLE
+    namespace
+ADD
+    COLUMN max_concurrent_queries integer DEFAULT NULL;
+
+ALTER TABLE
+    namespace
+ADD
+    COLUMN max_query_memory_bytes numeric DEFAULT NULL;
@@ -0,0 +1,8 @@
+-- Add the per-namespace policy for writes with a field type that conflicts
+-- with the existing column type.
+--
+-- 0 (strict) rejects such writes.
+ALTER TABLE
+    namespace
+ADD
+    COLUMN type_coercion_policy smallint NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- Add the primary key declared when creating a table: its tag columns in sort
+-- order followed by the time column, as a JSON array.
+--
+-- An empty array means the sort order is derived from the data.
+ALTER TABLE
+    table_name
+ADD
+    COLUMN primary_key text [] NOT NULL DEFAULT '[]';
@@ -0,0 +1,8 @@
+-- Add the priority class of queries against a namespace that do not request
+-- one.
+--
+-- 0 (normal) is the class all queries ran with before.
+ALTER TABLE
+    namespace
+ADD
+    COLUMN default_query_priority smallint NOT NULL DEFAULT 0;
@@ -0,0 +1,772 @@
+//! Backup and restore of the catalog.
+//!
+//! Without the catalog, the parquet files in object storage cannot be mapped back to their
+//! namespaces, tables and partitions. A [`CatalogSnapshot`] captures the records that are needed
+//! to make them queryable again: topics, query pools, shards, namespaces, tables, columns,
+//! partitions (including their sort keys) and the parquet files that are not flagged for deletion.
+//!
+//! Snapshots are plain serializable data with an explicit [version](SNAPSHOT_VERSION), so they
+//! do not change when the catalog's in-memory types do, and can be [restored](restore) into an
+//! empty catalog of any backend. IDs are preserved, because object store paths are derived from
+//! them.
+
+use crate::interface::{Catalog, RepoCollection, SoftDeletedRows};
+use data_types::{
+    Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile,
+    ParquetFileId, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId, SequenceNumber,
+    Shard, ShardId, ShardIndex, Table, TableId, Timestamp, TopicId, TopicMetadata,
+    TypeCoercionPolicy,
+};
+use iox_time::TimeProvider;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt, Snafu};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// The version of the snapshot format written by [`backup`].
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("catalog error: {source}"))]
+    Catalog { source: crate::interface::Error },
+
+    #[snafu(display("unsupported snapshot version {version}, expected {SNAPSHOT_VERSION}"))]
+    UnsupportedVersion { version: u32 },
+
+    #[snafu(display("cannot restore into a catalog that is not empty: {reason}"))]
+    NotEmpty { reason: String },
+
+    #[snafu(display("invalid {kind} record {id} in snapshot: {reason}"))]
+    InvalidRecord {
+        kind: &'static str,
+        id: i64,
+        reason: String,
+    },
+}
+
+/// A specialized `Error` for backup and restore errors
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Consistent copy of the catalog records needed to restore access to the data in object storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct CatalogSnapshot {
+    /// Version of the snapshot format, see [`SNAPSHOT_VERSION`].
+    pub version: u32,
+    /// When the snapshot was taken, in nanoseconds since the epoch.
+    pub created_at: i64,
+    pub topics: Vec<TopicRecord>,
+    pub query_pools: Vec<QueryPoolRecord>,
+    pub shards: Vec<ShardRecord>,
+    pub namespaces: Vec<NamespaceRecord>,
+    pub tables: Vec<TableRecord>,
+    pub columns: Vec<ColumnRecord>,
+    pub partitions: Vec<PartitionRecord>,
+    pub parquet_files: Vec<ParquetFileRecord>,
+}
+
+/// Serialized [`TopicMetadata`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TopicRecord {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Serialized [`QueryPool`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct QueryPoolRecord {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Serialized [`Shard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ShardRecord {
+    pub id: i64,
+    pub topic_id: i64,
+    pub shard_index: i32,
+    pub min_unpersisted_sequence_number: i64,
+}
+
+/// Serialized [`Namespace`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct NamespaceRecord {
+    pub id: i64,
+    pub name: String,
+    pub retention_period_ns: Option<i64>,
+    pub topic_id: i64,
+    pub query_pool_id: i64,
+    pub max_tables: i32,
+    pub max_columns_per_table: i32,
+    pub max_lines_per_second: Option<i64>,
+    #[serde(alias = "max_bytes_per_second")]
+    pub max_batch_bytes_per_second: Option<i64>,
+    pub max_concurrent_queries: Option<i32>,
+    pub max_query_memory_bytes: Option<i64>,
+    #[serde(default)]
+    pub type_coercion_policy: i16,
+    #[serde(default)]
+    pub default_query_priority: i16,
+    pub deleted_at: Option<i64>,
+}
+
+/// Serialized [`Table`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TableRecord {
+    pub id: i64,
+    pub namespace_id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub primary_key: Vec<String>,
+    pub deleted_at: Option<i64>,
+}
+
+/// Serialized [`Column`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ColumnRecord {
+    pub id: i64,
+    pub table_id: i64,
+    pub name: String,
+    pub column_type: i16,
+    pub deleted_at: Option<i64>,
+}
+
+/// Serialized [`Partition`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct PartitionRecord {
+    pub id: i64,
+    pub shard_id: i64,
+    pub table_id: i64,
+    pub partition_key: String,
+    pub sort_key: Vec<String>,
+    pub persisted_sequence_number: Option<i64>,
+    pub new_file_at: Option<i64>,
+    pub rollup_interval_ns: Option<i64>,
+}
+
+/// Serialized [`ParquetFile`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ParquetFileRecord {
+    pub id: i64,
+    pub shard_id: i64,
+    pub namespace_id: i64,
+    pub table_id: i64,
+    pub partition_id: i64,
+    pub object_store_id: String,
+    pub max_sequence_number: i64,
+    pub min_time: i64,
+    pub max_time: i64,
+    pub to_delete: Option<i64>,
+    pub file_size_bytes: i64,
+    pub row_count: i64,
+    pub compaction_level: i16,
+    pub created_at: i64,
+    pub column_set: Vec<i64>,
+    pub max_l0_created_at: i64,
+}
+
+/// Take a snapshot of `catalog`.
+///
+/// All records are read in one [snapshot transaction](Catalog::start_snapshot_transaction), so
+/// the snapshot is consistent even if the catalog is modified while the backup runs.
+pub async fn backup(catalog: &dyn Catalog) -> Result<CatalogSnapshot> {
+    let created_at = catalog.time_provider().now().timestamp_nanos();
+
+    let mut txn = catalog
+        .start_snapshot_transaction()
+        .await
+        .context(CatalogSnafu)?;
+    let snapshot = read_records(txn.as_mut(), created_at).await;
+    // Nothing was written, so there is nothing to commit.
+    txn.abort().await.context(CatalogSnafu)?;
+
+    snapshot
+}
+
+async fn read_records<R>(repos: &mut R, created_at: i64) -> Result<CatalogSnapshot>
+where
+    R: RepoCollection + ?Sized,
+{
+    let topics = repos.topics().list().await.context(CatalogSnafu)?;
+    let query_pools = repos.query_pools().list().await.context(CatalogSnafu)?;
+    let shards = repos.shards().list().await.context(CatalogSnafu)?;
+    let namespaces = repos
+        .namespaces()
+        .list(SoftDeletedRows::AllRows)
+        .await
+        .context(CatalogSnafu)?;
+    let tables = repos.tables().list().await.context(CatalogSnafu)?;
+    let columns = repos.columns().list().await.context(CatalogSnafu)?;
+    let mut partitions = vec![];
+    let mut parquet_files = vec![];
+    for table in &tables {
+        partitions.extend(
+            repos
+                .partitions()
+                .list_by_table_id(table.id)
+                .await
+                .context(CatalogSnafu)?,
+        );
+        parquet_files.extend(
+            repos
+                .parquet_files()
+                .list_by_table_not_to_delete(table.id)
+                .await
+                .context(CatalogSnafu)?,
+        );
+    }
+
+    Ok(CatalogSnapshot {
+        version: SNAPSHOT_VERSION,
+        created_at,
+        topics: topics.iter().map(Into::into).collect(),
+        query_pools: query_pools.iter().map(Into::into).collect(),
+        shards: shards.iter().map(Into::into).collect(),
+        namespaces: namespaces.iter().map(Into::into).collect(),
+        tables: tables.iter().map(Into::into).collect(),
+        columns: columns.iter().map(Into::into).collect(),
+        partitions: partitions.iter().map(Into::into).collect(),
+        parquet_files: parquet_files.iter().map(Into::into).collect(),
+    })
+}
+
+/// Restore `snapshot` into `catalog`, which must not contain any namespaces or tables.
+///
+/// The catalog must have been [set up](Catalog::setup). Topics, query pools and shards that
+/// already exist with the same ID and content (such as the ones created during setup) are kept.
+/// The restore runs in a single transaction, so it either restores everything or nothing.
+pub async fn restore(catalog: &dyn Catalog, snapshot: &CatalogSnapshot) -> Result<()> {
+    ensure!(
+        snapshot.version == SNAPSHOT_VERSION,
+        UnsupportedVersionSnafu {
+            version: snapshot.version
+        }
+    );
+
+    let mut txn = catalog.start_transaction().await.context(CatalogSnafu)?;
+    match restore_records(txn.as_mut(), snapshot).await {
+        Ok(()) => txn.commit().await.context(CatalogSnafu),
+        Err(e) => {
+            txn.abort().await.context(CatalogSnafu)?;
+            Err(e)
+        }
+    }
+}
+
+async fn restore_records<R>(repos: &mut R, snapshot: &CatalogSnapshot) -> Result<()>
+where
+    R: RepoCollection + ?Sized,
+{
+    let namespaces = repos
+        .namespaces()
+        .list(SoftDeletedRows::AllRows)
+        .await
+        .context(CatalogSnafu)?;
+    let tables = repos.tables().list().await.context(CatalogSnafu)?;
+    ensure!(
+        namespaces.is_empty() && tables.is_empty(),
+        NotEmptySnafu {
+            reason: format!(
+                "found {} namespaces and {} tables",
+                namespaces.len(),
+                tables.len()
+            )
+        }
+    );
+
+    let existing = repos.topics().list().await.context(CatalogSnafu)?;
+    for record in &snapshot.topics {
+        let topic = TopicMetadata::from(record);
+        if keep_existing("topic", &topic, existing.iter().find(|t| t.id == topic.id))? {
+            continue;
+        }
+        repos.topics().restore(&topic).await.context(CatalogSnafu)?;
+    }
+
+    let existing = repos.query_pools().list().await.context(CatalogSnafu)?;
+    for record in &snapshot.query_pools {
+        let pool = QueryPool::from(record);
+        if keep_existing(
+            "query pool",
+            &pool,
+            existing.iter().find(|p| p.id == pool.id),
+        )? {
+            continue;
+        }
+        repos
+            .query_pools()
+            .restore(&pool)
+            .await
+            .context(CatalogSnafu)?;
+    }
+
+    let existing = repos.shards().list().await.context(CatalogSnafu)?;
+    for record in &snapshot.shards {
+        let shard = Shard::from(record);
+        if keep_existing("shard", &shard, existing.iter().find(|s| s.id == shard.id))? {
+            continue;
+        }
+        repos.shards().restore(&shard).await.context(CatalogSnafu)?;
+    }
+
+    for record in &snapshot.namespaces {
+        repos
+            .namespaces()
+            .restore(&record.try_into()?)
+            .await
+            .context(CatalogSnafu)?;
+    }
+    for record in &snapshot.tables {
+        repos
+            .tables()
+            .restore(&record.into())
+            .await
+            .context(CatalogSnafu)?;
+    }
+    for record in &snapshot.columns {
+        repos
+            .columns()
+            .restore(&record.try_into()?)
+            .await
+            .context(CatalogSnafu)?;
+    }
+    for record in &snapshot.partitions {
+        repos
+            .partitions()
+            .restore(&record.try_into()?)
+            .await
+            .context(CatalogSnafu)?;
+    }
+    for record in &snapshot.parquet_files {
+        repos
+            .parquet_files()
+            .restore(&record.try_into()?)
+            .await
+            .context(CatalogSnafu)?;
+    }
+
+    Ok(())
+}
+
+/// Returns true if the record to restore already exists, and fails if the existing record is
+/// different.
+fn keep_existing<T>(kind: &str, record: &T, existing: Option<&T>) -> Result<bool>
+where
+    T: PartialEq + std::fmt::Debug,
+{
+    match existing {
+        None => Ok(false),
+        Some(existing) if existing == record => Ok(true),
+        Some(existing) => NotEmptySnafu {
+            reason: format!("existing {kind} {existing:?} differs from {record:?}"),
+        }
+        .fail(),
+    }
+}
+
+fn invalid(kind: &'static str, id: i64, reason: impl ToString) -> Error {
+    Error::InvalidRecord {
+        kind,
+        id,
+        reason: reason.to_string(),
+    }
+}
+
+impl From<&TopicMetadata> for TopicRecord {
+    fn from(v: &TopicMetadata) -> Self {
+        Self {
+            id: v.id.get(),
+            name: v.name.clone(),
+        }
+    }
+}
+
+impl From<&TopicRecord> for TopicMetadata {
+    fn from(v: &TopicRecord) -> Self {
+        Self {
+            id: TopicId::new(v.id),
+            name: v.name.clone(),
+        }
+    }
+}
+
+impl From<&QueryPool> for QueryPoolRecord {
+    fn from(v: &QueryPool) -> Self {
+        Self {
+            id: v.id.get(),
+            name: v.name.clone(),
+        }
+    }
+}
+
+impl From<&QueryPoolRecord> for QueryPool {
+    fn from(v: &QueryPoolRecord) -> Self {
+        Self {
+            id: QueryPoolId::new(v.id),
+            name: v.name.clone(),
+        }
+    }
+}
+
+impl From<&Shard> for ShardRecord {
+    fn from(v: &Shard) -> Self {
+        Self {
+            id: v.id.get(),
+            topic_id: v.topic_id.get(),
+            shard_index: v.shard_index.get(),
+            min_unpersisted_sequence_number: v.min_unpersisted_sequence_number.get(),
+        }
+    }
+}
+
+impl From<&ShardRecord> for Shard {
+    fn from(v: &ShardRecord) -> Self {
+        Self {
+            id: ShardId::new(v.id),
+            topic_id: TopicId::new(v.topic_id),
+            shard_index: ShardIndex::new(v.shard_index),
+            min_unpersisted_sequence_number: SequenceNumber::new(v.min_unpersisted_sequence_number),
+        }
+    }
+}
+
+impl From<&Namespace> for NamespaceRecord {
+    fn from(v: &Namespace) -> Self {
+        Self {
+            id: v.id.get(),
+            name: v.name.clone(),
+            retention_period_ns: v.retention_period_ns,
+            topic_id: v.topic_id.get(),
+            query_pool_id: v.query_pool_id.get(),
+            max_tables: v.max_tables,
+            max_columns_per_table: v.max_columns_per_table,
+            max_lines_per_second: v.max_lines_per_second,
+            max_batch_bytes_per_second: v.max_batch_bytes_per_second,
+            max_concurrent_queries: v.max_concurrent_queries,
+            max_query_memory_bytes: v.max_query_memory_bytes,
+            type_coercion_policy: v.type_coercion_policy as i16,
+            default_query_priority: v.default_query_priority as i16,
+            deleted_at: v.deleted_at.map(|t| t.get()),
+        }
+    }
+}
+
+impl TryFrom<&NamespaceRecord> for Namespace {
+    type Error = Error;
+
+    fn try_from(v: &NamespaceRecord) -> Result<Self> {
+        Ok(Self {
+            id: NamespaceId::new(v.id),
+            name: v.name.clone(),
+            retention_period_ns: v.retention_period_ns,
+            topic_id: TopicId::new(v.topic_id),
+            query_pool_id: QueryPoolId::new(v.query_pool_id),
+            max_tables: v.max_tables,
+            max_columns_per_table: v.max_columns_per_table,
+            max_lines_per_second: v.max_lines_per_second,
+            max_batch_bytes_per_second: v.max_batch_bytes_per_second,
+            max_concurrent_queries: v.max_concurrent_queries,
+            max_query_memory_bytes: v.max_query_memory_bytes,
+            type_coercion_policy: TypeCoercionPolicy::try_from(i32::from(v.type_coercion_policy))
+                .map_err(|e| invalid("namespace", v.id, e))?,
+            default_query_priority: QueryPriority::try_from(i32::from(v.default_query_priority))
+                .map_err(|e| invalid("namespace", v.id, e))?,
+            deleted_at: v.deleted_at.map(Timestamp::new),
+        })
+    }
+}
+
+impl From<&Table> for TableRecord {
+    fn from(v: &Table) -> Self {
+        Self {
+            id: v.id.get(),
+            namespace_id: v.namespace_id.get(),
+            name: v.name.clone(),
+            primary_key: v.primary_key.clone(),
+            deleted_at: v.deleted_at.map(|t| t.get()),
+        }
+    }
+}
+
+impl From<&TableRecord> for Table {
+    fn from(v: &TableRecord) -> Self {
+        Self {
+            id: TableId::new(v.id),
+            namespace_id: NamespaceId::new(v.namespace_id),
+            name: v.name.clone(),
+            primary_key: v.primary_key.clone(),
+            deleted_at: v.deleted_at.map(Timestamp::new),
+        }
+    }
+}
+
+impl From<&Column> for ColumnRecord {
+    fn from(v: &Column) -> Self {
+        Self {
+            id: v.id.get(),
+            table_id: v.table_id.get(),
+            name: v.name.clone(),
+            column_type: v.column_type as i16,
+            deleted_at: v.deleted_at.map(|t| t.get()),
+        }
+    }
+}
+
+impl TryFrom<&ColumnRecord> for Column {
+    type Error = Error;
+
+    fn try_from(v: &ColumnRecord) -> Result<Self> {
+        Ok(Self {
+            id: ColumnId::new(v.id),
+            table_id: TableId::new(v.table_id),
+            name: v.name.clone(),
+            column_type: ColumnType::try_from(v.column_type)
+                .map_err(|e| invalid("column", v.id, e))?,
+            deleted_at: v.deleted_at.map(Timestamp::new),
+        })
+    }
+}
+
+impl From<&Partition> for PartitionRecord {
+    fn from(v: &Partition) -> Self {
+        Self {
+            id: v.id.get(),
+            shard_id: v.shard_id.get(),
+            table_id: v.table_id.get(),
+            partition_key: v.partition_key.to_string(),
+            sort_key: v.sort_key.clone(),
+            persisted_sequence_number: v.persisted_sequence_number.map(|s| s.get()),
+            new_file_at: v.new_file_at.map(|t| t.get()),
+            rollup_interval_ns: v.rollup_interval_ns,
+        }
+    }
+}
+
+impl TryFrom<&PartitionRecord> for Partition {
+    type Error = Error;
+
+    fn try_from(v: &PartitionRecord) -> Result<Self> {
+        if v.partition_key.is_empty() {
+            return Err(invalid("partition", v.id, "empty partition key"));
+        }
+
+        Ok(Self {
+            id: PartitionId::new(v.id),
+            shard_id: ShardId::new(v.shard_id),
+            table_id: TableId::new(v.table_id),
+            partition_key: PartitionKey::from(v.partition_key.clone()),
+            sort_key: v.sort_key.clone(),
+            persisted_sequence_number: v.persisted_sequence_number.map(SequenceNumber::new),
+            new_file_at: v.new_file_at.map(Timestamp::new),
+            rollup_interval_ns: v.rollup_interval_ns,
+        })
+    }
+}
+
+impl From<&ParquetFile> for ParquetFileRecord {
+    fn from(v: &ParquetFile) -> Self {
+        Self {
+            id: v.id.get(),
+            shard_id: v.shard_id.get(),
+            namespace_id: v.namespace_id.get(),
+            table_id: v.table_id.get(),
+            partition_id: v.partition_id.get(),
+            object_store_id: v.object_store_id.to_string(),
+            max_sequence_number: v.max_sequence_number.get(),
+            min_time: v.min_time.get(),
+            max_time: v.max_time.get(),
+            to_delete: v.to_delete.map(|t| t.get()),
+            file_size_bytes: v.file_size_bytes,
+            row_count: v.row_count,
+            compaction_level: v.compaction_level as i16,
+            created_at: v.created_at.get(),
+            column_set: v.column_set.iter().map(|c| c.get()).collect(),
+            max_l0_created_at: v.max_l0_created_at.get(),
+        }
+    }
+}
+
+impl TryFrom<&ParquetFileRecord> for ParquetFile {
+    type Error = Error;
+
+    fn try_from(v: &ParquetFileRecord) -> Result<Self> {
+        let object_store_id =
+            Uuid::parse_str(&v.object_store_id).map_err(|e| invalid("parquet file", v.id, e))?;
+        let compaction_level = CompactionLevel::try_from(i32::from(v.compaction_level))
+            .map_err(|e| invalid("parquet file", v.id, e))?;
+        let column_ids = v.column_set.iter().copied().collect::<HashSet<_>>();
+        if column_ids.len() != v.column_set.len() {
+            return Err(invalid("parquet file", v.id, "duplicate columns"));
+        }
+
+        Ok(Self {
+            id: ParquetFileId::new(v.id),
+            shard_id: ShardId::new(v.shard_id),
+            namespace_id: NamespaceId::new(v.namespace_id),
+            table_id: TableId::new(v.table_id),
+            partition_id: PartitionId::new(v.partition_id),
+            object_store_id,
+            max_sequence_number: SequenceNumber::new(v.max_sequence_number),
+            min_time: Timestamp::new(v.min_time),
+            max_time: Timestamp::new(v.max_time),
+            to_delete: v.to_delete.map(Timestamp::new),
+            file_size_bytes: v.file_size_bytes,
+            row_count: v.row_count,
+            compaction_level,
+            created_at: Timestamp::new(v.created_at),
+            column_set: ColumnSet::new(v.column_set.iter().copied().map(ColumnId::new)),
+            max_l0_created_at: Timestamp::new(v.max_l0_created_at),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interface::get_schema_by_name, mem::MemCatalog, SHARED_TOPIC_NAME};
+    use assert_matches::assert_matches;
+    use data_types::{ParquetFileParams, TRANSITION_SHARD_ID};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_backup_restore() {
+        let source = catalog().await;
+        let snapshot = populate(&source).await;
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert_eq!(snapshot.namespaces.len(), 1);
+        assert_eq!(snapshot.tables.len(), 1);
+        assert_eq!(snapshot.columns.len(), 2);
+        assert_eq!(snapshot.partitions.len(), 1);
+        assert_eq!(snapshot.partitions[0].sort_key, vec!["tag", "time"]);
+        // the file flagged for deletion is not backed up
+        assert_eq!(snapshot.parquet_files.len(), 1);
+
+        let target = catalog().await;
+        restore(&target, &snapshot).await.unwrap();
+
+        let mut restored = backup(&target).await.unwrap();
+        restored.created_at = snapshot.created_at;
+        assert_eq!(restored, snapshot);
+
+        let mut repos = target.repositories().await;
+        let schema = get_schema_by_name("ns", repos.as_mut(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap();
+        assert_eq!(schema.tables["cpu"].columns.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_restore_into_non_empty_catalog() {
+        let source = catalog().await;
+        let snapshot = populate(&source).await;
+
+        assert_matches!(
+            restore(&source, &snapshot).await,
+            Err(Error::NotEmpty { .. })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_unsupported_version() {
+        let source = catalog().await;
+        let mut snapshot = populate(&source).await;
+        snapshot.version = SNAPSHOT_VERSION + 1;
+
+        let target = catalog().await;
+        assert_matches!(
+            restore(&target, &snapshot).await,
+            Err(Error::UnsupportedVersion { .. })
+        );
+    }
+
+    async fn catalog() -> MemCatalog {
+        let catalog = MemCatalog::new(Arc::new(metric::Registry::default()));
+        catalog.setup().await.unwrap();
+        catalog
+    }
+
+    /// Create a namespace with one table, partition and live parquet file, and return a backup.
+    async fn populate(catalog: &MemCatalog) -> CatalogSnapshot {
+        let mut repos = catalog.repositories().await;
+        let topic = repos
+            .topics()
+            .create_or_get(SHARED_TOPIC_NAME)
+            .await
+            .unwrap();
+        let query_pool = repos.query_pools().create_or_get("pool").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("ns", None, topic.id, query_pool.id)
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create_or_get("cpu", namespace.id)
+            .await
+            .unwrap();
+        let tag = repos
+            .columns()
+            .create_or_get("tag", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let time = repos
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("2023-01-01".into(), TRANSITION_SHARD_ID, table.id)
+            .await
+            .unwrap();
+        repos
+            .partitions()
+            .cas_sort_key(partition.id, None, &["tag", "time"])
+            .await
+            .unwrap();
+
+        let params = ParquetFileParams {
+            shard_id: TRANSITION_SHARD_ID,
+            namespace_id: namespace.id,
+            table_id: table.id,
+            partition_id: partition.id,
+            object_store_id: Uuid::new_v4(),
+            max_sequence_number: SequenceNumber::new(1),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(10),
+            file_size_bytes: 1337,
+            row_count: 42,
+            compaction_level: CompactionLevel::Initial,
+            created_at: Timestamp::new(1),
+            column_set: ColumnSet::new([tag.id, time.id]),
+            max_l0_created_at: Timestamp::new(1),
+        };
+        repos.parquet_files().create(params.clone()).await.unwrap();
+        let deleted = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                ..params
+            })
+            .await
+            .unwrap();
+        repos
+            .parquet_files()
+            .flag_for_delete(deleted.id)
+            .await
+            .unwrap();
+        drop(repos);
+
+        backup(catalog).await.unwrap()
+    }
+}
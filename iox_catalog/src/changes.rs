@@ -0,0 +1,162 @@
+//! A feed of changes made to the catalog, for consumers that cache catalog state.
+//!
+//! Changes are published once they become visible to other catalog users, i.e. when the
+//! [`Transaction`](crate::interface::Transaction) they were made in commits. Delivery is
+//! best-effort: a subscriber that falls more than [`CHANGE_FEED_CAPACITY`] changes behind observes
+//! a [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged), and a catalog that
+//! may have missed changes itself publishes [`CatalogChange::Reset`]. In both cases the subscriber
+//! must assume that any of its cached state may be stale.
+
+use data_types::{NamespaceId, PartitionId, TableId};
+use tokio::sync::broadcast;
+
+/// Number of changes buffered for each subscriber of the change feed.
+pub const CHANGE_FEED_CAPACITY: usize = 10_000;
+
+/// A change to a catalog object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CatalogChange {
+    /// The namespace or its limits, quotas or retention changed.
+    Namespace {
+        /// Namespace ID.
+        id: NamespaceId,
+    },
+
+    /// The table or one of its columns changed.
+    Table {
+        /// ID of the namespace the table belongs to.
+        namespace_id: NamespaceId,
+        /// Table ID.
+        id: TableId,
+    },
+
+    /// The partition, e.g. its sort key, changed.
+    Partition {
+        /// ID of the table the partition belongs to.
+        table_id: TableId,
+        /// Partition ID.
+        id: PartitionId,
+    },
+
+    /// A parquet file of the partition was added, flagged for deletion or compacted.
+    ParquetFile {
+        /// ID of the table the file belongs to.
+        table_id: TableId,
+        /// ID of the partition the file belongs to.
+        partition_id: PartitionId,
+    },
+
+    /// Changes may have been missed, e.g. while the catalog was reconnecting to its source of
+    /// changes, so any catalog object may have changed.
+    Reset,
+}
+
+/// Create the sending half of a change feed.
+pub(crate) fn sender() -> broadcast::Sender<CatalogChange> {
+    broadcast::channel(CHANGE_FEED_CAPACITY).0
+}
+
+/// Changes made through one [`RepoCollection`](crate::interface::RepoCollection) that have not
+/// been published yet.
+///
+/// Changes made outside of a transaction are published immediately, changes made within a
+/// transaction are held back until it commits.
+#[derive(Debug)]
+pub(crate) struct PendingChanges {
+    sender: broadcast::Sender<CatalogChange>,
+    buffer: Option<Vec<CatalogChange>>,
+}
+
+impl PendingChanges {
+    /// Publish every change as soon as it is recorded.
+    pub(crate) fn oneshot(sender: broadcast::Sender<CatalogChange>) -> Self {
+        Self {
+            sender,
+            buffer: None,
+        }
+    }
+
+    /// Hold changes back until [`commit`](Self::commit).
+    pub(crate) fn transaction(sender: broadcast::Sender<CatalogChange>) -> Self {
+        Self {
+            sender,
+            buffer: Some(vec![]),
+        }
+    }
+
+    /// Record a change.
+    pub(crate) fn push(&mut self, change: CatalogChange) {
+        match &mut self.buffer {
+            Some(buffer) => {
+                if !buffer.contains(&change) {
+                    buffer.push(change);
+                }
+            }
+            None => publish(&self.sender, change),
+        }
+    }
+
+    /// Publish all changes recorded so far.
+    pub(crate) fn commit(&mut self) {
+        for change in self.buffer.as_mut().map(std::mem::take).unwrap_or_default() {
+            publish(&self.sender, change);
+        }
+    }
+
+    /// Drop all changes recorded so far.
+    pub(crate) fn abort(&mut self) {
+        if let Some(buffer) = &mut self.buffer {
+            buffer.clear();
+        }
+    }
+}
+
+/// Publish `change`, ignoring the absence of subscribers.
+pub(crate) fn publish(sender: &broadcast::Sender<CatalogChange>, change: CatalogChange) {
+    let _ = sender.send(change);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGE: CatalogChange = CatalogChange::Namespace {
+        id: NamespaceId::new(1),
+    };
+
+    #[test]
+    fn test_oneshot_publishes_immediately() {
+        let sender = sender();
+        let mut rx = sender.subscribe();
+
+        PendingChanges::oneshot(sender).push(CHANGE);
+        assert_eq!(rx.try_recv().unwrap(), CHANGE);
+    }
+
+    #[test]
+    fn test_transaction_publishes_on_commit() {
+        let sender = sender();
+        let mut rx = sender.subscribe();
+
+        let mut changes = PendingChanges::transaction(sender);
+        changes.push(CHANGE);
+        changes.push(CHANGE);
+        assert!(rx.try_recv().is_err());
+
+        changes.commit();
+        assert_eq!(rx.try_recv().unwrap(), CHANGE);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_transaction_abort_drops_changes() {
+        let sender = sender();
+        let mut rx = sender.subscribe();
+
+        let mut changes = PendingChanges::transaction(sender);
+        changes.push(CHANGE);
+        changes.abort();
+        changes.commit();
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -1,11 +1,13 @@
 //! Traits and data types for the IOx Catalog API.
 
+use crate::changes::CatalogChange;
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnSchema, ColumnType, CompactionLevel, Namespace, NamespaceId, NamespaceSchema,
-    ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool,
-    QueryPoolId, SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId,
-    TableSchema, Timestamp, TopicId, TopicMetadata,
+    Column, ColumnId, ColumnSchema, ColumnType, CompactionLevel, Namespace, NamespaceId,
+    NamespaceSchema, ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId,
+    PartitionKey, QueryPool, QueryPoolId, QueryPriority, QueryQuota, RollupRule, RollupRuleParams,
+    SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, TableSchema,
+    Timestamp, TopicId, TopicMetadata, TypeCoercionPolicy, WriteRateLimit,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -13,7 +15,9 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::{Debug, Display},
     sync::Arc,
+    time::Duration,
 };
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 /// An error wrapper detailing the reason for a compare-and-swap failure.
@@ -61,9 +65,36 @@ pub enum Error {
     #[snafu(display("namespace {} not found", id))]
     NamespaceNotFoundById { id: NamespaceId },
 
+    #[snafu(display("no soft-deleted namespace {} found", name))]
+    SoftDeletedNamespaceNotFound { name: String },
+
     #[snafu(display("table {} not found", id))]
     TableNotFound { id: TableId },
 
+    #[snafu(display("column {} not found", id))]
+    ColumnNotFound { id: ColumnId },
+
+    #[snafu(display("table {} in namespace {} has been deleted", name, namespace_id))]
+    TableSoftDeleted {
+        name: String,
+        namespace_id: NamespaceId,
+    },
+
+    #[snafu(display("column {} in table {} has been deleted", name, table_id))]
+    ColumnSoftDeleted { name: String, table_id: TableId },
+
+    #[snafu(display(
+        "tag {} is not part of the primary key of table {}: {}",
+        name,
+        table_id,
+        primary_key.join(", ")
+    ))]
+    NotInPrimaryKey {
+        name: String,
+        table_id: TableId,
+        primary_key: Vec<String>,
+    },
+
     #[snafu(display("partition {} not found", id))]
     PartitionNotFound { id: PartitionId },
 
@@ -130,6 +161,12 @@ pub enum Error {
 
     #[snafu(display("could not delete namespace: {source}"))]
     CouldNotDeleteNamespace { source: sqlx::Error },
+
+    #[snafu(display("invalid rollup rule: {reason}"))]
+    InvalidRollupRule { reason: String },
+
+    #[snafu(display("write rate limit must be positive, got {limit}"))]
+    InvalidWriteRateLimit { limit: i64 },
 }
 
 /// A specialized `Error` for Catalog errors
@@ -196,6 +233,16 @@ pub trait Catalog: Send + Sync + Debug + Display {
     /// must only be used for scaling.
     async fn start_transaction(&self) -> Result<Box<dyn Transaction>, Error>;
 
+    /// Creates a read-only [`Transaction`] whose reads all observe the same consistent snapshot
+    /// of the catalog, for reads that span many repositories such as a
+    /// [backup](crate::backup::backup).
+    ///
+    /// Defaults to [`start_transaction`](Self::start_transaction), for backends whose
+    /// transactions already provide this isolation.
+    async fn start_snapshot_transaction(&self) -> Result<Box<dyn Transaction>, Error> {
+        self.start_transaction().await
+    }
+
     /// Accesses the repositories without a transaction scope.
     async fn repositories(&self) -> Box<dyn RepoCollection>;
 
@@ -204,6 +251,11 @@ pub trait Catalog: Send + Sync + Debug + Display {
 
     /// Gets the time provider associated with this catalog.
     fn time_provider(&self) -> Arc<dyn TimeProvider>;
+
+    /// Subscribes to changes of namespaces, tables, columns, partitions and parquet files.
+    ///
+    /// See [`crate::changes`] for the delivery guarantees.
+    fn subscribe_changes(&self) -> broadcast::Receiver<CatalogChange>;
 }
 
 /// Secret module for [sealed traits].
@@ -305,6 +357,9 @@ pub trait RepoCollection: Send + Sync + Debug {
 
     /// Repository for [Parquet files](data_types::ParquetFile).
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo;
+
+    /// Repository for [rollup rules](data_types::RollupRule).
+    fn rollup_rules(&mut self) -> &mut dyn RollupRuleRepo;
 }
 
 /// Functions for working with topics in the catalog.
@@ -315,6 +370,14 @@ pub trait TopicMetadataRepo: Send + Sync {
 
     /// Gets the topic by its unique name
     async fn get_by_name(&mut self, name: &str) -> Result<Option<TopicMetadata>>;
+
+    /// List all topics.
+    async fn list(&mut self) -> Result<Vec<TopicMetadata>>;
+
+    /// Insert `topic` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, topic: &TopicMetadata) -> Result<()>;
 }
 
 /// Functions for working with query pools in the catalog.
@@ -322,6 +385,14 @@ pub trait TopicMetadataRepo: Send + Sync {
 pub trait QueryPoolRepo: Send + Sync {
     /// Creates the query pool in the catalog or gets the existing record by name.
     async fn create_or_get(&mut self, name: &str) -> Result<QueryPool>;
+
+    /// List all query pools.
+    async fn list(&mut self) -> Result<Vec<QueryPool>>;
+
+    /// Insert `query_pool` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, query_pool: &QueryPool) -> Result<()>;
 }
 
 /// Functions for working with namespaces in the catalog
@@ -365,23 +436,109 @@ pub trait NamespaceRepo: Send + Sync {
     /// Soft-delete a namespace by name
     async fn soft_delete(&mut self, name: &str) -> Result<()>;
 
+    /// Undo the [soft deletion](Self::soft_delete) of the namespace with the specified name,
+    /// provided it was deleted at or after `deleted_after`.
+    ///
+    /// Namespaces deleted before `deleted_after` are outside of the undelete grace window and
+    /// about to be purged, so they result in [`Error::SoftDeletedNamespaceNotFound`] just like
+    /// namespaces that are not soft-deleted.
+    async fn undelete(&mut self, name: &str, deleted_after: Timestamp) -> Result<Namespace>;
+
+    /// Permanently remove the soft-deleted namespace with the specified name, including its
+    /// tables, columns, partitions and parquet file records.
+    ///
+    /// The parquet files of the namespace are no longer referenced by the catalog afterwards,
+    /// so the object store garbage collector removes them.
+    async fn purge(&mut self, name: &str) -> Result<()>;
+
     /// Update the limit on the number of tables that can exist per namespace.
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
 
     /// Update the limit on the number of columns that can exist per table in a given namespace.
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+
+    /// Update the maximum number of lines per second the namespace accepts.
+    ///
+    /// [`None`] removes the limit. Returns [`Error::InvalidWriteRateLimit`] if `new_max` is not
+    /// positive.
+    async fn update_lines_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace>;
+
+    /// Update the maximum number of bytes of decoded, in-memory write batches per second the
+    /// namespace accepts.
+    ///
+    /// [`None`] removes the limit. Returns [`Error::InvalidWriteRateLimit`] if `new_max` is not
+    /// positive.
+    async fn update_batch_bytes_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace>;
+
+    /// Update the maximum number of queries the querier runs concurrently for the namespace.
+    ///
+    /// [`None`] removes the limit.
+    async fn update_concurrent_queries_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i32>,
+    ) -> Result<Namespace>;
+
+    /// Update the maximum number of bytes of querier memory the queries of the namespace may hold.
+    ///
+    /// [`None`] removes the limit.
+    async fn update_query_memory_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace>;
+
+    /// Update the [`TypeCoercionPolicy`] applied to writes to the namespace.
+    async fn update_type_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: TypeCoercionPolicy,
+    ) -> Result<Namespace>;
+
+    /// Update the [`QueryPriority`] of queries against the namespace that do not request one.
+    async fn update_default_query_priority(
+        &mut self,
+        name: &str,
+        priority: QueryPriority,
+    ) -> Result<Namespace>;
+
+    /// Insert `namespace` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, namespace: &Namespace) -> Result<()>;
 }
 
 /// Functions for working with tables in the catalog
 #[async_trait]
 pub trait TableRepo: Send + Sync {
     /// Creates the table in the catalog or get the existing record by name.
+    ///
+    /// Returns [`Error::TableSoftDeleted`] if a table of that name exists but was soft-deleted.
     async fn create_or_get(&mut self, name: &str, namespace_id: NamespaceId) -> Result<Table>;
 
-    /// get table by ID
+    /// Creates a table with the declared `primary_key`, see [`Table::primary_key`].
+    ///
+    /// Returns [`Error::NameExists`] if a table of that name exists, including a soft-deleted
+    /// one.
+    async fn create(
+        &mut self,
+        name: &str,
+        namespace_id: NamespaceId,
+        primary_key: &[String],
+    ) -> Result<Table>;
+
+    /// get table by ID, including soft-deleted tables
     async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>>;
 
-    /// get table by namespace ID and name
+    /// get table by namespace ID and name, excluding soft-deleted tables
     async fn get_by_namespace_and_name(
         &mut self,
         namespace_id: NamespaceId,
@@ -389,10 +546,24 @@ pub trait TableRepo: Send + Sync {
     ) -> Result<Option<Table>>;
 
     /// Lists all tables in the catalog for the given namespace id.
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>>;
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Table>>;
 
-    /// List all tables.
+    /// List all tables, including soft-deleted ones.
     async fn list(&mut self) -> Result<Vec<Table>>;
+
+    /// Mark the table as deleted and return it.
+    ///
+    /// The name of the table stays reserved. Deleting an already deleted table is a no-op.
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table>;
+
+    /// Insert `table` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, table: &Table) -> Result<()>;
 }
 
 /// Functions for working with columns in the catalog
@@ -424,13 +595,30 @@ pub trait ColumnRepo: Send + Sync {
     ) -> Result<Vec<Column>>;
 
     /// Lists all columns in the passed in namespace id.
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>>;
+    ///
+    /// Columns of soft-deleted tables count as deleted and report the deletion time of their table
+    /// unless they were deleted themselves.
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Column>>;
 
-    /// List all columns for the given table ID.
+    /// List all columns for the given table ID, excluding soft-deleted columns.
     async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>>;
 
-    /// List all columns.
+    /// List all columns, including soft-deleted ones.
     async fn list(&mut self) -> Result<Vec<Column>>;
+
+    /// Mark the column as deleted and return it.
+    ///
+    /// The name of the column stays reserved. Deleting an already deleted column is a no-op.
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column>;
+
+    /// Insert `column` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, column: &Column) -> Result<()>;
 }
 
 /// Functions for working with shards in the catalog
@@ -462,6 +650,11 @@ pub trait ShardRepo: Send + Sync {
         shard: ShardId,
         sequence_number: SequenceNumber,
     ) -> Result<()>;
+
+    /// Insert `shard` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, shard: &Shard) -> Result<()>;
 }
 
 /// Functions for working with IOx partitions in the catalog. Note that these are how IOx splits up
@@ -532,6 +725,34 @@ pub trait PartitionRepo: Send + Sync {
         partition_id: PartitionId,
     ) -> Result<Option<SkippedCompaction>>;
 
+    /// Try to acquire a time-limited lease on the given partition for `owner`.
+    ///
+    /// The lease is granted if the partition is not leased yet, if the existing lease has expired,
+    /// or if it is already held by `owner` (in which case it is renewed). A granted lease expires
+    /// `lease_duration` after the current time of the catalog's time provider.
+    ///
+    /// Returns `true` if `owner` holds the lease after this call.
+    async fn try_acquire_lease(
+        &mut self,
+        partition_id: PartitionId,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<bool>;
+
+    /// Release the lease on the given partition if it is held by `owner`.
+    ///
+    /// Releasing a lease that does not exist or that is held by another owner (e.g. because it
+    /// expired and was taken over) is a no-op.
+    async fn release_lease(&mut self, partition_id: PartitionId, owner: &str) -> Result<()>;
+
+    /// Record that the data of the given partition was rolled up to windows of
+    /// `rollup_interval_ns` nanoseconds.
+    async fn set_rollup_interval(
+        &mut self,
+        partition_id: PartitionId,
+        rollup_interval_ns: i64,
+    ) -> Result<Partition>;
+
     /// Return the N most recently created partitions.
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
 
@@ -543,6 +764,69 @@ pub trait PartitionRepo: Send + Sync {
         minimum_time: Timestamp,
         maximum_time: Option<Timestamp>,
     ) -> Result<Vec<PartitionId>>;
+
+    /// Insert `partition` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, partition: &Partition) -> Result<()>;
+}
+
+/// Functions for working with rollup rules in the catalog.
+#[async_trait]
+pub trait RollupRuleRepo: Send + Sync {
+    /// Create the rollup rule for the namespace or table given in `params`, replacing the
+    /// existing rule of that namespace or table.
+    async fn create_or_update(&mut self, params: RollupRuleParams) -> Result<RollupRule>;
+
+    /// Get the rule that applies to the given table: the rule of the table itself if there is
+    /// one, the namespace-wide rule otherwise.
+    async fn get_for_table(
+        &mut self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+    ) -> Result<Option<RollupRule>>;
+
+    /// List all rules of the given namespace, including the rules of its tables.
+    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<RollupRule>>;
+
+    /// Delete the namespace-wide rule (if `table_id` is [`None`]) or the rule of the given table.
+    ///
+    /// Deleting a rule that does not exist is a no-op.
+    async fn delete(&mut self, namespace_id: NamespaceId, table_id: Option<TableId>) -> Result<()>;
+}
+
+/// Check that `limit` is a write rate the router can enforce.
+pub(crate) fn validate_write_rate_limit(limit: Option<i64>) -> Result<()> {
+    match limit {
+        Some(limit) if limit <= 0 => InvalidWriteRateLimitSnafu { limit }.fail(),
+        _ => Ok(()),
+    }
+}
+
+/// Check that `params` describe a rollup rule the compactor can apply.
+pub(crate) fn validate_rollup_rule(params: &RollupRuleParams) -> Result<()> {
+    if params.interval_ns <= 0 {
+        return InvalidRollupRuleSnafu {
+            reason: format!("interval must be positive, got {}ns", params.interval_ns),
+        }
+        .fail();
+    }
+    if params.min_age_ns < 0 {
+        return InvalidRollupRuleSnafu {
+            reason: format!(
+                "minimum age must not be negative, got {}ns",
+                params.min_age_ns
+            ),
+        }
+        .fail();
+    }
+    if let Some((field_type, agg)) = params.invalid_field_type() {
+        return InvalidRollupRuleSnafu {
+            reason: format!("aggregate {agg} cannot be used for {field_type:?} fields"),
+        }
+        .fail();
+    }
+    Ok(())
 }
 
 /// Functions for working with parquet file pointers in the catalog
@@ -557,6 +841,16 @@ pub trait ParquetFileRepo: Send + Sync {
     /// Flag all parquet files for deletion that are older than their namespace's retention period.
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
 
+    /// Flag all parquet files for deletion that belong to a soft-deleted table.
+    async fn flag_for_delete_by_table_deletion(&mut self) -> Result<Vec<ParquetFileId>>;
+
+    /// Flag all parquet files for deletion that belong to a namespace soft-deleted before
+    /// `deleted_before`.
+    async fn flag_for_delete_by_namespace_deletion(
+        &mut self,
+        deleted_before: Timestamp,
+    ) -> Result<Vec<ParquetFileId>>;
+
     /// List all parquet files within a given namespace that are NOT marked as
     /// [`to_delete`](ParquetFile::to_delete).
     async fn list_by_namespace_not_to_delete(
@@ -607,6 +901,11 @@ pub trait ParquetFileRepo: Send + Sync {
         &mut self,
         object_store_id: Uuid,
     ) -> Result<Option<ParquetFile>>;
+
+    /// Insert `parquet_file` as is, including its ID.
+    ///
+    /// Only meant for [restoring a backup](crate::backup::restore).
+    async fn restore(&mut self, parquet_file: &ParquetFile) -> Result<()>;
 }
 
 /// Gets the namespace schema including all tables and columns.
@@ -650,8 +949,14 @@ where
     R: RepoCollection + ?Sized,
 {
     // get the columns first just in case someone else is creating schema while we're doing this.
-    let columns = repos.columns().list_by_namespace_id(namespace.id).await?;
-    let tables = repos.tables().list_by_namespace_id(namespace.id).await?;
+    let columns = repos
+        .columns()
+        .list_by_namespace_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+        .await?;
+    let tables = repos
+        .tables()
+        .list_by_namespace_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+        .await?;
 
     let mut namespace = NamespaceSchema::new(
         namespace.id,
@@ -660,15 +965,24 @@ where
         namespace.max_columns_per_table,
         namespace.max_tables,
         namespace.retention_period_ns,
-    );
+    )
+    .with_write_rate_limit(WriteRateLimit::from(&namespace))
+    .with_query_quota(QueryQuota::from(&namespace))
+    .with_type_coercion_policy(namespace.type_coercion_policy)
+    .with_default_query_priority(namespace.default_query_priority);
 
     let mut table_id_to_schema = BTreeMap::new();
     for t in tables {
-        table_id_to_schema.insert(t.id, (t.name, TableSchema::new(t.id)));
+        let schema = TableSchema::new_for_table(&t);
+        table_id_to_schema.insert(t.id, (t.name, schema));
     }
 
     for c in columns {
-        let (_, t) = table_id_to_schema.get_mut(&c.table_id).unwrap();
+        // the table may have been deleted since the columns were listed
+        let (_, t) = match table_id_to_schema.get_mut(&c.table_id) {
+            Some(v) => v,
+            None => continue,
+        };
         t.columns.insert(
             c.name,
             ColumnSchema {
@@ -734,8 +1048,14 @@ pub async fn list_schemas(
     // simply ignored at the end when joining to the namespace query result.
 
     // First fetch all the columns - this is the state snapshot of the catalog
-    // schemas.
-    let columns = repos.columns().list().await?;
+    // schemas. Soft-deleted columns are not part of any schema.
+    let columns = repos
+        .columns()
+        .list()
+        .await?
+        .into_iter()
+        .filter(|c| c.deleted_at.is_none())
+        .collect::<Vec<_>>();
 
     // Construct the set of table IDs these columns belong to.
     let retain_table_ids = columns.iter().map(|c| c.table_id).collect::<HashSet<_>>();
@@ -751,7 +1071,7 @@ pub async fn list_schemas(
         .await?
         .into_iter()
         .filter_map(|t| {
-            if !retain_table_ids.contains(&t.id) {
+            if !retain_table_ids.contains(&t.id) || t.deleted_at.is_some() {
                 return None;
             }
 
@@ -776,8 +1096,12 @@ pub async fn list_schemas(
 
     let mut joined = HashMap::<NamespaceId, NamespaceTables>::default();
     for column in columns {
-        // Resolve the table this column references
-        let table = tables.get(&column.table_id).expect("no table for column");
+        // Resolve the table this column references, skipping columns of
+        // soft-deleted tables.
+        let table = match tables.get(&column.table_id) {
+            Some(t) => t,
+            None => continue,
+        };
 
         let table_schema = joined
             // Find or create a record in the joined <NamespaceId, Tables> map
@@ -786,7 +1110,7 @@ pub async fn list_schemas(
             .or_default()
             // Fetch the schema record for this table, or create an empty one.
             .entry(table.name.clone())
-            .or_insert_with(|| TableSchema::new(column.table_id));
+            .or_insert_with(|| TableSchema::new_for_table(table));
 
         table_schema.add_column(&column);
     }
@@ -813,7 +1137,11 @@ pub async fn list_schemas(
                 v.max_columns_per_table,
                 v.max_tables,
                 v.retention_period_ns,
-            );
+            )
+            .with_write_rate_limit(WriteRateLimit::from(&v))
+            .with_query_quota(QueryQuota::from(&v))
+            .with_type_coercion_policy(v.type_coercion_policy)
+            .with_default_query_priority(v.default_query_priority);
             ns.tables = joined.remove(&v.id)?;
             Some((v, ns))
         });
@@ -832,7 +1160,8 @@ pub(crate) mod test_helpers {
     use ::test_helpers::{assert_contains, tracing::TracingCapture};
     use assert_matches::assert_matches;
     use data_types::{
-        ColumnId, ColumnSet, CompactionLevel, TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
+        ColumnId, ColumnSet, CompactionLevel, RollupAggregate, TRANSITION_SHARD_ID,
+        TRANSITION_SHARD_INDEX,
     };
     use futures::Future;
     use metric::{Attributes, DurationHistogram, Metric};
@@ -845,7 +1174,11 @@ pub(crate) mod test_helpers {
     {
         test_setup(clean_state().await).await;
         test_namespace_soft_deletion(clean_state().await).await;
+        test_namespace_undelete_and_purge(clean_state().await).await;
+        test_table_and_column_soft_deletion(clean_state().await).await;
         test_partitions_new_file_between(clean_state().await).await;
+        test_partition_lease(clean_state().await).await;
+        test_rollup_rules(clean_state().await).await;
         test_query_pool(clean_state().await).await;
         test_column(clean_state().await).await;
         test_partition(clean_state().await).await;
@@ -858,6 +1191,7 @@ pub(crate) mod test_helpers {
         test_list_schemas(clean_state().await).await;
         test_list_schemas_soft_deleted_rows(clean_state().await).await;
         test_delete_namespace(clean_state().await).await;
+        test_change_feed(clean_state().await).await;
 
         let catalog = clean_state().await;
         test_topic(Arc::clone(&catalog)).await;
@@ -871,6 +1205,10 @@ pub(crate) mod test_helpers {
         test_table(Arc::clone(&catalog)).await;
         assert_metric_hit(&catalog.metrics(), "table_create_or_get");
 
+        let catalog = clean_state().await;
+        test_table_primary_key(Arc::clone(&catalog)).await;
+        assert_metric_hit(&catalog.metrics(), "table_create");
+
         let catalog = clean_state().await;
         test_column(Arc::clone(&catalog)).await;
         assert_metric_hit(&catalog.metrics(), "column_create_or_get");
@@ -1022,6 +1360,152 @@ pub(crate) mod test_helpers {
             .expect("namespace should be updateable");
         assert_eq!(NEW_COLUMN_LIMIT, modified.max_columns_per_table);
 
+        assert_eq!(modified.max_lines_per_second, None);
+        assert_eq!(modified.max_batch_bytes_per_second, None);
+
+        let modified = repos
+            .namespaces()
+            .update_lines_per_second_limit(namespace_name, Some(1_000))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_lines_per_second, Some(1_000));
+        let modified = repos
+            .namespaces()
+            .update_batch_bytes_per_second_limit(namespace_name, Some(42_000))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_lines_per_second, Some(1_000));
+        assert_eq!(modified.max_batch_bytes_per_second, Some(42_000));
+        let schema = get_schema_by_name(
+            namespace_name,
+            repos.deref_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        .expect("schema should be readable");
+        assert_eq!(
+            schema.write_rate_limit,
+            WriteRateLimit {
+                lines_per_second: Some(1_000),
+                batch_bytes_per_second: Some(42_000),
+            }
+        );
+        let modified = repos
+            .namespaces()
+            .update_lines_per_second_limit(namespace_name, None)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_lines_per_second, None);
+        assert_eq!(modified.max_batch_bytes_per_second, Some(42_000));
+        let err = repos
+            .namespaces()
+            .update_batch_bytes_per_second_limit("does_not_exist", Some(1))
+            .await
+            .expect_err("unknown namespace should error");
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+        for invalid in [0, -1] {
+            let err = repos
+                .namespaces()
+                .update_lines_per_second_limit(namespace_name, Some(invalid))
+                .await
+                .expect_err("non-positive limit should error");
+            assert!(matches!(err, Error::InvalidWriteRateLimit { .. }));
+            let err = repos
+                .namespaces()
+                .update_batch_bytes_per_second_limit(namespace_name, Some(invalid))
+                .await
+                .expect_err("non-positive limit should error");
+            assert!(matches!(err, Error::InvalidWriteRateLimit { .. }));
+        }
+
+        assert_eq!(modified.max_concurrent_queries, None);
+        assert_eq!(modified.max_query_memory_bytes, None);
+
+        let modified = repos
+            .namespaces()
+            .update_concurrent_queries_limit(namespace_name, Some(8))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_concurrent_queries, Some(8));
+        let modified = repos
+            .namespaces()
+            .update_query_memory_limit(namespace_name, Some(1 << 30))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_concurrent_queries, Some(8));
+        assert_eq!(modified.max_query_memory_bytes, Some(1 << 30));
+        let schema = get_schema_by_name(
+            namespace_name,
+            repos.deref_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        .expect("schema should be readable");
+        assert_eq!(
+            schema.query_quota,
+            QueryQuota {
+                max_concurrent_queries: Some(8),
+                max_memory_bytes: Some(1 << 30),
+            }
+        );
+        let modified = repos
+            .namespaces()
+            .update_concurrent_queries_limit(namespace_name, None)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_concurrent_queries, None);
+        assert_eq!(modified.max_query_memory_bytes, Some(1 << 30));
+        let err = repos
+            .namespaces()
+            .update_query_memory_limit("does_not_exist", Some(1))
+            .await
+            .expect_err("unknown namespace should error");
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        assert_eq!(modified.type_coercion_policy, TypeCoercionPolicy::Strict);
+        let modified = repos
+            .namespaces()
+            .update_type_coercion_policy(namespace_name, TypeCoercionPolicy::Widen)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.type_coercion_policy, TypeCoercionPolicy::Widen);
+        let schema = get_schema_by_name(
+            namespace_name,
+            repos.deref_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        .expect("schema should be readable");
+        assert_eq!(schema.type_coercion_policy, TypeCoercionPolicy::Widen);
+        let err = repos
+            .namespaces()
+            .update_type_coercion_policy("does_not_exist", TypeCoercionPolicy::LosslessCast)
+            .await
+            .expect_err("unknown namespace should error");
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        assert_eq!(modified.default_query_priority, QueryPriority::Normal);
+        let modified = repos
+            .namespaces()
+            .update_default_query_priority(namespace_name, QueryPriority::Interactive)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.default_query_priority, QueryPriority::Interactive);
+        let schema = get_schema_by_name(
+            namespace_name,
+            repos.deref_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        .expect("schema should be readable");
+        assert_eq!(schema.default_query_priority, QueryPriority::Interactive);
+        let err = repos
+            .namespaces()
+            .update_default_query_priority("does_not_exist", QueryPriority::Batch)
+            .await
+            .expect_err("unknown namespace should error");
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
         const NEW_RETENTION_PERIOD_NS: i64 = 5 * 60 * 60 * 1000 * 1000 * 1000;
         let modified = repos
             .namespaces()
@@ -1253,57 +1737,215 @@ pub(crate) mod test_helpers {
         assert_string_set_eq(got, ["active-ns"]);
     }
 
-    // Assert the set of strings "a" is equal to the set "b", tolerating
-    // duplicates.
-    #[track_caller]
-    fn assert_string_set_eq<T, U>(a: impl IntoIterator<Item = T>, b: impl IntoIterator<Item = U>)
-    where
-        T: Into<String>,
-        U: Into<String>,
-    {
-        let mut a = a.into_iter().map(Into::into).collect::<Vec<String>>();
-        a.sort_unstable();
-        let mut b = b.into_iter().map(Into::into).collect::<Vec<String>>();
-        b.sort_unstable();
-        assert_eq!(a, b);
-    }
-
-    async fn test_table(catalog: Arc<dyn Catalog>) {
+    async fn test_namespace_undelete_and_purge(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let topic = repos.topics().create_or_get("foo").await.unwrap();
         let pool = repos.query_pools().create_or_get("foo").await.unwrap();
-        let namespace = repos
-            .namespaces()
-            .create("namespace_table_test", None, topic.id, pool.id)
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
             .await
             .unwrap();
 
-        // test we can create or get a table
-        let t = repos
-            .tables()
-            .create_or_get("test_table", namespace.id)
+        let namespace = repos
+            .namespaces()
+            .create("purged-ns", None, topic.id, pool.id)
             .await
             .unwrap();
-        let tt = repos
-            .tables()
-            .create_or_get("test_table", namespace.id)
+        let other_namespace = repos
+            .namespaces()
+            .create("other-ns", None, topic.id, pool.id)
             .await
             .unwrap();
-        assert!(t.id > TableId::new(0));
-        assert_eq!(t, tt);
-
-        // get by id
-        assert_eq!(t, repos.tables().get_by_id(t.id).await.unwrap().unwrap());
-        assert!(repos
-            .tables()
-            .get_by_id(TableId::new(i64::MAX))
-            .await
-            .unwrap()
-            .is_none());
 
-        let tables = repos
-            .tables()
-            .list_by_namespace_id(namespace.id)
+        let mut parquet_files = vec![];
+        for ns in [&namespace, &other_namespace] {
+            let table = repos.tables().create_or_get("cpu", ns.id).await.unwrap();
+            let column = repos
+                .columns()
+                .create_or_get("time", table.id, ColumnType::Time)
+                .await
+                .unwrap();
+            let partition = repos
+                .partitions()
+                .create_or_get("one".into(), shard.id, table.id)
+                .await
+                .unwrap();
+            let parquet_file = repos
+                .parquet_files()
+                .create(ParquetFileParams {
+                    shard_id: shard.id,
+                    namespace_id: ns.id,
+                    table_id: table.id,
+                    partition_id: partition.id,
+                    object_store_id: Uuid::new_v4(),
+                    max_sequence_number: SequenceNumber::new(140),
+                    min_time: Timestamp::new(1),
+                    max_time: Timestamp::new(10),
+                    file_size_bytes: 1337,
+                    row_count: 0,
+                    compaction_level: CompactionLevel::Initial,
+                    created_at: Timestamp::new(1),
+                    column_set: ColumnSet::new([column.id]),
+                    max_l0_created_at: Timestamp::new(1),
+                })
+                .await
+                .unwrap();
+            parquet_files.push(parquet_file);
+        }
+
+        // only soft-deleted namespaces can be undeleted or purged
+        assert_matches!(
+            repos
+                .namespaces()
+                .undelete("purged-ns", Timestamp::new(0))
+                .await,
+            Err(Error::SoftDeletedNamespaceNotFound { .. })
+        );
+        assert_matches!(
+            repos.namespaces().purge("purged-ns").await,
+            Err(Error::SoftDeletedNamespaceNotFound { .. })
+        );
+
+        repos.namespaces().soft_delete("purged-ns").await.unwrap();
+        let deleted_at = repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::OnlyDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should be soft-deleted")
+            .deleted_at
+            .unwrap();
+
+        // outside of the grace window
+        assert_matches!(
+            repos
+                .namespaces()
+                .undelete("purged-ns", Timestamp::new(deleted_at.get() + 1))
+                .await,
+            Err(Error::SoftDeletedNamespaceNotFound { .. })
+        );
+
+        // within the grace window
+        let undeleted = repos
+            .namespaces()
+            .undelete("purged-ns", deleted_at)
+            .await
+            .unwrap();
+        assert_eq!(undeleted.id, namespace.id);
+        assert_eq!(undeleted.deleted_at, None);
+
+        repos.namespaces().soft_delete("purged-ns").await.unwrap();
+        let deleted_at = repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::OnlyDeleted)
+            .await
+            .unwrap()
+            .unwrap()
+            .deleted_at
+            .unwrap();
+
+        // files are only flagged once the namespace was deleted long enough ago
+        assert!(repos
+            .parquet_files()
+            .flag_for_delete_by_namespace_deletion(deleted_at)
+            .await
+            .unwrap()
+            .is_empty());
+        let flagged = repos
+            .parquet_files()
+            .flag_for_delete_by_namespace_deletion(Timestamp::new(deleted_at.get() + 1))
+            .await
+            .unwrap();
+        assert_eq!(flagged, vec![parquet_files[0].id]);
+
+        repos.namespaces().purge("purged-ns").await.unwrap();
+        assert!(repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::AllRows)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repos
+            .tables()
+            .list_by_namespace_id(namespace.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(!repos
+            .parquet_files()
+            .exist(parquet_files[0].id)
+            .await
+            .unwrap());
+
+        // the other namespace is untouched
+        assert_eq!(
+            repos
+                .tables()
+                .list_by_namespace_id(other_namespace.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(repos
+            .parquet_files()
+            .exist(parquet_files[1].id)
+            .await
+            .unwrap());
+    }
+
+    // Assert the set of strings "a" is equal to the set "b", tolerating
+    // duplicates.
+    #[track_caller]
+    fn assert_string_set_eq<T, U>(a: impl IntoIterator<Item = T>, b: impl IntoIterator<Item = U>)
+    where
+        T: Into<String>,
+        U: Into<String>,
+    {
+        let mut a = a.into_iter().map(Into::into).collect::<Vec<String>>();
+        a.sort_unstable();
+        let mut b = b.into_iter().map(Into::into).collect::<Vec<String>>();
+        b.sort_unstable();
+        assert_eq!(a, b);
+    }
+
+    async fn test_table(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("foo").await.unwrap();
+        let pool = repos.query_pools().create_or_get("foo").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("namespace_table_test", None, topic.id, pool.id)
+            .await
+            .unwrap();
+
+        // test we can create or get a table
+        let t = repos
+            .tables()
+            .create_or_get("test_table", namespace.id)
+            .await
+            .unwrap();
+        let tt = repos
+            .tables()
+            .create_or_get("test_table", namespace.id)
+            .await
+            .unwrap();
+        assert!(t.id > TableId::new(0));
+        assert_eq!(t, tt);
+
+        // get by id
+        assert_eq!(t, repos.tables().get_by_id(t.id).await.unwrap().unwrap());
+        assert!(repos
+            .tables()
+            .get_by_id(TableId::new(i64::MAX))
+            .await
+            .unwrap()
+            .is_none());
+
+        let tables = repos
+            .tables()
+            .list_by_namespace_id(namespace.id, SoftDeletedRows::AllRows)
             .await
             .unwrap();
         assert_eq!(vec![t.clone()], tables);
@@ -1461,7 +2103,7 @@ pub(crate) mod test_helpers {
 
         let columns = repos
             .columns()
-            .list_by_namespace_id(namespace.id)
+            .list_by_namespace_id(namespace.id, SoftDeletedRows::AllRows)
             .await
             .unwrap();
 
@@ -1542,6 +2184,260 @@ pub(crate) mod test_helpers {
             .expect("delete namespace should succeed");
     }
 
+    async fn test_table_primary_key(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("foo").await.unwrap();
+        let pool = repos.query_pools().create_or_get("foo").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("namespace_table_primary_key_test", None, topic.id, pool.id)
+            .await
+            .unwrap();
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
+            .await
+            .unwrap();
+
+        let primary_key = vec!["region".to_string(), "host".to_string(), "time".to_string()];
+        let t = repos
+            .tables()
+            .create("declared", namespace.id, &primary_key)
+            .await
+            .unwrap();
+        assert_eq!(t.primary_key, primary_key);
+        assert_eq!(t, repos.tables().get_by_id(t.id).await.unwrap().unwrap());
+        assert_eq!(repos.tables().list().await.unwrap(), [t.clone()]);
+
+        // the name must not exist, even for tables without a primary key
+        let err = repos
+            .tables()
+            .create("declared", namespace.id, &primary_key)
+            .await
+            .expect_err("table exists");
+        assert_matches!(err, Error::NameExists { .. });
+        let derived = repos
+            .tables()
+            .create_or_get("derived", namespace.id)
+            .await
+            .unwrap();
+        assert!(derived.primary_key.is_empty());
+        let err = repos
+            .tables()
+            .create("derived", namespace.id, &primary_key)
+            .await
+            .expect_err("table exists");
+        assert_matches!(err, Error::NameExists { .. });
+
+        // new partitions are sorted by the primary key
+        let partition = repos
+            .partitions()
+            .create_or_get("p1".into(), shard.id, t.id)
+            .await
+            .unwrap();
+        assert_eq!(partition.sort_key, primary_key);
+        let partition = repos
+            .partitions()
+            .create_or_get("p1".into(), shard.id, derived.id)
+            .await
+            .unwrap();
+        assert!(partition.sort_key.is_empty());
+
+        // the primary key is part of the schema
+        repos
+            .columns()
+            .create_or_get("region", t.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let schema = get_schema_by_name(
+            &namespace.name,
+            repos.as_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        .unwrap();
+        assert_eq!(schema.tables["declared"].primary_key, primary_key);
+        assert!(schema.tables["derived"].primary_key.is_empty());
+
+        // the table limit applies
+        repos
+            .namespaces()
+            .update_table_limit(&namespace.name, 2)
+            .await
+            .unwrap();
+        let err = repos
+            .tables()
+            .create("another", namespace.id, &primary_key)
+            .await
+            .expect_err("table limit reached");
+        assert_matches!(err, Error::TableCreateLimitError { .. });
+
+        drop(repos);
+        let schemas = list_schemas(&*catalog).await.unwrap().collect::<Vec<_>>();
+        assert_eq!(schemas[0].1.tables["declared"].primary_key, primary_key);
+    }
+
+    async fn test_change_feed(catalog: Arc<dyn Catalog>) {
+        let mut changes = catalog.subscribe_changes();
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("foo").await.unwrap();
+        let pool = repos.query_pools().create_or_get("foo").await.unwrap();
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
+            .await
+            .unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("namespace_change_feed_test", None, topic.id, pool.id)
+            .await
+            .unwrap();
+
+        // Catalogs may start listening for changes in the background, so retry until the first
+        // change arrives.
+        let mut max_tables = 1;
+        loop {
+            repos
+                .namespaces()
+                .update_table_limit(&namespace.name, max_tables)
+                .await
+                .unwrap();
+            let want = CatalogChange::Namespace { id: namespace.id };
+            if wait_for_change(&mut changes, want, Duration::from_millis(100)).await {
+                break;
+            }
+            max_tables += 1;
+            assert!(max_tables < 100, "no change received");
+        }
+
+        let table = repos
+            .tables()
+            .create_or_get("table", namespace.id)
+            .await
+            .unwrap();
+        let table_change = CatalogChange::Table {
+            namespace_id: namespace.id,
+            id: table.id,
+        };
+        assert_change(&mut changes, table_change).await;
+
+        let column = repos
+            .columns()
+            .create_or_get("column", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        assert_change(&mut changes, table_change).await;
+        repos.columns().soft_delete(column.id).await.unwrap();
+        assert_change(&mut changes, table_change).await;
+
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), shard.id, table.id)
+            .await
+            .unwrap();
+        let partition_change = CatalogChange::Partition {
+            table_id: table.id,
+            id: partition.id,
+        };
+        assert_change(&mut changes, partition_change).await;
+        repos
+            .partitions()
+            .cas_sort_key(partition.id, None, &["column", "time"])
+            .await
+            .unwrap();
+        assert_change(&mut changes, partition_change).await;
+
+        let parquet_file = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                shard_id: shard.id,
+                namespace_id: namespace.id,
+                table_id: table.id,
+                partition_id: partition.id,
+                object_store_id: Uuid::new_v4(),
+                max_sequence_number: SequenceNumber::new(1),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(10),
+                file_size_bytes: 1337,
+                row_count: 0,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(1),
+                column_set: ColumnSet::new([]),
+                max_l0_created_at: Timestamp::new(1),
+            })
+            .await
+            .unwrap();
+        let parquet_file_change = CatalogChange::ParquetFile {
+            table_id: table.id,
+            partition_id: partition.id,
+        };
+        assert_change(&mut changes, parquet_file_change).await;
+        repos
+            .parquet_files()
+            .flag_for_delete(parquet_file.id)
+            .await
+            .unwrap();
+        assert_change(&mut changes, parquet_file_change).await;
+
+        repos.tables().soft_delete(table.id).await.unwrap();
+        assert_change(&mut changes, table_change).await;
+
+        // changes made in a transaction are published once it commits
+        drop(repos);
+        let namespace_change = CatalogChange::Namespace { id: namespace.id };
+        let mut txn = catalog.start_transaction().await.unwrap();
+        txn.namespaces()
+            .update_retention_period(&namespace.name, Some(42))
+            .await
+            .unwrap();
+        assert!(!wait_for_change(&mut changes, namespace_change, Duration::from_millis(100)).await);
+        txn.commit().await.unwrap();
+        assert_change(&mut changes, namespace_change).await;
+
+        // ... and dropped if it aborts
+        let mut txn = catalog.start_transaction().await.unwrap();
+        txn.namespaces()
+            .update_retention_period(&namespace.name, None)
+            .await
+            .unwrap();
+        txn.abort().await.unwrap();
+        assert!(!wait_for_change(&mut changes, namespace_change, Duration::from_millis(100)).await);
+
+        let mut repos = catalog.repositories().await;
+        repos
+            .namespaces()
+            .soft_delete(&namespace.name)
+            .await
+            .unwrap();
+        assert_change(&mut changes, namespace_change).await;
+    }
+
+    /// Receive changes until `want` arrives, returning false if it does not within `timeout`.
+    async fn wait_for_change(
+        changes: &mut broadcast::Receiver<CatalogChange>,
+        want: CatalogChange,
+        timeout: Duration,
+    ) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                match changes.recv().await {
+                    Ok(change) if change == want => return,
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => panic!("change feed closed"),
+                }
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    async fn assert_change(changes: &mut broadcast::Receiver<CatalogChange>, want: CatalogChange) {
+        assert!(
+            wait_for_change(changes, want, Duration::from_secs(10)).await,
+            "change {want:?} not received"
+        );
+    }
+
     async fn test_partition(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let topic = repos.topics().create_or_get("foo").await.unwrap();
@@ -1801,38 +2697,308 @@ pub(crate) mod test_helpers {
             "There should be no skipped compation",
         );
 
-        let skipped_compactions = repos.partitions().list_skipped_compactions().await.unwrap();
-        assert!(
-            skipped_compactions.is_empty(),
-            "Expected no skipped compactions, got: {skipped_compactions:?}"
+        let skipped_compactions = repos.partitions().list_skipped_compactions().await.unwrap();
+        assert!(
+            skipped_compactions.is_empty(),
+            "Expected no skipped compactions, got: {skipped_compactions:?}"
+        );
+
+        let recent = repos
+            .partitions()
+            .most_recent_n(10)
+            .await
+            .expect("should list most recent");
+        assert_eq!(recent.len(), 3);
+
+        let recent = repos
+            .partitions()
+            .most_recent_n(3)
+            .await
+            .expect("should list most recent");
+        assert_eq!(recent.len(), 3);
+
+        let recent = repos
+            .partitions()
+            .most_recent_n(2)
+            .await
+            .expect("should list most recent");
+        assert_eq!(recent.len(), 2);
+
+        repos
+            .namespaces()
+            .soft_delete("namespace_partition_test")
+            .await
+            .expect("delete namespace should succeed");
+    }
+
+    /// Soft-delete a table and a column of another table and assert that they are hidden from
+    /// schema lookups, keep their names reserved and that the parquet files of the deleted table
+    /// are flagged for deletion.
+    async fn test_table_and_column_soft_deletion(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("foo").await.unwrap();
+        let pool = repos.query_pools().create_or_get("foo").await.unwrap();
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
+            .await
+            .unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("namespace_table_soft_delete_test", None, topic.id, pool.id)
+            .await
+            .unwrap();
+
+        let deleted_table = repos
+            .tables()
+            .create_or_get("deleted", namespace.id)
+            .await
+            .unwrap();
+        let deleted_table_column = repos
+            .columns()
+            .create_or_get("col", deleted_table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create_or_get("active", namespace.id)
+            .await
+            .unwrap();
+        let active_column = repos
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+        let deleted_column = repos
+            .columns()
+            .create_or_get("gone", table.id, ColumnType::F64)
+            .await
+            .unwrap();
+
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), shard.id, deleted_table.id)
+            .await
+            .unwrap();
+        let other_partition = repos
+            .partitions()
+            .create_or_get("one".into(), shard.id, table.id)
+            .await
+            .unwrap();
+        let parquet_file_params = ParquetFileParams {
+            shard_id: shard.id,
+            namespace_id: namespace.id,
+            table_id: partition.table_id,
+            partition_id: partition.id,
+            object_store_id: Uuid::new_v4(),
+            max_sequence_number: SequenceNumber::new(140),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(10),
+            file_size_bytes: 1337,
+            row_count: 0,
+            compaction_level: CompactionLevel::Initial,
+            created_at: Timestamp::new(1),
+            column_set: ColumnSet::new([deleted_table_column.id]),
+            max_l0_created_at: Timestamp::new(1),
+        };
+        let parquet_file = repos
+            .parquet_files()
+            .create(parquet_file_params.clone())
+            .await
+            .unwrap();
+        let other_parquet_file = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                table_id: other_partition.table_id,
+                partition_id: other_partition.id,
+                object_store_id: Uuid::new_v4(),
+                column_set: ColumnSet::new([active_column.id, deleted_column.id]),
+                ..parquet_file_params
+            })
+            .await
+            .unwrap();
+
+        // nothing is flagged before the table is deleted
+        assert!(repos
+            .parquet_files()
+            .flag_for_delete_by_table_deletion()
+            .await
+            .unwrap()
+            .is_empty());
+
+        let t = repos.tables().soft_delete(deleted_table.id).await.unwrap();
+        assert_eq!(t.id, deleted_table.id);
+        let deleted_at = t.deleted_at.expect("table should be marked as deleted");
+        let c = repos
+            .columns()
+            .soft_delete(deleted_column.id)
+            .await
+            .unwrap();
+        assert_eq!(c.id, deleted_column.id);
+        assert!(c.deleted_at.is_some());
+
+        // deleting again is a no-op
+        let t = repos.tables().soft_delete(deleted_table.id).await.unwrap();
+        assert_eq!(t.deleted_at, Some(deleted_at));
+
+        // unknown IDs
+        let err = repos
+            .tables()
+            .soft_delete(TableId::new(i64::MAX))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TableNotFound { .. });
+        let err = repos
+            .columns()
+            .soft_delete(ColumnId::new(i64::MAX))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::ColumnNotFound { .. });
+
+        // table lookups
+        assert!(repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, "deleted")
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            repos
+                .tables()
+                .get_by_id(deleted_table.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .deleted_at,
+            Some(deleted_at)
+        );
+        let names = |tables: Vec<Table>| tables.into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(
+                repos
+                    .tables()
+                    .list_by_namespace_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+                    .await
+                    .unwrap()
+            ),
+            ["active"]
+        );
+        assert_eq!(
+            names(
+                repos
+                    .tables()
+                    .list_by_namespace_id(namespace.id, SoftDeletedRows::OnlyDeleted)
+                    .await
+                    .unwrap()
+            ),
+            ["deleted"]
+        );
+        assert_eq!(
+            repos
+                .tables()
+                .list_by_namespace_id(namespace.id, SoftDeletedRows::AllRows)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // column lookups, columns of deleted tables count as deleted
+        let ids = |columns: Vec<Column>| {
+            let mut ids = columns.into_iter().map(|c| c.id).collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(repos
+                .columns()
+                .list_by_namespace_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+                .await
+                .unwrap()),
+            [active_column.id]
+        );
+        assert_eq!(
+            ids(repos
+                .columns()
+                .list_by_namespace_id(namespace.id, SoftDeletedRows::OnlyDeleted)
+                .await
+                .unwrap()),
+            [deleted_table_column.id, deleted_column.id]
+        );
+        assert_eq!(
+            ids(repos.columns().list_by_table_id(table.id).await.unwrap()),
+            [active_column.id]
         );
 
-        let recent = repos
-            .partitions()
-            .most_recent_n(10)
+        // names stay reserved
+        let err = repos
+            .tables()
+            .create_or_get("deleted", namespace.id)
             .await
-            .expect("should list most recent");
-        assert_eq!(recent.len(), 3);
-
-        let recent = repos
-            .partitions()
-            .most_recent_n(3)
+            .unwrap_err();
+        assert_matches!(err, Error::TableSoftDeleted { .. });
+        let err = repos
+            .columns()
+            .create_or_get("gone", table.id, ColumnType::F64)
             .await
-            .expect("should list most recent");
-        assert_eq!(recent.len(), 3);
+            .unwrap_err();
+        assert_matches!(err, Error::ColumnSoftDeleted { .. });
+        let err = repos
+            .columns()
+            .create_or_get_many_unchecked(table.id, HashMap::from([("gone", ColumnType::F64)]))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::ColumnSoftDeleted { .. });
 
-        let recent = repos
-            .partitions()
-            .most_recent_n(2)
+        // the schema only contains what is left
+        let schema = get_schema_by_name(
+            &namespace.name,
+            repos.deref_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        .unwrap();
+        assert_eq!(schema.tables.keys().collect::<Vec<_>>(), ["active"]);
+        assert_eq!(
+            schema.tables["active"].columns.keys().collect::<Vec<_>>(),
+            ["time"]
+        );
+        let schema = get_table_schema_by_id(table.id, repos.deref_mut())
             .await
-            .expect("should list most recent");
-        assert_eq!(recent.len(), 2);
+            .unwrap();
+        assert_eq!(schema.columns.keys().collect::<Vec<_>>(), ["time"]);
 
-        repos
-            .namespaces()
-            .soft_delete("namespace_partition_test")
+        // only the files of the deleted table are flagged
+        let flagged = repos
+            .parquet_files()
+            .flag_for_delete_by_table_deletion()
             .await
-            .expect("delete namespace should succeed");
+            .unwrap();
+        assert_eq!(flagged, [parquet_file.id]);
+        assert!(repos
+            .parquet_files()
+            .flag_for_delete_by_table_deletion()
+            .await
+            .unwrap()
+            .is_empty());
+        let files = repos
+            .parquet_files()
+            .list_by_namespace_not_to_delete(namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(files, [other_parquet_file]);
+
+        drop(repos);
+        let schemas = list_schemas(&*catalog).await.unwrap().collect::<Vec<_>>();
+        assert_eq!(schemas.len(), 1);
+        assert_eq!(schemas[0].1.tables.keys().collect::<Vec<_>>(), ["active"]);
+        assert_eq!(
+            schemas[0].1.tables["active"]
+                .columns
+                .keys()
+                .collect::<Vec<_>>(),
+            ["time"]
+        );
     }
 
     async fn test_parquet_file(catalog: Arc<dyn Catalog>) {
@@ -2312,6 +3478,319 @@ pub(crate) mod test_helpers {
         assert_eq!(ids, vec![parquet_file_2.id]);
     }
 
+    async fn test_partition_lease(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("lease").await.unwrap();
+        let pool = repos.query_pools().create_or_get("lease").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("test_partition_lease", None, topic.id, pool.id)
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create_or_get("test_table_for_lease", namespace.id)
+            .await
+            .unwrap();
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), shard.id, table.id)
+            .await
+            .unwrap();
+        let other_partition = repos
+            .partitions()
+            .create_or_get("two".into(), shard.id, table.id)
+            .await
+            .unwrap();
+
+        let long = Duration::from_secs(3_600);
+
+        // first owner gets the lease
+        assert!(repos
+            .partitions()
+            .try_acquire_lease(partition.id, "a", long)
+            .await
+            .unwrap());
+
+        // second owner is rejected while the lease is valid
+        assert!(!repos
+            .partitions()
+            .try_acquire_lease(partition.id, "b", long)
+            .await
+            .unwrap());
+
+        // leases are per partition
+        assert!(repos
+            .partitions()
+            .try_acquire_lease(other_partition.id, "b", long)
+            .await
+            .unwrap());
+
+        // owner can renew its own lease
+        assert!(repos
+            .partitions()
+            .try_acquire_lease(partition.id, "a", long)
+            .await
+            .unwrap());
+
+        // releasing someone else's lease is a no-op
+        repos
+            .partitions()
+            .release_lease(partition.id, "b")
+            .await
+            .unwrap();
+        assert!(!repos
+            .partitions()
+            .try_acquire_lease(partition.id, "b", long)
+            .await
+            .unwrap());
+
+        // after releasing, the partition can be leased by another owner
+        repos
+            .partitions()
+            .release_lease(partition.id, "a")
+            .await
+            .unwrap();
+        assert!(repos
+            .partitions()
+            .try_acquire_lease(partition.id, "b", Duration::ZERO)
+            .await
+            .unwrap());
+
+        // an expired lease can be taken over
+        assert!(repos
+            .partitions()
+            .try_acquire_lease(partition.id, "a", long)
+            .await
+            .unwrap());
+        assert!(!repos
+            .partitions()
+            .try_acquire_lease(partition.id, "b", long)
+            .await
+            .unwrap());
+
+        // releasing an unknown lease is fine
+        repos
+            .partitions()
+            .release_lease(PartitionId::new(i64::MAX), "a")
+            .await
+            .unwrap();
+    }
+
+    async fn test_rollup_rules(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("rollup").await.unwrap();
+        let pool = repos.query_pools().create_or_get("rollup").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("test_rollup_rules", None, topic.id, pool.id)
+            .await
+            .unwrap();
+        let other_namespace = repos
+            .namespaces()
+            .create("test_rollup_rules_other", None, topic.id, pool.id)
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create_or_get("test_table_for_rollup", namespace.id)
+            .await
+            .unwrap();
+        let other_table = repos
+            .tables()
+            .create_or_get("test_other_table_for_rollup", namespace.id)
+            .await
+            .unwrap();
+
+        let namespace_params = RollupRuleParams {
+            namespace_id: namespace.id,
+            table_id: None,
+            interval_ns: 300_000_000_000,
+            min_age_ns: 30 * 86_400_000_000_000,
+            float_aggregate: RollupAggregate::Mean,
+            integer_aggregate: RollupAggregate::Max,
+            unsigned_aggregate: RollupAggregate::Max,
+            boolean_aggregate: RollupAggregate::Last,
+            string_aggregate: RollupAggregate::Last,
+        };
+
+        // no rules yet
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .get_for_table(namespace.id, table.id)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // the namespace-wide rule applies to all tables
+        let namespace_rule = repos
+            .rollup_rules()
+            .create_or_update(namespace_params.clone())
+            .await
+            .unwrap();
+        assert_eq!(namespace_rule.table_id, None);
+        assert_eq!(namespace_rule.float_aggregate, RollupAggregate::Mean);
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .get_for_table(namespace.id, table.id)
+                .await
+                .unwrap(),
+            Some(namespace_rule.clone())
+        );
+
+        // a table rule takes precedence
+        let table_rule = repos
+            .rollup_rules()
+            .create_or_update(RollupRuleParams {
+                table_id: Some(table.id),
+                interval_ns: 3_600_000_000_000,
+                ..namespace_params.clone()
+            })
+            .await
+            .unwrap();
+        assert_ne!(table_rule.id, namespace_rule.id);
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .get_for_table(namespace.id, table.id)
+                .await
+                .unwrap(),
+            Some(table_rule.clone())
+        );
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .get_for_table(namespace.id, other_table.id)
+                .await
+                .unwrap(),
+            Some(namespace_rule.clone())
+        );
+
+        // updating replaces the existing rule
+        let updated = repos
+            .rollup_rules()
+            .create_or_update(RollupRuleParams {
+                float_aggregate: RollupAggregate::Min,
+                ..namespace_params.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(updated.id, namespace_rule.id);
+        assert_eq!(updated.float_aggregate, RollupAggregate::Min);
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .list_by_namespace_id(namespace.id)
+                .await
+                .unwrap(),
+            vec![updated.clone(), table_rule.clone()]
+        );
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .list_by_namespace_id(other_namespace.id)
+                .await
+                .unwrap(),
+            vec![]
+        );
+
+        // rules that would change the type of a field are rejected
+        let err = repos
+            .rollup_rules()
+            .create_or_update(RollupRuleParams {
+                string_aggregate: RollupAggregate::Max,
+                ..namespace_params.clone()
+            })
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::InvalidRollupRule { .. });
+        let err = repos
+            .rollup_rules()
+            .create_or_update(RollupRuleParams {
+                interval_ns: 0,
+                ..namespace_params.clone()
+            })
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::InvalidRollupRule { .. });
+
+        // deleting the table rule falls back to the namespace rule
+        repos
+            .rollup_rules()
+            .delete(namespace.id, Some(table.id))
+            .await
+            .unwrap();
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .get_for_table(namespace.id, table.id)
+                .await
+                .unwrap(),
+            Some(updated)
+        );
+        repos
+            .rollup_rules()
+            .delete(namespace.id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            repos
+                .rollup_rules()
+                .list_by_namespace_id(namespace.id)
+                .await
+                .unwrap(),
+            vec![]
+        );
+
+        // deleting an unknown rule is fine
+        repos
+            .rollup_rules()
+            .delete(namespace.id, None)
+            .await
+            .unwrap();
+
+        // the rollup resolution is recorded per partition
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), shard.id, table.id)
+            .await
+            .unwrap();
+        assert_eq!(partition.rollup_interval_ns, None);
+        let partition = repos
+            .partitions()
+            .set_rollup_interval(partition.id, 300_000_000_000)
+            .await
+            .unwrap();
+        assert_eq!(partition.rollup_interval_ns, Some(300_000_000_000));
+        let partition = repos
+            .partitions()
+            .get_by_id(partition.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(partition.rollup_interval_ns, Some(300_000_000_000));
+
+        let err = repos
+            .partitions()
+            .set_rollup_interval(PartitionId::new(i64::MAX), 1)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::PartitionNotFound { .. });
+    }
+
     async fn test_partitions_new_file_between(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let topic = repos
@@ -3100,7 +4579,7 @@ pub(crate) mod test_helpers {
         assert_eq!(
             repos
                 .columns()
-                .list_by_namespace_id(namespace_1.id)
+                .list_by_namespace_id(namespace_1.id, SoftDeletedRows::AllRows)
                 .await
                 .expect("listing columns should succeed")
                 .len(),
@@ -3149,7 +4628,7 @@ pub(crate) mod test_helpers {
         assert_eq!(
             repos
                 .columns()
-                .list_by_namespace_id(namespace_2.id)
+                .list_by_namespace_id(namespace_2.id, SoftDeletedRows::AllRows)
                 .await
                 .expect("listing columns should succeed")
                 .len(),
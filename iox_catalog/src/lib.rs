@@ -13,7 +13,9 @@
     clippy::dbg_macro
 )]
 
-use crate::interface::{ColumnTypeMismatchSnafu, Error, RepoCollection, Result, Transaction};
+use crate::interface::{
+    ColumnTypeMismatchSnafu, Error, NotInPrimaryKeySnafu, RepoCollection, Result, Transaction,
+};
 use data_types::{
     ColumnType, NamespaceSchema, QueryPool, Shard, ShardId, ShardIndex, TableSchema, TopicId,
     TopicMetadata,
@@ -38,6 +40,8 @@ pub const DEFAULT_MAX_COLUMNS_PER_TABLE: i32 = 200;
 pub const DEFAULT_RETENTION_PERIOD: Option<i64> = None;
 
 /// A string value representing an infinite retention policy.
+pub mod backup;
+pub mod changes;
 pub mod interface;
 pub mod mem;
 pub mod metrics;
@@ -50,6 +54,11 @@ pub mod sqlite;
 pub struct TableScopedError(String, Error);
 
 impl TableScopedError {
+    /// Scope `err` to the table named `table`.
+    pub fn new(table: impl Into<String>, err: Error) -> Self {
+        Self(table.into(), err)
+    }
+
     /// Return the table name for this error.
     pub fn table(&self) -> &str {
         &self.0
@@ -127,7 +136,7 @@ where
                 .tables()
                 .create_or_get(table_name, schema.id)
                 .await
-                .map(|t| TableSchema::new(t.id))?;
+                .map(|t| TableSchema::new_for_table(&t))?;
 
             // Always add a time column to all new tables.
             let time_col = repos
@@ -156,6 +165,17 @@ where
     let mut column_batch: HashMap<&str, ColumnType> = HashMap::new();
 
     for (name, col) in mb.columns() {
+        // Tags outside of a declared primary key would change the series key
+        // and are rejected.
+        if ColumnType::from(col.influx_type()) == ColumnType::Tag && !table.permits_tag(name) {
+            return NotInPrimaryKeySnafu {
+                name,
+                table_id: table.id,
+                primary_key: table.primary_key.clone(),
+            }
+            .fail();
+        }
+
         // Check if the column exists in the cached schema.
         //
         // If it does, validate it. If it does not exist, create it and insert
@@ -2,10 +2,13 @@
 //! used for testing or for an IOx designed to run without catalog persistence.
 
 use crate::{
+    changes::{self, CatalogChange, PendingChanges},
     interface::{
-        sealed::TransactionFinalize, CasFailure, Catalog, ColumnRepo, ColumnTypeMismatchSnafu,
-        Error, NamespaceRepo, ParquetFileRepo, PartitionRepo, QueryPoolRepo, RepoCollection,
-        Result, ShardRepo, SoftDeletedRows, TableRepo, TopicMetadataRepo, Transaction,
+        sealed::TransactionFinalize, validate_rollup_rule, validate_write_rate_limit, CasFailure,
+        Catalog, ColumnRepo, ColumnSoftDeletedSnafu, ColumnTypeMismatchSnafu, Error, NamespaceRepo,
+        ParquetFileRepo, PartitionRepo, QueryPoolRepo, RepoCollection, Result, RollupRuleRepo,
+        ShardRepo, SoftDeletedRows, TableRepo, TableSoftDeletedSnafu, TopicMetadataRepo,
+        Transaction,
     },
     metrics::MetricDecorator,
     DEFAULT_MAX_COLUMNS_PER_TABLE, DEFAULT_MAX_TABLES, SHARED_TOPIC_ID, SHARED_TOPIC_NAME,
@@ -14,8 +17,9 @@ use async_trait::async_trait;
 use data_types::{
     Column, ColumnId, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile,
     ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId,
-    SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, Timestamp,
-    TopicId, TopicMetadata, TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
+    QueryPriority, RollupRule, RollupRuleId, RollupRuleParams, SequenceNumber, Shard, ShardId,
+    ShardIndex, SkippedCompaction, Table, TableId, Timestamp, TopicId, TopicMetadata,
+    TypeCoercionPolicy, TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::warn;
@@ -26,8 +30,9 @@ use std::{
     convert::TryFrom,
     fmt::{Display, Formatter},
     sync::Arc,
+    time::Duration,
 };
-use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio::sync::{broadcast, Mutex, OwnedMutexGuard};
 
 /// In-memory catalog that implements the `RepoCollection` and individual repo traits from
 /// the catalog interface.
@@ -35,6 +40,7 @@ pub struct MemCatalog {
     metrics: Arc<metric::Registry>,
     collections: Arc<Mutex<MemCollections>>,
     time_provider: Arc<dyn TimeProvider>,
+    changes: broadcast::Sender<CatalogChange>,
 }
 
 impl MemCatalog {
@@ -44,6 +50,7 @@ impl MemCatalog {
             metrics,
             collections: Default::default(),
             time_provider: Arc::new(SystemProvider::new()),
+            changes: changes::sender(),
         }
     }
 
@@ -71,7 +78,16 @@ struct MemCollections {
     shards: Vec<Shard>,
     partitions: Vec<Partition>,
     skipped_compactions: Vec<SkippedCompaction>,
+    partition_leases: Vec<PartitionLease>,
     parquet_files: Vec<ParquetFile>,
+    rollup_rules: Vec<RollupRule>,
+}
+
+#[derive(Debug, Clone)]
+struct PartitionLease {
+    partition_id: PartitionId,
+    owner: String,
+    expires_at: Timestamp,
 }
 
 #[derive(Debug)]
@@ -92,6 +108,7 @@ enum MemTxnInner {
 pub struct MemTxn {
     inner: MemTxnInner,
     time_provider: Arc<dyn TimeProvider>,
+    changes: PendingChanges,
 }
 
 impl MemTxn {
@@ -101,6 +118,62 @@ impl MemTxn {
             MemTxnInner::NoTxn { collections } => collections,
         }
     }
+
+    /// Apply `update` to the namespace called `name` and return the updated namespace.
+    fn update_namespace(
+        &mut self,
+        name: &str,
+        update: impl FnOnce(&mut Namespace),
+    ) -> Result<Namespace> {
+        let namespace = match self.stage().namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                update(n);
+                n.clone()
+            }
+            None => {
+                return Err(Error::NamespaceNotFoundByName {
+                    name: name.to_string(),
+                })
+            }
+        };
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    /// Record a change to the table with ID `table_id`, e.g. to one of its columns.
+    fn table_changed(&mut self, table_id: TableId) {
+        let change = self
+            .stage()
+            .tables
+            .iter()
+            .find(|t| t.id == table_id)
+            .map(|t| CatalogChange::Table {
+                namespace_id: t.namespace_id,
+                id: t.id,
+            });
+        if let Some(change) = change {
+            self.changes.push(change);
+        }
+    }
+
+    /// Record changes to the partitions of the parquet files with the given IDs.
+    fn parquet_files_changed(&mut self, ids: &[ParquetFileId]) {
+        let ids: HashSet<_> = ids.iter().collect();
+        let changes: HashSet<_> = self
+            .stage()
+            .parquet_files
+            .iter()
+            .filter(|f| ids.contains(&f.id))
+            .map(|f| CatalogChange::ParquetFile {
+                table_id: f.table_id,
+                partition_id: f.partition_id,
+            })
+            .collect();
+        for change in changes {
+            self.changes.push(change);
+        }
+    }
 }
 
 impl Drop for MemTxn {
@@ -132,6 +205,7 @@ impl Catalog for MemCatalog {
                 finalized: false,
             },
             time_provider: self.time_provider(),
+            changes: PendingChanges::transaction(self.changes.clone()),
         };
         let stage = transaction.stage();
 
@@ -166,6 +240,7 @@ impl Catalog for MemCatalog {
                     finalized: false,
                 },
                 time_provider: self.time_provider(),
+                changes: PendingChanges::transaction(self.changes.clone()),
             },
             Arc::clone(&self.metrics),
         )))
@@ -177,6 +252,7 @@ impl Catalog for MemCatalog {
             MemTxn {
                 inner: MemTxnInner::NoTxn { collections },
                 time_provider: self.time_provider(),
+                changes: PendingChanges::oneshot(self.changes.clone()),
             },
             Arc::clone(&self.metrics),
         ))
@@ -189,6 +265,10 @@ impl Catalog for MemCatalog {
     fn time_provider(&self) -> Arc<dyn TimeProvider> {
         Arc::clone(&self.time_provider)
     }
+
+    fn subscribe_changes(&self) -> broadcast::Receiver<CatalogChange> {
+        self.changes.subscribe()
+    }
 }
 
 #[async_trait]
@@ -203,6 +283,7 @@ impl TransactionFinalize for MemTxn {
                 assert!(!*finalized);
                 **guard = std::mem::take(stage);
                 *finalized = true;
+                self.changes.commit();
             }
             MemTxnInner::NoTxn { .. } => {
                 panic!("cannot commit oneshot");
@@ -216,6 +297,7 @@ impl TransactionFinalize for MemTxn {
             MemTxnInner::Txn { finalized, .. } => {
                 assert!(!*finalized);
                 *finalized = true;
+                self.changes.abort();
             }
             MemTxnInner::NoTxn { .. } => {
                 panic!("cannot abort oneshot");
@@ -258,6 +340,10 @@ impl RepoCollection for MemTxn {
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn rollup_rules(&mut self) -> &mut dyn RollupRuleRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -286,6 +372,19 @@ impl TopicMetadataRepo for MemTxn {
         let topic = stage.topics.iter().find(|t| t.name == name).cloned();
         Ok(topic)
     }
+
+    async fn list(&mut self) -> Result<Vec<TopicMetadata>> {
+        let stage = self.stage();
+
+        Ok(stage.topics.clone())
+    }
+
+    async fn restore(&mut self, topic: &TopicMetadata) -> Result<()> {
+        let stage = self.stage();
+
+        stage.topics.push(topic.clone());
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -307,6 +406,19 @@ impl QueryPoolRepo for MemTxn {
 
         Ok(pool.clone())
     }
+
+    async fn list(&mut self) -> Result<Vec<QueryPool>> {
+        let stage = self.stage();
+
+        Ok(stage.query_pools.clone())
+    }
+
+    async fn restore(&mut self, query_pool: &QueryPool) -> Result<()> {
+        let stage = self.stage();
+
+        stage.query_pools.push(query_pool.clone());
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -334,10 +446,18 @@ impl NamespaceRepo for MemTxn {
             max_tables: DEFAULT_MAX_TABLES,
             max_columns_per_table: DEFAULT_MAX_COLUMNS_PER_TABLE,
             retention_period_ns,
+            max_lines_per_second: None,
+            max_batch_bytes_per_second: None,
+            max_concurrent_queries: None,
+            max_query_memory_bytes: None,
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
             deleted_at: None,
         };
-        stage.namespaces.push(namespace);
-        Ok(stage.namespaces.last().unwrap().clone())
+        stage.namespaces.push(namespace.clone());
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
     }
 
     async fn list(&mut self, deleted: SoftDeletedRows) -> Result<Vec<Namespace>> {
@@ -376,43 +496,133 @@ impl NamespaceRepo for MemTxn {
     // namespace
     async fn soft_delete(&mut self, name: &str) -> Result<()> {
         let timestamp = self.time_provider.now();
+        self.update_namespace(name, |n| n.deleted_at = Some(Timestamp::from(timestamp)))
+            .map(|_| ())
+    }
+
+    async fn undelete(&mut self, name: &str, deleted_after: Timestamp) -> Result<Namespace> {
         let stage = self.stage();
-        // get namespace by name
-        match stage.namespaces.iter_mut().find(|n| n.name == name) {
-            Some(n) => {
-                n.deleted_at = Some(Timestamp::from(timestamp));
-                Ok(())
-            }
-            None => Err(Error::NamespaceNotFoundByName {
+        let namespace = stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.map_or(false, |t| t >= deleted_after))
+            .map(|n| {
+                n.deleted_at = None;
+                n.clone()
+            })
+            .ok_or_else(|| Error::SoftDeletedNamespaceNotFound {
                 name: name.to_string(),
-            }),
-        }
+            })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
     }
 
-    async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
+    async fn purge(&mut self, name: &str) -> Result<()> {
         let stage = self.stage();
-        match stage.namespaces.iter_mut().find(|n| n.name == name) {
-            Some(n) => {
-                n.max_tables = new_max;
-                Ok(n.clone())
-            }
-            None => Err(Error::NamespaceNotFoundByName {
+        let namespace_id = stage
+            .namespaces
+            .iter()
+            .find(|n| n.name == name && n.deleted_at.is_some())
+            .map(|n| n.id)
+            .ok_or_else(|| Error::SoftDeletedNamespaceNotFound {
                 name: name.to_string(),
-            }),
-        }
+            })?;
+
+        let table_ids: HashSet<_> = stage
+            .tables
+            .iter()
+            .filter_map(|t| (t.namespace_id == namespace_id).then_some(t.id))
+            .collect();
+        let partition_ids: HashSet<_> = stage
+            .partitions
+            .iter()
+            .filter_map(|p| table_ids.contains(&p.table_id).then_some(p.id))
+            .collect();
+
+        stage
+            .parquet_files
+            .retain(|f| f.namespace_id != namespace_id);
+        stage
+            .skipped_compactions
+            .retain(|s| !partition_ids.contains(&s.partition_id));
+        stage
+            .partition_leases
+            .retain(|l| !partition_ids.contains(&l.partition_id));
+        stage
+            .partitions
+            .retain(|p| !table_ids.contains(&p.table_id));
+        stage.columns.retain(|c| !table_ids.contains(&c.table_id));
+        stage
+            .rollup_rules
+            .retain(|r| r.namespace_id != namespace_id);
+        stage.tables.retain(|t| t.namespace_id != namespace_id);
+        stage.namespaces.retain(|n| n.id != namespace_id);
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace_id });
+        Ok(())
+    }
+
+    async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
+        self.update_namespace(name, |n| n.max_tables = new_max)
     }
 
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
-        let stage = self.stage();
-        match stage.namespaces.iter_mut().find(|n| n.name == name) {
-            Some(n) => {
-                n.max_columns_per_table = new_max;
-                Ok(n.clone())
-            }
-            None => Err(Error::NamespaceNotFoundByName {
-                name: name.to_string(),
-            }),
-        }
+        self.update_namespace(name, |n| n.max_columns_per_table = new_max)
+    }
+
+    async fn update_lines_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        validate_write_rate_limit(new_max)?;
+
+        self.update_namespace(name, |n| n.max_lines_per_second = new_max)
+    }
+
+    async fn update_batch_bytes_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        validate_write_rate_limit(new_max)?;
+
+        self.update_namespace(name, |n| n.max_batch_bytes_per_second = new_max)
+    }
+
+    async fn update_concurrent_queries_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i32>,
+    ) -> Result<Namespace> {
+        self.update_namespace(name, |n| n.max_concurrent_queries = new_max)
+    }
+
+    async fn update_query_memory_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        self.update_namespace(name, |n| n.max_query_memory_bytes = new_max)
+    }
+
+    async fn update_type_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: TypeCoercionPolicy,
+    ) -> Result<Namespace> {
+        self.update_namespace(name, |n| n.type_coercion_policy = policy)
+    }
+
+    async fn update_default_query_priority(
+        &mut self,
+        name: &str,
+        priority: QueryPriority,
+    ) -> Result<Namespace> {
+        self.update_namespace(name, |n| n.default_query_priority = priority)
     }
 
     async fn update_retention_period(
@@ -420,16 +630,14 @@ impl NamespaceRepo for MemTxn {
         name: &str,
         retention_period_ns: Option<i64>,
     ) -> Result<Namespace> {
+        self.update_namespace(name, |n| n.retention_period_ns = retention_period_ns)
+    }
+
+    async fn restore(&mut self, namespace: &Namespace) -> Result<()> {
         let stage = self.stage();
-        match stage.namespaces.iter_mut().find(|n| n.name == name) {
-            Some(n) => {
-                n.retention_period_ns = retention_period_ns;
-                Ok(n.clone())
-            }
-            None => Err(Error::NamespaceNotFoundByName {
-                name: name.to_string(),
-            }),
-        }
+
+        stage.namespaces.push(namespace.clone());
+        Ok(())
     }
 }
 
@@ -455,7 +663,7 @@ impl TableRepo for MemTxn {
                 let tables_count = stage
                     .tables
                     .iter()
-                    .filter(|t| t.namespace_id == namespace_id)
+                    .filter(|t| t.namespace_id == namespace_id && t.deleted_at.is_none())
                     .count();
                 if tables_count >= max_tables.try_into().unwrap() {
                     return Err(Error::TableCreateLimitError {
@@ -466,24 +674,90 @@ impl TableRepo for MemTxn {
                 Ok(())
             })?;
 
-        let table = match stage
+        let (table, created) = match stage
             .tables
             .iter()
             .find(|t| t.name == name && t.namespace_id == namespace_id)
         {
-            Some(t) => t,
+            Some(t) => {
+                ensure!(
+                    t.deleted_at.is_none(),
+                    TableSoftDeletedSnafu { name, namespace_id }
+                );
+                (t.clone(), false)
+            }
             None => {
                 let table = Table {
                     id: TableId::new(stage.tables.len() as i64 + 1),
                     namespace_id,
                     name: name.to_string(),
+                    primary_key: vec![],
+                    deleted_at: None,
                 };
-                stage.tables.push(table);
-                stage.tables.last().unwrap()
+                stage.tables.push(table.clone());
+                (table, true)
             }
         };
 
-        Ok(table.clone())
+        if created {
+            self.changes.push(CatalogChange::Table {
+                namespace_id,
+                id: table.id,
+            });
+        }
+        Ok(table)
+    }
+
+    async fn create(
+        &mut self,
+        name: &str,
+        namespace_id: NamespaceId,
+        primary_key: &[String],
+    ) -> Result<Table> {
+        let stage = self.stage();
+
+        if stage
+            .tables
+            .iter()
+            .any(|t| t.name == name && t.namespace_id == namespace_id)
+        {
+            return Err(Error::NameExists {
+                name: name.to_string(),
+            });
+        }
+
+        let max_tables = stage
+            .namespaces
+            .iter()
+            .find(|n| n.id == namespace_id)
+            .ok_or(Error::NamespaceNotFoundById { id: namespace_id })?
+            .max_tables;
+        let tables_count = stage
+            .tables
+            .iter()
+            .filter(|t| t.namespace_id == namespace_id && t.deleted_at.is_none())
+            .count();
+        if tables_count >= max_tables.try_into().unwrap() {
+            return Err(Error::TableCreateLimitError {
+                table_name: name.to_string(),
+                namespace_id,
+            });
+        }
+
+        let table = Table {
+            id: TableId::new(stage.tables.len() as i64 + 1),
+            namespace_id,
+            name: name.to_string(),
+            primary_key: primary_key.to_vec(),
+            deleted_at: None,
+        };
+        stage.tables.push(table.clone());
+
+        self.changes.push(CatalogChange::Table {
+            namespace_id,
+            id: table.id,
+        });
+        Ok(table)
     }
 
     async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>> {
@@ -502,17 +776,22 @@ impl TableRepo for MemTxn {
         Ok(stage
             .tables
             .iter()
-            .find(|t| t.namespace_id == namespace_id && t.name == name)
+            .find(|t| t.namespace_id == namespace_id && t.name == name && t.deleted_at.is_none())
             .cloned())
     }
 
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>> {
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Table>> {
         let stage = self.stage();
 
         let tables: Vec<_> = stage
             .tables
             .iter()
             .filter(|t| t.namespace_id == namespace_id)
+            .filter(|t| filter_deleted(t.deleted_at, deleted))
             .cloned()
             .collect();
         Ok(tables)
@@ -522,6 +801,34 @@ impl TableRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.tables.clone())
     }
+
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table> {
+        let timestamp = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let table = stage
+            .tables
+            .iter_mut()
+            .find(|t| t.id == table_id)
+            .map(|t| {
+                t.deleted_at.get_or_insert(timestamp);
+                t.clone()
+            })
+            .ok_or(Error::TableNotFound { id: table_id })?;
+
+        self.changes.push(CatalogChange::Table {
+            namespace_id: table.namespace_id,
+            id: table.id,
+        });
+        Ok(table)
+    }
+
+    async fn restore(&mut self, table: &Table) -> Result<()> {
+        let stage = self.stage();
+
+        stage.tables.push(table.clone());
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -558,7 +865,7 @@ impl ColumnRepo for MemTxn {
                         let columns_count = stage
                             .columns
                             .iter()
-                            .filter(|t| t.table_id == table_id)
+                            .filter(|t| t.table_id == table_id && t.deleted_at.is_none())
                             .count();
                         if columns_count >= max_columns_per_table.try_into().unwrap() {
                             return Err(Error::ColumnCreateLimitError {
@@ -571,12 +878,16 @@ impl ColumnRepo for MemTxn {
                 Ok(())
             })?;
 
-        let column = match stage
+        let (column, created) = match stage
             .columns
             .iter()
             .find(|t| t.name == name && t.table_id == table_id)
         {
             Some(c) => {
+                ensure!(
+                    c.deleted_at.is_none(),
+                    ColumnSoftDeletedSnafu { name, table_id }
+                );
                 ensure!(
                     column_type == c.column_type,
                     ColumnTypeMismatchSnafu {
@@ -585,7 +896,7 @@ impl ColumnRepo for MemTxn {
                         new: column_type
                     }
                 );
-                c
+                (c.clone(), false)
             }
             None => {
                 let column = Column {
@@ -593,13 +904,17 @@ impl ColumnRepo for MemTxn {
                     table_id,
                     name: name.to_string(),
                     column_type,
+                    deleted_at: None,
                 };
-                stage.columns.push(column);
-                stage.columns.last().unwrap()
+                stage.columns.push(column.clone());
+                (column, true)
             }
         };
 
-        Ok(column.clone())
+        if created {
+            self.table_changed(table_id);
+        }
+        Ok(column)
     }
 
     async fn create_or_get_many_unchecked(
@@ -612,6 +927,7 @@ impl ColumnRepo for MemTxn {
         // and for testing purposes the in-memory catalog needs to match its functionality.
 
         let stage = self.stage();
+        let mut created = false;
 
         let out: Vec<_> = columns
             .iter()
@@ -622,6 +938,13 @@ impl ColumnRepo for MemTxn {
                     .find(|t| t.name == column_name && t.table_id == table_id)
                 {
                     Some(c) => {
+                        ensure!(
+                            c.deleted_at.is_none(),
+                            ColumnSoftDeletedSnafu {
+                                name: column_name,
+                                table_id
+                            }
+                        );
                         ensure!(
                             column_type == c.column_type,
                             ColumnTypeMismatchSnafu {
@@ -638,31 +961,47 @@ impl ColumnRepo for MemTxn {
                             table_id,
                             name: column_name.to_string(),
                             column_type,
+                            deleted_at: None,
                         };
                         stage.columns.push(new_column);
+                        created = true;
                         Ok(stage.columns.last().unwrap().clone())
                     }
                 }
             })
             .collect::<Result<Vec<Column>>>()?;
 
+        if created {
+            self.table_changed(table_id);
+        }
         Ok(out)
     }
 
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>> {
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Column>> {
         let stage = self.stage();
 
-        let table_ids: Vec<_> = stage
+        let tables: HashMap<_, _> = stage
             .tables
             .iter()
             .filter(|t| t.namespace_id == namespace_id)
-            .map(|t| t.id)
+            .map(|t| (t.id, t.deleted_at))
             .collect();
         let columns: Vec<_> = stage
             .columns
             .iter()
-            .filter(|c| table_ids.contains(&c.table_id))
-            .cloned()
+            .filter_map(|c| {
+                // columns of a deleted table count as deleted
+                let table_deleted_at = tables.get(&c.table_id)?;
+                let deleted_at = c.deleted_at.or(*table_deleted_at);
+                filter_deleted(deleted_at, deleted).then(|| Column {
+                    deleted_at,
+                    ..c.clone()
+                })
+            })
             .collect();
 
         Ok(columns)
@@ -674,7 +1013,7 @@ impl ColumnRepo for MemTxn {
         let columns: Vec<_> = stage
             .columns
             .iter()
-            .filter(|c| c.table_id == table_id)
+            .filter(|c| c.table_id == table_id && c.deleted_at.is_none())
             .cloned()
             .collect();
 
@@ -685,6 +1024,31 @@ impl ColumnRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.columns.clone())
     }
+
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column> {
+        let timestamp = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let column = stage
+            .columns
+            .iter_mut()
+            .find(|c| c.id == column_id)
+            .map(|c| {
+                c.deleted_at.get_or_insert(timestamp);
+                c.clone()
+            })
+            .ok_or(Error::ColumnNotFound { id: column_id })?;
+
+        self.table_changed(column.table_id);
+        Ok(column)
+    }
+
+    async fn restore(&mut self, column: &Column) -> Result<()> {
+        let stage = self.stage();
+
+        stage.columns.push(column.clone());
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -765,6 +1129,13 @@ impl ShardRepo for MemTxn {
 
         Ok(())
     }
+
+    async fn restore(&mut self, shard: &Shard) -> Result<()> {
+        let stage = self.stage();
+
+        stage.shards.push(*shard);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -777,27 +1148,42 @@ impl PartitionRepo for MemTxn {
     ) -> Result<Partition> {
         let stage = self.stage();
 
-        let partition =
+        let (partition, created) =
             match stage.partitions.iter().find(|p| {
                 p.partition_key == key && p.shard_id == shard_id && p.table_id == table_id
             }) {
-                Some(p) => p,
+                Some(p) => (p.clone(), false),
                 None => {
+                    // New partitions of a table with a declared primary key
+                    // are sorted by it.
+                    let sort_key = stage
+                        .tables
+                        .iter()
+                        .find(|t| t.id == table_id)
+                        .map(|t| t.primary_key.clone())
+                        .unwrap_or_default();
                     let p = Partition {
                         id: PartitionId::new(stage.partitions.len() as i64 + 1),
                         shard_id,
                         table_id,
                         partition_key: key,
-                        sort_key: vec![],
+                        sort_key,
                         persisted_sequence_number: None,
                         new_file_at: None,
+                        rollup_interval_ns: None,
                     };
-                    stage.partitions.push(p);
-                    stage.partitions.last().unwrap()
+                    stage.partitions.push(p.clone());
+                    (p, true)
                 }
             };
 
-        Ok(partition.clone())
+        if created {
+            self.changes.push(CatalogChange::Partition {
+                table_id,
+                id: partition.id,
+            });
+        }
+        Ok(partition)
     }
 
     async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>> {
@@ -838,16 +1224,24 @@ impl PartitionRepo for MemTxn {
     ) -> Result<Partition, CasFailure<Vec<String>>> {
         let stage = self.stage();
         let old_sort_key = old_sort_key.unwrap_or_default();
-        match stage.partitions.iter_mut().find(|p| p.id == partition_id) {
+        let partition = match stage.partitions.iter_mut().find(|p| p.id == partition_id) {
             Some(p) if p.sort_key == old_sort_key => {
                 p.sort_key = new_sort_key.iter().map(|s| s.to_string()).collect();
-                Ok(p.clone())
+                p.clone()
             }
             Some(p) => return Err(CasFailure::ValueMismatch(p.sort_key.clone())),
-            None => Err(CasFailure::QueryError(Error::PartitionNotFound {
-                id: partition_id,
-            })),
-        }
+            None => {
+                return Err(CasFailure::QueryError(Error::PartitionNotFound {
+                    id: partition_id,
+                }))
+            }
+        };
+
+        self.changes.push(CatalogChange::Partition {
+            table_id: partition.table_id,
+            id: partition.id,
+        });
+        Ok(partition)
     }
 
     async fn record_skipped_compaction(
@@ -929,6 +1323,74 @@ impl PartitionRepo for MemTxn {
         }
     }
 
+    async fn try_acquire_lease(
+        &mut self,
+        partition_id: PartitionId,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<bool> {
+        let now = self.time_provider.now();
+        let expires_at = Timestamp::from(now + lease_duration);
+        let now = Timestamp::from(now);
+
+        let stage = self.stage();
+        if !stage.partitions.iter().any(|p| p.id == partition_id) {
+            return Err(Error::PartitionNotFound { id: partition_id });
+        }
+
+        match stage
+            .partition_leases
+            .iter_mut()
+            .find(|l| l.partition_id == partition_id)
+        {
+            Some(l) if l.owner == owner || l.expires_at <= now => {
+                l.owner = owner.to_string();
+                l.expires_at = expires_at;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => {
+                stage.partition_leases.push(PartitionLease {
+                    partition_id,
+                    owner: owner.to_string(),
+                    expires_at,
+                });
+                Ok(true)
+            }
+        }
+    }
+
+    async fn release_lease(&mut self, partition_id: PartitionId, owner: &str) -> Result<()> {
+        let stage = self.stage();
+        stage
+            .partition_leases
+            .retain(|l| !(l.partition_id == partition_id && l.owner == owner));
+        Ok(())
+    }
+
+    async fn set_rollup_interval(
+        &mut self,
+        partition_id: PartitionId,
+        rollup_interval_ns: i64,
+    ) -> Result<Partition> {
+        let stage = self.stage();
+        let partition = stage
+            .partitions
+            .iter_mut()
+            .find(|p| p.id == partition_id)
+            .map(|p| {
+                p.rollup_interval_ns = Some(rollup_interval_ns);
+                p.clone()
+            })
+            .ok_or(Error::PartitionNotFound { id: partition_id })?;
+
+        self.changes.push(CatalogChange::Partition {
+            table_id: partition.table_id,
+            id: partition.id,
+        });
+        Ok(partition)
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         let stage = self.stage();
         Ok(stage.partitions.iter().rev().take(n).cloned().collect())
@@ -955,6 +1417,13 @@ impl PartitionRepo for MemTxn {
 
         Ok(partitions)
     }
+
+    async fn restore(&mut self, partition: &Partition) -> Result<()> {
+        let stage = self.stage();
+
+        stage.partitions.push(partition.clone());
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -992,7 +1461,12 @@ impl ParquetFileRepo for MemTxn {
             partition.new_file_at = Some(created_at);
         }
 
-        Ok(stage.parquet_files.last().unwrap().clone())
+        let parquet_file = stage.parquet_files.last().unwrap().clone();
+        self.changes.push(CatalogChange::ParquetFile {
+            table_id: parquet_file.table_id,
+            partition_id: parquet_file.partition_id,
+        });
+        Ok(parquet_file)
     }
 
     async fn flag_for_delete(&mut self, id: ParquetFileId) -> Result<()> {
@@ -1004,6 +1478,7 @@ impl ParquetFileRepo for MemTxn {
             None => return Err(Error::ParquetRecordNotFound { id }),
         }
 
+        self.parquet_files_changed(&[id]);
         Ok(())
     }
 
@@ -1011,7 +1486,7 @@ impl ParquetFileRepo for MemTxn {
         let now = Timestamp::from(self.time_provider.now());
         let stage = self.stage();
 
-        Ok(stage
+        let flagged: Vec<_> = stage
             .parquet_files
             .iter_mut()
             // don't flag if already flagged for deletion
@@ -1034,7 +1509,65 @@ impl ParquetFileRepo for MemTxn {
                         })
                     })
             })
-            .collect())
+            .collect();
+
+        self.parquet_files_changed(&flagged);
+        Ok(flagged)
+    }
+
+    async fn flag_for_delete_by_table_deletion(&mut self) -> Result<Vec<ParquetFileId>> {
+        let now = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let deleted_table_ids: HashSet<_> = stage
+            .tables
+            .iter()
+            .filter_map(|t| t.deleted_at.map(|_| t.id))
+            .collect();
+
+        let flagged: Vec<_> = stage
+            .parquet_files
+            .iter_mut()
+            .filter(|f| f.to_delete.is_none() && deleted_table_ids.contains(&f.table_id))
+            .map(|f| {
+                f.to_delete = Some(now);
+                f.id
+            })
+            .collect();
+
+        self.parquet_files_changed(&flagged);
+        Ok(flagged)
+    }
+
+    async fn flag_for_delete_by_namespace_deletion(
+        &mut self,
+        deleted_before: Timestamp,
+    ) -> Result<Vec<ParquetFileId>> {
+        let now = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let deleted_namespace_ids: HashSet<_> = stage
+            .namespaces
+            .iter()
+            .filter_map(|n| {
+                n.deleted_at
+                    .filter(|deleted_at| *deleted_at < deleted_before)
+                    .map(|_| n.id)
+            })
+            .collect();
+
+        let flagged: Vec<_> = stage
+            .parquet_files
+            .iter_mut()
+            .filter(|f| f.to_delete.is_none() && deleted_namespace_ids.contains(&f.namespace_id))
+            .map(|f| {
+                f.to_delete = Some(now);
+                f.id
+            })
+            .collect();
+
+        self.parquet_files_changed(&flagged);
+        Ok(flagged)
     }
 
     async fn list_by_namespace_not_to_delete(
@@ -1126,6 +1659,7 @@ impl ParquetFileRepo for MemTxn {
             updated.push(f.id);
         }
 
+        self.parquet_files_changed(&updated);
         Ok(updated)
     }
 
@@ -1158,6 +1692,13 @@ impl ParquetFileRepo for MemTxn {
             .find(|f| f.object_store_id.eq(&object_store_id))
             .cloned())
     }
+
+    async fn restore(&mut self, parquet_file: &ParquetFile) -> Result<()> {
+        let stage = self.stage();
+
+        stage.parquet_files.push(parquet_file.clone());
+        Ok(())
+    }
 }
 
 fn filter_namespace_soft_delete<'a>(
@@ -1171,6 +1712,118 @@ fn filter_namespace_soft_delete<'a>(
     })
 }
 
+/// Returns true if a row with the given deletion timestamp matches `deleted`.
+fn filter_deleted(deleted_at: Option<Timestamp>, deleted: SoftDeletedRows) -> bool {
+    match deleted {
+        SoftDeletedRows::AllRows => true,
+        SoftDeletedRows::ExcludeDeleted => deleted_at.is_none(),
+        SoftDeletedRows::OnlyDeleted => deleted_at.is_some(),
+    }
+}
+
+#[async_trait]
+impl RollupRuleRepo for MemTxn {
+    async fn create_or_update(&mut self, params: RollupRuleParams) -> Result<RollupRule> {
+        validate_rollup_rule(&params)?;
+
+        let stage = self.stage();
+        if !stage.namespaces.iter().any(|n| n.id == params.namespace_id) {
+            return Err(Error::NamespaceNotFoundById {
+                id: params.namespace_id,
+            });
+        }
+        if let Some(table_id) = params.table_id {
+            if !stage.tables.iter().any(|t| t.id == table_id) {
+                return Err(Error::TableNotFound { id: table_id });
+            }
+        }
+
+        let RollupRuleParams {
+            namespace_id,
+            table_id,
+            interval_ns,
+            min_age_ns,
+            float_aggregate,
+            integer_aggregate,
+            unsigned_aggregate,
+            boolean_aggregate,
+            string_aggregate,
+        } = params;
+
+        let id = match stage
+            .rollup_rules
+            .iter()
+            .position(|r| r.namespace_id == namespace_id && r.table_id == table_id)
+        {
+            Some(pos) => stage.rollup_rules.remove(pos).id,
+            None => RollupRuleId::new(
+                stage
+                    .rollup_rules
+                    .iter()
+                    .map(|r| r.id.get())
+                    .max()
+                    .unwrap_or_default()
+                    + 1,
+            ),
+        };
+
+        let rule = RollupRule {
+            id,
+            namespace_id,
+            table_id,
+            interval_ns,
+            min_age_ns,
+            float_aggregate,
+            integer_aggregate,
+            unsigned_aggregate,
+            boolean_aggregate,
+            string_aggregate,
+        };
+        stage.rollup_rules.push(rule.clone());
+
+        Ok(rule)
+    }
+
+    async fn get_for_table(
+        &mut self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+    ) -> Result<Option<RollupRule>> {
+        let stage = self.stage();
+
+        let rules = stage
+            .rollup_rules
+            .iter()
+            .filter(|r| r.namespace_id == namespace_id);
+        let table_rule = rules.clone().find(|r| r.table_id == Some(table_id));
+        let namespace_rule = rules.clone().find(|r| r.table_id.is_none());
+
+        Ok(table_rule.or(namespace_rule).cloned())
+    }
+
+    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<RollupRule>> {
+        let stage = self.stage();
+
+        let mut rules: Vec<_> = stage
+            .rollup_rules
+            .iter()
+            .filter(|r| r.namespace_id == namespace_id)
+            .cloned()
+            .collect();
+        rules.sort_by_key(|r| r.id);
+
+        Ok(rules)
+    }
+
+    async fn delete(&mut self, namespace_id: NamespaceId, table_id: Option<TableId>) -> Result<()> {
+        let stage = self.stage();
+        stage
+            .rollup_rules
+            .retain(|r| !(r.namespace_id == namespace_id && r.table_id == table_id));
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,19 +2,19 @@
 
 use crate::interface::{
     sealed::TransactionFinalize, CasFailure, ColumnRepo, NamespaceRepo, ParquetFileRepo,
-    PartitionRepo, QueryPoolRepo, RepoCollection, Result, ShardRepo, SoftDeletedRows, TableRepo,
-    TopicMetadataRepo,
+    PartitionRepo, QueryPoolRepo, RepoCollection, Result, RollupRuleRepo, ShardRepo,
+    SoftDeletedRows, TableRepo, TopicMetadataRepo,
 };
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId,
-    SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, Timestamp,
-    TopicId, TopicMetadata,
+    Column, ColumnId, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile,
+    ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId,
+    QueryPriority, RollupRule, RollupRuleParams, SequenceNumber, Shard, ShardId, ShardIndex,
+    SkippedCompaction, Table, TableId, Timestamp, TopicId, TopicMetadata, TypeCoercionPolicy,
 };
 use iox_time::{SystemProvider, TimeProvider};
-use metric::{DurationHistogram, Metric};
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use metric::{DurationHistogram, Metric, U64Counter, U64Gauge};
+use std::{borrow::Cow, collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 use uuid::Uuid;
 
 /// Decorates a implementation of the catalog's [`RepoCollection`] (and the
@@ -51,6 +51,7 @@ where
         + ShardRepo
         + PartitionRepo
         + ParquetFileRepo
+        + RollupRuleRepo
         + Debug,
     P: TimeProvider,
 {
@@ -85,6 +86,10 @@ where
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn rollup_rules(&mut self) -> &mut dyn RollupRuleRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -101,6 +106,122 @@ where
     }
 }
 
+/// Instrumentation of a connection pool serving reads that tolerate stale results.
+///
+/// Values are recorded under the `catalog_pool_reads` metric, labelled by pool name
+/// (`primary` or `replica_<n>`) and result (success/error). Reads the pool served because no
+/// read replica was healthy are counted under `catalog_replica_fallbacks` with reason
+/// `unhealthy`; only the primary serves such reads.
+#[derive(Debug)]
+pub(crate) struct PoolMetrics {
+    reads_success: U64Counter,
+    reads_error: U64Counter,
+    fallbacks_unhealthy: U64Counter,
+}
+
+impl PoolMetrics {
+    /// Register the metrics of the pool called `pool` in `metrics`.
+    pub(crate) fn new(metrics: &metric::Registry, pool: &str) -> Self {
+        let reads = metrics.register_metric::<U64Counter>(
+            "catalog_pool_reads",
+            "number of catalog reads served by a connection pool",
+        );
+        let recorder = |result: &'static str| {
+            reads.recorder([
+                ("pool", Cow::Owned(pool.to_owned())),
+                ("result", Cow::Borrowed(result)),
+            ])
+        };
+
+        Self {
+            reads_success: recorder("success"),
+            reads_error: recorder("error"),
+            fallbacks_unhealthy: register_fallbacks(metrics).recorder([
+                ("pool", Cow::Owned(pool.to_owned())),
+                ("reason", Cow::Borrowed("unhealthy")),
+            ]),
+        }
+    }
+
+    /// Count a read redirected to this pool because no read replica was healthy.
+    pub(crate) fn fallback_unhealthy(&self) {
+        self.fallbacks_unhealthy.inc(1);
+    }
+
+    /// Count the read that produced `result` and pass it on.
+    ///
+    /// A row that was not found is a successful read.
+    pub(crate) fn record<T>(&self, result: Result<T, sqlx::Error>) -> Result<T, sqlx::Error> {
+        match &result {
+            Ok(_) | Err(sqlx::Error::RowNotFound) => self.reads_success.inc(1),
+            Err(_) => self.reads_error.inc(1),
+        }
+        result
+    }
+}
+
+fn register_fallbacks(metrics: &metric::Registry) -> Metric<U64Counter> {
+    metrics.register_metric::<U64Counter>(
+        "catalog_replica_fallbacks",
+        "number of catalog reads redirected from a read replica to the primary",
+    )
+}
+
+/// Instrumentation of a read replica connection pool.
+///
+/// In addition to the [`PoolMetrics`], this records the replication lag observed by the last
+/// health check under `catalog_replica_lag_ms`, whether the replica is used under
+/// `catalog_replica_healthy` and the reads that failed on the replica and were redirected to
+/// the primary under `catalog_replica_fallbacks`, labelled by pool name and reason `error`.
+#[derive(Debug)]
+pub(crate) struct ReplicaMetrics {
+    pub(crate) pool: PoolMetrics,
+    lag_ms: U64Gauge,
+    healthy: U64Gauge,
+    fallbacks_error: U64Counter,
+}
+
+impl ReplicaMetrics {
+    /// Register the metrics of the replica pool called `pool` in `metrics`.
+    pub(crate) fn new(metrics: &metric::Registry, pool: &str) -> Self {
+        let attributes = || [("pool", Cow::Owned(pool.to_owned()))];
+
+        Self {
+            pool: PoolMetrics::new(metrics, pool),
+            lag_ms: metrics
+                .register_metric::<U64Gauge>(
+                    "catalog_replica_lag_ms",
+                    "replication lag of a catalog read replica at the last health check",
+                )
+                .recorder(attributes()),
+            healthy: metrics
+                .register_metric::<U64Gauge>(
+                    "catalog_replica_healthy",
+                    "1 if a catalog read replica serves reads, 0 otherwise",
+                )
+                .recorder(attributes()),
+            fallbacks_error: register_fallbacks(metrics).recorder([
+                ("pool", Cow::Owned(pool.to_owned())),
+                ("reason", Cow::Borrowed("error")),
+            ]),
+        }
+    }
+
+    /// Record the outcome of a health check.
+    pub(crate) fn set_health(&self, healthy: bool, lag: Option<Duration>) {
+        self.healthy.set(healthy as u64);
+        if let Some(lag) = lag {
+            self.lag_ms.set(lag.as_millis() as u64);
+        }
+    }
+
+    /// Count a read that failed on the replica and is redirected to the primary.
+    pub(crate) fn read_failed(&self) {
+        self.pool.reads_error.inc(1);
+        self.fallbacks_error.inc(1);
+    }
+}
+
 /// Emit a trait impl for `impl_trait` that delegates calls to the inner
 /// implementation, recording the duration and result to the metrics registry.
 ///
@@ -169,6 +290,8 @@ decorate!(
     methods = [
         "topic_create_or_get" = create_or_get(&mut self, name: &str) -> Result<TopicMetadata>;
         "topic_get_by_name" = get_by_name(&mut self, name: &str) -> Result<Option<TopicMetadata>>;
+        "topic_list" = list(&mut self) -> Result<Vec<TopicMetadata>>;
+        "topic_restore" = restore(&mut self, topic: &TopicMetadata) -> Result<()>;
     ]
 );
 
@@ -176,6 +299,8 @@ decorate!(
     impl_trait = QueryPoolRepo,
     methods = [
         "query_create_or_get" = create_or_get(&mut self, name: &str) -> Result<QueryPool>;
+        "query_list" = list(&mut self) -> Result<Vec<QueryPool>>;
+        "query_restore" = restore(&mut self, query_pool: &QueryPool) -> Result<()>;
     ]
 );
 
@@ -188,8 +313,17 @@ decorate!(
         "namespace_get_by_id" = get_by_id(&mut self, id: NamespaceId, deleted: SoftDeletedRows) -> Result<Option<Namespace>>;
         "namespace_get_by_name" = get_by_name(&mut self, name: &str, deleted: SoftDeletedRows) -> Result<Option<Namespace>>;
         "namespace_soft_delete" = soft_delete(&mut self, name: &str) -> Result<()>;
+        "namespace_undelete" = undelete(&mut self, name: &str, deleted_after: Timestamp) -> Result<Namespace>;
+        "namespace_purge" = purge(&mut self, name: &str) -> Result<()>;
         "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
         "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+        "namespace_update_lines_per_second_limit" = update_lines_per_second_limit(&mut self, name: &str, new_max: Option<i64>) -> Result<Namespace>;
+        "namespace_update_batch_bytes_per_second_limit" = update_batch_bytes_per_second_limit(&mut self, name: &str, new_max: Option<i64>) -> Result<Namespace>;
+        "namespace_update_concurrent_queries_limit" = update_concurrent_queries_limit(&mut self, name: &str, new_max: Option<i32>) -> Result<Namespace>;
+        "namespace_update_query_memory_limit" = update_query_memory_limit(&mut self, name: &str, new_max: Option<i64>) -> Result<Namespace>;
+        "namespace_update_type_coercion_policy" = update_type_coercion_policy(&mut self, name: &str, policy: TypeCoercionPolicy) -> Result<Namespace>;
+        "namespace_update_default_query_priority" = update_default_query_priority(&mut self, name: &str, priority: QueryPriority) -> Result<Namespace>;
+        "namespace_restore" = restore(&mut self, namespace: &Namespace) -> Result<()>;
     ]
 );
 
@@ -197,10 +331,13 @@ decorate!(
     impl_trait = TableRepo,
     methods = [
         "table_create_or_get" = create_or_get(&mut self, name: &str, namespace_id: NamespaceId) -> Result<Table>;
+        "table_create" = create(&mut self, name: &str, namespace_id: NamespaceId, primary_key: &[String]) -> Result<Table>;
         "table_get_by_id" = get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>>;
         "table_get_by_namespace_and_name" = get_by_namespace_and_name(&mut self, namespace_id: NamespaceId, name: &str) -> Result<Option<Table>>;
-        "table_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>>;
+        "table_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId, deleted: SoftDeletedRows) -> Result<Vec<Table>>;
         "table_list" = list(&mut self) -> Result<Vec<Table>>;
+        "table_soft_delete" = soft_delete(&mut self, table_id: TableId) -> Result<Table>;
+        "table_restore" = restore(&mut self, table: &Table) -> Result<()>;
     ]
 );
 
@@ -208,10 +345,12 @@ decorate!(
     impl_trait = ColumnRepo,
     methods = [
         "column_create_or_get" = create_or_get(&mut self, name: &str, table_id: TableId, column_type: ColumnType) -> Result<Column>;
-        "column_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>>;
+        "column_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId, deleted: SoftDeletedRows) -> Result<Vec<Column>>;
         "column_list_by_table_id" = list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>>;
         "column_create_or_get_many_unchecked" = create_or_get_many_unchecked(&mut self, table_id: TableId, columns: HashMap<&str, ColumnType>) -> Result<Vec<Column>>;
         "column_list" = list(&mut self) -> Result<Vec<Column>>;
+        "column_soft_delete" = soft_delete(&mut self, column_id: ColumnId) -> Result<Column>;
+        "column_restore" = restore(&mut self, column: &Column) -> Result<()>;
     ]
 );
 
@@ -223,6 +362,7 @@ decorate!(
         "shard_list" = list(&mut self) -> Result<Vec<Shard>>;
         "shard_list_by_topic" = list_by_topic(&mut self, topic: &TopicMetadata) -> Result<Vec<Shard>>;
         "shard_update_min_unpersisted_sequence_number" = update_min_unpersisted_sequence_number(&mut self, shard_id: ShardId, sequence_number: SequenceNumber) -> Result<()>;
+        "shard_restore" = restore(&mut self, shard: &Shard) -> Result<()>;
     ]
 );
 
@@ -237,9 +377,13 @@ decorate!(
         "partition_record_skipped_compaction" = record_skipped_compaction(&mut self, partition_id: PartitionId, reason: &str, num_files: usize, limit_num_files: usize, limit_num_files_first_in_partition: usize, estimated_bytes: u64, limit_bytes: u64) -> Result<()>;
         "partition_list_skipped_compactions" = list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>>;
         "partition_delete_skipped_compactions" = delete_skipped_compactions(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
+        "partition_try_acquire_lease" = try_acquire_lease(&mut self, partition_id: PartitionId, owner: &str, lease_duration: Duration) -> Result<bool>;
+        "partition_release_lease" = release_lease(&mut self, partition_id: PartitionId, owner: &str) -> Result<()>;
+        "partition_set_rollup_interval" = set_rollup_interval(&mut self, partition_id: PartitionId, rollup_interval_ns: i64) -> Result<Partition>;
         "partition_most_recent_n" = most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
         "partitions_new_file_between" = partitions_new_file_between(&mut self, minimum_time: Timestamp, maximum_time: Option<Timestamp>) -> Result<Vec<PartitionId>>;
         "get_in_skipped_compaction" = get_in_skipped_compaction(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
+        "partition_restore" = restore(&mut self, partition: &Partition) -> Result<()>;
     ]
 );
 
@@ -249,6 +393,8 @@ decorate!(
         "parquet_create" = create( &mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
         "parquet_flag_for_delete" = flag_for_delete(&mut self, id: ParquetFileId) -> Result<()>;
         "parquet_flag_for_delete_by_retention" = flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
+        "parquet_flag_for_delete_by_table_deletion" = flag_for_delete_by_table_deletion(&mut self) -> Result<Vec<ParquetFileId>>;
+        "parquet_flag_for_delete_by_namespace_deletion" = flag_for_delete_by_namespace_deletion(&mut self, deleted_before: Timestamp) -> Result<Vec<ParquetFileId>>;
         "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_table_not_to_delete" = list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_table" = list_by_table(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
@@ -258,5 +404,16 @@ decorate!(
         "parquet_exist" = exist(&mut self, id: ParquetFileId) -> Result<bool>;
         "parquet_count" = count(&mut self) -> Result<i64>;
         "parquet_get_by_object_store_id" = get_by_object_store_id(&mut self, object_store_id: Uuid) -> Result<Option<ParquetFile>>;
+        "parquet_restore" = restore(&mut self, parquet_file: &ParquetFile) -> Result<()>;
+    ]
+);
+
+decorate!(
+    impl_trait = RollupRuleRepo,
+    methods = [
+        "rollup_rule_create_or_update" = create_or_update(&mut self, params: RollupRuleParams) -> Result<RollupRule>;
+        "rollup_rule_get_for_table" = get_for_table(&mut self, namespace_id: NamespaceId, table_id: TableId) -> Result<Option<RollupRule>>;
+        "rollup_rule_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<RollupRule>>;
+        "rollup_rule_delete" = delete(&mut self, namespace_id: NamespaceId, table_id: Option<TableId>) -> Result<()>;
     ]
 );
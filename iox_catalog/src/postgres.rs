@@ -1,39 +1,69 @@
 //! A Postgres backed implementation of the Catalog
 
 use crate::{
+    changes::{self, CatalogChange},
     interface::{
-        self, sealed::TransactionFinalize, CasFailure, Catalog, ColumnRepo,
-        ColumnTypeMismatchSnafu, Error, NamespaceRepo, ParquetFileRepo, PartitionRepo,
-        QueryPoolRepo, RepoCollection, Result, ShardRepo, SoftDeletedRows, TableRepo,
+        self, sealed::TransactionFinalize, validate_rollup_rule, validate_write_rate_limit,
+        CasFailure, Catalog, ColumnRepo, ColumnSoftDeletedSnafu, ColumnTypeMismatchSnafu, Error,
+        NamespaceRepo, ParquetFileRepo, PartitionRepo, QueryPoolRepo, RepoCollection, Result,
+        RollupRuleRepo, ShardRepo, SoftDeletedRows, TableRepo, TableSoftDeletedSnafu,
         TopicMetadataRepo, Transaction,
     },
-    metrics::MetricDecorator,
+    metrics::{MetricDecorator, PoolMetrics, ReplicaMetrics},
     DEFAULT_MAX_COLUMNS_PER_TABLE, DEFAULT_MAX_TABLES, SHARED_TOPIC_ID, SHARED_TOPIC_NAME,
 };
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId,
-    SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, Timestamp,
-    TopicId, TopicMetadata, TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
+    Column, ColumnId, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile,
+    ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId,
+    QueryPriority, RollupRule, RollupRuleParams, SequenceNumber, Shard, ShardId, ShardIndex,
+    SkippedCompaction, Table, TableId, Timestamp, TopicId, TopicMetadata, TypeCoercionPolicy,
+    TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::{debug, info, warn};
 use snafu::prelude::*;
 use sqlx::{
     migrate::Migrator,
-    postgres::{PgConnectOptions, PgPoolOptions},
+    postgres::{PgConnectOptions, PgListener, PgPoolOptions},
     types::Uuid,
     Acquire, ConnectOptions, Executor, Postgres, Row,
 };
 use sqlx_hotswap_pool::HotSwapPool;
-use std::{collections::HashMap, fmt::Display, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{sync::broadcast, task::JoinHandle};
 
 static MIGRATOR: Migrator = sqlx::migrate!();
 
 /// Maximum number of files deleted by [`ParquetFileRepo::delete_old_ids_only].
 const MAX_PARQUET_FILES_DELETED_ONCE: i64 = 1_000;
 
+/// How long to wait before listening for catalog changes again after the connection failed.
+const CHANGE_FEED_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the health and replication lag of read replicas is checked.
+const REPLICA_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Replication lag of a read replica in seconds.
+///
+/// A replica that has replayed everything it received is not lagging, even if the last replayed
+/// transaction is old because the primary is idle.
+const REPLICATION_LAG_QUERY: &str = r#"
+SELECT CASE
+    WHEN NOT pg_is_in_recovery() OR pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+    ELSE COALESCE(EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()), 0)
+END::float8;
+"#;
+
 /// Postgres connection options.
 #[derive(Debug, Clone)]
 pub struct PostgresConnectionOptions {
@@ -62,6 +92,16 @@ pub struct PostgresConnectionOptions {
     ///
     /// If an update is encountered, the underlying connection pool will be hot-swapped.
     pub hotswap_poll_interval: Duration,
+
+    /// DSNs of read replicas of the catalog, which may point to files like [`dsn`](Self::dsn).
+    ///
+    /// Reads that tolerate stale results are spread over the replicas, all other operations use
+    /// the primary. A replica that fails or lags behind by more than
+    /// [`max_replica_lag`](Self::max_replica_lag) is skipped until it recovers.
+    pub replica_dsns: Vec<String>,
+
+    /// Maximum replication lag of a read replica that serves reads.
+    pub max_replica_lag: Duration,
 }
 
 impl PostgresConnectionOptions {
@@ -79,6 +119,9 @@ impl PostgresConnectionOptions {
 
     /// Default value for [`hotswap_poll_interval`](Self::hotswap_poll_interval).
     pub const DEFAULT_HOTSWAP_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Default value for [`max_replica_lag`](Self::max_replica_lag).
+    pub const DEFAULT_MAX_REPLICA_LAG: Duration = Duration::from_secs(10);
 }
 
 impl Default for PostgresConnectionOptions {
@@ -91,6 +134,8 @@ impl Default for PostgresConnectionOptions {
             connect_timeout: Self::DEFAULT_CONNECT_TIMEOUT,
             idle_timeout: Self::DEFAULT_IDLE_TIMEOUT,
             hotswap_poll_interval: Self::DEFAULT_HOTSWAP_POLL_INTERVAL,
+            replica_dsns: vec![],
+            max_replica_lag: Self::DEFAULT_MAX_REPLICA_LAG,
         }
     }
 }
@@ -103,6 +148,12 @@ pub struct PostgresCatalog {
     time_provider: Arc<dyn TimeProvider>,
     // Connection options for display
     options: PostgresConnectionOptions,
+    changes: broadcast::Sender<CatalogChange>,
+    // Forwards the change notifications of the catalog triggers, started by the first subscriber
+    change_listener: parking_lot::Mutex<Option<JoinHandle<()>>>,
+    // Serve reads that tolerate stale results, if any are configured
+    replicas: Option<Arc<ReadReplicas>>,
+    replica_monitors: Vec<JoinHandle<()>>,
 }
 
 // struct to get return value from "select count(id) ..." query
@@ -117,21 +168,195 @@ impl PostgresCatalog {
         options: PostgresConnectionOptions,
         metrics: Arc<metric::Registry>,
     ) -> Result<Self> {
-        let pool = new_pool(&options)
+        let pool = new_pool(&options, false)
             .await
             .map_err(|e| Error::SqlxError { source: e })?;
 
+        let replicas = if options.replica_dsns.is_empty() {
+            None
+        } else {
+            let replicas = ReadReplicas::connect(&options, &metrics)
+                .await
+                .map_err(|e| Error::SqlxError { source: e })?;
+            Some(Arc::new(replicas))
+        };
+        let replica_monitors = replicas
+            .iter()
+            .flat_map(|replicas| replicas.replicas.iter())
+            .map(|replica| {
+                tokio::spawn(monitor_replica(
+                    Arc::clone(replica),
+                    options.max_replica_lag,
+                ))
+            })
+            .collect();
+
         Ok(Self {
             pool,
             metrics,
             time_provider: Arc::new(SystemProvider::new()),
             options,
+            changes: changes::sender(),
+            change_listener: Default::default(),
+            replicas,
+            replica_monitors,
         })
     }
 
     fn schema_name(&self) -> &str {
         &self.options.schema_name
     }
+
+    fn wrap_transaction(
+        &self,
+        transaction: sqlx::Transaction<'static, Postgres>,
+    ) -> Box<dyn Transaction> {
+        Box::new(MetricDecorator::new(
+            PostgresTxn {
+                inner: PostgresTxnInner::Txn(Some(transaction)),
+                time_provider: Arc::clone(&self.time_provider),
+                replicas: None,
+                restored_tables: BTreeSet::new(),
+            },
+            Arc::clone(&self.metrics),
+        ))
+    }
+}
+
+impl Drop for PostgresCatalog {
+    fn drop(&mut self) {
+        if let Some(listener) = self.change_listener.get_mut() {
+            listener.abort();
+        }
+        for monitor in &self.replica_monitors {
+            monitor.abort();
+        }
+    }
+}
+
+/// The read replicas of a [`PostgresCatalog`].
+#[derive(Debug)]
+struct ReadReplicas {
+    replicas: Vec<Arc<Replica>>,
+    // Index of the replica to try first for the next read
+    next: AtomicUsize,
+    // Reads that could not be served by a replica
+    primary_metrics: PoolMetrics,
+}
+
+impl ReadReplicas {
+    /// Connect to the replicas in `options`.
+    ///
+    /// Replicas are connected to lazily, so that an unreachable replica does not prevent the
+    /// catalog from starting. It is marked unhealthy until [`monitor_replica`] finds it usable.
+    async fn connect(
+        options: &PostgresConnectionOptions,
+        metrics: &metric::Registry,
+    ) -> Result<Self, sqlx::Error> {
+        let mut replicas = Vec::with_capacity(options.replica_dsns.len());
+        for (i, dsn) in options.replica_dsns.iter().enumerate() {
+            let name = format!("replica_{i}");
+            let pool = new_pool(
+                &PostgresConnectionOptions {
+                    dsn: dsn.clone(),
+                    ..options.clone()
+                },
+                true,
+            )
+            .await?;
+
+            let replica = Replica {
+                metrics: ReplicaMetrics::new(metrics, &name),
+                name,
+                pool,
+                // so that the initial health check logs why the replica is not usable
+                healthy: AtomicBool::new(true),
+            };
+            replica.check_health(options.max_replica_lag).await;
+            replicas.push(Arc::new(replica));
+        }
+
+        Ok(Self {
+            replicas,
+            next: AtomicUsize::new(0),
+            primary_metrics: PoolMetrics::new(metrics, "primary"),
+        })
+    }
+
+    /// Pick the replica to serve the next read, rotating over the healthy ones.
+    ///
+    /// Returns [`None`] if no replica is healthy.
+    fn pick(&self) -> Option<&Replica> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let picked = (0..self.replicas.len())
+            .map(|i| &self.replicas[(start + i) % self.replicas.len()])
+            .find(|replica| replica.healthy.load(Ordering::Relaxed));
+
+        if picked.is_none() {
+            self.primary_metrics.fallback_unhealthy();
+        }
+        picked.map(|replica| replica.as_ref())
+    }
+}
+
+/// A read replica of a [`PostgresCatalog`].
+#[derive(Debug)]
+struct Replica {
+    name: String,
+    pool: HotSwapPool<Postgres>,
+    // Set by the health checks, cleared by failed reads
+    healthy: AtomicBool,
+    metrics: ReplicaMetrics,
+}
+
+impl Replica {
+    /// Use the replica only if it is reachable and lags behind by at most `max_lag`.
+    async fn check_health(&self, max_lag: Duration) {
+        let lag = sqlx::query_scalar::<_, f64>(REPLICATION_LAG_QUERY)
+            .fetch_one(&self.pool)
+            .await
+            .map(|lag| Duration::from_secs_f64(lag.max(0.0)));
+
+        let healthy = matches!(lag, Ok(lag) if lag <= max_lag);
+        if healthy != self.healthy.swap(healthy, Ordering::Relaxed) {
+            match &lag {
+                Ok(lag) if healthy => {
+                    info!(replica=%self.name, ?lag, "catalog read replica is healthy")
+                }
+                Ok(lag) => warn!(
+                    replica=%self.name,
+                    ?lag,
+                    "catalog read replica lags behind, reading from the primary"
+                ),
+                Err(e) => warn!(
+                    replica=%self.name,
+                    error=%e,
+                    "catalog read replica is unreachable, reading from the primary"
+                ),
+            }
+        }
+        self.metrics.set_health(healthy, lag.ok());
+    }
+
+    /// Stop using the replica until the next health check because a read failed on it.
+    fn failed(&self, e: &sqlx::Error) {
+        warn!(
+            replica=%self.name,
+            error=%e,
+            "catalog read failed on read replica, retrying on the primary"
+        );
+        self.healthy.store(false, Ordering::Relaxed);
+        self.metrics.set_health(false, None);
+        self.metrics.read_failed();
+    }
+}
+
+/// Check the health of `replica` periodically.
+async fn monitor_replica(replica: Arc<Replica>, max_lag: Duration) {
+    loop {
+        tokio::time::sleep(REPLICA_HEALTH_CHECK_INTERVAL).await;
+        replica.check_health(max_lag).await;
+    }
 }
 
 impl Display for PostgresCatalog {
@@ -151,6 +376,46 @@ impl Display for PostgresCatalog {
 pub struct PostgresTxn {
     inner: PostgresTxnInner,
     time_provider: Arc<dyn TimeProvider>,
+    // Only set outside of transactions
+    replicas: Option<Arc<ReadReplicas>>,
+    // Tables whose ID sequence must be advanced on commit, see `restored_id`
+    restored_tables: BTreeSet<&'static str>,
+}
+
+/// Run a read that tolerates stale results on a healthy read replica, falling back to the
+/// primary if there is none or the read fails on the replica.
+///
+/// The query is built by the closure-like `|executor| <future>` argument, which may be evaluated
+/// twice.
+macro_rules! read_stale {
+    ($self:ident, |$executor:ident| $query:expr) => {{
+        let replicas = $self.replicas.clone();
+        let replica_result = match replicas.as_deref().and_then(ReadReplicas::pick) {
+            Some(replica) => {
+                let $executor = &replica.pool;
+                match $query.await {
+                    Err(e) if !matches!(e, sqlx::Error::RowNotFound) => {
+                        replica.failed(&e);
+                        None
+                    }
+                    result => Some(replica.metrics.pool.record(result)),
+                }
+            }
+            None => None,
+        };
+
+        match replica_result {
+            Some(result) => result,
+            None => {
+                let $executor = &mut $self.inner;
+                let result = $query.await;
+                match &replicas {
+                    Some(replicas) => replicas.primary_metrics.record(result),
+                    None => result,
+                }
+            }
+        }
+    }};
 }
 
 #[derive(Debug)]
@@ -243,6 +508,36 @@ impl<'c> Executor<'c> for &'c mut PostgresTxnInner {
     }
 }
 
+impl PostgresTxn {
+    /// Record that a record with an explicit ID was restored into `table`.
+    ///
+    /// Records restored with an explicit ID do not advance the ID sequence of their table, so
+    /// without moving it past the largest ID in the table the next created record could collide
+    /// with a restored one. Within a transaction, each sequence is moved once on commit rather
+    /// than after every restored record.
+    async fn restored_id(&mut self, table: &'static str) -> Result<()> {
+        match &self.inner {
+            PostgresTxnInner::Txn(_) => {
+                self.restored_tables.insert(table);
+                Ok(())
+            }
+            PostgresTxnInner::Oneshot(_) => self.advance_id_sequence(table).await,
+        }
+    }
+
+    /// Move the ID sequence of `table` past the largest ID in the table.
+    async fn advance_id_sequence(&mut self, table: &str) -> Result<()> {
+        sqlx::query(&format!(
+            "SELECT setval(pg_get_serial_sequence('{table}', 'id'), (SELECT MAX(id) FROM {table}));"
+        ))
+        .execute(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+}
+
 impl Drop for PostgresTxn {
     fn drop(&mut self) {
         if let PostgresTxnInner::Txn(Some(_)) = self.inner {
@@ -257,6 +552,10 @@ impl Drop for PostgresTxn {
 #[async_trait]
 impl TransactionFinalize for PostgresTxn {
     async fn commit_inplace(&mut self) -> Result<(), Error> {
+        for table in std::mem::take(&mut self.restored_tables) {
+            self.advance_id_sequence(table).await?;
+        }
+
         match &mut self.inner {
             PostgresTxnInner::Txn(txn) => txn
                 .take()
@@ -348,13 +647,24 @@ DO NOTHING;
             .await
             .map_err(|e| Error::SqlxError { source: e })?;
 
-        Ok(Box::new(MetricDecorator::new(
-            PostgresTxn {
-                inner: PostgresTxnInner::Txn(Some(transaction)),
-                time_provider: Arc::clone(&self.time_provider),
-            },
-            Arc::clone(&self.metrics),
-        )))
+        Ok(self.wrap_transaction(transaction))
+    }
+
+    async fn start_snapshot_transaction(&self) -> Result<Box<dyn Transaction>, Error> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        // The default READ COMMITTED isolation takes a new snapshot for every statement. This must
+        // be the first statement of the transaction.
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY;")
+            .execute(&mut transaction)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(self.wrap_transaction(transaction))
     }
 
     async fn repositories(&self) -> Box<dyn RepoCollection> {
@@ -362,6 +672,8 @@ DO NOTHING;
             PostgresTxn {
                 inner: PostgresTxnInner::Oneshot(self.pool.clone()),
                 time_provider: Arc::clone(&self.time_provider),
+                replicas: self.replicas.clone(),
+                restored_tables: BTreeSet::new(),
             },
             Arc::clone(&self.metrics),
         ))
@@ -374,14 +686,113 @@ DO NOTHING;
     fn time_provider(&self) -> Arc<dyn TimeProvider> {
         Arc::clone(&self.time_provider)
     }
+
+    fn subscribe_changes(&self) -> broadcast::Receiver<CatalogChange> {
+        let receiver = self.changes.subscribe();
+        self.change_listener.lock().get_or_insert_with(|| {
+            tokio::spawn(listen_for_changes(
+                self.pool.clone(),
+                format!("{}_changes", self.schema_name()),
+                self.changes.clone(),
+            ))
+        });
+        receiver
+    }
+}
+
+/// Forward the notifications the catalog triggers publish on `channel` to `sender`, reconnecting
+/// whenever the connection fails.
+async fn listen_for_changes(
+    pool: HotSwapPool<Postgres>,
+    channel: String,
+    sender: broadcast::Sender<CatalogChange>,
+) {
+    let mut reconnect = false;
+    loop {
+        if let Err(e) = forward_changes(&pool, &channel, &sender, reconnect).await {
+            warn!(error=%e, "catalog change feed failed, changes may have been missed");
+        }
+        reconnect = true;
+        tokio::time::sleep(CHANGE_FEED_RETRY_INTERVAL).await;
+    }
+}
+
+/// Forward notifications until the connection is lost.
+///
+/// If `reconnect` is set, [`CatalogChange::Reset`] is published once listening, as notifications
+/// sent while disconnected are lost.
+async fn forward_changes(
+    pool: &HotSwapPool<Postgres>,
+    channel: &str,
+    sender: &broadcast::Sender<CatalogChange>,
+    reconnect: bool,
+) -> Result<(), sqlx::Error> {
+    // Connect through the current pool, so that a hot-swapped DSN is picked up on reconnect.
+    let mut listener = PgListener::connect_with(&pool.current()).await?;
+    listener.listen(channel).await?;
+    info!(%channel, "listening for catalog changes");
+
+    if reconnect {
+        changes::publish(sender, CatalogChange::Reset);
+    }
+
+    loop {
+        match listener.try_recv().await? {
+            Some(notification) => match parse_change(notification.payload()) {
+                Some(change) => changes::publish(sender, change),
+                None => warn!(
+                    payload = notification.payload(),
+                    "ignoring unrecognised catalog change notification"
+                ),
+            },
+            // Reconnect with a fresh listener, so that subscribers are told about the gap.
+            None => {
+                warn!("catalog change feed connection lost, changes may have been missed");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Parse a notification payload of the catalog change triggers, see the
+/// `catalog-change-feed` migration for the format.
+fn parse_change(payload: &str) -> Option<CatalogChange> {
+    let mut parts = payload.split(':');
+    let kind = parts.next()?;
+    let mut next_id = || parts.next()?.parse::<i64>().ok();
+
+    let change = match kind {
+        "namespace" => CatalogChange::Namespace {
+            id: NamespaceId::new(next_id()?),
+        },
+        "table" => CatalogChange::Table {
+            namespace_id: NamespaceId::new(next_id()?),
+            id: TableId::new(next_id()?),
+        },
+        "partition" => CatalogChange::Partition {
+            table_id: TableId::new(next_id()?),
+            id: PartitionId::new(next_id()?),
+        },
+        "parquet_file" => CatalogChange::ParquetFile {
+            table_id: TableId::new(next_id()?),
+            partition_id: PartitionId::new(next_id()?),
+        },
+        _ => return None,
+    };
+
+    parts.next().is_none().then_some(change)
 }
 
 /// Creates a new [`sqlx::Pool`] from a database config and an explicit DSN.
 ///
 /// This function doesn't support the IDPE specific `dsn-file://` uri scheme.
+///
+/// If `lazy` is set, the pool is returned without connecting to the database, so an unreachable
+/// database only fails the queries run on the pool.
 async fn new_raw_pool(
     options: &PostgresConnectionOptions,
     parsed_dsn: &str,
+    lazy: bool,
 ) -> Result<sqlx::Pool<Postgres>, sqlx::Error> {
     // sqlx exposes some options as pool options, while other options are available as connection options.
     let mut connect_options = PgConnectOptions::from_str(parsed_dsn)?;
@@ -391,7 +802,7 @@ async fn new_raw_pool(
     let app_name = options.app_name.clone();
     let app_name2 = options.app_name.clone(); // just to log below
     let schema_name = options.schema_name.clone();
-    let pool = PgPoolOptions::new()
+    let pool_options = PgPoolOptions::new()
         .min_connections(1)
         .max_connections(options.max_conns)
         .acquire_timeout(options.connect_timeout)
@@ -424,9 +835,11 @@ async fn new_raw_pool(
                 c.execute("SET timezone = 'UTC';").await?;
                 Ok(())
             })
-        })
-        .connect_with(connect_options)
-        .await?;
+        });
+    if lazy {
+        return Ok(pool_options.connect_lazy_with(connect_options));
+    }
+    let pool = pool_options.connect_with(connect_options).await?;
 
     // Log a connection was successfully established and include the application
     // name for cross-correlation between Conductor logs & database connections.
@@ -447,14 +860,18 @@ async fn new_raw_pool(
 /// The pool is replaced only once the new pool is successfully created.
 /// The [`new_raw_pool`] function will return a new pool only if the connection
 /// is successfull (see [`sqlx::pool::PoolOptions::test_before_acquire`]).
+///
+/// If `lazy` is set, the initial pool is created without connecting to the
+/// database, see [`new_raw_pool`].
 async fn new_pool(
     options: &PostgresConnectionOptions,
+    lazy: bool,
 ) -> Result<HotSwapPool<Postgres>, sqlx::Error> {
     let parsed_dsn = match get_dsn_file_path(&options.dsn) {
         Some(filename) => std::fs::read_to_string(filename)?,
         None => options.dsn.clone(),
     };
-    let pool = HotSwapPool::new(new_raw_pool(options, &parsed_dsn).await?);
+    let pool = HotSwapPool::new(new_raw_pool(options, &parsed_dsn, lazy).await?);
     let polling_interval = options.hotswap_poll_interval;
 
     if let Some(dsn_file) = get_dsn_file_path(&options.dsn) {
@@ -483,7 +900,7 @@ async fn new_pool(
                     if new_dsn == current_dsn {
                         Ok(None)
                     } else {
-                        let new_pool = new_raw_pool(options, &new_dsn).await?;
+                        let new_pool = new_raw_pool(options, &new_dsn, false).await?;
                         let old_pool = pool.replace(new_pool);
                         info!("replaced hotswap pool");
                         info!(?old_pool, "closing old DB connection pool");
@@ -561,6 +978,10 @@ impl RepoCollection for PostgresTxn {
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn rollup_rules(&mut self) -> &mut dyn RollupRuleRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -603,6 +1024,31 @@ WHERE name = $1;
 
         Ok(Some(topic))
     }
+
+    async fn list(&mut self) -> Result<Vec<TopicMetadata>> {
+        sqlx::query_as::<_, TopicMetadata>("SELECT * FROM topic;")
+            .fetch_all(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn restore(&mut self, topic: &TopicMetadata) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO topic (
+    id, name )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2 );
+        "#,
+        )
+        .bind(topic.id) // $1
+        .bind(&topic.name) // $2
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("topic").await
+    }
 }
 
 #[async_trait]
@@ -624,6 +1070,31 @@ RETURNING *;
 
         Ok(rec)
     }
+
+    async fn list(&mut self) -> Result<Vec<QueryPool>> {
+        sqlx::query_as::<_, QueryPool>("SELECT * FROM query_pool;")
+            .fetch_all(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn restore(&mut self, query_pool: &QueryPool) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO query_pool (
+    id, name )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2 );
+        "#,
+        )
+        .bind(query_pool.id) // $1
+        .bind(&query_pool.name) // $2
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("query_pool").await
+    }
 }
 
 #[async_trait]
@@ -668,15 +1139,14 @@ impl NamespaceRepo for PostgresTxn {
     }
 
     async fn list(&mut self, deleted: SoftDeletedRows) -> Result<Vec<Namespace>> {
-        let rec = sqlx::query_as::<_, Namespace>(
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Namespace>(
             format!(
                 r#"SELECT * FROM namespace WHERE {v};"#,
                 v = deleted.as_sql_predicate()
             )
             .as_str(),
         )
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
@@ -687,7 +1157,7 @@ impl NamespaceRepo for PostgresTxn {
         id: NamespaceId,
         deleted: SoftDeletedRows,
     ) -> Result<Option<Namespace>> {
-        let rec = sqlx::query_as::<_, Namespace>(
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Namespace>(
             format!(
                 r#"SELECT * FROM namespace WHERE id=$1 AND {v};"#,
                 v = deleted.as_sql_predicate()
@@ -695,8 +1165,7 @@ impl NamespaceRepo for PostgresTxn {
             .as_str(),
         )
         .bind(id) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+        .fetch_one(executor));
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -712,7 +1181,7 @@ impl NamespaceRepo for PostgresTxn {
         name: &str,
         deleted: SoftDeletedRows,
     ) -> Result<Option<Namespace>> {
-        let rec = sqlx::query_as::<_, Namespace>(
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Namespace>(
             format!(
                 r#"SELECT * FROM namespace WHERE name=$1 AND {v};"#,
                 v = deleted.as_sql_predicate()
@@ -720,8 +1189,7 @@ impl NamespaceRepo for PostgresTxn {
             .as_str(),
         )
         .bind(name) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+        .fetch_one(executor));
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -745,6 +1213,51 @@ impl NamespaceRepo for PostgresTxn {
             .map(|_| ())
     }
 
+    async fn undelete(&mut self, name: &str, deleted_after: Timestamp) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET deleted_at = NULL
+WHERE name = $1
+AND deleted_at IS NOT NULL
+AND deleted_at >= $2
+RETURNING *;
+        "#,
+        )
+        .bind(name) // $1
+        .bind(deleted_after) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::SoftDeletedNamespaceNotFound {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn purge(&mut self, name: &str) -> Result<()> {
+        // tables, columns, partitions, parquet files and rollup rules are removed by the
+        // `ON DELETE CASCADE` of their foreign keys
+        let result =
+            sqlx::query(r#"DELETE FROM namespace WHERE name = $1 AND deleted_at IS NOT NULL;"#)
+                .bind(name) // $1
+                .execute(&mut self.inner)
+                .await
+                .context(interface::CouldNotDeleteNamespaceSnafu)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::SoftDeletedNamespaceNotFound {
+                name: name.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let rec = sqlx::query_as::<_, Namespace>(
             r#"
@@ -793,16 +1306,23 @@ RETURNING *;
         Ok(namespace)
     }
 
-    async fn update_retention_period(
+    async fn update_lines_per_second_limit(
         &mut self,
         name: &str,
-        retention_period_ns: Option<i64>,
+        new_max: Option<i64>,
     ) -> Result<Namespace> {
+        validate_write_rate_limit(new_max)?;
+
         let rec = sqlx::query_as::<_, Namespace>(
-            r#"UPDATE namespace SET retention_period_ns = $1 WHERE name = $2 RETURNING *;"#,
+            r#"
+UPDATE namespace
+SET max_lines_per_second = $1
+WHERE name = $2
+RETURNING *;
+        "#,
         )
-        .bind(retention_period_ns) // $1
-        .bind(name) // $2
+        .bind(new_max)
+        .bind(name)
         .fetch_one(&mut self.inner)
         .await;
 
@@ -815,36 +1335,235 @@ RETURNING *;
 
         Ok(namespace)
     }
-}
 
-#[async_trait]
-impl TableRepo for PostgresTxn {
-    async fn create_or_get(&mut self, name: &str, namespace_id: NamespaceId) -> Result<Table> {
-        // A simple insert statement becomes quite complicated in order to avoid checking the table
-        // limits in a select and then conditionally inserting (which would be racey).
-        //
-        // from https://www.postgresql.org/docs/current/sql-insert.html
-        //   "INSERT inserts new rows into a table. One can insert one or more rows specified by
-        //   value expressions, or zero or more rows resulting from a query."
-        // By using SELECT rather than VALUES it will insert zero rows if it finds a null in the
-        // subquery, i.e. if count >= max_tables. fetch_one() will return a RowNotFound error if
-        // nothing was inserted. Not pretty!
-        let rec = sqlx::query_as::<_, Table>(
+    async fn update_batch_bytes_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        validate_write_rate_limit(new_max)?;
+
+        let rec = sqlx::query_as::<_, Namespace>(
             r#"
-INSERT INTO table_name ( name, namespace_id )
-SELECT $1, id FROM (
-    SELECT namespace.id AS id, max_tables, COUNT(table_name.*) AS count
-    FROM namespace LEFT JOIN table_name ON namespace.id = table_name.namespace_id
-    WHERE namespace.id = $2
-    GROUP BY namespace.max_tables, table_name.namespace_id, namespace.id
-) AS get_count WHERE count < max_tables
-ON CONFLICT ON CONSTRAINT table_name_unique
-DO UPDATE SET name = table_name.name
+UPDATE namespace
+SET max_batch_bytes_per_second = $1
+WHERE name = $2
 RETURNING *;
         "#,
         )
-        .bind(name) // $1
-        .bind(namespace_id) // $2
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_concurrent_queries_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i32>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_concurrent_queries = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_query_memory_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_query_memory_bytes = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_type_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: TypeCoercionPolicy,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET type_coercion_policy = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(policy)
+        .bind(name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_default_query_priority(
+        &mut self,
+        name: &str,
+        priority: QueryPriority,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET default_query_priority = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(priority)
+        .bind(name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_retention_period(
+        &mut self,
+        name: &str,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"UPDATE namespace SET retention_period_ns = $1 WHERE name = $2 RETURNING *;"#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn restore(&mut self, namespace: &Namespace) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO namespace (
+    id, name, retention_period_ns, topic_id, query_pool_id, max_tables,
+    max_columns_per_table, max_lines_per_second, max_batch_bytes_per_second,
+    max_concurrent_queries, max_query_memory_bytes, type_coercion_policy, default_query_priority,
+    deleted_at )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 );
+        "#,
+        )
+        .bind(namespace.id) // $1
+        .bind(&namespace.name) // $2
+        .bind(namespace.retention_period_ns) // $3
+        .bind(namespace.topic_id) // $4
+        .bind(namespace.query_pool_id) // $5
+        .bind(namespace.max_tables) // $6
+        .bind(namespace.max_columns_per_table) // $7
+        .bind(namespace.max_lines_per_second) // $8
+        .bind(namespace.max_batch_bytes_per_second) // $9
+        .bind(namespace.max_concurrent_queries) // $10
+        .bind(namespace.max_query_memory_bytes) // $11
+        .bind(namespace.type_coercion_policy) // $12
+        .bind(namespace.default_query_priority) // $13
+        .bind(namespace.deleted_at) // $14
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("namespace").await
+    }
+}
+
+#[async_trait]
+impl TableRepo for PostgresTxn {
+    async fn create_or_get(&mut self, name: &str, namespace_id: NamespaceId) -> Result<Table> {
+        // A simple insert statement becomes quite complicated in order to avoid checking the table
+        // limits in a select and then conditionally inserting (which would be racey).
+        //
+        // from https://www.postgresql.org/docs/current/sql-insert.html
+        //   "INSERT inserts new rows into a table. One can insert one or more rows specified by
+        //   value expressions, or zero or more rows resulting from a query."
+        // By using SELECT rather than VALUES it will insert zero rows if it finds a null in the
+        // subquery, i.e. if count >= max_tables. fetch_one() will return a RowNotFound error if
+        // nothing was inserted. Not pretty!
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+INSERT INTO table_name ( name, namespace_id )
+SELECT $1, id FROM (
+    SELECT namespace.id AS id, max_tables, COUNT(table_name.*) AS count
+    FROM namespace LEFT JOIN table_name
+        ON namespace.id = table_name.namespace_id AND table_name.deleted_at IS NULL
+    WHERE namespace.id = $2
+    GROUP BY namespace.max_tables, table_name.namespace_id, namespace.id
+) AS get_count WHERE count < max_tables
+ON CONFLICT ON CONSTRAINT table_name_unique
+DO UPDATE SET name = table_name.name
+RETURNING *;
+        "#,
+        )
+        .bind(name) // $1
+        .bind(namespace_id) // $2
         .fetch_one(&mut self.inner)
         .await
         .map_err(|e| match e {
@@ -861,20 +1580,64 @@ RETURNING *;
             }
         })?;
 
+        ensure!(
+            rec.deleted_at.is_none(),
+            TableSoftDeletedSnafu { name, namespace_id }
+        );
+
         Ok(rec)
     }
 
-    async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>> {
+    async fn create(
+        &mut self,
+        name: &str,
+        namespace_id: NamespaceId,
+        primary_key: &[String],
+    ) -> Result<Table> {
+        // Like create_or_get, inserting from the table count subquery enforces the table limit.
         let rec = sqlx::query_as::<_, Table>(
             r#"
+INSERT INTO table_name ( name, namespace_id, primary_key )
+SELECT $1, id, $3 FROM (
+    SELECT namespace.id AS id, max_tables, COUNT(table_name.*) AS count
+    FROM namespace LEFT JOIN table_name
+        ON namespace.id = table_name.namespace_id AND table_name.deleted_at IS NULL
+    WHERE namespace.id = $2
+    GROUP BY namespace.max_tables, table_name.namespace_id, namespace.id
+) AS get_count WHERE count < max_tables
+RETURNING *;
+        "#,
+        )
+        .bind(name) // $1
+        .bind(namespace_id) // $2
+        .bind(primary_key) // $3
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableCreateLimitError {
+                table_name: name.to_string(),
+                namespace_id,
+            },
+            _ if is_unique_violation(&e) => Error::NameExists {
+                name: name.to_string(),
+            },
+            _ if is_fk_violation(&e) => Error::ForeignKeyViolation { source: e },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>> {
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Table>(
+            r#"
 SELECT *
 FROM table_name
 WHERE id = $1;
             "#,
         )
         .bind(table_id) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+        .fetch_one(executor));
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -890,17 +1653,16 @@ WHERE id = $1;
         namespace_id: NamespaceId,
         name: &str,
     ) -> Result<Option<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Table>(
             r#"
 SELECT *
 FROM table_name
-WHERE namespace_id = $1 AND name = $2;
+WHERE namespace_id = $1 AND name = $2 AND deleted_at IS NULL;
             "#,
         )
         .bind(namespace_id) // $1
         .bind(name) // $2
-        .fetch_one(&mut self.inner)
-        .await;
+        .fetch_one(executor));
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -911,30 +1673,83 @@ WHERE namespace_id = $1 AND name = $2;
         Ok(Some(table))
     }
 
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
-            r#"
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Table>> {
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Table>(
+            format!(
+                r#"
 SELECT *
 FROM table_name
-WHERE namespace_id = $1;
+WHERE namespace_id = $1 AND {v};
             "#,
+                v = deleted.as_sql_predicate()
+            )
+            .as_str(),
         )
         .bind(namespace_id)
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
     async fn list(&mut self) -> Result<Vec<Table>> {
-        let rec = sqlx::query_as::<_, Table>("SELECT * FROM table_name;")
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })?;
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Table>(
+            "SELECT * FROM table_name;"
+        )
+        .fetch_all(executor))
+        .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
+
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET deleted_at = COALESCE(deleted_at, $1)
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(flagged_at) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let table = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(table)
+    }
+
+    async fn restore(&mut self, table: &Table) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO table_name (
+    id, namespace_id, name, deleted_at, primary_key )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2, $3, $4, $5 );
+        "#,
+        )
+        .bind(table.id) // $1
+        .bind(table.namespace_id) // $2
+        .bind(&table.name) // $3
+        .bind(table.deleted_at) // $4
+        .bind(&table.primary_key) // $5
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("table_name").await
+    }
 }
 
 #[async_trait]
@@ -951,7 +1766,8 @@ INSERT INTO column_name ( name, table_id, column_type )
 SELECT $1, table_id, $3 FROM (
     SELECT max_columns_per_table, namespace.id, table_name.id as table_id, COUNT(column_name.*) AS count
     FROM namespace LEFT JOIN table_name ON namespace.id = table_name.namespace_id
-                   LEFT JOIN column_name ON table_name.id = column_name.table_id
+                   LEFT JOIN column_name
+                       ON table_name.id = column_name.table_id AND column_name.deleted_at IS NULL
     WHERE table_name.id = $2
     GROUP BY namespace.max_columns_per_table, namespace.id, table_name.id
 ) AS get_count WHERE count < max_columns_per_table
@@ -978,6 +1794,10 @@ RETURNING *;
             }
         }})?;
 
+        ensure!(
+            rec.deleted_at.is_none(),
+            ColumnSoftDeletedSnafu { name, table_id }
+        );
         ensure!(
             rec.column_type == column_type,
             ColumnTypeMismatchSnafu {
@@ -990,46 +1810,83 @@ RETURNING *;
         Ok(rec)
     }
 
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>> {
-        let rec = sqlx::query_as::<_, Column>(
-            r#"
-SELECT column_name.* FROM table_name
-INNER JOIN column_name on column_name.table_id = table_name.id
-WHERE table_name.namespace_id = $1;
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Column>> {
+        // columns of a deleted table count as deleted
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Column>(
+            format!(
+                r#"
+SELECT * FROM (
+    SELECT column_name.id, column_name.table_id, column_name.name, column_name.column_type,
+           COALESCE(column_name.deleted_at, table_name.deleted_at) AS deleted_at
+    FROM table_name
+    INNER JOIN column_name on column_name.table_id = table_name.id
+    WHERE table_name.namespace_id = $1
+) AS namespace_columns
+WHERE {v};
             "#,
+                v = deleted.as_sql_predicate()
+            )
+            .as_str(),
         )
         .bind(namespace_id)
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
     async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>> {
-        let rec = sqlx::query_as::<_, Column>(
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Column>(
             r#"
 SELECT * FROM column_name
-WHERE table_id = $1;
+WHERE table_id = $1 AND deleted_at IS NULL;
             "#,
         )
         .bind(table_id)
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
     async fn list(&mut self) -> Result<Vec<Column>> {
-        let rec = sqlx::query_as::<_, Column>("SELECT * FROM column_name;")
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })?;
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, Column>(
+            "SELECT * FROM column_name;"
+        )
+        .fetch_all(executor))
+        .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+
+        let rec = sqlx::query_as::<_, Column>(
+            r#"
+UPDATE column_name
+SET deleted_at = COALESCE(deleted_at, $1)
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(flagged_at) // $1
+        .bind(column_id) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let column = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::ColumnNotFound { id: column_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(column)
+    }
+
     async fn create_or_get_many_unchecked(
         &mut self,
         table_id: TableId,
@@ -1075,6 +1932,13 @@ RETURNING *;
 
         for existing in &out {
             let want = columns.get(existing.name.as_str()).unwrap();
+            ensure!(
+                existing.deleted_at.is_none(),
+                ColumnSoftDeletedSnafu {
+                    name: &existing.name,
+                    table_id,
+                }
+            );
             ensure!(
                 existing.column_type == *want,
                 ColumnTypeMismatchSnafu {
@@ -1087,6 +1951,27 @@ RETURNING *;
 
         Ok(out)
     }
+
+    async fn restore(&mut self, column: &Column) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO column_name (
+    id, table_id, name, column_type, deleted_at )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2, $3, $4, $5 );
+        "#,
+        )
+        .bind(column.id) // $1
+        .bind(column.table_id) // $2
+        .bind(&column.name) // $3
+        .bind(column.column_type) // $4
+        .bind(column.deleted_at) // $5
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("column_name").await
+    }
 }
 
 #[async_trait]
@@ -1182,6 +2067,26 @@ WHERE id = $2;
 
         Ok(())
     }
+
+    async fn restore(&mut self, shard: &Shard) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO shard (
+    id, topic_id, shard_index, min_unpersisted_sequence_number )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2, $3, $4 );
+        "#,
+        )
+        .bind(shard.id) // $1
+        .bind(shard.topic_id) // $2
+        .bind(shard.shard_index) // $3
+        .bind(shard.min_unpersisted_sequence_number) // $4
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("shard").await
+    }
 }
 
 #[async_trait]
@@ -1195,13 +2100,15 @@ impl PartitionRepo for PostgresTxn {
         // Note: since sort_key is now an array, we must explicitly insert '{}' which is an empty
         // array rather than NULL which sqlx will throw `UnexpectedNullError` while is is doing
         // `ColumnDecode`
+        //
+        // New partitions of a table with a declared primary key are sorted by it.
 
         let v = sqlx::query_as::<_, Partition>(
             r#"
 INSERT INTO partition
     ( partition_key, shard_id, table_id, sort_key)
 VALUES
-    ( $1, $2, $3, '{}')
+    ( $1, $2, $3, COALESCE((SELECT primary_key FROM table_name WHERE id = $3), '{}'))
 ON CONFLICT ON CONSTRAINT partition_key_unique
 DO UPDATE SET partition_key = partition.partition_key
 RETURNING *;
@@ -1233,6 +2140,8 @@ RETURNING *;
     }
 
     async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>> {
+        // Not read from a replica: the compactor reads the files of the partition from the
+        // primary, and a stale sort key could miss columns of the files.
         let rec = sqlx::query_as::<_, Partition>(r#"SELECT * FROM partition WHERE id = $1;"#)
             .bind(partition_id) // $1
             .fetch_one(&mut self.inner)
@@ -1248,7 +2157,7 @@ RETURNING *;
     }
 
     async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Partition>> {
-        sqlx::query_as::<_, Partition>(
+        read_stale!(self, |executor| sqlx::query_as::<_, Partition>(
             r#"
 SELECT *
 FROM partition
@@ -1256,20 +2165,18 @@ WHERE table_id = $1;
             "#,
         )
         .bind(table_id) // $1
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })
     }
 
     async fn list_ids(&mut self) -> Result<Vec<PartitionId>> {
-        sqlx::query_as(
+        read_stale!(self, |executor| sqlx::query_as(
             r#"
             SELECT p.id as partition_id
             FROM partition p
             "#,
         )
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })
     }
 
@@ -1312,12 +2219,17 @@ RETURNING *;
                 // To differentiate, we submit a get partition query, returning
                 // the actual sort key if successful.
                 //
+                // The query goes to the primary, as a read replica may not have
+                // the current sort key yet.
+                //
                 // NOTE: this is racy, but documented - this might return "Sort
                 // key differs! Old key: <old sort key you provided>"
                 return Err(CasFailure::ValueMismatch(
-                    PartitionRepo::get_by_id(self, partition_id)
+                    sqlx::query_as::<_, Partition>(r#"SELECT * FROM partition WHERE id = $1;"#)
+                        .bind(partition_id) // $1
+                        .fetch_optional(&mut self.inner)
                         .await
-                        .map_err(CasFailure::QueryError)?
+                        .map_err(|e| CasFailure::QueryError(Error::SqlxError { source: e }))?
                         .ok_or(CasFailure::QueryError(Error::PartitionNotFound {
                             id: partition_id,
                         }))?
@@ -1382,12 +2294,11 @@ skipped_at = EXCLUDED.skipped_at;
         &mut self,
         partition_id: PartitionId,
     ) -> Result<Option<SkippedCompaction>> {
-        let rec = sqlx::query_as::<_, SkippedCompaction>(
+        let rec = read_stale!(self, |executor| sqlx::query_as::<_, SkippedCompaction>(
             r#"SELECT * FROM skipped_compactions WHERE partition_id = $1;"#,
         )
         .bind(partition_id) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+        .fetch_one(executor));
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -1399,39 +2310,117 @@ skipped_at = EXCLUDED.skipped_at;
     }
 
     async fn list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>> {
-        sqlx::query_as::<_, SkippedCompaction>(
+        read_stale!(self, |executor| sqlx::query_as::<_, SkippedCompaction>(
             r#"
 SELECT * FROM skipped_compactions
         "#,
         )
-        .fetch_all(&mut self.inner)
+        .fetch_all(executor))
+        .context(interface::CouldNotListSkippedCompactionsSnafu)
+    }
+
+    async fn delete_skipped_compactions(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>> {
+        sqlx::query_as::<_, SkippedCompaction>(
+            r#"
+DELETE FROM skipped_compactions
+WHERE partition_id = $1
+RETURNING *
+        "#,
+        )
+        .bind(partition_id)
+        .fetch_optional(&mut self.inner)
+        .await
+        .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
+    }
+
+    async fn try_acquire_lease(
+        &mut self,
+        partition_id: PartitionId,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<bool> {
+        let now = self.time_provider.now();
+        let expires_at = Timestamp::from(now + lease_duration);
+        let now = Timestamp::from(now);
+
+        let rec = sqlx::query(
+            r#"
+INSERT INTO partition_lease ( partition_id, owner, expires_at )
+VALUES ( $1, $2, $3 )
+ON CONFLICT ( partition_id )
+DO UPDATE
+SET
+owner = EXCLUDED.owner,
+expires_at = EXCLUDED.expires_at
+WHERE partition_lease.owner = EXCLUDED.owner OR partition_lease.expires_at <= $4
+RETURNING partition_id;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(owner) // $2
+        .bind(expires_at) // $3
+        .bind(now) // $4
+        .fetch_optional(&mut self.inner)
+        .await;
+
+        match rec {
+            Ok(rec) => Ok(rec.is_some()),
+            Err(e) if is_fk_violation(&e) => Err(Error::PartitionNotFound { id: partition_id }),
+            Err(e) => Err(Error::SqlxError { source: e }),
+        }
+    }
+
+    async fn release_lease(&mut self, partition_id: PartitionId, owner: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+DELETE FROM partition_lease
+WHERE partition_id = $1 AND owner = $2;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(owner) // $2
+        .execute(&mut self.inner)
         .await
-        .context(interface::CouldNotListSkippedCompactionsSnafu)
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
     }
 
-    async fn delete_skipped_compactions(
+    async fn set_rollup_interval(
         &mut self,
         partition_id: PartitionId,
-    ) -> Result<Option<SkippedCompaction>> {
-        sqlx::query_as::<_, SkippedCompaction>(
+        rollup_interval_ns: i64,
+    ) -> Result<Partition> {
+        let rec = sqlx::query_as::<_, Partition>(
             r#"
-DELETE FROM skipped_compactions
-WHERE partition_id = $1
-RETURNING *
+UPDATE partition
+SET rollup_interval_ns = $1
+WHERE id = $2
+RETURNING *;
         "#,
         )
-        .bind(partition_id)
-        .fetch_optional(&mut self.inner)
-        .await
-        .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
+        .bind(rollup_interval_ns) // $1
+        .bind(partition_id) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        match rec {
+            Ok(partition) => Ok(partition),
+            Err(sqlx::Error::RowNotFound) => Err(Error::PartitionNotFound { id: partition_id }),
+            Err(e) => Err(Error::SqlxError { source: e }),
+        }
     }
 
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
-        sqlx::query_as(r#"SELECT * FROM partition ORDER BY id DESC LIMIT $1;"#)
-            .bind(n as i64) // $1
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })
+        read_stale!(self, |executor| sqlx::query_as(
+            r#"SELECT * FROM partition ORDER BY id DESC LIMIT $1;"#
+        )
+        .bind(n as i64) // $1
+        .fetch_all(executor))
+        .map_err(|e| Error::SqlxError { source: e })
     }
 
     async fn partitions_new_file_between(
@@ -1451,12 +2440,36 @@ RETURNING *
                 .unwrap_or_default()
         );
 
-        sqlx::query_as(&sql)
+        read_stale!(self, |executor| sqlx::query_as(&sql)
             .bind(minimum_time) // $1
             .bind(maximum_time) // $2
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })
+            .fetch_all(executor))
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn restore(&mut self, partition: &Partition) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO partition (
+    id, shard_id, table_id, partition_key, sort_key, persisted_sequence_number,
+    new_file_at, rollup_interval_ns )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 );
+        "#,
+        )
+        .bind(partition.id) // $1
+        .bind(partition.shard_id) // $2
+        .bind(partition.table_id) // $3
+        .bind(&partition.partition_key) // $4
+        .bind(&partition.sort_key) // $5
+        .bind(partition.persisted_sequence_number) // $6
+        .bind(partition.new_file_at) // $7
+        .bind(partition.rollup_interval_ns) // $8
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("partition").await
     }
 }
 
@@ -1556,13 +2569,62 @@ RETURNING *;
         Ok(flagged)
     }
 
+    async fn flag_for_delete_by_table_deletion(&mut self) -> Result<Vec<ParquetFileId>> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+        let flagged = sqlx::query(
+            r#"
+                UPDATE parquet_file
+                SET to_delete = $1
+                FROM table_name
+                WHERE table_name.deleted_at IS NOT NULL
+                AND parquet_file.to_delete IS NULL
+                AND table_name.id = parquet_file.table_id
+                RETURNING parquet_file.id;
+            "#,
+        )
+        .bind(flagged_at) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        let flagged = flagged.into_iter().map(|row| row.get("id")).collect();
+        Ok(flagged)
+    }
+
+    async fn flag_for_delete_by_namespace_deletion(
+        &mut self,
+        deleted_before: Timestamp,
+    ) -> Result<Vec<ParquetFileId>> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+        let flagged = sqlx::query(
+            r#"
+                UPDATE parquet_file
+                SET to_delete = $1
+                FROM namespace
+                WHERE namespace.deleted_at IS NOT NULL
+                AND namespace.deleted_at < $2
+                AND parquet_file.to_delete IS NULL
+                AND namespace.id = parquet_file.namespace_id
+                RETURNING parquet_file.id;
+            "#,
+        )
+        .bind(flagged_at) // $1
+        .bind(deleted_before) // $2
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        let flagged = flagged.into_iter().map(|row| row.get("id")).collect();
+        Ok(flagged)
+    }
+
     async fn list_by_namespace_not_to_delete(
         &mut self,
         namespace_id: NamespaceId,
     ) -> Result<Vec<ParquetFile>> {
         // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
         // `parquet_metadata` column!!
-        sqlx::query_as::<_, ParquetFile>(
+        read_stale!(self, |executor| sqlx::query_as::<_, ParquetFile>(
             r#"
 SELECT parquet_file.id, parquet_file.shard_id, parquet_file.namespace_id,
        parquet_file.table_id, parquet_file.partition_id, parquet_file.object_store_id,
@@ -1577,15 +2639,14 @@ WHERE table_name.namespace_id = $1
              "#,
         )
         .bind(namespace_id) // $1
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })
     }
 
     async fn list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>> {
         // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
         // `parquet_metadata` column!!
-        sqlx::query_as::<_, ParquetFile>(
+        read_stale!(self, |executor| sqlx::query_as::<_, ParquetFile>(
             r#"
 SELECT id, shard_id, namespace_id, table_id, partition_id, object_store_id,
        max_sequence_number, min_time, max_time, to_delete, file_size_bytes,
@@ -1595,15 +2656,14 @@ WHERE table_id = $1 AND to_delete IS NULL;
              "#,
         )
         .bind(table_id) // $1
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })
     }
 
     async fn list_by_table(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>> {
         // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
         // `parquet_metadata` column!!
-        sqlx::query_as::<_, ParquetFile>(
+        read_stale!(self, |executor| sqlx::query_as::<_, ParquetFile>(
             r#"
 SELECT id, shard_id, namespace_id, table_id, partition_id, object_store_id,
        max_sequence_number, min_time, max_time, to_delete, file_size_bytes,
@@ -1613,8 +2673,7 @@ WHERE table_id = $1;
              "#,
         )
         .bind(table_id) // $1
-        .fetch_all(&mut self.inner)
-        .await
+        .fetch_all(executor))
         .map_err(|e| Error::SqlxError { source: e })
     }
 
@@ -1704,11 +2763,11 @@ RETURNING id;
     }
 
     async fn count(&mut self) -> Result<i64> {
-        let read_result =
-            sqlx::query_as::<_, Count>(r#"SELECT count(1) as count FROM parquet_file;"#)
-                .fetch_one(&mut self.inner)
-                .await
-                .map_err(|e| Error::SqlxError { source: e })?;
+        let read_result = read_stale!(self, |executor| sqlx::query_as::<_, Count>(
+            r#"SELECT count(1) as count FROM parquet_file;"#
+        )
+        .fetch_one(executor))
+        .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(read_result.count)
     }
@@ -1740,6 +2799,40 @@ WHERE object_store_id = $1;
 
         Ok(Some(parquet_file))
     }
+
+    async fn restore(&mut self, parquet_file: &ParquetFile) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO parquet_file (
+    id, shard_id, namespace_id, table_id, partition_id, object_store_id,
+    max_sequence_number, min_time, max_time, to_delete, file_size_bytes, row_count,
+    compaction_level, created_at, column_set, max_l0_created_at )
+OVERRIDING SYSTEM VALUE
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16 );
+        "#,
+        )
+        .bind(parquet_file.id) // $1
+        .bind(parquet_file.shard_id) // $2
+        .bind(parquet_file.namespace_id) // $3
+        .bind(parquet_file.table_id) // $4
+        .bind(parquet_file.partition_id) // $5
+        .bind(parquet_file.object_store_id) // $6
+        .bind(parquet_file.max_sequence_number) // $7
+        .bind(parquet_file.min_time) // $8
+        .bind(parquet_file.max_time) // $9
+        .bind(parquet_file.to_delete) // $10
+        .bind(parquet_file.file_size_bytes) // $11
+        .bind(parquet_file.row_count) // $12
+        .bind(parquet_file.compaction_level) // $13
+        .bind(parquet_file.created_at) // $14
+        .bind(&parquet_file.column_set) // $15
+        .bind(parquet_file.max_l0_created_at) // $16
+        .execute(&mut self.inner)
+        .await
+        .map_err(restore_error)?;
+
+        self.restored_id("parquet_file").await
+    }
 }
 
 /// The error code returned by Postgres for a unique constraint violation.
@@ -1760,6 +2853,111 @@ fn is_unique_violation(e: &sqlx::Error) -> bool {
     false
 }
 
+#[async_trait]
+impl RollupRuleRepo for PostgresTxn {
+    async fn create_or_update(&mut self, params: RollupRuleParams) -> Result<RollupRule> {
+        validate_rollup_rule(&params)?;
+
+        // there is one unique index for namespace-wide rules and one for table rules, so the
+        // conflict target depends on the kind of rule
+        let conflict_target = if params.table_id.is_some() {
+            "( namespace_id, table_id ) WHERE table_id IS NOT NULL"
+        } else {
+            "( namespace_id ) WHERE table_id IS NULL"
+        };
+        let query = format!(
+            r#"
+INSERT INTO rollup_rule
+    ( namespace_id, table_id, interval_ns, min_age_ns, float_aggregate, integer_aggregate,
+      unsigned_aggregate, boolean_aggregate, string_aggregate )
+VALUES
+    ( $1, $2, $3, $4, $5, $6, $7, $8, $9 )
+ON CONFLICT {conflict_target}
+DO UPDATE
+SET
+interval_ns = EXCLUDED.interval_ns,
+min_age_ns = EXCLUDED.min_age_ns,
+float_aggregate = EXCLUDED.float_aggregate,
+integer_aggregate = EXCLUDED.integer_aggregate,
+unsigned_aggregate = EXCLUDED.unsigned_aggregate,
+boolean_aggregate = EXCLUDED.boolean_aggregate,
+string_aggregate = EXCLUDED.string_aggregate
+RETURNING *;
+        "#
+        );
+
+        sqlx::query_as::<_, RollupRule>(&query)
+            .bind(params.namespace_id) // $1
+            .bind(params.table_id) // $2
+            .bind(params.interval_ns) // $3
+            .bind(params.min_age_ns) // $4
+            .bind(params.float_aggregate) // $5
+            .bind(params.integer_aggregate) // $6
+            .bind(params.unsigned_aggregate) // $7
+            .bind(params.boolean_aggregate) // $8
+            .bind(params.string_aggregate) // $9
+            .fetch_one(&mut self.inner)
+            .await
+            .map_err(|e| {
+                if is_fk_violation(&e) {
+                    Error::ForeignKeyViolation { source: e }
+                } else {
+                    Error::SqlxError { source: e }
+                }
+            })
+    }
+
+    async fn get_for_table(
+        &mut self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+    ) -> Result<Option<RollupRule>> {
+        read_stale!(self, |executor| sqlx::query_as::<_, RollupRule>(
+            r#"
+SELECT *
+FROM rollup_rule
+WHERE namespace_id = $1 AND (table_id = $2 OR table_id IS NULL)
+ORDER BY table_id NULLS LAST
+LIMIT 1;
+        "#,
+        )
+        .bind(namespace_id) // $1
+        .bind(table_id) // $2
+        .fetch_optional(executor))
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<RollupRule>> {
+        read_stale!(self, |executor| sqlx::query_as::<_, RollupRule>(
+            r#"
+SELECT *
+FROM rollup_rule
+WHERE namespace_id = $1
+ORDER BY id;
+        "#,
+        )
+        .bind(namespace_id) // $1
+        .fetch_all(executor))
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn delete(&mut self, namespace_id: NamespaceId, table_id: Option<TableId>) -> Result<()> {
+        sqlx::query(
+            r#"
+DELETE FROM rollup_rule
+WHERE namespace_id = $1 AND table_id IS NOT DISTINCT FROM $2;
+        "#,
+        )
+        .bind(namespace_id) // $1
+        .bind(table_id) // $2
+        .execute(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+}
+
 /// Error code returned by Postgres for a foreign key constraint violation.
 const PG_FK_VIOLATION: &str = "23503";
 
@@ -1775,13 +2973,21 @@ fn is_fk_violation(e: &sqlx::Error) -> bool {
     false
 }
 
+fn restore_error(e: sqlx::Error) -> Error {
+    if is_fk_violation(&e) {
+        Error::ForeignKeyViolation { source: e }
+    } else {
+        Error::SqlxError { source: e }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::create_or_get_default_records;
     use assert_matches::assert_matches;
     use data_types::{ColumnId, ColumnSet};
-    use metric::{Attributes, DurationHistogram, Metric};
+    use metric::{Attributes, DurationHistogram, Metric, U64Counter};
     use rand::Rng;
     use sqlx::migrate::MigrateDatabase;
     use std::{env, io::Write, ops::DerefMut, sync::Arc, time::Instant};
@@ -1855,6 +3061,17 @@ mod tests {
     }
 
     async fn setup_db() -> PostgresCatalog {
+        setup_db_with_replicas(0).await
+    }
+
+    /// Like [`setup_db`], using the test database as its own read replica `n_replicas` times.
+    async fn setup_db_with_replicas(n_replicas: usize) -> PostgresCatalog {
+        let dsn = std::env::var("TEST_INFLUXDB_IOX_CATALOG_DSN").unwrap();
+        setup_db_with_replica_dsns(vec![dsn; n_replicas]).await
+    }
+
+    /// Like [`setup_db`], with the given read replicas.
+    async fn setup_db_with_replica_dsns(replica_dsns: Vec<String>) -> PostgresCatalog {
         // create a random schema for this particular pool
         let schema_name = {
             // use scope to make it clear to clippy / rust that `rng` is
@@ -1876,6 +3093,7 @@ mod tests {
         let options = PostgresConnectionOptions {
             app_name: String::from("test"),
             schema_name: schema_name.clone(),
+            replica_dsns,
             dsn,
             max_conns: 3,
             ..Default::default()
@@ -1959,6 +3177,186 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_snapshot_transaction() {
+        maybe_skip_integration!();
+
+        let postgres = setup_db().await;
+
+        let mut txn = postgres.start_transaction().await.expect("txn start");
+        let (topic, query_pool, _) = create_or_get_default_records(1, txn.deref_mut())
+            .await
+            .expect("db init failed");
+        txn.commit().await.expect("txn commit");
+
+        let mut snapshot = postgres
+            .start_snapshot_transaction()
+            .await
+            .expect("snapshot start");
+        let before = snapshot
+            .namespaces()
+            .list(SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+
+        postgres
+            .repositories()
+            .await
+            .namespaces()
+            .create("ns", None, topic.id, query_pool.id)
+            .await
+            .unwrap();
+
+        // Changes committed after the snapshot was taken are not visible.
+        let after = snapshot
+            .namespaces()
+            .list(SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+        assert_eq!(before, after);
+
+        // The snapshot is read-only.
+        snapshot
+            .namespaces()
+            .create("ns2", None, topic.id, query_pool.id)
+            .await
+            .unwrap_err();
+        snapshot.abort().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_replicas() {
+        maybe_skip_integration!();
+
+        let postgres = setup_db_with_replicas(1).await;
+        let metrics = postgres.metrics();
+        let replica = Arc::clone(&postgres.replicas.as_ref().unwrap().replicas[0]);
+        let reads = |pool: &'static str| {
+            metrics
+                .get_instrument::<Metric<U64Counter>>("catalog_pool_reads")
+                .expect("failed to read metric")
+                .get_observer(&Attributes::from(&[("pool", pool), ("result", "success")]))
+                .map(|observer| observer.fetch())
+                .unwrap_or_default()
+        };
+
+        // The database is not in recovery, so it is a replica without lag.
+        assert!(replica.healthy.load(Ordering::Relaxed));
+
+        let mut txn = postgres.start_transaction().await.expect("txn start");
+        let (topic, query_pool, shards) = create_or_get_default_records(1, txn.deref_mut())
+            .await
+            .expect("db init failed");
+        txn.commit().await.expect("txn commit");
+
+        // Writes go to the primary.
+        let mut repos = postgres.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .create("ns", None, topic.id, query_pool.id)
+            .await
+            .unwrap();
+
+        // Reads that tolerate stale results are served by the replica.
+        let got = repos
+            .namespaces()
+            .get_by_name("ns", SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap();
+        assert_eq!(got, Some(namespace.clone()));
+        assert_eq!(reads("replica_0"), 1);
+        assert_eq!(reads("primary"), 0);
+
+        // Reads that fail on the replica are retried on the primary.
+        replica.pool.current().close().await;
+        let got = repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap();
+        assert_eq!(got, Some(namespace.clone()));
+        assert!(!replica.healthy.load(Ordering::Relaxed));
+        assert_eq!(reads("primary"), 1);
+
+        // Until the replica recovers, reads go to the primary directly.
+        let got = repos
+            .namespaces()
+            .list(SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+        assert_eq!(got, vec![namespace]);
+        assert_eq!(reads("replica_0"), 1);
+        assert_eq!(reads("primary"), 2);
+
+        let fallbacks = metrics
+            .get_instrument::<Metric<U64Counter>>("catalog_replica_fallbacks")
+            .expect("failed to read metric");
+        for (pool, reason) in [("replica_0", "error"), ("primary", "unhealthy")] {
+            let count = fallbacks
+                .get_observer(&Attributes::from(&[("pool", pool), ("reason", reason)]))
+                .expect("failed to get observer")
+                .fetch();
+            assert_eq!(count, 1, "{reason}");
+        }
+
+        // Partitions are always read from the primary.
+        replica.healthy.store(true, Ordering::Relaxed);
+        let table = repos
+            .tables()
+            .create_or_get("t", namespace.id)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("k".into(), *shards.keys().next().unwrap(), table.id)
+            .await
+            .unwrap();
+        let got = repos.partitions().get_by_id(partition.id).await.unwrap();
+        assert_eq!(got, Some(partition));
+        assert_eq!(reads("replica_0"), 1);
+        assert_eq!(reads("primary"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_read_replica() {
+        maybe_skip_integration!();
+
+        // Nothing listens on port 1, the catalog starts nevertheless.
+        let postgres =
+            setup_db_with_replica_dsns(vec!["postgres://postgres@127.0.0.1:1/iox".to_string()])
+                .await;
+        let metrics = postgres.metrics();
+        let replica = Arc::clone(&postgres.replicas.as_ref().unwrap().replicas[0]);
+        assert!(!replica.healthy.load(Ordering::Relaxed));
+
+        // Reads are served by the primary.
+        let got = postgres
+            .repositories()
+            .await
+            .namespaces()
+            .list(SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+        assert!(got.is_empty());
+
+        let fallbacks = metrics
+            .get_instrument::<Metric<U64Counter>>("catalog_replica_fallbacks")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[
+                ("pool", "primary"),
+                ("reason", "unhealthy"),
+            ]))
+            .expect("failed to get observer")
+            .fetch();
+        assert_eq!(fallbacks, 1);
+
+        // The replica stays unhealthy until a health check succeeds.
+        replica
+            .check_health(PostgresConnectionOptions::DEFAULT_MAX_REPLICA_LAG)
+            .await;
+        assert!(!replica.healthy.load(Ordering::Relaxed));
+    }
+
     #[tokio::test]
     async fn test_partition_create_or_get_idempotent() {
         // If running an integration test on your laptop, this requires that you have Postgres running
@@ -2135,7 +3533,7 @@ mod tests {
             hotswap_poll_interval: POLLING_INTERVAL,
             ..Default::default()
         };
-        let pool = new_pool(&options).await.expect("connect");
+        let pool = new_pool(&options, false).await.expect("connect");
         eprintln!("got a pool");
 
         // ensure the application name is set as expected
@@ -2491,4 +3889,46 @@ mod tests {
                 .expect("fetch total file size failed");
         assert_eq!(total_file_size_bytes, 1337 * 2);
     }
+
+    #[test]
+    fn test_parse_change() {
+        assert_eq!(
+            parse_change("namespace:1"),
+            Some(CatalogChange::Namespace {
+                id: NamespaceId::new(1)
+            })
+        );
+        assert_eq!(
+            parse_change("table:1:2"),
+            Some(CatalogChange::Table {
+                namespace_id: NamespaceId::new(1),
+                id: TableId::new(2),
+            })
+        );
+        assert_eq!(
+            parse_change("partition:2:3"),
+            Some(CatalogChange::Partition {
+                table_id: TableId::new(2),
+                id: PartitionId::new(3),
+            })
+        );
+        assert_eq!(
+            parse_change("parquet_file:2:3"),
+            Some(CatalogChange::ParquetFile {
+                table_id: TableId::new(2),
+                partition_id: PartitionId::new(3),
+            })
+        );
+
+        for payload in [
+            "",
+            "namespace",
+            "namespace:x",
+            "table:1",
+            "table:1:2:3",
+            "shard:1",
+        ] {
+            assert_eq!(parse_change(payload), None, "{payload}");
+        }
+    }
 }
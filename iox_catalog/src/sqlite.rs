@@ -1,10 +1,12 @@
 //! A SQLite backed implementation of the Catalog
 
 use crate::{
+    changes::{self, CatalogChange, PendingChanges},
     interface::{
-        self, sealed::TransactionFinalize, CasFailure, Catalog, ColumnRepo,
-        ColumnTypeMismatchSnafu, Error, NamespaceRepo, ParquetFileRepo, PartitionRepo,
-        QueryPoolRepo, RepoCollection, Result, ShardRepo, SoftDeletedRows, TableRepo,
+        self, sealed::TransactionFinalize, validate_rollup_rule, validate_write_rate_limit,
+        CasFailure, Catalog, ColumnRepo, ColumnSoftDeletedSnafu, ColumnTypeMismatchSnafu, Error,
+        NamespaceRepo, ParquetFileRepo, PartitionRepo, QueryPoolRepo, RepoCollection, Result,
+        RollupRuleRepo, ShardRepo, SoftDeletedRows, TableRepo, TableSoftDeletedSnafu,
         TopicMetadataRepo, Transaction,
     },
     metrics::MetricDecorator,
@@ -14,12 +16,17 @@ use async_trait::async_trait;
 use data_types::{
     Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, Namespace, NamespaceId, ParquetFile,
     ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionKey, QueryPool, QueryPoolId,
-    SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, Timestamp,
-    TopicId, TopicMetadata, TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
+    QueryPriority, RollupRule, RollupRuleParams, SequenceNumber, Shard, ShardId, ShardIndex,
+    SkippedCompaction, Table, TableId, Timestamp, TopicId, TopicMetadata, TypeCoercionPolicy,
+    TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
 };
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    time::Duration,
+};
 
 use iox_time::{SystemProvider, TimeProvider};
 use metric::Registry;
@@ -28,11 +35,14 @@ use parking_lot::Mutex;
 use snafu::prelude::*;
 use sqlx::types::Json;
 use sqlx::{
-    migrate::Migrator, sqlite::SqliteConnectOptions, types::Uuid, Executor, Pool, Row, Sqlite,
-    SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteRow},
+    types::Uuid,
+    Executor, Pool, Row, Sqlite, SqlitePool,
 };
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 static MIGRATOR: Migrator = sqlx::migrate!("sqlite/migrations");
 
@@ -53,6 +63,7 @@ pub struct SqliteCatalog {
     pool: Pool<Sqlite>,
     time_provider: Arc<dyn TimeProvider>,
     options: SqliteConnectionOptions,
+    changes: broadcast::Sender<CatalogChange>,
 }
 
 // struct to get return value from "select count(id) ..." query
@@ -66,6 +77,7 @@ struct Count {
 pub struct SqliteTxn {
     inner: Mutex<SqliteTxnInner>,
     time_provider: Arc<dyn TimeProvider>,
+    changes: PendingChanges,
 }
 
 #[derive(Debug)]
@@ -156,6 +168,42 @@ impl<'c> Executor<'c> for &'c mut SqliteTxnInner {
     }
 }
 
+impl SqliteTxn {
+    /// Record a change to the table with ID `table_id`, e.g. to one of its columns.
+    async fn table_changed(&mut self, table_id: TableId) -> Result<()> {
+        let namespace_id = sqlx::query_scalar::<_, NamespaceId>(
+            r#"SELECT namespace_id FROM table_name WHERE id = $1;"#,
+        )
+        .bind(table_id) // $1
+        .fetch_optional(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        if let Some(namespace_id) = namespace_id {
+            self.changes.push(CatalogChange::Table {
+                namespace_id,
+                id: table_id,
+            });
+        }
+        Ok(())
+    }
+
+    /// Record changes to the partitions of the parquet file `rows`, which must include the
+    /// `table_id` and `partition_id` columns.
+    fn parquet_files_changed(&mut self, rows: &[SqliteRow]) {
+        let changes: HashSet<_> = rows
+            .iter()
+            .map(|row| CatalogChange::ParquetFile {
+                table_id: row.get("table_id"),
+                partition_id: row.get("partition_id"),
+            })
+            .collect();
+        for change in changes {
+            self.changes.push(change);
+        }
+    }
+}
+
 impl Drop for SqliteTxn {
     fn drop(&mut self) {
         if let SqliteTxnInner::Txn(Some(_)) = self.inner.lock().deref() {
@@ -171,12 +219,15 @@ impl Drop for SqliteTxn {
 impl TransactionFinalize for SqliteTxn {
     async fn commit_inplace(&mut self) -> Result<(), Error> {
         match self.inner.get_mut() {
-            SqliteTxnInner::Txn(txn) => txn
-                .take()
-                .expect("Not yet finalized")
-                .commit()
-                .await
-                .map_err(|e| Error::SqlxError { source: e }),
+            SqliteTxnInner::Txn(txn) => {
+                txn.take()
+                    .expect("Not yet finalized")
+                    .commit()
+                    .await
+                    .map_err(|e| Error::SqlxError { source: e })?;
+                self.changes.commit();
+                Ok(())
+            }
             SqliteTxnInner::Oneshot(_) => {
                 panic!("cannot commit oneshot");
             }
@@ -185,12 +236,14 @@ impl TransactionFinalize for SqliteTxn {
 
     async fn abort_inplace(&mut self) -> Result<(), Error> {
         match self.inner.get_mut() {
-            SqliteTxnInner::Txn(txn) => txn
-                .take()
-                .expect("Not yet finalized")
-                .rollback()
-                .await
-                .map_err(|e| Error::SqlxError { source: e }),
+            SqliteTxnInner::Txn(txn) => {
+                self.changes.abort();
+                txn.take()
+                    .expect("Not yet finalized")
+                    .rollback()
+                    .await
+                    .map_err(|e| Error::SqlxError { source: e })
+            }
             SqliteTxnInner::Oneshot(_) => {
                 panic!("cannot abort oneshot");
             }
@@ -213,6 +266,7 @@ impl SqliteCatalog {
             pool,
             time_provider: Arc::new(SystemProvider::new()),
             options,
+            changes: changes::sender(),
         })
     }
 }
@@ -275,6 +329,7 @@ DO NOTHING;
             SqliteTxn {
                 inner: Mutex::new(SqliteTxnInner::Txn(Some(transaction))),
                 time_provider: Arc::clone(&self.time_provider),
+                changes: PendingChanges::transaction(self.changes.clone()),
             },
             Arc::clone(&self.metrics),
         )))
@@ -285,6 +340,7 @@ DO NOTHING;
             SqliteTxn {
                 inner: Mutex::new(SqliteTxnInner::Oneshot(self.pool.clone())),
                 time_provider: Arc::clone(&self.time_provider),
+                changes: PendingChanges::oneshot(self.changes.clone()),
             },
             Arc::clone(&self.metrics),
         ))
@@ -297,6 +353,10 @@ DO NOTHING;
     fn time_provider(&self) -> Arc<dyn TimeProvider> {
         Arc::clone(&self.time_provider)
     }
+
+    fn subscribe_changes(&self) -> broadcast::Receiver<CatalogChange> {
+        self.changes.subscribe()
+    }
 }
 
 #[async_trait]
@@ -332,6 +392,10 @@ impl RepoCollection for SqliteTxn {
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn rollup_rules(&mut self) -> &mut dyn RollupRuleRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -374,6 +438,30 @@ WHERE name = $1;
 
         Ok(Some(topic))
     }
+
+    async fn list(&mut self) -> Result<Vec<TopicMetadata>> {
+        sqlx::query_as::<_, TopicMetadata>("SELECT * FROM topic;")
+            .fetch_all(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn restore(&mut self, topic: &TopicMetadata) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO topic (
+    id, name )
+VALUES ( $1, $2 );
+        "#,
+        )
+        .bind(topic.id) // $1
+        .bind(&topic.name) // $2
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -395,6 +483,30 @@ RETURNING *;
 
         Ok(rec)
     }
+
+    async fn list(&mut self) -> Result<Vec<QueryPool>> {
+        sqlx::query_as::<_, QueryPool>("SELECT * FROM query_pool;")
+            .fetch_all(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn restore(&mut self, query_pool: &QueryPool) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO query_pool (
+    id, name )
+VALUES ( $1, $2 );
+        "#,
+        )
+        .bind(query_pool.id) // $1
+        .bind(&query_pool.name) // $2
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -435,6 +547,7 @@ impl NamespaceRepo for SqliteTxn {
         debug_assert_eq!(rec.max_tables, DEFAULT_MAX_TABLES);
         debug_assert_eq!(rec.max_columns_per_table, DEFAULT_MAX_COLUMNS_PER_TABLE);
 
+        self.changes.push(CatalogChange::Namespace { id: rec.id });
         Ok(rec)
     }
 
@@ -507,13 +620,67 @@ impl NamespaceRepo for SqliteTxn {
         let flagged_at = Timestamp::from(self.time_provider.now());
 
         // note that there is a uniqueness constraint on the name column in the DB
-        sqlx::query(r#"UPDATE namespace SET deleted_at=$1 WHERE name = $2;"#)
-            .bind(flagged_at) // $1
-            .bind(name) // $2
-            .execute(self.inner.get_mut())
-            .await
-            .context(interface::CouldNotDeleteNamespaceSnafu)
-            .map(|_| ())
+        let deleted =
+            sqlx::query(r#"UPDATE namespace SET deleted_at=$1 WHERE name = $2 RETURNING id;"#)
+                .bind(flagged_at) // $1
+                .bind(name) // $2
+                .fetch_optional(self.inner.get_mut())
+                .await
+                .context(interface::CouldNotDeleteNamespaceSnafu)?;
+
+        if let Some(row) = deleted {
+            self.changes
+                .push(CatalogChange::Namespace { id: row.get("id") });
+        }
+        Ok(())
+    }
+
+    async fn undelete(&mut self, name: &str, deleted_after: Timestamp) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET deleted_at = NULL
+WHERE name = $1
+AND deleted_at IS NOT NULL
+AND deleted_at >= $2
+RETURNING *;
+        "#,
+        )
+        .bind(name) // $1
+        .bind(deleted_after) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::SoftDeletedNamespaceNotFound {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn purge(&mut self, name: &str) -> Result<()> {
+        // tables, columns, partitions, parquet files and rollup rules are removed by the
+        // `ON DELETE CASCADE` of their foreign keys
+        let purged = sqlx::query(
+            r#"DELETE FROM namespace WHERE name = $1 AND deleted_at IS NOT NULL RETURNING id;"#,
+        )
+        .bind(name) // $1
+        .fetch_optional(self.inner.get_mut())
+        .await
+        .context(interface::CouldNotDeleteNamespaceSnafu)?
+        .ok_or_else(|| Error::SoftDeletedNamespaceNotFound {
+            name: name.to_string(),
+        })?;
+
+        self.changes.push(CatalogChange::Namespace {
+            id: purged.get("id"),
+        });
+        Ok(())
     }
 
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
@@ -537,6 +704,8 @@ RETURNING *;
             _ => Error::SqlxError { source: e },
         })?;
 
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
         Ok(namespace)
     }
 
@@ -561,6 +730,192 @@ RETURNING *;
             _ => Error::SqlxError { source: e },
         })?;
 
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn update_lines_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        validate_write_rate_limit(new_max)?;
+
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_lines_per_second = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn update_batch_bytes_per_second_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        validate_write_rate_limit(new_max)?;
+
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_batch_bytes_per_second = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn update_concurrent_queries_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i32>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_concurrent_queries = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn update_query_memory_limit(
+        &mut self,
+        name: &str,
+        new_max: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_query_memory_bytes = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_max)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn update_type_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: TypeCoercionPolicy,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET type_coercion_policy = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(policy)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
+        Ok(namespace)
+    }
+
+    async fn update_default_query_priority(
+        &mut self,
+        name: &str,
+        priority: QueryPriority,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET default_query_priority = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(priority)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
         Ok(namespace)
     }
 
@@ -584,8 +939,66 @@ RETURNING *;
             _ => Error::SqlxError { source: e },
         })?;
 
+        self.changes
+            .push(CatalogChange::Namespace { id: namespace.id });
         Ok(namespace)
     }
+
+    async fn restore(&mut self, namespace: &Namespace) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO namespace (
+    id, name, retention_period_ns, topic_id, query_pool_id, max_tables,
+    max_columns_per_table, max_lines_per_second, max_batch_bytes_per_second,
+    max_concurrent_queries, max_query_memory_bytes, type_coercion_policy, default_query_priority,
+    deleted_at )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 );
+        "#,
+        )
+        .bind(namespace.id) // $1
+        .bind(&namespace.name) // $2
+        .bind(namespace.retention_period_ns) // $3
+        .bind(namespace.topic_id) // $4
+        .bind(namespace.query_pool_id) // $5
+        .bind(namespace.max_tables) // $6
+        .bind(namespace.max_columns_per_table) // $7
+        .bind(namespace.max_lines_per_second) // $8
+        .bind(namespace.max_batch_bytes_per_second) // $9
+        .bind(namespace.max_concurrent_queries) // $10
+        .bind(namespace.max_query_memory_bytes) // $11
+        .bind(namespace.type_coercion_policy) // $12
+        .bind(namespace.default_query_priority) // $13
+        .bind(namespace.deleted_at) // $14
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
+}
+
+// We can't use [`Table`], as uses Vec<String> which the Sqlite
+// driver cannot serialise
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+struct TablePod {
+    id: TableId,
+    namespace_id: NamespaceId,
+    name: String,
+    primary_key: Json<Vec<String>>,
+    deleted_at: Option<Timestamp>,
+}
+
+impl From<TablePod> for Table {
+    fn from(value: TablePod) -> Self {
+        Self {
+            id: value.id,
+            namespace_id: value.namespace_id,
+            name: value.name,
+            primary_key: value.primary_key.0,
+            deleted_at: value.deleted_at,
+        }
+    }
 }
 
 #[async_trait]
@@ -600,12 +1013,13 @@ impl TableRepo for SqliteTxn {
         // By using SELECT rather than VALUES it will insert zero rows if it finds a null in the
         // subquery, i.e. if count >= max_tables. fetch_one() will return a RowNotFound error if
         // nothing was inserted. Not pretty!
-        let rec = sqlx::query_as::<_, Table>(
+        let rec = sqlx::query_as::<_, TablePod>(
             r#"
 INSERT INTO table_name ( name, namespace_id )
 SELECT $1, id FROM (
     SELECT namespace.id AS id, max_tables, COUNT(table_name.id) AS count
-    FROM namespace LEFT JOIN table_name ON namespace.id = table_name.namespace_id
+    FROM namespace LEFT JOIN table_name
+        ON namespace.id = table_name.namespace_id AND table_name.deleted_at IS NULL
     WHERE namespace.id = $2
     GROUP BY namespace.max_tables, table_name.namespace_id, namespace.id
 ) AS get_count WHERE count < max_tables
@@ -632,11 +1046,66 @@ RETURNING *;
             }
         })?;
 
-        Ok(rec)
+        ensure!(
+            rec.deleted_at.is_none(),
+            TableSoftDeletedSnafu { name, namespace_id }
+        );
+
+        // The upsert cannot tell an existing table from a new one, so this may record a change
+        // that did not happen. Subscribers treat that as an early expiry.
+        self.changes.push(CatalogChange::Table {
+            namespace_id,
+            id: rec.id,
+        });
+        Ok(rec.into())
+    }
+
+    async fn create(
+        &mut self,
+        name: &str,
+        namespace_id: NamespaceId,
+        primary_key: &[String],
+    ) -> Result<Table> {
+        // Like create_or_get, inserting from the table count subquery enforces the table limit.
+        let rec = sqlx::query_as::<_, TablePod>(
+            r#"
+INSERT INTO table_name ( name, namespace_id, primary_key )
+SELECT $1, id, $3 FROM (
+    SELECT namespace.id AS id, max_tables, COUNT(table_name.id) AS count
+    FROM namespace LEFT JOIN table_name
+        ON namespace.id = table_name.namespace_id AND table_name.deleted_at IS NULL
+    WHERE namespace.id = $2
+    GROUP BY namespace.max_tables, table_name.namespace_id, namespace.id
+) AS get_count WHERE count < max_tables
+RETURNING *;
+        "#,
+        )
+        .bind(name) // $1
+        .bind(namespace_id) // $2
+        .bind(Json(primary_key)) // $3
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableCreateLimitError {
+                table_name: name.to_string(),
+                namespace_id,
+            },
+            _ if is_unique_violation(&e) => Error::NameExists {
+                name: name.to_string(),
+            },
+            _ if is_fk_violation(&e) => Error::ForeignKeyViolation { source: e },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes.push(CatalogChange::Table {
+            namespace_id,
+            id: rec.id,
+        });
+        Ok(rec.into())
     }
 
     async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
+        let rec = sqlx::query_as::<_, TablePod>(
             r#"
 SELECT *
 FROM table_name
@@ -653,7 +1122,7 @@ WHERE id = $1;
 
         let table = rec.map_err(|e| Error::SqlxError { source: e })?;
 
-        Ok(Some(table))
+        Ok(Some(table.into()))
     }
 
     async fn get_by_namespace_and_name(
@@ -661,11 +1130,11 @@ WHERE id = $1;
         namespace_id: NamespaceId,
         name: &str,
     ) -> Result<Option<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
+        let rec = sqlx::query_as::<_, TablePod>(
             r#"
 SELECT *
 FROM table_name
-WHERE namespace_id = $1 AND name = $2;
+WHERE namespace_id = $1 AND name = $2 AND deleted_at IS NULL;
             "#,
         )
         .bind(namespace_id) // $1
@@ -679,32 +1148,88 @@ WHERE namespace_id = $1 AND name = $2;
 
         let table = rec.map_err(|e| Error::SqlxError { source: e })?;
 
-        Ok(Some(table))
+        Ok(Some(table.into()))
     }
 
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
-            r#"
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Table>> {
+        let rec = sqlx::query_as::<_, TablePod>(
+            format!(
+                r#"
 SELECT *
 FROM table_name
-WHERE namespace_id = $1;
+WHERE namespace_id = $1 AND {v};
             "#,
+                v = deleted.as_sql_predicate()
+            )
+            .as_str(),
         )
         .bind(namespace_id)
         .fetch_all(self.inner.get_mut())
         .await
         .map_err(|e| Error::SqlxError { source: e })?;
 
-        Ok(rec)
+        Ok(rec.into_iter().map(Into::into).collect())
     }
 
     async fn list(&mut self) -> Result<Vec<Table>> {
-        let rec = sqlx::query_as::<_, Table>("SELECT * FROM table_name;")
+        let rec = sqlx::query_as::<_, TablePod>("SELECT * FROM table_name;")
             .fetch_all(self.inner.get_mut())
             .await
             .map_err(|e| Error::SqlxError { source: e })?;
 
-        Ok(rec)
+        Ok(rec.into_iter().map(Into::into).collect())
+    }
+
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+
+        let rec = sqlx::query_as::<_, TablePod>(
+            r#"
+UPDATE table_name
+SET deleted_at = COALESCE(deleted_at, $1)
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(flagged_at) // $1
+        .bind(table_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let table = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes.push(CatalogChange::Table {
+            namespace_id: table.namespace_id,
+            id: table.id,
+        });
+        Ok(table.into())
+    }
+
+    async fn restore(&mut self, table: &Table) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO table_name (
+    id, namespace_id, name, deleted_at, primary_key )
+VALUES ( $1, $2, $3, $4, $5 );
+        "#,
+        )
+        .bind(table.id) // $1
+        .bind(table.namespace_id) // $2
+        .bind(&table.name) // $3
+        .bind(table.deleted_at) // $4
+        .bind(Json(&table.primary_key)) // $5
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
     }
 }
 
@@ -722,7 +1247,8 @@ INSERT INTO column_name ( name, table_id, column_type )
 SELECT $1, table_id, $3 FROM (
     SELECT max_columns_per_table, namespace.id, table_name.id as table_id, COUNT(column_name.id) AS count
     FROM namespace LEFT JOIN table_name ON namespace.id = table_name.namespace_id
-                   LEFT JOIN column_name ON table_name.id = column_name.table_id
+                   LEFT JOIN column_name
+                       ON table_name.id = column_name.table_id AND column_name.deleted_at IS NULL
     WHERE table_name.id = $2
     GROUP BY namespace.max_columns_per_table, namespace.id, table_name.id
 ) AS get_count WHERE count < max_columns_per_table
@@ -749,6 +1275,10 @@ RETURNING *;
                     }
                 }})?;
 
+        ensure!(
+            rec.deleted_at.is_none(),
+            ColumnSoftDeletedSnafu { name, table_id }
+        );
         ensure!(
             rec.column_type == column_type,
             ColumnTypeMismatchSnafu {
@@ -758,16 +1288,32 @@ RETURNING *;
             }
         );
 
+        // As for tables, the upsert may record a change for an existing column.
+        self.table_changed(table_id).await?;
         Ok(rec)
     }
 
-    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>> {
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Vec<Column>> {
+        // columns of a deleted table count as deleted
         let rec = sqlx::query_as::<_, Column>(
-            r#"
-SELECT column_name.* FROM table_name
-INNER JOIN column_name on column_name.table_id = table_name.id
-WHERE table_name.namespace_id = $1;
+            format!(
+                r#"
+SELECT * FROM (
+    SELECT column_name.id, column_name.table_id, column_name.name, column_name.column_type,
+           COALESCE(column_name.deleted_at, table_name.deleted_at) AS deleted_at
+    FROM table_name
+    INNER JOIN column_name on column_name.table_id = table_name.id
+    WHERE table_name.namespace_id = $1
+) AS namespace_columns
+WHERE {v};
             "#,
+                v = deleted.as_sql_predicate()
+            )
+            .as_str(),
         )
         .bind(namespace_id)
         .fetch_all(self.inner.get_mut())
@@ -781,7 +1327,7 @@ WHERE table_name.namespace_id = $1;
         let rec = sqlx::query_as::<_, Column>(
             r#"
 SELECT * FROM column_name
-WHERE table_id = $1;
+WHERE table_id = $1 AND deleted_at IS NULL;
             "#,
         )
         .bind(table_id)
@@ -801,6 +1347,31 @@ WHERE table_id = $1;
         Ok(rec)
     }
 
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+
+        let rec = sqlx::query_as::<_, Column>(
+            r#"
+UPDATE column_name
+SET deleted_at = COALESCE(deleted_at, $1)
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(flagged_at) // $1
+        .bind(column_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let column = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::ColumnNotFound { id: column_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.table_changed(column.table_id).await?;
+        Ok(column)
+    }
+
     async fn create_or_get_many_unchecked(
         &mut self,
         table_id: TableId,
@@ -855,6 +1426,13 @@ RETURNING *;
 
         for existing in &out {
             let want = columns.get(existing.name.as_str()).unwrap();
+            ensure!(
+                existing.deleted_at.is_none(),
+                ColumnSoftDeletedSnafu {
+                    name: &existing.name,
+                    table_id,
+                }
+            );
             ensure!(
                 existing.column_type == *want,
                 ColumnTypeMismatchSnafu {
@@ -865,8 +1443,29 @@ RETURNING *;
             );
         }
 
+        self.table_changed(table_id).await?;
         Ok(out)
     }
+
+    async fn restore(&mut self, column: &Column) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO column_name (
+    id, table_id, name, column_type, deleted_at )
+VALUES ( $1, $2, $3, $4, $5 );
+        "#,
+        )
+        .bind(column.id) // $1
+        .bind(column.table_id) // $2
+        .bind(&column.name) // $3
+        .bind(column.column_type) // $4
+        .bind(column.deleted_at) // $5
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -962,6 +1561,25 @@ WHERE id = $2;
 
         Ok(())
     }
+
+    async fn restore(&mut self, shard: &Shard) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO shard (
+    id, topic_id, shard_index, min_unpersisted_sequence_number )
+VALUES ( $1, $2, $3, $4 );
+        "#,
+        )
+        .bind(shard.id) // $1
+        .bind(shard.topic_id) // $2
+        .bind(shard.shard_index) // $3
+        .bind(shard.min_unpersisted_sequence_number) // $4
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
 }
 
 // We can't use [`Partition`], as uses Vec<String> which the Sqlite
@@ -976,6 +1594,7 @@ struct PartitionPod {
     sort_key: Json<Vec<String>>,
     persisted_sequence_number: Option<SequenceNumber>,
     new_file_at: Option<Timestamp>,
+    rollup_interval_ns: Option<i64>,
 }
 
 impl From<PartitionPod> for Partition {
@@ -988,6 +1607,7 @@ impl From<PartitionPod> for Partition {
             sort_key: value.sort_key.0,
             persisted_sequence_number: value.persisted_sequence_number,
             new_file_at: value.new_file_at,
+            rollup_interval_ns: value.rollup_interval_ns,
         }
     }
 }
@@ -1003,13 +1623,15 @@ impl PartitionRepo for SqliteTxn {
         // Note: since sort_key is now an array, we must explicitly insert '{}' which is an empty
         // array rather than NULL which sqlx will throw `UnexpectedNullError` while is is doing
         // `ColumnDecode`
+        //
+        // New partitions of a table with a declared primary key are sorted by it.
 
         let v = sqlx::query_as::<_, PartitionPod>(
             r#"
 INSERT INTO partition
     ( partition_key, shard_id, table_id, sort_key)
 VALUES
-    ( $1, $2, $3, '[]')
+    ( $1, $2, $3, COALESCE((SELECT primary_key FROM table_name WHERE id = $3), '[]'))
 ON CONFLICT (table_id, partition_key)
 DO UPDATE SET partition_key = partition.partition_key
 RETURNING *;
@@ -1037,6 +1659,9 @@ RETURNING *;
             "attempted to overwrite partition with different shard ID"
         );
 
+        // As for tables, the upsert may record a change for an existing partition.
+        self.changes
+            .push(CatalogChange::Partition { table_id, id: v.id });
         Ok(v.into())
     }
 
@@ -1145,6 +1770,10 @@ RETURNING *;
             "partition sort key cas successful"
         );
 
+        self.changes.push(CatalogChange::Partition {
+            table_id: partition.table_id,
+            id: partition.id,
+        });
         Ok(partition.into())
     }
 
@@ -1238,6 +1867,89 @@ RETURNING *
         .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
     }
 
+    async fn try_acquire_lease(
+        &mut self,
+        partition_id: PartitionId,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<bool> {
+        let now = self.time_provider.now();
+        let expires_at = Timestamp::from(now + lease_duration);
+        let now = Timestamp::from(now);
+
+        let rec = sqlx::query(
+            r#"
+INSERT INTO partition_lease ( partition_id, owner, expires_at )
+VALUES ( $1, $2, $3 )
+ON CONFLICT ( partition_id )
+DO UPDATE
+SET
+owner = EXCLUDED.owner,
+expires_at = EXCLUDED.expires_at
+WHERE partition_lease.owner = EXCLUDED.owner OR partition_lease.expires_at <= $4
+RETURNING partition_id;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(owner) // $2
+        .bind(expires_at) // $3
+        .bind(now) // $4
+        .fetch_optional(self.inner.get_mut())
+        .await;
+
+        match rec {
+            Ok(rec) => Ok(rec.is_some()),
+            Err(e) if is_fk_violation(&e) => Err(Error::PartitionNotFound { id: partition_id }),
+            Err(e) => Err(Error::SqlxError { source: e }),
+        }
+    }
+
+    async fn release_lease(&mut self, partition_id: PartitionId, owner: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+DELETE FROM partition_lease
+WHERE partition_id = $1 AND owner = $2;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(owner) // $2
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+
+    async fn set_rollup_interval(
+        &mut self,
+        partition_id: PartitionId,
+        rollup_interval_ns: i64,
+    ) -> Result<Partition> {
+        let rec = sqlx::query_as::<_, PartitionPod>(
+            r#"
+UPDATE partition
+SET rollup_interval_ns = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(rollup_interval_ns) // $1
+        .bind(partition_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let partition = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::PartitionNotFound { id: partition_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        self.changes.push(CatalogChange::Partition {
+            table_id: partition.table_id,
+            id: partition.id,
+        });
+        Ok(partition.into())
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         Ok(sqlx::query_as::<_, PartitionPod>(
             r#"SELECT * FROM partition ORDER BY id DESC LIMIT $1;"#,
@@ -1275,6 +1987,30 @@ RETURNING *
             .await
             .map_err(|e| Error::SqlxError { source: e })
     }
+
+    async fn restore(&mut self, partition: &Partition) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO partition (
+    id, shard_id, table_id, partition_key, sort_key, persisted_sequence_number,
+    new_file_at, rollup_interval_ns )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 );
+        "#,
+        )
+        .bind(partition.id) // $1
+        .bind(partition.shard_id) // $2
+        .bind(partition.table_id) // $3
+        .bind(&partition.partition_key) // $4
+        .bind(Json(&partition.sort_key)) // $5
+        .bind(partition.persisted_sequence_number) // $6
+        .bind(partition.new_file_at) // $7
+        .bind(partition.rollup_interval_ns) // $8
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
 }
 
 fn from_column_set(v: &ColumnSet) -> Json<Vec<i64>> {
@@ -1384,19 +2120,26 @@ RETURNING *;
             }
         })?;
 
+        self.changes.push(CatalogChange::ParquetFile {
+            table_id: rec.table_id,
+            partition_id: rec.partition_id,
+        });
         Ok(rec.into())
     }
 
     async fn flag_for_delete(&mut self, id: ParquetFileId) -> Result<()> {
         let marked_at = Timestamp::from(self.time_provider.now());
 
-        let _ = sqlx::query(r#"UPDATE parquet_file SET to_delete = $1 WHERE id = $2;"#)
-            .bind(marked_at) // $1
-            .bind(id) // $2
-            .execute(self.inner.get_mut())
-            .await
-            .map_err(|e| Error::SqlxError { source: e })?;
+        let flagged = sqlx::query(
+            r#"UPDATE parquet_file SET to_delete = $1 WHERE id = $2 RETURNING id, table_id, partition_id;"#,
+        )
+        .bind(marked_at) // $1
+        .bind(id) // $2
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
 
+        self.parquet_files_changed(&flagged);
         Ok(())
     }
 
@@ -1412,14 +2155,66 @@ RETURNING *;
                 AND parquet_file.to_delete IS NULL
                 AND parquet_file.max_time < $1 - namespace.retention_period_ns
                 AND namespace.id = parquet_file.namespace_id
-                RETURNING parquet_file.id;
+                RETURNING parquet_file.id, parquet_file.table_id, parquet_file.partition_id;
+            "#,
+        )
+        .bind(flagged_at) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        self.parquet_files_changed(&flagged);
+        let flagged = flagged.into_iter().map(|row| row.get("id")).collect();
+        Ok(flagged)
+    }
+
+    async fn flag_for_delete_by_table_deletion(&mut self) -> Result<Vec<ParquetFileId>> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+        let flagged = sqlx::query(
+            r#"
+                UPDATE parquet_file
+                SET to_delete = $1
+                FROM table_name
+                WHERE table_name.deleted_at IS NOT NULL
+                AND parquet_file.to_delete IS NULL
+                AND table_name.id = parquet_file.table_id
+                RETURNING parquet_file.id, parquet_file.table_id, parquet_file.partition_id;
+            "#,
+        )
+        .bind(flagged_at) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        self.parquet_files_changed(&flagged);
+        let flagged = flagged.into_iter().map(|row| row.get("id")).collect();
+        Ok(flagged)
+    }
+
+    async fn flag_for_delete_by_namespace_deletion(
+        &mut self,
+        deleted_before: Timestamp,
+    ) -> Result<Vec<ParquetFileId>> {
+        let flagged_at = Timestamp::from(self.time_provider.now());
+        let flagged = sqlx::query(
+            r#"
+                UPDATE parquet_file
+                SET to_delete = $1
+                FROM namespace
+                WHERE namespace.deleted_at IS NOT NULL
+                AND namespace.deleted_at < $2
+                AND parquet_file.to_delete IS NULL
+                AND namespace.id = parquet_file.namespace_id
+                RETURNING parquet_file.id, parquet_file.table_id, parquet_file.partition_id;
             "#,
         )
         .bind(flagged_at) // $1
+        .bind(deleted_before) // $2
         .fetch_all(self.inner.get_mut())
         .await
         .map_err(|e| Error::SqlxError { source: e })?;
 
+        self.parquet_files_changed(&flagged);
         let flagged = flagged.into_iter().map(|row| row.get("id")).collect();
         Ok(flagged)
     }
@@ -1558,7 +2353,7 @@ WHERE parquet_file.partition_id = $1
 UPDATE parquet_file
 SET compaction_level = $1
 WHERE id IN (SELECT value FROM json_each($2))
-RETURNING id;
+RETURNING id, table_id, partition_id;
         "#,
         )
         .bind(compaction_level) // $1
@@ -1567,6 +2362,7 @@ RETURNING id;
         .await
         .map_err(|e| Error::SqlxError { source: e })?;
 
+        self.parquet_files_changed(&updated);
         let updated = updated.into_iter().map(|row| row.get("id")).collect();
         Ok(updated)
     }
@@ -1620,6 +2416,39 @@ WHERE object_store_id = $1;
 
         Ok(Some(parquet_file.into()))
     }
+
+    async fn restore(&mut self, parquet_file: &ParquetFile) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO parquet_file (
+    id, shard_id, namespace_id, table_id, partition_id, object_store_id,
+    max_sequence_number, min_time, max_time, to_delete, file_size_bytes, row_count,
+    compaction_level, created_at, column_set, max_l0_created_at )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16 );
+        "#,
+        )
+        .bind(parquet_file.id) // $1
+        .bind(parquet_file.shard_id) // $2
+        .bind(parquet_file.namespace_id) // $3
+        .bind(parquet_file.table_id) // $4
+        .bind(parquet_file.partition_id) // $5
+        .bind(parquet_file.object_store_id) // $6
+        .bind(parquet_file.max_sequence_number) // $7
+        .bind(parquet_file.min_time) // $8
+        .bind(parquet_file.max_time) // $9
+        .bind(parquet_file.to_delete) // $10
+        .bind(parquet_file.file_size_bytes) // $11
+        .bind(parquet_file.row_count) // $12
+        .bind(parquet_file.compaction_level) // $13
+        .bind(parquet_file.created_at) // $14
+        .bind(from_column_set(&parquet_file.column_set)) // $15
+        .bind(parquet_file.max_l0_created_at) // $16
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(restore_error)?;
+
+        Ok(())
+    }
 }
 
 /// The error code returned by SQLite for a unique constraint violation.
@@ -1627,6 +2456,113 @@ WHERE object_store_id = $1;
 /// See <https://sqlite.org/rescode.html#constraint_unique>
 const SQLITE_UNIQUE_VIOLATION: &str = "2067";
 
+#[async_trait]
+impl RollupRuleRepo for SqliteTxn {
+    async fn create_or_update(&mut self, params: RollupRuleParams) -> Result<RollupRule> {
+        validate_rollup_rule(&params)?;
+
+        // there is one unique index for namespace-wide rules and one for table rules, so the
+        // conflict target depends on the kind of rule
+        let conflict_target = if params.table_id.is_some() {
+            "( namespace_id, table_id ) WHERE table_id IS NOT NULL"
+        } else {
+            "( namespace_id ) WHERE table_id IS NULL"
+        };
+        let query = format!(
+            r#"
+INSERT INTO rollup_rule
+    ( namespace_id, table_id, interval_ns, min_age_ns, float_aggregate, integer_aggregate,
+      unsigned_aggregate, boolean_aggregate, string_aggregate )
+VALUES
+    ( $1, $2, $3, $4, $5, $6, $7, $8, $9 )
+ON CONFLICT {conflict_target}
+DO UPDATE
+SET
+interval_ns = EXCLUDED.interval_ns,
+min_age_ns = EXCLUDED.min_age_ns,
+float_aggregate = EXCLUDED.float_aggregate,
+integer_aggregate = EXCLUDED.integer_aggregate,
+unsigned_aggregate = EXCLUDED.unsigned_aggregate,
+boolean_aggregate = EXCLUDED.boolean_aggregate,
+string_aggregate = EXCLUDED.string_aggregate
+RETURNING *;
+        "#
+        );
+
+        sqlx::query_as::<_, RollupRule>(&query)
+            .bind(params.namespace_id) // $1
+            .bind(params.table_id) // $2
+            .bind(params.interval_ns) // $3
+            .bind(params.min_age_ns) // $4
+            .bind(params.float_aggregate) // $5
+            .bind(params.integer_aggregate) // $6
+            .bind(params.unsigned_aggregate) // $7
+            .bind(params.boolean_aggregate) // $8
+            .bind(params.string_aggregate) // $9
+            .fetch_one(self.inner.get_mut())
+            .await
+            .map_err(|e| {
+                if is_fk_violation(&e) {
+                    Error::ForeignKeyViolation { source: e }
+                } else {
+                    Error::SqlxError { source: e }
+                }
+            })
+    }
+
+    async fn get_for_table(
+        &mut self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+    ) -> Result<Option<RollupRule>> {
+        sqlx::query_as::<_, RollupRule>(
+            r#"
+SELECT *
+FROM rollup_rule
+WHERE namespace_id = $1 AND (table_id = $2 OR table_id IS NULL)
+ORDER BY table_id NULLS LAST
+LIMIT 1;
+        "#,
+        )
+        .bind(namespace_id) // $1
+        .bind(table_id) // $2
+        .fetch_optional(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<RollupRule>> {
+        sqlx::query_as::<_, RollupRule>(
+            r#"
+SELECT *
+FROM rollup_rule
+WHERE namespace_id = $1
+ORDER BY id;
+        "#,
+        )
+        .bind(namespace_id) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn delete(&mut self, namespace_id: NamespaceId, table_id: Option<TableId>) -> Result<()> {
+        sqlx::query(
+            r#"
+DELETE FROM rollup_rule
+WHERE namespace_id = $1 AND table_id IS $2;
+        "#,
+        )
+        .bind(namespace_id) // $1
+        .bind(table_id) // $2
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+}
+
 /// Error code returned by SQLite for a foreign key constraint violation.
 /// See <https://sqlite.org/rescode.html#constraint_foreignkey>
 const SQLITE_FK_VIOLATION: &str = "787";
@@ -1656,6 +2592,14 @@ fn is_unique_violation(e: &sqlx::Error) -> bool {
     false
 }
 
+fn restore_error(e: sqlx::Error) -> Error {
+    if is_fk_violation(&e) {
+        Error::ForeignKeyViolation { source: e }
+    } else {
+        Error::SqlxError { source: e }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,7 @@
 //! This module handles the manipulation / execution of storage
 //! plans. This is currently implemented using DataFusion, and this
 //! interface abstracts away many of the details
+pub mod cancel;
 pub(crate) mod context;
 pub mod field;
 pub mod fieldlist;
@@ -11,6 +12,7 @@ mod schema_pivot;
 pub mod seriesset;
 pub(crate) mod split;
 pub mod stringset;
+use data_types::QueryPriority;
 use datafusion_util::config::register_iox_object_store;
 use executor::DedicatedExecutor;
 use object_store::DynObjectStore;
@@ -23,6 +25,7 @@ use datafusion::{
     self,
     execution::{
         disk_manager::DiskManagerConfig,
+        memory_pool::MemoryPool,
         runtime_env::{RuntimeConfig, RuntimeEnv},
     },
     logical_expr::{expr_rewriter::normalize_col, Extension},
@@ -48,6 +51,11 @@ pub struct ExecutorConfig {
 
     /// Memory pool size in bytes.
     pub mem_pool_size: usize,
+
+    /// Number of threads of the dedicated pools of query priority classes.
+    ///
+    /// Classes without a dedicated pool run on the query pool.
+    pub query_priority_threads: HashMap<QueryPriority, NonZeroUsize>,
 }
 
 impl Display for ExecutorConfig {
@@ -69,6 +77,11 @@ pub struct DedicatedExecutors {
     /// compact
     reorg_exec: DedicatedExecutor,
 
+    /// Dedicated executors for user queries of some priority classes.
+    ///
+    /// Queries of other classes run on `query_exec`.
+    priority_query_execs: HashMap<QueryPriority, DedicatedExecutor>,
+
     /// Number of threads per thread pool
     num_threads: NonZeroUsize,
 }
@@ -81,10 +94,25 @@ impl DedicatedExecutors {
         Self {
             query_exec,
             reorg_exec,
+            priority_query_execs: HashMap::default(),
             num_threads,
         }
     }
 
+    /// Run user queries of the given priority class on a dedicated pool
+    /// with `num_threads` threads, so they do not compete with the queries
+    /// of other classes.
+    pub fn with_priority_query_exec(
+        mut self,
+        priority: QueryPriority,
+        num_threads: NonZeroUsize,
+    ) -> Self {
+        let name = format!("IOx Query ({priority})");
+        self.priority_query_execs
+            .insert(priority, DedicatedExecutor::new(&name, num_threads));
+        self
+    }
+
     pub fn new_testing() -> Self {
         let query_exec = DedicatedExecutor::new_testing();
         let reorg_exec = DedicatedExecutor::new_testing();
@@ -93,6 +121,7 @@ impl DedicatedExecutors {
         Self {
             query_exec,
             reorg_exec,
+            priority_query_execs: HashMap::default(),
             num_threads,
         }
     }
@@ -128,6 +157,10 @@ pub enum ExecutorType {
     /// Run using the pool for queries
     Query,
 
+    /// Run using the pool for queries of the given priority class, which is
+    /// the [`Query`](Self::Query) pool unless the class has a dedicated one
+    PriorityQuery(QueryPriority),
+
     /// Run using the pool for system / reorganization tasks
     Reorg,
 }
@@ -141,13 +174,19 @@ impl Executor {
             target_query_partitions: num_threads,
             object_stores: HashMap::default(),
             mem_pool_size,
+            query_priority_threads: HashMap::default(),
         })
     }
 
     /// Create new executor based on a specific config.
     pub fn new_with_config(config: ExecutorConfig) -> Self {
-        let executors = Arc::new(DedicatedExecutors::new(config.num_threads));
-        Self::new_with_config_and_executors(config, executors)
+        let executors = config.query_priority_threads.iter().fold(
+            DedicatedExecutors::new(config.num_threads),
+            |executors, (priority, num_threads)| {
+                executors.with_priority_query_exec(*priority, *num_threads)
+            },
+        );
+        Self::new_with_config_and_executors(config, Arc::new(executors))
     }
 
     /// Get testing executor that runs a on single thread and a low memory bound
@@ -158,6 +197,7 @@ impl Executor {
             target_query_partitions: NonZeroUsize::new(1).unwrap(),
             object_stores: HashMap::default(),
             mem_pool_size: 1024 * 1024 * 1024, // 1GB
+            query_priority_threads: HashMap::default(),
         };
         let executors = Arc::new(DedicatedExecutors::new_testing());
         Self::new_with_config_and_executors(config, executors)
@@ -196,7 +236,11 @@ impl Executor {
     /// Note that this context (and all its clones) will be shut down once `Executor` is dropped.
     pub fn new_execution_config(&self, executor_type: ExecutorType) -> IOxSessionConfig {
         let exec = self.executor(executor_type).clone();
-        IOxSessionConfig::new(exec, Arc::clone(&self.runtime))
+        let priority = match executor_type {
+            ExecutorType::PriorityQuery(priority) => priority,
+            ExecutorType::Query | ExecutorType::Reorg => QueryPriority::default(),
+        };
+        IOxSessionConfig::new(exec, priority, Arc::clone(&self.runtime))
             .with_target_partitions(self.config.target_query_partitions)
     }
 
@@ -207,10 +251,20 @@ impl Executor {
         self.new_execution_config(executor_type).build()
     }
 
+    /// Return the DataFusion [`MemoryPool`] shared by all executions.
+    pub fn memory_pool(&self) -> Arc<dyn MemoryPool> {
+        Arc::clone(&self.runtime.memory_pool)
+    }
+
     /// Return the execution pool  of the specified type
     pub fn executor(&self, executor_type: ExecutorType) -> &DedicatedExecutor {
         match executor_type {
             ExecutorType::Query => &self.executors.query_exec,
+            ExecutorType::PriorityQuery(priority) => self
+                .executors
+                .priority_query_execs
+                .get(&priority)
+                .unwrap_or(&self.executors.query_exec),
             ExecutorType::Reorg => &self.executors.reorg_exec,
         }
     }
@@ -219,6 +273,9 @@ impl Executor {
     pub fn shutdown(&self) {
         self.executors.query_exec.shutdown();
         self.executors.reorg_exec.shutdown();
+        for exec in self.executors.priority_query_execs.values() {
+            exec.shutdown();
+        }
     }
 
     /// Stops all subsequent task executions, and waits for the worker
@@ -230,6 +287,9 @@ impl Executor {
     pub async fn join(&self) {
         self.executors.query_exec.join().await;
         self.executors.reorg_exec.join().await;
+        for exec in self.executors.priority_query_execs.values() {
+            exec.join().await;
+        }
     }
 }
 
@@ -341,8 +401,18 @@ pub fn make_stream_split(input: LogicalPlan, split_exprs: Vec<Expr>) -> LogicalP
 
 /// A type that can provide `IOxSessionContext` for query
 pub trait ExecutionContextProvider {
-    /// Returns a new execution context suitable for running queries
-    fn new_query_context(&self, span_ctx: Option<trace::ctx::SpanContext>) -> IOxSessionContext;
+    /// Returns a new execution context suitable for running queries of the default priority class
+    fn new_query_context(&self, span_ctx: Option<trace::ctx::SpanContext>) -> IOxSessionContext {
+        self.new_query_context_with_priority(span_ctx, None)
+    }
+
+    /// Returns a new execution context suitable for running queries of the given priority class, or of the default
+    /// class if `None`.
+    fn new_query_context_with_priority(
+        &self,
+        span_ctx: Option<trace::ctx::SpanContext>,
+        priority: Option<QueryPriority>,
+    ) -> IOxSessionContext;
 }
 
 #[cfg(test)]
@@ -362,6 +432,39 @@ mod tests {
     use crate::plan::stringset::StringSetPlan;
     use arrow::record_batch::RecordBatch;
 
+    #[tokio::test]
+    async fn executor_priority_query_pools() {
+        let one = NonZeroUsize::new(1).unwrap();
+        let exec = Executor::new_with_config(ExecutorConfig {
+            num_threads: one,
+            target_query_partitions: one,
+            object_stores: HashMap::default(),
+            mem_pool_size: 1024 * 1024,
+            query_priority_threads: HashMap::from([(QueryPriority::Batch, one)]),
+        });
+
+        async fn thread_name(exec: &DedicatedExecutor) -> String {
+            exec.spawn(async { std::thread::current().name().unwrap().to_string() })
+                .await
+                .unwrap()
+        }
+
+        let batch = exec.executor(ExecutorType::PriorityQuery(QueryPriority::Batch));
+        assert_eq!(thread_name(batch).await, "IOx Query (batch) 1");
+
+        // classes without a dedicated pool use the query pool
+        let interactive = exec.executor(ExecutorType::PriorityQuery(QueryPriority::Interactive));
+        assert_eq!(thread_name(interactive).await, "IOx Query 1");
+
+        let ctx = exec.new_context(ExecutorType::PriorityQuery(QueryPriority::Batch));
+        assert_eq!(ctx.priority(), QueryPriority::Batch);
+        assert_eq!(ctx.child_ctx("child").priority(), QueryPriority::Batch);
+        let ctx = exec.new_context(ExecutorType::Query);
+        assert_eq!(ctx.priority(), QueryPriority::Normal);
+
+        exec.join().await;
+    }
+
     #[tokio::test]
     async fn executor_known_string_set_plan_ok() {
         let expected_strings = to_set(&["Foo", "Bar"]);
@@ -520,6 +623,23 @@ mod tests {
         assert_eq!(results, to_set(&["f1", "f2"]));
     }
 
+    #[tokio::test]
+    async fn cancel_running_query() {
+        let exec = Executor::new_testing();
+        let ctx = exec.new_context(ExecutorType::Query);
+
+        let child_ctx = ctx.child_ctx("child");
+        let fut = child_ctx.run(async move {
+            futures::future::pending::<()>().await;
+            Ok(())
+        });
+
+        ctx.cancel();
+
+        let err = fut.await.unwrap_err();
+        assert!(cancel::QueryCancelled::is_cancelled(&err), "{err}");
+    }
+
     /// return a set for testing
     fn to_set(strs: &[&str]) -> StringSetRef {
         StringSetRef::new(strs.iter().map(|s| s.to_string()).collect::<StringSet>())
@@ -0,0 +1,141 @@
+//! Tooling to stop running queries.
+//!
+//! Every [`IOxSessionContext`](super::IOxSessionContext) carries a [`CancellationToken`]. Cancelling it stops all
+//! planning work and result streams that were created from that context (and its children).
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use datafusion::error::DataFusionError;
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+
+/// Error returned by queries that were cancelled while running.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryCancelled;
+
+impl std::fmt::Display for QueryCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query cancelled")
+    }
+}
+
+impl std::error::Error for QueryCancelled {}
+
+impl QueryCancelled {
+    /// Returns a [`DataFusionError`] signalling that the query was cancelled.
+    pub fn into_df_error(self) -> DataFusionError {
+        DataFusionError::External(Box::new(self))
+    }
+
+    /// Returns `true` if the given error signals a cancelled query.
+    pub fn is_cancelled(e: &DataFusionError) -> bool {
+        match e {
+            DataFusionError::External(e) => e.is::<Self>(),
+            DataFusionError::Context(_, e) => Self::is_cancelled(e),
+            _ => false,
+        }
+    }
+}
+
+/// Drive `fut` to completion unless `token` is cancelled first.
+///
+/// On cancellation `fut` is dropped and a [`QueryCancelled`] error is returned.
+pub async fn run_cancellable<F, T>(
+    token: Option<CancellationToken>,
+    fut: F,
+) -> Result<T, DataFusionError>
+where
+    F: Future<Output = Result<T, DataFusionError>> + Send,
+{
+    let Some(token) = token else {
+        return fut.await;
+    };
+
+    tokio::select! {
+        res = fut => res,
+        _ = token.cancelled() => Err(QueryCancelled.into_df_error()),
+    }
+}
+
+/// [`Stream`] that ends with a [`QueryCancelled`] error once its [`CancellationToken`] is cancelled.
+///
+/// The inner stream is dropped as soon as cancellation is observed, which releases all the resources (incl. tasks
+/// running on the [`DedicatedExecutor`](executor::DedicatedExecutor)) held by it.
+pub struct CancellableStream<S> {
+    /// Inner stream, set to `None` after cancellation.
+    inner: Option<S>,
+
+    /// Resolves once the query is cancelled.
+    cancelled: BoxFuture<'static, ()>,
+}
+
+impl<S> std::fmt::Debug for CancellableStream<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellableStream")
+            .field("cancelled", &self.inner.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> CancellableStream<S> {
+    /// Create a new stream that yields the items of `inner` until `token` is cancelled.
+    pub fn new(inner: S, token: CancellationToken) -> Self {
+        Self {
+            inner: Some(inner),
+            cancelled: async move { token.cancelled().await }.boxed(),
+        }
+    }
+}
+
+impl<S, T> Stream for CancellableStream<S>
+where
+    S: Stream<Item = Result<T, DataFusionError>> + Unpin,
+{
+    type Item = Result<T, DataFusionError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if this.cancelled.poll_unpin(cx).is_ready() {
+            this.inner = None;
+            return Poll::Ready(Some(Err(QueryCancelled.into_df_error())));
+        }
+
+        inner.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_not_cancelled() {
+        let inner = futures::stream::iter([Ok(1), Ok(2)]);
+        let stream = CancellableStream::new(inner, CancellationToken::new());
+        let res: Vec<i32> = stream.try_collect().await.unwrap();
+        assert_eq!(res, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled() {
+        let token = CancellationToken::new();
+        let inner = futures::stream::iter([Ok(1), Ok(2)]).chain(futures::stream::pending());
+        let mut stream = CancellableStream::new(inner, token.clone());
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        token.cancel();
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(QueryCancelled::is_cancelled(&err));
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -2,6 +2,7 @@
 //! DataFusion
 
 use super::{
+    cancel::{CancellableStream, QueryCancelled},
     cross_rt_stream::CrossRtStream,
     gapfill::{plan_gap_fill, GapFill},
     non_null_checker::NonNullCheckerNode,
@@ -32,6 +33,7 @@ use crate::{
 };
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
+use data_types::QueryPriority;
 use datafusion::{
     catalog::catalog::CatalogProvider,
     execution::{
@@ -56,6 +58,7 @@ use futures::{Stream, StreamExt, TryStreamExt};
 use observability_deps::tracing::{debug, warn};
 use query_functions::{register_scalar_functions, selectors::register_selector_aggregates};
 use std::{convert::TryInto, fmt, num::NonZeroUsize, sync::Arc};
+use tokio_util::sync::CancellationToken;
 use trace::{
     ctx::SpanContext,
     span::{MetaValue, Span, SpanExt, SpanRecorder},
@@ -168,6 +171,9 @@ pub struct IOxSessionConfig {
     /// Executor to run on
     exec: DedicatedExecutor,
 
+    /// Priority class of the query
+    priority: QueryPriority,
+
     /// DataFusion session configuration
     session_config: SessionConfig,
 
@@ -188,7 +194,11 @@ impl fmt::Debug for IOxSessionConfig {
 }
 
 impl IOxSessionConfig {
-    pub(super) fn new(exec: DedicatedExecutor, runtime: Arc<RuntimeEnv>) -> Self {
+    pub(super) fn new(
+        exec: DedicatedExecutor,
+        priority: QueryPriority,
+        runtime: Arc<RuntimeEnv>,
+    ) -> Self {
         let mut session_config = iox_session_config();
         session_config
             .options_mut()
@@ -197,6 +207,7 @@ impl IOxSessionConfig {
 
         Self {
             exec,
+            priority,
             session_config,
             runtime,
             default_catalog: None,
@@ -225,6 +236,19 @@ impl IOxSessionConfig {
         Self { span_ctx, ..self }
     }
 
+    /// Set the DataFusion [`MemoryPool`] used by this context.
+    ///
+    /// This replaces the pool of the shared runtime, e.g. to apply a quota on top of it. The disk manager and object
+    /// stores of the shared runtime are kept.
+    pub fn with_memory_pool(mut self, memory_pool: Arc<dyn MemoryPool>) -> Self {
+        self.runtime = Arc::new(RuntimeEnv {
+            memory_pool,
+            disk_manager: Arc::clone(&self.runtime.disk_manager),
+            object_store_registry: Arc::clone(&self.runtime.object_store_registry),
+        });
+        self
+    }
+
     /// Set DataFusion [config option].
     ///
     /// May be used to set [IOx-specific] option as well.
@@ -249,11 +273,13 @@ impl IOxSessionConfig {
     pub fn build(self) -> IOxSessionContext {
         let maybe_span = self.span_ctx.child_span("Query Execution");
         let recorder = SpanRecorder::new(maybe_span);
+        let cancellation_token = CancellationToken::new();
 
-        // attach span to DataFusion session
+        // attach span and cancellation token to DataFusion session
         let session_config = self
             .session_config
-            .with_extension(Arc::new(recorder.span().cloned()));
+            .with_extension(Arc::new(recorder.span().cloned()))
+            .with_extension(Arc::new(cancellation_token.clone()));
 
         let state = SessionState::with_config_rt(session_config, self.runtime)
             .with_query_planner(Arc::new(IOxQueryPlanner {}));
@@ -267,7 +293,13 @@ impl IOxSessionConfig {
             inner.register_catalog(DEFAULT_CATALOG, default_catalog);
         }
 
-        IOxSessionContext::new(inner, self.exec, recorder)
+        IOxSessionContext::new(
+            inner,
+            self.exec,
+            self.priority,
+            recorder,
+            cancellation_token,
+        )
     }
 }
 
@@ -294,8 +326,16 @@ pub struct IOxSessionContext {
     /// can be handled.
     exec: DedicatedExecutor,
 
+    /// Priority class of the query, which determined `exec`.
+    priority: QueryPriority,
+
     /// Span context from which to create spans for this query
     recorder: SpanRecorder,
+
+    /// Token used to cancel the query.
+    ///
+    /// This is shared with all child contexts.
+    cancellation_token: CancellationToken,
 }
 
 impl fmt::Debug for IOxSessionContext {
@@ -303,7 +343,9 @@ impl fmt::Debug for IOxSessionContext {
         f.debug_struct("IOxSessionContext")
             .field("inner", &"<DataFusion ExecutionContext>")
             .field("exec", &self.exec)
+            .field("priority", &self.priority)
             .field("recorder", &self.recorder)
+            .field("cancelled", &self.cancellation_token.is_cancelled())
             .finish()
     }
 }
@@ -317,7 +359,9 @@ impl IOxSessionContext {
         Self {
             inner: SessionContext::default(),
             exec: DedicatedExecutor::new_testing(),
+            priority: QueryPriority::default(),
             recorder: SpanRecorder::default(),
+            cancellation_token: CancellationToken::new(),
         }
     }
 
@@ -325,12 +369,16 @@ impl IOxSessionContext {
     pub(crate) fn new(
         inner: SessionContext,
         exec: DedicatedExecutor,
+        priority: QueryPriority,
         recorder: SpanRecorder,
+        cancellation_token: CancellationToken,
     ) -> Self {
         Self {
             inner,
             exec,
+            priority,
             recorder,
+            cancellation_token,
         }
     }
 
@@ -474,6 +522,7 @@ impl IOxSessionContext {
         // requests timetouts (either for new requests, metrics or even for HTTP2 pings on the active connection).
         let schema = stream.schema();
         let stream = CrossRtStream::new_with_df_error_stream(stream, self.exec.clone());
+        let stream = CancellableStream::new(stream, self.cancellation_token.clone());
         let stream = RecordBatchStreamAdapter::new(schema, stream);
         Ok(Box::pin(stream))
     }
@@ -500,13 +549,15 @@ impl IOxSessionContext {
         // Run the plans in parallel
         let ctx = self.child_ctx("to_series_set");
         let exec = self.exec.clone();
+        let token = self.cancellation_token.clone();
         let data = futures::stream::iter(plans)
             .then(move |plan| {
                 let ctx = ctx.child_ctx("for plan");
                 let exec = exec.clone();
+                let token = token.clone();
 
                 async move {
-                    let stream = Self::run_inner(exec.clone(), async move {
+                    let stream = Self::run_inner(exec.clone(), token.clone(), async move {
                         let SeriesSetPlan {
                             table_name,
                             plan,
@@ -526,7 +577,8 @@ impl IOxSessionContext {
                     })
                     .await?;
 
-                    Ok::<_, Error>(CrossRtStream::new_with_df_error_stream(stream, exec))
+                    let stream = CrossRtStream::new_with_df_error_stream(stream, exec);
+                    Ok::<_, Error>(CancellableStream::new(stream, token))
                 }
             })
             .try_flatten()
@@ -663,20 +715,29 @@ impl IOxSessionContext {
         Fut: std::future::Future<Output = Result<T>> + Send + 'static,
         T: Send + 'static,
     {
-        Self::run_inner(self.exec.clone(), fut).await
+        Self::run_inner(self.exec.clone(), self.cancellation_token.clone(), fut).await
     }
 
-    async fn run_inner<Fut, T>(exec: DedicatedExecutor, fut: Fut) -> Result<T>
+    async fn run_inner<Fut, T>(
+        exec: DedicatedExecutor,
+        cancellation_token: CancellationToken,
+        fut: Fut,
+    ) -> Result<T>
     where
         Fut: std::future::Future<Output = Result<T>> + Send + 'static,
         T: Send + 'static,
     {
-        exec.spawn(fut).await.unwrap_or_else(|e| {
-            Err(Error::Context(
-                "Join Error".to_string(),
-                Box::new(Error::External(e.into())),
-            ))
-        })
+        // dropping the job on cancellation also stops the task on the executor
+        let job = exec.spawn(fut);
+        tokio::select! {
+            res = job => res.unwrap_or_else(|e| {
+                Err(Error::Context(
+                    "Join Error".to_string(),
+                    Box::new(Error::External(e.into())),
+                ))
+            }),
+            _ = cancellation_token.cancelled() => Err(QueryCancelled.into_df_error()),
+        }
     }
 
     /// Returns a IOxSessionContext with a SpanRecorder that is a child of the current
@@ -684,7 +745,9 @@ impl IOxSessionContext {
         Self::new(
             self.inner.clone(),
             self.exec.clone(),
+            self.priority,
             self.recorder.child(name),
+            self.cancellation_token.clone(),
         )
     }
 
@@ -708,6 +771,23 @@ impl IOxSessionContext {
         self.recorder.child_span(name)
     }
 
+    /// Returns the token that cancels this query.
+    ///
+    /// Cancelling it stops all planning and execution started from this context and its children.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Returns the priority class of this query.
+    pub fn priority(&self) -> QueryPriority {
+        self.priority
+    }
+
+    /// Cancel this query.
+    pub fn cancel(&self) {
+        self.cancellation_token.cancel();
+    }
+
     /// Number of currently active tasks.
     pub fn tasks(&self) -> usize {
         self.exec.tasks()
@@ -721,6 +801,9 @@ pub trait SessionContextIOxExt {
 
     /// Get span context
     fn span_ctx(&self) -> Option<SpanContext>;
+
+    /// Get the token that cancels the query, if any.
+    fn cancellation_token(&self) -> Option<CancellationToken>;
 }
 
 impl SessionContextIOxExt for SessionState {
@@ -735,4 +818,10 @@ impl SessionContextIOxExt for SessionState {
             .get_extension::<Option<Span>>()
             .and_then(|span| span.as_ref().as_ref().map(|span| span.ctx.clone()))
     }
+
+    fn cancellation_token(&self) -> Option<CancellationToken> {
+        self.config()
+            .get_extension::<CancellationToken>()
+            .map(|token| token.as_ref().clone())
+    }
 }
@@ -558,6 +558,12 @@ impl Cursor {
                 input_time_array,
                 input_aggr_array,
             ),
+            AggrColState::Default(_) => self.build_aggr_fill_default(
+                params,
+                series_ends,
+                input_time_array,
+                input_aggr_array,
+            ),
         }
     }
 
@@ -596,6 +602,61 @@ impl Cursor {
         take::take(input_aggr_array, &take_arr, None).map_err(DataFusionError::ArrowError)
     }
 
+    /// Builds an array using the [`interleave`](arrow::compute::interleave) kernel
+    /// to produce an aggregate output column, filling gaps and null values
+    /// with a constant value.
+    fn build_aggr_fill_default(
+        &mut self,
+        params: &GapFillParams,
+        series_ends: &[usize],
+        input_time_array: &TimestampNanosecondArray,
+        input_aggr_array: &ArrayRef,
+    ) -> Result<ArrayRef> {
+        struct AggrBuilder<'a> {
+            interleave_idxs: Vec<(usize, usize)>,
+            input_aggr_array: &'a ArrayRef,
+        }
+
+        impl<'a> AggrBuilder<'a> {
+            const FILL_VALUE: (usize, usize) = (0, 0);
+            const BUFFERED_INPUT_ARRAY: usize = 1;
+        }
+
+        impl<'a> VecBuilder for AggrBuilder<'a> {
+            fn push(&mut self, row_status: RowStatus) -> Result<()> {
+                match row_status {
+                    RowStatus::NullTimestamp { offset, .. } => self
+                        .interleave_idxs
+                        .push((Self::BUFFERED_INPUT_ARRAY, offset)),
+                    RowStatus::Present { offset, .. } if self.input_aggr_array.is_valid(offset) => {
+                        self.interleave_idxs
+                            .push((Self::BUFFERED_INPUT_ARRAY, offset))
+                    }
+                    RowStatus::Present { .. } | RowStatus::Missing { .. } => {
+                        self.interleave_idxs.push(Self::FILL_VALUE)
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        let fill_array = self
+            .get_aggr_col_state()
+            .default_value()
+            .to_array_of_size(1);
+        let mut aggr_builder = AggrBuilder {
+            interleave_idxs: Vec::with_capacity(self.remaining_output_batch_size),
+            input_aggr_array,
+        };
+        self.build_vec(params, input_time_array, series_ends, &mut aggr_builder)?;
+
+        arrow::compute::interleave(
+            &[&fill_array, input_aggr_array],
+            &aggr_builder.interleave_idxs,
+        )
+        .map_err(DataFusionError::ArrowError)
+    }
+
     /// Builds an array using the [`take`](take::take) kernel
     /// to produce an aggregate output column, filling gaps with the
     /// previous values in the column.
@@ -839,6 +900,9 @@ enum AggrColState {
     /// of a "segment" (two non-null points in the input separated by more
     /// than the stride) between output batches.
     LinearInterpolate(Option<Segment<ScalarValue>>),
+    /// For [FillStrategy::Default], the value used to fill gaps.
+    /// There is no other state to maintain.
+    Default(ScalarValue),
 }
 
 impl AggrColState {
@@ -849,6 +913,7 @@ impl AggrColState {
             FillStrategy::PrevNullAsIntentional => Self::PrevNullAsIntentional { offset: None },
             FillStrategy::PrevNullAsMissing => Self::PrevNullAsMissing { offset: None },
             FillStrategy::LinearInterpolate => Self::LinearInterpolate(None),
+            FillStrategy::Default(v) => Self::Default(v.clone()),
         }
     }
 
@@ -895,6 +960,18 @@ impl AggrColState {
         }
     }
 
+    /// Return the value used to fill gaps.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `self` is not [AggrColState::Default].
+    fn default_value(&self) -> &ScalarValue {
+        match self {
+            Self::Default(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
     /// Return the segment being interpolated, if any.
     ///
     /// # Panics
@@ -909,6 +909,84 @@ fn test_gapfill_fill_interpolate() {
     }
 }
 
+#[test]
+fn test_gapfill_fill_default() {
+    test_helpers::maybe_start_logging();
+    insta::allow_duplicates! {
+        for output_batch_size in [16, 1] {
+        for input_batch_size in [8, 1] {
+            let records = TestRecords {
+                group_cols: vec![vec![
+                    Some("a"),
+                    Some("a"),
+                    Some("b"),
+                    Some("b"),
+                    Some("b"),
+                ]],
+                time_col: vec![
+                    // 975
+                    Some(1000),
+                    // 1025
+                    // 1050
+                    Some(1075),
+                    // 1100
+                    // 1125
+                    // --- new series
+                    // 975
+                    Some(1000),
+                    // 1025
+                    Some(1050),
+                    // 1075
+                    Some(1100),
+                    // 1125
+                ],
+                agg_cols: vec![vec![
+                    Some(10),  // a: 1000
+                    None,      // a: 1075
+                    Some(20),  // b: 1000
+                    None,      // b: 1050
+                    Some(21),  // b: 1100
+                ]],
+                input_batch_size,
+            };
+            let params = get_params_ms_with_fill_strategy(&records, 25, Some(975), 1_125, FillStrategy::Default(ScalarValue::Int64(Some(0))));
+            let tc = TestCase {
+                test_records: records,
+                output_batch_size,
+                params,
+            };
+            let batches = tc.run().unwrap();
+            let actual = batches_to_lines(&batches);
+            insta::with_settings!({
+                description => format!("input_batch_size: {input_batch_size}, output_batch_size: {output_batch_size}"),
+            }, {
+                insta::assert_yaml_snapshot!(actual, @r###"
+                ---
+                - +----+--------------------------+----+
+                - "| g0 | time                     | a0 |"
+                - +----+--------------------------+----+
+                - "| a  | 1970-01-01T00:00:00.975Z | 0  |"
+                - "| a  | 1970-01-01T00:00:01Z     | 10 |"
+                - "| a  | 1970-01-01T00:00:01.025Z | 0  |"
+                - "| a  | 1970-01-01T00:00:01.050Z | 0  |"
+                - "| a  | 1970-01-01T00:00:01.075Z | 0  |"
+                - "| a  | 1970-01-01T00:00:01.100Z | 0  |"
+                - "| a  | 1970-01-01T00:00:01.125Z | 0  |"
+                - "| b  | 1970-01-01T00:00:00.975Z | 0  |"
+                - "| b  | 1970-01-01T00:00:01Z     | 20 |"
+                - "| b  | 1970-01-01T00:00:01.025Z | 0  |"
+                - "| b  | 1970-01-01T00:00:01.050Z | 0  |"
+                - "| b  | 1970-01-01T00:00:01.075Z | 0  |"
+                - "| b  | 1970-01-01T00:00:01.100Z | 21 |"
+                - "| b  | 1970-01-01T00:00:01.125Z | 0  |"
+                - +----+--------------------------+----+
+                "###)
+            });
+            assert_batch_count(&batches, output_batch_size);
+        }
+    }}
+}
+
 #[test]
 fn test_gapfill_simple_no_lower_bound_with_nulls() {
     test_helpers::maybe_start_logging();
@@ -31,6 +31,7 @@ use datafusion::{
         SendableRecordBatchStream, Statistics,
     },
     prelude::Expr,
+    scalar::ScalarValue,
 };
 
 use self::stream::GapFillStream;
@@ -86,6 +87,11 @@ pub enum FillStrategy {
     /// Null values will not be considered as missing, so two non-null values
     /// with a null in between will not be filled.
     LinearInterpolate,
+    /// Fill with the given constant value, which must have the same
+    /// type as the aggregate column. Null values in the input
+    /// column are also replaced.
+    /// This is the InfluxQL behavior for `FILL(<value>)`.
+    Default(ScalarValue),
 }
 
 impl GapFillParams {
@@ -219,6 +225,7 @@ impl UserDefinedLogicalNodeCore for GapFill {
                 FillStrategy::PrevNullAsIntentional => format!("LOCF(null-as-intentional, {})", e),
                 FillStrategy::PrevNullAsMissing => format!("LOCF({})", e),
                 FillStrategy::LinearInterpolate => format!("INTERPOLATE({})", e),
+                FillStrategy::Default(v) => format!("FILL({}, {:?})", e, v),
                 FillStrategy::Null => e.to_string(),
             })
             .collect::<Vec<String>>()
@@ -542,6 +549,7 @@ impl ExecutionPlan for GapFillExec {
                         }
                         FillStrategy::PrevNullAsMissing => format!("LOCF({})", e),
                         FillStrategy::LinearInterpolate => format!("INTERPOLATE({})", e),
+                        FillStrategy::Default(v) => format!("FILL({}, {:?})", e, v),
                         FillStrategy::Null => e.to_string(),
                     })
                     .collect();
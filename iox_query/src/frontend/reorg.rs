@@ -2,12 +2,16 @@
 
 use std::sync::Arc;
 
+use arrow::datatypes::Field;
+use data_types::{RollupAggregate, RollupRule};
 use datafusion::{
     logical_expr::LogicalPlan,
-    prelude::{col, lit_timestamp_nano},
+    prelude::{avg, col, date_bin, lit, lit_timestamp_nano, max, min, sum, Expr},
+    scalar::ScalarValue,
 };
 use observability_deps::tracing::debug;
-use schema::{sort::SortKey, Schema, TIME_COLUMN_NAME};
+use query_functions::selectors::{selector_first, selector_last, SelectorOutput};
+use schema::{sort::SortKey, InfluxColumnType, Schema, TIME_COLUMN_NAME};
 
 use crate::{exec::make_stream_split, QueryChunk};
 use snafu::{ResultExt, Snafu};
@@ -209,11 +213,111 @@ impl ReorgPlanner {
 
         Ok(plan)
     }
+
+    /// Creates an execution plan for the ROLLUP operation which does the following:
+    ///
+    /// 1. Merges chunks together into a single stream
+    /// 2. Deduplicates via PK as necessary
+    /// 3. Aggregates the fields of each series into windows of `rule.interval_ns`, using the
+    ///    aggregate that `rule` specifies for the type of the field
+    /// 4. Sorts the result according to the requested `output_sort_key`
+    ///
+    /// Each window is stamped with its start time. Stamping with the end time (as the
+    /// `window_bounds` function does) could move the last window of a partition into the
+    /// time range of the next partition.
+    ///
+    /// The plan looks like:
+    ///
+    /// ```text
+    /// (Projection to the columns of schema)
+    ///   (Sort on output_sort_key)
+    ///     (Aggregate grouped by tags and window start)
+    ///       (Scan chunks) <-- any needed deduplication happens here
+    /// ```
+    pub fn rollup_plan<I>(
+        &self,
+        table_name: Arc<str>,
+        schema: &Schema,
+        chunks: I,
+        output_sort_key: SortKey,
+        rule: &RollupRule,
+    ) -> Result<LogicalPlan>
+    where
+        I: IntoIterator<Item = Arc<dyn QueryChunk>>,
+    {
+        let scan_plan = ScanPlanBuilder::new(table_name, schema)
+            .with_chunks(chunks)
+            .build()
+            .context(BuildingScanSnafu)?;
+
+        let mut group_exprs = Vec::with_capacity(schema.len());
+        let mut aggr_exprs = Vec::with_capacity(schema.len());
+        for (column_type, field) in schema.iter() {
+            match column_type {
+                InfluxColumnType::Tag => group_exprs.push(col(field.name())),
+                InfluxColumnType::Timestamp => group_exprs.push(
+                    date_bin(
+                        lit(ScalarValue::IntervalMonthDayNano(Some(
+                            rule.interval_ns as i128,
+                        ))),
+                        col(TIME_COLUMN_NAME),
+                        lit_timestamp_nano(0),
+                    )
+                    .alias(TIME_COLUMN_NAME),
+                ),
+                InfluxColumnType::Field(field_type) => {
+                    aggr_exprs.push(make_rollup_expr(rule.aggregate(field_type), field))
+                }
+            }
+        }
+
+        let sort_exprs = output_sort_key
+            .iter()
+            .map(|(name, options)| {
+                col(name.as_ref()).sort(!options.descending, options.nulls_first)
+            })
+            .collect::<Vec<_>>();
+
+        // the aggregate reorders the columns, restore the order of the schema
+        let projection = schema
+            .iter()
+            .map(|(_, field)| col(field.name()))
+            .collect::<Vec<_>>();
+
+        let plan = scan_plan
+            .plan_builder
+            .aggregate(group_exprs, aggr_exprs)?
+            .sort(sort_exprs)?
+            .project(projection)?
+            .build()?;
+
+        debug!(table_name=scan_plan.provider.table_name(), plan=%plan.display_indent_schema(),
+               "created rollup plan for table");
+
+        Ok(plan)
+    }
+}
+
+/// Creates the expression that combines all values of `field` within one window.
+fn make_rollup_expr(agg: RollupAggregate, field: &Field) -> Expr {
+    let value = col(field.name());
+    let expr = match agg {
+        RollupAggregate::Mean => avg(value),
+        RollupAggregate::Sum => sum(value),
+        RollupAggregate::Min => min(value),
+        RollupAggregate::Max => max(value),
+        RollupAggregate::First => selector_first(field.data_type(), SelectorOutput::Value)
+            .call(vec![value, col(TIME_COLUMN_NAME)]),
+        RollupAggregate::Last => selector_last(field.data_type(), SelectorOutput::Value)
+            .call(vec![value, col(TIME_COLUMN_NAME)]),
+    };
+    expr.alias(field.name())
 }
 
 #[cfg(test)]
 mod test {
     use arrow_util::assert_batches_eq;
+    use data_types::{NamespaceId, RollupRuleId};
     use datafusion_util::{test_collect, test_collect_partition};
     use schema::merge::SchemaMerger;
     use schema::sort::SortKeyBuilder;
@@ -435,6 +539,60 @@ mod test {
         assert_batches_eq!(&expected, &batches);
     }
 
+    #[tokio::test]
+    async fn test_rollup_plan() {
+        test_helpers::maybe_start_logging();
+
+        let (schema, chunks) = get_test_chunks().await;
+
+        let sort_key = SortKeyBuilder::with_capacity(2)
+            .with_col_opts("tag1", false, false)
+            .with_col_opts(TIME_COLUMN_NAME, false, false)
+            .build();
+
+        let rule = RollupRule {
+            id: RollupRuleId::new(1),
+            namespace_id: NamespaceId::new(1),
+            table_id: None,
+            interval_ns: 10_000,
+            min_age_ns: 0,
+            float_aggregate: RollupAggregate::Mean,
+            integer_aggregate: RollupAggregate::Sum,
+            unsigned_aggregate: RollupAggregate::Sum,
+            boolean_aggregate: RollupAggregate::Last,
+            string_aggregate: RollupAggregate::Last,
+        };
+
+        let rollup_plan = ReorgPlanner::new()
+            .rollup_plan(Arc::from("t"), &schema, chunks, sort_key, &rule)
+            .expect("created rollup plan");
+
+        let executor = Executor::new_testing();
+        let physical_plan = executor
+            .new_context(ExecutorType::Reorg)
+            .create_physical_plan(&rollup_plan)
+            .await
+            .unwrap();
+
+        let batches = test_collect(physical_plan).await;
+
+        // one row per series and window, stamped with the start of the window
+        let expected = vec![
+            "+-----------+------------+------+-----------------------------+",
+            "| field_int | field_int2 | tag1 | time                        |",
+            "+-----------+------------+------+-----------------------------+",
+            "| 100       |            | AL   | 1970-01-01T00:00:00Z        |",
+            "| 70        |            | CT   | 1970-01-01T00:00:00Z        |",
+            "| 1015      |            | MT   | 1970-01-01T00:00:00Z        |",
+            "| 70        | 70         | UT   | 1970-01-01T00:00:00.000220Z |",
+            "| 50        | 50         | VT   | 1970-01-01T00:00:00.000210Z |",
+            "| 1000      | 1000       | WA   | 1970-01-01T00:00:00.000020Z |",
+            "+-----------+------------+------+-----------------------------+",
+        ];
+
+        assert_batches_eq!(&expected, &batches);
+    }
+
     #[tokio::test]
     async fn test_split_plan() {
         test_helpers::maybe_start_logging();
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use crate::exec::context::IOxSessionContext;
+use crate::{
+    exec::context::IOxSessionContext,
+    result_cache::{self, QueryResultCache},
+};
 use datafusion::{error::Result, physical_plan::ExecutionPlan};
 
 /// This struct can create plans for running SQL queries against databases
@@ -21,4 +24,17 @@ impl SqlQueryPlanner {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         ctx.sql_to_physical_plan(query).await
     }
+
+    /// Same as [`query`](Self::query) but serves (parts of) the result from
+    /// `cache` if possible and stores the result for later queries.
+    ///
+    /// See [`result_cache`] for details.
+    pub async fn query_cached(
+        &self,
+        query: &str,
+        cache: Arc<dyn QueryResultCache>,
+        ctx: &IOxSessionContext,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        result_cache::plan_sql(query, cache, ctx).await
+    }
 }
@@ -23,6 +23,7 @@ use observability_deps::tracing::{debug, trace};
 use once_cell::sync::Lazy;
 use parquet_file::storage::ParquetExecInput;
 use predicate::{rpc_predicate::QueryNamespaceMeta, Predicate, PredicateMatch};
+use result_cache::QueryResultCache;
 use schema::{
     sort::{SortKey, SortKeyBuilder},
     Projection, Schema, TIME_COLUMN_NAME,
@@ -37,6 +38,7 @@ pub mod physical_optimizer;
 pub mod plan;
 pub mod provider;
 pub mod pruning;
+pub mod result_cache;
 pub mod statistics;
 pub mod util;
 
@@ -176,13 +178,40 @@ pub trait QueryNamespace: QueryNamespaceMeta + Debug + Send + Sync {
         ctx: IOxSessionContext,
     ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError>;
 
+    /// Returns chunks holding only the most recent value of every column for each series of the
+    /// table, if the namespace can prove they are complete.
+    ///
+    /// Planning a query that is insensitive to older rows (such as a `last()` selector with no
+    /// upper time bound) against these chunks yields the same result as planning it against
+    /// [`chunks`](Self::chunks). Returns `None` if such chunks are not available.
+    async fn last_value_chunks(
+        &self,
+        _table_name: &str,
+        _ctx: IOxSessionContext,
+    ) -> Result<Option<Vec<Arc<dyn QueryChunk>>>, DataFusionError> {
+        Ok(None)
+    }
+
     /// Record that particular type of query was run / planned
+    ///
+    /// Returns an error if the namespace may not run another query right now, e.g. because it exhausted a quota. The
+    /// returned token must be held until the query completes.
     fn record_query(
         &self,
         ctx: &IOxSessionContext,
         query_type: &str,
         query_text: QueryText,
-    ) -> QueryCompletedToken;
+    ) -> Result<QueryCompletedToken, DataFusionError>;
+
+    /// Cancel the running query with the given ID.
+    ///
+    /// Returns `false` if no such query is running in this namespace.
+    fn cancel_query(&self, query_id: u64) -> bool;
+
+    /// Result cache of this namespace, if enabled.
+    fn result_cache(&self) -> Option<Arc<dyn QueryResultCache>> {
+        None
+    }
 
     /// Upcast to [`QueryNamespaceMeta`].
     ///
@@ -289,6 +318,15 @@ pub trait QueryChunk: QueryChunkMeta + Debug + Send + Sync + 'static {
     /// Returns chunk type. Useful in tests and debug logs.
     fn chunk_type(&self) -> &str;
 
+    /// Fingerprint of the chunk data.
+    ///
+    /// Two chunks with the same fingerprint MUST contain the same data. This is used to validate entries of the
+    /// [query result cache](crate::result_cache). Returns `None` if the chunk cannot provide a stable fingerprint,
+    /// which prevents results derived from it from being cached.
+    fn data_fingerprint(&self) -> Option<u64> {
+        None
+    }
+
     /// Order of this chunk relative to other overlapping chunks.
     fn order(&self) -> ChunkOrder;
 
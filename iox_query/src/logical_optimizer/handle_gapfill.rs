@@ -5,16 +5,22 @@ pub mod range_predicate;
 
 use crate::exec::gapfill::{FillStrategy, GapFill, GapFillParams};
 use datafusion::{
-    common::tree_node::{RewriteRecursion, TreeNode, TreeNodeRewriter, VisitRecursion},
+    common::{
+        tree_node::{RewriteRecursion, TreeNode, TreeNodeRewriter, VisitRecursion},
+        DFSchema,
+    },
     error::{DataFusionError, Result},
     logical_expr::{
-        utils::expr_to_columns, Aggregate, BuiltinScalarFunction, Extension, LogicalPlan,
-        Projection,
+        utils::expr_to_columns, Aggregate, BuiltinScalarFunction, ExprSchemable, Extension,
+        LogicalPlan, Projection,
     },
     optimizer::{optimizer::ApplyOrder, OptimizerConfig, OptimizerRule},
     prelude::{col, Expr},
+    scalar::ScalarValue,
+};
+use query_functions::gapfill::{
+    DATE_BIN_GAPFILL_UDF_NAME, FILL_UDF_NAME, INTERPOLATE_UDF_NAME, LOCF_UDF_NAME,
 };
-use query_functions::gapfill::{DATE_BIN_GAPFILL_UDF_NAME, INTERPOLATE_UDF_NAME, LOCF_UDF_NAME};
 use std::{
     collections::HashSet,
     ops::{Bound, Range},
@@ -357,12 +363,47 @@ impl TreeNodeRewriter for DateBinGapfillRewriter {
     }
 }
 
-fn udf_to_fill_strategy(name: &str) -> Option<FillStrategy> {
-    match name {
+/// The names of the functions that specify how a gap-filled
+/// aggregate column should be filled.
+const FILL_FUNCTION_NAMES: [&str; 3] = [LOCF_UDF_NAME, INTERPOLATE_UDF_NAME, FILL_UDF_NAME];
+
+fn udf_to_fill_strategy(
+    name: &str,
+    args: &[Expr],
+    schema: &DFSchema,
+) -> Result<Option<FillStrategy>> {
+    Ok(match name {
         LOCF_UDF_NAME => Some(FillStrategy::PrevNullAsMissing),
         INTERPOLATE_UDF_NAME => Some(FillStrategy::LinearInterpolate),
+        FILL_UDF_NAME => Some(FillStrategy::Default(fill_value(
+            &args[0], &args[1], schema,
+        )?)),
         _ => None,
+    })
+}
+
+// Coerce the second argument to a call to FILL, which must be a constant,
+// to the type of the column being filled.
+fn fill_value(e: &Expr, value: &Expr, schema: &DFSchema) -> Result<ScalarValue> {
+    let Expr::Literal(value) = value else {
+        return Err(DataFusionError::Plan(format!(
+            "{FILL_UDF_NAME} requires a constant fill value, got {value}"
+        )))
+    };
+
+    let data_type = e.get_type(schema)?;
+    if value.get_datatype() == data_type {
+        return Ok(value.clone());
+    }
+
+    let array = arrow::compute::cast(&value.to_array(), &data_type)?;
+    let coerced = ScalarValue::try_from_array(&array, 0)?;
+    if coerced.is_null() && !value.is_null() {
+        return Err(DataFusionError::Plan(format!(
+            "{FILL_UDF_NAME} value {value} cannot be coerced to {data_type}"
+        )));
     }
+    Ok(coerced)
 }
 
 fn handle_projection(proj: &Projection) -> Result<Option<LogicalPlan>> {
@@ -381,20 +422,15 @@ fn handle_projection(proj: &Projection) -> Result<Option<LogicalPlan>> {
         return Ok(None)
     };
 
-    let fill_cols: Vec<(&Expr, FillStrategy, &str)> = proj_exprs
-        .iter()
-        .filter_map(|e| match e {
-            Expr::ScalarUDF { fun, args } => {
-                if let Some(strategy) = udf_to_fill_strategy(&fun.name) {
-                    let col = &args[0];
-                    Some((col, strategy, fun.name.as_str()))
-                } else {
-                    None
-                }
+    let mut fill_cols: Vec<(&Expr, FillStrategy, &str)> = vec![];
+    for e in proj_exprs {
+        if let Expr::ScalarUDF { fun, args } = e {
+            if let Some(strategy) = udf_to_fill_strategy(&fun.name, args, input.schema())? {
+                let col = &args[0];
+                fill_cols.push((col, strategy, fun.name.as_str()));
             }
-            _ => None,
-        })
-        .collect();
+        }
+    }
     if fill_cols.is_empty() {
         // No special gap-filling functions, nothing to do.
         return Ok(None);
@@ -417,7 +453,9 @@ fn handle_projection(proj: &Projection) -> Result<Option<LogicalPlan>> {
         .iter()
         .cloned()
         .map(|e| match e {
-            Expr::ScalarUDF { fun, mut args } if udf_to_fill_strategy(&fun.name).is_some() => {
+            Expr::ScalarUDF { fun, mut args }
+                if FILL_FUNCTION_NAMES.contains(&fun.name.as_str()) =>
+            {
                 args.remove(0)
             }
             _ => e,
@@ -460,7 +498,7 @@ fn check_node(node: &LogicalPlan) -> Result<()> {
             )));
         }
 
-        for fn_name in [LOCF_UDF_NAME, INTERPOLATE_UDF_NAME] {
+        for fn_name in FILL_FUNCTION_NAMES {
             if count_udf(expr, fn_name)? > 0 {
                 return Err(DataFusionError::Plan(format!(
                     "{fn_name} may only be used in the SELECT list of a gap-filling query"
@@ -485,7 +523,7 @@ mod test {
     use datafusion::prelude::{avg, case, col, lit, lit_timestamp_nano, min, Expr};
     use datafusion::scalar::ScalarValue;
     use query_functions::gapfill::{
-        DATE_BIN_GAPFILL_UDF_NAME, INTERPOLATE_UDF_NAME, LOCF_UDF_NAME,
+        DATE_BIN_GAPFILL_UDF_NAME, FILL_UDF_NAME, INTERPOLATE_UDF_NAME, LOCF_UDF_NAME,
     };
 
     fn table_scan() -> Result<LogicalPlan> {
@@ -539,6 +577,13 @@ mod test {
         })
     }
 
+    fn fill(arg: Expr, value: Expr) -> Result<Expr> {
+        Ok(Expr::ScalarUDF {
+            fun: query_functions::registry().udf(FILL_UDF_NAME)?,
+            args: vec![arg, value],
+        })
+    }
+
     fn optimize(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
         let optimizer = Optimizer::with_rules(vec![Arc::new(HandleGapFill::default())]);
         optimizer.optimize_recursively(
@@ -971,4 +1016,97 @@ mod test {
         "###);
         Ok(())
     }
+
+    #[test]
+    fn with_fill() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(table_scan()?)
+            .filter(
+                col("time")
+                    .gt_eq(lit_timestamp_nano(1000))
+                    .and(col("time").lt(lit_timestamp_nano(2000))),
+            )?
+            .aggregate(
+                vec![date_bin_gapfill(
+                    lit(ScalarValue::IntervalDayTime(Some(60_000))),
+                    col("time"),
+                )?],
+                vec![avg(col("temp")), min(col("temp"))],
+            )?
+            .project(vec![
+                col("date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)"),
+                fill(col("AVG(temps.temp)"), lit(0_i64))?,
+                fill(col("MIN(temps.temp)"), lit(1.5))?,
+            ])?
+            .build()?;
+
+        insta::assert_yaml_snapshot!(
+            format_optimized_plan(&plan)?,
+            @r###"
+        ---
+        - "Projection: date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time), AVG(temps.temp), MIN(temps.temp)"
+        - "  GapFill: groupBy=[[date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)]], aggr=[[FILL(AVG(temps.temp), Float64(0)), FILL(MIN(temps.temp), Float64(1.5))]], time_column=date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time), stride=IntervalDayTime(\"60000\"), range=Included(TimestampNanosecond(1000, None))..Excluded(TimestampNanosecond(2000, None))"
+        - "    Aggregate: groupBy=[[datebin(IntervalDayTime(\"60000\"), temps.time) AS date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)]], aggr=[[AVG(temps.temp), MIN(temps.temp)]]"
+        - "      Filter: temps.time >= TimestampNanosecond(1000, None) AND temps.time < TimestampNanosecond(2000, None)"
+        - "        TableScan: temps"
+        "###);
+        Ok(())
+    }
+
+    /// the fill value passed to FILL must be a constant
+    #[test]
+    fn fill_non_constant_err() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(table_scan()?)
+            .filter(
+                col("time")
+                    .gt_eq(lit_timestamp_nano(1000))
+                    .and(col("time").lt(lit_timestamp_nano(2000))),
+            )?
+            .aggregate(
+                vec![date_bin_gapfill(
+                    lit(ScalarValue::IntervalDayTime(Some(60_000))),
+                    col("time"),
+                )?],
+                vec![avg(col("temp")), min(col("temp"))],
+            )?
+            .project(vec![
+                col("date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)"),
+                fill(col("AVG(temps.temp)"), col("MIN(temps.temp)"))?,
+            ])?
+            .build()?;
+
+        assert_optimizer_err(
+            &plan,
+            "Error during planning: fill requires a constant fill value",
+        );
+        Ok(())
+    }
+
+    /// the fill value passed to FILL must be coercible to the column type
+    #[test]
+    fn fill_invalid_value_err() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(table_scan()?)
+            .filter(
+                col("time")
+                    .gt_eq(lit_timestamp_nano(1000))
+                    .and(col("time").lt(lit_timestamp_nano(2000))),
+            )?
+            .aggregate(
+                vec![date_bin_gapfill(
+                    lit(ScalarValue::IntervalDayTime(Some(60_000))),
+                    col("time"),
+                )?],
+                vec![avg(col("temp"))],
+            )?
+            .project(vec![
+                col("date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)"),
+                fill(col("AVG(temps.temp)"), lit("bananas"))?,
+            ])?
+            .build()?;
+
+        assert_optimizer_err(
+            &plan,
+            "Error during planning: fill value bananas cannot be coerced to Float64",
+        );
+        Ok(())
+    }
 }
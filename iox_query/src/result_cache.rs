@@ -0,0 +1,1295 @@
+//! Cache for query results.
+//!
+//! Dashboards tend to issue the very same queries over and over again while the data they read mostly lives in
+//! immutable parquet files. A [`QueryResultCache`] allows a namespace to serve the results of such queries without
+//! executing them again.
+//!
+//! # Keys & Invalidation
+//! Entries are keyed by the [normalized](normalize_query) query text; a cache handle is always scoped to a single
+//! namespace. Every entry records a fingerprint of the query input, i.e. of all chunks that the physical plan reads
+//! (see [`QueryChunk::data_fingerprint`]). An entry is only served if a freshly planned query reads exactly the same
+//! input, otherwise the query is executed and the entry is replaced. New writes, persisted or compacted files, and
+//! deletes therefore invalidate entries automatically.
+//!
+//! Plans that read anything that cannot be fingerprinted (e.g. system tables) or that use volatile functions like
+//! `random()` are never cached.
+//!
+//! # Relative Time Ranges
+//! Queries that depend on the query time, e.g. via `now()`, never produce the same result twice. For the common
+//! dashboard query shape
+//!
+//! ```sql
+//! SELECT date_bin(INTERVAL '1 minute', time) AS minute, avg(usage)
+//! FROM cpu
+//! WHERE time > now() - INTERVAL '1 hour'
+//! GROUP BY minute
+//! ORDER BY minute
+//! ```
+//!
+//! the output is split into time buckets of [`QueryResultCache::bucket_width`]. Buckets that are closed (i.e. end
+//! before `now()`) and lie entirely within the queried time range are cached individually, keyed by their start and
+//! validated by the fingerprint of the chunks that overlap them. Only the remaining buckets -- usually the open tail
+//! -- are computed; cached buckets are excluded from the query predicate and merged back into the output.
+//!
+//! This only applies to plans of the shape `[Sort] -> Projection -> Aggregate -> Filter -> ...` with exactly one
+//! `date_bin` group expression whose stride evenly divides the bucket width, and where only the `WHERE` clause
+//! depends on the query time. Note that it requires planning the query twice on a partial cache hit.
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    mem::size_of_val,
+    ops::Bound,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use arrow::{
+    array::{TimestampNanosecondArray, UInt32Array},
+    compute::{kernels::cast_utils::string_to_timestamp_nanos, take},
+    datatypes::{IntervalDayTimeType, IntervalMonthDayNanoType, SchemaRef},
+    record_batch::RecordBatch,
+};
+use data_types::TimestampRange;
+use datafusion::{
+    common::tree_node::{TreeNode, VisitRecursion},
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    logical_expr::{utils::from_plan, BuiltinScalarFunction, Filter, LogicalPlan, Volatility},
+    optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext},
+    physical_expr::execution_props::ExecutionProps,
+    physical_plan::{
+        coalesce_partitions::CoalescePartitionsExec,
+        empty::EmptyExec,
+        expressions::PhysicalSortExpr,
+        file_format::ParquetExec,
+        memory::MemoryExec,
+        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+        sorts::sort::SortExec,
+        union::UnionExec,
+        visit_execution_plan, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanVisitor,
+        Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics,
+    },
+    prelude::{lit_timestamp_nano, Column, Expr},
+    scalar::ScalarValue,
+};
+use futures::{ready, Stream, StreamExt};
+use observability_deps::tracing::debug;
+
+use crate::{
+    exec::IOxSessionContext,
+    logical_optimizer::range_predicate::find_time_range,
+    provider::{PartitionedFileExt, RecordBatchesExec},
+    QueryChunk,
+};
+
+/// Upper bound for the number of time buckets that are looked up for a single query.
+const MAX_BUCKETS: usize = 10_000;
+
+const NANOS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Cache for query results of a single namespace.
+pub trait QueryResultCache: Debug + Send + Sync {
+    /// Get cached result.
+    ///
+    /// The caller MUST check that the result was produced from the expected input.
+    fn get(&self, key: &ResultCacheKey) -> Option<Arc<CachedResult>>;
+
+    /// Store result, replacing any existing entry for the same key.
+    fn put(&self, key: ResultCacheKey, result: Arc<CachedResult>);
+
+    /// Width of the time buckets used to cache queries with relative time ranges.
+    ///
+    /// `None` disables caching of such queries.
+    fn bucket_width(&self) -> Option<Duration>;
+
+    /// Results larger than this (in bytes) are not cached.
+    fn max_entry_bytes(&self) -> usize;
+}
+
+/// Key of a [`QueryResultCache`] entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResultCacheKey {
+    /// Normalized query text.
+    pub query: Arc<str>,
+
+    /// Start of the time bucket in nanoseconds since the epoch, or `None` if the entry holds the entire result.
+    pub bucket: Option<i64>,
+}
+
+impl ResultCacheKey {
+    /// Size of the key in bytes, including `Self`.
+    pub fn size(&self) -> usize {
+        size_of_val(self) + self.query.len()
+    }
+}
+
+/// Value of a [`QueryResultCache`] entry.
+#[derive(Debug)]
+pub struct CachedResult {
+    /// Fingerprint of the input that produced this result, see [`input_fingerprint`].
+    pub input: u64,
+
+    /// Schema of the result.
+    pub schema: SchemaRef,
+
+    /// Result data.
+    pub batches: Vec<RecordBatch>,
+}
+
+impl CachedResult {
+    /// Size of the result in bytes, including `Self`.
+    pub fn size(&self) -> usize {
+        size_of_val(self)
+            + self
+                .batches
+                .iter()
+                .map(|batch| batch.get_array_memory_size())
+                .sum::<usize>()
+    }
+
+    fn is_valid(&self, input: u64, schema: &SchemaRef) -> bool {
+        self.input == input && &self.schema == schema
+    }
+}
+
+/// Normalize query text so that trivially different queries share cache entries.
+///
+/// Whitespace outside of quoted strings and identifiers is collapsed and trailing semicolons are removed.
+pub fn normalize_query(query: &str) -> String {
+    let mut out = String::with_capacity(query.len());
+    let mut quote = None;
+    let mut pending_space = false;
+
+    for c in query.chars() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && !out.is_empty() {
+            out.push(' ');
+        }
+        pending_space = false;
+
+        if c == '\'' || c == '"' {
+            quote = Some(c);
+        }
+        out.push(c);
+    }
+
+    if quote.is_none() {
+        let len = out
+            .trim_end_matches(|c: char| c == ';' || c.is_whitespace())
+            .len();
+        out.truncate(len);
+    }
+
+    out
+}
+
+/// Compute a fingerprint of all chunks that `plan` reads.
+///
+/// If `range` is given, only chunks that may contain data within this time range are considered.
+///
+/// Returns `None` if the plan reads data that cannot be fingerprinted, e.g. system tables or chunks without a
+/// [data fingerprint](QueryChunk::data_fingerprint).
+pub fn input_fingerprint(plan: &dyn ExecutionPlan, range: Option<TimestampRange>) -> Option<u64> {
+    let mut visitor = FingerprintVisitor {
+        range,
+        fingerprints: vec![],
+    };
+    if let Err(e) = visit_execution_plan(plan, &mut visitor) {
+        debug!(%e, "cannot fingerprint query input");
+        return None;
+    }
+
+    let mut fingerprints = visitor.fingerprints;
+    fingerprints.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    fingerprints.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[derive(Debug)]
+struct FingerprintVisitor {
+    range: Option<TimestampRange>,
+    fingerprints: Vec<u64>,
+}
+
+impl FingerprintVisitor {
+    fn add_chunk(&mut self, chunk: &dyn QueryChunk) -> Result<()> {
+        if let (Some(range), Some(time_range)) = (self.range, chunk.summary().time_range()) {
+            if !time_range.overlaps(range) {
+                return Ok(());
+            }
+        }
+
+        let fingerprint = chunk.data_fingerprint().ok_or_else(|| {
+            DataFusionError::External(
+                format!("{} chunk has no data fingerprint", chunk.chunk_type()).into(),
+            )
+        })?;
+
+        let mut hasher = DefaultHasher::new();
+        fingerprint.hash(&mut hasher);
+        chunk.delete_predicates().hash(&mut hasher);
+        self.fingerprints.push(hasher.finish());
+
+        Ok(())
+    }
+}
+
+impl ExecutionPlanVisitor for FingerprintVisitor {
+    type Error = DataFusionError;
+
+    fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        let plan_any = plan.as_any();
+
+        if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+            for chunk in record_batches_exec.chunks() {
+                self.add_chunk(chunk.as_ref())?;
+            }
+        } else if let Some(parquet_exec) = plan_any.downcast_ref::<ParquetExec>() {
+            for file in parquet_exec.base_config().file_groups.iter().flatten() {
+                let ext = file
+                    .extensions
+                    .as_ref()
+                    .and_then(|any| any.downcast_ref::<PartitionedFileExt>())
+                    .ok_or_else(|| {
+                        DataFusionError::External(
+                            String::from("PartitionedFileExt not found").into(),
+                        )
+                    })?;
+                self.add_chunk(ext.chunk.as_ref())?;
+            }
+        } else if plan.children().is_empty() && plan_any.downcast_ref::<EmptyExec>().is_none() {
+            return Err(DataFusionError::External(
+                String::from("plan reads data that is not backed by chunks").into(),
+            ));
+        }
+
+        Ok(true)
+    }
+}
+
+/// Plan a SQL query against the catalogs registered with `ctx`, serving (parts of) the result from `cache` where
+/// possible.
+///
+/// See the [module docs](self) for details.
+pub async fn plan_sql(
+    query: &str,
+    cache: Arc<dyn QueryResultCache>,
+    ctx: &IOxSessionContext,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let logical_plan = ctx.sql_to_logical_plan(query).await?;
+    let query: Arc<str> = normalize_query(query).into();
+
+    match plan_volatility(&logical_plan)? {
+        Volatility::Immutable => plan_whole(query, &logical_plan, cache, ctx).await,
+        Volatility::Stable => {
+            let Some(width) = cache.bucket_width() else {
+                return ctx.create_physical_plan(&logical_plan).await;
+            };
+
+            // `state()` marks the start of the execution and fixes the value of `now()`
+            let state = ctx.inner().state();
+            match BucketedQuery::try_new(&logical_plan, width, state.execution_props())? {
+                Some(bucketed) => plan_bucketed(query, bucketed, cache, ctx).await,
+                None => ctx.create_physical_plan(&logical_plan).await,
+            }
+        }
+        Volatility::Volatile => ctx.create_physical_plan(&logical_plan).await,
+    }
+}
+
+/// Plan query that is cached as a whole.
+async fn plan_whole(
+    query: Arc<str>,
+    logical_plan: &LogicalPlan,
+    cache: Arc<dyn QueryResultCache>,
+    ctx: &IOxSessionContext,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let plan = ctx.create_physical_plan(logical_plan).await?;
+    let Some(input) = input_fingerprint(plan.as_ref(), None) else {
+        return Ok(plan);
+    };
+
+    let key = ResultCacheKey {
+        query,
+        bucket: None,
+    };
+    let schema = plan.schema();
+    if let Some(cached) = cache.get(&key) {
+        if cached.is_valid(input, &schema) {
+            debug!(query=%key.query, "query result cache hit");
+            return Ok(Arc::new(MemoryExec::try_new(
+                &[cached.batches.clone()],
+                schema,
+                None,
+            )?));
+        }
+    }
+
+    debug!(query=%key.query, "query result cache miss");
+    Ok(ResultCacheExec::new_plan(
+        plan,
+        cache,
+        ResultSink::Whole { key, input },
+    ))
+}
+
+/// Plan query that is cached per time bucket.
+async fn plan_bucketed(
+    query: Arc<str>,
+    bucketed: BucketedQuery,
+    cache: Arc<dyn QueryResultCache>,
+    ctx: &IOxSessionContext,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let full_plan = ctx
+        .create_physical_plan(&bucketed.plan_excluding(&[])?)
+        .await?;
+    let schema = full_plan.schema();
+
+    let mut cached_batches = vec![];
+    let mut cached_buckets = vec![];
+    let mut inputs = BTreeMap::new();
+    for bucket in bucketed.closed_buckets() {
+        let range = TimestampRange::new(bucket, bucket.saturating_add(bucketed.grid.width));
+        let Some(input) = input_fingerprint(full_plan.as_ref(), Some(range)) else {
+            return Ok(full_plan);
+        };
+
+        let key = ResultCacheKey {
+            query: Arc::clone(&query),
+            bucket: Some(bucket),
+        };
+        match cache.get(&key) {
+            Some(cached) if cached.is_valid(input, &schema) => {
+                cached_batches.extend(cached.batches.iter().cloned());
+                cached_buckets.push(bucket);
+            }
+            _ => {
+                inputs.insert(bucket, input);
+            }
+        }
+    }
+    debug!(
+        %query,
+        hits=cached_buckets.len(),
+        misses=inputs.len(),
+        "query result cache bucket lookup",
+    );
+
+    let plan = if cached_buckets.is_empty() {
+        full_plan
+    } else {
+        let live_plan = ctx
+            .create_physical_plan(&bucketed.plan_excluding(&cached_buckets)?)
+            .await?;
+        if live_plan.schema() != schema {
+            return Ok(full_plan);
+        }
+
+        let ordering = live_plan
+            .output_ordering()
+            .map(|ordering| ordering.to_vec());
+        let cached_plan = Arc::new(MemoryExec::try_new(&[cached_batches], schema, None)?);
+        let union: Arc<dyn ExecutionPlan> = Arc::new(UnionExec::new(vec![cached_plan, live_plan]));
+        match ordering {
+            Some(ordering) => Arc::new(SortExec::new(
+                ordering,
+                Arc::new(CoalescePartitionsExec::new(union)),
+            )),
+            None => union,
+        }
+    };
+
+    if inputs.is_empty() {
+        return Ok(plan);
+    }
+
+    Ok(ResultCacheExec::new_plan(
+        plan,
+        cache,
+        ResultSink::Buckets {
+            query,
+            column: bucketed.output_column,
+            grid: bucketed.grid,
+            inputs,
+        },
+    ))
+}
+
+/// Determine the least restrictive volatility of all expressions in `plan`.
+///
+/// Subqueries are treated as volatile.
+fn plan_volatility(plan: &LogicalPlan) -> Result<Volatility> {
+    let mut volatility = Volatility::Immutable;
+    plan.apply(&mut |plan| {
+        for expr in plan.expressions() {
+            volatility = max_volatility(volatility, expr_volatility(&expr)?);
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(volatility)
+}
+
+fn expr_volatility(expr: &Expr) -> Result<Volatility> {
+    let mut volatility = Volatility::Immutable;
+    expr.apply(&mut |expr| {
+        let v = match expr {
+            Expr::ScalarFunction { fun, .. } => fun.volatility(),
+            Expr::ScalarUDF { fun, .. } => fun.signature.volatility,
+            Expr::Exists { .. } | Expr::InSubquery { .. } | Expr::ScalarSubquery(_) => {
+                Volatility::Volatile
+            }
+            _ => Volatility::Immutable,
+        };
+        volatility = max_volatility(volatility, v);
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(volatility)
+}
+
+fn max_volatility(a: Volatility, b: Volatility) -> Volatility {
+    match (a, b) {
+        (Volatility::Volatile, _) | (_, Volatility::Volatile) => Volatility::Volatile,
+        (Volatility::Stable, _) | (_, Volatility::Stable) => Volatility::Stable,
+        _ => Volatility::Immutable,
+    }
+}
+
+/// Aligned time buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BucketGrid {
+    /// Origin of the grid in nanoseconds since the epoch.
+    origin: i64,
+
+    /// Bucket width in nanoseconds.
+    width: i64,
+}
+
+impl BucketGrid {
+    /// Start of the bucket that contains `t`.
+    fn bucket_start(&self, t: i64) -> i64 {
+        let offset = t.saturating_sub(self.origin).div_euclid(self.width) * self.width;
+        self.origin.saturating_add(offset)
+    }
+}
+
+/// A time-dependent query whose output can be cached per time bucket.
+#[derive(Debug)]
+struct BucketedQuery {
+    /// Original logical plan.
+    plan: LogicalPlan,
+
+    /// `WHERE` predicate with all time-dependent expressions evaluated.
+    predicate: Expr,
+
+    /// Time column that is passed to `date_bin`.
+    time_column: Column,
+
+    /// Index of the binned time column within the output.
+    output_column: usize,
+
+    /// Bucket grid.
+    grid: BucketGrid,
+
+    /// Queried time range, inclusive start and exclusive end.
+    range: (i64, i64),
+
+    /// Query time.
+    now: i64,
+}
+
+impl BucketedQuery {
+    /// Analyze `plan`.
+    ///
+    /// Returns `None` if the plan does not have the supported shape.
+    fn try_new(
+        plan: &LogicalPlan,
+        width: Duration,
+        props: &ExecutionProps,
+    ) -> Result<Option<Self>> {
+        let (sort_exprs, rest) = match plan {
+            LogicalPlan::Sort(sort) => (sort.expr.as_slice(), sort.input.as_ref()),
+            other => ([].as_slice(), other),
+        };
+        let LogicalPlan::Projection(projection) = rest else {
+            return Ok(None);
+        };
+        let LogicalPlan::Aggregate(aggregate) = projection.input.as_ref() else {
+            return Ok(None);
+        };
+        let LogicalPlan::Filter(filter) = aggregate.input.as_ref() else {
+            return Ok(None);
+        };
+
+        // only the WHERE clause may depend on the query time
+        for expr in sort_exprs
+            .iter()
+            .chain(&projection.expr)
+            .chain(&aggregate.group_expr)
+            .chain(&aggregate.aggr_expr)
+        {
+            if expr_volatility(expr)? != Volatility::Immutable {
+                return Ok(None);
+            }
+        }
+        if plan_volatility(filter.input.as_ref())? != Volatility::Immutable
+            || expr_volatility(&filter.predicate)? == Volatility::Volatile
+        {
+            return Ok(None);
+        }
+
+        // find the single `date_bin` group expression
+        let mut date_bins = aggregate
+            .group_expr
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, expr)| match unwrap_alias(expr) {
+                Expr::ScalarFunction {
+                    fun: BuiltinScalarFunction::DateBin,
+                    args,
+                } => Some((idx, args)),
+                _ => None,
+            });
+        let (Some((group_idx, args)), None) = (date_bins.next(), date_bins.next()) else {
+            return Ok(None);
+        };
+        let (stride, time_column, origin) = match args.as_slice() {
+            [stride, Expr::Column(time_column)] => (stride, time_column, None),
+            [stride, Expr::Column(time_column), origin] => (stride, time_column, Some(origin)),
+            _ => return Ok(None),
+        };
+        let Some(stride) = interval_nanos(stride) else {
+            return Ok(None);
+        };
+        let origin = match origin {
+            Some(origin) => match timestamp_nanos(origin) {
+                Some(origin) => origin,
+                None => return Ok(None),
+            },
+            None => 0,
+        };
+        let Ok(width) = i64::try_from(width.as_nanos()) else {
+            return Ok(None);
+        };
+        if stride <= 0 || width <= 0 || width % stride != 0 {
+            return Ok(None);
+        }
+
+        // the binned time column must be part of the output
+        let group_column = aggregate.schema.field(group_idx).qualified_column();
+        let Some(output_column) = projection
+            .expr
+            .iter()
+            .position(|expr| matches!(unwrap_alias(expr), Expr::Column(c) if c.name == group_column.name))
+        else {
+            return Ok(None);
+        };
+
+        // evaluate `now()` & co
+        let simplifier = ExprSimplifier::new(
+            SimplifyContext::new(props).with_schema(Arc::clone(filter.input.schema())),
+        );
+        let Ok(predicate) = simplifier.simplify(filter.predicate.clone()) else {
+            return Ok(None);
+        };
+        if expr_volatility(&predicate)? != Volatility::Immutable {
+            return Ok(None);
+        }
+
+        let filter_plan = LogicalPlan::Filter(Filter::try_new(
+            predicate.clone(),
+            Arc::clone(&filter.input),
+        )?);
+        let range = find_time_range(&filter_plan, time_column)?;
+        let start = match &range.start {
+            Bound::Included(expr) => timestamp_nanos(expr),
+            Bound::Excluded(expr) => timestamp_nanos(expr).and_then(|t| t.checked_add(1)),
+            Bound::Unbounded => None,
+        };
+        let end = match &range.end {
+            Bound::Included(expr) => timestamp_nanos(expr).and_then(|t| t.checked_add(1)),
+            Bound::Excluded(expr) => timestamp_nanos(expr),
+            Bound::Unbounded => Some(i64::MAX),
+        };
+        let (Some(start), Some(end)) = (start, end) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            plan: plan.clone(),
+            predicate,
+            time_column: time_column.clone(),
+            output_column,
+            grid: BucketGrid { origin, width },
+            range: (start, end),
+            now: props.query_execution_start_time.timestamp_nanos(),
+        }))
+    }
+
+    /// Start of all closed buckets that lie entirely within the queried time range.
+    fn closed_buckets(&self) -> Vec<i64> {
+        let (start, end) = self.range;
+        let limit = end.min(self.now);
+
+        let mut bucket = self.grid.bucket_start(start);
+        if bucket < start {
+            bucket = bucket.saturating_add(self.grid.width);
+        }
+
+        let mut buckets = vec![];
+        while let Some(bucket_end) = bucket.checked_add(self.grid.width) {
+            if bucket_end > limit || buckets.len() >= MAX_BUCKETS {
+                break;
+            }
+            buckets.push(bucket);
+            bucket = bucket_end;
+        }
+        buckets
+    }
+
+    /// Logical plan that skips the given buckets.
+    ///
+    /// `buckets` must be sorted.
+    fn plan_excluding(&self, buckets: &[i64]) -> Result<LogicalPlan> {
+        // merge adjacent buckets into ranges
+        let mut ranges: Vec<(i64, i64)> = vec![];
+        for bucket in buckets {
+            let bucket_end = bucket.saturating_add(self.grid.width);
+            match ranges.last_mut() {
+                Some((_, end)) if end == bucket => *end = bucket_end,
+                _ => ranges.push((*bucket, bucket_end)),
+            }
+        }
+
+        let time = Expr::Column(self.time_column.clone());
+        let predicate =
+            ranges
+                .into_iter()
+                .fold(self.predicate.clone(), |predicate, (start, end)| {
+                    predicate.and(
+                        time.clone()
+                            .lt(lit_timestamp_nano(start))
+                            .or(time.clone().gt_eq(lit_timestamp_nano(end))),
+                    )
+                });
+
+        replace_filter(&self.plan, predicate)
+    }
+}
+
+/// Replace the predicate of the first [`Filter`] in a linear plan.
+fn replace_filter(plan: &LogicalPlan, predicate: Expr) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::Filter(filter) => Ok(LogicalPlan::Filter(Filter::try_new(
+            predicate,
+            Arc::clone(&filter.input),
+        )?)),
+        other => {
+            let inputs = other.inputs();
+            if inputs.len() != 1 {
+                return Err(DataFusionError::Internal(format!(
+                    "cannot replace filter in plan node with {} inputs",
+                    inputs.len()
+                )));
+            }
+            let input = replace_filter(inputs[0], predicate)?;
+            from_plan(other, &other.expressions(), &[input])
+        }
+    }
+}
+
+fn unwrap_alias(mut e: &Expr) -> &Expr {
+    loop {
+        match e {
+            Expr::Alias(inner, _) => e = inner.as_ref(),
+            e => break e,
+        }
+    }
+}
+
+fn interval_nanos(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(v))) => {
+            let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(*v);
+            if months != 0 {
+                return None;
+            }
+            (days as i64).checked_mul(NANOS_PER_DAY)?.checked_add(nanos)
+        }
+        Expr::Literal(ScalarValue::IntervalDayTime(Some(v))) => {
+            let (days, millis) = IntervalDayTimeType::to_parts(*v);
+            (days as i64)
+                .checked_mul(NANOS_PER_DAY)?
+                .checked_add((millis as i64).checked_mul(1_000_000)?)
+        }
+        _ => None,
+    }
+}
+
+fn timestamp_nanos(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(ScalarValue::TimestampNanosecond(Some(v), _)) => Some(*v),
+        Expr::Literal(ScalarValue::TimestampMicrosecond(Some(v), _)) => v.checked_mul(1_000),
+        Expr::Literal(ScalarValue::TimestampMillisecond(Some(v), _)) => v.checked_mul(1_000_000),
+        Expr::Literal(ScalarValue::TimestampSecond(Some(v), _)) => v.checked_mul(1_000_000_000),
+        Expr::Literal(ScalarValue::Utf8(Some(s))) => string_to_timestamp_nanos(s).ok(),
+        _ => None,
+    }
+}
+
+/// Describes how the output of a [`ResultCacheExec`] is stored.
+#[derive(Debug)]
+enum ResultSink {
+    /// Store the entire output under a single key.
+    Whole { key: ResultCacheKey, input: u64 },
+
+    /// Split the output into time buckets and store the given ones.
+    Buckets {
+        query: Arc<str>,
+
+        /// Index of the binned time column.
+        column: usize,
+
+        grid: BucketGrid,
+
+        /// Input fingerprint for every bucket that shall be stored.
+        inputs: BTreeMap<i64, u64>,
+    },
+}
+
+impl ResultSink {
+    fn store(
+        &self,
+        cache: &dyn QueryResultCache,
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> Result<()> {
+        match self {
+            Self::Whole { key, input } => {
+                cache.put(
+                    key.clone(),
+                    Arc::new(CachedResult {
+                        input: *input,
+                        schema,
+                        batches,
+                    }),
+                );
+            }
+            Self::Buckets {
+                query,
+                column,
+                grid,
+                inputs,
+            } => {
+                let mut bucket_batches: BTreeMap<i64, Vec<RecordBatch>> =
+                    inputs.keys().map(|bucket| (*bucket, vec![])).collect();
+
+                for batch in batches {
+                    let times = batch
+                        .column(*column)
+                        .as_any()
+                        .downcast_ref::<TimestampNanosecondArray>()
+                        .ok_or_else(|| {
+                            DataFusionError::Internal(
+                                "binned time column is not a nanosecond timestamp".to_string(),
+                            )
+                        })?;
+
+                    let mut indices: BTreeMap<i64, Vec<u32>> = BTreeMap::new();
+                    for (row, t) in times.iter().enumerate() {
+                        let Some(t) = t else { continue };
+                        let bucket = grid.bucket_start(t);
+                        if bucket_batches.contains_key(&bucket) {
+                            indices.entry(bucket).or_default().push(row as u32);
+                        }
+                    }
+
+                    for (bucket, indices) in indices {
+                        let indices = UInt32Array::from(indices);
+                        let columns = batch
+                            .columns()
+                            .iter()
+                            .map(|array| take(array.as_ref(), &indices, None))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        bucket_batches
+                            .get_mut(&bucket)
+                            .expect("checked above")
+                            .push(RecordBatch::try_new(batch.schema(), columns)?);
+                    }
+                }
+
+                for (bucket, batches) in bucket_batches {
+                    cache.put(
+                        ResultCacheKey {
+                            query: Arc::clone(query),
+                            bucket: Some(bucket),
+                        },
+                        Arc::new(CachedResult {
+                            input: inputs[&bucket],
+                            schema: Arc::clone(&schema),
+                            batches,
+                        }),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Passes through the output of its input and stores it in a [`QueryResultCache`] once the input is exhausted.
+struct ResultCacheExec {
+    input: Arc<dyn ExecutionPlan>,
+    cache: Arc<dyn QueryResultCache>,
+    sink: Arc<ResultSink>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ResultCacheExec {
+    fn new_plan(
+        input: Arc<dyn ExecutionPlan>,
+        cache: Arc<dyn QueryResultCache>,
+        sink: ResultSink,
+    ) -> Arc<dyn ExecutionPlan> {
+        let input = if input.output_partitioning().partition_count() == 1 {
+            input
+        } else {
+            Arc::new(CoalescePartitionsExec::new(input))
+        };
+
+        Arc::new(Self {
+            input,
+            cache,
+            sink: Arc::new(sink),
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl Debug for ResultCacheExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ResultCacheExec")
+    }
+}
+
+impl ExecutionPlan for ResultCacheExec {
+    fn as_any(&self) -> &(dyn std::any::Any + 'static) {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self {
+                input: Arc::clone(&children[0]),
+                cache: Arc::clone(&self.cache),
+                sink: Arc::clone(&self.sink),
+                metrics: ExecutionPlanMetricsSet::new(),
+            })),
+            _ => Err(DataFusionError::Internal(
+                "ResultCacheExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "ResultCacheExec invalid partition {partition}"
+            )));
+        }
+
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let input = self.input.execute(0, context)?;
+        Ok(Box::pin(ResultCacheStream {
+            input,
+            cache: Arc::clone(&self.cache),
+            sink: Arc::clone(&self.sink),
+            collected: Some(vec![]),
+            collected_bytes: 0,
+            baseline_metrics,
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "ResultCacheExec"),
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+struct ResultCacheStream {
+    input: SendableRecordBatchStream,
+    cache: Arc<dyn QueryResultCache>,
+    sink: Arc<ResultSink>,
+
+    /// Output collected so far, `None` if the result will not be cached.
+    collected: Option<Vec<RecordBatch>>,
+    collected_bytes: usize,
+
+    baseline_metrics: BaselineMetrics,
+}
+
+impl Stream for ResultCacheStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let res = ready!(this.input.poll_next_unpin(cx));
+
+        match &res {
+            Some(Ok(batch)) => {
+                if let Some(collected) = this.collected.as_mut() {
+                    this.collected_bytes += batch.get_array_memory_size();
+                    if this.collected_bytes > this.cache.max_entry_bytes() {
+                        this.collected = None;
+                    } else {
+                        collected.push(batch.clone());
+                    }
+                }
+            }
+            Some(Err(_)) => {
+                this.collected = None;
+            }
+            None => {
+                if let Some(batches) = this.collected.take() {
+                    let _timer = this.baseline_metrics.elapsed_compute().timer();
+                    if let Err(e) =
+                        this.sink
+                            .store(this.cache.as_ref(), this.input.schema(), batches)
+                    {
+                        debug!(%e, "cannot store query result");
+                    }
+                }
+            }
+        }
+
+        this.baseline_metrics.record_poll(Poll::Ready(res))
+    }
+}
+
+impl RecordBatchStream for ResultCacheStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{provider::chunks_to_physical_nodes, test::TestChunk, QueryChunkMeta};
+    use arrow::{
+        array::{ArrayRef, Int64Array},
+        datatypes::{DataType, Field, Schema, TimeUnit},
+    };
+    use chrono::{TimeZone, Utc};
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Default)]
+    struct TestCache {
+        entries: Mutex<HashMap<ResultCacheKey, Arc<CachedResult>>>,
+    }
+
+    impl QueryResultCache for TestCache {
+        fn get(&self, key: &ResultCacheKey) -> Option<Arc<CachedResult>> {
+            self.entries.lock().get(key).cloned()
+        }
+
+        fn put(&self, key: ResultCacheKey, result: Arc<CachedResult>) {
+            self.entries.lock().insert(key, result);
+        }
+
+        fn bucket_width(&self) -> Option<Duration> {
+            Some(Duration::from_nanos(10))
+        }
+
+        fn max_entry_bytes(&self) -> usize {
+            usize::MAX
+        }
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!(normalize_query("SELECT 1"), "SELECT 1");
+        assert_eq!(
+            normalize_query("  SELECT\n\t*   FROM cpu ;\n"),
+            "SELECT * FROM cpu"
+        );
+        assert_eq!(
+            normalize_query("SELECT * FROM cpu WHERE host = 'a   b' AND \"my  col\" > 1;;"),
+            "SELECT * FROM cpu WHERE host = 'a   b' AND \"my  col\" > 1"
+        );
+        assert_eq!(
+            normalize_query("SELECT 'unterminated ;"),
+            "SELECT 'unterminated ;"
+        );
+    }
+
+    #[test]
+    fn test_bucket_grid() {
+        let grid = BucketGrid {
+            origin: 5,
+            width: 10,
+        };
+        assert_eq!(grid.bucket_start(5), 5);
+        assert_eq!(grid.bucket_start(14), 5);
+        assert_eq!(grid.bucket_start(15), 15);
+        assert_eq!(grid.bucket_start(4), -5);
+        assert_eq!(grid.bucket_start(-5), -5);
+        assert_eq!(grid.bucket_start(-6), -15);
+    }
+
+    #[test]
+    fn test_input_fingerprint() {
+        let chunk_1 = chunk(1, 1, 0, 10);
+        let chunk_2 = chunk(2, 2, 20, 30);
+
+        let fp_both = fingerprint(&[&chunk_1, &chunk_2], None).unwrap();
+        assert_eq!(fp_both, fingerprint(&[&chunk_2, &chunk_1], None).unwrap());
+        assert_ne!(fp_both, fingerprint(&[&chunk_1], None).unwrap());
+
+        // changed data
+        let chunk_2b = chunk(2, 3, 20, 30);
+        assert_ne!(fp_both, fingerprint(&[&chunk_1, &chunk_2b], None).unwrap());
+
+        // time range only considers overlapping chunks
+        let range = TimestampRange::new(0, 11);
+        assert_eq!(
+            fingerprint(&[&chunk_1, &chunk_2], Some(range)).unwrap(),
+            fingerprint(&[&chunk_1, &chunk_2b], Some(range)).unwrap(),
+        );
+
+        // chunks without fingerprint make the input opaque
+        let opaque = Arc::new(
+            TestChunk::new("t")
+                .with_id(3)
+                .with_time_column_with_full_stats(Some(0), Some(10), 1, None),
+        );
+        assert_eq!(fingerprint(&[&chunk_1, &opaque], None), None);
+    }
+
+    #[test]
+    fn test_store_whole() {
+        let cache = TestCache::default();
+        let key = ResultCacheKey {
+            query: Arc::from("SELECT 1"),
+            bucket: None,
+        };
+        let sink = ResultSink::Whole {
+            key: key.clone(),
+            input: 42,
+        };
+
+        let batch = time_batch(&[1, 2]);
+        sink.store(&cache, batch.schema(), vec![batch.clone()])
+            .unwrap();
+
+        let cached = cache.get(&key).unwrap();
+        assert!(cached.is_valid(42, &batch.schema()));
+        assert!(!cached.is_valid(43, &batch.schema()));
+        assert_eq!(cached.batches, vec![batch]);
+    }
+
+    #[test]
+    fn test_store_buckets() {
+        let cache = TestCache::default();
+        let query: Arc<str> = Arc::from("q");
+        let sink = ResultSink::Buckets {
+            query: Arc::clone(&query),
+            column: 0,
+            grid: BucketGrid {
+                origin: 0,
+                width: 10,
+            },
+            inputs: BTreeMap::from([(0, 1), (10, 2), (20, 3)]),
+        };
+
+        let batch = time_batch(&[1, 12, 5, 31, 15]);
+        let schema = batch.schema();
+        sink.store(&cache, Arc::clone(&schema), vec![batch])
+            .unwrap();
+
+        let get = |bucket: i64| {
+            let cached = cache
+                .get(&ResultCacheKey {
+                    query: Arc::clone(&query),
+                    bucket: Some(bucket),
+                })
+                .unwrap();
+            let times: Vec<i64> = cached
+                .batches
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<TimestampNanosecondArray>()
+                        .unwrap()
+                        .values()
+                        .to_vec()
+                })
+                .collect();
+            (cached.input, times)
+        };
+        assert_eq!(get(0), (1, vec![1, 5]));
+        assert_eq!(get(10), (2, vec![12, 15]));
+        // closed buckets without rows are stored as well
+        assert_eq!(get(20), (3, vec![]));
+        // buckets that were not requested are not stored
+        assert!(cache
+            .get(&ResultCacheKey {
+                query,
+                bucket: Some(30),
+            })
+            .is_none());
+    }
+
+    #[test]
+    fn test_bucketed_query() {
+        let schema = Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("v", DataType::Int64, true),
+        ]);
+        let scan = datafusion::logical_expr::logical_plan::table_scan(Some("t"), &schema, None)
+            .unwrap()
+            .build()
+            .unwrap();
+        let time = Expr::Column(Column::from_qualified_name("t.time"));
+        let date_bin = Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::DateBin,
+            args: vec![
+                Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(
+                    IntervalMonthDayNanoType::make_value(0, 0, 10),
+                ))),
+                time.clone(),
+            ],
+        };
+        let plan = datafusion::logical_expr::LogicalPlanBuilder::from(scan)
+            .filter(
+                time.clone()
+                    .gt_eq(lit_timestamp_nano(5))
+                    .and(time.lt(lit_timestamp_nano(100))),
+            )
+            .unwrap()
+            .aggregate(
+                vec![date_bin.alias("bin")],
+                vec![datafusion::prelude::count(datafusion::prelude::col("v"))],
+            )
+            .unwrap()
+            .project(vec![datafusion::prelude::col("bin")])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut props = ExecutionProps::new();
+        props.query_execution_start_time = Utc.timestamp_nanos(47);
+
+        // stride must divide the bucket width
+        assert!(
+            BucketedQuery::try_new(&plan, Duration::from_nanos(15), &props)
+                .unwrap()
+                .is_none()
+        );
+
+        let bucketed = BucketedQuery::try_new(&plan, Duration::from_nanos(20), &props)
+            .unwrap()
+            .unwrap();
+        assert_eq!(bucketed.output_column, 0);
+        assert_eq!(bucketed.range, (5, 100));
+        assert_eq!(bucketed.now, 47);
+        // [0, 20) is not fully within the range, [40, 60) is still open
+        assert_eq!(bucketed.closed_buckets(), vec![20]);
+
+        // plans of other shapes are not bucketed
+        let LogicalPlan::Projection(projection) = &plan else {
+            unreachable!()
+        };
+        assert!(BucketedQuery::try_new(
+            projection.input.as_ref(),
+            Duration::from_nanos(20),
+            &props
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    fn chunk(id: u128, fingerprint: u64, min: i64, max: i64) -> Arc<TestChunk> {
+        Arc::new(
+            TestChunk::new("t")
+                .with_id(id)
+                .with_time_column_with_full_stats(Some(min), Some(max), 1, None)
+                .with_data_fingerprint(fingerprint),
+        )
+    }
+
+    fn fingerprint(chunks: &[&Arc<TestChunk>], range: Option<TimestampRange>) -> Option<u64> {
+        let schema = chunks[0].schema().as_arrow();
+        let chunks = chunks
+            .iter()
+            .map(|c| Arc::clone(c) as Arc<dyn QueryChunk>)
+            .collect();
+        let plan = chunks_to_physical_nodes(&schema, None, chunks, 2);
+        input_fingerprint(plan.as_ref(), range)
+    }
+
+    fn time_batch(times: &[i64]) -> RecordBatch {
+        let times: ArrayRef = Arc::new(TimestampNanosecondArray::from(times.to_vec()));
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(0..times.len() as i64));
+        RecordBatch::try_from_iter([("time", times), ("v", values)]).unwrap()
+    }
+}
@@ -21,8 +21,8 @@ use arrow::{
 };
 use async_trait::async_trait;
 use data_types::{
-    ChunkId, ChunkOrder, ColumnSummary, DeletePredicate, InfluxDbType, PartitionId, StatValues,
-    Statistics, TableSummary,
+    ChunkId, ChunkOrder, ColumnSummary, DeletePredicate, InfluxDbType, PartitionId, QueryPriority,
+    StatValues, Statistics, TableSummary,
 };
 use datafusion::datasource::{object_store::ObjectStoreUrl, TableProvider, TableType};
 use datafusion::error::DataFusionError;
@@ -147,8 +147,12 @@ impl QueryNamespace for TestDatabase {
         _ctx: &IOxSessionContext,
         _query_type: &str,
         _query_text: QueryText,
-    ) -> QueryCompletedToken {
-        QueryCompletedToken::new(|_| {})
+    ) -> Result<QueryCompletedToken, DataFusionError> {
+        Ok(QueryCompletedToken::new(|_| {}))
+    }
+
+    fn cancel_query(&self, _query_id: u64) -> bool {
+        false
     }
 
     fn as_meta(&self) -> &dyn QueryNamespaceMeta {
@@ -188,10 +192,14 @@ impl QueryNamespaceMeta for TestDatabase {
 }
 
 impl ExecutionContextProvider for TestDatabase {
-    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+    fn new_query_context_with_priority(
+        &self,
+        span_ctx: Option<SpanContext>,
+        priority: Option<QueryPriority>,
+    ) -> IOxSessionContext {
         // Note: unlike Db this does not register a catalog provider
         self.executor
-            .new_execution_config(ExecutorType::Query)
+            .new_execution_config(ExecutorType::PriorityQuery(priority.unwrap_or_default()))
             .with_default_catalog(Arc::new(TestDatabaseCatalogProvider::from_test_database(
                 self,
             )))
@@ -348,6 +356,9 @@ pub struct TestChunk {
 
     /// Suppress output
     quiet: bool,
+
+    /// Return value for data_fingerprint()
+    data_fingerprint: Option<u64>,
 }
 
 /// Implements a method for adding a column with default stats
@@ -430,6 +441,7 @@ impl TestChunk {
             partition_sort_key: None,
             partition_id: PartitionId::new(0),
             quiet: false,
+            data_fingerprint: None,
         }
     }
 
@@ -489,6 +501,13 @@ impl TestChunk {
         self
     }
 
+    pub fn with_data_fingerprint(self, data_fingerprint: u64) -> Self {
+        Self {
+            data_fingerprint: Some(data_fingerprint),
+            ..self
+        }
+    }
+
     pub fn with_id(mut self, id: u128) -> Self {
         self.id = ChunkId::new_test(id);
 
@@ -1186,6 +1205,10 @@ impl QueryChunk for TestChunk {
         "Test Chunk"
     }
 
+    fn data_fingerprint(&self) -> Option<u64> {
+        self.data_fingerprint
+    }
+
     fn apply_predicate_to_metadata(
         &self,
         _ctx: &IOxSessionContext,
@@ -0,0 +1,251 @@
+//! Detection of `SELECT` statements that can be answered from the last value
+//! cache.
+//!
+//! A statement qualifies if its result depends only on the most recent value
+//! of each field for every series. This holds for statements that:
+//!
+//! * only project `last()` selectors of fields, and tags,
+//! * do not group by time, or select from subqueries,
+//! * only filter on tags, and on lower bounds of `time`.
+//!
+//! A condition built from such predicates with `AND` and `OR` holds for a
+//! row of a series if it holds for any older row of the same series. The most
+//! recent value that matches the condition is therefore always the most
+//! recent value of the series, if any value matches at all.
+
+use influxdb_influxql_parser::{
+    expression::{
+        arithmetic::Expr, conditional::ConditionalExpression, ConditionalOperator, VarRef,
+        VarRefDataType, WildcardType,
+    },
+    literal::Literal,
+    select::{Dimension, MeasurementSelection, SelectStatement},
+};
+use schema::{InfluxColumnType, Schema};
+
+/// Returns `true` if the result of `select` over the tables described by
+/// `schemas` only depends on the most recent row of each series.
+pub(super) fn is_last_value_query<'a>(
+    select: &SelectStatement,
+    schemas: impl IntoIterator<Item = &'a Schema>,
+) -> bool {
+    let schemas: Vec<_> = schemas.into_iter().collect();
+    let columns = Columns(&schemas);
+
+    if select
+        .from
+        .iter()
+        .any(|m| matches!(m, MeasurementSelection::Subquery(_)))
+    {
+        return false;
+    }
+
+    if let Some(group_by) = &select.group_by {
+        if group_by.iter().any(|d| matches!(d, Dimension::Time(_))) {
+            return false;
+        }
+    }
+
+    let mut has_selector = false;
+    for field in select.fields.iter() {
+        match &field.expr {
+            Expr::Call(call) if call.name == "last" && call.args.len() == 1 => {
+                if !columns.is_field_selection(&call.args[0]) {
+                    return false;
+                }
+                has_selector = true;
+            }
+            Expr::VarRef(v) if columns.is_tag(v) => {}
+            _ => return false,
+        }
+    }
+
+    has_selector
+        && select
+            .condition
+            .as_ref()
+            .map_or(true, |cond| columns.is_monotonic_condition(cond))
+}
+
+/// Classifies column references against the schemas of all queried tables.
+struct Columns<'a>(&'a [&'a Schema]);
+
+impl<'a> Columns<'a> {
+    fn column_type(&self, name: &str) -> impl Iterator<Item = InfluxColumnType> + '_ {
+        let name = name.to_owned();
+        self.0
+            .iter()
+            .filter_map(move |s| s.field_by_name(&name).map(|(t, _)| t))
+    }
+
+    /// A reference to a tag, or to a column that is not a field of any table
+    /// (and therefore always `NULL`).
+    fn is_tag(&self, v: &VarRef) -> bool {
+        matches!(v.data_type, None | Some(VarRefDataType::Tag))
+            && !is_time(v)
+            && self
+                .column_type(&v.name)
+                .all(|t| matches!(t, InfluxColumnType::Tag))
+    }
+
+    /// The argument of a `last()` call, selecting one or more fields.
+    fn is_field_selection(&self, arg: &Expr) -> bool {
+        match arg {
+            Expr::VarRef(v) => {
+                v.data_type != Some(VarRefDataType::Tag)
+                    && !is_time(v)
+                    && self
+                        .column_type(&v.name)
+                        .all(|t| matches!(t, InfluxColumnType::Field(_)))
+            }
+            Expr::Wildcard(None | Some(WildcardType::Field)) => true,
+            Expr::Literal(Literal::Regex(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// A condition that, if it holds for a row of a series, also holds for
+    /// every more recent row of that series.
+    fn is_monotonic_condition(&self, cond: &ConditionalExpression) -> bool {
+        match cond {
+            ConditionalExpression::Grouped(inner) => self.is_monotonic_condition(inner),
+            ConditionalExpression::Binary(b) => match b.op {
+                ConditionalOperator::And | ConditionalOperator::Or => {
+                    self.is_monotonic_condition(&b.lhs) && self.is_monotonic_condition(&b.rhs)
+                }
+                op => {
+                    let (Some(lhs), Some(rhs)) = (b.lhs.expr(), b.rhs.expr()) else {
+                        return false;
+                    };
+                    self.is_monotonic_comparison(lhs, op, rhs)
+                }
+            },
+            ConditionalExpression::Expr(_) => false,
+        }
+    }
+
+    fn is_monotonic_comparison(&self, lhs: &Expr, op: ConditionalOperator, rhs: &Expr) -> bool {
+        use ConditionalOperator::*;
+
+        match (unnest(lhs), op, unnest(rhs)) {
+            // time > <constant>, <constant> < time
+            (Expr::VarRef(v), Gt | GtEq, c) | (c, Lt | LtEq, Expr::VarRef(v))
+                if is_time(v) && is_constant(c) =>
+            {
+                true
+            }
+            // Any other reference to time is an upper bound or equality.
+            (Expr::VarRef(v), _, _) | (_, _, Expr::VarRef(v)) if is_time(v) => false,
+            // <tag> <op> <constant>
+            (Expr::VarRef(v), Eq | NotEq | EqRegex | NotEqRegex | Lt | LtEq | Gt | GtEq, c)
+            | (c, Eq | NotEq | EqRegex | NotEqRegex | Lt | LtEq | Gt | GtEq, Expr::VarRef(v)) => {
+                self.is_tag(v) && is_constant(c)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn unnest(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Nested(inner) => unnest(inner),
+        e => e,
+    }
+}
+
+fn is_time(v: &VarRef) -> bool {
+    v.name.eq_ignore_ascii_case("time")
+}
+
+/// An expression that does not depend on the row it is evaluated against.
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) | Expr::BindParameter(_) => true,
+        Expr::Call(call) => call.args.iter().all(is_constant),
+        Expr::Binary(b) => is_constant(&b.lhs) && is_constant(&b.rhs),
+        Expr::Nested(inner) => is_constant(inner),
+        Expr::VarRef(_) | Expr::Wildcard(_) | Expr::Distinct(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use influxdb_influxql_parser::{parse_statements, statement::Statement};
+    use schema::{builder::SchemaBuilder, InfluxFieldType};
+
+    fn qualifies(q: &str) -> bool {
+        let schema = SchemaBuilder::new()
+            .tag("host")
+            .tag("region")
+            .influx_field("usage", InfluxFieldType::Float)
+            .influx_field("status", InfluxFieldType::String)
+            .timestamp()
+            .build()
+            .unwrap();
+
+        let mut statements = parse_statements(q).unwrap();
+        let Statement::Select(select) = statements.pop().unwrap() else {
+            panic!("expected SELECT statement");
+        };
+        is_last_value_query(&select, [&schema])
+    }
+
+    #[test]
+    fn test_qualifying_queries() {
+        assert!(qualifies("SELECT last(usage) FROM cpu"));
+        assert!(qualifies("SELECT last(*) FROM cpu"));
+        assert!(qualifies("SELECT last(/us/) FROM cpu"));
+        assert!(qualifies("SELECT last(usage), host FROM cpu"));
+        assert!(qualifies(
+            "SELECT last(usage), last(status) FROM cpu GROUP BY host"
+        ));
+        assert!(qualifies("SELECT last(usage) FROM cpu GROUP BY *"));
+        assert!(qualifies(
+            "SELECT last(usage) FROM cpu WHERE host = 'a' OR region =~ /eu/"
+        ));
+        assert!(qualifies(
+            "SELECT last(usage) FROM cpu WHERE time > now() - 1h AND (host != 'a')"
+        ));
+        assert!(qualifies(
+            "SELECT last(usage) FROM cpu WHERE '2023-01-01T00:00:00Z' <= time"
+        ));
+        assert!(qualifies("SELECT last(usage) FROM cpu LIMIT 1 SLIMIT 2"));
+    }
+
+    #[test]
+    fn test_non_qualifying_queries() {
+        // Other aggregates, selectors and raw projections
+        assert!(!qualifies("SELECT usage FROM cpu"));
+        assert!(!qualifies("SELECT host FROM cpu"));
+        assert!(!qualifies("SELECT first(usage) FROM cpu"));
+        assert!(!qualifies("SELECT last(usage), count(usage) FROM cpu"));
+        assert!(!qualifies("SELECT last(usage) + 1 FROM cpu"));
+        assert!(!qualifies("SELECT last(host) FROM cpu"));
+        assert!(!qualifies("SELECT last(usage), usage FROM cpu"));
+
+        // Time grouping and subqueries
+        assert!(!qualifies("SELECT last(usage) FROM cpu GROUP BY time(1m)"));
+        assert!(!qualifies(
+            "SELECT last(usage) FROM (SELECT last(usage) FROM cpu)"
+        ));
+
+        // Upper time bounds and field predicates
+        assert!(!qualifies(
+            "SELECT last(usage) FROM cpu WHERE time < now() - 1h"
+        ));
+        assert!(!qualifies(
+            "SELECT last(usage) FROM cpu WHERE time = '2023-01-01T00:00:00Z'"
+        ));
+        assert!(!qualifies(
+            "SELECT last(usage) FROM cpu WHERE time > now() - 1h AND time < now()"
+        ));
+        assert!(!qualifies("SELECT last(usage) FROM cpu WHERE usage > 5"));
+        assert!(!qualifies(
+            "SELECT last(usage) FROM cpu WHERE host = 'a' OR status = 'ok'"
+        ));
+        assert!(!qualifies(
+            "SELECT last(usage) FROM cpu WHERE host = region"
+        ));
+    }
+}
@@ -1 +1,2 @@
+mod last_value;
 pub mod planner;
@@ -10,22 +10,27 @@ use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::Arc;
 
+use super::last_value::is_last_value_query;
 use crate::plan::{parse_regex, InfluxQLToLogicalPlan, SchemaProvider};
 use datafusion::common::Statistics;
 use datafusion::datasource::provider_as_source;
 use datafusion::execution::context::{SessionState, TaskContext};
 use datafusion::logical_expr::{AggregateUDF, LogicalPlan, ScalarUDF, TableSource};
 use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::{Partitioning, SendableRecordBatchStream};
 use datafusion::{
     error::{DataFusionError, Result},
     physical_plan::ExecutionPlan,
 };
 use influxdb_influxql_parser::common::MeasurementName;
+use influxdb_influxql_parser::kill::KillQueryStatement;
 use influxdb_influxql_parser::parse_statements;
 use influxdb_influxql_parser::statement::Statement;
 use influxdb_influxql_parser::visit::{Visitable, Visitor};
 use iox_query::exec::IOxSessionContext;
+use iox_query::provider::ProviderBuilder;
+use iox_query::QueryNamespace;
 use observability_deps::tracing::debug;
 use schema::Schema;
 
@@ -129,15 +134,25 @@ impl InfluxQLQueryPlanner {
 
     /// Plan an InfluxQL query against the catalogs registered with `ctx`, and return a
     /// DataFusion physical execution plan that runs on the query executor.
+    ///
+    /// Statements that act on the `namespace` rather than reading data, such as
+    /// `KILL QUERY`, are executed during planning and return an empty plan.
     pub async fn query(
         &self,
         query: &str,
+        namespace: Arc<dyn QueryNamespace>,
         ctx: &IOxSessionContext,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         debug!(text=%query, "planning InfluxQL query");
 
         let statement = self.query_to_statement(query)?;
-        let logical_plan = self.statement_to_plan(statement, ctx).await?;
+        if let Statement::KillQuery(kill_query) = statement {
+            return self.kill_query(*kill_query, namespace.as_ref());
+        }
+
+        let logical_plan = self
+            .statement_to_plan(statement, namespace.as_ref(), ctx)
+            .await?;
 
         let input = ctx.create_physical_plan(&logical_plan).await?;
 
@@ -155,9 +170,30 @@ impl InfluxQLQueryPlanner {
         Ok(Arc::new(SchemaExec { input, schema }))
     }
 
+    fn kill_query(
+        &self,
+        kill_query: KillQueryStatement,
+        namespace: &dyn QueryNamespace,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        debug!(id = kill_query.id, "killing query");
+
+        if !namespace.cancel_query(kill_query.id) {
+            return Err(DataFusionError::Plan(format!(
+                "no such query id: {}",
+                kill_query.id
+            )));
+        }
+
+        Ok(Arc::new(EmptyExec::new(
+            false,
+            Arc::new(arrow::datatypes::Schema::empty()),
+        )))
+    }
+
     async fn statement_to_plan(
         &self,
         statement: Statement,
+        namespace: &dyn QueryNamespace,
         ctx: &IOxSessionContext,
     ) -> Result<LogicalPlan> {
         use std::collections::hash_map::Entry;
@@ -200,6 +236,30 @@ impl InfluxQLQueryPlanner {
             }
         }
 
+        // Answer selections of the most recent values from the last value
+        // cache of the tables that provably hold them.
+        if let Statement::Select(select) = &statement {
+            if is_last_value_query(select, sp.tables.values().map(|(_, schema)| schema)) {
+                for (table_name, (source, schema)) in sp.tables.iter_mut() {
+                    let Some(chunks) = namespace
+                        .last_value_chunks(table_name, ctx.child_ctx("last_value_chunks"))
+                        .await? else {
+                            continue;
+                        };
+
+                    debug!(%table_name, n_chunks=chunks.len(), "using last value cache");
+                    let provider = chunks
+                        .into_iter()
+                        .fold(
+                            ProviderBuilder::new(Arc::from(table_name.as_str()), schema.clone()),
+                            |builder, chunk| builder.add_chunk(chunk),
+                        )
+                        .build()?;
+                    *source = provider_as_source(Arc::new(provider));
+                }
+            }
+        }
+
         let planner = InfluxQLToLogicalPlan::new(&sp, ctx);
         let logical_plan = planner.statement_to_plan(statement)?;
         debug!(plan=%logical_plan.display_graphviz(), "logical plan");
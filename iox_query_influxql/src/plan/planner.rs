@@ -10,7 +10,7 @@ use crate::plan::planner_time_range_expression::{
 use crate::plan::rewriter::{
     rewrite_statement, select_statement_info, ProjectionType, SelectStatementInfo,
 };
-use crate::plan::util::{binary_operator_to_df_operator, rebase_expr, Schemas};
+use crate::plan::util::{binary_operator_to_df_operator, number_to_scalar, rebase_expr, Schemas};
 use crate::plan::var_ref::{column_type_to_var_ref_data_type, var_ref_data_type_to_data_type};
 use crate::plan::{error, planner_rewrite_expression};
 use arrow::array::{StringBuilder, StringDictionaryBuilder};
@@ -208,6 +208,9 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
             Statement::Delete(_) => error::not_implemented("DELETE"),
             Statement::DropMeasurement(_) => error::not_implemented("DROP MEASUREMENT"),
             Statement::Explain(explain) => self.explain_statement_to_plan(*explain),
+            Statement::KillQuery(_) => {
+                error::internal("KILL QUERY must be handled by the InfluxQL frontend")
+            }
             Statement::Select(select) => {
                 self.select_statement_to_plan(&self.rewrite_select_statement(*select)?)
             }
@@ -660,9 +663,9 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
         // * `literal` value
         // * `linear`
         //
-        let plan = if ctx.group_by.and_then(|gb| gb.time_dimension()).is_some()
-            && fill_option != FillClause::None
-        {
+        let is_gap_filled = ctx.group_by.and_then(|gb| gb.time_dimension()).is_some()
+            && fill_option != FillClause::None;
+        let plan = if is_gap_filled {
             let args = match select_exprs[time_column_index].clone().unalias() {
                 Expr::ScalarFunction {
                     fun: BuiltinScalarFunction::DateBin,
@@ -675,14 +678,7 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
                 }
             };
 
-            let fill_strategy = match fill_option {
-                FillClause::Null | FillClause::Value(_) => FillStrategy::Null,
-                FillClause::Previous => FillStrategy::PrevNullAsMissing,
-                FillClause::Linear => FillStrategy::LinearInterpolate,
-                FillClause::None => unreachable!(),
-            };
-
-            build_gap_fill_node(plan, time_column_index, args, fill_strategy)?
+            build_gap_fill_node(plan, time_column_index, args, fill_option)?
         } else {
             plan
         };
@@ -699,9 +695,10 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
             .collect::<Result<Vec<Expr>>>()?;
 
         // Create a literal expression for `value` if the strategy
-        // is `FILL(<value>)`
+        // is `FILL(<value>)` and the GapFill operator has not already
+        // filled the aggregate columns with `value`.
         let fill_if_null = match fill_option {
-            FillClause::Value(v) => Some(v),
+            FillClause::Value(v) if !is_gap_filled => Some(v),
             _ => None,
         };
 
@@ -1928,12 +1925,12 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
 /// * `input` - An aggregate plan which requires gap-filling.
 /// * `date_bin_index` - The index of the field in the input schema that refers to the `date_bin` expression.
 /// * `date_bin_args` - The list of arguments passed to the `date_bin` function, used to configure the gap-fill parameters.
-/// * `fill_strategy` - The strategy used to fill gaps in the data.
+/// * `fill_clause` - The `FILL` option, which determines how gaps in the data are filled.
 fn build_gap_fill_node(
     input: LogicalPlan,
     date_bin_index: usize,
     date_bin_args: Vec<Expr>,
-    fill_strategy: FillStrategy,
+    fill_clause: FillClause,
 ) -> Result<LogicalPlan> {
     // Extract the gap-fill parameters from the arguments to the `DATE_BIN` function.
     // Any unexpected conditions represents an internal error, as the `DATE_BIN` function is
@@ -1980,12 +1977,27 @@ fn build_gap_fill_node(
         .collect();
     let aggr_expr = new_group_expr.split_off(aggr.group_expr.len());
 
-    // The fill strategy for InfluxQL is specified at the query level
+    // The fill strategy for InfluxQL is specified at the query level,
+    // with any `FILL(<value>)` coerced to the type of each aggregate column.
     let fill_strategy = aggr_expr
         .iter()
         .cloned()
-        .map(|e| (e, fill_strategy.clone()))
-        .collect();
+        .map(|e| {
+            let fs = match fill_clause {
+                FillClause::Null => FillStrategy::Null,
+                FillClause::Value(v) => {
+                    let data_type = e.get_type(input.schema())?;
+                    FillStrategy::Default(number_to_scalar(&v, &data_type)?)
+                }
+                FillClause::Previous => FillStrategy::PrevNullAsMissing,
+                FillClause::Linear => FillStrategy::LinearInterpolate,
+                FillClause::None => {
+                    return error::internal("FILL(none) does not require gap filling")
+                }
+            };
+            Ok((e, fs))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     let time_column = col(input.schema().fields()[date_bin_index].qualified_column());
 
@@ -2761,8 +2773,8 @@ mod test {
                 // aggregate query, grouping by time with gap filling
                 assert_snapshot!(plan("SELECT FIRST(usage_idle) FROM cpu GROUP BY TIME(5s) FILL(0)"), @r###"
                 Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, first:Float64;N]
-                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, coalesce(selector_first_value(cpu.usage_idle,cpu.time), Float64(0)) AS first [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, first:Float64;N]
-                    GapFill: groupBy=[[time]], aggr=[[selector_first_value(cpu.usage_idle,cpu.time)]], time_column=time, stride=IntervalMonthDayNano("5000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, selector_first_value(cpu.usage_idle,cpu.time):Float64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, selector_first_value(cpu.usage_idle,cpu.time) AS first [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, first:Float64;N]
+                    GapFill: groupBy=[[time]], aggr=[[FILL(selector_first_value(cpu.usage_idle,cpu.time), Float64(0))]], time_column=time, stride=IntervalMonthDayNano("5000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, selector_first_value(cpu.usage_idle,cpu.time):Float64;N]
                       Aggregate: groupBy=[[datebin(IntervalMonthDayNano("5000000000"), cpu.time, TimestampNanosecond(0, None)) AS time]], aggr=[[selector_first_value(cpu.usage_idle, cpu.time)]] [time:Timestamp(Nanosecond, None);N, selector_first_value(cpu.usage_idle,cpu.time):Float64;N]
                         TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
                 "###);
@@ -3413,8 +3425,8 @@ mod test {
                 "###);
                 assert_snapshot!(plan("SELECT COUNT(f64_field) FROM data GROUP BY TIME(10s) FILL(0)"), @r###"
                 Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count:Int64;N]
-                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, coalesce(COUNT(data.f64_field), Int64(0)) AS count [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count:Int64;N]
-                    GapFill: groupBy=[[time]], aggr=[[COUNT(data.f64_field)]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N]
+                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, COUNT(data.f64_field) AS count [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count:Int64;N]
+                    GapFill: groupBy=[[time]], aggr=[[FILL(COUNT(data.f64_field), Int64(0))]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N]
                       Aggregate: groupBy=[[datebin(IntervalMonthDayNano("10000000000"), data.time, TimestampNanosecond(0, None)) AS time]], aggr=[[COUNT(data.f64_field)]] [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N]
                         TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
                 "###);
@@ -3426,20 +3438,29 @@ mod test {
                         TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
                 "###);
 
-                // Coalesces the fill value, which is a float, to the matching type of a `COUNT` aggregate.
+                // Coerces the fill value, which is a float, to the matching type of a `COUNT` aggregate.
                 assert_snapshot!(plan("SELECT COUNT(f64_field) FROM data GROUP BY TIME(10s) FILL(3.2)"), @r###"
                 Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count:Int64;N]
-                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, coalesce(COUNT(data.f64_field), Int64(3)) AS count [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count:Int64;N]
-                    GapFill: groupBy=[[time]], aggr=[[COUNT(data.f64_field)]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N]
+                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, COUNT(data.f64_field) AS count [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count:Int64;N]
+                    GapFill: groupBy=[[time]], aggr=[[FILL(COUNT(data.f64_field), Int64(3))]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N]
                       Aggregate: groupBy=[[datebin(IntervalMonthDayNano("10000000000"), data.time, TimestampNanosecond(0, None)) AS time]], aggr=[[COUNT(data.f64_field)]] [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N]
                         TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
                 "###);
 
+                // Coerces the fill value to the zero value of string and boolean aggregates.
+                assert_snapshot!(plan("SELECT FIRST(str_field), LAST(bool_field) FROM data GROUP BY TIME(10s) FILL(1)"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, first:Utf8;N, last:Boolean;N]
+                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, selector_first_value(data.str_field,data.time) AS first, selector_last_value(data.bool_field,data.time) AS last [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, first:Utf8;N, last:Boolean;N]
+                    GapFill: groupBy=[[time]], aggr=[[FILL(selector_first_value(data.str_field,data.time), Utf8("")), FILL(selector_last_value(data.bool_field,data.time), Boolean(false))]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, selector_first_value(data.str_field,data.time):Utf8;N, selector_last_value(data.bool_field,data.time):Boolean;N]
+                      Aggregate: groupBy=[[datebin(IntervalMonthDayNano("10000000000"), data.time, TimestampNanosecond(0, None)) AS time]], aggr=[[selector_first_value(data.str_field, data.time), selector_last_value(data.bool_field, data.time)]] [time:Timestamp(Nanosecond, None);N, selector_first_value(data.str_field,data.time):Utf8;N, selector_last_value(data.bool_field,data.time):Boolean;N]
+                        TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                "###);
+
                 // Aggregates as part of a binary expression
                 assert_snapshot!(plan("SELECT COUNT(f64_field) + MEAN(f64_field) FROM data GROUP BY TIME(10s) FILL(3.2)"), @r###"
                 Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count_f64_field_mean_f64_field:Float64;N]
-                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, coalesce(COUNT(data.f64_field), Int64(3)) + coalesce(AVG(data.f64_field), Float64(3.2)) AS count_f64_field_mean_f64_field [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count_f64_field_mean_f64_field:Float64;N]
-                    GapFill: groupBy=[[time]], aggr=[[COUNT(data.f64_field), AVG(data.f64_field)]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N, AVG(data.f64_field):Float64;N]
+                  Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, time, COUNT(data.f64_field) + AVG(data.f64_field) AS count_f64_field_mean_f64_field [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, count_f64_field_mean_f64_field:Float64;N]
+                    GapFill: groupBy=[[time]], aggr=[[FILL(COUNT(data.f64_field), Int64(3)), FILL(AVG(data.f64_field), Float64(3.2))]], time_column=time, stride=IntervalMonthDayNano("10000000000"), range=Unbounded..Excluded(now()) [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N, AVG(data.f64_field):Float64;N]
                       Aggregate: groupBy=[[datebin(IntervalMonthDayNano("10000000000"), data.time, TimestampNanosecond(0, None)) AS time]], aggr=[[COUNT(data.f64_field), AVG(data.f64_field)]] [time:Timestamp(Nanosecond, None);N, COUNT(data.f64_field):Int64;N, AVG(data.f64_field):Float64;N]
                         TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
                 "###);
@@ -1,6 +1,6 @@
 use crate::plan::{error, util_copy};
 use arrow::datatypes::DataType;
-use datafusion::common::{DFSchema, DFSchemaRef, Result};
+use datafusion::common::{DFSchema, DFSchemaRef, Result, ScalarValue};
 use datafusion::logical_expr::utils::expr_as_column_expr;
 use datafusion::logical_expr::{coalesce, lit, Expr, ExprSchemable, LogicalPlan, Operator};
 use influxdb_influxql_parser::expression::BinaryOperator;
@@ -57,15 +57,26 @@ pub(crate) fn parse_regex(re: &Regex) -> Result<regex::Regex> {
 
 /// Returns `n` as a literal expression of the specified `data_type`.
 fn number_to_expr(n: &Number, data_type: DataType) -> Result<Expr> {
+    Ok(lit(number_to_scalar(n, &data_type)?))
+}
+
+/// Returns `n` as a scalar value of the specified `data_type`.
+///
+/// Following InfluxQL, numbers are truncated when converted to integers,
+/// and string and boolean values are filled with their zero values, as
+/// a number cannot be converted to either type.
+pub(in crate::plan) fn number_to_scalar(n: &Number, data_type: &DataType) -> Result<ScalarValue> {
     Ok(match (n, data_type) {
-        (Number::Integer(v), DataType::Int64) => lit(*v),
-        (Number::Integer(v), DataType::Float64) => lit(*v as f64),
-        (Number::Integer(v), DataType::UInt64) => lit(*v as u64),
-        (Number::Float(v), DataType::Int64) => lit(*v as i64),
-        (Number::Float(v), DataType::Float64) => lit(*v),
-        (Number::Float(v), DataType::UInt64) => lit(*v as u64),
+        (Number::Integer(v), DataType::Int64) => ScalarValue::from(*v),
+        (Number::Integer(v), DataType::Float64) => ScalarValue::from(*v as f64),
+        (Number::Integer(v), DataType::UInt64) => ScalarValue::from(*v as u64),
+        (Number::Float(v), DataType::Int64) => ScalarValue::from(*v as i64),
+        (Number::Float(v), DataType::Float64) => ScalarValue::from(*v),
+        (Number::Float(v), DataType::UInt64) => ScalarValue::from(*v as u64),
+        (_, DataType::Utf8) => ScalarValue::Utf8(Some(String::new())),
+        (_, DataType::Boolean) => ScalarValue::Boolean(Some(false)),
         (n, data_type) => {
-            // The only output data types expected are Int64, Float64 or UInt64
+            // The only output data types expected are Int64, Float64, UInt64, Utf8 or Boolean
             return error::internal(format!("no conversion from {n} to {data_type}"));
         }
     })
@@ -249,51 +249,55 @@ impl InfluxRpcPlanner {
         let table_predicates = rpc_predicate
             .table_predicates(namespace.as_meta())
             .context(CreatingPredicatesSnafu)?;
-        let tables: Vec<_> =
-            table_chunk_stream(Arc::clone(&namespace), false, &table_predicates, &ctx)
-                .try_filter_map(|(table_name, predicate, chunks)| async move {
-                    // Identify which chunks can answer from its metadata and then record its table,
-                    // and which chunks needs full plan and group them into their table
-                    let mut chunks_full = vec![];
-
-                    for chunk in cheap_chunk_first(chunks) {
-                        trace!(chunk_id=%chunk.id(), %table_name, "Considering table");
-
-                        // If the chunk has delete predicates, we need to scan (do full plan) the data to eliminate
-                        // deleted data before we can determine if its table participates in the requested predicate.
-                        if chunk.has_delete_predicates() {
+        let tables: Vec<_> = table_chunk_stream(
+            Arc::clone(&namespace),
+            false,
+            false,
+            &table_predicates,
+            &ctx,
+        )
+        .try_filter_map(|(table_name, predicate, chunks)| async move {
+            // Identify which chunks can answer from its metadata and then record its table,
+            // and which chunks needs full plan and group them into their table
+            let mut chunks_full = vec![];
+
+            for chunk in cheap_chunk_first(chunks) {
+                trace!(chunk_id=%chunk.id(), %table_name, "Considering table");
+
+                // If the chunk has delete predicates, we need to scan (do full plan) the data to eliminate
+                // deleted data before we can determine if its table participates in the requested predicate.
+                if chunk.has_delete_predicates() {
+                    chunks_full.push(chunk);
+                } else {
+                    // Try and apply the predicate using only metadata
+                    let pred_result = chunk
+                        .apply_predicate_to_metadata(metadata_ctx, predicate)
+                        .context(CheckingChunkPredicateSnafu {
+                            chunk_id: chunk.id(),
+                        })?;
+
+                    match pred_result {
+                        PredicateMatch::AtLeastOneNonNullField => {
+                            trace!("Metadata predicate: table matches");
+                            // Meta data of the table covers predicates of the request
+                            return Ok(Some((table_name, None)));
+                        }
+                        PredicateMatch::Unknown => {
+                            trace!("Metadata predicate: unknown match");
+                            // We cannot match the predicate to get answer from meta data, let do full plan
                             chunks_full.push(chunk);
-                        } else {
-                            // Try and apply the predicate using only metadata
-                            let pred_result = chunk
-                                .apply_predicate_to_metadata(metadata_ctx, predicate)
-                                .context(CheckingChunkPredicateSnafu {
-                                    chunk_id: chunk.id(),
-                                })?;
-
-                            match pred_result {
-                                PredicateMatch::AtLeastOneNonNullField => {
-                                    trace!("Metadata predicate: table matches");
-                                    // Meta data of the table covers predicates of the request
-                                    return Ok(Some((table_name, None)));
-                                }
-                                PredicateMatch::Unknown => {
-                                    trace!("Metadata predicate: unknown match");
-                                    // We cannot match the predicate to get answer from meta data, let do full plan
-                                    chunks_full.push(chunk);
-                                }
-                                PredicateMatch::Zero => {
-                                    trace!("Metadata predicate: zero rows match");
-                                } // this chunk's table does not participate in the request
-                            }
                         }
+                        PredicateMatch::Zero => {
+                            trace!("Metadata predicate: zero rows match");
+                        } // this chunk's table does not participate in the request
                     }
+                }
+            }
 
-                    Ok((!chunks_full.is_empty())
-                        .then_some((table_name, Some((predicate, chunks_full)))))
-                })
-                .try_collect()
-                .await?;
+            Ok((!chunks_full.is_empty()).then_some((table_name, Some((predicate, chunks_full)))))
+        })
+        .try_collect()
+        .await?;
 
         // Feed builder
         let mut builder = StringSetPlanBuilder::new();
@@ -369,6 +373,7 @@ impl InfluxRpcPlanner {
         let tables: Vec<_> = table_chunk_stream(
             Arc::clone(&namespace),
             false,
+            false,
             &table_predicates_need_chunks,
             &ctx,
         )
@@ -525,6 +530,7 @@ impl InfluxRpcPlanner {
         let tables: Vec<_> = table_chunk_stream(
             Arc::clone(&namespace),
             false,
+            false,
             &table_predicates_filtered,
             &ctx,
         )
@@ -721,6 +727,7 @@ impl InfluxRpcPlanner {
         let plans = create_plans(
             namespace,
             &table_predicates_need_chunks,
+            false,
             ctx,
             |table_name, predicate, chunks, schema| {
                 Self::field_columns_plan(Arc::from(table_name), schema, predicate, chunks)
@@ -767,6 +774,7 @@ impl InfluxRpcPlanner {
         let plans = create_plans(
             namespace,
             &table_predicates,
+            false,
             ctx,
             |table_name, predicate, chunks, schema| {
                 Self::read_filter_plan(table_name, schema, predicate, chunks)
@@ -831,9 +839,13 @@ impl InfluxRpcPlanner {
             }
         }
 
+        // A `last` selector over every series only depends on the most recent
+        // value of each field, which may be answered from the last value
+        // cache of the table.
         let plans = create_plans(
             namespace,
             &table_predicates,
+            matches!(agg, Aggregate::Last),
             ctx,
             |table_name, predicate, chunks, schema| {
                 // check group_columns for unknown columns
@@ -902,6 +914,7 @@ impl InfluxRpcPlanner {
         let plans = create_plans(
             namespace,
             &table_predicates,
+            false,
             ctx,
             |table_name, predicate, chunks, schema| {
                 Self::read_window_aggregate_plan(
@@ -1355,9 +1368,14 @@ impl InfluxRpcPlanner {
 /// This function is directly invoked by `table_name, `tag_keys` and `tag_values` where need_fields should be false.
 /// This function is indirectly invoked by `field_columns`, `read_filter`, `read_group` and `read_window_aggregate`
 /// through the function `create_plans` where need_fields should be true.
+///
+/// If `last_value` is true, the query only reads the most recent value of each field for every
+/// series, and the chunks of [`QueryNamespace::last_value_chunks`] are used for the tables whose
+/// predicate [`is_last_value_predicate`].
 fn table_chunk_stream<'a>(
     namespace: Arc<dyn QueryNamespace>,
     need_fields: bool,
+    last_value: bool,
     table_predicates: &'a [(Arc<str>, Predicate)],
     ctx: &'a IOxSessionContext,
 ) -> impl Stream<Item = Result<(&'a Arc<str>, &'a Predicate, Vec<Arc<dyn QueryChunk>>)>> + 'a {
@@ -1369,6 +1387,10 @@ fn table_chunk_stream<'a>(
             let namespace = Arc::clone(&namespace);
 
             let table_schema = namespace.table_schema(table_name);
+            let last_value = last_value
+                && table_schema
+                    .as_ref()
+                    .map_or(false, |schema| is_last_value_predicate(schema, predicate));
             let projection = match table_schema {
                 Some(table_schema) => {
                     columns_in_predicates(need_fields, &table_schema, table_name, predicate)
@@ -1377,6 +1399,20 @@ fn table_chunk_stream<'a>(
             };
 
             async move {
+                if last_value {
+                    let chunks = namespace
+                        .last_value_chunks(table_name, ctx.child_ctx("last_value_chunks"))
+                        .await
+                        .context(GettingChunksSnafu {
+                            table_name: table_name.as_ref(),
+                        })?;
+
+                    if let Some(chunks) = chunks {
+                        debug!(%table_name, n_chunks=chunks.len(), "using last value cache");
+                        return Ok((table_name, predicate, chunks));
+                    }
+                }
+
                 let chunks = namespace
                     .chunks(
                         table_name,
@@ -1481,9 +1517,12 @@ fn columns_in_predicates(
 ///
 /// `f(ctx, table_name, table_predicate, chunks, table_schema)` is
 ///  invoked on the chunks for each table to produce a plan for each
+///
+/// See [`table_chunk_stream`] for `last_value`.
 async fn create_plans<F, P>(
     namespace: Arc<dyn QueryNamespace>,
     table_predicates: &[(Arc<str>, Predicate)],
+    last_value: bool,
     ctx: IOxSessionContext,
     f: F,
 ) -> Result<Vec<P>>
@@ -1497,36 +1536,72 @@ where
     let metadata_ctx = ctx.child_ctx("apply_predicate_to_metadata");
     let metadata_ctx = &metadata_ctx; // needed to use inside the move closure
 
-    table_chunk_stream(Arc::clone(&namespace), true, table_predicates, &ctx)
-        .and_then(|(table_name, predicate, chunks)| async move {
-            let chunks = prune_chunks_metadata(metadata_ctx, chunks, predicate)?;
-            Ok((table_name, predicate, chunks))
-        })
-        // rustc seems to heavily confused about the filter step here, esp. it dislikes `.try_filter` and even
-        // `.try_filter_map` requires some additional type annotations
-        .try_filter_map(|(table_name, predicate, chunks)| async move {
-            Ok((!chunks.is_empty()).then_some((table_name, predicate, chunks)))
-                as Result<Option<(&Arc<str>, &Predicate, Vec<_>)>>
-        })
-        .and_then(|(table_name, predicate, chunks)| {
-            let mut ctx = ctx.child_ctx("table");
-            ctx.set_metadata("table", table_name.to_string());
+    table_chunk_stream(
+        Arc::clone(&namespace),
+        true,
+        last_value,
+        table_predicates,
+        &ctx,
+    )
+    .and_then(|(table_name, predicate, chunks)| async move {
+        let chunks = prune_chunks_metadata(metadata_ctx, chunks, predicate)?;
+        Ok((table_name, predicate, chunks))
+    })
+    // rustc seems to heavily confused about the filter step here, esp. it dislikes `.try_filter` and even
+    // `.try_filter_map` requires some additional type annotations
+    .try_filter_map(|(table_name, predicate, chunks)| async move {
+        Ok((!chunks.is_empty()).then_some((table_name, predicate, chunks)))
+            as Result<Option<(&Arc<str>, &Predicate, Vec<_>)>>
+    })
+    .and_then(|(table_name, predicate, chunks)| {
+        let mut ctx = ctx.child_ctx("table");
+        ctx.set_metadata("table", table_name.to_string());
 
-            let namespace = Arc::clone(&namespace);
-            let f = f.clone();
+        let namespace = Arc::clone(&namespace);
+        let f = f.clone();
 
-            async move {
-                let schema = namespace
-                    .table_schema(table_name)
-                    .context(TableRemovedSnafu {
-                        table_name: table_name.as_ref(),
-                    })?;
+        async move {
+            let schema = namespace
+                .table_schema(table_name)
+                .context(TableRemovedSnafu {
+                    table_name: table_name.as_ref(),
+                })?;
 
-                f(table_name, predicate, chunks, &schema)
-            }
-        })
-        .try_collect()
-        .await
+            f(table_name, predicate, chunks, &schema)
+        }
+    })
+    .try_collect()
+    .await
+}
+
+/// Returns true if the most recent value of each field for every series of the table described by
+/// `schema` is the most recent value that matches `predicate`, if any value matches at all.
+///
+/// This holds if `predicate` has no upper time bound and only filters on tags, as a row of a series
+/// then matches if any older row of the same series matches.
+fn is_last_value_predicate(schema: &Schema, predicate: &Predicate) -> bool {
+    if predicate
+        .range
+        .map_or(false, |range| range.end() < data_types::MAX_NANO_TIME)
+    {
+        return false;
+    }
+
+    if !predicate.value_expr.is_empty() {
+        return false;
+    }
+
+    let mut columns = StdHashSet::new();
+    if exprlist_to_columns(&predicate.exprs, &mut columns).is_err() {
+        return false;
+    }
+
+    columns.iter().all(|c| {
+        matches!(
+            schema.find_index_of(&c.name).map(|idx| schema.field(idx).0),
+            Some(InfluxColumnType::Tag)
+        )
+    })
 }
 
 /// Prunes the provided list of chunks using [`QueryChunk::apply_predicate_to_metadata`]
@@ -1996,6 +2071,47 @@ mod tests {
         assert_eq!(projection, vec![0, 2]);
     }
 
+    #[test]
+    fn test_is_last_value_predicate() {
+        let chunk0 = Arc::new(
+            TestChunk::new("h2o")
+                .with_id(0)
+                .with_tag_column("foo")
+                .with_i64_field_column("i64_field")
+                .with_time_column()
+                .with_one_row_of_data(),
+        );
+        let executor = Arc::new(Executor::new_testing());
+        let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
+        test_db.add_chunk("my_partition_key", Arc::clone(&chunk0));
+        let schema = test_db.table_schema("h2o").unwrap();
+
+        // no restrictions
+        assert!(is_last_value_predicate(&schema, &Predicate::new()));
+
+        // lower time bound and predicate on tag
+        let predicate = Predicate::new()
+            .with_range(100, i64::MAX)
+            .with_expr(col("foo").eq(lit("some_thing")).or(col("foo").is_null()));
+        assert!(is_last_value_predicate(&schema, &predicate));
+
+        // upper time bound
+        let predicate = Predicate::new().with_range(100, 200);
+        assert!(!is_last_value_predicate(&schema, &predicate));
+
+        // predicate on field
+        let predicate = Predicate::new().with_expr(col("i64_field").gt(lit(1)));
+        assert!(!is_last_value_predicate(&schema, &predicate));
+
+        // predicate on time
+        let predicate = Predicate::new().with_expr(col("time").gt(lit(1)));
+        assert!(!is_last_value_predicate(&schema, &predicate));
+
+        // predicate on unknown column
+        let predicate = Predicate::new().with_expr(col("bar").eq(lit("some_thing")));
+        assert!(!is_last_value_predicate(&schema, &predicate));
+    }
+
     #[tokio::test]
     async fn test_table_chunk_stream_no_field_columns() {
         let chunk0 = Arc::new(
@@ -2024,7 +2140,7 @@ mod tests {
         // Test 1: need_fields --> all columns will be selected
         let need_fields = true;
 
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2052,7 +2168,7 @@ mod tests {
         let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
         test_db.add_chunk("my_partition_key", Arc::clone(&chunk0));
         let ctx = test_db.new_query_context(None);
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2098,7 +2214,7 @@ mod tests {
         /////////////
         // Test 1: empty predicate with need_fields
         let need_fields = true;
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2125,7 +2241,7 @@ mod tests {
         let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
         test_db.add_chunk("my_partition_key", Arc::clone(&chunk0));
         let ctx = test_db.new_query_context(None);
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2164,7 +2280,7 @@ mod tests {
         let table_predicates = vec![(Arc::from("h2o"), predicate)];
 
         let need_fields = false;
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2213,7 +2329,7 @@ mod tests {
         let predicate = Predicate::new().with_expr(expr);
         let table_predicates = vec![(Arc::from("h2o"), predicate)];
 
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2246,7 +2362,7 @@ mod tests {
         let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
         test_db.add_chunk("my_partition_key", Arc::clone(&chunk0));
         let ctx = test_db.new_query_context(None);
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -2292,7 +2408,7 @@ mod tests {
         let table_predicates = vec![(Arc::from("h2o"), predicate)];
 
         let need_fields = false;
-        let result = table_chunk_stream(test_db, need_fields, &table_predicates, &ctx)
+        let result = table_chunk_stream(test_db, need_fields, false, &table_predicates, &ctx)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -123,6 +123,8 @@ impl TableBuilder {
                 id: TableId::new(id),
                 namespace_id: NamespaceId::new(0),
                 name: "table".to_string(),
+                primary_key: vec![],
+                deleted_at: None,
             },
         }
     }
@@ -161,6 +163,7 @@ impl PartitionBuilder {
                 sort_key: vec![],
                 persisted_sequence_number: None,
                 new_file_at: None,
+                rollup_interval_ns: None,
             },
         }
     }
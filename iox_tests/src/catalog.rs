@@ -6,8 +6,8 @@ use arrow::{
 };
 use data_types::{
     Column, ColumnSet, ColumnType, CompactionLevel, Namespace, NamespaceSchema, ParquetFile,
-    ParquetFileParams, Partition, PartitionId, QueryPool, SequenceNumber, Shard, ShardIndex, Table,
-    TableId, TableSchema, Timestamp, TopicMetadata,
+    ParquetFileParams, Partition, PartitionId, QueryPool, QueryPriority, SequenceNumber, Shard,
+    ShardIndex, Table, TableId, TableSchema, Timestamp, TopicMetadata, TypeCoercionPolicy,
 };
 use datafusion::physical_plan::metrics::Count;
 use datafusion_util::MemoryStream;
@@ -89,6 +89,7 @@ impl TestCatalog {
                     Arc::clone(parquet_store.object_store()),
                 )]),
                 mem_pool_size: 1024 * 1024 * 1024,
+                query_priority_threads: HashMap::default(),
             },
             exec,
         ));
@@ -302,6 +303,26 @@ impl TestNamespace {
             .await
             .unwrap();
     }
+
+    /// Set the type coercion policy of this namespace.
+    pub async fn update_type_coercion_policy(&self, policy: TypeCoercionPolicy) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_type_coercion_policy(&self.namespace.name, policy)
+            .await
+            .unwrap();
+    }
+
+    /// Set the default query priority of this namespace.
+    pub async fn update_default_query_priority(&self, priority: QueryPriority) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_default_query_priority(&self.namespace.name, priority)
+            .await
+            .unwrap();
+    }
 }
 
 /// A test shard with its namespace in the catalog
@@ -1,4 +1,6 @@
-use hyper::{Body, Response, StatusCode};
+use std::time::Duration;
+
+use hyper::{header::RETRY_AFTER, Body, Response, StatusCode};
 use observability_deps::tracing::warn;
 
 /// Constants used in API error codes.
@@ -101,6 +103,13 @@ pub struct HttpApiError {
 
     /// Human-readable message.
     msg: String,
+
+    /// Optional minimum duration the client should wait before retrying,
+    /// returned in the `Retry-After` header.
+    retry_after: Option<Duration>,
+
+    /// Optional line of a line protocol payload the error refers to.
+    line: Option<usize>,
 }
 
 impl HttpApiError {
@@ -109,27 +118,52 @@ impl HttpApiError {
         Self {
             code: code.into(),
             msg: msg.into(),
+            retry_after: None,
+            line: None,
         }
     }
 
+    /// Instruct the client to wait at least `retry_after` before retrying the
+    /// request.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Refer to `line` of the line protocol payload of the request, returned
+    /// in the `line` field of the body.
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
     /// Generate response body for this error.
     fn body(&self) -> Body {
-        let json = serde_json::json!({
+        let mut json = serde_json::json!({
             "code": self.code.as_text().to_string(),
             "message": self.msg.clone(),
-        })
-        .to_string();
+        });
+        if let Some(line) = self.line {
+            json["line"] = line.into();
+        }
 
-        Body::from(json)
+        Body::from(json.to_string())
     }
 
     /// Generate response for this error.
     pub fn response(&self) -> Response<Body> {
-        Response::builder()
+        let mut builder = Response::builder()
             .status(self.code.status_code())
-            .header("content-type", "application/json")
-            .body(self.body())
-            .unwrap()
+            .header("content-type", "application/json");
+
+        if let Some(retry_after) = self.retry_after {
+            // The header value is a whole number of seconds, so round up to
+            // avoid clients retrying too early.
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            builder = builder.header(RETRY_AFTER, secs.max(1));
+        }
+
+        builder.body(self.body()).unwrap()
     }
 
     /// Check if the error is an internal server error.
@@ -187,3 +221,47 @@ pub trait HttpApiErrorSource: std::error::Error {
     /// Create [`HttpApiError`].
     fn to_http_api_error(&self) -> HttpApiError;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_header() {
+        let resp = HttpApiError::new(StatusCode::TOO_MANY_REQUESTS, "slow down").response();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers().get(RETRY_AFTER).is_none());
+
+        let resp = HttpApiError::new(StatusCode::TOO_MANY_REQUESTS, "slow down")
+            .with_retry_after(Duration::from_millis(1_500))
+            .response();
+        assert_eq!(resp.headers().get(RETRY_AFTER).unwrap(), "2");
+
+        let resp = HttpApiError::new(StatusCode::TOO_MANY_REQUESTS, "slow down")
+            .with_retry_after(Duration::from_millis(10))
+            .response();
+        assert_eq!(resp.headers().get(RETRY_AFTER).unwrap(), "1");
+    }
+
+    #[tokio::test]
+    async fn test_line_body() {
+        let body = |err: HttpApiError| async move {
+            let body = hyper::body::to_bytes(err.response().into_body())
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let got = body(HttpApiError::new(StatusCode::BAD_REQUEST, "bad")).await;
+        assert_eq!(
+            got,
+            serde_json::json!({"code": "invalid", "message": "bad"})
+        );
+
+        let got = body(HttpApiError::new(StatusCode::BAD_REQUEST, "bad").with_line(3)).await;
+        assert_eq!(
+            got,
+            serde_json::json!({"code": "invalid", "message": "bad", "line": 3})
+        );
+    }
+}
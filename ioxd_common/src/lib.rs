@@ -25,7 +25,6 @@ use observability_deps::tracing::{error, info};
 use snafu::{ResultExt, Snafu};
 use std::{net::SocketAddr, sync::Arc};
 use tokio_util::sync::CancellationToken;
-use trace_http::ctx::TraceHeaderParser;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -109,19 +108,10 @@ pub async fn serve(
     http_listener: Option<AddrIncoming>,
     server_type: Arc<dyn ServerType>,
 ) -> Result<()> {
-    let trace_header_parser = TraceHeaderParser::new()
-        .with_jaeger_trace_context_header_name(
-            &common_state
-                .run_config()
-                .tracing_config()
-                .traces_jaeger_trace_context_header_name,
-        )
-        .with_jaeger_debug_name(
-            &common_state
-                .run_config()
-                .tracing_config()
-                .traces_jaeger_debug_name,
-        );
+    let trace_header_parser = common_state
+        .run_config()
+        .tracing_config()
+        .trace_header_parser();
 
     // Construct and start up gRPC server
     let grpc_server = rpc::serve(
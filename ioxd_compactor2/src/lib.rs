@@ -2,10 +2,12 @@ use async_trait::async_trait;
 use backoff::BackoffConfig;
 use clap_blocks::compactor2::{CompactionType, Compactor2Config};
 use compactor2::{
+    admin::CompactionAdmin,
     compactor::Compactor2,
-    config::{Config, PartitionsSourceConfig, ShardConfig},
+    config::{Config, LeaseConfig, PartitionsSourceConfig, ShardConfig},
 };
 use data_types::{PartitionId, TRANSITION_SHARD_NUMBER};
+use generated_types::influxdata::iox::compactor::v1::compaction_service_server::CompactionServiceServer;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
@@ -20,6 +22,7 @@ use ioxd_common::{
 };
 use metric::Registry;
 use parquet_file::storage::ParquetStorage;
+use service_grpc_compactor::CompactionService;
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
@@ -27,6 +30,7 @@ use std::{
 };
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
+use uuid::Uuid;
 
 // There is only one shard with index 1
 const TOPIC: &str = "iox-shared";
@@ -34,6 +38,8 @@ const TRANSITION_SHARD_INDEX: i32 = TRANSITION_SHARD_NUMBER;
 
 pub struct Compactor2ServerType {
     compactor: Compactor2,
+    catalog: Arc<dyn Catalog>,
+    admin: Arc<CompactionAdmin>,
     metric_registry: Arc<Registry>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
 }
@@ -47,11 +53,15 @@ impl std::fmt::Debug for Compactor2ServerType {
 impl Compactor2ServerType {
     pub fn new(
         compactor: Compactor2,
+        catalog: Arc<dyn Catalog>,
+        admin: Arc<CompactionAdmin>,
         metric_registry: Arc<metric::Registry>,
         common_state: &CommonServerState,
     ) -> Self {
         Self {
             compactor,
+            catalog,
+            admin,
             metric_registry,
             trace_collector: common_state.trace_collector(),
         }
@@ -87,6 +97,14 @@ impl ServerType for Compactor2ServerType {
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
         let builder = setup_builder!(builder_input, self);
 
+        add_service!(
+            builder,
+            CompactionServiceServer::new(CompactionService::new(
+                Arc::clone(&self.catalog),
+                Arc::clone(&self.admin),
+            ))
+        );
+
         serve_builder!(builder);
 
         Ok(())
@@ -156,6 +174,23 @@ pub async fn create_compactor2_server_type(
         n_shards: compactor_config.shard_count.expect("just checked"),
     });
 
+    assert!(
+        compactor_config.partition_lease_secs.is_none() || shard_config.is_none(),
+        "partition leases cannot be combined with shard ID and count"
+    );
+    let lease_config = compactor_config.partition_lease_secs.map(|lease_secs| {
+        let lease_duration = Duration::from_secs(lease_secs.get());
+        LeaseConfig {
+            owner: compactor_config
+                .partition_lease_owner
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            lease_duration,
+            // renew often enough that a single missed heartbeat does not lose the lease
+            heartbeat_interval: lease_duration / 3,
+        }
+    });
+
     let partitions_source = create_partition_source_config(
         compactor_config.partition_filter.as_deref(),
         compactor_config.process_all_partitions,
@@ -181,11 +216,12 @@ pub async fn create_compactor2_server_type(
         TRANSITION_SHARD_INDEX,
     )
     .await;
+    let admin = Arc::new(CompactionAdmin::new(Arc::clone(&time_provider)));
     let compactor = Compactor2::start(Config {
         compaction_type,
         shard_id,
         metric_registry: Arc::clone(&metric_registry),
-        catalog,
+        catalog: Arc::clone(&catalog),
         parquet_store_real,
         parquet_store_scratchpad,
         exec,
@@ -203,6 +239,7 @@ pub async fn create_compactor2_server_type(
         shadow_mode: compactor_config.shadow_mode,
         ignore_partition_skip_marker: compactor_config.ignore_partition_skip_marker,
         shard_config,
+        lease_config,
         min_num_l1_files_to_compact: compactor_config.min_num_l1_files_to_compact,
         process_once: compactor_config.process_once,
         simulate_without_object_store: false,
@@ -211,10 +248,13 @@ pub async fn create_compactor2_server_type(
         all_errors_are_fatal: false,
         max_num_columns_per_table: compactor_config.max_num_columns_per_table,
         max_num_files_per_plan: compactor_config.max_num_files_per_plan,
+        admin: Arc::clone(&admin),
     });
 
     Arc::new(Compactor2ServerType::new(
         compactor,
+        catalog,
+        admin,
         metric_registry,
         common_state,
     ))
@@ -205,6 +205,9 @@ pub async fn create_ingester_server_type(
         ingester_config.persist_queue_depth,
         ingester_config.persist_hot_partition_cost,
         object_store,
+        ingester_config
+            .last_value_cache
+            .then_some(ingester_config.last_value_cache_max_series),
         shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
     )
     .await?;
@@ -8,7 +8,7 @@ use iox_query::exec::{Executor, ExecutorType};
 use iox_time::TimeProvider;
 use ioxd_common::{
     add_service,
-    http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    http::error::{HttpApiError, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
     server_type::{CommonServerState, RpcError, ServerType},
@@ -20,6 +20,7 @@ use querier::{
     create_ingester_connections, QuerierCatalogCache, QuerierDatabase, QuerierHandler,
     QuerierHandlerImpl, QuerierServer,
 };
+use service_http_prom_read::PromReadService;
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
@@ -34,6 +35,7 @@ mod rpc;
 pub struct QuerierServerType<C: QuerierHandler> {
     database: Arc<QuerierDatabase>,
     server: QuerierServer<C>,
+    prom_read: PromReadService<QuerierDatabase>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
     authz: Option<Arc<dyn Authorizer>>,
 }
@@ -51,9 +53,16 @@ impl<C: QuerierHandler> QuerierServerType<C> {
         common_state: &CommonServerState,
         authz: Option<Arc<dyn Authorizer>>,
     ) -> Self {
+        let prom_read = PromReadService::new(
+            Arc::clone(&database),
+            authz.as_ref().map(Arc::clone),
+            common_state.run_config().max_http_request_size,
+        );
+
         Self {
             server,
             database,
+            prom_read,
             trace_collector: common_state.trace_collector(),
             authz,
         }
@@ -77,12 +86,15 @@ impl<C: QuerierHandler + std::fmt::Debug + 'static> ServerType for QuerierServer
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
-    /// Just return "not found".
+    /// Serve the Prometheus remote read API.
     async fn route_http_request(
         &self,
-        _req: Request<Body>,
+        req: Request<Body>,
     ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
-        Err(Box::new(IoxHttpError::NotFound))
+        self.prom_read
+            .route(req)
+            .await
+            .map_err(|e| Box::new(IoxHttpErrorAdaptor(e)) as _)
     }
 
     /// Configure the gRPC services.
@@ -122,31 +134,23 @@ impl<C: QuerierHandler + std::fmt::Debug + 'static> ServerType for QuerierServer
     }
 }
 
-/// Simple error struct, we're not really providing an HTTP interface for the compactor.
+/// This adaptor converts the Prometheus remote read http error type into a
+/// type that satisfies the requirements of ioxd's runner framework, keeping
+/// the two decoupled.
 #[derive(Debug)]
-pub enum IoxHttpError {
-    NotFound,
-}
+pub struct IoxHttpErrorAdaptor(service_http_prom_read::Error);
 
-impl IoxHttpError {
-    fn status_code(&self) -> HttpApiErrorCode {
-        match self {
-            IoxHttpError::NotFound => HttpApiErrorCode::NotFound,
-        }
-    }
-}
-
-impl Display for IoxHttpError {
+impl Display for IoxHttpErrorAdaptor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        Display::fmt(&self.0, f)
     }
 }
 
-impl std::error::Error for IoxHttpError {}
+impl std::error::Error for IoxHttpErrorAdaptor {}
 
-impl HttpApiErrorSource for IoxHttpError {
+impl HttpApiErrorSource for IoxHttpErrorAdaptor {
     fn to_http_api_error(&self) -> HttpApiError {
-        HttpApiError::new(self.status_code(), self.to_string())
+        HttpApiError::new(self.0.status_code(), self.to_string())
     }
 }
 
@@ -178,7 +182,7 @@ pub enum Error {
 pub async fn create_querier_server_type(
     args: QuerierServerTypeArgs<'_>,
 ) -> Result<Arc<dyn ServerType>, Error> {
-    let catalog_cache = Arc::new(QuerierCatalogCache::new(
+    let mut catalog_cache = QuerierCatalogCache::new(
         Arc::clone(&args.catalog),
         args.time_provider,
         Arc::clone(&args.metric_registry),
@@ -186,7 +190,12 @@ pub async fn create_querier_server_type(
         args.querier_config.ram_pool_metadata_bytes(),
         args.querier_config.ram_pool_data_bytes(),
         &Handle::current(),
-    ));
+    )
+    .with_catalog_change_feed(&Handle::current());
+    if let Some(bucket_width) = args.querier_config.query_result_cache_bucket_width() {
+        catalog_cache = catalog_cache.with_query_result_cache(bucket_width);
+    }
+    let catalog_cache = Arc::new(catalog_cache);
 
     // register cached object store with the execution context
     let parquet_store = catalog_cache.parquet_store();
@@ -230,9 +239,15 @@ pub async fn create_querier_server_type(
             ingester_addresses,
             Arc::clone(&catalog_cache),
             args.querier_config.ingester_circuit_breaker_threshold,
+            args.common_state
+                .run_config()
+                .tracing_config()
+                .trace_header_formatter(),
         ))
     };
 
+    let max_concurrent_queries_by_priority =
+        args.querier_config.max_concurrent_queries_by_priority();
     let database = Arc::new(
         QuerierDatabase::new(
             catalog_cache,
@@ -242,7 +257,8 @@ pub async fn create_querier_server_type(
             args.querier_config.max_concurrent_queries(),
             Arc::new(args.querier_config.datafusion_config),
         )
-        .await?,
+        .await?
+        .with_max_concurrent_queries_by_priority(max_concurrent_queries_by_priority),
     );
     let querier_handler = Arc::new(QuerierHandlerImpl::new(
         args.catalog,
@@ -38,6 +38,13 @@ fn namespace_to_proto(namespace: Namespace) -> proto::Namespace {
         retention_period_ns: namespace.retention_period_ns,
         max_tables: namespace.max_tables,
         max_columns_per_table: namespace.max_columns_per_table,
+        max_lines_per_second: namespace.max_lines_per_second,
+        max_batch_bytes_per_second: namespace.max_batch_bytes_per_second,
+        max_concurrent_queries: namespace.max_concurrent_queries,
+        max_query_memory_bytes: namespace.max_query_memory_bytes,
+        type_coercion_policy: proto::TypeCoercionPolicy::from(namespace.type_coercion_policy)
+            .into(),
+        default_query_priority: proto::QueryPriority::from(namespace.default_query_priority).into(),
     }
 }
 
@@ -76,6 +83,24 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
         ))
     }
 
+    async fn undelete_namespace(
+        &self,
+        _request: tonic::Request<proto::UndeleteNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::UndeleteNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn purge_namespace(
+        &self,
+        _request: tonic::Request<proto::PurgeNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::PurgeNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
     async fn update_namespace_retention(
         &self,
         _request: tonic::Request<proto::UpdateNamespaceRetentionRequest>,
@@ -94,6 +119,26 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
             "use router instances to manage namespaces",
         ))
     }
+
+    async fn update_namespace_type_coercion_policy(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceTypeCoercionPolicyRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceTypeCoercionPolicyResponse>, tonic::Status>
+    {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_default_query_priority(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceDefaultQueryPriorityRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceDefaultQueryPriorityResponse>, tonic::Status>
+    {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -192,6 +237,12 @@ mod tests {
                         retention_period_ns: TEST_RETENTION_PERIOD_NS,
                         max_tables: TEST_MAX_TABLES,
                         max_columns_per_table: TEST_MAX_COLUMNS_PER_TABLE,
+                        max_lines_per_second: None,
+                        max_batch_bytes_per_second: None,
+                        max_concurrent_queries: None,
+                        max_query_memory_bytes: None,
+                        type_coercion_policy: proto::TypeCoercionPolicy::Strict.into(),
+                        default_query_priority: proto::QueryPriority::Normal.into(),
                     },
                     proto::Namespace {
                         id: 2,
@@ -199,6 +250,12 @@ mod tests {
                         retention_period_ns: TEST_RETENTION_PERIOD_NS,
                         max_tables: TEST_MAX_TABLES,
                         max_columns_per_table: TEST_MAX_COLUMNS_PER_TABLE,
+                        max_lines_per_second: None,
+                        max_batch_bytes_per_second: None,
+                        max_concurrent_queries: None,
+                        max_query_memory_bytes: None,
+                        type_coercion_policy: proto::TypeCoercionPolicy::Strict.into(),
+                        default_query_priority: proto::QueryPriority::Normal.into(),
                     },
                 ]
             }
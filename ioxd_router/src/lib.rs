@@ -14,9 +14,12 @@ use ioxd_common::{
     add_service,
     http::error::{HttpApiError, HttpApiErrorSource},
     reexport::{
-        generated_types::influxdata::iox::{
-            catalog::v1::catalog_service_server, namespace::v1::namespace_service_server,
-            object_store::v1::object_store_service_server, schema::v1::schema_service_server,
+        generated_types::{
+            influxdata::iox::{
+                catalog::v1::catalog_service_server, namespace::v1::namespace_service_server,
+                object_store::v1::object_store_service_server, schema::v1::schema_service_server,
+            },
+            opentelemetry::proto::collector::metrics::v1::metrics_service_server::MetricsServiceServer,
         },
         tonic::transport::Endpoint,
     },
@@ -31,17 +34,18 @@ use object_store::DynObjectStore;
 use router::{
     dml_handlers::{
         lazy_connector::LazyConnector, DmlHandler, DmlHandlerChainExt, FanOutAdaptor,
-        InstrumentationDecorator, Partitioner, RetentionValidator, RpcWrite, SchemaValidator,
+        InstrumentationDecorator, Partitioner, RateLimiter, RetentionValidator, RpcWrite,
+        SchemaValidator,
     },
     namespace_cache::{
-        metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache, ReadThroughCache,
-        ShardedCache,
+        metrics::InstrumentedCache, refresh_on_change, MemoryNamespaceCache, NamespaceCache,
+        ReadThroughCache, ShardedCache,
     },
     namespace_resolver::{
         MissingNamespaceAction, NamespaceAutocreation, NamespaceResolver, NamespaceSchemaResolver,
     },
     server::{
-        grpc::RpcWriteGrpcDelegate,
+        grpc::{otlp::OtlpMetricsService, NamespaceCacheRefresher, RpcWriteGrpcDelegate},
         http::{
             write::{
                 multi_tenant::MultiTenantRequestUnifier, single_tenant::SingleTenantRequestUnifier,
@@ -158,6 +162,7 @@ where
                 self.server.grpc().namespace_service()
             )
         );
+        add_service!(builder, MetricsServiceServer::from_arc(self.server.otlp()));
         serve_builder!(builder);
 
         Ok(())
@@ -189,7 +194,15 @@ impl std::error::Error for IoxHttpErrorAdaptor {}
 
 impl HttpApiErrorSource for IoxHttpErrorAdaptor {
     fn to_http_api_error(&self) -> HttpApiError {
-        HttpApiError::new(self.0.as_status_code(), self.to_string())
+        let err = HttpApiError::new(self.0.as_status_code(), self.to_string());
+        let err = match self.0.retry_after() {
+            Some(d) => err.with_retry_after(d),
+            None => err,
+        };
+        match self.0.line() {
+            Some(line) => err.with_line(line),
+            None => err,
+        }
     }
 }
 
@@ -201,6 +214,10 @@ pub async fn create_router2_server_type(
     object_store: Arc<DynObjectStore>,
     router_config: &Router2Config,
 ) -> Result<Arc<dyn ServerType>> {
+    let trace_header_formatter = common_state
+        .run_config()
+        .tracing_config()
+        .trace_header_formatter();
     let ingester_connections = router_config.ingester_addresses.iter().map(|addr| {
         let addr = addr.to_string();
         let endpoint = Endpoint::from_shared(hyper::body::Bytes::from(addr.clone()))
@@ -210,6 +227,7 @@ pub async fn create_router2_server_type(
                 endpoint,
                 router_config.rpc_write_timeout_seconds,
                 router_config.rpc_write_max_outgoing_bytes,
+                trace_header_formatter.clone(),
             ),
             addr,
         )
@@ -242,6 +260,14 @@ pub async fn create_router2_server_type(
         .await
         .expect("namespace cache pre-warming failed");
 
+    // Keep the cached schemas up to date with changes made through other
+    // routers or the namespace API.
+    tokio::spawn(refresh_on_change(
+        catalog.subscribe_changes(),
+        Arc::clone(&ns_cache),
+        Arc::clone(&catalog),
+    ));
+
     // # Schema validator
     //
     // Initialise and instrument the schema validator
@@ -257,6 +283,13 @@ pub async fn create_router2_server_type(
     let retention_validator =
         InstrumentationDecorator::new("retention_validator", &metrics, retention_validator);
 
+    // # Rate limiter
+    //
+    // Reject writes to namespaces exceeding their configured write rate limits
+    // before doing any further work.
+    let rate_limiter = RateLimiter::new(Arc::clone(&ns_cache), &metrics);
+    let rate_limiter = InstrumentationDecorator::new("rate_limiter", &metrics, rate_limiter);
+
     // # Write partitioner
     //
     // Add a write partitioner into the handler stack that splits by the date
@@ -335,7 +368,8 @@ pub async fn create_router2_server_type(
     // # Handler stack
     //
     // Build the chain of DML handlers that forms the request processing pipeline
-    let handler_stack = retention_validator
+    let handler_stack = rate_limiter
+        .and_then(retention_validator)
         .and_then(schema_validator)
         .and_then(partitioner)
         // Once writes have been partitioned, they are processed in parallel.
@@ -351,10 +385,18 @@ pub async fn create_router2_server_type(
         ));
 
     // Record the overall request handling latency
-    let handler_stack = InstrumentationDecorator::new("request", &metrics, handler_stack);
+    //
+    // The handler stack and namespace resolver are shared between the HTTP
+    // API and the OTLP/gRPC metrics service.
+    let handler_stack = Arc::new(InstrumentationDecorator::new(
+        "request",
+        &metrics,
+        handler_stack,
+    ));
+    let namespace_resolver = Arc::new(namespace_resolver);
 
     // Initialize the HTTP API delegate
-    let write_request_unifier: Result<Box<dyn WriteRequestUnifier>> = match (
+    let write_request_unifier: Result<Arc<dyn WriteRequestUnifier>> = match (
         router_config.single_tenant_deployment,
         &router_config.authz_address,
     ) {
@@ -367,7 +409,7 @@ pub async fn create_router2_server_type(
                 })?;
             authz.probe().await.expect("Authz connection test failed.");
 
-            Ok(Box::new(SingleTenantRequestUnifier::new(authz)))
+            Ok(Arc::new(SingleTenantRequestUnifier::new(authz)))
         }
         (true, None) => {
             // Single tenancy was requested, but no auth was provided - the
@@ -376,29 +418,50 @@ pub async fn create_router2_server_type(
             // never reach here.
             unreachable!("INFLUXDB_IOX_SINGLE_TENANCY is set, but could not create an authz service. Check the INFLUXDB_IOX_AUTHZ_ADDR")
         }
-        (false, None) => Ok(Box::<MultiTenantRequestUnifier>::default()),
+        (false, None) => Ok(Arc::<MultiTenantRequestUnifier>::default()),
         (false, Some(_)) => {
             // As above, this combination should be prevented by the
             // router's clap flag parse configuration.
             unreachable!("INFLUXDB_IOX_AUTHZ_ADDR is set, but authz only exists for single_tenancy. Check the INFLUXDB_IOX_SINGLE_TENANCY")
         }
     };
+    let write_request_unifier = write_request_unifier?;
     let http = HttpDelegate::new(
         common_state.run_config().max_http_request_size,
         router_config.http_request_limit,
+        Arc::clone(&namespace_resolver),
+        Arc::clone(&handler_stack),
+        &metrics,
+        Box::new(Arc::clone(&write_request_unifier)),
+    );
+    let http = match router_config.http_write_stream_chunk_bytes {
+        Some(chunk_bytes) => http.with_write_stream_chunk_bytes(chunk_bytes),
+        None => http,
+    };
+
+    // Initialize the OTLP/gRPC metrics service, resolving namespaces in the
+    // same way as the HTTP API.
+    let otlp = OtlpMetricsService::new(
         namespace_resolver,
         handler_stack,
+        Box::new(write_request_unifier),
         &metrics,
-        write_request_unifier?,
     );
 
     // Initialize the gRPC API delegate that creates the services relevant to the RPC
     // write router path and use it to create the relevant `RpcWriteRouterServer` and
     // `RpcWriteRouterServerType`.
-    let grpc = RpcWriteGrpcDelegate::new(catalog, object_store, topic_id, query_id);
+    let grpc = RpcWriteGrpcDelegate::new(
+        catalog,
+        object_store,
+        Arc::new(NamespaceCacheRefresher::new(ns_cache)),
+        topic_id,
+        query_id,
+    )
+    .with_namespace_delete_grace_period(router_config.namespace_delete_grace_period);
 
     let router_server =
-        RpcWriteRouterServer::new(http, grpc, metrics, common_state.trace_collector());
+        RpcWriteRouterServer::new(http, grpc, otlp, metrics, common_state.trace_collector());
     let server_type = Arc::new(RpcWriteRouterServerType::new(router_server, common_state));
     Ok(server_type)
 }
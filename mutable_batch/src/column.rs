@@ -10,7 +10,7 @@ use arrow::{
     error::ArrowError,
 };
 use arrow_util::{bitset::BitSet, string::PackedStringArray};
-use data_types::{StatValues, Statistics};
+use data_types::{IsNan, StatValues, Statistics, TypeCoercionPolicy};
 use schema::{InfluxColumnType, InfluxFieldType, TIME_DATA_TYPE};
 use snafu::{ResultExt, Snafu};
 use std::{fmt::Formatter, mem, sync::Arc};
@@ -45,8 +45,24 @@ pub enum Error {
 
     #[snafu(display("Internal MUB error constructing Arrow Array: {}", source))]
     CreatingArrowArray { source: ArrowError },
+
+    #[snafu(display(
+        "Cannot coerce {} values to {} with the {} type coercion policy",
+        from,
+        to,
+        policy
+    ))]
+    TypeCoercion {
+        from: InfluxColumnType,
+        to: InfluxColumnType,
+        policy: TypeCoercionPolicy,
+    },
 }
 
+/// The largest integer magnitude that converts to and from an [`f64`] without
+/// loss (2^53).
+const MAX_EXACT_F64_INT: u64 = 1 << f64::MANTISSA_DIGITS;
+
 /// A specialized `Error` for [`Column`] errors
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -259,6 +275,75 @@ impl Column {
         }
     }
 
+    /// Converts the values of this field column to the field type `to`, as
+    /// permitted by `policy`, returning the number of converted values.
+    ///
+    /// If any value cannot be converted, an error is returned and the column
+    /// is left unchanged.
+    pub(crate) fn coerce(
+        &mut self,
+        to: InfluxFieldType,
+        policy: TypeCoercionPolicy,
+    ) -> Result<usize> {
+        let from = self.influx_type;
+        let to_type = InfluxColumnType::Field(to);
+        let err = || Error::TypeCoercion {
+            from,
+            to: to_type,
+            policy,
+        };
+        if !policy.allows(from.into(), to_type.into()) {
+            return Err(err());
+        }
+        // Widening converts every integer, casts only convert exact values.
+        let widen = policy == TypeCoercionPolicy::Widen;
+
+        let data = match (&self.data, to) {
+            (ColumnData::I64(data, stats), InfluxFieldType::Float) => {
+                coerce_values(&self.valid, data, stats, |v| {
+                    (widen || v.unsigned_abs() <= MAX_EXACT_F64_INT).then_some(v as f64)
+                })
+                .map(|(data, stats)| ColumnData::F64(data, stats))
+            }
+            (ColumnData::U64(data, stats), InfluxFieldType::Float) => {
+                coerce_values(&self.valid, data, stats, |v| {
+                    (widen || v <= MAX_EXACT_F64_INT).then_some(v as f64)
+                })
+                .map(|(data, stats)| ColumnData::F64(data, stats))
+            }
+            (ColumnData::I64(data, stats), InfluxFieldType::UInteger) => {
+                coerce_values(&self.valid, data, stats, |v| u64::try_from(v).ok())
+                    .map(|(data, stats)| ColumnData::U64(data, stats))
+            }
+            (ColumnData::U64(data, stats), InfluxFieldType::Integer) => {
+                coerce_values(&self.valid, data, stats, |v| i64::try_from(v).ok())
+                    .map(|(data, stats)| ColumnData::I64(data, stats))
+            }
+            (ColumnData::F64(data, stats), InfluxFieldType::Integer) => {
+                coerce_values(&self.valid, data, stats, |v| {
+                    // i64::MIN is exactly representable, i64::MAX is not.
+                    let in_range = v >= i64::MIN as f64 && v < i64::MAX as f64;
+                    (v.fract() == 0.0 && in_range).then_some(v as i64)
+                })
+                .map(|(data, stats)| ColumnData::I64(data, stats))
+            }
+            (ColumnData::F64(data, stats), InfluxFieldType::UInteger) => {
+                coerce_values(&self.valid, data, stats, |v| {
+                    let in_range = v >= 0.0 && v < u64::MAX as f64;
+                    (v.fract() == 0.0 && in_range).then_some(v as u64)
+                })
+                .map(|(data, stats)| ColumnData::U64(data, stats))
+            }
+            _ => None,
+        }
+        .ok_or_else(err)?;
+
+        let converted = (0..self.len()).filter(|&idx| self.valid.get(idx)).count();
+        self.influx_type = to_type;
+        self.data = data;
+        Ok(converted)
+    }
+
     /// Converts this column to an arrow [`ArrayRef`]
     pub fn to_arrow(&self) -> Result<ArrayRef> {
         let nulls = Some(NullBuffer::new(self.valid.to_arrow()));
@@ -324,3 +409,39 @@ impl Column {
         Ok(data)
     }
 }
+
+/// Convert the valid values of `data` with `f`, returning the converted values
+/// and their statistics, or [`None`] if `f` fails to convert any value.
+fn coerce_values<T, U>(
+    valid: &BitSet,
+    data: &[T],
+    stats: &StatValues<T>,
+    f: impl Fn(T) -> Option<U>,
+) -> Option<(Vec<U>, StatValues<U>)>
+where
+    T: Copy,
+    U: Copy + Default + PartialOrd + IsNan,
+{
+    let mut min: Option<U> = None;
+    let mut max: Option<U> = None;
+    let data = data
+        .iter()
+        .enumerate()
+        .map(|(idx, v)| {
+            if !valid.get(idx) {
+                return Some(U::default());
+            }
+            let v = f(*v)?;
+            if min.map_or(true, |min| v < min) {
+                min = Some(v);
+            }
+            if max.map_or(true, |max| v > max) {
+                max = Some(v);
+            }
+            Some(v)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let stats = StatValues::new(min, max, stats.total_count, stats.null_count);
+    Some((data, stats))
+}
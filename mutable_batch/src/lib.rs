@@ -19,11 +19,11 @@
 
 use crate::column::{Column, ColumnData};
 use arrow::record_batch::RecordBatch;
-use data_types::StatValues;
+use data_types::{StatValues, TypeCoercionPolicy};
 use hashbrown::HashMap;
 use iox_time::Time;
 use schema::Projection;
-use schema::{builder::SchemaBuilder, Schema, TIME_COLUMN_NAME};
+use schema::{builder::SchemaBuilder, InfluxFieldType, Schema, TIME_COLUMN_NAME};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{collections::BTreeSet, ops::Range};
 
@@ -200,6 +200,26 @@ impl MutableBatch {
         Ok(&self.columns[*idx])
     }
 
+    /// Convert the values of the field column `column` to the field type `to`,
+    /// as permitted by `policy`, returning the number of converted values.
+    ///
+    /// The column is left unchanged if any of its values cannot be converted.
+    pub fn coerce_column(
+        &mut self,
+        column: &str,
+        to: InfluxFieldType,
+        policy: TypeCoercionPolicy,
+    ) -> Result<usize> {
+        let idx = *self
+            .column_names
+            .get(column)
+            .context(ColumnNotFoundSnafu { column })?;
+
+        self.columns[idx]
+            .coerce(to, policy)
+            .context(ColumnSnafu { column })
+    }
+
     /// Return the approximate memory size of the batch, in bytes.
     ///
     /// This includes `Self`.
@@ -0,0 +1,128 @@
+use data_types::{StatValues, Statistics, TypeCoercionPolicy};
+use mutable_batch::{writer::Writer, MutableBatch};
+use schema::{InfluxColumnType, InfluxFieldType};
+
+fn batch() -> MutableBatch {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 3);
+
+    writer
+        .write_i64("i64", Some(&[0b00000101]), vec![-3, 42].into_iter())
+        .unwrap();
+    writer
+        .write_i64("i64_big", None, vec![1, i64::MAX, 2].into_iter())
+        .unwrap();
+    writer
+        .write_u64("u64", None, vec![1, 2, 3].into_iter())
+        .unwrap();
+    writer
+        .write_f64("f64", None, vec![1.0, -2.0, 3.0].into_iter())
+        .unwrap();
+    writer
+        .write_f64("f64_fract", None, vec![1.0, 2.5, 3.0].into_iter())
+        .unwrap();
+    writer
+        .write_tag("tag", None, vec!["a", "b", "c"].into_iter())
+        .unwrap();
+    writer
+        .write_time("time", vec![1, 2, 3].into_iter())
+        .unwrap();
+
+    writer.commit();
+    batch
+}
+
+#[test]
+fn test_coerce_widen() {
+    let mut batch = batch();
+
+    let converted = batch
+        .coerce_column("i64", InfluxFieldType::Float, TypeCoercionPolicy::Widen)
+        .unwrap();
+    assert_eq!(converted, 2);
+    let column = batch.column("i64").unwrap();
+    assert_eq!(
+        column.influx_type(),
+        InfluxColumnType::Field(InfluxFieldType::Float)
+    );
+    assert_eq!(
+        column.stats(),
+        Statistics::F64(StatValues::new(Some(-3.0), Some(42.0), 3, Some(1)))
+    );
+
+    // Widening accepts integers that are not exactly representable.
+    batch
+        .coerce_column("i64_big", InfluxFieldType::Float, TypeCoercionPolicy::Widen)
+        .unwrap();
+    batch
+        .coerce_column("u64", InfluxFieldType::Float, TypeCoercionPolicy::Widen)
+        .unwrap();
+
+    // ...but only to floats.
+    batch
+        .coerce_column("f64", InfluxFieldType::Integer, TypeCoercionPolicy::Widen)
+        .unwrap_err();
+    batch
+        .coerce_column("tag", InfluxFieldType::Float, TypeCoercionPolicy::Widen)
+        .unwrap_err();
+
+    assert_eq!(
+        batch.column("u64").unwrap().stats(),
+        Statistics::F64(StatValues::new(Some(1.0), Some(3.0), 3, Some(0)))
+    );
+}
+
+#[test]
+fn test_coerce_lossless_cast() {
+    let mut batch = batch();
+    let policy = TypeCoercionPolicy::LosslessCast;
+
+    assert_eq!(
+        batch
+            .coerce_column("f64", InfluxFieldType::Integer, policy)
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        batch.column("f64").unwrap().stats(),
+        Statistics::I64(StatValues::new(Some(-2), Some(3), 3, Some(0)))
+    );
+
+    // Negative values are not valid unsigned integers.
+    batch
+        .coerce_column("f64", InfluxFieldType::UInteger, policy)
+        .unwrap_err();
+    batch
+        .coerce_column("u64", InfluxFieldType::Integer, policy)
+        .unwrap();
+
+    // Values with a fractional part, or integers too large for a float to
+    // represent exactly, are not converted and leave the column unchanged.
+    batch
+        .coerce_column("f64_fract", InfluxFieldType::Integer, policy)
+        .unwrap_err();
+    batch
+        .coerce_column("i64_big", InfluxFieldType::Float, policy)
+        .unwrap_err();
+    assert_eq!(
+        batch.column("f64_fract").unwrap().influx_type(),
+        InfluxColumnType::Field(InfluxFieldType::Float)
+    );
+    assert_eq!(
+        batch.column("i64_big").unwrap().influx_type(),
+        InfluxColumnType::Field(InfluxFieldType::Integer)
+    );
+
+    batch
+        .coerce_column("missing", InfluxFieldType::Integer, policy)
+        .unwrap_err();
+}
+
+#[test]
+fn test_coerce_strict() {
+    let mut batch = batch();
+
+    batch
+        .coerce_column("i64", InfluxFieldType::Float, TypeCoercionPolicy::Strict)
+        .unwrap_err();
+}
@@ -14,7 +14,7 @@
 )]
 
 use hashbrown::{hash_map::Entry, HashMap, HashSet};
-use influxdb_line_protocol::{parse_lines, FieldValue, ParsedLine};
+use influxdb_line_protocol::{parse_lines, split_lines, FieldValue, ParsedLine};
 use mutable_batch::writer::Writer;
 use mutable_batch::MutableBatch;
 use snafu::{ResultExt, Snafu};
@@ -51,6 +51,15 @@ pub struct PayloadStatistics {
     pub num_lines: usize,
 }
 
+/// A line skipped by [`LinesConverter::write_lp_partial()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedLine {
+    /// The 1-based number of the line within the payload
+    pub line: usize,
+    /// Why the line was skipped
+    pub reason: String,
+}
+
 /// Converts line protocol to a set of [`MutableBatch`]
 #[derive(Debug)]
 pub struct LinesConverter {
@@ -101,31 +110,96 @@ impl LinesConverter {
     ///
     pub fn write_lp(&mut self, lines: &str) -> Result<()> {
         for (line_idx, maybe_line) in parse_lines(lines).enumerate() {
-            let mut line = maybe_line.context(LineProtocolSnafu { line: line_idx + 1 })?;
+            let line = maybe_line.context(LineProtocolSnafu { line: line_idx + 1 })?;
+            self.write_parsed_line(line, line_idx + 1)?;
+        }
+        Ok(())
+    }
 
-            if let Some(t) = line.timestamp.as_mut() {
-                *t = t
-                    .checked_mul(self.timestamp_base)
-                    .ok_or(Error::TimestampOverflow)?;
-            }
+    /// Write some line protocol data, skipping the lines that cannot be parsed
+    /// or written instead of failing the whole payload.
+    ///
+    /// Returns the skipped lines in payload order. Unlike the line numbers in
+    /// the errors of [`Self::write_lp()`], the line numbers count every line
+    /// of the payload, including empty lines and comments.
+    pub fn write_lp_partial(&mut self, lines: &str) -> Vec<RejectedLine> {
+        self.write_lp_partial_with(lines, |_| Ok(()))
+    }
 
-            self.stats.num_lines += 1;
-            self.stats.num_fields += line.field_set.len();
+    /// Like [`Self::write_lp_partial()`], but additionally skips the lines for
+    /// which `check` returns an error, using the error as the reason.
+    pub fn write_lp_partial_with<F>(&mut self, lines: &str, mut check: F) -> Vec<RejectedLine>
+    where
+        F: FnMut(&ParsedLine<'_>) -> Result<(), String>,
+    {
+        let mut rejected = vec![];
+        let mut line_number = 1;
+
+        for raw_line in split_lines(lines) {
+            let this_line = line_number;
+            // Quoted string field values may contain newlines
+            line_number += 1 + raw_line.matches('\n').count();
+
+            // Yields nothing for empty lines and comments
+            for maybe_line in parse_lines(raw_line) {
+                let res = maybe_line
+                    .map_err(|e| e.to_string())
+                    .and_then(|line| check(&line).map(|_| line))
+                    .and_then(|line| {
+                        self.write_parsed_line(line, this_line)
+                            .map_err(|e| match e {
+                                Error::Write { source, .. } => source.to_string(),
+                                e => e.to_string(),
+                            })
+                    });
+
+                if let Err(reason) = res {
+                    rejected.push(RejectedLine {
+                        line: this_line,
+                        reason,
+                    });
+                }
+            }
+        }
 
-            let measurement = line.series.measurement.as_str();
+        rejected
+    }
 
-            let (_, batch) = self
-                .batches
-                .raw_entry_mut()
-                .from_key(measurement)
-                .or_insert_with(|| (measurement.to_string(), MutableBatch::new()));
+    /// Write a single parsed line, leaving the batches unchanged on error.
+    fn write_parsed_line(&mut self, mut line: ParsedLine<'_>, line_number: usize) -> Result<()> {
+        if let Some(t) = line.timestamp.as_mut() {
+            *t = t
+                .checked_mul(self.timestamp_base)
+                .ok_or(Error::TimestampOverflow)?;
+        }
 
-            // TODO: Reuse writer
-            let mut writer = Writer::new(batch, 1);
-            write_line(&mut writer, &line, self.default_time)
-                .context(WriteSnafu { line: line_idx + 1 })?;
-            writer.commit();
+        let measurement = line.series.measurement.as_str();
+
+        let (_, batch) = self
+            .batches
+            .raw_entry_mut()
+            .from_key(measurement)
+            .or_insert_with(|| (measurement.to_string(), MutableBatch::new()));
+
+        // TODO: Reuse writer
+        let mut writer = Writer::new(batch, 1);
+        if let Err(source) = write_line(&mut writer, &line, self.default_time) {
+            // Dropping the writer rolls back the partial write, don't leave an
+            // empty batch behind for a table that only had invalid lines.
+            drop(writer);
+            if batch.rows() == 0 {
+                self.batches.remove(measurement);
+            }
+            return Err(Error::Write {
+                source,
+                line: line_number,
+            });
         }
+        writer.commit();
+
+        self.stats.num_lines += 1;
+        self.stats.num_fields += line.field_set.len();
+
         Ok(())
     }
 
@@ -445,6 +519,74 @@ m b=t 1639612800000000000
         assert!(!u.is_valid(2));
     }
 
+    #[test]
+    fn test_write_lp_partial() {
+        let lp = r#"cpu val=1i 1
+
+cpu val= 2
+cpu val=3.0 3
+# a comment
+mem,tag=a,tag=b val=4i 4
+mem s="multi
+line" 5
+cpu val=6i 99999999999999999
+cpu val=7i 7"#;
+
+        let mut converter = LinesConverter::new(5);
+        converter.set_timestamp_base(1_000);
+        let rejected = converter.write_lp_partial(lp);
+
+        assert_eq!(
+            rejected.iter().map(|r| r.line).collect::<Vec<_>>(),
+            [3, 4, 6, 9]
+        );
+        assert_eq!(
+            rejected[2].reason,
+            "the tag 'tag' is specified more than once with conflicting values"
+        );
+        assert_eq!(rejected[3].reason, "timestamp overflows i64");
+
+        let (batches, stats) = converter.finish().unwrap();
+        assert_eq!(stats.num_lines, 3);
+        assert_batches_eq!(
+            &[
+                "+-----------------------------+-----+",
+                "| time                        | val |",
+                "+-----------------------------+-----+",
+                "| 1970-01-01T00:00:00.000001Z | 1   |",
+                "| 1970-01-01T00:00:00.000007Z | 7   |",
+                "+-----------------------------+-----+",
+            ],
+            &[batches["cpu"].to_arrow(Projection::All).unwrap()]
+        );
+        assert_eq!(batches["mem"].rows(), 1);
+    }
+
+    #[test]
+    fn test_write_lp_partial_with() {
+        let lp = "cpu val=1i 1\nmem val=2i 2\ncpu val=3i 3";
+
+        let mut converter = LinesConverter::new(5);
+        let rejected =
+            converter.write_lp_partial_with(lp, |line| match line.series.measurement.as_str() {
+                "mem" => Err("no mem".to_string()),
+                _ => Ok(()),
+            });
+
+        assert_eq!(
+            rejected,
+            [RejectedLine {
+                line: 2,
+                reason: "no mem".to_string()
+            }]
+        );
+
+        // A table with only rejected lines is not created
+        let (batches, _) = converter.finish().unwrap();
+        assert_eq!(batches.keys().collect::<Vec<_>>(), ["cpu"]);
+        assert_eq!(batches["cpu"].rows(), 2);
+    }
+
     // https://github.com/influxdata/influxdb_iox/issues/4326
     mod issue4326 {
         use super::*;
@@ -0,0 +1,944 @@
+//! Code to convert an OpenTelemetry (OTLP) [`ExportMetricsServiceRequest`] to
+//! [`MutableBatch`].
+//!
+//! Each OTLP metric is mapped to IOx as follows:
+//!
+//!   * The metric name is the measurement / table name.
+//!   * Resource attributes, instrumentation scope attributes and data point
+//!     attributes are tags, in that order of precedence (a data point attribute
+//!     overrides a scope attribute with the same key, which in turn overrides a
+//!     resource attribute). The instrumentation scope name and version are
+//!     added as the [`SCOPE_NAME_TAG`] and [`SCOPE_VERSION_TAG`] tags.
+//!     Non-string attribute values are converted to their string
+//!     representation, and attributes with an empty value are skipped.
+//!   * Each data point is a single row, timestamped with the data point
+//!     `time_unix_nano`.
+//!
+//! The fields of each row depend on the metric type:
+//!
+//!   * Gauges and sums store the data point value in a field named `value`,
+//!     which is a float or an integer field depending on the data point.
+//!   * Histograms store the `count` (unsigned integer), `sum`, `min` and `max`
+//!     (float) of each data point in the metric table. The bucket counts are
+//!     written to a separate `<name>_bucket` table as cumulative counts, one
+//!     row per bucket, with the bucket upper bound stored in the `le` tag and
+//!     the cumulative count in the `count` field - the same representation
+//!     used by Prometheus. The final bucket has an upper bound of `+Inf`.
+//!   * Exponential histograms are written in the same way as histograms, with
+//!     the additional `zero_count` (unsigned integer) and `scale` (integer)
+//!     fields. The exponential buckets are converted to cumulative `le`
+//!     buckets, covering the negative buckets, the zero bucket and the positive
+//!     buckets in ascending order.
+//!
+//! Summary metrics and metrics without any data are not supported, and are
+//! skipped (and counted in [`PayloadStatistics::num_skipped_metrics`]).
+//!
+//! For example, a gauge `memory_usage` with the resource attribute
+//! `service.name="api"` and a single data point with the value `42.5` is
+//! equivalent to the line protocol:
+//!
+//! ```text
+//! memory_usage,service.name=api value=42.5 1000000000
+//! ```
+//!
+//! [`ExportMetricsServiceRequest`]:
+//!     generated_types::opentelemetry::proto::collector::metrics::v1::ExportMetricsServiceRequest
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro
+)]
+
+use std::collections::BTreeMap;
+
+use generated_types::opentelemetry::proto::{
+    collector::metrics::v1::ExportMetricsServiceRequest,
+    common::v1::{any_value, AnyValue, KeyValue},
+    metrics::v1::{
+        exponential_histogram_data_point::Buckets, metric, number_data_point,
+        ExponentialHistogramDataPoint, HistogramDataPoint, NumberDataPoint,
+    },
+};
+use hashbrown::HashMap;
+use mutable_batch::{writer::Writer, MutableBatch};
+use schema::TIME_COLUMN_NAME;
+use snafu::{ResultExt, Snafu};
+
+/// The tag holding the instrumentation scope name.
+pub const SCOPE_NAME_TAG: &str = "otel_scope_name";
+
+/// The tag holding the instrumentation scope version.
+pub const SCOPE_VERSION_TAG: &str = "otel_scope_version";
+
+/// The name of the field gauge and sum values are stored in.
+pub const VALUE_FIELD_NAME: &str = "value";
+
+/// The suffix appended to the metric name to form the name of the table
+/// histogram buckets are stored in.
+pub const BUCKET_TABLE_SUFFIX: &str = "_bucket";
+
+/// The tag holding the upper bound of a histogram bucket.
+pub const BUCKET_BOUND_TAG: &str = "le";
+
+/// The field names used by histogram metrics.
+const COUNT_FIELD_NAME: &str = "count";
+const SUM_FIELD_NAME: &str = "sum";
+const MIN_FIELD_NAME: &str = "min";
+const MAX_FIELD_NAME: &str = "max";
+const ZERO_COUNT_FIELD_NAME: &str = "zero_count";
+const SCALE_FIELD_NAME: &str = "scale";
+
+/// Error type for OTLP metrics conversion
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("metric {} has no name", index))]
+    MissingMetricName { index: usize },
+
+    #[snafu(display("metric {} uses reserved attribute name {}", metric, name))]
+    ReservedAttribute { metric: String, name: String },
+
+    #[snafu(display("metric {} data point has no value", metric))]
+    MissingValue { metric: String },
+
+    #[snafu(display(
+        "metric {} histogram data point has {} bucket counts for {} explicit bounds",
+        metric,
+        counts,
+        bounds
+    ))]
+    InvalidBuckets {
+        metric: String,
+        counts: usize,
+        bounds: usize,
+    },
+
+    #[snafu(display("error writing metric {}: {}", metric, source))]
+    Write {
+        source: mutable_batch::writer::Error,
+        metric: String,
+    },
+
+    #[snafu(display("empty write payload"))]
+    EmptyPayload,
+
+    #[snafu(display("timestamp overflows i64"))]
+    TimestampOverflow,
+}
+
+/// Result type for OTLP metrics conversion
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Statistics about an OTLP metrics payload
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PayloadStatistics {
+    /// The number of metrics converted
+    pub num_metrics: usize,
+    /// The number of data points converted
+    pub num_data_points: usize,
+    /// The number of unsupported or empty metrics that were skipped
+    pub num_skipped_metrics: usize,
+}
+
+/// Converts the provided [`ExportMetricsServiceRequest`] to a set of
+/// [`MutableBatch`] keyed by measurement name, and a set of statistics about
+/// the converted payload.
+///
+/// If the request contains no supported data points at all,
+/// [`Error::EmptyPayload`] is returned.
+pub fn export_request_to_batches(
+    req: &ExportMetricsServiceRequest,
+) -> Result<(HashMap<String, MutableBatch>, PayloadStatistics)> {
+    let mut converter = Converter::default();
+    let mut index = 0;
+
+    for resource_metrics in &req.resource_metrics {
+        let mut resource_tags = Tags::default();
+        if let Some(resource) = &resource_metrics.resource {
+            resource_tags.extend(&resource.attributes);
+        }
+
+        for scope_metrics in &resource_metrics.scope_metrics {
+            let mut scope_tags = resource_tags.clone();
+            if let Some(scope) = &scope_metrics.scope {
+                scope_tags.insert(SCOPE_NAME_TAG, scope.name.clone());
+                scope_tags.insert(SCOPE_VERSION_TAG, scope.version.clone());
+                scope_tags.extend(&scope.attributes);
+            }
+
+            for metric in &scope_metrics.metrics {
+                if metric.name.is_empty() {
+                    return MissingMetricNameSnafu { index }.fail();
+                }
+                index += 1;
+
+                let name = metric.name.as_str();
+                let points = match &metric.data {
+                    Some(metric::Data::Gauge(v)) => {
+                        converter.write_numbers(name, &scope_tags, &v.data_points)?
+                    }
+                    Some(metric::Data::Sum(v)) => {
+                        converter.write_numbers(name, &scope_tags, &v.data_points)?
+                    }
+                    Some(metric::Data::Histogram(v)) => {
+                        converter.write_histograms(name, &scope_tags, &v.data_points)?
+                    }
+                    Some(metric::Data::ExponentialHistogram(v)) => {
+                        converter.write_exponential_histograms(name, &scope_tags, &v.data_points)?
+                    }
+                    Some(metric::Data::Summary(_)) | None => 0,
+                };
+
+                if points == 0 {
+                    converter.stats.num_skipped_metrics += 1;
+                    continue;
+                }
+
+                converter.stats.num_metrics += 1;
+                converter.stats.num_data_points += points;
+            }
+        }
+    }
+
+    if converter.batches.is_empty() {
+        return Err(Error::EmptyPayload);
+    }
+
+    Ok((converter.batches, converter.stats))
+}
+
+/// The set of tags for a row, keyed by tag name.
+///
+/// Attributes added later override attributes with the same key added
+/// earlier.
+#[derive(Debug, Clone, Default)]
+struct Tags<'a>(BTreeMap<&'a str, String>);
+
+impl<'a> Tags<'a> {
+    fn insert(&mut self, key: &'a str, value: String) {
+        if !value.is_empty() {
+            self.0.insert(key, value);
+        }
+    }
+
+    fn extend(&mut self, attributes: &'a [KeyValue]) {
+        for kv in attributes {
+            if let Some(value) = &kv.value {
+                self.insert(kv.key.as_str(), any_value_to_string(value));
+            }
+        }
+    }
+
+    /// Returns the tags for a data point with the provided `attributes`.
+    fn with(&self, attributes: &'a [KeyValue]) -> Self {
+        let mut tags = self.clone();
+        tags.extend(attributes);
+        tags
+    }
+
+    /// Returns an error if any of the tags collide with the time column or
+    /// one of the provided field names.
+    fn check_reserved(&self, metric: &str, fields: &[&str]) -> Result<()> {
+        match self
+            .0
+            .keys()
+            .find(|k| **k == TIME_COLUMN_NAME || fields.contains(*k))
+        {
+            Some(name) => ReservedAttributeSnafu {
+                metric,
+                name: *name,
+            }
+            .fail(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Render an attribute value as a tag value.
+fn any_value_to_string(v: &AnyValue) -> String {
+    match &v.value {
+        Some(any_value::Value::StringValue(v)) => v.clone(),
+        Some(any_value::Value::BoolValue(v)) => v.to_string(),
+        Some(any_value::Value::IntValue(v)) => v.to_string(),
+        Some(any_value::Value::DoubleValue(v)) => v.to_string(),
+        Some(any_value::Value::BytesValue(v)) => v.iter().map(|b| format!("{b:02x}")).collect(),
+        Some(any_value::Value::ArrayValue(v)) => {
+            let values = v.values.iter().map(any_value_to_string).collect::<Vec<_>>();
+            format!("[{}]", values.join(","))
+        }
+        Some(any_value::Value::KvlistValue(v)) => {
+            let values = v
+                .values
+                .iter()
+                .map(|kv| {
+                    let value = kv.value.as_ref().map(any_value_to_string);
+                    format!("{}={}", kv.key, value.unwrap_or_default())
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", values.join(","))
+        }
+        None => String::new(),
+    }
+}
+
+/// Render a histogram bucket upper bound as a tag value.
+fn bound_to_string(v: f64) -> String {
+    if v == f64::INFINITY {
+        return "+Inf".to_string();
+    }
+    v.to_string()
+}
+
+/// Convert an OTLP nanosecond timestamp to an IOx timestamp.
+fn timestamp(v: u64) -> Result<i64> {
+    i64::try_from(v).map_err(|_| Error::TimestampOverflow)
+}
+
+/// A single field value.
+#[derive(Debug, Clone, Copy)]
+enum FieldValue {
+    F64(f64),
+    I64(i64),
+    U64(u64),
+}
+
+#[derive(Debug, Default)]
+struct Converter {
+    batches: HashMap<String, MutableBatch>,
+    stats: PayloadStatistics,
+}
+
+impl Converter {
+    fn batch(&mut self, table: &str) -> &mut MutableBatch {
+        let (_, batch) = self
+            .batches
+            .raw_entry_mut()
+            .from_key(table)
+            .or_insert_with(|| (table.to_string(), MutableBatch::new()));
+        batch
+    }
+
+    /// Write a single row to `table`, reverting any changes on error.
+    fn write_row(
+        &mut self,
+        metric: &str,
+        table: &str,
+        tags: &Tags<'_>,
+        fields: &[(&str, Option<FieldValue>)],
+        time: i64,
+    ) -> Result<()> {
+        let batch = self.batch(table);
+        let mut writer = Writer::new(batch, 1);
+
+        let res = (|| {
+            for (key, value) in &tags.0 {
+                writer.write_tag(key, None, std::iter::once(value.as_str()))?;
+            }
+
+            for (name, value) in fields {
+                match value {
+                    Some(FieldValue::F64(v)) => {
+                        writer.write_f64(name, None, std::iter::once(*v))?
+                    }
+                    Some(FieldValue::I64(v)) => {
+                        writer.write_i64(name, None, std::iter::once(*v))?
+                    }
+                    Some(FieldValue::U64(v)) => {
+                        writer.write_u64(name, None, std::iter::once(*v))?
+                    }
+                    None => {}
+                }
+            }
+
+            writer.write_time(TIME_COLUMN_NAME, std::iter::once(time))
+        })();
+
+        res.context(WriteSnafu { metric })?;
+        writer.commit();
+        Ok(())
+    }
+
+    /// Write cumulative bucket counts to the `<metric>_bucket` table.
+    fn write_buckets(
+        &mut self,
+        metric: &str,
+        tags: &Tags<'_>,
+        buckets: impl IntoIterator<Item = (f64, u64)>,
+        time: i64,
+    ) -> Result<()> {
+        let table = format!("{metric}{BUCKET_TABLE_SUFFIX}");
+        let mut cumulative = 0_u64;
+
+        for (bound, count) in buckets {
+            cumulative = cumulative.saturating_add(count);
+
+            let mut tags = tags.clone();
+            tags.insert(BUCKET_BOUND_TAG, bound_to_string(bound));
+            self.write_row(
+                metric,
+                &table,
+                &tags,
+                &[(COUNT_FIELD_NAME, Some(FieldValue::U64(cumulative)))],
+                time,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_numbers(
+        &mut self,
+        metric: &str,
+        tags: &Tags<'_>,
+        points: &[NumberDataPoint],
+    ) -> Result<usize> {
+        for point in points {
+            let value = match point.value {
+                Some(number_data_point::Value::AsDouble(v)) => FieldValue::F64(v),
+                Some(number_data_point::Value::AsInt(v)) => FieldValue::I64(v),
+                None => return MissingValueSnafu { metric }.fail(),
+            };
+
+            let tags = tags.with(&point.attributes);
+            tags.check_reserved(metric, &[VALUE_FIELD_NAME])?;
+
+            self.write_row(
+                metric,
+                metric,
+                &tags,
+                &[(VALUE_FIELD_NAME, Some(value))],
+                timestamp(point.time_unix_nano)?,
+            )?;
+        }
+
+        Ok(points.len())
+    }
+
+    fn write_histograms(
+        &mut self,
+        metric: &str,
+        tags: &Tags<'_>,
+        points: &[HistogramDataPoint],
+    ) -> Result<usize> {
+        for point in points {
+            // The bucket counts are optional, but if present there must be one
+            // more count than there are explicit bounds.
+            if !point.bucket_counts.is_empty()
+                && point.bucket_counts.len() != point.explicit_bounds.len() + 1
+            {
+                return InvalidBucketsSnafu {
+                    metric,
+                    counts: point.bucket_counts.len(),
+                    bounds: point.explicit_bounds.len(),
+                }
+                .fail();
+            }
+
+            let tags = tags.with(&point.attributes);
+            tags.check_reserved(
+                metric,
+                &[
+                    COUNT_FIELD_NAME,
+                    SUM_FIELD_NAME,
+                    MIN_FIELD_NAME,
+                    MAX_FIELD_NAME,
+                    BUCKET_BOUND_TAG,
+                ],
+            )?;
+
+            let time = timestamp(point.time_unix_nano)?;
+            self.write_row(
+                metric,
+                metric,
+                &tags,
+                &[
+                    (COUNT_FIELD_NAME, Some(FieldValue::U64(point.count))),
+                    (SUM_FIELD_NAME, point.sum.map(FieldValue::F64)),
+                    (MIN_FIELD_NAME, point.min.map(FieldValue::F64)),
+                    (MAX_FIELD_NAME, point.max.map(FieldValue::F64)),
+                ],
+                time,
+            )?;
+
+            let bounds = point
+                .explicit_bounds
+                .iter()
+                .copied()
+                .chain(std::iter::once(f64::INFINITY));
+            self.write_buckets(
+                metric,
+                &tags,
+                bounds.zip(point.bucket_counts.iter().copied()),
+                time,
+            )?;
+        }
+
+        Ok(points.len())
+    }
+
+    fn write_exponential_histograms(
+        &mut self,
+        metric: &str,
+        tags: &Tags<'_>,
+        points: &[ExponentialHistogramDataPoint],
+    ) -> Result<usize> {
+        for point in points {
+            let tags = tags.with(&point.attributes);
+            tags.check_reserved(
+                metric,
+                &[
+                    COUNT_FIELD_NAME,
+                    SUM_FIELD_NAME,
+                    MIN_FIELD_NAME,
+                    MAX_FIELD_NAME,
+                    ZERO_COUNT_FIELD_NAME,
+                    SCALE_FIELD_NAME,
+                    BUCKET_BOUND_TAG,
+                ],
+            )?;
+
+            let time = timestamp(point.time_unix_nano)?;
+            self.write_row(
+                metric,
+                metric,
+                &tags,
+                &[
+                    (COUNT_FIELD_NAME, Some(FieldValue::U64(point.count))),
+                    (SUM_FIELD_NAME, point.sum.map(FieldValue::F64)),
+                    (MIN_FIELD_NAME, point.min.map(FieldValue::F64)),
+                    (MAX_FIELD_NAME, point.max.map(FieldValue::F64)),
+                    (
+                        ZERO_COUNT_FIELD_NAME,
+                        Some(FieldValue::U64(point.zero_count)),
+                    ),
+                    (
+                        SCALE_FIELD_NAME,
+                        Some(FieldValue::I64(i64::from(point.scale))),
+                    ),
+                ],
+                time,
+            )?;
+
+            self.write_buckets(metric, &tags, exponential_buckets(point), time)?;
+        }
+
+        Ok(points.len())
+    }
+}
+
+/// Convert the exponential buckets of `point` to a list of (upper bound,
+/// count) buckets in ascending bound order, terminated by a `+Inf` bucket
+/// holding any remaining observations.
+///
+/// A positive bucket at index `i` covers the range `(base^i, base^(i+1)]`, and
+/// a negative bucket at index `i` covers the range `[-base^(i+1), -base^i)`,
+/// where `base = 2^(2^-scale)`.
+fn exponential_buckets(point: &ExponentialHistogramDataPoint) -> Vec<(f64, u64)> {
+    let base = 2_f64.powf(2_f64.powi(-point.scale));
+    let empty = Buckets::default();
+    let negative = point.negative.as_ref().unwrap_or(&empty);
+    let positive = point.positive.as_ref().unwrap_or(&empty);
+
+    let mut buckets =
+        Vec::with_capacity(negative.bucket_counts.len() + positive.bucket_counts.len() + 2);
+
+    // Negative buckets, from the largest magnitude to the smallest.
+    for (i, count) in negative.bucket_counts.iter().enumerate().rev() {
+        let index = negative.offset as f64 + i as f64;
+        buckets.push((-base.powf(index), *count));
+    }
+
+    // The zero bucket.
+    buckets.push((point.zero_threshold, point.zero_count));
+
+    // Positive buckets, from the smallest to the largest.
+    for (i, count) in positive.bucket_counts.iter().enumerate() {
+        let index = positive.offset as f64 + i as f64;
+        buckets.push((base.powf(index + 1.0), *count));
+    }
+
+    // Any observations not accounted for by the buckets.
+    let total = buckets
+        .iter()
+        .map(|(_, c)| *c)
+        .fold(0_u64, u64::saturating_add);
+    buckets.push((f64::INFINITY, point.count.saturating_sub(total)));
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_sorted_eq;
+    use assert_matches::assert_matches;
+    use generated_types::opentelemetry::proto::{
+        common::v1::InstrumentationScope,
+        metrics::v1::{
+            ExponentialHistogram, Gauge, Histogram, Metric, ResourceMetrics, ScopeMetrics, Sum,
+            Summary,
+        },
+        resource::v1::Resource,
+    };
+    use schema::Projection;
+
+    fn kv(key: &str, value: any_value::Value) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue { value: Some(value) }),
+        }
+    }
+
+    fn string_kv(key: &str, value: &str) -> KeyValue {
+        kv(key, any_value::Value::StringValue(value.to_string()))
+    }
+
+    fn number(attributes: Vec<KeyValue>, value: number_data_point::Value) -> NumberDataPoint {
+        NumberDataPoint {
+            attributes,
+            time_unix_nano: 1_000_000_000,
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn request(metrics: Vec<Metric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![
+                        string_kv("service.name", "api"),
+                        string_kv("host", "resource"),
+                    ],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Some(InstrumentationScope {
+                        name: "bananas".to_string(),
+                        version: "".to_string(),
+                        attributes: vec![string_kv("host", "scope")],
+                        dropped_attributes_count: 0,
+                    }),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    fn metric(name: &str, data: metric::Data) -> Metric {
+        Metric {
+            name: name.to_string(),
+            description: String::new(),
+            unit: String::new(),
+            data: Some(data),
+        }
+    }
+
+    #[test]
+    fn test_gauge_and_sum() {
+        let req = request(vec![
+            metric(
+                "memory",
+                metric::Data::Gauge(Gauge {
+                    data_points: vec![
+                        number(vec![], number_data_point::Value::AsDouble(42.5)),
+                        number(
+                            vec![
+                                string_kv("host", "point"),
+                                kv("ok", any_value::Value::BoolValue(true)),
+                            ],
+                            number_data_point::Value::AsDouble(1.0),
+                        ),
+                    ],
+                }),
+            ),
+            metric(
+                "requests",
+                metric::Data::Sum(Sum {
+                    data_points: vec![number(
+                        vec![kv("code", any_value::Value::IntValue(200))],
+                        number_data_point::Value::AsInt(12),
+                    )],
+                    aggregation_temporality: 2,
+                    is_monotonic: true,
+                }),
+            ),
+            metric("quantiles", metric::Data::Summary(Summary::default())),
+        ]);
+
+        let (batches, stats) = export_request_to_batches(&req).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(stats.num_metrics, 2);
+        assert_eq!(stats.num_data_points, 3);
+        assert_eq!(stats.num_skipped_metrics, 1);
+
+        assert_batches_sorted_eq!(
+            &[
+                "+-------+------+-----------------+--------------+----------------------+-------+",
+                "| host  | ok   | otel_scope_name | service.name | time                 | value |",
+                "+-------+------+-----------------+--------------+----------------------+-------+",
+                "| point | true | bananas         | api          | 1970-01-01T00:00:01Z | 1.0   |",
+                "| scope |      | bananas         | api          | 1970-01-01T00:00:01Z | 42.5  |",
+                "+-------+------+-----------------+--------------+----------------------+-------+",
+            ],
+            &[batches["memory"].to_arrow(Projection::All).unwrap()]
+        );
+
+        assert_batches_sorted_eq!(
+            &[
+                "+------+-------+-----------------+--------------+----------------------+-------+",
+                "| code | host  | otel_scope_name | service.name | time                 | value |",
+                "+------+-------+-----------------+--------------+----------------------+-------+",
+                "| 200  | scope | bananas         | api          | 1970-01-01T00:00:01Z | 12    |",
+                "+------+-------+-----------------+--------------+----------------------+-------+",
+            ],
+            &[batches["requests"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let req = request(vec![metric(
+            "latency",
+            metric::Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    time_unix_nano: 1_000_000_000,
+                    count: 6,
+                    sum: Some(12.5),
+                    bucket_counts: vec![1, 2, 3],
+                    explicit_bounds: vec![0.5, 1.0],
+                    max: Some(4.0),
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+            }),
+        )]);
+
+        let (batches, stats) = export_request_to_batches(&req).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(stats.num_data_points, 1);
+
+        assert_batches_sorted_eq!(
+            &[
+                "+-------+-------+-----+-----------------+--------------+------+----------------------+",
+                "| count | host  | max | otel_scope_name | service.name | sum  | time                 |",
+                "+-------+-------+-----+-----------------+--------------+------+----------------------+",
+                "| 6     | scope | 4.0 | bananas         | api          | 12.5 | 1970-01-01T00:00:01Z |",
+                "+-------+-------+-----+-----------------+--------------+------+----------------------+",
+            ],
+            &[batches["latency"].to_arrow(Projection::All).unwrap()]
+        );
+
+        assert_batches_sorted_eq!(
+            &[
+                "+-------+-------+------+-----------------+--------------+----------------------+",
+                "| count | host  | le   | otel_scope_name | service.name | time                 |",
+                "+-------+-------+------+-----------------+--------------+----------------------+",
+                "| 1     | scope | 0.5  | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "| 3     | scope | 1    | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "| 6     | scope | +Inf | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "+-------+-------+------+-----------------+--------------+----------------------+",
+            ],
+            &[batches["latency_bucket"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_histogram_invalid_buckets() {
+        let req = request(vec![metric(
+            "latency",
+            metric::Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    count: 3,
+                    bucket_counts: vec![1, 2],
+                    explicit_bounds: vec![0.5, 1.0],
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+            }),
+        )]);
+
+        assert_matches!(
+            export_request_to_batches(&req),
+            Err(Error::InvalidBuckets {
+                counts: 2,
+                bounds: 2,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_exponential_histogram() {
+        let req = request(vec![metric(
+            "latency",
+            metric::Data::ExponentialHistogram(ExponentialHistogram {
+                data_points: vec![ExponentialHistogramDataPoint {
+                    time_unix_nano: 1_000_000_000,
+                    count: 8,
+                    scale: 0,
+                    zero_count: 1,
+                    negative: Some(Buckets {
+                        offset: 0,
+                        bucket_counts: vec![2],
+                    }),
+                    positive: Some(Buckets {
+                        offset: 1,
+                        bucket_counts: vec![3, 1],
+                    }),
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+            }),
+        )]);
+
+        let (batches, _) = export_request_to_batches(&req).unwrap();
+
+        assert_batches_sorted_eq!(
+            &[
+                "+-------+-------+-----------------+-------+--------------+----------------------+------------+",
+                "| count | host  | otel_scope_name | scale | service.name | time                 | zero_count |",
+                "+-------+-------+-----------------+-------+--------------+----------------------+------------+",
+                "| 8     | scope | bananas         | 0     | api          | 1970-01-01T00:00:01Z | 1          |",
+                "+-------+-------+-----------------+-------+--------------+----------------------+------------+",
+            ],
+            &[batches["latency"].to_arrow(Projection::All).unwrap()]
+        );
+
+        // base = 2, so the negative bucket 0 covers [-2, -1), the positive
+        // bucket 1 covers (2, 4] and the positive bucket 2 covers (4, 8].
+        assert_batches_sorted_eq!(
+            &[
+                "+-------+-------+------+-----------------+--------------+----------------------+",
+                "| count | host  | le   | otel_scope_name | service.name | time                 |",
+                "+-------+-------+------+-----------------+--------------+----------------------+",
+                "| 2     | scope | -1   | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "| 3     | scope | 0    | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "| 6     | scope | 4    | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "| 7     | scope | 8    | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "| 8     | scope | +Inf | bananas         | api          | 1970-01-01T00:00:01Z |",
+                "+-------+-------+------+-----------------+--------------+----------------------+",
+            ],
+            &[batches["latency_bucket"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let req = request(vec![metric("empty", metric::Data::Gauge(Gauge::default()))]);
+        assert_matches!(export_request_to_batches(&req), Err(Error::EmptyPayload));
+
+        let req = ExportMetricsServiceRequest::default();
+        assert_matches!(export_request_to_batches(&req), Err(Error::EmptyPayload));
+    }
+
+    #[test]
+    fn test_missing_metric_name() {
+        let req = request(vec![metric("", metric::Data::Gauge(Gauge::default()))]);
+        assert_matches!(
+            export_request_to_batches(&req),
+            Err(Error::MissingMetricName { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_missing_value() {
+        let req = request(vec![metric(
+            "memory",
+            metric::Data::Gauge(Gauge {
+                data_points: vec![NumberDataPoint::default()],
+            }),
+        )]);
+        assert_matches!(
+            export_request_to_batches(&req),
+            Err(Error::MissingValue { metric }) => {
+                assert_eq!(metric, "memory");
+            }
+        );
+    }
+
+    #[test]
+    fn test_reserved_attribute() {
+        let req = request(vec![metric(
+            "memory",
+            metric::Data::Gauge(Gauge {
+                data_points: vec![number(
+                    vec![string_kv("value", "bananas")],
+                    number_data_point::Value::AsDouble(1.0),
+                )],
+            }),
+        )]);
+        assert_matches!(
+            export_request_to_batches(&req),
+            Err(Error::ReservedAttribute { name, .. }) => {
+                assert_eq!(name, "value");
+            }
+        );
+    }
+
+    #[test]
+    fn test_timestamp_overflow() {
+        let req = request(vec![metric(
+            "memory",
+            metric::Data::Gauge(Gauge {
+                data_points: vec![NumberDataPoint {
+                    time_unix_nano: u64::MAX,
+                    value: Some(number_data_point::Value::AsDouble(1.0)),
+                    ..Default::default()
+                }],
+            }),
+        )]);
+        assert_matches!(
+            export_request_to_batches(&req),
+            Err(Error::TimestampOverflow)
+        );
+    }
+
+    #[test]
+    fn test_type_conflict() {
+        let req = request(vec![metric(
+            "memory",
+            metric::Data::Gauge(Gauge {
+                data_points: vec![
+                    number(vec![], number_data_point::Value::AsDouble(1.0)),
+                    number(vec![], number_data_point::Value::AsInt(1)),
+                ],
+            }),
+        )]);
+        assert_matches!(
+            export_request_to_batches(&req),
+            Err(Error::Write { metric, .. }) => {
+                assert_eq!(metric, "memory");
+            }
+        );
+    }
+
+    #[test]
+    fn test_any_value_to_string() {
+        let v = AnyValue {
+            value: Some(any_value::Value::ArrayValue(
+                generated_types::opentelemetry::proto::common::v1::ArrayValue {
+                    values: vec![
+                        AnyValue {
+                            value: Some(any_value::Value::DoubleValue(1.5)),
+                        },
+                        AnyValue {
+                            value: Some(any_value::Value::BytesValue(vec![0xde, 0xad])),
+                        },
+                    ],
+                },
+            )),
+        };
+        assert_eq!(any_value_to_string(&v), "[1.5,dead]");
+    }
+}
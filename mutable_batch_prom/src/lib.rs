@@ -0,0 +1,348 @@
+//! Code to convert a Prometheus remote write [`WriteRequest`] to
+//! [`MutableBatch`].
+//!
+//! Each Prometheus time series is mapped to IOx as follows:
+//!
+//!   * The metric name (the `__name__` label) is the measurement / table name.
+//!   * All other labels are tags, using the label name as the tag key. Labels
+//!     with an empty value are equivalent to an absent label, and are skipped.
+//!   * Each sample is a single row, with the sample value stored in a float
+//!     field named `value`.
+//!   * The sample timestamp (milliseconds since the epoch) is converted to
+//!     nanoseconds and stored in the `time` column.
+//!
+//! For example, the sample `http_requests_total{job="api", code="200"} 42` at
+//! timestamp `1000` is equivalent to the line protocol:
+//!
+//! ```text
+//! http_requests_total,code=200,job=api value=42 1000000000
+//! ```
+//!
+//! [`WriteRequest`]: generated_types::prometheus::WriteRequest
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro
+)]
+
+use generated_types::prometheus::{TimeSeries, WriteRequest};
+use hashbrown::{HashMap, HashSet};
+use mutable_batch::{writer::Writer, MutableBatch};
+use schema::TIME_COLUMN_NAME;
+use snafu::{ResultExt, Snafu};
+
+/// The label holding the metric name of a Prometheus time series.
+pub const METRIC_NAME_LABEL: &str = "__name__";
+
+/// The name of the field the sample values are stored in.
+pub const VALUE_FIELD_NAME: &str = "value";
+
+/// Prometheus sample timestamps are in milliseconds.
+const NANOS_PER_MILLI: i64 = 1_000_000;
+
+/// Error type for Prometheus remote write conversion
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display(
+        "time series {} has no metric name ({} label)",
+        index,
+        METRIC_NAME_LABEL
+    ))]
+    MissingMetricName { index: usize },
+
+    #[snafu(display("time series {} contains duplicate label {}", index, name))]
+    DuplicateLabel { index: usize, name: String },
+
+    #[snafu(display("time series {} uses reserved label name {}", index, name))]
+    ReservedLabel { index: usize, name: String },
+
+    #[snafu(display("error writing time series {}: {}", index, source))]
+    Write {
+        source: mutable_batch::writer::Error,
+        index: usize,
+    },
+
+    #[snafu(display("empty write payload"))]
+    EmptyPayload,
+
+    #[snafu(display("timestamp overflows i64"))]
+    TimestampOverflow,
+}
+
+/// Result type for Prometheus remote write conversion
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Statistics about a Prometheus remote write payload
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PayloadStatistics {
+    /// The number of time series
+    pub num_series: usize,
+    /// The number of samples (rows)
+    pub num_samples: usize,
+}
+
+/// Converts the provided [`WriteRequest`] to a set of [`MutableBatch`] keyed
+/// by measurement name, and a set of statistics about the converted payload.
+///
+/// Time series without samples are skipped. If the request contains no
+/// samples at all, [`Error::EmptyPayload`] is returned.
+pub fn write_request_to_batches(
+    req: &WriteRequest,
+) -> Result<(HashMap<String, MutableBatch>, PayloadStatistics)> {
+    let mut batches: HashMap<String, MutableBatch> = HashMap::new();
+    let mut stats = PayloadStatistics::default();
+
+    for (index, series) in req.timeseries.iter().enumerate() {
+        if series.samples.is_empty() {
+            continue;
+        }
+
+        let (name, tags) = split_labels(index, series)?;
+
+        if series
+            .samples
+            .iter()
+            .any(|s| s.timestamp.checked_mul(NANOS_PER_MILLI).is_none())
+        {
+            return Err(Error::TimestampOverflow);
+        }
+
+        let (_, batch) = batches
+            .raw_entry_mut()
+            .from_key(name)
+            .or_insert_with(|| (name.to_string(), MutableBatch::new()));
+
+        write_series(batch, series, &tags).context(WriteSnafu { index })?;
+
+        stats.num_series += 1;
+        stats.num_samples += series.samples.len();
+    }
+
+    if batches.is_empty() {
+        return Err(Error::EmptyPayload);
+    }
+
+    Ok((batches, stats))
+}
+
+/// Split the labels of `series` into the metric name and the set of tag
+/// key/value pairs.
+fn split_labels(index: usize, series: &TimeSeries) -> Result<(&str, Vec<(&str, &str)>)> {
+    let mut name = None;
+    let mut seen = HashSet::with_capacity(series.labels.len());
+    let mut tags = Vec::with_capacity(series.labels.len());
+
+    for label in &series.labels {
+        if !seen.insert(label.name.as_str()) {
+            return DuplicateLabelSnafu {
+                index,
+                name: &label.name,
+            }
+            .fail();
+        }
+
+        match label.name.as_str() {
+            METRIC_NAME_LABEL => name = Some(label.value.as_str()),
+            TIME_COLUMN_NAME | VALUE_FIELD_NAME => {
+                return ReservedLabelSnafu {
+                    index,
+                    name: &label.name,
+                }
+                .fail()
+            }
+            _ if label.value.is_empty() => {}
+            _ => tags.push((label.name.as_str(), label.value.as_str())),
+        }
+    }
+
+    match name {
+        Some(v) if !v.is_empty() => Ok((v, tags)),
+        _ => MissingMetricNameSnafu { index }.fail(),
+    }
+}
+
+/// Write all the samples of `series` to `batch`, reverting any changes on
+/// error.
+fn write_series(
+    batch: &mut MutableBatch,
+    series: &TimeSeries,
+    tags: &[(&str, &str)],
+) -> Result<(), mutable_batch::writer::Error> {
+    let n = series.samples.len();
+    let mut writer = Writer::new(batch, n);
+
+    for (key, value) in tags {
+        writer.write_tag(key, None, std::iter::repeat(*value).take(n))?;
+    }
+
+    writer.write_f64(
+        VALUE_FIELD_NAME,
+        None,
+        series.samples.iter().map(|s| s.value),
+    )?;
+
+    // Timestamps are checked for overflow by the caller.
+    writer.write_time(
+        TIME_COLUMN_NAME,
+        series.samples.iter().map(|s| s.timestamp * NANOS_PER_MILLI),
+    )?;
+
+    writer.commit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use assert_matches::assert_matches;
+    use generated_types::prometheus::{Label, Sample};
+    use schema::Projection;
+
+    fn series(labels: &[(&str, &str)], samples: &[(f64, i64)]) -> TimeSeries {
+        TimeSeries {
+            labels: labels
+                .iter()
+                .map(|(name, value)| Label {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            samples: samples
+                .iter()
+                .map(|(value, timestamp)| Sample {
+                    value: *value,
+                    timestamp: *timestamp,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_basic() {
+        let req = WriteRequest {
+            timeseries: vec![
+                series(
+                    &[
+                        ("__name__", "http_requests_total"),
+                        ("job", "api"),
+                        ("code", "200"),
+                    ],
+                    &[(42.0, 1_000), (43.0, 2_000)],
+                ),
+                series(
+                    &[
+                        ("job", "api"),
+                        ("__name__", "http_requests_total"),
+                        ("env", ""),
+                    ],
+                    &[(1.5, 1_000)],
+                ),
+                series(&[("__name__", "up"), ("job", "api")], &[(1.0, 0)]),
+                series(&[("__name__", "empty")], &[]),
+            ],
+        };
+
+        let (batches, stats) = write_request_to_batches(&req).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(stats.num_series, 3);
+        assert_eq!(stats.num_samples, 4);
+
+        assert_batches_eq!(
+            &[
+                "+------+-----+----------------------+-------+",
+                "| code | job | time                 | value |",
+                "+------+-----+----------------------+-------+",
+                "| 200  | api | 1970-01-01T00:00:01Z | 42.0  |",
+                "| 200  | api | 1970-01-01T00:00:02Z | 43.0  |",
+                "|      | api | 1970-01-01T00:00:01Z | 1.5   |",
+                "+------+-----+----------------------+-------+",
+            ],
+            &[batches["http_requests_total"]
+                .to_arrow(Projection::All)
+                .unwrap()]
+        );
+
+        assert_batches_eq!(
+            &[
+                "+-----+----------------------+-------+",
+                "| job | time                 | value |",
+                "+-----+----------------------+-------+",
+                "| api | 1970-01-01T00:00:00Z | 1.0   |",
+                "+-----+----------------------+-------+",
+            ],
+            &[batches["up"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let req = WriteRequest {
+            timeseries: vec![series(&[("__name__", "up")], &[])],
+        };
+        assert_matches!(write_request_to_batches(&req), Err(Error::EmptyPayload));
+    }
+
+    #[test]
+    fn test_timestamp_overflow() {
+        let req = WriteRequest {
+            timeseries: vec![series(&[("__name__", "up")], &[(1.0, i64::MAX)])],
+        };
+        assert_matches!(
+            write_request_to_batches(&req),
+            Err(Error::TimestampOverflow)
+        );
+    }
+
+    #[test]
+    fn test_missing_metric_name() {
+        let req = WriteRequest {
+            timeseries: vec![
+                series(&[("__name__", "up")], &[(1.0, 0)]),
+                series(&[("job", "api")], &[(1.0, 0)]),
+            ],
+        };
+        assert_matches!(
+            write_request_to_batches(&req),
+            Err(Error::MissingMetricName { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_duplicate_label() {
+        let req = WriteRequest {
+            timeseries: vec![series(
+                &[("__name__", "up"), ("job", "a"), ("job", "b")],
+                &[(1.0, 0)],
+            )],
+        };
+        assert_matches!(
+            write_request_to_batches(&req),
+            Err(Error::DuplicateLabel { index: 0, name }) => {
+                assert_eq!(name, "job");
+            }
+        );
+    }
+
+    #[test]
+    fn test_reserved_label() {
+        let req = WriteRequest {
+            timeseries: vec![series(&[("__name__", "up"), ("value", "a")], &[(1.0, 0)])],
+        };
+        assert_matches!(
+            write_request_to_batches(&req),
+            Err(Error::ReservedLabel { index: 0, name }) => {
+                assert_eq!(name, "value");
+            }
+        );
+    }
+}
@@ -31,7 +31,7 @@ use std::{
     sync::Arc,
 };
 mod batch;
-use batch::convert_to_lines;
+pub use batch::convert_to_lines;
 pub type Result<T = (), E = Error> = std::result::Result<T, E>;
 
 #[derive(Debug, Snafu)]
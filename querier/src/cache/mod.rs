@@ -3,14 +3,20 @@ use ::object_store::ObjectStore;
 use ::parquet_file::storage::{ParquetStorage, StorageId};
 use backoff::BackoffConfig;
 use cache_system::backend::policy::lru::ResourcePool;
-use iox_catalog::interface::Catalog;
+use iox_catalog::{changes::CatalogChange, interface::Catalog};
 use iox_time::TimeProvider;
-use std::sync::Arc;
-use tokio::runtime::Handle;
+use observability_deps::tracing::warn;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    runtime::Handle,
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
 
 use self::{
     namespace::NamespaceCache, object_store::ObjectStoreCache, parquet_file::ParquetFileCache,
-    partition::PartitionCache, projected_schema::ProjectedSchemaCache, ram::RamSize,
+    partition::PartitionCache, projected_schema::ProjectedSchemaCache,
+    query_result::QueryResultCache, ram::RamSize,
 };
 
 pub mod namespace;
@@ -18,6 +24,7 @@ pub mod object_store;
 pub mod parquet_file;
 pub mod partition;
 pub mod projected_schema;
+pub mod query_result;
 mod ram;
 
 #[cfg(test)]
@@ -30,13 +37,13 @@ pub struct CatalogCache {
     catalog: Arc<dyn Catalog>,
 
     /// Partition cache.
-    partition_cache: PartitionCache,
+    partition_cache: Arc<PartitionCache>,
 
     /// Namespace cache.
-    namespace_cache: NamespaceCache,
+    namespace_cache: Arc<NamespaceCache>,
 
     /// Parquet file cache
-    parquet_file_cache: ParquetFileCache,
+    parquet_file_cache: Arc<ParquetFileCache>,
 
     /// Projected schema cache.
     projected_schema_cache: ProjectedSchemaCache,
@@ -44,6 +51,15 @@ pub struct CatalogCache {
     /// Object store cache.
     object_store_cache: ObjectStoreCache,
 
+    /// Query result cache, if enabled.
+    query_result_cache: Option<Arc<QueryResultCache>>,
+
+    /// Task expiring entries on catalog changes, if enabled.
+    change_feed_task: Option<AbortOnDrop>,
+
+    /// RAM pool for data, shared by the object store and query result caches.
+    ram_pool_data: Arc<ResourcePool<RamSize>>,
+
     /// Metric registry
     metric_registry: Arc<metric::Registry>,
 
@@ -120,15 +136,15 @@ impl CatalogCache {
             Arc::clone(&metric_registry),
         ));
 
-        let partition_cache = PartitionCache::new(
+        let partition_cache = Arc::new(PartitionCache::new(
             Arc::clone(&catalog),
             backoff_config.clone(),
             Arc::clone(&time_provider),
             &metric_registry,
             Arc::clone(&ram_pool_metadata),
             testing,
-        );
-        let namespace_cache = NamespaceCache::new(
+        ));
+        let namespace_cache = Arc::new(NamespaceCache::new(
             Arc::clone(&catalog),
             backoff_config.clone(),
             Arc::clone(&time_provider),
@@ -136,15 +152,15 @@ impl CatalogCache {
             Arc::clone(&ram_pool_metadata),
             handle,
             testing,
-        );
-        let parquet_file_cache = ParquetFileCache::new(
+        ));
+        let parquet_file_cache = Arc::new(ParquetFileCache::new(
             Arc::clone(&catalog),
             backoff_config.clone(),
             Arc::clone(&time_provider),
             &metric_registry,
             Arc::clone(&ram_pool_metadata),
             testing,
-        );
+        ));
         let projected_schema_cache = ProjectedSchemaCache::new(
             Arc::clone(&time_provider),
             &metric_registry,
@@ -167,11 +183,49 @@ impl CatalogCache {
             parquet_file_cache,
             projected_schema_cache,
             object_store_cache,
+            query_result_cache: None,
+            change_feed_task: None,
+            ram_pool_data,
             metric_registry,
             time_provider,
         }
     }
 
+    /// Enable the query result cache.
+    ///
+    /// Results are stored in the RAM pool for data. See [`QueryResultCache::new`] for `bucket_width`.
+    pub fn with_query_result_cache(self, bucket_width: Duration) -> Self {
+        let query_result_cache = Arc::new(QueryResultCache::new(
+            Arc::clone(&self.time_provider),
+            &self.metric_registry,
+            Arc::clone(&self.ram_pool_data),
+            bucket_width,
+        ));
+
+        Self {
+            query_result_cache: Some(query_result_cache),
+            ..self
+        }
+    }
+
+    /// Expire cached namespaces, partitions and parquet files as the catalog reports changes to
+    /// them, instead of only after their TTL or refresh.
+    ///
+    /// The expiry task is spawned on `handle`.
+    pub fn with_catalog_change_feed(self, handle: &Handle) -> Self {
+        let task = handle.spawn(expire_on_change(
+            self.catalog.subscribe_changes(),
+            Arc::clone(&self.namespace_cache),
+            Arc::clone(&self.partition_cache),
+            Arc::clone(&self.parquet_file_cache),
+        ));
+
+        Self {
+            change_feed_task: Some(AbortOnDrop(task)),
+            ..self
+        }
+    }
+
     /// Get underlying catalog
     pub(crate) fn catalog(&self) -> Arc<dyn Catalog> {
         Arc::clone(&self.catalog)
@@ -213,6 +267,11 @@ impl CatalogCache {
         &self.object_store_cache
     }
 
+    /// Query result cache, if enabled.
+    pub(crate) fn query_result(&self) -> Option<&Arc<QueryResultCache>> {
+        self.query_result_cache.as_ref()
+    }
+
     /// Parquet store that points to the cached object store.
     pub fn parquet_store(&self) -> ParquetStorage {
         ParquetStorage::new(
@@ -221,3 +280,155 @@ impl CatalogCache {
         )
     }
 }
+
+/// Expire the cache entries of the objects in `changes`.
+async fn expire_on_change(
+    mut changes: broadcast::Receiver<CatalogChange>,
+    namespace_cache: Arc<NamespaceCache>,
+    partition_cache: Arc<PartitionCache>,
+    parquet_file_cache: Arc<ParquetFileCache>,
+) {
+    loop {
+        match changes.recv().await {
+            // tables and their columns are cached as part of the namespace
+            Ok(
+                CatalogChange::Namespace { id }
+                | CatalogChange::Table {
+                    namespace_id: id, ..
+                },
+            ) => namespace_cache.expire(id),
+            Ok(CatalogChange::Partition { id, .. }) => partition_cache.expire(id),
+            Ok(CatalogChange::ParquetFile { table_id, .. }) => parquet_file_cache.expire(table_id),
+            Ok(CatalogChange::Reset) => {
+                warn!("catalog change feed reset, expiring all cached entries");
+                expire_all(&namespace_cache, &partition_cache, &parquet_file_cache);
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(
+                    skipped,
+                    "querier fell behind the catalog change feed, expiring all cached entries"
+                );
+                expire_all(&namespace_cache, &partition_cache, &parquet_file_cache);
+            }
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Expire all cache entries that are kept up to date by the catalog change feed.
+fn expire_all(
+    namespace_cache: &NamespaceCache,
+    partition_cache: &PartitionCache,
+    parquet_file_cache: &ParquetFileCache,
+) {
+    namespace_cache.expire_all();
+    partition_cache.expire_all();
+    parquet_file_cache.expire_all();
+}
+
+/// Aborts the wrapped task when dropped.
+#[derive(Debug)]
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::ColumnType;
+    use iox_tests::TestCatalog;
+
+    #[tokio::test]
+    async fn test_catalog_change_feed() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        table.create_column("time", ColumnType::Time).await;
+
+        let cache = CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        )
+        .with_catalog_change_feed(&Handle::current());
+
+        let column_count = || async {
+            cache
+                .namespace()
+                .get(Arc::from("ns"), &[], None)
+                .await
+                .unwrap()
+                .tables["table"]
+                .column_id_map
+                .len()
+        };
+        assert_eq!(column_count().await, 1);
+
+        // the cached namespace is expired without the TTL passing or a query asking for the column
+        table.create_column("tag", ColumnType::Tag).await;
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while column_count().await != 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("namespace not expired");
+    }
+
+    #[tokio::test]
+    async fn test_catalog_change_feed_reset() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        table.create_column("time", ColumnType::Time).await;
+
+        let cache = CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        );
+
+        // a feed that never reports the individual change
+        let (tx, rx) = broadcast::channel(1);
+        let task = AbortOnDrop(tokio::spawn(expire_on_change(
+            rx,
+            Arc::clone(&cache.namespace_cache),
+            Arc::clone(&cache.partition_cache),
+            Arc::clone(&cache.parquet_file_cache),
+        )));
+
+        let column_count = || async {
+            cache
+                .namespace()
+                .get(Arc::from("ns"), &[], None)
+                .await
+                .unwrap()
+                .tables["table"]
+                .column_id_map
+                .len()
+        };
+        assert_eq!(column_count().await, 1);
+
+        table.create_column("tag", ColumnType::Tag).await;
+        assert_eq!(column_count().await, 1);
+
+        tx.send(CatalogChange::Reset).unwrap();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while column_count().await != 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("namespace not expired");
+
+        drop(task);
+    }
+}
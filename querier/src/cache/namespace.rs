@@ -13,9 +13,12 @@ use cache_system::{
     loader::{metrics::MetricsLoader, FunctionLoader},
     resource_consumption::FunctionEstimator,
 };
-use data_types::{ColumnId, NamespaceId, NamespaceSchema, TableId, TableSchema};
+use data_types::{
+    ColumnId, NamespaceId, NamespaceSchema, QueryPriority, QueryQuota, TableId, TableSchema,
+};
 use iox_catalog::interface::{get_schema_by_name, Catalog, SoftDeletedRows};
 use iox_time::TimeProvider;
+use parking_lot::Mutex;
 use schema::Schema;
 use std::{
     collections::{HashMap, HashSet},
@@ -70,6 +73,8 @@ type CacheT = Box<
 pub struct NamespaceCache {
     cache: CacheT,
     remove_if_handle: RemoveIfHandle<Arc<str>, Option<Arc<CachedNamespace>>>,
+    /// Names of the namespaces loaded so far, to expire them by ID.
+    names: Arc<Mutex<HashMap<NamespaceId, Arc<str>>>>,
 }
 
 impl NamespaceCache {
@@ -83,9 +88,12 @@ impl NamespaceCache {
         handle: &Handle,
         testing: bool,
     ) -> Self {
+        let names: Arc<Mutex<HashMap<NamespaceId, Arc<str>>>> = Default::default();
+        let names_captured = Arc::clone(&names);
         let loader = FunctionLoader::new(move |namespace_name: Arc<str>, _extra: ()| {
             let catalog = Arc::clone(&catalog);
             let backoff_config = backoff_config.clone();
+            let names = Arc::clone(&names_captured);
 
             async move {
                 let schema = Backoff::new(&backoff_config)
@@ -98,7 +106,16 @@ impl NamespaceCache {
                         )
                         .await
                         {
-                            Ok(schema) => Ok(Some(schema)),
+                            Ok(schema) => {
+                                let deleted_column_ids = repos
+                                    .columns()
+                                    .list_by_namespace_id(schema.id, SoftDeletedRows::OnlyDeleted)
+                                    .await?
+                                    .into_iter()
+                                    .map(|c| c.id)
+                                    .collect::<HashSet<_>>();
+                                Ok(Some((schema, deleted_column_ids)))
+                            }
                             Err(iox_catalog::interface::Error::NamespaceNotFoundByName {
                                 ..
                             }) => Ok(None),
@@ -108,7 +125,12 @@ impl NamespaceCache {
                     .await
                     .expect("retry forever")?;
 
-                Some(Arc::new(schema.into()))
+                let (schema, deleted_column_ids) = schema;
+                names.lock().insert(schema.id, Arc::clone(&namespace_name));
+                let mut namespace = CachedNamespace::from(schema);
+                namespace.deleted_column_ids = deleted_column_ids;
+                namespace.deleted_column_ids.shrink_to_fit();
+                Some(Arc::new(namespace))
             }
         });
         let loader = Arc::new(MetricsLoader::new(
@@ -169,13 +191,27 @@ impl NamespaceCache {
         Self {
             cache,
             remove_if_handle,
+            names,
         }
     }
 
+    /// Mark the namespace with the given ID as expired (and needs a refresh), if it is cached.
+    pub fn expire(&self, id: NamespaceId) {
+        let name = self.names.lock().get(&id).cloned();
+        if let Some(name) = name {
+            self.remove_if_handle.remove_if(&name, |_| true);
+        }
+    }
+
+    /// Mark all cached namespaces as expired (and need a refresh).
+    pub fn expire_all(&self) {
+        self.remove_if_handle.remove_all_if(|_| true);
+    }
+
     /// Get namespace schema by name.
     ///
     /// Expire namespace if the cached schema does NOT cover the given set of columns. The set is given as a list of
-    /// pairs of table name and column set.
+    /// pairs of table name and column set. Columns that are known to be deleted count as covered.
     pub async fn get(
         &self,
         name: Arc<str>,
@@ -190,9 +226,10 @@ impl NamespaceCache {
                     if let Some(namespace) = cached_namespace.as_ref() {
                         should_cover.iter().any(|(table_name, columns)| {
                             if let Some(table) = namespace.tables.get(*table_name) {
-                                columns
-                                    .iter()
-                                    .any(|col| !table.column_id_map.contains_key(col))
+                                columns.iter().any(|col| {
+                                    !table.column_id_map.contains_key(col)
+                                        && !namespace.deleted_column_ids.contains(col)
+                                })
                             } else {
                                 // table unknown => need to update
                                 true
@@ -282,6 +319,14 @@ pub struct CachedNamespace {
     pub id: NamespaceId,
     pub retention_period: Option<Duration>,
     pub tables: HashMap<Arc<str>, Arc<CachedTable>>,
+    /// Soft-deleted columns, including the columns of soft-deleted tables.
+    ///
+    /// Parquet files may still reference these.
+    pub deleted_column_ids: HashSet<ColumnId>,
+    /// Per-namespace query quotas.
+    pub query_quota: QueryQuota,
+    /// Priority of queries that do not request one.
+    pub default_query_priority: QueryPriority,
 }
 
 impl CachedNamespace {
@@ -293,6 +338,7 @@ impl CachedNamespace {
                 .iter()
                 .map(|(name, table)| name.len() + table.size())
                 .sum::<usize>()
+            + self.deleted_column_ids.capacity() * size_of::<ColumnId>()
     }
 }
 
@@ -315,6 +361,9 @@ impl From<NamespaceSchema> for CachedNamespace {
             id: ns.id,
             retention_period,
             tables,
+            deleted_column_ids: HashSet::new(),
+            query_quota: ns.query_quota,
+            default_query_priority: ns.default_query_priority,
         }
     }
 }
@@ -416,6 +465,9 @@ mod tests {
                     }),
                 ),
             ]),
+            deleted_column_ids: HashSet::new(),
+            query_quota: QueryQuota::default(),
+            default_query_priority: QueryPriority::default(),
         };
         assert_eq!(actual_ns_1_a.as_ref(), &expected_ns_1);
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 1);
@@ -447,6 +499,9 @@ mod tests {
                     primary_key_column_ids: vec![col211.column.id],
                 }),
             )]),
+            deleted_column_ids: HashSet::new(),
+            query_quota: QueryQuota::default(),
+            default_query_priority: QueryPriority::default(),
         };
         assert_eq!(actual_ns_2.as_ref(), &expected_ns_2);
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 2);
@@ -568,5 +623,40 @@ mod tests {
             .await
             .is_some());
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 6);
+
+        // ========== deleted columns ==========
+        let c3 = t1.create_column("c3", ColumnType::Bool).await;
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .columns()
+            .soft_delete(c3.column.id)
+            .await
+            .unwrap();
+
+        // unknown to the cache => refresh
+        let ns = cache
+            .get(
+                Arc::from("ns1"),
+                &[("t1", &HashSet::from([c3.column.id]))],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 7);
+        assert!(!ns.tables["t1"].column_id_map.contains_key(&c3.column.id));
+        assert_eq!(ns.deleted_column_ids, HashSet::from([c3.column.id]));
+
+        // known to be deleted => no refresh
+        assert!(cache
+            .get(
+                Arc::from("ns1"),
+                &[("t1", &HashSet::from([c3.column.id]))],
+                None
+            )
+            .await
+            .is_some());
+        assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 7);
     }
 }
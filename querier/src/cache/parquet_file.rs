@@ -248,10 +248,14 @@ impl ParquetFileCache {
     }
 
     /// Mark the entry for table_id as expired (and needs a refresh)
-    #[cfg(test)]
     pub fn expire(&self, table_id: TableId) {
         self.remove_if_handle.remove_if(&table_id, |_| true);
     }
+
+    /// Mark all entries as expired (and need a refresh)
+    pub fn expire_all(&self) {
+        self.remove_if_handle.remove_all_if(|_| true);
+    }
 }
 
 fn different(stored_counts: Option<&[(Uuid, u64)]>, ingester_counts: &[(Uuid, u64)]) -> bool {
@@ -14,7 +14,7 @@ use cache_system::{
 use data_types::{ColumnId, PartitionId, ShardId};
 use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
-use schema::sort::SortKey;
+use schema::sort::{SortKey, SortKeyBuilder};
 use std::{
     collections::{HashMap, HashSet},
     mem::{size_of, size_of_val},
@@ -160,6 +160,16 @@ impl PartitionCache {
             .await
             .and_then(|p| p.sort_key)
     }
+
+    /// Mark the entry for partition_id as expired (and needs a refresh)
+    pub fn expire(&self, partition_id: PartitionId) {
+        self.remove_if_handle.remove_if(&partition_id, |_| true);
+    }
+
+    /// Mark all entries as expired (and need a refresh)
+    pub fn expire_all(&self) {
+        self.remove_if_handle.remove_all_if(|_| true);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -188,16 +198,17 @@ pub struct PartitionSortKey {
 
 impl PartitionSortKey {
     fn new(sort_key: SortKey, column_id_map_rev: &HashMap<Arc<str>, ColumnId>) -> Self {
-        let sort_key = Arc::new(sort_key);
-
-        let mut column_order: Vec<ColumnId> = sort_key
-            .iter()
-            .map(|(name, _opts)| {
-                *column_id_map_rev
-                    .get(name.as_ref())
-                    .unwrap_or_else(|| panic!("column_id_map_rev misses data: {name}"))
-            })
-            .collect();
+        // Columns that were deleted from the table are no longer part of the table schema, drop
+        // them from the sort key.
+        let mut builder = SortKeyBuilder::with_capacity(sort_key.len());
+        let mut column_order: Vec<ColumnId> = Vec::with_capacity(sort_key.len());
+        for (name, opts) in sort_key.iter() {
+            if let Some(id) = column_id_map_rev.get(name.as_ref()) {
+                builder = builder.with_col_sort_opts(Arc::clone(name), *opts);
+                column_order.push(*id);
+            }
+        }
+        let sort_key = Arc::new(builder.build());
         column_order.shrink_to_fit();
 
         let mut column_set: HashSet<ColumnId> = column_order.iter().copied().collect();
@@ -567,6 +578,31 @@ mod tests {
         assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 5);
     }
 
+    #[test]
+    fn test_sort_key_drops_deleted_columns() {
+        let column_id_map_rev = HashMap::from([
+            (Arc::from("tag"), ColumnId::new(1)),
+            (Arc::from("time"), ColumnId::new(3)),
+        ]);
+        let sort_key = PartitionSortKey::new(
+            SortKey::from_columns(["tag", "deleted", "time"]),
+            &column_id_map_rev,
+        );
+
+        assert_eq!(
+            sort_key.sort_key.as_ref(),
+            &SortKey::from_columns(["tag", "time"])
+        );
+        assert_eq!(
+            sort_key.column_order,
+            vec![ColumnId::new(1), ColumnId::new(3)]
+        );
+        assert_eq!(
+            sort_key.column_set,
+            HashSet::from([ColumnId::new(1), ColumnId::new(3)])
+        );
+    }
+
     fn schema() -> Schema {
         SchemaBuilder::new().build().unwrap()
     }
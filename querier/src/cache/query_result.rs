@@ -0,0 +1,217 @@
+//! Cache for query results.
+//!
+//! See [`iox_query::result_cache`] for how entries are keyed and validated.
+use std::{mem::size_of_val, sync::Arc, time::Duration};
+
+use cache_system::{
+    backend::{
+        policy::{
+            lru::{LruPolicy, ResourcePool},
+            PolicyBackend,
+        },
+        CacheBackend,
+    },
+    resource_consumption::FunctionEstimator,
+};
+use data_types::NamespaceId;
+use iox_query::result_cache::{self, CachedResult, ResultCacheKey};
+use iox_time::TimeProvider;
+use metric::U64Counter;
+use parking_lot::Mutex;
+
+use super::ram::RamSize;
+
+const CACHE_ID: &str = "query_result";
+
+/// Cache key.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+struct CacheKey {
+    namespace_id: NamespaceId,
+    key: ResultCacheKey,
+}
+
+impl CacheKey {
+    /// Size in of key including `Self`.
+    fn size(&self) -> usize {
+        size_of_val(&self.namespace_id) + self.key.size()
+    }
+}
+
+/// Cache for query results of all namespaces.
+///
+/// Entries share the RAM pool for data with the other caches and are evicted in LRU order.
+#[derive(Debug)]
+pub struct QueryResultCache {
+    backend: Mutex<PolicyBackend<CacheKey, Arc<CachedResult>>>,
+    bucket_width: Option<Duration>,
+    max_entry_bytes: usize,
+    hit: U64Counter,
+    miss: U64Counter,
+    stale: U64Counter,
+}
+
+impl QueryResultCache {
+    /// Create new empty cache.
+    ///
+    /// A `bucket_width` of zero disables caching of queries with relative time ranges.
+    pub fn new(
+        time_provider: Arc<dyn TimeProvider>,
+        metric_registry: &metric::Registry,
+        ram_pool: Arc<ResourcePool<RamSize>>,
+        bucket_width: Duration,
+    ) -> Self {
+        // a single result shall not flush the entire pool
+        let max_entry_bytes = ram_pool.limit().0 / 10;
+
+        let mut backend = PolicyBackend::hashmap_backed(time_provider);
+        backend.add_policy(LruPolicy::new(
+            ram_pool,
+            CACHE_ID,
+            Arc::new(FunctionEstimator::new(
+                |k: &CacheKey, v: &Arc<CachedResult>| RamSize(k.size() + v.size()),
+            )),
+        ));
+
+        let metric = metric_registry.register_metric::<U64Counter>(
+            "query_result_cache_lookup",
+            "Number of query result cache lookups",
+        );
+
+        Self {
+            backend: Mutex::new(backend),
+            bucket_width: (!bucket_width.is_zero()).then_some(bucket_width),
+            max_entry_bytes,
+            hit: metric.recorder(&[("status", "hit")]),
+            miss: metric.recorder(&[("status", "miss")]),
+            stale: metric.recorder(&[("status", "stale")]),
+        }
+    }
+
+    /// Get cache handle for the given namespace.
+    pub fn namespace(
+        self: &Arc<Self>,
+        namespace_id: NamespaceId,
+    ) -> Arc<dyn result_cache::QueryResultCache> {
+        Arc::new(NamespaceResultCache {
+            store: Arc::clone(self),
+            namespace_id,
+        })
+    }
+}
+
+/// [`QueryResultCache`](result_cache::QueryResultCache) of a single namespace.
+#[derive(Debug)]
+struct NamespaceResultCache {
+    store: Arc<QueryResultCache>,
+    namespace_id: NamespaceId,
+}
+
+impl result_cache::QueryResultCache for NamespaceResultCache {
+    fn get(&self, key: &ResultCacheKey) -> Option<Arc<CachedResult>> {
+        let key = CacheKey {
+            namespace_id: self.namespace_id,
+            key: key.clone(),
+        };
+        let res = self.store.backend.lock().get(&key);
+        match &res {
+            Some(_) => self.store.hit.inc(1),
+            None => self.store.miss.inc(1),
+        }
+        res
+    }
+
+    fn put(&self, key: ResultCacheKey, result: Arc<CachedResult>) {
+        let key = CacheKey {
+            namespace_id: self.namespace_id,
+            key,
+        };
+        let mut backend = self.store.backend.lock();
+        if backend.get(&key).is_some() {
+            // entry was computed from a different input
+            self.store.stale.inc(1);
+        }
+        backend.set(key, result);
+    }
+
+    fn bucket_width(&self) -> Option<Duration> {
+        self.store.bucket_width
+    }
+
+    fn max_entry_bytes(&self) -> usize {
+        self.store.max_entry_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::ram::test_util::test_ram_pool;
+    use arrow::{
+        array::{ArrayRef, Int64Array},
+        record_batch::RecordBatch,
+    };
+    use iox_query::result_cache::QueryResultCache as _;
+    use iox_time::SystemProvider;
+    use metric::{Attributes, Metric};
+
+    #[test]
+    fn test_namespaces_are_isolated() {
+        let metric_registry = metric::Registry::new();
+        let store = Arc::new(QueryResultCache::new(
+            Arc::new(SystemProvider::new()),
+            &metric_registry,
+            test_ram_pool(),
+            Duration::from_secs(60),
+        ));
+        let cache_1 = store.namespace(NamespaceId::new(1));
+        let cache_2 = store.namespace(NamespaceId::new(2));
+        assert_eq!(cache_1.bucket_width(), Some(Duration::from_secs(60)));
+
+        let key = ResultCacheKey {
+            query: Arc::from("SELECT * FROM cpu"),
+            bucket: None,
+        };
+        assert!(cache_1.get(&key).is_none());
+
+        cache_1.put(key.clone(), result(1));
+        assert_eq!(cache_1.get(&key).unwrap().input, 1);
+        assert!(cache_2.get(&key).is_none());
+
+        cache_1.put(key.clone(), result(2));
+        assert_eq!(cache_1.get(&key).unwrap().input, 2);
+
+        let metric = metric_registry
+            .get_instrument::<Metric<U64Counter>>("query_result_cache_lookup")
+            .unwrap();
+        let fetch = |status: &'static str| {
+            metric
+                .get_observer(&Attributes::from(&[("status", status)]))
+                .unwrap()
+                .fetch()
+        };
+        assert_eq!(fetch("hit"), 2);
+        assert_eq!(fetch("miss"), 2);
+        assert_eq!(fetch("stale"), 1);
+    }
+
+    #[test]
+    fn test_zero_bucket_width() {
+        let store = Arc::new(QueryResultCache::new(
+            Arc::new(SystemProvider::new()),
+            &metric::Registry::new(),
+            test_ram_pool(),
+            Duration::ZERO,
+        ));
+        assert_eq!(store.namespace(NamespaceId::new(1)).bucket_width(), None);
+    }
+
+    fn result(input: u64) -> Arc<CachedResult> {
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![1, 2, 3]));
+        let batch = RecordBatch::try_from_iter([("a", array)]).unwrap();
+        Arc::new(CachedResult {
+            input,
+            schema: batch.schema(),
+            batches: vec![batch],
+        })
+    }
+}
@@ -2,11 +2,12 @@
 
 use crate::{
     cache::CatalogCache, ingester::IngesterConnection, namespace::QuerierNamespace,
-    parquet::ChunkAdapter, query_log::QueryLog, table::PruneMetrics,
+    parquet::ChunkAdapter, query_log::QueryLog, query_priority::QueryPriorityClasses,
+    query_quota::QueryQuotas, table::PruneMetrics,
 };
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
-use data_types::Namespace;
+use data_types::{Namespace, QueryPriority};
 use iox_catalog::interface::SoftDeletedRows;
 use iox_query::exec::Executor;
 use service_common::QueryNamespaceProvider;
@@ -65,6 +66,12 @@ pub struct QuerierDatabase {
     /// If the same namespace is requested twice for different queries, it is counted twice.
     query_execution_semaphore: Arc<InstrumentedAsyncSemaphore>,
 
+    /// Per-namespace query quotas, carved out of the global limits.
+    query_quotas: Arc<QueryQuotas>,
+
+    /// Admission control of the query priority classes.
+    query_priorities: Arc<QueryPriorityClasses>,
+
     /// Chunk prune metrics.
     prune_metrics: Arc<PruneMetrics>,
 
@@ -126,6 +133,9 @@ impl QuerierDatabase {
         let query_execution_semaphore =
             Arc::new(semaphore_metrics.new_semaphore(max_concurrent_queries));
 
+        let query_quotas = Arc::new(QueryQuotas::new(exec.memory_pool(), &metric_registry));
+        let query_priorities = Arc::new(QueryPriorityClasses::new(&metric_registry));
+
         let prune_metrics = Arc::new(PruneMetrics::new(&metric_registry));
 
         Ok(Self {
@@ -137,11 +147,27 @@ impl QuerierDatabase {
             ingester_connection,
             query_log,
             query_execution_semaphore,
+            query_quotas,
+            query_priorities,
             prune_metrics,
             datafusion_config,
         })
     }
 
+    /// Limit the number of concurrently running queries per priority class.
+    ///
+    /// Classes without an entry are only limited by `max_concurrent_queries`.
+    pub fn with_max_concurrent_queries_by_priority(
+        self,
+        limits: HashMap<QueryPriority, usize>,
+    ) -> Self {
+        for (priority, max) in limits {
+            self.query_priorities
+                .set_max_concurrent_queries(priority, max);
+        }
+        self
+    }
+
     /// Get namespace if it exists.
     ///
     /// This will await the internal namespace semaphore. Existence of namespaces is checked AFTER
@@ -159,6 +185,9 @@ impl QuerierDatabase {
                 span_recorder.child_span("cache GET namespace schema"),
             )
             .await?;
+        let quota = self
+            .query_quotas
+            .namespace(ns.id, Arc::clone(&name), ns.query_quota);
         Some(Arc::new(QuerierNamespace::new(
             Arc::clone(&self.chunk_adapter),
             ns,
@@ -166,6 +195,8 @@ impl QuerierDatabase {
             Arc::clone(&self.exec),
             self.ingester_connection.clone(),
             Arc::clone(&self.query_log),
+            quota,
+            Arc::clone(&self.query_priorities),
             Arc::clone(&self.prune_metrics),
             Arc::clone(&self.datafusion_config),
         )))
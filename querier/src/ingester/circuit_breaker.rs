@@ -1095,6 +1095,7 @@ mod tests {
             table_id: TableId::new(0),
             columns: vec![],
             predicate: None,
+            last_values: false,
         }
     }
 
@@ -12,7 +12,7 @@ use prost::Message;
 use snafu::{ResultExt, Snafu};
 use std::{collections::HashMap, fmt::Debug, ops::DerefMut, sync::Arc};
 use trace::{ctx::SpanContext, span::SpanRecorder};
-use trace_http::ctx::format_jaeger_trace_context;
+use trace_http::ctx::TraceHeaderFormatter;
 
 pub use influxdb_iox_client::flight::Error as FlightError;
 
@@ -87,7 +87,7 @@ pub trait IngesterFlightClient: Debug + Send + Sync + 'static {
 }
 
 /// Default [`IngesterFlightClient`] implementation that uses a real connection
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct FlightClientImpl {
     /// Cached connections
     /// key: ingester_address (e.g. "http://ingester-1:8082")
@@ -97,12 +97,19 @@ pub struct FlightClientImpl {
     /// for a very short period of time, and any actual connection (and
     /// waiting) is done in CachedConnection
     connections: parking_lot::Mutex<HashMap<String, CachedConnection>>,
+
+    /// Formats the trace context headers sent to the ingester.
+    trace_header_formatter: TraceHeaderFormatter,
 }
 
 impl FlightClientImpl {
-    /// Create new client.
-    pub fn new() -> Self {
-        Self::default()
+    /// Create new client, propagating trace context in the formats of
+    /// `trace_header_formatter`.
+    pub fn new(trace_header_formatter: TraceHeaderFormatter) -> Self {
+        Self {
+            connections: Default::default(),
+            trace_header_formatter,
+        }
     }
 
     /// Establish connection to given addr and perform handshake.
@@ -162,17 +169,16 @@ impl IngesterFlightClient for FlightClientImpl {
             // use lower level client to send a custom message type
             .into_inner();
 
-        // Add the span context header, if any
+        // Add the span context headers, if any
         let span_recorder_comm = span_recorder.child("comm");
         if let Some(span) = span_recorder_comm.span() {
-            client
-                .add_header(
-                    trace_exporters::DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME,
-                    &format_jaeger_trace_context(&span.ctx),
-                )
-                // wrap in client error type
-                .map_err(FlightError::ArrowFlightError)
-                .context(FlightSnafu)?;
+            for (name, value) in self.trace_header_formatter.headers(&span.ctx) {
+                client
+                    .add_header(name, &value)
+                    // wrap in client error type
+                    .map_err(FlightError::ArrowFlightError)
+                    .context(FlightSnafu)?;
+            }
         }
 
         let data_stream = {
@@ -475,6 +481,7 @@ mod tests {
             table_id: TableId::new(1337),
             columns: vec![String::from("col1"), String::from("col2")],
             predicate: Some(predicate),
+            last_values: false,
         };
 
         let proto = serialize_ingester_query_request(request.clone()).expect("serialization");
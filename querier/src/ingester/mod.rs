@@ -33,11 +33,13 @@ use schema::{sort::SortKey, Projection, Schema};
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::{
     any::Any,
-    collections::{HashMap, HashSet},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
     sync::Arc,
     time::Duration,
 };
 use trace::span::{Span, SpanRecorder};
+use trace_http::ctx::TraceHeaderFormatter;
 use uuid::Uuid;
 
 mod circuit_breaker;
@@ -151,6 +153,7 @@ pub fn create_ingester_connections(
     ingester_addresses: Vec<Arc<str>>,
     catalog_cache: Arc<CatalogCache>,
     open_circuit_after_n_errors: u64,
+    trace_header_formatter: TraceHeaderFormatter,
 ) -> Arc<dyn IngesterConnection> {
     // This backoff config is used to retry requests for a specific table-scoped query.
     let retry_backoff_config = BackoffConfig {
@@ -175,6 +178,7 @@ pub fn create_ingester_connections(
         retry_backoff_config,
         circuit_breaker_backoff_config,
         open_circuit_after_n_errors,
+        trace_header_formatter,
     ))
 }
 
@@ -196,6 +200,21 @@ pub trait IngesterConnection: std::fmt::Debug + Send + Sync + 'static {
         span: Option<Span>,
     ) -> Result<Vec<IngesterPartition>>;
 
+    /// Returns the most recent value of each column for every series of the
+    /// specified table, answered from the ingesters' last value caches.
+    ///
+    /// Returns [`None`] if any ingester cannot prove its cache covers the
+    /// table, in which case the caller must fall back to a regular query.
+    async fn last_values(
+        &self,
+        _namespace_id: NamespaceId,
+        _cached_table: Arc<CachedTable>,
+        _columns: Vec<String>,
+        _span: Option<Span>,
+    ) -> Result<Option<Vec<IngesterPartition>>> {
+        Ok(None)
+    }
+
     /// Return backend as [`Any`] which can be used to downcast to a specific implementation.
     fn as_any(&self) -> &dyn Any;
 }
@@ -337,8 +356,9 @@ impl IngesterConnectionImpl {
         backoff_config: BackoffConfig,
         circuit_breaker_backoff_config: BackoffConfig,
         open_circuit_after_n_errors: u64,
+        trace_header_formatter: TraceHeaderFormatter,
     ) -> Self {
-        let flight_client = Arc::new(FlightClientImpl::new());
+        let flight_client = Arc::new(FlightClientImpl::new(trace_header_formatter));
         let flight_client = Arc::new(InvalidateOnErrorFlightClient::new(flight_client));
         let flight_client = Arc::new(CircuitBreakerFlightClient::new(
             flight_client,
@@ -389,13 +409,18 @@ struct GetPartitionForIngester<'a> {
     columns: Vec<String>,
     predicate: &'a Predicate,
     cached_table: Arc<CachedTable>,
+    last_values: bool,
 }
 
-/// Fetches the partitions for a single ingester
+/// Fetches the partitions for a single ingester.
+///
+/// Returns [`None`] if the ingester cannot answer the request, either because
+/// it is unreachable, does not know the table, or (for last value requests)
+/// its cache does not cover the table.
 async fn execute(
     request: GetPartitionForIngester<'_>,
     span_recorder: &SpanRecorder,
-) -> Result<Vec<IngesterPartition>> {
+) -> Result<Option<Vec<IngesterPartition>>> {
     let GetPartitionForIngester {
         flight_client,
         catalog_cache,
@@ -404,6 +429,7 @@ async fn execute(
         columns,
         predicate,
         cached_table,
+        last_values,
     } = request;
 
     let ingester_query_request = IngesterQueryRequest {
@@ -411,6 +437,7 @@ async fn execute(
         table_id: cached_table.id,
         columns: columns.clone(),
         predicate: Some(predicate.clone()),
+        last_values,
     };
 
     let query_res = {
@@ -432,7 +459,7 @@ async fn execute(
                 table_id = cached_table.id.get(),
                 "Could not connect to ingester, circuit broken",
             );
-            return Ok(vec![]);
+            return Ok(None);
         }
         Err(FlightClientError::Flight {
             source: FlightError::ArrowFlightError(arrow_flight::error::FlightError::Tonic(status)),
@@ -443,7 +470,18 @@ async fn execute(
                 table_id = cached_table.id.get(),
                 "Ingester does not know namespace or table, skipping",
             );
-            return Ok(vec![]);
+            return Ok(None);
+        }
+        Err(FlightClientError::Flight {
+            source: FlightError::ArrowFlightError(arrow_flight::error::FlightError::Tonic(status)),
+        }) if last_values && status.code() == tonic::Code::FailedPrecondition => {
+            debug!(
+                ingester_address = ingester_address.as_ref(),
+                namespace_id = namespace_id.get(),
+                table_id = cached_table.id.get(),
+                "Ingester last value cache does not cover table",
+            );
+            return Ok(None);
         }
         _ => {}
     }
@@ -495,7 +533,7 @@ async fn execute(
         decoder.register(msg, md).await?;
     }
 
-    decoder.finalize().await
+    decoder.finalize().await.map(Some)
 }
 
 /// Current partition used while decoding the ingester response stream.
@@ -769,19 +807,18 @@ fn encode_predicate_as_base64(predicate: &Predicate) -> String {
     }
 }
 
-#[async_trait]
-impl IngesterConnection for IngesterConnectionImpl {
-    /// Retrieve chunks from the ingester for the particular table, shard, and predicate
-    async fn partitions(
+impl IngesterConnectionImpl {
+    /// Query all ingesters for the specified table, returning the response of
+    /// each ingester (or [`None`] for those that could not answer).
+    async fn query_ingesters(
         &self,
         namespace_id: NamespaceId,
         cached_table: Arc<CachedTable>,
         columns: Vec<String>,
         predicate: &Predicate,
-        span: Option<Span>,
-    ) -> Result<Vec<IngesterPartition>> {
-        let mut span_recorder = SpanRecorder::new(span);
-
+        last_values: bool,
+        span_recorder: &SpanRecorder,
+    ) -> Result<Vec<Option<Vec<IngesterPartition>>>> {
         let metrics = Arc::clone(&self.metrics);
 
         let measured_ingester_request = |ingester_address: Arc<str>| {
@@ -794,6 +831,7 @@ impl IngesterConnection for IngesterConnectionImpl {
                 cached_table: Arc::clone(&cached_table),
                 columns: columns.clone(),
                 predicate,
+                last_values,
             };
 
             let backoff_config = self.backoff_config.clone();
@@ -801,7 +839,7 @@ impl IngesterConnection for IngesterConnectionImpl {
             // wrap `execute` into an additional future so that we can measure the request time
             // INFO: create the measurement structure outside of the async block so cancellation is
             // always measured
-            let measure_me = ObserveIngesterRequest::new(request.clone(), metrics, &span_recorder);
+            let measure_me = ObserveIngesterRequest::new(request.clone(), metrics, span_recorder);
             async move {
                 let span_recorder = measure_me
                     .span_recorder()
@@ -819,7 +857,7 @@ impl IngesterConnection for IngesterConnectionImpl {
                 match &res {
                     Ok(partitions) => {
                         let mut status = IngesterResponseOk::default();
-                        for p in partitions {
+                        for p in partitions.iter().flatten() {
                             status.n_partitions += 1;
                             for c in p.chunks() {
                                 status.n_chunks += 1;
@@ -836,23 +874,51 @@ impl IngesterConnection for IngesterConnectionImpl {
             }
         };
 
-        let mut ingester_partitions: Vec<IngesterPartition> = self
-            .unique_ingester_addresses
+        self.unique_ingester_addresses
             .iter()
             .cloned()
             .map(move |ingester_address| measured_ingester_request(ingester_address))
             .collect::<FuturesUnordered<_>>()
             .try_collect::<Vec<_>>()
             .await
+            .map_err(|e| match e {
+                BackoffError::DeadlineExceeded { source, .. } => source,
+            })
+    }
+}
+
+#[async_trait]
+impl IngesterConnection for IngesterConnectionImpl {
+    /// Retrieve chunks from the ingester for the particular table, shard, and predicate
+    async fn partitions(
+        &self,
+        namespace_id: NamespaceId,
+        cached_table: Arc<CachedTable>,
+        columns: Vec<String>,
+        predicate: &Predicate,
+        span: Option<Span>,
+    ) -> Result<Vec<IngesterPartition>> {
+        let mut span_recorder = SpanRecorder::new(span);
+
+        let mut ingester_partitions: Vec<IngesterPartition> = self
+            .query_ingesters(
+                namespace_id,
+                cached_table,
+                columns,
+                predicate,
+                false,
+                &span_recorder,
+            )
+            .await
             .map_err(|e| {
                 span_recorder.error("failed");
-                match e {
-                    BackoffError::DeadlineExceeded { source, .. } => source,
-                }
+                e
             })?
-            // We have a Vec<Vec<..>> flatten to Vec<_>
+            // Ingesters that could not answer are skipped, and the remaining
+            // Vec<Vec<..>> is flattened to Vec<_>
             .into_iter()
             .flatten()
+            .flatten()
             .collect();
 
         ingester_partitions.sort_by_key(|p| p.partition_id);
@@ -860,6 +926,45 @@ impl IngesterConnection for IngesterConnectionImpl {
         Ok(ingester_partitions)
     }
 
+    async fn last_values(
+        &self,
+        namespace_id: NamespaceId,
+        cached_table: Arc<CachedTable>,
+        columns: Vec<String>,
+        span: Option<Span>,
+    ) -> Result<Option<Vec<IngesterPartition>>> {
+        let mut span_recorder = SpanRecorder::new(span);
+
+        // The last values are only complete if every ingester answered.
+        let ingester_partitions: Option<Vec<Vec<IngesterPartition>>> = self
+            .query_ingesters(
+                namespace_id,
+                cached_table,
+                columns,
+                &Predicate::default(),
+                true,
+                &span_recorder,
+            )
+            .await
+            .map_err(|e| {
+                span_recorder.error("failed");
+                e
+            })?
+            .into_iter()
+            .collect();
+
+        let Some(ingester_partitions) = ingester_partitions else {
+            span_recorder.ok("not covered");
+            return Ok(None);
+        };
+
+        let mut ingester_partitions: Vec<IngesterPartition> =
+            ingester_partitions.into_iter().flatten().collect();
+        ingester_partitions.sort_by_key(|p| p.partition_id);
+        span_recorder.ok("done");
+        Ok(Some(ingester_partitions))
+    }
+
     fn as_any(&self) -> &dyn Any {
         self as &dyn Any
     }
@@ -959,6 +1064,23 @@ impl IngesterPartition {
             ts_min_max,
         ));
 
+        // The ingester only appends to its buffer until the data is persisted, which bumps the
+        // persistence count. Hence the buffer state is identified by the ingester instance, the
+        // persistence count, and the number of rows. Without an ingester UUID a restarted
+        // ingester cannot be told apart, so no fingerprint is provided.
+        let data_fingerprint = self.ingester_uuid.map(|ingester_uuid| {
+            let mut hasher = DefaultHasher::new();
+            ingester_uuid.hash(&mut hasher);
+            self.partition_id.hash(&mut hasher);
+            self.completed_persistence_count.hash(&mut hasher);
+            self.parquet_max_sequence_number.hash(&mut hasher);
+            self.chunks.len().hash(&mut hasher);
+            row_count.hash(&mut hasher);
+            ts_min_max.min.hash(&mut hasher);
+            ts_min_max.max.hash(&mut hasher);
+            hasher.finish()
+        });
+
         let chunk = IngesterChunk {
             chunk_id,
             partition_id: self.partition_id,
@@ -968,6 +1090,7 @@ impl IngesterPartition {
             ts_min_max,
             summary,
             delete_predicates: vec![],
+            data_fingerprint,
         };
 
         self.chunks.push(chunk);
@@ -1053,6 +1176,9 @@ pub struct IngesterChunk {
     summary: Arc<TableSummary>,
 
     delete_predicates: Vec<Arc<DeletePredicate>>,
+
+    /// Fingerprint of the buffered data, see [`QueryChunk::data_fingerprint`].
+    data_fingerprint: Option<u64>,
 }
 
 impl IngesterChunk {
@@ -1158,6 +1284,10 @@ impl QueryChunk for IngesterChunk {
         "IngesterPartition"
     }
 
+    fn data_fingerprint(&self) -> Option<u64> {
+        self.data_fingerprint
+    }
+
     fn order(&self) -> ChunkOrder {
         // since this is always the 'most recent' chunk for this
         // partition, put it at the end
@@ -1296,6 +1426,70 @@ mod tests {
         assert!(partitions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_last_values_not_covered() {
+        let mock_flight_client = Arc::new(
+            MockFlightClient::new([
+                ("addr1", Ok(MockQueryData { results: vec![] })),
+                (
+                    "addr2",
+                    Err(FlightClientError::Flight {
+                        source: tonic::Status::failed_precondition("not cached").into(),
+                    }),
+                ),
+            ])
+            .await,
+        );
+        let mut ingester_conn = mock_flight_client.ingester_conn().await;
+        ingester_conn.backoff_config = BackoffConfig::default();
+        let partitions = get_last_values(&ingester_conn).await.unwrap();
+        assert!(partitions.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_last_values_covered() {
+        let ingester_uuid = Uuid::new_v4();
+        let record_batch = lp_to_record_batch("table foo=1 1");
+
+        let mock_flight_client = Arc::new(
+            MockFlightClient::new([
+                ("addr1", Ok(MockQueryData { results: vec![] })),
+                (
+                    "addr2",
+                    Ok(MockQueryData {
+                        results: vec![
+                            metadata(
+                                1,
+                                Some(PartitionStatus {
+                                    parquet_max_sequence_number: None,
+                                }),
+                                ingester_uuid.to_string(),
+                                0,
+                            ),
+                            Ok((
+                                DecodedPayload::Schema(record_batch.schema()),
+                                IngesterQueryResponseMetadata::default(),
+                            )),
+                            Ok((
+                                DecodedPayload::RecordBatch(record_batch),
+                                IngesterQueryResponseMetadata::default(),
+                            )),
+                        ],
+                    }),
+                ),
+            ])
+            .await,
+        );
+        let ingester_conn = mock_flight_client.ingester_conn().await;
+        let partitions = get_last_values(&ingester_conn)
+            .await
+            .unwrap()
+            .expect("should be covered");
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].chunks().len(), 1);
+        assert_eq!(partitions[0].chunks()[0].rows(), 1);
+    }
+
     #[tokio::test]
     async fn test_flight_stream_error() {
         let mock_flight_client = Arc::new(
@@ -1893,6 +2087,15 @@ mod tests {
             .await
     }
 
+    async fn get_last_values(
+        ingester_conn: &IngesterConnectionImpl,
+    ) -> Result<Option<Vec<IngesterPartition>>, Error> {
+        let columns = vec![String::from("foo"), String::from("time")];
+        ingester_conn
+            .last_values(NamespaceId::new(1), cached_table(), columns, None)
+            .await
+    }
+
     fn schema() -> Schema {
         SchemaBuilder::new()
             .influx_field("bar", InfluxFieldType::Float)
@@ -2052,6 +2255,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ingester_chunk_data_fingerprint() {
+        let expected_schema = SchemaBuilder::new().tag("t").timestamp().build().unwrap();
+        let ingester_uuid = Uuid::new_v4();
+        let batch =
+            || RecordBatch::try_from_iter(vec![("t", dict_array()), ("time", ts_array())]).unwrap();
+
+        let fingerprint = |ingester_uuid: Option<Uuid>, persist_count: u64| {
+            let partition = IngesterPartition::new(
+                ingester_uuid,
+                PartitionId::new(1),
+                ShardId::new(1),
+                persist_count,
+                None,
+                None,
+            )
+            .try_add_chunk(ChunkId::new(), expected_schema.clone(), vec![batch()])
+            .unwrap();
+            partition.chunks[0].data_fingerprint()
+        };
+
+        // chunk IDs are random, but the fingerprint is stable
+        let fp = fingerprint(Some(ingester_uuid), 0);
+        assert!(fp.is_some());
+        assert_eq!(fp, fingerprint(Some(ingester_uuid), 0));
+
+        // persisting or restarting changes the fingerprint
+        assert_ne!(fp, fingerprint(Some(ingester_uuid), 1));
+        assert_ne!(fp, fingerprint(Some(Uuid::new_v4()), 0));
+
+        // without UUID a restart cannot be detected
+        assert_eq!(fingerprint(None, 0), None);
+    }
+
     #[test]
     fn test_ingester_partition_fail_type_cast() {
         let ingester_uuid = Uuid::new_v4();
@@ -98,6 +98,7 @@ impl IngesterConnection for MockIngesterConnection {
                             ts_min_max: ic.ts_min_max,
                             summary: Arc::new(summary),
                             delete_predicates: vec![],
+                            data_fingerprint: ic.data_fingerprint,
                         }
                     })
                     .collect::<Vec<_>>();
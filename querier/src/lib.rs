@@ -19,6 +19,8 @@ mod namespace;
 mod parquet;
 mod poison;
 mod query_log;
+mod query_priority;
+mod query_quota;
 mod server;
 mod system_tables;
 mod table;
@@ -5,9 +5,11 @@ use crate::{
     ingester::IngesterConnection,
     parquet::ChunkAdapter,
     query_log::QueryLog,
+    query_priority::QueryPriorityClasses,
+    query_quota::{NamespaceQuota, QueryQuotas},
     table::{PruneMetrics, QuerierTable, QuerierTableArgs},
 };
-use data_types::NamespaceId;
+use data_types::{NamespaceId, QueryPriority};
 use iox_query::exec::Executor;
 use std::{collections::HashMap, sync::Arc};
 
@@ -45,6 +47,15 @@ pub struct QuerierNamespace {
     /// Query log.
     query_log: Arc<QueryLog>,
 
+    /// Query quota state, shared by all queries of this namespace.
+    quota: Arc<NamespaceQuota>,
+
+    /// Priority of queries that do not request one.
+    default_priority: QueryPriority,
+
+    /// Admission control of the query priority classes, shared by all namespaces.
+    priorities: Arc<QueryPriorityClasses>,
+
     /// DataFusion config.
     datafusion_config: Arc<HashMap<String, String>>,
 }
@@ -59,6 +70,8 @@ impl QuerierNamespace {
         exec: Arc<Executor>,
         ingester_connection: Option<Arc<dyn IngesterConnection>>,
         query_log: Arc<QueryLog>,
+        quota: Arc<NamespaceQuota>,
+        priorities: Arc<QueryPriorityClasses>,
         prune_metrics: Arc<PruneMetrics>,
         datafusion_config: Arc<HashMap<String, String>>,
     ) -> Self {
@@ -83,6 +96,7 @@ impl QuerierNamespace {
             .collect();
 
         let id = ns.id;
+        let default_priority = ns.default_query_priority;
 
         Self {
             id,
@@ -91,6 +105,9 @@ impl QuerierNamespace {
             exec,
             catalog_cache: Arc::clone(chunk_adapter.catalog_cache()),
             query_log,
+            quota,
+            default_priority,
+            priorities,
             datafusion_config,
         }
     }
@@ -109,6 +126,9 @@ impl QuerierNamespace {
         let chunk_adapter = Arc::new(ChunkAdapter::new(catalog_cache, metric_registry));
         let query_log = Arc::new(QueryLog::new(10, time_provider));
         let prune_metrics = Arc::new(PruneMetrics::new(&chunk_adapter.metric_registry()));
+        let quota = QueryQuotas::new(exec.memory_pool(), &chunk_adapter.metric_registry())
+            .namespace(ns.id, Arc::clone(&name), ns.query_quota);
+        let priorities = Arc::new(QueryPriorityClasses::new(&chunk_adapter.metric_registry()));
 
         Self::new(
             chunk_adapter,
@@ -117,6 +137,8 @@ impl QuerierNamespace {
             exec,
             ingester_connection,
             query_log,
+            quota,
+            priorities,
             prune_metrics,
             Arc::new(HashMap::default()),
         )
@@ -7,7 +7,7 @@ use crate::{
     table::QuerierTable,
 };
 use async_trait::async_trait;
-use data_types::NamespaceId;
+use data_types::{NamespaceId, QueryPriority};
 use datafusion::{
     catalog::{catalog::CatalogProvider, schema::SchemaProvider},
     datasource::TableProvider,
@@ -15,7 +15,8 @@ use datafusion::{
 };
 use datafusion_util::config::DEFAULT_SCHEMA;
 use iox_query::{
-    exec::{ExecutionContextProvider, ExecutorType, IOxSessionContext},
+    exec::{cancel::run_cancellable, ExecutionContextProvider, ExecutorType, IOxSessionContext},
+    result_cache::QueryResultCache,
     QueryChunk, QueryCompletedToken, QueryNamespace, QueryText,
 };
 use observability_deps::tracing::{debug, trace};
@@ -56,13 +57,17 @@ impl QueryNamespace for QuerierNamespace {
             }
         };
 
-        let mut chunks = table
-            .chunks(
-                predicate,
-                ctx.child_span("QuerierNamespace chunks"),
-                projection,
-            )
-            .await?;
+        let mut chunks = run_cancellable(Some(ctx.cancellation_token().clone()), async {
+            table
+                .chunks(
+                    predicate,
+                    ctx.child_span("QuerierNamespace chunks"),
+                    projection,
+                )
+                .await
+                .map_err(DataFusionError::from)
+        })
+        .await?;
 
         // if there is a field restriction on the predicate, only
         // chunks with that field should be returned. If the chunk has
@@ -80,18 +85,62 @@ impl QueryNamespace for QuerierNamespace {
         Ok(chunks)
     }
 
+    async fn last_value_chunks(
+        &self,
+        table_name: &str,
+        ctx: IOxSessionContext,
+    ) -> Result<Option<Vec<Arc<dyn QueryChunk>>>, DataFusionError> {
+        let Some(table) = self.tables.get(table_name).map(Arc::clone) else {
+            trace!(%table_name, "No entry for table");
+            return Ok(None);
+        };
+
+        run_cancellable(Some(ctx.cancellation_token().clone()), async {
+            table
+                .last_value_chunks(ctx.child_span("QuerierNamespace last_value_chunks"))
+                .await
+                .map_err(DataFusionError::from)
+        })
+        .await
+    }
+
     fn record_query(
         &self,
         ctx: &IOxSessionContext,
         query_type: &str,
         query_text: QueryText,
-    ) -> QueryCompletedToken {
+    ) -> Result<QueryCompletedToken, DataFusionError> {
+        // The slot is held until the query token is dropped.
+        let slot = self.quota.try_acquire_query_slot()?;
+        let admitted = self.priorities.try_admit(ctx.priority())?;
+
         // When the query token is dropped the query entry's completion time
         // will be set.
         let query_log = Arc::clone(&self.query_log);
         let trace_id = ctx.span().map(|s| s.ctx.trace_id);
-        let entry = query_log.push(self.id, query_type, query_text, trace_id);
-        QueryCompletedToken::new(move |success| query_log.set_completed(entry, success))
+        let entry = query_log.push(
+            self.id,
+            query_type,
+            ctx.priority(),
+            query_text,
+            trace_id,
+            ctx.cancellation_token().clone(),
+        );
+        Ok(QueryCompletedToken::new(move |success| {
+            query_log.set_completed(entry, success);
+            drop(slot);
+            drop(admitted);
+        }))
+    }
+
+    fn cancel_query(&self, query_id: u64) -> bool {
+        self.query_log.cancel(self.id, query_id)
+    }
+
+    fn result_cache(&self) -> Option<Arc<dyn QueryResultCache>> {
+        self.catalog_cache
+            .query_result()
+            .map(|cache| cache.namespace(self.id))
     }
 
     fn as_meta(&self) -> &dyn QueryNamespaceMeta {
@@ -185,11 +234,17 @@ impl SchemaProvider for UserSchemaProvider {
 }
 
 impl ExecutionContextProvider for QuerierNamespace {
-    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+    fn new_query_context_with_priority(
+        &self,
+        span_ctx: Option<SpanContext>,
+        priority: Option<QueryPriority>,
+    ) -> IOxSessionContext {
+        let priority = priority.unwrap_or(self.default_priority);
         let mut cfg = self
             .exec
-            .new_execution_config(ExecutorType::Query)
+            .new_execution_config(ExecutorType::PriorityQuery(priority))
             .with_default_catalog(Arc::new(QuerierCatalogProvider::from_namespace(self)) as _)
+            .with_memory_pool(self.quota.memory_pool())
             .with_span_context(span_ctx);
 
         for (k, v) in self.datafusion_config.as_ref() {
@@ -7,7 +7,12 @@ use iox_query::{
 };
 use predicate::Predicate;
 use schema::{sort::SortKey, Projection, Schema};
-use std::{any::Any, sync::Arc};
+use std::{
+    any::Any,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 impl QueryChunkMeta for QuerierParquetChunk {
     fn summary(&self) -> Arc<TableSummary> {
@@ -85,6 +90,13 @@ impl QueryChunk for QuerierParquetChunk {
         "parquet"
     }
 
+    fn data_fingerprint(&self) -> Option<u64> {
+        // parquet files are immutable, so the ID identifies the data
+        let mut hasher = DefaultHasher::new();
+        self.meta().chunk_id.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     fn order(&self) -> ChunkOrder {
         self.meta().order()
     }
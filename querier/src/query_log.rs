@@ -1,6 +1,6 @@
 //! Ring buffer of queries that have been run with some brief information
 
-use data_types::NamespaceId;
+use data_types::{NamespaceId, QueryPriority};
 use iox_query::QueryText;
 use iox_time::{Time, TimeProvider};
 use parking_lot::Mutex;
@@ -9,6 +9,7 @@ use std::{
     sync::{atomic, Arc},
     time::Duration,
 };
+use tokio_util::sync::CancellationToken;
 use trace::ctx::TraceId;
 
 // The query duration used for queries still running.
@@ -16,12 +17,18 @@ const UNCOMPLETED_DURATION: i64 = -1;
 
 /// Information about a single query that was executed
 pub struct QueryLogEntry {
+    /// Unique ID of the query, used to cancel it.
+    pub id: u64,
+
     /// Namespace ID.
     pub namespace_id: NamespaceId,
 
     /// The type of query
     pub query_type: String,
 
+    /// The priority class the query ran in
+    pub priority: QueryPriority,
+
     /// The text of the query (SQL for sql queries, pbjson for storage rpc queries)
     pub query_text: QueryText,
 
@@ -37,37 +44,50 @@ pub struct QueryLogEntry {
 
     /// If the query completed successfully
     pub success: atomic::AtomicBool,
+
+    /// Token used to cancel the query while it is running.
+    cancellation_token: CancellationToken,
 }
 
 impl std::fmt::Debug for QueryLogEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("QueryLogEntry")
+            .field("id", &self.id)
             .field("query_type", &self.query_type)
+            .field("priority", &self.priority)
             .field("query_text", &self.query_text.to_string())
             .field("issue_time", &self.issue_time)
             .field("query_completed_duration", &self.query_completed_duration)
             .field("success", &self.success)
+            .field("cancelled", &self.cancelled())
             .finish()
     }
 }
 
 impl QueryLogEntry {
     /// Creates a new QueryLogEntry -- use `QueryLog::push` to add new entries to the log
+    #[allow(clippy::too_many_arguments)]
     fn new(
+        id: u64,
         namespace_id: NamespaceId,
         query_type: String,
+        priority: QueryPriority,
         query_text: QueryText,
         trace_id: Option<TraceId>,
         issue_time: Time,
+        cancellation_token: CancellationToken,
     ) -> Self {
         Self {
+            id,
             namespace_id,
             query_type,
+            priority,
             query_text,
             trace_id,
             issue_time,
             query_completed_duration: UNCOMPLETED_DURATION.into(),
             success: atomic::AtomicBool::new(false),
+            cancellation_token,
         }
     }
 
@@ -88,6 +108,11 @@ impl QueryLogEntry {
         self.success.load(atomic::Ordering::SeqCst)
     }
 
+    /// Returns true if the query was cancelled.
+    pub fn cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
     /// Mark this entry complete as of `now`. `success` records if the
     /// entry is successful or not.
     pub fn set_completed(&self, now: Time, success: bool) {
@@ -105,6 +130,7 @@ pub struct QueryLog {
     log: Mutex<VecDeque<Arc<QueryLogEntry>>>,
     max_size: usize,
     time_provider: Arc<dyn TimeProvider>,
+    next_id: atomic::AtomicU64,
 }
 
 impl QueryLog {
@@ -115,6 +141,7 @@ impl QueryLog {
             log: Mutex::new(VecDeque::with_capacity(max_size)),
             max_size,
             time_provider,
+            next_id: atomic::AtomicU64::new(1),
         }
     }
 
@@ -122,15 +149,20 @@ impl QueryLog {
         &self,
         namespace_id: NamespaceId,
         query_type: impl Into<String>,
+        priority: QueryPriority,
         query_text: QueryText,
         trace_id: Option<TraceId>,
+        cancellation_token: CancellationToken,
     ) -> Arc<QueryLogEntry> {
         let entry = Arc::new(QueryLogEntry::new(
+            self.next_id.fetch_add(1, atomic::Ordering::Relaxed),
             namespace_id,
             query_type.into(),
+            priority,
             query_text,
             trace_id,
             self.time_provider.now(),
+            cancellation_token,
         ));
 
         if self.max_size == 0 {
@@ -158,6 +190,26 @@ impl QueryLog {
     pub fn set_completed(&self, entry: Arc<QueryLogEntry>, success: bool) {
         entry.set_completed(self.time_provider.now(), success)
     }
+
+    /// Cancels the running query with the given `id` within the given namespace.
+    ///
+    /// Returns `false` if no such query is running. Queries that were already evicted from the log cannot be cancelled.
+    pub fn cancel(&self, namespace_id: NamespaceId, id: u64) -> bool {
+        let log = self.log.lock();
+        let Some(entry) = log
+            .iter()
+            .find(|e| e.id == id && e.namespace_id == namespace_id)
+        else {
+            return false;
+        };
+
+        if entry.query_completed_duration().is_some() {
+            return false;
+        }
+
+        entry.cancellation_token.cancel();
+        true
+    }
 }
 
 #[cfg(test)]
@@ -171,11 +223,14 @@ mod test_super {
         let time_provider = MockProvider::new(Time::from_timestamp_millis(100).unwrap());
 
         let entry = Arc::new(QueryLogEntry::new(
+            1,
             NamespaceId::new(1),
             "sql".into(),
+            QueryPriority::Normal,
             Box::new("SELECT 1"),
             None,
             time_provider.now(),
+            CancellationToken::new(),
         ));
         // query has not completed
         assert_eq!(entry.query_completed_duration(), None);
@@ -198,4 +253,45 @@ mod test_super {
         );
         assert!(!entry.success());
     }
+
+    #[test]
+    fn test_query_log_cancel() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_millis(100).unwrap()));
+        let query_log = QueryLog::new(10, time_provider);
+        let ns1 = NamespaceId::new(1);
+        let ns2 = NamespaceId::new(2);
+
+        let entry1 = query_log.push(
+            ns1,
+            "sql",
+            QueryPriority::Normal,
+            Box::new("SELECT 1"),
+            None,
+            CancellationToken::new(),
+        );
+        let entry2 = query_log.push(
+            ns1,
+            "sql",
+            QueryPriority::Normal,
+            Box::new("SELECT 2"),
+            None,
+            CancellationToken::new(),
+        );
+        assert_ne!(entry1.id, entry2.id);
+
+        // unknown ID
+        assert!(!query_log.cancel(ns1, 42));
+
+        // wrong namespace
+        assert!(!query_log.cancel(ns2, entry1.id));
+        assert!(!entry1.cancelled());
+
+        // completed queries cannot be cancelled
+        query_log.set_completed(Arc::clone(&entry2), true);
+        assert!(!query_log.cancel(ns1, entry2.id));
+        assert!(!entry2.cancelled());
+
+        assert!(query_log.cancel(ns1, entry1.id));
+        assert!(entry1.cancelled());
+    }
 }
@@ -0,0 +1,197 @@
+//! Admission control for query priority classes.
+//!
+//! Every query runs in one [`QueryPriority`] class, which selects the executor pool it runs on (see
+//! [`ExecutorType::PriorityQuery`](iox_query::exec::ExecutorType::PriorityQuery)). On top of the querier-wide
+//! `max_concurrent_queries`, each class may be limited to a number of concurrently running queries, so that e.g. a
+//! burst of batch queries cannot take all query slots away from interactive ones.
+use data_types::QueryPriority;
+use datafusion::error::{DataFusionError, Result};
+use metric::{U64Counter, U64Gauge};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Sentinel for "no limit".
+const UNLIMITED: usize = usize::MAX;
+
+/// Admission state of all priority classes.
+#[derive(Debug)]
+pub struct QueryPriorityClasses {
+    classes: HashMap<QueryPriority, Arc<PriorityClass>>,
+}
+
+impl QueryPriorityClasses {
+    /// Create classes without limits.
+    pub fn new(metric_registry: &metric::Registry) -> Self {
+        let admitted = metric_registry.register_metric::<U64Counter>(
+            "query_priority_admitted",
+            "Number of queries admitted by their priority class",
+        );
+        let rejected = metric_registry.register_metric::<U64Counter>(
+            "query_priority_rejected",
+            "Number of queries rejected because their priority class was running its maximum number of queries",
+        );
+        let running = metric_registry.register_metric::<U64Gauge>(
+            "query_priority_running",
+            "Number of currently running queries by priority class",
+        );
+
+        let classes = QueryPriority::ALL
+            .into_iter()
+            .map(|priority| {
+                let attributes = [("priority", priority.as_str())];
+                let class = PriorityClass {
+                    priority,
+                    max_concurrent_queries: AtomicUsize::new(UNLIMITED),
+                    running_queries: AtomicUsize::new(0),
+                    admitted: admitted.recorder(&attributes),
+                    rejected: rejected.recorder(&attributes),
+                    running: running.recorder(&attributes),
+                };
+                (priority, Arc::new(class))
+            })
+            .collect();
+
+        Self { classes }
+    }
+
+    /// Limit the number of concurrently running queries of `priority`.
+    ///
+    /// Queries that already run are NOT affected by lowered limits.
+    pub fn set_max_concurrent_queries(&self, priority: QueryPriority, max: usize) {
+        self.class(priority)
+            .max_concurrent_queries
+            .store(max, Ordering::Relaxed);
+    }
+
+    /// Admit a query of the given priority class.
+    ///
+    /// The query counts against the limit of its class until the returned [`AdmittedQuery`] is dropped.
+    pub fn try_admit(&self, priority: QueryPriority) -> Result<AdmittedQuery> {
+        let class = self.class(priority);
+
+        let max = class.max_concurrent_queries.load(Ordering::Relaxed);
+        class
+            .running_queries
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |running| {
+                (running < max).then_some(running + 1)
+            })
+            .map_err(|_| {
+                class.rejected.inc(1);
+                DataFusionError::ResourcesExhausted(format!(
+                    "querier is already running its limit of {max} concurrent {} queries",
+                    class.priority
+                ))
+            })?;
+        class.admitted.inc(1);
+        class.running.inc(1);
+
+        Ok(AdmittedQuery {
+            class: Arc::clone(class),
+        })
+    }
+
+    fn class(&self, priority: QueryPriority) -> &Arc<PriorityClass> {
+        self.classes
+            .get(&priority)
+            .expect("all priority classes are registered")
+    }
+}
+
+/// State of a single priority class.
+#[derive(Debug)]
+struct PriorityClass {
+    /// The class, used in error messages.
+    priority: QueryPriority,
+
+    /// Maximum number of concurrently running queries.
+    max_concurrent_queries: AtomicUsize,
+
+    /// Number of currently running queries.
+    running_queries: AtomicUsize,
+
+    /// Metrics.
+    admitted: U64Counter,
+    rejected: U64Counter,
+    running: U64Gauge,
+}
+
+/// A query admitted by its priority class, released on drop.
+#[derive(Debug)]
+pub struct AdmittedQuery {
+    class: Arc<PriorityClass>,
+}
+
+impl Drop for AdmittedQuery {
+    fn drop(&mut self) {
+        self.class.running_queries.fetch_sub(1, Ordering::AcqRel);
+        self.class.running.dec(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn counter(registry: &metric::Registry, name: &'static str, priority: QueryPriority) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>(name)
+            .expect("metric registered")
+            .get_observer(&Attributes::from(&[("priority", priority.as_str())]))
+            .expect("observer exists")
+            .fetch()
+    }
+
+    fn running(registry: &metric::Registry, priority: QueryPriority) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Gauge>>("query_priority_running")
+            .expect("metric registered")
+            .get_observer(&Attributes::from(&[("priority", priority.as_str())]))
+            .expect("observer exists")
+            .fetch()
+    }
+
+    #[test]
+    fn test_admission() {
+        let registry = metric::Registry::default();
+        let classes = QueryPriorityClasses::new(&registry);
+        classes.set_max_concurrent_queries(QueryPriority::Batch, 1);
+
+        let batch_1 = classes.try_admit(QueryPriority::Batch).unwrap();
+        let err = classes.try_admit(QueryPriority::Batch).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Resources exhausted: querier is already running its limit of 1 concurrent batch queries"
+        );
+        assert_eq!(
+            counter(&registry, "query_priority_admitted", QueryPriority::Batch),
+            1
+        );
+        assert_eq!(
+            counter(&registry, "query_priority_rejected", QueryPriority::Batch),
+            1
+        );
+        assert_eq!(running(&registry, QueryPriority::Batch), 1);
+
+        // other classes are not affected
+        let _interactive_1 = classes.try_admit(QueryPriority::Interactive).unwrap();
+        let _interactive_2 = classes.try_admit(QueryPriority::Interactive).unwrap();
+        assert_eq!(running(&registry, QueryPriority::Interactive), 2);
+
+        // finishing a query makes room for another one
+        drop(batch_1);
+        assert_eq!(running(&registry, QueryPriority::Batch), 0);
+        let _batch_2 = classes.try_admit(QueryPriority::Batch).unwrap();
+
+        // lifting the limit applies to the existing state
+        classes.set_max_concurrent_queries(QueryPriority::Batch, UNLIMITED);
+        let _batch_3 = classes.try_admit(QueryPriority::Batch).unwrap();
+        assert_eq!(running(&registry, QueryPriority::Batch), 2);
+    }
+}
@@ -0,0 +1,358 @@
+//! Per-namespace query quotas.
+//!
+//! The querier-wide limits (`max_concurrent_queries` and the DataFusion memory pool) are shared by all namespaces.
+//! Namespaces may additionally be limited by a [`QueryQuota`] stored in the catalog, which caps the number of queries
+//! they run at the same time and the number of bytes their queries reserve from the global memory pool.
+use data_types::{NamespaceId, QueryQuota};
+use datafusion::{
+    error::{DataFusionError, Result},
+    execution::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation},
+};
+use metric::U64Counter;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Sentinel for "no limit".
+const UNLIMITED: usize = usize::MAX;
+
+/// Metrics for quota rejections.
+#[derive(Debug)]
+struct QuotaMetrics {
+    /// Queries rejected because the namespace was already running its maximum number of queries.
+    rejected_concurrent_queries: U64Counter,
+
+    /// Memory reservations rejected because the namespace exhausted its share of the memory pool.
+    rejected_memory: U64Counter,
+}
+
+impl QuotaMetrics {
+    fn new(metric_registry: &metric::Registry) -> Self {
+        let metric = metric_registry.register_metric::<U64Counter>(
+            "query_namespace_quota_rejected",
+            "Number of queries or memory reservations rejected by a per-namespace query quota",
+        );
+
+        Self {
+            rejected_concurrent_queries: metric.recorder(&[("quota", "concurrent_queries")]),
+            rejected_memory: metric.recorder(&[("quota", "memory")]),
+        }
+    }
+}
+
+/// Registry of the quota state of all namespaces the querier has seen.
+#[derive(Debug)]
+pub struct QueryQuotas {
+    /// Querier-wide memory pool the namespace pools are carved out of.
+    global_pool: Arc<dyn MemoryPool>,
+
+    /// Quota state by namespace.
+    namespaces: Mutex<HashMap<NamespaceId, Arc<NamespaceQuota>>>,
+
+    /// Metrics shared by all namespaces.
+    metrics: Arc<QuotaMetrics>,
+}
+
+impl QueryQuotas {
+    /// Create new, empty registry on top of the given global memory pool.
+    pub fn new(global_pool: Arc<dyn MemoryPool>, metric_registry: &metric::Registry) -> Self {
+        Self {
+            global_pool,
+            namespaces: Default::default(),
+            metrics: Arc::new(QuotaMetrics::new(metric_registry)),
+        }
+    }
+
+    /// Get the quota state of the given namespace.
+    ///
+    /// The limits are updated to `quota`, so catalog changes apply once the namespace cache picked them up. Queries
+    /// that already run are NOT affected by lowered limits.
+    pub fn namespace(
+        &self,
+        namespace_id: NamespaceId,
+        namespace_name: Arc<str>,
+        quota: QueryQuota,
+    ) -> Arc<NamespaceQuota> {
+        let ns_quota = Arc::clone(self.namespaces.lock().entry(namespace_id).or_insert_with(
+            || {
+                Arc::new(NamespaceQuota::new(
+                    namespace_name,
+                    Arc::clone(&self.global_pool),
+                    Arc::clone(&self.metrics),
+                ))
+            },
+        ));
+        ns_quota.set_quota(quota);
+        ns_quota
+    }
+}
+
+/// Quota state of a single namespace, shared by all its queries.
+#[derive(Debug)]
+pub struct NamespaceQuota {
+    /// Namespace name, used in error messages.
+    namespace_name: Arc<str>,
+
+    /// Maximum number of concurrently running queries.
+    max_concurrent_queries: AtomicUsize,
+
+    /// Number of currently running queries.
+    running_queries: AtomicUsize,
+
+    /// Memory pool that accounts the reservations of all queries of this namespace.
+    memory_pool: Arc<NamespaceMemoryPool>,
+
+    /// Metrics.
+    metrics: Arc<QuotaMetrics>,
+}
+
+impl NamespaceQuota {
+    fn new(
+        namespace_name: Arc<str>,
+        global_pool: Arc<dyn MemoryPool>,
+        metrics: Arc<QuotaMetrics>,
+    ) -> Self {
+        Self {
+            memory_pool: Arc::new(NamespaceMemoryPool {
+                namespace_name: Arc::clone(&namespace_name),
+                inner: global_pool,
+                limit: AtomicUsize::new(UNLIMITED),
+                used: AtomicUsize::new(0),
+                metrics: Arc::clone(&metrics),
+            }),
+            namespace_name,
+            max_concurrent_queries: AtomicUsize::new(UNLIMITED),
+            running_queries: AtomicUsize::new(0),
+            metrics,
+        }
+    }
+
+    fn set_quota(&self, quota: QueryQuota) {
+        self.max_concurrent_queries.store(
+            to_limit(quota.max_concurrent_queries.map(i64::from)),
+            Ordering::Relaxed,
+        );
+        self.memory_pool
+            .limit
+            .store(to_limit(quota.max_memory_bytes), Ordering::Relaxed);
+    }
+
+    /// Reserve one of the concurrent query slots of this namespace.
+    ///
+    /// The slot is released when the returned [`QuerySlot`] is dropped.
+    pub fn try_acquire_query_slot(self: &Arc<Self>) -> Result<QuerySlot> {
+        let max = self.max_concurrent_queries.load(Ordering::Relaxed);
+        self.running_queries
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |running| {
+                (running < max).then_some(running + 1)
+            })
+            .map_err(|_| {
+                self.metrics.rejected_concurrent_queries.inc(1);
+                DataFusionError::ResourcesExhausted(format!(
+                    "namespace '{}' is already running its quota of {max} concurrent queries",
+                    self.namespace_name
+                ))
+            })?;
+
+        Ok(QuerySlot {
+            quota: Arc::clone(self),
+        })
+    }
+
+    /// Memory pool for the queries of this namespace.
+    pub fn memory_pool(&self) -> Arc<dyn MemoryPool> {
+        Arc::clone(&self.memory_pool) as _
+    }
+}
+
+/// Converts a catalog limit to the internal encoding.
+fn to_limit(v: Option<i64>) -> usize {
+    match v {
+        Some(v) if v > 0 => v as usize,
+        _ => UNLIMITED,
+    }
+}
+
+/// One of the concurrent query slots of a namespace, released on drop.
+#[derive(Debug)]
+pub struct QuerySlot {
+    quota: Arc<NamespaceQuota>,
+}
+
+impl Drop for QuerySlot {
+    fn drop(&mut self) {
+        self.quota.running_queries.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// [`MemoryPool`] that caps the reservations of a namespace and forwards them to the querier-wide pool.
+#[derive(Debug)]
+struct NamespaceMemoryPool {
+    /// Namespace name, used in error messages.
+    namespace_name: Arc<str>,
+
+    /// Querier-wide pool.
+    inner: Arc<dyn MemoryPool>,
+
+    /// Maximum number of bytes that may be reserved by this namespace.
+    limit: AtomicUsize,
+
+    /// Number of bytes currently reserved by this namespace.
+    used: AtomicUsize,
+
+    /// Metrics.
+    metrics: Arc<QuotaMetrics>,
+}
+
+impl MemoryPool for NamespaceMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.used.fetch_add(additional, Ordering::Relaxed);
+        self.inner.grow(reservation, additional)
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.used.fetch_sub(shrink, Ordering::Relaxed);
+        self.inner.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        let limit = self.limit.load(Ordering::Relaxed);
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let new_used = used.checked_add(additional)?;
+                (new_used <= limit).then_some(new_used)
+            })
+            .map_err(|used| {
+                self.metrics.rejected_memory.inc(1);
+                DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate additional {additional} bytes: namespace '{}' query memory quota \
+                    of {limit} bytes exhausted ({used} bytes in use)",
+                    self.namespace_name,
+                ))
+            })?;
+
+        if let Err(e) = self.inner.try_grow(reservation, additional) {
+            self.used.fetch_sub(additional, Ordering::Relaxed);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn rejected(registry: &metric::Registry, quota: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>("query_namespace_quota_rejected")
+            .expect("metric registered")
+            .get_observer(&Attributes::from(&[("quota", quota)]))
+            .expect("observer exists")
+            .fetch()
+    }
+
+    #[test]
+    fn test_concurrent_queries() {
+        let registry = metric::Registry::default();
+        let quotas = QueryQuotas::new(Arc::new(GreedyMemoryPool::new(usize::MAX)), &registry);
+        let quota = QueryQuota {
+            max_concurrent_queries: Some(2),
+            max_memory_bytes: None,
+        };
+        let ns = quotas.namespace(NamespaceId::new(1), Arc::from("ns"), quota);
+
+        let slot_1 = ns.try_acquire_query_slot().unwrap();
+        let _slot_2 = ns.try_acquire_query_slot().unwrap();
+        let err = ns.try_acquire_query_slot().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Resources exhausted: namespace 'ns' is already running its quota of 2 concurrent queries"
+        );
+        assert_eq!(rejected(&registry, "concurrent_queries"), 1);
+
+        // releasing a slot makes room for another query
+        drop(slot_1);
+        let _slot_3 = ns.try_acquire_query_slot().unwrap();
+
+        // other namespaces are not affected
+        let other = quotas.namespace(NamespaceId::new(2), Arc::from("other"), quota);
+        let _slot = other.try_acquire_query_slot().unwrap();
+
+        // lifting the quota applies to the existing state
+        let ns = quotas.namespace(NamespaceId::new(1), Arc::from("ns"), QueryQuota::default());
+        let _slot_4 = ns.try_acquire_query_slot().unwrap();
+    }
+
+    #[test]
+    fn test_memory() {
+        let registry = metric::Registry::default();
+        let global_pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let quotas = QueryQuotas::new(Arc::clone(&global_pool), &registry);
+        let quota = QueryQuota {
+            max_concurrent_queries: None,
+            max_memory_bytes: Some(10),
+        };
+        let ns = quotas.namespace(NamespaceId::new(1), Arc::from("ns"), quota);
+        let pool = ns.memory_pool();
+
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        reservation.try_grow(8).unwrap();
+        assert_eq!(pool.reserved(), 8);
+        assert_eq!(global_pool.reserved(), 8);
+
+        let err = reservation.try_grow(3).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert!(err
+            .to_string()
+            .contains("namespace 'ns' query memory quota of 10 bytes exhausted"));
+        assert_eq!(pool.reserved(), 8);
+        assert_eq!(global_pool.reserved(), 8);
+        assert_eq!(rejected(&registry, "memory"), 1);
+
+        // other namespaces are only bound by the global pool
+        let other_pool = quotas
+            .namespace(
+                NamespaceId::new(2),
+                Arc::from("other"),
+                QueryQuota::default(),
+            )
+            .memory_pool();
+        let mut other_reservation = MemoryConsumer::new("test").register(&other_pool);
+        other_reservation.try_grow(90).unwrap();
+        assert_eq!(global_pool.reserved(), 98);
+
+        // the global pool still applies to namespaces with a quota
+        reservation.shrink(8);
+        assert_eq!(pool.reserved(), 0);
+        let err = reservation.try_grow(10).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(pool.reserved(), 0);
+
+        drop(other_reservation);
+        reservation.try_grow(10).unwrap();
+        drop(reservation);
+        assert_eq!(pool.reserved(), 0);
+        assert_eq!(global_pool.reserved(), 0);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 use arrow::{
     array::{
         ArrayRef, BooleanArray, DurationNanosecondArray, Int64Array, StringArray,
-        TimestampNanosecondArray,
+        TimestampNanosecondArray, UInt64Array,
     },
     datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
     error::Result,
@@ -75,7 +75,7 @@ impl IoxSystemTable for QueriesTable {
 }
 
 fn queries_schema(include_namespace_id: bool) -> SchemaRef {
-    let mut columns = vec![];
+    let mut columns = vec![Field::new("id", DataType::UInt64, false)];
     if include_namespace_id {
         columns.push(Field::new("namespace_id", DataType::Int64, false));
     }
@@ -86,6 +86,7 @@ fn queries_schema(include_namespace_id: bool) -> SchemaRef {
             false,
         ),
         Field::new("query_type", DataType::Utf8, false),
+        Field::new("priority", DataType::Utf8, false),
         Field::new("query_text", DataType::Utf8, false),
         Field::new(
             "completed_duration",
@@ -106,7 +107,14 @@ fn from_query_log_entries(
     len: usize,
     include_namespace_id: bool,
 ) -> Result<RecordBatch> {
-    let mut columns: Vec<ArrayRef> = vec![];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(
+        entries
+            .iter()
+            .skip(offset)
+            .take(len)
+            .map(|e| Some(e.id))
+            .collect::<UInt64Array>(),
+    )];
 
     if include_namespace_id {
         columns.push(Arc::new(
@@ -138,6 +146,15 @@ fn from_query_log_entries(
             .collect::<StringArray>(),
     ));
 
+    columns.push(Arc::new(
+        entries
+            .iter()
+            .skip(offset)
+            .take(len)
+            .map(|e| Some(e.priority.as_str()))
+            .collect::<StringArray>(),
+    ));
+
     columns.push(Arc::new(
         entries
             .iter()
@@ -181,7 +198,9 @@ fn from_query_log_entries(
 mod tests {
     use super::*;
     use arrow_util::assert_batches_eq;
+    use data_types::QueryPriority;
     use iox_time::{Time, TimeProvider};
+    use tokio_util::sync::CancellationToken;
     use trace::ctx::TraceId;
 
     #[test]
@@ -196,26 +215,42 @@ mod tests {
             10,
             Arc::clone(&time_provider) as Arc<dyn TimeProvider>,
         ));
-        query_log.push(id1, "sql", Box::new("select * from foo"), None);
+        query_log.push(
+            id1,
+            "sql",
+            QueryPriority::Normal,
+            Box::new("select * from foo"),
+            None,
+            CancellationToken::new(),
+        );
         time_provider.inc(std::time::Duration::from_secs(24 * 60 * 60));
-        let sql2_entry = query_log.push(id1, "sql", Box::new("select * from bar"), None);
+        let sql2_entry = query_log.push(
+            id1,
+            "sql",
+            QueryPriority::Batch,
+            Box::new("select * from bar"),
+            None,
+            CancellationToken::new(),
+        );
         let read_filter_entry = query_log.push(
             id2,
             "read_filter",
+            QueryPriority::Interactive,
             Box::new("json goop"),
             Some(TraceId::new(0x45fe).unwrap()),
+            CancellationToken::new(),
         );
 
         let table = QueriesTable::new(Arc::clone(&query_log), None);
 
         let expected = vec![
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          |",
-            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar |                    | false   |          |",
-            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         |                    | false   | 45fe     |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
+            "+----+--------------+----------------------+-------------+-------------+-------------------+--------------------+---------+----------+",
+            "| id | namespace_id | issue_time           | query_type  | priority    | query_text        | completed_duration | success | trace_id |",
+            "+----+--------------+----------------------+-------------+-------------+-------------------+--------------------+---------+----------+",
+            "| 1  | 1            | 1996-12-19T16:39:57Z | sql         | normal      | select * from foo |                    | false   |          |",
+            "| 2  | 1            | 1996-12-20T16:39:57Z | sql         | batch       | select * from bar |                    | false   |          |",
+            "| 3  | 2            | 1996-12-20T16:39:57Z | read_filter | interactive | json goop         |                    | false   | 45fe     |",
+            "+----+--------------+----------------------+-------------+-------------+-------------------+--------------------+---------+----------+",
         ];
 
         let entries = table.scan(3).unwrap().collect::<Result<Vec<_>>>().unwrap();
@@ -230,13 +265,13 @@ mod tests {
         read_filter_entry.set_completed(now, true);
 
         let expected = vec![
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          |",
-            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar | 4s                 | false   |          |",
-            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         | 4s                 | true    | 45fe     |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
+            "+----+--------------+----------------------+-------------+-------------+-------------------+--------------------+---------+----------+",
+            "| id | namespace_id | issue_time           | query_type  | priority    | query_text        | completed_duration | success | trace_id |",
+            "+----+--------------+----------------------+-------------+-------------+-------------------+--------------------+---------+----------+",
+            "| 1  | 1            | 1996-12-19T16:39:57Z | sql         | normal      | select * from foo |                    | false   |          |",
+            "| 2  | 1            | 1996-12-20T16:39:57Z | sql         | batch       | select * from bar | 4s                 | false   |          |",
+            "| 3  | 2            | 1996-12-20T16:39:57Z | read_filter | interactive | json goop         | 4s                 | true    | 45fe     |",
+            "+----+--------------+----------------------+-------------+-------------+-------------------+--------------------+---------+----------+",
         ];
 
         let entries = table.scan(2).unwrap().collect::<Result<Vec<_>>>().unwrap();
@@ -247,12 +282,12 @@ mod tests {
         let table = QueriesTable::new(Arc::clone(&query_log), Some(id1));
 
         let expected = vec![
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
-            "| issue_time           | query_type | query_text        | completed_duration | success | trace_id |",
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
-            "| 1996-12-19T16:39:57Z | sql        | select * from foo |                    | false   |          |",
-            "| 1996-12-20T16:39:57Z | sql        | select * from bar | 4s                 | false   |          |",
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
+            "+----+----------------------+------------+----------+-------------------+--------------------+---------+----------+",
+            "| id | issue_time           | query_type | priority | query_text        | completed_duration | success | trace_id |",
+            "+----+----------------------+------------+----------+-------------------+--------------------+---------+----------+",
+            "| 1  | 1996-12-19T16:39:57Z | sql        | normal   | select * from foo |                    | false   |          |",
+            "| 2  | 1996-12-20T16:39:57Z | sql        | batch    | select * from bar | 4s                 | false   |          |",
+            "+----+----------------------+------------+----------+-------------------+--------------------+---------+----------+",
         ];
 
         let entries = table.scan(3).unwrap().collect::<Result<Vec<_>>>().unwrap();
@@ -323,6 +323,90 @@ impl QuerierTable {
         Ok(chunks)
     }
 
+    /// Query the most recent value of every column for each series within
+    /// this table from the ingesters' last value caches.
+    ///
+    /// Returns [`None`] if no ingesters are configured, or if the ingester
+    /// caches do not provably hold the latest values of the table.
+    pub async fn last_value_chunks(
+        &self,
+        span: Option<Span>,
+    ) -> Result<Option<Vec<Arc<dyn QueryChunk>>>> {
+        let mut span_recorder = SpanRecorder::new(span);
+
+        let Some(ingester_connection) = &self.ingester_connection else {
+            span_recorder.ok("No ingesters configured");
+            return Ok(None);
+        };
+
+        // get cached table w/o any must-coverage information
+        let Some(cached_table) = self.chunk_adapter
+            .catalog_cache()
+            .namespace()
+            .get(
+                Arc::clone(&self.namespace_name),
+                &[],
+                span_recorder.child_span("get namespace")
+            )
+            .await
+            .and_then(|ns| ns.tables.get(&self.table_name).cloned())
+        else {
+            span_recorder.ok("Table not found");
+            return Ok(None)
+        };
+
+        let partitions = match ingester_connection
+            .last_values(
+                self.namespace_id,
+                cached_table,
+                self.schema.select_given_and_pk_columns(None),
+                span_recorder.child_span("IngesterConnection last_values"),
+            )
+            .await
+            .context(GettingIngesterPartitionsSnafu)
+        {
+            Ok(Some(partitions)) => partitions,
+            Ok(None) => {
+                span_recorder.ok("Not covered");
+                return Ok(None);
+            }
+            Err(e) => {
+                span_recorder.error("failed");
+                return Err(e);
+            }
+        };
+
+        // The cache may still hold values older than the retention period,
+        // mask them out as is done for regular ingester data.
+        let retention_delete_pred = self.namespace_retention_period.map(|retention_period| {
+            let retention_time_ns = self
+                .chunk_adapter
+                .catalog_cache()
+                .time_provider()
+                .now()
+                .timestamp_nanos()
+                - retention_period.as_nanos() as i64;
+            Arc::new(DeletePredicate::retention_delete_predicate(
+                retention_time_ns,
+            ))
+        });
+
+        let chunks = partitions
+            .into_iter()
+            .flat_map(|p| {
+                let p = match &retention_delete_pred {
+                    Some(pred) => p.with_delete_predicates(vec![Arc::clone(pred)]),
+                    None => p,
+                };
+                p.into_chunks()
+            })
+            .map(|c| Arc::new(c) as Arc<dyn QueryChunk>)
+            .collect();
+
+        span_recorder.ok("got last value chunks");
+        Ok(Some(chunks))
+    }
+
     /// Get a chunk pruner that can be used to prune chunks retrieved via [`chunks`](Self::chunks)
     pub fn chunk_pruner(&self) -> Arc<dyn ChunkPruner> {
         Arc::new(QuerierTableChunkPruner::new(Arc::clone(
@@ -11,7 +11,7 @@ use datafusion::{
     prelude::Expr,
 };
 use iox_query::{
-    exec::SessionContextIOxExt,
+    exec::{cancel::run_cancellable, SessionContextIOxExt},
     provider::{ChunkPruner, Error as ProviderError, ProviderBuilder},
     pruning::{prune_chunks, NotPrunedReason, PruningObserver},
     QueryChunk,
@@ -59,13 +59,17 @@ impl TableProvider for QuerierTable {
             .cloned()
             .fold(Predicate::default(), Predicate::with_expr);
 
-        let chunks = self
-            .chunks(
+        // Dropping the future on cancellation also stops any in-flight ingester requests.
+        let chunks = run_cancellable(ctx.cancellation_token(), async {
+            self.chunks(
                 &pruning_predicate,
                 ctx.child_span("QuerierTable chunks"),
                 projection,
             )
-            .await?;
+            .await
+            .map_err(DataFusionError::from)
+        })
+        .await?;
 
         for chunk in chunks {
             builder = builder.add_chunk(chunk);
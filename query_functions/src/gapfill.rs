@@ -9,12 +9,13 @@
 //!   DATE_BIN_GAPFILL(INTERVAL '1 minute', time, '1970-01-01T00:00:00Z') AS minute,
 //!   LOCF(AVG(temp))
 //!   INTERPOLATE(AVG(humidity))
+//!   FILL(MAX(pressure), 0)
 //! FROM temps
 //! WHERE time > NOW() - INTERVAL '6 hours' AND time < NOW()
 //! GROUP BY LOCATION, MINUTE
 //! ```
 //!
-//! The functions `DATE_BIN_GAPFILL`, `LOCF`, and `INTERPOLATE` are special,
+//! The functions `DATE_BIN_GAPFILL`, `LOCF`, `INTERPOLATE` and `FILL` are special,
 //! in that they don't have normal implementations, but instead
 //! are transformed by logical optimizer rule `HandleGapFill` to
 //! produce a plan that fills gaps.
@@ -103,6 +104,26 @@ pub(crate) static INTERPOLATE: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
     ))
 });
 
+/// The name of the fill UDF given to DataFusion.
+pub const FILL_UDF_NAME: &str = "fill";
+
+/// (Non-)Implementation of fill.
+/// This function takes two arguments: a column of any type and
+/// a constant value, and produces a value of the same type as the column.
+/// It is used in the context of gap-filling queries to indicate
+/// columns whose gaps should be filled with the constant value. It does
+/// not have an implementation since it will be consumed by the logical
+/// optimizer rule `HandleGapFill`.
+pub(crate) static FILL: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|args| Ok(Arc::new(args[0].clone())));
+    Arc::new(ScalarUDF::new(
+        FILL_UDF_NAME,
+        &Signature::any(2, Volatility::Volatile),
+        &return_type_fn,
+        &unimplemented_scalar_impl(FILL_UDF_NAME),
+    ))
+});
+
 fn unimplemented_scalar_impl(name: &'static str) -> ScalarFunctionImplementation {
     Arc::new(move |_| {
         Err(DataFusionError::NotImplemented(format!(
@@ -117,7 +138,7 @@ mod test {
     use arrow::record_batch::RecordBatch;
     use datafusion::common::assert_contains;
     use datafusion::error::Result;
-    use datafusion::prelude::{col, lit_timestamp_nano, Expr};
+    use datafusion::prelude::{col, lit, lit_timestamp_nano, Expr};
     use datafusion::scalar::ScalarValue;
     use datafusion_util::context_with_table;
     use std::sync::Arc;
@@ -197,4 +218,27 @@ mod test {
             .to_string()
             .contains(expected));
     }
+
+    fn fill(arg: Expr, value: Expr) -> Expr {
+        crate::registry()
+            .udf(super::FILL_UDF_NAME)
+            .expect("should be registered")
+            .call(vec![arg, value])
+    }
+
+    #[tokio::test]
+    async fn fill_errs() {
+        let arg = Arc::new(Float64Array::from(vec![100.0]));
+        let rb = RecordBatch::try_from_iter(vec![("f0", arg as ArrayRef)]).unwrap();
+        let ctx = context_with_table(rb);
+        let df = ctx
+            .table("t")
+            .await
+            .unwrap()
+            .select(vec![fill(col("f0"), lit(0.0))])
+            .unwrap();
+        let res = df.collect().await;
+        let expected = "fill is not yet implemented";
+        assert_contains!(res.expect_err("should be an error").to_string(), expected);
+    }
 }
@@ -27,6 +27,7 @@ impl FunctionRegistry for IOxFunctionRegistry {
             gapfill::DATE_BIN_GAPFILL_UDF_NAME,
             gapfill::LOCF_UDF_NAME,
             gapfill::INTERPOLATE_UDF_NAME,
+            gapfill::FILL_UDF_NAME,
             regex::REGEX_MATCH_UDF_NAME,
             regex::REGEX_NOT_MATCH_UDF_NAME,
             window::WINDOW_BOUNDS_UDF_NAME,
@@ -41,6 +42,7 @@ impl FunctionRegistry for IOxFunctionRegistry {
             gapfill::DATE_BIN_GAPFILL_UDF_NAME => Ok(gapfill::DATE_BIN_GAPFILL.clone()),
             gapfill::LOCF_UDF_NAME => Ok(gapfill::LOCF.clone()),
             gapfill::INTERPOLATE_UDF_NAME => Ok(gapfill::INTERPOLATE.clone()),
+            gapfill::FILL_UDF_NAME => Ok(gapfill::FILL.clone()),
             regex::REGEX_MATCH_UDF_NAME => Ok(regex::REGEX_MATCH_UDF.clone()),
             regex::REGEX_NOT_MATCH_UDF_NAME => Ok(regex::REGEX_NOT_MATCH_UDF.clone()),
             window::WINDOW_BOUNDS_UDF_NAME => Ok(window::WINDOW_BOUNDS_UDF.clone()),
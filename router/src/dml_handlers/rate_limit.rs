@@ -0,0 +1,482 @@
+use std::{borrow::Cow, time::Duration};
+
+use async_trait::async_trait;
+use data_types::{NamespaceId, NamespaceName};
+use hashbrown::HashMap;
+use iox_time::{SystemProvider, Time, TimeProvider};
+use metric::{Metric, U64Counter};
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use thiserror::Error;
+use trace::ctx::SpanContext;
+
+use super::DmlHandler;
+use crate::namespace_cache::NamespaceCache;
+
+/// Errors emitted by the [`RateLimiter`].
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    /// The requested namespace could not be found in the catalog.
+    #[error("failed to read namespace schema from catalog: {0}")]
+    NamespaceLookup(iox_catalog::interface::Error),
+
+    /// The namespace has exceeded its configured write rate.
+    #[error(
+        "namespace {namespace} has exceeded its write rate limit, retry after {retry_after:?}"
+    )]
+    RateLimited {
+        /// The rate limited namespace.
+        namespace: String,
+        /// The minimum time the caller should wait before retrying.
+        retry_after: Duration,
+    },
+}
+
+impl RateLimitError {
+    /// Returns the minimum duration a client should wait before retrying the
+    /// rejected write, if applicable.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::NamespaceLookup(_) => None,
+            Self::RateLimited { retry_after, .. } => Some(*retry_after),
+        }
+    }
+}
+
+/// A token bucket refilled at a constant rate of `rate` tokens per second, up
+/// to a burst capacity of one second worth of tokens.
+///
+/// A write is admitted whenever at least one token is available, and is then
+/// charged in full, possibly leaving the bucket in debt. This allows writes
+/// larger than the per-second rate to succeed, while still bounding the
+/// sustained throughput to `rate`.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: i64,
+    tokens: f64,
+    last_refill: Time,
+}
+
+impl TokenBucket {
+    fn new(rate: i64, now: Time) -> Self {
+        Self {
+            rate,
+            tokens: rate as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Add the tokens accrued since the last refill, adopting `rate` as the
+    /// new refill rate if it changed.
+    fn refill(&mut self, rate: i64, now: Time) {
+        let elapsed = now
+            .checked_duration_since(self.last_refill)
+            .unwrap_or_default();
+        self.rate = rate;
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate as f64).min(rate as f64);
+        self.last_refill = now;
+    }
+
+    /// Return the time until at least one token is available, or [`None`] if
+    /// a token is available now.
+    ///
+    /// A bucket with a non-positive rate never refills and therefore admits
+    /// all writes. The catalog rejects such rates.
+    fn wait_time(&self) -> Option<Duration> {
+        if self.tokens >= 1.0 || self.rate <= 0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(
+            (1.0 - self.tokens) / self.rate as f64,
+        ))
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.tokens -= n as f64;
+    }
+}
+
+/// The rate limiting state of a single namespace.
+#[derive(Debug, Default)]
+struct NamespaceBuckets {
+    lines: Option<TokenBucket>,
+    batch_bytes: Option<TokenBucket>,
+}
+
+/// Refill (or initialise) `bucket` to reflect the configured `rate`, removing
+/// it if the rate is unlimited.
+fn refill(bucket: &mut Option<TokenBucket>, rate: Option<i64>, now: Time) {
+    match (bucket.as_mut(), rate) {
+        (_, None) => *bucket = None,
+        (Some(b), Some(rate)) => b.refill(rate, now),
+        (None, Some(rate)) => *bucket = Some(TokenBucket::new(rate, now)),
+    }
+}
+
+/// A [`DmlHandler`] implementation that enforces the per-namespace write
+/// [`WriteRateLimit`] using a pair of token buckets per namespace - one
+/// counting line protocol lines, and one counting batch bytes.
+///
+/// The number of lines in a write is the total number of rows across all
+/// tables, and the number of batch bytes is the approximate in-memory size of
+/// the decoded write data ([`MutableBatch::size_data()`]). The batch bytes are
+/// independent of the wire format and compression of the request, so they
+/// usually differ from the number of bytes sent by the client.
+///
+/// The batch bytes of all admitted writes are counted per namespace, so that
+/// limits can be chosen based on the observed rates.
+///
+/// Writes to a namespace exceeding either rate are rejected with
+/// [`RateLimitError::RateLimited`], reporting how long the caller should wait
+/// before retrying. A rejected write does not consume any tokens.
+///
+/// Namespace rate limits are loaded from the provided [`NamespaceCache`]
+/// implementation, so changes to the limits in the catalog take effect once
+/// the cached schema of the namespace is refreshed (see
+/// [`refresh_on_change`](crate::namespace_cache::refresh_on_change)).
+///
+/// [`WriteRateLimit`]: data_types::WriteRateLimit
+#[derive(Debug)]
+pub struct RateLimiter<C, P = SystemProvider> {
+    cache: C,
+    time_provider: P,
+
+    buckets: Mutex<HashMap<NamespaceName<'static>, NamespaceBuckets>>,
+
+    rate_limited: Metric<U64Counter>,
+    admitted_batch_bytes: Metric<U64Counter>,
+}
+
+impl<C> RateLimiter<C> {
+    /// Initialise a new [`RateLimiter`] that reads the namespace limits from
+    /// `cache`.
+    pub fn new(cache: C, metrics: &metric::Registry) -> Self {
+        let rate_limited = metrics.register_metric::<U64Counter>(
+            "router_write_rate_limited",
+            "number of writes rejected because the namespace exceeded its write rate limit",
+        );
+        let admitted_batch_bytes = metrics.register_metric::<U64Counter>(
+            "router_write_admitted_batch_bytes",
+            "in-memory size of the write batches admitted by the write rate limiter",
+        );
+
+        Self {
+            cache,
+            time_provider: Default::default(),
+            buckets: Default::default(),
+            rate_limited,
+            admitted_batch_bytes,
+        }
+    }
+}
+
+impl<C, P> RateLimiter<C, P> {
+    #[cfg(test)]
+    fn with_time_provider<U>(self, time_provider: U) -> RateLimiter<C, U>
+    where
+        U: TimeProvider,
+    {
+        RateLimiter {
+            cache: self.cache,
+            time_provider,
+            buckets: self.buckets,
+            rate_limited: self.rate_limited,
+            admitted_batch_bytes: self.admitted_batch_bytes,
+        }
+    }
+
+    fn record_rejection(&self, namespace: &NamespaceName<'static>, limit: &'static str) {
+        self.rate_limited
+            .recorder([
+                ("namespace", Cow::Owned(namespace.to_string())),
+                ("limit", Cow::Borrowed(limit)),
+            ])
+            .inc(1);
+    }
+
+    fn record_admission(&self, namespace: &NamespaceName<'static>, batch_bytes: usize) {
+        self.admitted_batch_bytes
+            .recorder([("namespace", Cow::Owned(namespace.to_string()))])
+            .inc(batch_bytes as u64);
+    }
+}
+
+#[async_trait]
+impl<C, P> DmlHandler for RateLimiter<C, P>
+where
+    C: NamespaceCache<ReadError = iox_catalog::interface::Error>, // The handler expects the cache to read from the catalog if necessary.
+    P: TimeProvider,
+{
+    type WriteError = RateLimitError;
+
+    type WriteInput = HashMap<String, MutableBatch>;
+    type WriteOutput = Self::WriteInput;
+
+    /// Admit or reject the write according to the namespace rate limits.
+    async fn write(
+        &self,
+        namespace: &NamespaceName<'static>,
+        _namespace_id: NamespaceId,
+        batch: Self::WriteInput,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        // Try to fetch the namespace schema through the cache.
+        let limit = match self.cache.get_schema(namespace).await {
+            Ok(v) => v.write_rate_limit,
+            Err(e) => return Err(RateLimitError::NamespaceLookup(e)),
+        };
+
+        let batch_bytes = batch.values().map(|b| b.size_data()).sum::<usize>();
+
+        if limit.is_unlimited() {
+            // Drop any state from a previously configured limit.
+            self.buckets.lock().remove(namespace);
+            self.record_admission(namespace, batch_bytes);
+            return Ok(batch);
+        }
+
+        let lines = batch.values().map(|b| b.rows()).sum::<usize>();
+        let now = self.time_provider.now();
+
+        let mut buckets = self.buckets.lock();
+        let state = buckets.entry(namespace.clone()).or_default();
+
+        refill(&mut state.lines, limit.lines_per_second, now);
+        refill(&mut state.batch_bytes, limit.batch_bytes_per_second, now);
+
+        let lines_wait = state.lines.as_ref().and_then(|b| b.wait_time());
+        let batch_bytes_wait = state.batch_bytes.as_ref().and_then(|b| b.wait_time());
+
+        if lines_wait.is_some() || batch_bytes_wait.is_some() {
+            drop(buckets);
+
+            if lines_wait.is_some() {
+                self.record_rejection(namespace, "lines");
+            }
+            if batch_bytes_wait.is_some() {
+                self.record_rejection(namespace, "batch_bytes");
+            }
+
+            let retry_after = lines_wait.max(batch_bytes_wait).unwrap_or_default();
+            debug!(
+                %namespace,
+                lines,
+                batch_bytes,
+                ?retry_after,
+                "write rate limit exceeded"
+            );
+            return Err(RateLimitError::RateLimited {
+                namespace: namespace.to_string(),
+                retry_after,
+            });
+        }
+
+        if let Some(b) = state.lines.as_mut() {
+            b.consume(lines);
+        }
+        if let Some(b) = state.batch_bytes.as_mut() {
+            b.consume(batch_bytes);
+        }
+        drop(buckets);
+
+        self.record_admission(namespace, batch_bytes);
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use iox_tests::{TestCatalog, TestNamespace};
+    use iox_time::MockProvider;
+    use metric::Attributes;
+    use once_cell::sync::Lazy;
+
+    use super::*;
+    use crate::namespace_cache::{MemoryNamespaceCache, ReadThroughCache};
+
+    static NAMESPACE: Lazy<NamespaceName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    // Parse `lp` into a table-keyed MutableBatch map.
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    async fn test_setup(
+        lines_per_second: Option<i64>,
+        batch_bytes_per_second: Option<i64>,
+    ) -> (
+        Arc<TestNamespace>,
+        Arc<metric::Registry>,
+        Arc<MockProvider>,
+        RateLimiter<Arc<ReadThroughCache<Arc<MemoryNamespaceCache>>>, Arc<MockProvider>>,
+    ) {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+
+        let mut repos = catalog.catalog().repositories().await;
+        repos
+            .namespaces()
+            .update_lines_per_second_limit(&NAMESPACE, lines_per_second)
+            .await
+            .unwrap();
+        repos
+            .namespaces()
+            .update_batch_bytes_per_second_limit(&NAMESPACE, batch_bytes_per_second)
+            .await
+            .unwrap();
+
+        let cache = Arc::new(ReadThroughCache::new(
+            Arc::new(MemoryNamespaceCache::default()),
+            catalog.catalog(),
+        ));
+        let metrics = Arc::new(metric::Registry::default());
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let handler = RateLimiter::new(cache, &metrics).with_time_provider(Arc::clone(&time));
+
+        (namespace, metrics, time, handler)
+    }
+
+    fn rejections(metrics: &metric::Registry, limit: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("router_write_rate_limited")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[
+                ("namespace", "bananas"),
+                ("limit", limit),
+            ]))
+            .map(|v| v.fetch())
+            .unwrap_or_default()
+    }
+
+    fn admitted_batch_bytes(metrics: &metric::Registry) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("router_write_admitted_batch_bytes")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("namespace", "bananas")]))
+            .map(|v| v.fetch())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_unlimited() {
+        let (namespace, metrics, _time, handler) = test_setup(None, None).await;
+
+        for _ in 0..10 {
+            let writes = lp_to_writes("bananas,tag1=A val=42i 42\nbananas,tag1=B val=42i 42");
+            handler
+                .write(&NAMESPACE, namespace.namespace.id, writes, None)
+                .await
+                .expect("unlimited namespace should accept writes");
+        }
+
+        assert_eq!(rejections(&metrics, "lines"), 0);
+        assert_eq!(rejections(&metrics, "batch_bytes"), 0);
+        assert!(admitted_batch_bytes(&metrics) > 0);
+    }
+
+    #[tokio::test]
+    async fn test_lines_limit() {
+        let (namespace, metrics, time, handler) = test_setup(Some(2), None).await;
+
+        // The first write consumes the full burst capacity.
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42\nbananas,tag1=B val=42i 42");
+        handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect("write within limit should succeed");
+
+        // Which causes the next write to be rejected.
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42");
+        let err = handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect_err("write exceeding limit should fail");
+        assert_matches!(err, RateLimitError::RateLimited { ref namespace, retry_after } => {
+            assert_eq!(namespace, "bananas");
+            assert_eq!(retry_after, Duration::from_millis(500));
+        });
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(500)));
+        assert_eq!(rejections(&metrics, "lines"), 1);
+        assert_eq!(rejections(&metrics, "batch_bytes"), 0);
+
+        // Once enough time has passed, the write is admitted.
+        time.inc(Duration::from_millis(500));
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42");
+        handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect("write after refill should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_large_write_admitted_into_debt() {
+        let (namespace, _metrics, time, handler) = test_setup(Some(1), None).await;
+
+        // A single write larger than the per-second rate is admitted...
+        let writes = lp_to_writes(
+            "bananas,tag1=A val=42i 42\nbananas,tag1=B val=42i 42\nbananas,tag1=C val=42i 42",
+        );
+        handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect("write should succeed");
+
+        // ...but must be paid off before the next write is accepted.
+        time.inc(Duration::from_secs(2));
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42");
+        let err = handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect_err("write exceeding limit should fail");
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(1)));
+
+        time.inc(Duration::from_secs(1));
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42");
+        handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect("write after refill should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_batch_bytes_limit() {
+        let (namespace, metrics, _time, handler) = test_setup(Some(1_000), Some(1)).await;
+
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42");
+        let batch_bytes = writes.values().map(|b| b.size_data()).sum::<usize>() as u64;
+        handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect("write within limit should succeed");
+        assert_eq!(admitted_batch_bytes(&metrics), batch_bytes);
+
+        let writes = lp_to_writes("bananas,tag1=A val=42i 42");
+        let err = handler
+            .write(&NAMESPACE, namespace.namespace.id, writes, None)
+            .await
+            .expect_err("write exceeding limit should fail");
+        assert_matches!(err, RateLimitError::RateLimited { .. });
+        assert_eq!(rejections(&metrics, "lines"), 0);
+        assert_eq!(rejections(&metrics, "batch_bytes"), 1);
+
+        // Rejected writes are not counted as admitted.
+        assert_eq!(admitted_batch_bytes(&metrics), batch_bytes);
+    }
+
+    #[test]
+    fn test_non_positive_rate_does_not_panic() {
+        let now = Time::from_timestamp_nanos(0);
+        for rate in [0, -1] {
+            let mut bucket = TokenBucket::new(rate, now);
+            bucket.consume(10);
+            bucket.refill(rate, now + Duration::from_secs(1));
+            assert_eq!(bucket.wait_time(), None);
+        }
+    }
+}
@@ -220,7 +220,7 @@ where
             // Perform the gRPC write to an ingester.
             //
             // This call is bounded to at most RPC_TIMEOUT duration of time.
-            write_loop(&mut snap, &req).await.map_err(|e| {
+            write_loop(&mut snap, &req, &span_ctx).await.map_err(|e| {
                 // In all cases, if at least one write succeeded, then this
                 // becomes a partial write error.
                 if i > 0 {
@@ -263,7 +263,7 @@ where
 }
 
 /// Perform an RPC write with `req` against one of the upstream ingesters in
-/// `endpoints`.
+/// `endpoints`, propagating the trace context in `span_ctx`.
 ///
 /// This write attempt is bounded in time to at most [`RPC_TIMEOUT`].
 ///
@@ -272,6 +272,7 @@ where
 async fn write_loop<T>(
     endpoints: &mut UpstreamSnapshot<'_, T>,
     req: &WriteRequest,
+    span_ctx: &Option<SpanContext>,
 ) -> Result<(), RpcWriteError>
 where
     T: WriteClient,
@@ -287,7 +288,7 @@ where
             match endpoints
                 .next()
                 .ok_or(RpcWriteError::NoUpstreams)?
-                .write(req.clone())
+                .write(req.clone(), span_ctx.clone())
                 .await
             {
                 Ok(()) => return Ok(()),
@@ -451,6 +452,33 @@ mod tests {
         assert_eq!(got_tables, want_tables);
     }
 
+    /// Ensure the caller's trace context is passed to the RPC client.
+    #[tokio::test]
+    async fn test_write_propagates_span_context() {
+        let batches = lp_to_writes("bananas,tag1=A,tag2=B val=42i 1");
+        let input = Partitioned::new(PartitionKey::from("2022-01-01"), batches);
+
+        let client = Arc::new(MockWriteClient::default());
+        let handler = RpcWrite::new(
+            [(Arc::clone(&client), "mock client")],
+            None,
+            &metric::Registry::default(),
+        );
+
+        let span_ctx = SpanContext::new_with_optional_collector(None);
+        let got = handler
+            .write(
+                &NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                NAMESPACE_ID,
+                input,
+                Some(span_ctx.clone()),
+            )
+            .await;
+        assert_matches!(got, Ok(_));
+
+        assert_eq!(client.span_contexts(), [Some(span_ctx)]);
+    }
+
     /// Ensure all candidates returned by the balancer are tried, aborting after
     /// the first successful request.
     #[tokio::test]
@@ -2,6 +2,7 @@ use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 use generated_types::influxdata::iox::ingester::v1::WriteRequest;
+use trace::ctx::SpanContext;
 
 use super::{circuit_breaker::CircuitBreaker, client::WriteClient, RpcWriteError};
 
@@ -95,8 +96,12 @@ where
     T: WriteClient,
     C: CircuitBreakerState,
 {
-    async fn write(&self, op: WriteRequest) -> Result<(), RpcWriteError> {
-        let res = self.inner.write(op).await;
+    async fn write(
+        &self,
+        op: WriteRequest,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), RpcWriteError> {
+        let res = self.inner.write(op, span_ctx).await;
         self.state.observe(&res);
         res
     }
@@ -205,7 +210,7 @@ mod tests {
 
         wrapper
             .borrow()
-            .write(WriteRequest::default())
+            .write(WriteRequest::default(), None)
             .await
             .expect("wrapper should return Ok mock value");
         assert_eq!(circuit_breaker.ok_count(), 1);
@@ -213,7 +218,7 @@ mod tests {
 
         wrapper
             .borrow()
-            .write(WriteRequest::default())
+            .write(WriteRequest::default(), None)
             .await
             .expect_err("wrapper should return Err mock value");
         assert_eq!(circuit_breaker.ok_count(), 1);
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use generated_types::influxdata::iox::ingester::v1::{
     write_service_client::WriteServiceClient, WriteRequest,
 };
+use trace::ctx::SpanContext;
 
 use super::RpcWriteError;
 
@@ -11,13 +12,26 @@ use super::RpcWriteError;
 #[async_trait]
 pub(super) trait WriteClient: Send + Sync + std::fmt::Debug {
     /// Write `op` and wait for a response.
-    async fn write(&self, op: WriteRequest) -> Result<(), RpcWriteError>;
+    ///
+    /// If provided, `span_ctx` is propagated to the receiver as the parent
+    /// of any spans it emits.
+    async fn write(
+        &self,
+        op: WriteRequest,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), RpcWriteError>;
 }
 
 /// An implementation of [`WriteClient`] for the tonic gRPC client.
+///
+/// This implementation does not propagate the trace context.
 #[async_trait]
 impl WriteClient for WriteServiceClient<tonic::transport::Channel> {
-    async fn write(&self, op: WriteRequest) -> Result<(), RpcWriteError> {
+    async fn write(
+        &self,
+        op: WriteRequest,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<(), RpcWriteError> {
         WriteServiceClient::write(&mut self.clone(), op).await?;
         Ok(())
     }
@@ -31,6 +45,7 @@ pub mod mock {
 
     struct State {
         calls: Vec<WriteRequest>,
+        span_contexts: Vec<Option<SpanContext>>,
         ret: Box<dyn Iterator<Item = Result<(), RpcWriteError>> + Send + Sync>,
     }
 
@@ -53,6 +68,7 @@ pub mod mock {
             Self {
                 state: Mutex::new(State {
                     calls: Default::default(),
+                    span_contexts: Default::default(),
                     ret: Box::new(iter::repeat_with(|| Ok(()))),
                 }),
             }
@@ -65,6 +81,12 @@ pub mod mock {
             self.state.lock().calls.clone()
         }
 
+        /// Retrieve the span contexts passed alongside each request in
+        /// [`Self::calls()`].
+        pub fn span_contexts(&self) -> Vec<Option<SpanContext>> {
+            self.state.lock().span_contexts.clone()
+        }
+
         /// Read values off of the provided iterator and return them for calls
         /// to [`Self::write()`].
         #[cfg(test)]
@@ -80,9 +102,14 @@ pub mod mock {
 
     #[async_trait]
     impl WriteClient for Arc<MockWriteClient> {
-        async fn write(&self, op: WriteRequest) -> Result<(), RpcWriteError> {
+        async fn write(
+            &self,
+            op: WriteRequest,
+            span_ctx: Option<SpanContext>,
+        ) -> Result<(), RpcWriteError> {
             let mut guard = self.state.lock();
             guard.calls.push(op);
+            guard.span_contexts.push(span_ctx);
             guard.ret.next().expect("no mock response")
         }
     }
@@ -16,10 +16,12 @@ use observability_deps::tracing::*;
 use parking_lot::Mutex;
 use tokio::task::JoinHandle;
 use tonic::{
-    metadata::AsciiMetadataValue,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
     transport::{Channel, Endpoint},
-    Code,
+    Code, Request,
 };
+use trace::ctx::SpanContext;
+use trace_http::ctx::TraceHeaderFormatter;
 
 use super::{client::WriteClient, RpcWriteError};
 
@@ -52,6 +54,9 @@ pub struct LazyConnector {
     /// ingester SHOULD NOT ever generate a response larger than this.
     max_outgoing_msg_bytes: usize,
 
+    /// Formats the trace context metadata sent with each request.
+    trace_header_formatter: TraceHeaderFormatter,
+
     /// The number of request errors observed without a single success.
     consecutive_errors: Arc<AtomicUsize>,
     /// A task that periodically opens a new connection to `addr` when
@@ -60,8 +65,14 @@ pub struct LazyConnector {
 }
 
 impl LazyConnector {
-    /// Lazily connect to `addr`.
-    pub fn new(addr: Endpoint, request_timeout: Duration, max_outgoing_msg_bytes: usize) -> Self {
+    /// Lazily connect to `addr`, propagating trace context in the formats of
+    /// `trace_header_formatter`.
+    pub fn new(
+        addr: Endpoint,
+        request_timeout: Duration,
+        max_outgoing_msg_bytes: usize,
+        trace_header_formatter: TraceHeaderFormatter,
+    ) -> Self {
         let addr = addr
             .connect_timeout(CONNECT_TIMEOUT)
             .timeout(request_timeout);
@@ -72,6 +83,7 @@ impl LazyConnector {
         Self {
             addr: addr.clone(),
             max_outgoing_msg_bytes,
+            trace_header_formatter,
             connection: Arc::clone(&connection),
             connection_task: tokio::spawn(try_connect(
                 addr,
@@ -94,15 +106,24 @@ impl LazyConnector {
 
 #[async_trait]
 impl WriteClient for LazyConnector {
-    async fn write(&self, op: WriteRequest) -> Result<(), RpcWriteError> {
+    async fn write(
+        &self,
+        op: WriteRequest,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), RpcWriteError> {
         let conn = self.connection.lock().clone();
         let conn =
             conn.ok_or_else(|| RpcWriteError::UpstreamNotConnected(self.addr.uri().to_string()))?;
 
+        let mut req = Request::new(op);
+        if let Some(span_ctx) = span_ctx {
+            add_trace_metadata(&mut req, &self.trace_header_formatter, &span_ctx);
+        }
+
         match WriteServiceClient::new(conn)
             .max_encoding_message_size(self.max_outgoing_msg_bytes)
             .max_decoding_message_size(MAX_INCOMING_MSG_BYTES)
-            .write(op)
+            .write(req)
             .await
         {
             Err(e) if is_envoy_unavailable_error(&e) => {
@@ -123,6 +144,27 @@ impl WriteClient for LazyConnector {
     }
 }
 
+/// Add the metadata describing `span_ctx` to `req`, in the formats of
+/// `formatter`.
+fn add_trace_metadata<T>(
+    req: &mut Request<T>,
+    formatter: &TraceHeaderFormatter,
+    span_ctx: &SpanContext,
+) {
+    for (name, value) in formatter.headers(span_ctx) {
+        match (
+            AsciiMetadataKey::from_bytes(name.as_bytes()),
+            AsciiMetadataValue::try_from(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                req.metadata_mut().insert(name, value);
+            }
+            // Propagating the trace context is best-effort.
+            _ => warn!(name, "invalid trace context metadata"),
+        }
+    }
+}
+
 /// Returns `true` if `e` is a gRPC error with the status [`Code::Unavailable`],
 /// and a metadata entry indicating the response was generated by an envoy proxy
 /// instance.
@@ -177,3 +219,31 @@ async fn try_connect(
         tokio::time::sleep(RETRY_INTERVAL).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use trace_http::ctx::TraceContextFormat;
+
+    use super::*;
+
+    #[test]
+    fn test_add_trace_metadata() {
+        let formatter = TraceHeaderFormatter::new()
+            .with_formats([TraceContextFormat::Jaeger, TraceContextFormat::W3C]);
+        let span_ctx = SpanContext::new_with_optional_collector(None);
+
+        let mut req = Request::new(WriteRequest::default());
+        add_trace_metadata(&mut req, &formatter, &span_ctx);
+
+        let metadata = req.metadata();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(
+            metadata.get("uber-trace-id").unwrap(),
+            trace_http::ctx::format_jaeger_trace_context(&span_ctx).as_str()
+        );
+        assert_eq!(
+            metadata.get("traceparent").unwrap(),
+            trace_http::ctx::format_w3c_traceparent(&span_ctx).as_str()
+        );
+    }
+}
@@ -1,15 +1,18 @@
-use std::{ops::DerefMut, sync::Arc};
+use std::{borrow::Cow, ops::DerefMut, sync::Arc};
 
 use async_trait::async_trait;
-use data_types::{NamespaceId, NamespaceName, NamespaceSchema, TableId};
+use data_types::{
+    ColumnType, NamespaceId, NamespaceName, NamespaceSchema, TableId, TypeCoercionPolicy,
+};
 use hashbrown::HashMap;
 use iox_catalog::{
     interface::{Catalog, Error as CatalogError},
-    validate_or_insert_schema,
+    validate_or_insert_schema, TableScopedError,
 };
-use metric::U64Counter;
+use metric::{Metric, U64Counter};
 use mutable_batch::MutableBatch;
 use observability_deps::tracing::*;
+use schema::InfluxColumnType;
 use thiserror::Error;
 use trace::ctx::SpanContext;
 
@@ -60,6 +63,19 @@ pub enum SchemaError {
 /// Any successful write that adds new columns causes the new schema to be
 /// cached.
 ///
+/// # Type Coercion
+///
+/// A field whose type differs from the existing column is converted to the
+/// column type when the namespace [`TypeCoercionPolicy`] permits it, before
+/// the write is validated against the catalog. Columns that are not yet cached
+/// are converted when the catalog reports the conflict, and validation is then
+/// retried. If any value cannot be converted the write is rejected with a
+/// [`SchemaError::Conflict`] as if no policy was configured. The number of
+/// converted values is recorded per table and column.
+///
+/// Like the service limits, the policy is cached with the namespace schema and
+/// a change in the catalog only takes effect once the namespace is recached.
+///
 /// To minimise locking, this cache is designed to allow (and tolerate) spurious
 /// cache "updates" racing with each other and overwriting newer schemas with
 /// older schemas. This is acceptable due to the incremental, additive schema
@@ -69,13 +85,22 @@ pub enum SchemaError {
 /// relatively rare - it results in additional requests being made to the
 /// catalog until the cached schema converges to match the catalog schema.
 ///
+/// Tables and columns deleted via the schema gRPC API of this router are
+/// removed from its cache immediately. Other routers reload the namespace
+/// schema once the catalog change feed reports the deletion, see
+/// [`refresh_on_change`](crate::namespace_cache::refresh_on_change). Until
+/// then, usually for a moment after the delete is committed but for longer if
+/// the feed is delayed or has to reconnect, they keep accepting writes for the
+/// deleted tables and columns. Writes that
+/// reach the catalog for a deleted table or column are rejected with a
+/// [`SchemaError::Conflict`].
+///
 /// Note that the namespace-wide limit of the number of columns allowed per table
 /// is also cached, which has two implications:
 ///
 /// 1. If the namespace's column limit is updated in the catalog, the new limit
-///    will not be enforced until the whole namespace is recached, likely only
-///    on startup. In other words, updating the namespace's column limit requires
-///    both a catalog update and service restart.
+///    will not be enforced until the namespace is recached, i.e. once the
+///    catalog change feed reports the update.
 /// 2. There's a race condition that can result in a table ending up with more
 ///    columns than the namespace limit should allow. When multiple concurrent
 ///    writes come in to different service instances that each have their own
@@ -109,6 +134,7 @@ pub struct SchemaValidator<C> {
     service_limit_hit_tables: U64Counter,
     service_limit_hit_columns: U64Counter,
     schema_conflict: U64Counter,
+    coerced_values: Metric<U64Counter>,
 }
 
 impl<C> SchemaValidator<C> {
@@ -129,12 +155,106 @@ impl<C> SchemaValidator<C> {
             )
             .recorder(&[]);
 
+        let coerced_values = metrics.register_metric::<U64Counter>(
+            "schema_validation_coerced_values",
+            "number of field values converted to the type of the existing column",
+        );
+
         Self {
             catalog,
             cache: ns_cache,
             service_limit_hit_tables,
             service_limit_hit_columns,
             schema_conflict,
+            coerced_values,
+        }
+    }
+
+    /// Convert the values of `column` in `batch` to the `existing` column
+    /// type, returning false if `policy` does not permit it or any value
+    /// cannot be converted.
+    fn coerce_column(
+        &self,
+        policy: TypeCoercionPolicy,
+        table: &str,
+        batch: &mut MutableBatch,
+        column: &str,
+        existing: ColumnType,
+    ) -> bool {
+        let InfluxColumnType::Field(to) = InfluxColumnType::from(existing) else {
+            return false;
+        };
+
+        match batch.coerce_column(column, to, policy) {
+            Ok(n) => {
+                self.coerced_values
+                    .recorder([
+                        ("table", Cow::Owned(table.to_string())),
+                        ("column", Cow::Owned(column.to_string())),
+                    ])
+                    .inc(n as u64);
+                true
+            }
+            Err(e) => {
+                debug!(%table, %column, %policy, error=%e, "type coercion failed");
+                false
+            }
+        }
+    }
+
+    /// Coerce the columns in `batches` whose type differs from the column
+    /// cached in `schema`.
+    fn coerce_cached(&self, schema: &NamespaceSchema, batches: &mut HashMap<String, MutableBatch>) {
+        let policy = schema.type_coercion_policy;
+
+        for (table_name, batch) in batches.iter_mut() {
+            let table = match schema.tables.get(table_name) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let conflicts = batch
+                .columns()
+                .filter_map(|(name, col)| {
+                    let existing = table.columns.get(name)?.column_type;
+                    let new = ColumnType::from(col.influx_type());
+                    (existing != new && policy.allows(new, existing))
+                        .then(|| (name.clone(), existing))
+                })
+                .collect::<Vec<_>>();
+
+            for (column, existing) in conflicts {
+                // A failed conversion leaves the column as-is, to be rejected
+                // by the catalog validation.
+                self.coerce_column(policy, table_name, batch, &column, existing);
+            }
+        }
+    }
+
+    /// Coerce the column of a [`CatalogError::ColumnTypeMismatch`] in `e`,
+    /// returning true if validation should be retried.
+    fn coerce_conflict(
+        &self,
+        policy: TypeCoercionPolicy,
+        batches: &mut HashMap<String, MutableBatch>,
+        e: &TableScopedError,
+    ) -> bool {
+        let (name, existing, new) = match e.err() {
+            CatalogError::ColumnTypeMismatch {
+                name,
+                existing,
+                new,
+            } => (name, *existing, *new),
+            _ => return false,
+        };
+
+        if !policy.allows(new, existing) {
+            return false;
+        }
+
+        match batches.get_mut(e.table()) {
+            Some(batch) => self.coerce_column(policy, e.table(), batch, name, existing),
+            None => false,
         }
     }
 }
@@ -170,7 +290,7 @@ where
         &self,
         namespace: &NamespaceName<'static>,
         namespace_id: NamespaceId,
-        batches: Self::WriteInput,
+        mut batches: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
         // Try to fetch the namespace schema through the cache.
@@ -217,14 +337,27 @@ where
             SchemaError::ServiceLimit(Box::new(e))
         })?;
 
+        let policy = schema.type_coercion_policy;
+        if policy != TypeCoercionPolicy::Strict {
+            self.coerce_cached(&schema, &mut batches);
+        }
+
         let mut repos = self.catalog.repositories().await;
 
-        let maybe_new_schema = validate_or_insert_schema(
-            batches.iter().map(|(k, v)| (k.as_str(), v)),
-            &schema,
-            repos.deref_mut(),
-        )
-        .await
+        // Each retry converts one more column to the catalog type, so this
+        // terminates once all the conflicting columns have been coerced.
+        let maybe_new_schema = loop {
+            match validate_or_insert_schema(
+                batches.iter().map(|(k, v)| (k.as_str(), v)),
+                &schema,
+                repos.deref_mut(),
+            )
+            .await
+            {
+                Err(e) if self.coerce_conflict(policy, &mut batches, &e) => continue,
+                v => break v,
+            }
+        }
         .map_err(|e| {
             match e.err() {
                 // Schema conflicts
@@ -245,6 +378,30 @@ where
                     self.schema_conflict.inc(1);
                     SchemaError::Conflict(e)
                 }
+                // Writes to deleted tables or columns
+                CatalogError::TableSoftDeleted { .. } | CatalogError::ColumnSoftDeleted { .. } => {
+                    warn!(
+                        %namespace,
+                        %namespace_id,
+                        table_name=%e.table(),
+                        error=%e,
+                        "write to deleted table or column"
+                    );
+                    self.schema_conflict.inc(1);
+                    SchemaError::Conflict(e)
+                }
+                // Writes with tags outside of a declared primary key
+                CatalogError::NotInPrimaryKey { ref name, .. } => {
+                    warn!(
+                        %namespace,
+                        %namespace_id,
+                        table_name=%e.table(),
+                        column_name=%name,
+                        "tag not in primary key"
+                    );
+                    self.schema_conflict.inc(1);
+                    SchemaError::Conflict(e)
+                }
                 // Service limits
                 CatalogError::ColumnCreateLimitError { table_id, .. } => {
                     warn!(
@@ -852,6 +1009,184 @@ mod tests {
         assert_eq!(1, handler.schema_conflict.fetch());
     }
 
+    #[tokio::test]
+    async fn test_write_primary_key() {
+        let (catalog, namespace) = test_setup().await;
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .tables()
+            .create(
+                "bananas",
+                namespace.namespace.id,
+                &["region".to_string(), "time".to_string()],
+            )
+            .await
+            .unwrap();
+
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(catalog.catalog(), setup_test_cache(&catalog), &metrics);
+
+        // Tags of the primary key are accepted, as are writes without them
+        let writes = lp_to_writes("bananas,region=eu val=42i 123456\nbananas val=24i 123457");
+        handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+
+        // Other tags are rejected
+        let writes = lp_to_writes("bananas,region=eu,host=a val=42i 123456");
+        let err = handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(e) => {
+            assert_eq!(e.table(), "bananas");
+            assert_matches!(e.err(), CatalogError::NotInPrimaryKey { name, .. } => {
+                assert_eq!(name, "host");
+            });
+        });
+        assert_eq!(1, handler.schema_conflict.fetch());
+    }
+
+    fn coerced_values(
+        metrics: &metric::Registry,
+        table: &'static str,
+        column: &'static str,
+    ) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("schema_validation_coerced_values")
+            .expect("metric not registered")
+            .get_observer(&metric::Attributes::from(&[
+                ("table", table),
+                ("column", column),
+            ]))
+            .map(|v| v.fetch())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_write_type_coercion_widen() {
+        let (catalog, namespace) = test_setup().await;
+        namespace
+            .update_type_coercion_policy(TypeCoercionPolicy::Widen)
+            .await;
+
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(catalog.catalog(), setup_test_cache(&catalog), &metrics);
+
+        // First write sets the schema
+        let writes = lp_to_writes("bananas,tag1=A val=42.0 123456"); // val=float
+        handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+
+        // Integers are widened to the cached float column
+        let writes = lp_to_writes("bananas,tag1=A val=42i 123456\nbananas,tag1=B val=24i 123457");
+        let got = handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+        let (_name, data) = got.values().next().unwrap();
+        assert_eq!(
+            data.column("val").unwrap().influx_type(),
+            InfluxColumnType::Field(schema::InfluxFieldType::Float)
+        );
+        assert_cache(&handler, "bananas", "val", ColumnType::F64).await;
+        assert_eq!(coerced_values(&metrics, "bananas", "val"), 2);
+
+        // Non-numeric fields are never converted.
+        let writes = lp_to_writes("bananas,tag1=A val=\"str\" 123456");
+        let err = handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(_));
+        assert_eq!(1, handler.schema_conflict.fetch());
+    }
+
+    #[tokio::test]
+    async fn test_write_type_coercion_uncached_column() {
+        let (catalog, namespace) = test_setup().await;
+        namespace
+            .update_type_coercion_policy(TypeCoercionPolicy::Widen)
+            .await;
+
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(catalog.catalog(), setup_test_cache(&catalog), &metrics);
+
+        // Populate the cache of this handler before the column exists.
+        let writes = lp_to_writes("platanos val=42i 123456");
+        handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+
+        // Another router creates the column as a float.
+        let other = SchemaValidator::new(
+            catalog.catalog(),
+            setup_test_cache(&catalog),
+            &metric::Registry::default(),
+        );
+        let writes = lp_to_writes("bananas val=42.0 123456");
+        other
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+
+        // The conflict is only discovered in the catalog, and the retried
+        // write succeeds and caches the column.
+        let writes = lp_to_writes("bananas val=42i 123456");
+        handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+        assert_cache(&handler, "bananas", "val", ColumnType::F64).await;
+        assert_eq!(coerced_values(&metrics, "bananas", "val"), 1);
+        assert_eq!(0, handler.schema_conflict.fetch());
+    }
+
+    #[tokio::test]
+    async fn test_write_type_coercion_lossless_cast() {
+        let (catalog, namespace) = test_setup().await;
+        namespace
+            .update_type_coercion_policy(TypeCoercionPolicy::LosslessCast)
+            .await;
+
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(catalog.catalog(), setup_test_cache(&catalog), &metrics);
+
+        // First write sets the schema
+        let writes = lp_to_writes("bananas val=42i 123456"); // val=i64
+        handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+
+        // Whole floats are cast to the integer column
+        let writes = lp_to_writes("bananas val=24.0 123456");
+        handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect("request should succeed");
+        assert_eq!(coerced_values(&metrics, "bananas", "val"), 1);
+
+        // But values with a fractional part are rejected
+        let writes = lp_to_writes("bananas val=24.5 123456");
+        let err = handler
+            .write(&NAMESPACE, NamespaceId::new(42), writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(e) => {
+            assert_eq!(e.table(), "bananas");
+        });
+        assert_cache(&handler, "bananas", "val", ColumnType::I64).await;
+        assert_eq!(coerced_values(&metrics, "bananas", "val"), 1);
+        assert_eq!(1, handler.schema_conflict.fetch());
+    }
+
     #[tokio::test]
     async fn test_write_table_service_limit() {
         let (catalog, _namespace) = test_setup().await;
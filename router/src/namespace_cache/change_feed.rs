@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use data_types::{NamespaceId, NamespaceName};
+use iox_catalog::{
+    changes::CatalogChange,
+    interface::{get_schema_by_name, list_schemas, Catalog, SoftDeletedRows},
+};
+use observability_deps::tracing::*;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::NamespaceCache;
+
+/// Reload the [`NamespaceSchema`] of namespaces in `cache` from `catalog` as
+/// the catalog reports changes to them.
+///
+/// A change to a namespace, such as an update of its write rate limits, or to
+/// one of its tables or columns, such as their deletion, replaces the cached
+/// schema of the namespace with the current one from the catalog, so that
+/// every router picks up the change without a restart. If the change feed
+/// reports that changes were missed, or is reset, all schemas are reloaded.
+///
+/// Returns once the change feed is closed.
+///
+/// [`NamespaceSchema`]: data_types::NamespaceSchema
+pub async fn refresh_on_change<C>(
+    mut changes: broadcast::Receiver<CatalogChange>,
+    cache: C,
+    catalog: Arc<dyn Catalog>,
+) where
+    C: NamespaceCache,
+{
+    loop {
+        let res = match changes.recv().await {
+            // tables and their columns are cached as part of the namespace schema
+            Ok(
+                CatalogChange::Namespace { id }
+                | CatalogChange::Table {
+                    namespace_id: id, ..
+                },
+            ) => refresh_namespace(&cache, &*catalog, id).await,
+            Ok(CatalogChange::Partition { .. }) | Ok(CatalogChange::ParquetFile { .. }) => Ok(()),
+            Ok(CatalogChange::Reset) => {
+                warn!("catalog change feed reset, reloading all namespace schemas");
+                refresh_all(&cache, &*catalog).await
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(
+                    skipped,
+                    "router fell behind the catalog change feed, reloading all namespace schemas"
+                );
+                refresh_all(&cache, &*catalog).await
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        if let Err(e) = res {
+            warn!(error=%e, "failed to refresh cached namespace schema");
+        }
+    }
+}
+
+async fn refresh_namespace<C>(
+    cache: &C,
+    catalog: &dyn Catalog,
+    id: NamespaceId,
+) -> Result<(), iox_catalog::interface::Error>
+where
+    C: NamespaceCache,
+{
+    let mut repos = catalog.repositories().await;
+
+    let namespace = match repos
+        .namespaces()
+        .get_by_id(id, SoftDeletedRows::ExcludeDeleted)
+        .await?
+    {
+        Some(v) => v,
+        // Nothing to refresh for deleted namespaces.
+        None => return Ok(()),
+    };
+    let schema = get_schema_by_name(
+        &namespace.name,
+        repos.as_mut(),
+        SoftDeletedRows::ExcludeDeleted,
+    )
+    .await?;
+
+    match NamespaceName::try_from(namespace.name) {
+        Ok(name) => {
+            debug!(%name, namespace_id=%id, "refreshing cached namespace schema");
+            cache.put_schema(name, schema);
+        }
+        Err(e) => warn!(error=%e, namespace_id=%id, "invalid namespace name in catalog"),
+    }
+
+    Ok(())
+}
+
+async fn refresh_all<C>(
+    cache: &C,
+    catalog: &dyn Catalog,
+) -> Result<(), iox_catalog::interface::Error>
+where
+    C: NamespaceCache,
+{
+    for (namespace, schema) in list_schemas(catalog).await? {
+        match NamespaceName::try_from(namespace.name) {
+            Ok(name) => {
+                cache.put_schema(name, schema);
+            }
+            Err(e) => {
+                warn!(error=%e, namespace_id=%namespace.id, "invalid namespace name in catalog")
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use data_types::{ColumnType, WriteRateLimit};
+    use iox_tests::TestCatalog;
+
+    use super::*;
+    use crate::namespace_cache::MemoryNamespaceCache;
+
+    #[tokio::test]
+    async fn test_refresh_on_namespace_change() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention("bananas").await;
+        let name = NamespaceName::try_from("bananas").unwrap();
+
+        let cache = Arc::new(MemoryNamespaceCache::default());
+        let task = tokio::spawn(refresh_on_change(
+            catalog.catalog().subscribe_changes(),
+            Arc::clone(&cache),
+            catalog.catalog(),
+        ));
+
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_lines_per_second_limit("bananas", Some(42))
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Ok(schema) = cache.get_schema(&name).await {
+                    assert_eq!(schema.id, namespace.namespace.id);
+                    if schema.write_rate_limit
+                        == (WriteRateLimit {
+                            lines_per_second: Some(42),
+                            batch_bytes_per_second: None,
+                        })
+                    {
+                        return;
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("cached schema not refreshed");
+
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_on_column_delete() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention("bananas").await;
+        let table = namespace.create_table("platanos").await;
+        let column = table.create_column("tag", ColumnType::Tag).await;
+        let name = NamespaceName::try_from("bananas").unwrap();
+
+        let cache = Arc::new(MemoryNamespaceCache::default());
+        refresh_all(&cache, &*catalog.catalog()).await.unwrap();
+        assert!(cache.get_schema(&name).await.unwrap().tables["platanos"]
+            .columns
+            .contains_key("tag"));
+
+        let task = tokio::spawn(refresh_on_change(
+            catalog.catalog().subscribe_changes(),
+            Arc::clone(&cache),
+            catalog.catalog(),
+        ));
+
+        // A column deleted through another router (or any other catalog
+        // client) is dropped from the cached schema.
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .columns()
+            .soft_delete(column.column.id)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let schema = cache.get_schema(&name).await.unwrap();
+                if !schema.tables["platanos"].columns.contains_key("tag") {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("cached schema not refreshed");
+
+        task.abort();
+    }
+}
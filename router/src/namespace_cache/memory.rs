@@ -75,6 +75,10 @@ mod tests {
             max_columns_per_table: 50,
             max_tables: 24,
             retention_period_ns: Some(876),
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
         assert!(cache.put_schema(ns.clone(), schema1.clone()).is_none());
         assert_eq!(
@@ -90,6 +94,10 @@ mod tests {
             max_columns_per_table: 10,
             max_tables: 42,
             retention_period_ns: Some(876),
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         };
 
         assert_eq!(
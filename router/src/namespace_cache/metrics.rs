@@ -195,6 +195,7 @@ mod tests {
                     TableSchema {
                         id: TableId::new(i as _),
                         columns,
+                        primary_key: vec![],
                     },
                 )
             })
@@ -208,6 +209,10 @@ mod tests {
             max_columns_per_table: 100,
             max_tables: 42,
             retention_period_ns: None,
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         }
     }
 
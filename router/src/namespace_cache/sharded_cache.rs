@@ -76,6 +76,10 @@ mod tests {
             max_columns_per_table: 7,
             max_tables: 42,
             retention_period_ns: None,
+            write_rate_limit: Default::default(),
+            query_quota: Default::default(),
+            type_coercion_policy: Default::default(),
+            default_query_priority: Default::default(),
         }
     }
 
@@ -1,5 +1,7 @@
 //! An trait to abstract resolving a[`NamespaceName`] to [`NamespaceId`], and a
 //! collection of composable implementations.
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use data_types::{NamespaceId, NamespaceName};
 use observability_deps::tracing::*;
@@ -33,6 +35,19 @@ pub trait NamespaceResolver: std::fmt::Debug + Send + Sync {
     ) -> Result<NamespaceId, Error>;
 }
 
+#[async_trait]
+impl<T> NamespaceResolver for Arc<T>
+where
+    T: NamespaceResolver,
+{
+    async fn get_namespace_id(
+        &self,
+        namespace: &NamespaceName<'static>,
+    ) -> Result<NamespaceId, Error> {
+        (**self).get_namespace_id(namespace).await
+    }
+}
+
 /// An implementation of [`NamespaceResolver`] that resolves the [`NamespaceId`]
 /// for a given name through a [`NamespaceCache`].
 #[derive(Debug)]
@@ -102,6 +117,10 @@ mod tests {
                 max_columns_per_table: 4,
                 max_tables: 42,
                 retention_period_ns: None,
+                write_rate_limit: Default::default(),
+                query_quota: Default::default(),
+                type_coercion_policy: Default::default(),
+                default_query_priority: Default::default(),
             },
         );
 
@@ -184,6 +184,10 @@ mod tests {
                 max_columns_per_table: 4,
                 max_tables: 42,
                 retention_period_ns: None,
+                write_rate_limit: Default::default(),
+                query_quota: Default::default(),
+                type_coercion_policy: Default::default(),
+                default_query_priority: Default::default(),
             },
         );
 
@@ -264,6 +268,12 @@ mod tests {
                 max_tables: iox_catalog::DEFAULT_MAX_TABLES,
                 max_columns_per_table: iox_catalog::DEFAULT_MAX_COLUMNS_PER_TABLE,
                 retention_period_ns: TEST_RETENTION_PERIOD_NS,
+                max_lines_per_second: None,
+                max_batch_bytes_per_second: None,
+                max_concurrent_queries: None,
+                max_query_memory_bytes: None,
+                type_coercion_policy: Default::default(),
+                default_query_priority: Default::default(),
                 deleted_at: None,
             }
         );
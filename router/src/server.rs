@@ -1,6 +1,9 @@
 //! Router server entrypoint.
 
-use self::{grpc::RpcWriteGrpcDelegate, http::HttpDelegate};
+use self::{
+    grpc::{otlp::OtlpMetricsService, RpcWriteGrpcDelegate},
+    http::HttpDelegate,
+};
 use std::sync::Arc;
 use trace::TraceCollector;
 
@@ -16,6 +19,7 @@ pub struct RpcWriteRouterServer<D, N> {
 
     http: HttpDelegate<D, N>,
     grpc: RpcWriteGrpcDelegate,
+    otlp: Arc<OtlpMetricsService<D, N>>,
 }
 
 impl<D, N> RpcWriteRouterServer<D, N> {
@@ -24,6 +28,7 @@ impl<D, N> RpcWriteRouterServer<D, N> {
     pub fn new(
         http: HttpDelegate<D, N>,
         grpc: RpcWriteGrpcDelegate,
+        otlp: OtlpMetricsService<D, N>,
         metrics: Arc<metric::Registry>,
         trace_collector: Option<Arc<dyn TraceCollector>>,
     ) -> Self {
@@ -32,6 +37,7 @@ impl<D, N> RpcWriteRouterServer<D, N> {
             trace_collector,
             http,
             grpc,
+            otlp: Arc::new(otlp),
         }
     }
 
@@ -54,4 +60,9 @@ impl<D, N> RpcWriteRouterServer<D, N> {
     pub fn grpc(&self) -> &RpcWriteGrpcDelegate {
         &self.grpc
     }
+
+    /// Get the OTLP/gRPC metrics service.
+    pub fn otlp(&self) -> Arc<OtlpMetricsService<D, N>> {
+        Arc::clone(&self.otlp)
+    }
 }
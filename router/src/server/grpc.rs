@@ -1,20 +1,60 @@
 //! gRPC service implementations for `router`.
 
-use data_types::{QueryPoolId, TopicId};
+use data_types::{NamespaceName, NamespaceSchema, QueryPoolId, TopicId};
 use generated_types::influxdata::iox::{catalog::v1::*, namespace::v1::*, object_store::v1::*};
 use iox_catalog::interface::Catalog;
 use object_store::DynObjectStore;
+use observability_deps::tracing::warn;
 use service_grpc_catalog::CatalogService;
-use service_grpc_namespace::NamespaceService;
+use service_grpc_namespace::{NamespaceService, DEFAULT_DELETE_GRACE_PERIOD};
 use service_grpc_object_store::ObjectStoreService;
-use service_grpc_schema::SchemaService;
-use std::sync::Arc;
+use service_grpc_schema::{SchemaChangeObserver, SchemaService};
+use std::{sync::Arc, time::Duration};
+
+use crate::namespace_cache::NamespaceCache;
+
+pub mod otlp;
+
+/// A [`SchemaChangeObserver`] that places the new schema of a namespace in a
+/// [`NamespaceCache`] after a table or column was deleted via the schema gRPC
+/// service.
+///
+/// This refreshes the cache of the router serving the request immediately;
+/// other routers pick up the deletion from the catalog change feed, see
+/// [`refresh_on_change`](crate::namespace_cache::refresh_on_change).
+#[derive(Debug)]
+pub struct NamespaceCacheRefresher<C> {
+    cache: C,
+}
+
+impl<C> NamespaceCacheRefresher<C> {
+    /// Refresh `cache` on schema changes.
+    pub fn new(cache: C) -> Self {
+        Self { cache }
+    }
+}
+
+impl<C> SchemaChangeObserver for NamespaceCacheRefresher<C>
+where
+    C: NamespaceCache,
+{
+    fn schema_changed(&self, namespace: &str, schema: NamespaceSchema) {
+        match NamespaceName::try_from(namespace.to_string()) {
+            Ok(name) => {
+                self.cache.put_schema(name, schema);
+            }
+            Err(e) => warn!(error=%e, %namespace, "invalid namespace name in schema change"),
+        }
+    }
+}
 
 /// This type manages all gRPC services exposed by a `router` using the RPC write path.
 #[derive(Debug)]
 pub struct RpcWriteGrpcDelegate {
     catalog: Arc<dyn Catalog>,
     object_store: Arc<DynObjectStore>,
+    schema_observer: Arc<dyn SchemaChangeObserver>,
+    namespace_delete_grace_period: Duration,
 
     // Temporary values during kafka -> kafkaless transition.
     topic_id: TopicId,
@@ -26,22 +66,32 @@ impl RpcWriteGrpcDelegate {
     pub fn new(
         catalog: Arc<dyn Catalog>,
         object_store: Arc<DynObjectStore>,
+        schema_observer: Arc<dyn SchemaChangeObserver>,
         topic_id: TopicId,
         query_id: QueryPoolId,
     ) -> Self {
         Self {
             catalog,
             object_store,
+            schema_observer,
+            namespace_delete_grace_period: DEFAULT_DELETE_GRACE_PERIOD,
             topic_id,
             query_id,
         }
     }
 
+    /// Allow undeleting namespaces for `grace_period` after their deletion.
+    pub fn with_namespace_delete_grace_period(mut self, grace_period: Duration) -> Self {
+        self.namespace_delete_grace_period = grace_period;
+        self
+    }
+
     /// Acquire a [`SchemaService`] gRPC service implementation.
     ///
     /// [`SchemaService`]: generated_types::influxdata::iox::schema::v1::schema_service_server::SchemaService.
     pub fn schema_service(&self) -> SchemaService {
         SchemaService::new(Arc::clone(&self.catalog))
+            .with_observer(Arc::clone(&self.schema_observer))
     }
 
     /// Acquire a [`CatalogService`] gRPC service implementation.
@@ -67,5 +117,6 @@ impl RpcWriteGrpcDelegate {
             Some(self.topic_id),
             Some(self.query_id),
         )
+        .with_delete_grace_period(self.namespace_delete_grace_period)
     }
 }
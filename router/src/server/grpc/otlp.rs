@@ -0,0 +1,356 @@
+//! An OTLP/gRPC `MetricsService` implementation for the router.
+
+use std::time::Instant;
+
+use generated_types::opentelemetry::proto::collector::metrics::v1::{
+    metrics_service_server::MetricsService, ExportMetricsServiceRequest,
+    ExportMetricsServiceResponse,
+};
+use hashbrown::HashMap;
+use hyper::{header::AUTHORIZATION, Body, StatusCode};
+use metric::{DurationHistogram, U64Counter};
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::*;
+use tonic::{metadata::MetadataMap, Code, Request, Response, Status};
+use trace::ctx::SpanContext;
+
+use crate::{
+    dml_handlers::DmlHandler,
+    namespace_resolver::NamespaceResolver,
+    server::http::{write::WriteRequestUnifier, Error},
+};
+
+/// The gRPC metadata key carrying the org name of the write.
+const ORG_METADATA_KEY: &str = "org";
+
+/// The gRPC metadata key carrying the bucket name of the write.
+const BUCKET_METADATA_KEY: &str = "bucket";
+
+/// An OTLP/gRPC [`MetricsService`] that converts exported metrics into
+/// [`MutableBatch`] (as described in [`mutable_batch_otlp`]) and routes them
+/// through the [`DmlHandler`] like any other write.
+///
+/// The destination namespace is specified by the `org` and `bucket` gRPC
+/// metadata values, and is resolved (and authorised, in single tenant mode)
+/// exactly as the `org` and `bucket` parameters of a V2 HTTP write.
+#[derive(Debug)]
+pub struct OtlpMetricsService<D, N> {
+    namespace_resolver: N,
+    dml_handler: D,
+    write_request_unifier: Box<dyn WriteRequestUnifier>,
+
+    metric_metrics: U64Counter,
+    metric_data_points: U64Counter,
+    metric_tables: U64Counter,
+    decode_duration: DurationHistogram,
+}
+
+impl<D, N> OtlpMetricsService<D, N> {
+    /// Initialise a new [`OtlpMetricsService`] passing valid requests to the
+    /// specified `dml_handler`.
+    pub fn new(
+        namespace_resolver: N,
+        dml_handler: D,
+        write_request_unifier: Box<dyn WriteRequestUnifier>,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let metric_metrics = metrics
+            .register_metric::<U64Counter>(
+                "grpc_otlp_metrics_metrics",
+                "cumulative number of otlp metrics successfully routed",
+            )
+            .recorder(&[]);
+        let metric_data_points = metrics
+            .register_metric::<U64Counter>(
+                "grpc_otlp_metrics_data_points",
+                "cumulative number of otlp metric data points successfully routed",
+            )
+            .recorder(&[]);
+        let metric_tables = metrics
+            .register_metric::<U64Counter>(
+                "grpc_otlp_metrics_tables",
+                "cumulative number of tables in each otlp metrics request",
+            )
+            .recorder(&[]);
+        let decode_duration = metrics
+            .register_metric::<DurationHistogram>(
+                "grpc_otlp_metrics_decode_duration",
+                "write latency of otlp metrics conversion",
+            )
+            .recorder(&[]);
+
+        Self {
+            namespace_resolver,
+            dml_handler,
+            write_request_unifier,
+            metric_metrics,
+            metric_data_points,
+            metric_tables,
+            decode_duration,
+        }
+    }
+}
+
+impl<D, N> OtlpMetricsService<D, N>
+where
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()>,
+    N: NamespaceResolver,
+{
+    async fn write(
+        &self,
+        metadata: &MetadataMap,
+        req: &ExportMetricsServiceRequest,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), Error> {
+        let write_info = self
+            .write_request_unifier
+            .parse_v2(&v2_write_request(metadata))
+            .await?;
+
+        trace!(
+            namespace=%write_info.namespace,
+            "processing otlp/grpc metrics request"
+        );
+
+        let start_instant = Instant::now();
+        let (batches, stats) = match mutable_batch_otlp::export_request_to_batches(req) {
+            Ok(v) => v,
+            Err(mutable_batch_otlp::Error::EmptyPayload) => {
+                debug!("nothing to write");
+                return Ok(());
+            }
+            Err(e) => return Err(Error::ConvertOtlpMetrics(e)),
+        };
+
+        let num_tables = batches.len();
+        let duration = start_instant.elapsed();
+        self.decode_duration.record(duration);
+        debug!(
+            num_metrics=stats.num_metrics,
+            num_data_points=stats.num_data_points,
+            num_skipped_metrics=stats.num_skipped_metrics,
+            num_tables,
+            namespace=%write_info.namespace,
+            duration=?duration,
+            "routing otlp/grpc metrics",
+        );
+
+        let namespace_id = self
+            .namespace_resolver
+            .get_namespace_id(&write_info.namespace)
+            .await?;
+
+        self.dml_handler
+            .write(&write_info.namespace, namespace_id, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        self.metric_metrics.inc(stats.num_metrics as _);
+        self.metric_data_points.inc(stats.num_data_points as _);
+        self.metric_tables.inc(num_tables as _);
+
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl<D, N> MetricsService for OtlpMetricsService<D, N>
+where
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()> + 'static,
+    N: NamespaceResolver + 'static,
+{
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+
+        self.write(request.metadata(), request.get_ref(), span_ctx)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, "otlp/grpc metrics export failed");
+                Status::new(status_code(&e), e.to_string())
+            })?;
+
+        Ok(Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+/// Build a V2 HTTP write request carrying the `org`, `bucket` and
+/// authorization values of the gRPC `metadata`, so that the namespace is
+/// resolved by the same [`WriteRequestUnifier`] as the HTTP write endpoints.
+fn v2_write_request(metadata: &MetadataMap) -> hyper::Request<Body> {
+    let get = |key: &str| {
+        metadata
+            .get(key)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+    };
+
+    let query = serde_urlencoded::to_string([
+        (ORG_METADATA_KEY, get(ORG_METADATA_KEY)),
+        (BUCKET_METADATA_KEY, get(BUCKET_METADATA_KEY)),
+    ])
+    .expect("failed to encode org and bucket");
+
+    let authorization = metadata
+        .get(AUTHORIZATION.as_str())
+        .and_then(|v| hyper::header::HeaderValue::from_bytes(v.as_bytes()).ok());
+
+    hyper::Request::builder()
+        .uri(format!("/api/v2/write?{query}"))
+        .extension(authz::http::AuthorizationHeaderExtension::new(
+            authorization,
+        ))
+        .body(Body::empty())
+        .expect("failed to build write request")
+}
+
+/// Map the HTTP status code of `e` to the equivalent gRPC [`Code`].
+fn status_code(e: &Error) -> Code {
+    match e.as_status_code() {
+        StatusCode::BAD_REQUEST | StatusCode::UNSUPPORTED_MEDIA_TYPE => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::PAYLOAD_TOO_LARGE | StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+        StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+        StatusCode::GATEWAY_TIMEOUT => Code::DeadlineExceeded,
+        StatusCode::NOT_IMPLEMENTED => Code::Unimplemented,
+        _ => Code::Internal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use data_types::NamespaceId;
+    use generated_types::opentelemetry::proto::metrics::v1::{
+        metric, number_data_point, Gauge, Metric, NumberDataPoint, ResourceMetrics, ScopeMetrics,
+    };
+
+    use super::*;
+    use crate::{
+        dml_handlers::{
+            mock::{MockDmlHandler, MockDmlHandlerCall},
+            DmlError,
+        },
+        namespace_resolver::mock::MockNamespaceResolver,
+        server::http::write::multi_tenant::MultiTenantRequestUnifier,
+    };
+
+    const NAMESPACE_NAME: &str = "bananas_test";
+    const NAMESPACE_ID: NamespaceId = NamespaceId::new(42);
+
+    fn export_request() -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: None,
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics: vec![Metric {
+                        name: "platanos".to_string(),
+                        description: String::new(),
+                        unit: String::new(),
+                        data: Some(metric::Data::Gauge(Gauge {
+                            data_points: vec![NumberDataPoint {
+                                time_unix_nano: 1_000_000_000,
+                                value: Some(number_data_point::Value::AsInt(42)),
+                                ..Default::default()
+                            }],
+                        })),
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    fn request(
+        org: Option<&'static str>,
+        bucket: Option<&'static str>,
+    ) -> Request<ExportMetricsServiceRequest> {
+        let mut req = Request::new(export_request());
+        if let Some(org) = org {
+            req.metadata_mut()
+                .insert(ORG_METADATA_KEY, org.parse().unwrap());
+        }
+        if let Some(bucket) = bucket {
+            req.metadata_mut()
+                .insert(BUCKET_METADATA_KEY, bucket.parse().unwrap());
+        }
+        req
+    }
+
+    fn service(
+        dml_handler: Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+    ) -> (
+        OtlpMetricsService<
+            Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+            MockNamespaceResolver,
+        >,
+        Arc<metric::Registry>,
+    ) {
+        let metrics = Arc::new(metric::Registry::default());
+        let service = OtlpMetricsService::new(
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID),
+            dml_handler,
+            Box::<MultiTenantRequestUnifier>::default(),
+            &metrics,
+        );
+        (service, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_export_ok() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let (service, _metrics) = service(Arc::clone(&dml_handler));
+
+        let resp = service
+            .export(request(Some("bananas"), Some("test")))
+            .await
+            .expect("export should succeed");
+        assert_eq!(resp.into_inner(), ExportMetricsServiceResponse::default());
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, namespace_id, write_input }] => {
+                assert_eq!(namespace, NAMESPACE_NAME);
+                assert_eq!(*namespace_id, NAMESPACE_ID);
+                let table = write_input.get("platanos").expect("table not found");
+                assert_eq!(table.rows(), 1);
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_no_bucket() {
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let (service, _metrics) = service(Arc::clone(&dml_handler));
+
+        let err = service
+            .export(request(Some("bananas"), None))
+            .await
+            .expect_err("export should fail");
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_dml_error() {
+        let dml_handler = Arc::new(
+            MockDmlHandler::default()
+                .with_write_return([Err(DmlError::NamespaceNotFound(NAMESPACE_NAME.to_string()))]),
+        );
+        let (service, _metrics) = service(Arc::clone(&dml_handler));
+
+        let err = service
+            .export(request(Some("bananas"), Some("test")))
+            .await
+            .expect_err("export should fail");
+        assert_eq!(err.code(), Code::NotFound);
+    }
+}
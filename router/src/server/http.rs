@@ -1,33 +1,68 @@
 //! HTTP service implementations for `router`.
 
+mod encoding;
+mod partial;
 pub mod write;
 
-use std::{str::Utf8Error, time::Instant};
+use std::{
+    str::Utf8Error,
+    time::{Duration, Instant},
+};
 
 use bytes::{Bytes, BytesMut};
+use data_types::NamespaceId;
 use futures::StreamExt;
+use generated_types::opentelemetry::proto::collector::metrics::v1::{
+    ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
+use influxdb_line_protocol::split_lines;
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, U64Counter};
 use mutable_batch::MutableBatch;
-use mutable_batch_lp::LinesConverter;
+use mutable_batch_lp::{LinesConverter, RejectedLine};
 use observability_deps::tracing::*;
+use prost::Message;
 use thiserror::Error;
 use tokio::sync::{Semaphore, TryAcquireError};
 use trace::ctx::SpanContext;
 
+use self::encoding::ContentEncoding;
+use self::partial::SchemaConflicts;
 use self::write::{
     multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError, WriteParams,
     WriteRequestUnifier,
 };
 use crate::{
     dml_handlers::{
-        DmlError, DmlHandler, PartitionError, RetentionError, RpcWriteError, SchemaError,
+        DmlError, DmlHandler, PartitionError, RateLimitError, RetentionError, RpcWriteError,
+        SchemaError,
     },
     namespace_resolver::NamespaceResolver,
 };
 
+/// The `Content-Type` of a protobuf encoded OTLP/HTTP request and response.
+const OTLP_PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// The query parameter that enables partial line protocol writes when set to
+/// `true`.
+const PARTIAL_WRITES_PARAM: &str = "partial_writes";
+
+/// The header that enables partial line protocol writes when set to `true`.
+const PARTIAL_WRITES_HEADER: &str = "x-iox-partial-writes";
+
+/// The maximum number of rejected lines described in the message of an
+/// [`Error::PartialWrite`].
+const MAX_REPORTED_LINES: usize = 100;
+
+/// The size of the slices of an encoded request body that are decoded at a
+/// time, bounding the decoded data buffered before its size is checked.
+const DECODE_SLICE_BYTES: usize = 1024;
+
 /// Errors returned by the `router` HTTP request handler.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -71,10 +106,51 @@ pub enum Error {
     #[error("error decoding gzip stream: {0}")]
     InvalidGzip(std::io::Error),
 
+    /// Decoding a deflate, zstd or snappy encoded stream of data failed.
+    #[error("error decoding {0} stream: {1}")]
+    InvalidEncodedBody(&'static str, std::io::Error),
+
     /// Failure to decode the provided line protocol.
     #[error("failed to parse line protocol: {0}")]
     ParseLineProtocol(mutable_batch_lp::Error),
 
+    /// Some lines of a write with partial writes enabled were rejected, the
+    /// remaining lines were written.
+    #[error(
+        "partial write has occurred, {written} line(s) written, errors encountered on line(s):{}",
+        describe_rejected_lines(.rejected)
+    )]
+    PartialWrite {
+        /// The number of lines written.
+        written: usize,
+        /// The rejected lines, in payload order.
+        rejected: Vec<RejectedLine>,
+    },
+
+    /// Decoding a snappy-compressed Prometheus remote write payload failed.
+    #[error("error decoding snappy payload: {0}")]
+    InvalidSnappy(snap::Error),
+
+    /// Failure to decode the Prometheus remote write protobuf payload.
+    #[error("failed to decode prometheus remote write request: {0}")]
+    DecodePromWrite(prost::DecodeError),
+
+    /// Failure to convert the Prometheus remote write request into a write.
+    #[error("failed to convert prometheus remote write request: {0}")]
+    ConvertPromWrite(mutable_batch_prom::Error),
+
+    /// The specified `Content-Type` is not supported for OTLP metrics.
+    #[error("unsupported content-type for otlp metrics: {0}")]
+    InvalidOtlpContentType(String),
+
+    /// Failure to decode the OTLP metrics protobuf payload.
+    #[error("failed to decode otlp metrics request: {0}")]
+    DecodeOtlpMetrics(prost::DecodeError),
+
+    /// Failure to convert the OTLP metrics request into a write.
+    #[error("failed to convert otlp metrics request: {0}")]
+    ConvertOtlpMetrics(mutable_batch_otlp::Error),
+
     /// An error returned from the [`DmlHandler`].
     #[error("dml handler error: {0}")]
     DmlHandler(#[from] DmlError),
@@ -109,9 +185,17 @@ impl Error {
             Error::DeletesUnsupported => StatusCode::NOT_IMPLEMENTED,
             Error::ClientHangup(_) => StatusCode::BAD_REQUEST,
             Error::InvalidGzip(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidEncodedBody(..) => StatusCode::BAD_REQUEST,
             Error::NonUtf8ContentHeader(_) => StatusCode::BAD_REQUEST,
             Error::NonUtf8Body(_) => StatusCode::BAD_REQUEST,
             Error::ParseLineProtocol(_) => StatusCode::BAD_REQUEST,
+            Error::PartialWrite { .. } => StatusCode::BAD_REQUEST,
+            Error::InvalidSnappy(_) => StatusCode::BAD_REQUEST,
+            Error::DecodePromWrite(_) => StatusCode::BAD_REQUEST,
+            Error::ConvertPromWrite(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidOtlpContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Error::DecodeOtlpMetrics(_) => StatusCode::BAD_REQUEST,
+            Error::ConvertOtlpMetrics(_) => StatusCode::BAD_REQUEST,
             Error::RequestSizeExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
             Error::InvalidContentEncoding(_) => {
                 // https://www.rfc-editor.org/rfc/rfc7231#section-6.5.13
@@ -130,6 +214,46 @@ impl Error {
             Error::MultiTenantError(e) => StatusCode::from(e),
         }
     }
+
+    /// Return the minimum duration the client should wait before retrying the
+    /// request, if the error is the result of a rate limit.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::DmlHandler(DmlError::RateLimit(e)) => e.retry_after(),
+            _ => None,
+        }
+    }
+
+    /// Construct the error for a failure to decode a body with `encoding`.
+    fn decode(encoding: ContentEncoding, e: std::io::Error) -> Self {
+        match encoding {
+            ContentEncoding::Gzip => Self::InvalidGzip(e),
+            _ => Self::InvalidEncodedBody(encoding.as_str(), e),
+        }
+    }
+
+    /// Return the first rejected line of a partial write, reported in the
+    /// `line` field of the error response as InfluxDB does.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::PartialWrite { rejected, .. } => rejected.first().map(|r| r.line),
+            _ => None,
+        }
+    }
+}
+
+fn describe_rejected_lines(rejected: &[RejectedLine]) -> String {
+    let mut out = String::new();
+    for r in rejected.iter().take(MAX_REPORTED_LINES) {
+        out.push_str(&format!("\nline {}: {}", r.line, r.reason));
+    }
+    if rejected.len() > MAX_REPORTED_LINES {
+        out.push_str(&format!(
+            "\n... and {} more",
+            rejected.len() - MAX_REPORTED_LINES
+        ));
+    }
+    out
 }
 
 impl From<&DmlError> for StatusCode {
@@ -158,6 +282,12 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
             DmlError::Retention(RetentionError::OutsideRetention(_)) => StatusCode::FORBIDDEN,
+            DmlError::RateLimit(RateLimitError::NamespaceLookup(_)) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            DmlError::RateLimit(RateLimitError::RateLimited { .. }) => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
             DmlError::RpcWrite(RpcWriteError::Upstream(_)) => StatusCode::INTERNAL_SERVER_ERROR,
             DmlError::RpcWrite(RpcWriteError::Timeout(_)) => StatusCode::GATEWAY_TIMEOUT,
             DmlError::RpcWrite(
@@ -179,6 +309,7 @@ impl From<&DmlError> for StatusCode {
 #[derive(Debug)]
 pub struct HttpDelegate<D, N, T = SystemProvider> {
     max_request_bytes: usize,
+    write_stream_chunk_bytes: Option<usize>,
     time_provider: T,
     namespace_resolver: N,
     dml_handler: D,
@@ -198,7 +329,22 @@ pub struct HttpDelegate<D, N, T = SystemProvider> {
     write_metric_fields: U64Counter,
     write_metric_tables: U64Counter,
     write_metric_body_size: U64Counter,
+    write_metric_rejected_lines: U64Counter,
     request_limit_rejected: U64Counter,
+    body_encoding_requests: HashMap<ContentEncoding, U64Counter>,
+    body_encoding_bytes: HashMap<ContentEncoding, U64Counter>,
+
+    prom_write_metric_series: U64Counter,
+    prom_write_metric_samples: U64Counter,
+    prom_write_metric_tables: U64Counter,
+    prom_write_metric_body_size: U64Counter,
+    http_prom_write_decode_duration: DurationHistogram,
+
+    otlp_metric_metrics: U64Counter,
+    otlp_metric_data_points: U64Counter,
+    otlp_metric_tables: U64Counter,
+    otlp_metric_body_size: U64Counter,
+    http_otlp_decode_duration: DurationHistogram,
 }
 
 impl<D, N> HttpDelegate<D, N, SystemProvider> {
@@ -239,21 +385,109 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
                 "cumulative byte size of successfully routed (decompressed) line protocol write requests",
             )
             .recorder(&[]);
+        let write_metric_rejected_lines = metrics
+            .register_metric::<U64Counter>(
+                "http_write_rejected_lines",
+                "cumulative number of line protocol lines rejected by partial writes",
+            )
+            .recorder(&[]);
         let request_limit_rejected = metrics
             .register_metric::<U64Counter>(
                 "http_request_limit_rejected",
                 "number of HTTP requests rejected due to exceeding parallel request limit",
             )
             .recorder(&[]);
+        let body_encoding_requests = metrics.register_metric::<U64Counter>(
+            "http_request_body_encoding",
+            "cumulative number of HTTP requests with a line protocol or otlp metrics body, by content encoding",
+        );
+        let body_encoding_requests = ContentEncoding::ALL
+            .into_iter()
+            .map(|e| {
+                (
+                    e,
+                    body_encoding_requests.recorder(&[("encoding", e.as_str())]),
+                )
+            })
+            .collect();
+        let body_encoding_bytes = metrics.register_metric::<U64Counter>(
+            "http_request_body_encoded_bytes",
+            "cumulative byte size of line protocol and otlp metrics request bodies before decoding, by content encoding",
+        );
+        let body_encoding_bytes = ContentEncoding::ALL
+            .into_iter()
+            .map(|e| (e, body_encoding_bytes.recorder(&[("encoding", e.as_str())])))
+            .collect();
         let http_line_protocol_parse_duration = metrics
             .register_metric::<DurationHistogram>(
                 "http_line_protocol_parse_duration",
                 "write latency of line protocol parsing",
             )
             .recorder(&[]);
+        let prom_write_metric_series = metrics
+            .register_metric::<U64Counter>(
+                "http_prom_write_series",
+                "cumulative number of prometheus remote write time series successfully routed",
+            )
+            .recorder(&[]);
+        let prom_write_metric_samples = metrics
+            .register_metric::<U64Counter>(
+                "http_prom_write_samples",
+                "cumulative number of prometheus remote write samples successfully routed",
+            )
+            .recorder(&[]);
+        let prom_write_metric_tables = metrics
+            .register_metric::<U64Counter>(
+                "http_prom_write_tables",
+                "cumulative number of tables in each prometheus remote write request",
+            )
+            .recorder(&[]);
+        let prom_write_metric_body_size = metrics
+            .register_metric::<U64Counter>(
+                "http_prom_write_body_bytes",
+                "cumulative byte size of successfully routed (decompressed) prometheus remote write requests",
+            )
+            .recorder(&[]);
+        let http_prom_write_decode_duration = metrics
+            .register_metric::<DurationHistogram>(
+                "http_prom_write_decode_duration",
+                "write latency of prometheus remote write decoding",
+            )
+            .recorder(&[]);
+        let otlp_metric_metrics = metrics
+            .register_metric::<U64Counter>(
+                "http_otlp_metrics_metrics",
+                "cumulative number of otlp metrics successfully routed",
+            )
+            .recorder(&[]);
+        let otlp_metric_data_points = metrics
+            .register_metric::<U64Counter>(
+                "http_otlp_metrics_data_points",
+                "cumulative number of otlp metric data points successfully routed",
+            )
+            .recorder(&[]);
+        let otlp_metric_tables = metrics
+            .register_metric::<U64Counter>(
+                "http_otlp_metrics_tables",
+                "cumulative number of tables in each otlp metrics request",
+            )
+            .recorder(&[]);
+        let otlp_metric_body_size = metrics
+            .register_metric::<U64Counter>(
+                "http_otlp_metrics_body_bytes",
+                "cumulative byte size of successfully routed (decompressed) otlp metrics requests",
+            )
+            .recorder(&[]);
+        let http_otlp_decode_duration = metrics
+            .register_metric::<DurationHistogram>(
+                "http_otlp_metrics_decode_duration",
+                "write latency of otlp metrics decoding",
+            )
+            .recorder(&[]);
 
         Self {
             max_request_bytes,
+            write_stream_chunk_bytes: None,
             time_provider: SystemProvider::default(),
             namespace_resolver,
             write_request_mode_handler,
@@ -264,9 +498,35 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
             write_metric_fields,
             write_metric_tables,
             write_metric_body_size,
+            write_metric_rejected_lines,
             request_limit_rejected,
+            body_encoding_requests,
+            body_encoding_bytes,
+            prom_write_metric_series,
+            prom_write_metric_samples,
+            prom_write_metric_tables,
+            prom_write_metric_body_size,
+            http_prom_write_decode_duration,
+            otlp_metric_metrics,
+            otlp_metric_data_points,
+            otlp_metric_tables,
+            otlp_metric_body_size,
+            http_otlp_decode_duration,
         }
     }
+
+    /// Parse the body of line protocol write requests as it is read, writing
+    /// it through the DML handler in chunks of roughly `chunk_bytes` of
+    /// decoded line protocol.
+    ///
+    /// This bounds the memory used by a write request independently of its
+    /// size, so the maximum request size only limits the length of a single
+    /// line. Write requests are no longer atomic: if writing a chunk fails, the
+    /// chunks before it have been written.
+    pub fn with_write_stream_chunk_bytes(mut self, chunk_bytes: usize) -> Self {
+        self.write_stream_chunk_bytes = Some(chunk_bytes);
+        self
+    }
 }
 
 impl<D, N, T> HttpDelegate<D, N, T>
@@ -305,6 +565,24 @@ where
                 let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
                 self.write_handler(req, dml_info).await
             }
+            (&Method::POST, "/api/v1/prom/write") => {
+                let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                self.prom_write_handler(req, dml_info).await
+            }
+            (&Method::POST, "/api/v1/otlp/metrics") => {
+                let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                self.otlp_metrics_handler(req, dml_info).await?;
+
+                // OTLP/HTTP clients expect a 200 response carrying an encoded
+                // (and here, empty) ExportMetricsServiceResponse.
+                return Ok(Response::builder()
+                    .status(StatusCode::OK)
+                    .header(CONTENT_TYPE, OTLP_PROTOBUF_CONTENT_TYPE)
+                    .body(Body::from(
+                        ExportMetricsServiceResponse::default().encode_to_vec(),
+                    ))
+                    .unwrap());
+            }
             (&Method::POST, "/api/v2/delete") => return Err(Error::DeletesUnsupported),
             _ => return Err(Error::NoHandler),
         }
@@ -322,30 +600,125 @@ where
         write_info: WriteParams,
     ) -> Result<(), Error> {
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let partial_writes = partial_writes_enabled(&req);
 
         trace!(
             namespace=%write_info.namespace,
+            partial_writes,
+            streaming=self.write_stream_chunk_bytes.is_some(),
             "processing write request"
         );
 
-        // Read the HTTP body and convert it to a str.
-        let body = self.read_body(req).await?;
-        let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
-
         // The time, in nanoseconds since the epoch, to assign to any points that don't
         // contain a timestamp
         let default_time = self.time_provider.now().timestamp_nanos();
+
+        let mut write = LineProtocolWrite {
+            write_info,
+            span_ctx,
+            default_time,
+            partial_writes,
+            namespace_id: None,
+            lines_seen: 0,
+            written: 0,
+            rejected: vec![],
+        };
+
+        match self.write_stream_chunk_bytes {
+            None => {
+                // Read the HTTP body and convert it to a str.
+                let body = self.read_body(req).await?;
+                let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
+                self.write_lines(&mut write, body).await?;
+            }
+            Some(chunk_bytes) => self.stream_write(req, &mut write, chunk_bytes).await?,
+        }
+
+        match write.partial_writes {
+            true => partial_write_result(write.written, write.rejected),
+            false => Ok(()),
+        }
+    }
+
+    /// Parse and write the line protocol body of `req` as it is read, instead
+    /// of buffering the whole body.
+    ///
+    /// Complete lines are written through the [`DmlHandler`] whenever at least
+    /// `chunk_bytes` of decoded line protocol are buffered, bounding the memory
+    /// used by a request regardless of its size. Only a single line larger
+    /// than the maximum request size is rejected.
+    ///
+    /// Chunks are written independently: if a chunk fails, the chunks before it
+    /// have been written.
+    async fn stream_write(
+        &self,
+        req: Request<Body>,
+        write: &mut LineProtocolWrite,
+        chunk_bytes: usize,
+    ) -> Result<(), Error> {
+        let encoding = self.body_encoding(&req)?;
+        let mut decoder = encoding.decoder().map_err(|e| Error::decode(encoding, e))?;
+
+        let mut payload = req.into_body();
+        let mut pending = Vec::new();
+        // Rescan the pending bytes for complete lines only once another chunk
+        // worth of data has been decoded, to keep long lines linear to scan.
+        let mut flush_at = chunk_bytes;
+
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk.map_err(Error::ClientHangup)?;
+            self.body_encoding_bytes[&encoding].inc(chunk.len() as _);
+
+            // Decode in small slices to bound the size of the decoded data of
+            // highly compressed input.
+            for input in chunk.chunks(DECODE_SLICE_BYTES) {
+                decoder
+                    .decode(input, &mut pending)
+                    .map_err(|e| Error::decode(encoding, e))?;
+
+                if pending.len() >= flush_at {
+                    let len = complete_lines_len(&pending)?;
+                    if len > 0 {
+                        let rest = pending.split_off(len);
+                        let lines = std::mem::replace(&mut pending, rest);
+                        // Valid, as complete_lines_len() checked it.
+                        let lines = std::str::from_utf8(&lines).map_err(Error::NonUtf8Body)?;
+                        self.write_lines(write, lines).await?;
+                    } else if pending.len() > self.max_request_bytes {
+                        return Err(Error::RequestSizeExceeded(self.max_request_bytes));
+                    }
+                    flush_at = pending.len() + chunk_bytes;
+                }
+            }
+        }
+
+        decoder
+            .finish(&mut pending)
+            .map_err(|e| Error::decode(encoding, e))?;
+        let lines = std::str::from_utf8(&pending).map_err(Error::NonUtf8Body)?;
+        self.write_lines(write, lines).await
+    }
+
+    /// Parse and write a chunk of the line protocol of `write`, made up of
+    /// complete lines.
+    async fn write_lines(&self, write: &mut LineProtocolWrite, lines: &str) -> Result<(), Error> {
+        if write.partial_writes {
+            return self.partial_write_lines(write, lines).await;
+        }
+
         let start_instant = Instant::now();
 
-        let mut converter = LinesConverter::new(default_time);
-        converter.set_timestamp_base(write_info.precision.timestamp_base());
-        let (batches, stats) = match converter.write_lp(body).and_then(|_| converter.finish()) {
+        let mut converter = LinesConverter::new(write.default_time);
+        converter.set_timestamp_base(write.write_info.precision.timestamp_base());
+        let (batches, stats) = match converter.write_lp(lines).and_then(|_| converter.finish()) {
             Ok(v) => v,
             Err(mutable_batch_lp::Error::EmptyPayload) => {
                 debug!("nothing to write");
                 return Ok(());
             }
-            Err(e) => return Err(Error::ParseLineProtocol(e)),
+            Err(e) => {
+                return Err(Error::ParseLineProtocol(offset_line(e, write.lines_seen)));
+            }
         };
 
         let num_tables = batches.len();
@@ -355,11 +728,206 @@ where
             num_lines=stats.num_lines,
             num_fields=stats.num_fields,
             num_tables,
-            precision=?write_info.precision,
+            precision=?write.write_info.precision,
+            body_size=lines.len(),
+            namespace=%write.write_info.namespace,
+            duration=?duration,
+            "routing write",
+        );
+
+        let namespace_id = self.namespace_id(write).await?;
+
+        self.dml_handler
+            .write(
+                &write.write_info.namespace,
+                namespace_id,
+                batches,
+                write.span_ctx.clone(),
+            )
+            .await
+            .map_err(Into::into)?;
+
+        // The line numbers of parse errors count non-empty lines only.
+        write.lines_seen += stats.num_lines;
+
+        self.write_metric_lines.inc(stats.num_lines as _);
+        self.write_metric_fields.inc(stats.num_fields as _);
+        self.write_metric_tables.inc(num_tables as _);
+        self.write_metric_body_size.inc(lines.len() as _);
+
+        Ok(())
+    }
+
+    /// Write the valid lines of the line protocol chunk `lines`, recording the
+    /// lines that cannot be parsed or that conflict with the namespace schema
+    /// in `write` instead of failing the whole request.
+    ///
+    /// The [`DmlHandler`] reports a single schema conflict per call, so the
+    /// chunk is retried without the conflicting lines until it is accepted.
+    /// Errors other than schema conflicts fail the request as a whole.
+    async fn partial_write_lines(
+        &self,
+        write: &mut LineProtocolWrite,
+        lines: &str,
+    ) -> Result<(), Error> {
+        let mut conflicts = SchemaConflicts::default();
+
+        loop {
+            let start_instant = Instant::now();
+
+            let mut converter = LinesConverter::new(write.default_time);
+            converter.set_timestamp_base(write.write_info.precision.timestamp_base());
+            let offset = write.lines_seen;
+            let rejected = converter
+                .write_lp_partial_with(lines, |line| conflicts.check(line))
+                .into_iter()
+                .map(|r| RejectedLine {
+                    line: r.line + offset,
+                    ..r
+                })
+                .collect::<Vec<_>>();
+
+            let (batches, stats) = match converter.finish() {
+                Ok(v) => v,
+                Err(mutable_batch_lp::Error::EmptyPayload) => {
+                    debug!(rejected_lines = rejected.len(), "nothing to write");
+                    self.write_metric_rejected_lines.inc(rejected.len() as _);
+                    write.finish_chunk(rejected, lines);
+                    return Ok(());
+                }
+                Err(e) => return Err(Error::ParseLineProtocol(e)),
+            };
+
+            let num_tables = batches.len();
+            let duration = start_instant.elapsed();
+            self.http_line_protocol_parse_duration.record(duration);
+            debug!(
+                num_lines=stats.num_lines,
+                num_fields=stats.num_fields,
+                num_tables,
+                rejected_lines=rejected.len(),
+                precision=?write.write_info.precision,
+                body_size=lines.len(),
+                namespace=%write.write_info.namespace,
+                duration=?duration,
+                "routing partial write",
+            );
+
+            let namespace_id = self.namespace_id(write).await?;
+
+            let res: Result<(), DmlError> = self
+                .dml_handler
+                .write(
+                    &write.write_info.namespace,
+                    namespace_id,
+                    batches,
+                    write.span_ctx.clone(),
+                )
+                .await
+                .map_err(Into::into);
+
+            match res {
+                Ok(()) => {
+                    self.write_metric_lines.inc(stats.num_lines as _);
+                    self.write_metric_fields.inc(stats.num_fields as _);
+                    self.write_metric_tables.inc(num_tables as _);
+                    self.write_metric_body_size.inc(lines.len() as _);
+                    self.write_metric_rejected_lines.inc(rejected.len() as _);
+
+                    write.written += stats.num_lines;
+                    write.finish_chunk(rejected, lines);
+                    return Ok(());
+                }
+                Err(e) if conflicts.observe(&e) => {
+                    debug!(
+                        namespace=%write.write_info.namespace,
+                        error=%e,
+                        "retrying partial write without conflicting lines"
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Retrieve the namespace ID of the namespace `write` is for, once.
+    async fn namespace_id(&self, write: &mut LineProtocolWrite) -> Result<NamespaceId, Error> {
+        if let Some(id) = write.namespace_id {
+            return Ok(id);
+        }
+        let id = self
+            .namespace_resolver
+            .get_namespace_id(&write.write_info.namespace)
+            .await?;
+        write.namespace_id = Some(id);
+        Ok(id)
+    }
+
+    /// Handle a Prometheus remote write request.
+    ///
+    /// The request body is a snappy-compressed (block format) protobuf
+    /// `WriteRequest`, which is converted into [`MutableBatch`] as described
+    /// in [`mutable_batch_prom`] and routed through the [`DmlHandler`] like any
+    /// other write.
+    async fn prom_write_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<(), Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        trace!(
+            namespace=%write_info.namespace,
+            "processing prometheus remote write request"
+        );
+
+        let encoding = content_encoding(&req)?;
+        let unsnappy = match encoding {
+            None | Some("identity") => false,
+            Some("snappy") => true,
+            Some(v) => return Err(Error::InvalidContentEncoding(v.to_string())),
+        };
+
+        let body = self.read_limited_body(req.into_body()).await?;
+        let body = match unsnappy {
+            true => {
+                // Check the decompressed size before allocating to prevent a
+                // decompression bomb based DoS.
+                let len = snap::raw::decompress_len(&body).map_err(Error::InvalidSnappy)?;
+                if len > self.max_request_bytes {
+                    return Err(Error::RequestSizeExceeded(self.max_request_bytes));
+                }
+                snap::raw::Decoder::new()
+                    .decompress_vec(&body)
+                    .map_err(Error::InvalidSnappy)?
+                    .into()
+            }
+            false => body,
+        };
+
+        let start_instant = Instant::now();
+        let write_req = generated_types::prometheus::WriteRequest::decode(body.clone())
+            .map_err(Error::DecodePromWrite)?;
+        let (batches, stats) = match mutable_batch_prom::write_request_to_batches(&write_req) {
+            Ok(v) => v,
+            Err(mutable_batch_prom::Error::EmptyPayload) => {
+                debug!("nothing to write");
+                return Ok(());
+            }
+            Err(e) => return Err(Error::ConvertPromWrite(e)),
+        };
+
+        let num_tables = batches.len();
+        let duration = start_instant.elapsed();
+        self.http_prom_write_decode_duration.record(duration);
+        debug!(
+            num_series=stats.num_series,
+            num_samples=stats.num_samples,
+            num_tables,
             body_size=body.len(),
             namespace=%write_info.namespace,
             duration=?duration,
-            "routing write",
+            "routing prometheus remote write",
         );
 
         // Retrieve the namespace ID for this namespace.
@@ -373,30 +941,93 @@ where
             .await
             .map_err(Into::into)?;
 
-        self.write_metric_lines.inc(stats.num_lines as _);
-        self.write_metric_fields.inc(stats.num_fields as _);
-        self.write_metric_tables.inc(num_tables as _);
-        self.write_metric_body_size.inc(body.len() as _);
+        self.prom_write_metric_series.inc(stats.num_series as _);
+        self.prom_write_metric_samples.inc(stats.num_samples as _);
+        self.prom_write_metric_tables.inc(num_tables as _);
+        self.prom_write_metric_body_size.inc(body.len() as _);
 
         Ok(())
     }
 
-    /// Parse the request's body into raw bytes, applying the configured size
-    /// limits and decoding any content encoding.
-    async fn read_body(&self, req: hyper::Request<Body>) -> Result<Bytes, Error> {
-        let encoding = req
+    /// Handle an OTLP/HTTP metrics export request.
+    ///
+    /// The request body is a (optionally gzip-compressed) protobuf
+    /// `ExportMetricsServiceRequest`, which is converted into [`MutableBatch`]
+    /// as described in [`mutable_batch_otlp`] and routed through the
+    /// [`DmlHandler`] like any other write. The JSON encoding of OTLP is not
+    /// supported.
+    async fn otlp_metrics_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<(), Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        trace!(
+            namespace=%write_info.namespace,
+            "processing otlp metrics request"
+        );
+
+        match req
             .headers()
-            .get(&CONTENT_ENCODING)
+            .get(&CONTENT_TYPE)
             .map(|v| v.to_str().map_err(Error::NonUtf8ContentHeader))
-            .transpose()?;
-        let ungzip = match encoding {
-            None | Some("identity") => false,
-            Some("gzip") => true,
-            Some(v) => return Err(Error::InvalidContentEncoding(v.to_string())),
+            .transpose()?
+        {
+            None | Some(OTLP_PROTOBUF_CONTENT_TYPE) => {}
+            Some(v) => return Err(Error::InvalidOtlpContentType(v.to_string())),
+        }
+
+        let body = self.read_body(req).await?;
+
+        let start_instant = Instant::now();
+        let export_req =
+            ExportMetricsServiceRequest::decode(body.clone()).map_err(Error::DecodeOtlpMetrics)?;
+        let (batches, stats) = match mutable_batch_otlp::export_request_to_batches(&export_req) {
+            Ok(v) => v,
+            Err(mutable_batch_otlp::Error::EmptyPayload) => {
+                debug!("nothing to write");
+                return Ok(());
+            }
+            Err(e) => return Err(Error::ConvertOtlpMetrics(e)),
         };
 
-        let mut payload = req.into_body();
+        let num_tables = batches.len();
+        let duration = start_instant.elapsed();
+        self.http_otlp_decode_duration.record(duration);
+        debug!(
+            num_metrics=stats.num_metrics,
+            num_data_points=stats.num_data_points,
+            num_skipped_metrics=stats.num_skipped_metrics,
+            num_tables,
+            body_size=body.len(),
+            namespace=%write_info.namespace,
+            duration=?duration,
+            "routing otlp metrics",
+        );
+
+        // Retrieve the namespace ID for this namespace.
+        let namespace_id = self
+            .namespace_resolver
+            .get_namespace_id(&write_info.namespace)
+            .await?;
+
+        self.dml_handler
+            .write(&write_info.namespace, namespace_id, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        self.otlp_metric_metrics.inc(stats.num_metrics as _);
+        self.otlp_metric_data_points.inc(stats.num_data_points as _);
+        self.otlp_metric_tables.inc(num_tables as _);
+        self.otlp_metric_body_size.inc(body.len() as _);
+
+        Ok(())
+    }
 
+    /// Read the request body into memory, returning an error if it exceeds
+    /// the configured size limit.
+    async fn read_limited_body(&self, mut payload: Body) -> Result<Bytes, Error> {
         let mut body = BytesMut::new();
         while let Some(chunk) = payload.next().await {
             let chunk = chunk.map_err(Error::ClientHangup)?;
@@ -406,37 +1037,150 @@ where
             }
             body.extend_from_slice(&chunk);
         }
-        let body = body.freeze();
+        Ok(body.freeze())
+    }
+
+    /// Parse the request's body into raw bytes, applying the configured size
+    /// limits and decoding any content encoding.
+    async fn read_body(&self, req: hyper::Request<Body>) -> Result<Bytes, Error> {
+        let encoding = self.body_encoding(&req)?;
+
+        let body = self.read_limited_body(req.into_body()).await?;
+        self.body_encoding_bytes[&encoding].inc(body.len() as _);
 
         // If the body is not compressed, return early.
-        if !ungzip {
+        if encoding == ContentEncoding::Identity {
             return Ok(body);
         }
 
-        // Unzip the gzip-encoded content
-        use std::io::Read;
-        let decoder = flate2::read::GzDecoder::new(&body[..]);
-
-        // Read at most max_request_bytes bytes to prevent a decompression bomb
-        // based DoS.
-        //
-        // In order to detect if the entire stream ahs been read, or truncated,
-        // read an extra byte beyond the limit and check the resulting data
-        // length - see the max_request_size_truncation test.
-        let mut decoder = decoder.take(self.max_request_bytes as u64 + 1);
+        let mut decoder = encoding.decoder().map_err(|e| Error::decode(encoding, e))?;
         let mut decoded_data = Vec::new();
-        decoder
-            .read_to_end(&mut decoded_data)
-            .map_err(Error::InvalidGzip)?;
 
-        // If the length is max_size+1, the body is at least max_size+1 bytes in
-        // length, and possibly longer, but truncated.
+        // Decode the body in slices, checking the size of the decoded data as
+        // it grows to prevent a decompression bomb based DoS.
+        for input in body.chunks(DECODE_SLICE_BYTES) {
+            decoder
+                .decode(input, &mut decoded_data)
+                .map_err(|e| Error::decode(encoding, e))?;
+            if decoded_data.len() > self.max_request_bytes {
+                return Err(Error::RequestSizeExceeded(self.max_request_bytes));
+            }
+        }
+        decoder
+            .finish(&mut decoded_data)
+            .map_err(|e| Error::decode(encoding, e))?;
         if decoded_data.len() > self.max_request_bytes {
             return Err(Error::RequestSizeExceeded(self.max_request_bytes));
         }
 
         Ok(decoded_data.into())
     }
+
+    /// Return the [`ContentEncoding`] of the body of `req`, recording it in
+    /// the body encoding metrics.
+    fn body_encoding(&self, req: &Request<Body>) -> Result<ContentEncoding, Error> {
+        let header = content_encoding(req)?;
+        let encoding = ContentEncoding::parse(header)
+            .ok_or_else(|| Error::InvalidContentEncoding(header.unwrap_or_default().to_string()))?;
+        self.body_encoding_requests[&encoding].inc(1);
+        Ok(encoding)
+    }
+}
+
+/// The state of a single line protocol write request, which is written in
+/// one or more chunks of complete lines.
+#[derive(Debug)]
+struct LineProtocolWrite {
+    write_info: WriteParams,
+    span_ctx: Option<SpanContext>,
+    default_time: i64,
+    partial_writes: bool,
+    namespace_id: Option<NamespaceId>,
+
+    /// The number of lines in the chunks before the current one, to number
+    /// the lines of the current chunk within the whole request.
+    lines_seen: usize,
+
+    /// The number of lines written and rejected by a partial write.
+    written: usize,
+    rejected: Vec<RejectedLine>,
+}
+
+impl LineProtocolWrite {
+    /// Record the `rejected` lines of the partially written chunk `lines`.
+    fn finish_chunk(&mut self, rejected: Vec<RejectedLine>, lines: &str) {
+        self.rejected.extend(rejected);
+        // All but the last chunk end with a newline, the line numbers of
+        // partial writes count every line.
+        self.lines_seen += lines.matches('\n').count();
+    }
+}
+
+/// Return the length of the longest prefix of `buf` made up of complete lines
+/// of line protocol.
+fn complete_lines_len(buf: &[u8]) -> Result<usize, Error> {
+    let Some(last_newline) = buf.iter().rposition(|&b| b == b'\n') else {
+        return Ok(0);
+    };
+    let prefix = std::str::from_utf8(&buf[..=last_newline]).map_err(Error::NonUtf8Body)?;
+
+    // A newline within a quoted string field value does not end a line, the
+    // last line returned by split_lines() is the incomplete one (if any).
+    let incomplete = split_lines(prefix).last().unwrap_or_default();
+    Ok(prefix.len() - incomplete.len())
+}
+
+/// Offset the line number of a line protocol error by `lines`, the number of
+/// lines written in earlier chunks of the request.
+fn offset_line(e: mutable_batch_lp::Error, lines: usize) -> mutable_batch_lp::Error {
+    match e {
+        mutable_batch_lp::Error::LineProtocol { source, line } => {
+            mutable_batch_lp::Error::LineProtocol {
+                source,
+                line: line + lines,
+            }
+        }
+        mutable_batch_lp::Error::Write { source, line } => mutable_batch_lp::Error::Write {
+            source,
+            line: line + lines,
+        },
+        e => e,
+    }
+}
+
+/// Return the value of the `Content-Encoding` header of `req`, if any.
+fn content_encoding(req: &Request<Body>) -> Result<Option<&str>, Error> {
+    req.headers()
+        .get(&CONTENT_ENCODING)
+        .map(|v| v.to_str().map_err(Error::NonUtf8ContentHeader))
+        .transpose()
+}
+
+/// Returns true if the `partial_writes` query parameter or the
+/// `X-IOx-Partial-Writes` header of `req` is set to `true`.
+fn partial_writes_enabled(req: &Request<Body>) -> bool {
+    let param = req
+        .uri()
+        .query()
+        .and_then(|q| serde_urlencoded::from_str::<Vec<(String, String)>>(q).ok())
+        .map_or(false, |params| {
+            params
+                .iter()
+                .any(|(k, v)| k == PARTIAL_WRITES_PARAM && v == "true")
+        });
+    let header = req
+        .headers()
+        .get(PARTIAL_WRITES_HEADER)
+        .map_or(false, |v| v.as_bytes().eq_ignore_ascii_case(b"true"));
+
+    param || header
+}
+
+fn partial_write_result(written: usize, rejected: Vec<RejectedLine>) -> Result<(), Error> {
+    match rejected.is_empty() {
+        true => Ok(()),
+        false => Err(Error::PartialWrite { written, rejected }),
+    }
 }
 
 #[cfg(test)]
@@ -445,10 +1189,11 @@ mod tests {
 
     use assert_matches::assert_matches;
     use data_types::{
-        NamespaceId, NamespaceName, NamespaceNameError, OrgBucketMappingError, TableId,
+        ColumnType, NamespaceId, NamespaceName, NamespaceNameError, OrgBucketMappingError, TableId,
     };
     use flate2::{write::GzEncoder, Compression};
     use hyper::header::HeaderValue;
+    use iox_catalog::TableScopedError;
     use metric::{Attributes, Metric};
     use mutable_batch::column::ColumnData;
     use mutable_batch_lp::LineWriteError;
@@ -896,6 +1641,123 @@ mod tests {
         want_dml_calls = []
     );
 
+    test_write_handler!(
+        partial_writes_all_valid,
+        query_string = "?org=bananas&bucket=test&partial_writes=true",
+        body = "platanos val=42i 1\nplatanos val=43i 2".as_bytes(),
+        dml_handler = [Ok(())],
+        want_result = Ok(_),
+        want_dml_calls = [MockDmlHandlerCall::Write{write_input, ..}] => {
+            assert_eq!(write_input["platanos"].rows(), 2);
+        }
+    );
+
+    test_write_handler!(
+        partial_writes_invalid_lines,
+        query_string = "?org=bananas&bucket=test&partial_writes=true",
+        body = "platanos val=42i 1\nnot line protocol\n\nplatanos val=4.2 3".as_bytes(),
+        dml_handler = [Ok(())],
+        want_result = Err(Error::PartialWrite { written: 1, .. }),
+        want_dml_calls = [MockDmlHandlerCall::Write{write_input, ..}] => {
+            assert_eq!(write_input["platanos"].rows(), 1);
+        }
+    );
+
+    test_write_handler!(
+        partial_writes_all_invalid,
+        query_string = "?org=bananas&bucket=test&partial_writes=true",
+        body = "not line protocol".as_bytes(),
+        dml_handler = [],
+        want_result = Err(Error::PartialWrite { written: 0, .. }),
+        want_dml_calls = []
+    );
+
+    test_write_handler!(
+        partial_writes_schema_conflict,
+        query_string = "?org=bananas&bucket=test&partial_writes=true",
+        body = "platanos val=42i 1\nbananas val=4.2 2\nbananas val=42i 3".as_bytes(),
+        dml_handler = [
+            Err(DmlError::Schema(SchemaError::Conflict(TableScopedError::new(
+                "bananas",
+                iox_catalog::interface::Error::ColumnTypeMismatch {
+                    name: "val".to_string(),
+                    existing: ColumnType::I64,
+                    new: ColumnType::F64,
+                },
+            )))),
+            Ok(()),
+        ],
+        want_result = Err(Error::PartialWrite { written: 2, .. }),
+        want_dml_calls = [
+            MockDmlHandlerCall::Write{..},
+            MockDmlHandlerCall::Write{write_input, ..}
+        ] => {
+            assert_eq!(write_input["platanos"].rows(), 1);
+            assert_eq!(write_input["bananas"].rows(), 1);
+        }
+    );
+
+    test_write_handler!(
+        partial_writes_dml_handler_error,
+        query_string = "?org=bananas&bucket=test&partial_writes=true",
+        body = "platanos val=42i 1\nnot line protocol".as_bytes(),
+        dml_handler = [Err(DmlError::Internal("💣".into()))],
+        want_result = Err(Error::DmlHandler(DmlError::Internal(_))),
+        want_dml_calls = [MockDmlHandlerCall::Write { .. }]
+    );
+
+    #[test]
+    fn test_partial_writes_enabled() {
+        let request = |uri: &str, header: Option<&'static str>| {
+            let mut builder = Request::builder().uri(uri).method("POST");
+            if let Some(v) = header {
+                builder = builder.header(PARTIAL_WRITES_HEADER, v);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        assert!(!partial_writes_enabled(&request(
+            "/api/v2/write?org=a&bucket=b",
+            None
+        )));
+        assert!(partial_writes_enabled(&request(
+            "/api/v2/write?org=a&bucket=b&partial_writes=true",
+            None
+        )));
+        assert!(!partial_writes_enabled(&request(
+            "/api/v2/write?org=a&bucket=b&partial_writes=false",
+            None
+        )));
+        assert!(partial_writes_enabled(&request(
+            "/api/v2/write?org=a&bucket=b",
+            Some("True")
+        )));
+    }
+
+    #[test]
+    fn test_partial_write_error() {
+        let rejected = (1..=MAX_REPORTED_LINES + 2)
+            .map(|line| RejectedLine {
+                line: line * 2,
+                reason: "bad".to_string(),
+            })
+            .collect::<Vec<_>>();
+        let err = Error::PartialWrite {
+            written: 3,
+            rejected,
+        };
+
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.line(), Some(2));
+
+        let msg = err.to_string();
+        assert!(msg.starts_with(
+            "partial write has occurred, 3 line(s) written, errors encountered on line(s):\n\
+            line 2: bad\nline 4: bad\n"
+        ));
+        assert!(msg.ends_with("\n... and 2 more"));
+    }
+
     test_http_handler!(
         not_found,
         uri = "https://bananas.example/wat",
@@ -1259,6 +2121,517 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_write_body_encodings() {
+        let body = "platanos,tag1=A val=42i 123456\nplatanos,tag1=B val=43i 123457";
+
+        let encoded = [
+            ("deflate", {
+                let mut e = flate2::write::ZlibEncoder::new(Vec::new(), Compression::default());
+                e.write_all(body.as_bytes()).unwrap();
+                e.finish().unwrap()
+            }),
+            (
+                "zstd",
+                zstd::stream::encode_all(body.as_bytes(), 0).unwrap(),
+            ),
+            ("snappy", {
+                let mut e = snap::write::FrameEncoder::new(Vec::new());
+                e.write_all(body.as_bytes()).unwrap();
+                e.into_inner().unwrap()
+            }),
+        ];
+
+        for (encoding, encoded) in encoded {
+            let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+            let metrics = Arc::new(metric::Registry::default());
+            let delegate = HttpDelegate::new(
+                MAX_BYTES,
+                100,
+                MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID),
+                Arc::clone(&dml_handler),
+                &metrics,
+                Box::<MultiTenantRequestUnifier>::default(),
+            );
+
+            let encoded_len = encoded.len();
+            let request = Request::builder()
+                .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+                .method("POST")
+                .header(CONTENT_ENCODING, encoding)
+                .body(Body::from(encoded))
+                .unwrap();
+
+            let got = delegate.route(request).await;
+            assert_matches!(got, Ok(_), "{encoding}");
+            assert_matches!(
+                dml_handler.calls().as_slice(),
+                [MockDmlHandlerCall::Write { write_input, .. }] => {
+                    assert_eq!(write_input["platanos"].rows(), 2);
+                }
+            );
+
+            let attr = Attributes::from(&[("encoding", encoding)]);
+            let requests = metrics
+                .get_instrument::<Metric<U64Counter>>("http_request_body_encoding")
+                .expect("failed to read metric")
+                .get_observer(&attr)
+                .expect("failed to get observer")
+                .fetch();
+            assert_eq!(requests, 1, "{encoding}");
+            let bytes = metrics
+                .get_instrument::<Metric<U64Counter>>("http_request_body_encoded_bytes")
+                .expect("failed to read metric")
+                .get_observer(&attr)
+                .expect("failed to get observer")
+                .fetch();
+            assert_eq!(bytes, encoded_len as u64, "{encoding}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_invalid_encoded_body() {
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            100,
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID),
+            Arc::new(MockDmlHandler::default()),
+            &metric::Registry::default(),
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(CONTENT_ENCODING, "zstd")
+            .body(Body::from("platanos val=42i 1"))
+            .unwrap();
+
+        let got = delegate.route(request).await;
+        assert_matches!(got, Err(Error::InvalidEncodedBody("zstd", _)));
+    }
+
+    /// Build a [`HttpDelegate`] that streams writes in chunks of
+    /// `chunk_bytes`.
+    fn streaming_delegate(
+        chunk_bytes: usize,
+        dml_handler: Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+    ) -> HttpDelegate<Arc<MockDmlHandler<HashMap<String, MutableBatch>>>, MockNamespaceResolver>
+    {
+        HttpDelegate::new(
+            MAX_BYTES,
+            100,
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID),
+            dml_handler,
+            &metric::Registry::default(),
+            Box::<MultiTenantRequestUnifier>::default(),
+        )
+        .with_write_stream_chunk_bytes(chunk_bytes)
+    }
+
+    /// Return a write request with a body streamed in `pieces`.
+    fn streamed_write_request<B>(query_string: &str, pieces: Vec<B>) -> Request<Body>
+    where
+        B: Into<Bytes>,
+    {
+        let pieces = pieces
+            .into_iter()
+            .map(|p| Ok::<_, std::io::Error>(p.into()))
+            .collect::<Vec<_>>();
+        Request::builder()
+            .uri(format!(
+                "https://bananas.example/api/v2/write?org=bananas&bucket=test{query_string}"
+            ))
+            .method("POST")
+            .body(Body::wrap_stream(futures::stream::iter(pieces)))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stream_write_chunks() {
+        let dml_handler =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(()), Ok(()), Ok(()), Ok(())]));
+        let delegate = streaming_delegate(20, Arc::clone(&dml_handler));
+
+        // The string field of the second line contains a newline, and the
+        // third line is split across pieces of the body.
+        let request = streamed_write_request(
+            "",
+            vec![
+                "platanos val=42i 1\nplatanos ",
+                "str=\"a\nb\" 2\nplat",
+                "anos val=43i 3\n",
+                "platanos val=44i 4",
+            ],
+        );
+
+        let got = delegate.route(request).await;
+        assert_matches!(got, Ok(_));
+
+        let calls = dml_handler.calls();
+        assert!(calls.len() > 1, "write was not streamed in chunks");
+        let rows: usize = calls
+            .iter()
+            .map(|c| match c {
+                MockDmlHandlerCall::Write { write_input, .. } => write_input["platanos"].rows(),
+                c => panic!("unexpected call {c:?}"),
+            })
+            .sum();
+        assert_eq!(rows, 4);
+    }
+
+    #[tokio::test]
+    async fn test_stream_write_error_line_number() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let delegate = streaming_delegate(10, Arc::clone(&dml_handler));
+
+        let request =
+            streamed_write_request("", vec!["platanos val=42i 1\n", "\nnot line protocol\n"]);
+
+        let got = delegate.route(request).await;
+        assert_matches!(
+            got,
+            Err(Error::ParseLineProtocol(
+                mutable_batch_lp::Error::LineProtocol { line: 2, .. }
+            ))
+        );
+        // The first chunk was written before the error.
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { .. }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_write_partial() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(()), Ok(())]));
+        let delegate = streaming_delegate(10, Arc::clone(&dml_handler));
+
+        let request = streamed_write_request(
+            "&partial_writes=true",
+            vec![
+                "platanos val=42i 1\n",
+                "\nnot line protocol\nplatanos val=43i 4",
+            ],
+        );
+
+        let got = delegate.route(request).await;
+        assert_matches!(got, Err(Error::PartialWrite { written: 2, rejected }) => {
+            assert_matches!(rejected.as_slice(), [RejectedLine { line: 3, .. }]);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_stream_write_line_too_long() {
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let delegate = streaming_delegate(10, Arc::clone(&dml_handler));
+
+        // Longer than the maximum request size before its newline is decoded.
+        let line = format!("platanos str=\"{}\" 1\n", "a".repeat(2 * MAX_BYTES));
+        let request = streamed_write_request("", vec![line]);
+
+        let got = delegate.route(request).await;
+        assert_matches!(got, Err(Error::RequestSizeExceeded(_)));
+        assert_matches!(dml_handler.calls().as_slice(), []);
+    }
+
+    #[tokio::test]
+    async fn test_stream_write_larger_than_max_request() {
+        let dml_handler = Arc::new(
+            MockDmlHandler::default().with_write_return(
+                iter::repeat_with(|| Ok(()))
+                    .take(MAX_BYTES)
+                    .collect::<Vec<_>>(),
+            ),
+        );
+        let delegate = streaming_delegate(MAX_BYTES / 2, Arc::clone(&dml_handler));
+
+        let pieces = iter::repeat("platanos val=42i 1\n")
+            .take(MAX_BYTES)
+            .collect();
+        let request = streamed_write_request("", pieces);
+
+        let got = delegate.route(request).await;
+        assert_matches!(got, Ok(_));
+    }
+
+    #[test]
+    fn test_complete_lines_len() {
+        assert_eq!(complete_lines_len(b"").unwrap(), 0);
+        assert_eq!(complete_lines_len(b"platanos val=1i").unwrap(), 0);
+        assert_eq!(complete_lines_len(b"platanos val=1i\nplat").unwrap(), 16);
+        assert_eq!(complete_lines_len(b"a v=1i\nb v=2i\n").unwrap(), 14);
+        // A newline in a string field value does not end a line
+        assert_eq!(complete_lines_len(b"a v=1i\nb v=\"x\ny").unwrap(), 7);
+        assert_eq!(complete_lines_len(b"a v=1i\nb v=\"x\ny\" 1\n").unwrap(), 19);
+        assert_matches!(
+            complete_lines_len(b"a v=\"\xff\"\n"),
+            Err(Error::NonUtf8Body(_))
+        );
+    }
+
+    fn prom_write_body(series: usize) -> Vec<u8> {
+        use generated_types::prometheus::{Label, Sample, TimeSeries, WriteRequest};
+
+        let req = WriteRequest {
+            timeseries: (0..series)
+                .map(|i| TimeSeries {
+                    labels: vec![
+                        Label {
+                            name: "__name__".to_string(),
+                            value: "platanos".to_string(),
+                        },
+                        Label {
+                            name: "series".to_string(),
+                            value: i.to_string(),
+                        },
+                    ],
+                    samples: vec![Sample {
+                        value: 42.0,
+                        timestamp: 1_000,
+                    }],
+                })
+                .collect(),
+        };
+
+        snap::raw::Encoder::new()
+            .compress_vec(&req.encode_to_vec())
+            .expect("failed to compress test body")
+    }
+
+    async fn prom_write(
+        body: Vec<u8>,
+        encoding: Option<&'static str>,
+    ) -> (
+        Result<Response<Body>, Error>,
+        Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+        Arc<metric::Registry>,
+    ) {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let mut request = Request::builder()
+            .uri("https://bananas.example/api/v1/prom/write?org=bananas&bucket=test")
+            .method("POST");
+        if let Some(encoding) = encoding {
+            request = request.header(CONTENT_ENCODING, encoding);
+        }
+        let request = request.body(Body::from(body)).unwrap();
+
+        (delegate.route(request).await, dml_handler, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_prom_write_ok() {
+        let (got, dml_handler, metrics) = prom_write(prom_write_body(2), Some("snappy")).await;
+        assert_matches!(got, Ok(v) => {
+            assert_eq!(v.status(), StatusCode::NO_CONTENT);
+        });
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, namespace_id, write_input }] => {
+                assert_eq!(namespace, NAMESPACE_NAME);
+                assert_eq!(*namespace_id, NAMESPACE_ID);
+                let table = write_input.get("platanos").expect("table not found");
+                assert_eq!(table.rows(), 2);
+                let ts = table.timestamp_summary().unwrap();
+                assert_eq!(ts.stats.min, Some(1_000_000_000));
+            }
+        );
+
+        assert_metric_hit(&metrics, "http_prom_write_series", Some(2));
+        assert_metric_hit(&metrics, "http_prom_write_samples", Some(2));
+        assert_metric_hit(&metrics, "http_prom_write_tables", Some(1));
+        assert_metric_hit(&metrics, "http_prom_write_body_bytes", None);
+    }
+
+    #[tokio::test]
+    async fn test_prom_write_unsupported_encoding() {
+        let (got, dml_handler, _metrics) = prom_write(prom_write_body(1), Some("gzip")).await;
+        assert_matches!(got, Err(Error::InvalidContentEncoding(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prom_write_invalid_snappy() {
+        let (got, dml_handler, _metrics) = prom_write(b"bananas".to_vec(), Some("snappy")).await;
+        assert_matches!(got, Err(Error::InvalidSnappy(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prom_write_invalid_protobuf() {
+        let body = snap::raw::Encoder::new()
+            .compress_vec(b"\xFF\xFF\xFF")
+            .unwrap();
+        let (got, dml_handler, _metrics) = prom_write(body, Some("snappy")).await;
+        assert_matches!(got, Err(Error::DecodePromWrite(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prom_write_decompressed_size_limit() {
+        use generated_types::prometheus::{Label, TimeSeries, WriteRequest};
+
+        // A highly compressible payload that is below MAX_BYTES when
+        // compressed, but exceeds it once decompressed.
+        let req = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![Label {
+                    name: "__name__".to_string(),
+                    value: "a".repeat(MAX_BYTES * 4),
+                }],
+                samples: vec![],
+            }],
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&req.encode_to_vec())
+            .unwrap();
+        assert!(body.len() < MAX_BYTES);
+
+        let (got, dml_handler, _metrics) = prom_write(body, Some("snappy")).await;
+        assert_matches!(got, Err(Error::RequestSizeExceeded(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    fn otlp_metrics_body() -> Vec<u8> {
+        use generated_types::opentelemetry::proto::{
+            common::v1::{any_value, AnyValue, KeyValue},
+            metrics::v1::{
+                metric, number_data_point, Gauge, Metric, NumberDataPoint, ResourceMetrics,
+                ScopeMetrics,
+            },
+            resource::v1::Resource,
+        };
+
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![KeyValue {
+                        key: "service.name".to_string(),
+                        value: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue("api".to_string())),
+                        }),
+                    }],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics: vec![Metric {
+                        name: "platanos".to_string(),
+                        description: String::new(),
+                        unit: String::new(),
+                        data: Some(metric::Data::Gauge(Gauge {
+                            data_points: vec![
+                                NumberDataPoint {
+                                    time_unix_nano: 1_000_000_000,
+                                    value: Some(number_data_point::Value::AsDouble(42.0)),
+                                    ..Default::default()
+                                };
+                                2
+                            ],
+                        })),
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    async fn otlp_metrics(
+        body: Vec<u8>,
+        content_type: Option<&'static str>,
+    ) -> (
+        Result<Response<Body>, Error>,
+        Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+        Arc<metric::Registry>,
+    ) {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let mut request = Request::builder()
+            .uri("https://bananas.example/api/v1/otlp/metrics?org=bananas&bucket=test")
+            .method("POST");
+        if let Some(content_type) = content_type {
+            request = request.header(CONTENT_TYPE, content_type);
+        }
+        let request = request.body(Body::from(body)).unwrap();
+
+        (delegate.route(request).await, dml_handler, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_otlp_metrics_ok() {
+        let (got, dml_handler, metrics) =
+            otlp_metrics(otlp_metrics_body(), Some("application/x-protobuf")).await;
+        let resp = got.expect("request should succeed");
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "application/x-protobuf"
+        );
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(
+            ExportMetricsServiceResponse::decode(body).unwrap(),
+            ExportMetricsServiceResponse::default()
+        );
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, namespace_id, write_input }] => {
+                assert_eq!(namespace, NAMESPACE_NAME);
+                assert_eq!(*namespace_id, NAMESPACE_ID);
+                let table = write_input.get("platanos").expect("table not found");
+                assert_eq!(table.rows(), 2);
+                let ts = table.timestamp_summary().unwrap();
+                assert_eq!(ts.stats.min, Some(1_000_000_000));
+            }
+        );
+
+        assert_metric_hit(&metrics, "http_otlp_metrics_metrics", Some(1));
+        assert_metric_hit(&metrics, "http_otlp_metrics_data_points", Some(2));
+        assert_metric_hit(&metrics, "http_otlp_metrics_tables", Some(1));
+        assert_metric_hit(&metrics, "http_otlp_metrics_body_bytes", None);
+    }
+
+    #[tokio::test]
+    async fn test_otlp_metrics_json_unsupported() {
+        let (got, dml_handler, _metrics) =
+            otlp_metrics(b"{}".to_vec(), Some("application/json")).await;
+        assert_matches!(got, Err(Error::InvalidOtlpContentType(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_otlp_metrics_invalid_protobuf() {
+        let (got, dml_handler, _metrics) = otlp_metrics(b"\xFF\xFF\xFF".to_vec(), None).await;
+        assert_matches!(got, Err(Error::DecodeOtlpMetrics(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
     // The display text of Error gets passed through `ioxd_router::IoxHttpErrorAdaptor` then
     // `ioxd_common::http::error::HttpApiError` as the JSON "message" value in error response
     // bodies. These are fixture tests to document error messages that users might see when
@@ -1350,6 +2723,11 @@ mod tests {
             "error decoding gzip stream: [io Error]",
         ),
 
+        (
+            InvalidEncodedBody("zstd", std::io::Error::new(std::io::ErrorKind::Other, "[io Error]")),
+            "error decoding zstd stream: [io Error]",
+        ),
+
         (
             ParseLineProtocol(mutable_batch_lp::Error::LineProtocol {
                 source: influxdb_line_protocol::Error::FieldSetMissing,
@@ -1393,6 +2771,38 @@ mod tests {
             "failed to parse line protocol: timestamp overflows i64",
         ),
 
+        (
+            InvalidSnappy(snap::Error::Empty),
+            "error decoding snappy payload: snappy: corrupt input (empty)",
+        ),
+
+        (
+            DecodePromWrite(prost::DecodeError::new("bananas")),
+            "failed to decode prometheus remote write request: failed to decode Protobuf message: \
+            bananas",
+        ),
+
+        (
+            ConvertPromWrite(mutable_batch_prom::Error::MissingMetricName { index: 42 }),
+            "failed to convert prometheus remote write request: time series 42 has no metric name \
+            (__name__ label)",
+        ),
+
+        (
+            InvalidOtlpContentType("application/json".to_string()),
+            "unsupported content-type for otlp metrics: application/json",
+        ),
+
+        (
+            DecodeOtlpMetrics(prost::DecodeError::new("bananas")),
+            "failed to decode otlp metrics request: failed to decode Protobuf message: bananas",
+        ),
+
+        (
+            ConvertOtlpMetrics(mutable_batch_otlp::Error::MissingMetricName { index: 42 }),
+            "failed to convert otlp metrics request: metric 42 has no name",
+        ),
+
         (
             DmlHandler(DmlError::NamespaceNotFound("[namespace name]".into())),
             "dml handler error: namespace [namespace name] does not exist",
@@ -1467,6 +2877,14 @@ mod tests {
             "dml handler error: service limit reached: couldn't create table bananas; limit reached on namespace 42",
         ),
 
+        (
+            DmlHandler(DmlError::RateLimit(RateLimitError::RateLimited {
+                namespace: "bananas".to_string(),
+                retry_after: Duration::from_secs(1),
+            })),
+            "dml handler error: namespace bananas has exceeded its write rate limit, retry after 1s",
+        ),
+
         // A single-tenant namespace parsing error
         (
             SingleTenantError(SingleTenantExtractError::InvalidNamespace(NamespaceNameError::LengthConstraint{name: "bananas".to_string()})),
@@ -0,0 +1,264 @@
+//! Incremental decoding of request bodies with a `Content-Encoding`.
+
+use std::io::{self, Read, Write};
+
+/// The stream identifier chunk that starts a snappy framed stream.
+const SNAPPY_STREAM_IDENTIFIER: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+
+/// A supported `Content-Encoding` of a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ContentEncoding {
+    Identity,
+    Gzip,
+    /// The zlib format, as specified for HTTP.
+    Deflate,
+    Zstd,
+    /// The snappy framing format.
+    Snappy,
+}
+
+impl ContentEncoding {
+    /// All supported encodings.
+    pub(crate) const ALL: [Self; 5] = [
+        Self::Identity,
+        Self::Gzip,
+        Self::Deflate,
+        Self::Zstd,
+        Self::Snappy,
+    ];
+
+    /// Parse the value of a `Content-Encoding` header, returning [`None`] for
+    /// unsupported encodings.
+    pub(crate) fn parse(header: Option<&str>) -> Option<Self> {
+        match header {
+            None | Some("identity") => Some(Self::Identity),
+            Some("gzip") => Some(Self::Gzip),
+            Some("deflate") => Some(Self::Deflate),
+            Some("zstd") => Some(Self::Zstd),
+            Some("snappy") => Some(Self::Snappy),
+            Some(_) => None,
+        }
+    }
+
+    /// The name of the encoding, as used in the `Content-Encoding` header.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Identity => "identity",
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+            Self::Zstd => "zstd",
+            Self::Snappy => "snappy",
+        }
+    }
+
+    /// Return a decoder for a body with this encoding.
+    pub(crate) fn decoder(&self) -> io::Result<Decoder> {
+        Ok(match self {
+            Self::Identity => Decoder::Identity,
+            Self::Gzip => Decoder::Gzip(Box::new(flate2::write::GzDecoder::new(Vec::new()))),
+            Self::Deflate => Decoder::Deflate(flate2::write::ZlibDecoder::new(Vec::new())),
+            Self::Zstd => Decoder::Zstd(zstd::stream::write::Decoder::new(Vec::new())?),
+            Self::Snappy => Decoder::Snappy(SnappyFrames::default()),
+        })
+    }
+}
+
+/// An incremental decoder of a body with a [`ContentEncoding`].
+///
+/// Decoding a slice of the body produces the decoded bytes that are available
+/// so far, allowing the caller to bound the memory used for the decoded body.
+pub(crate) enum Decoder {
+    Identity,
+    Gzip(Box<flate2::write::GzDecoder<Vec<u8>>>),
+    Deflate(flate2::write::ZlibDecoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Decoder<'static, Vec<u8>>),
+    Snappy(SnappyFrames),
+}
+
+impl std::fmt::Debug for Decoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Identity => "identity",
+            Self::Gzip(_) => "gzip",
+            Self::Deflate(_) => "deflate",
+            Self::Zstd(_) => "zstd",
+            Self::Snappy(_) => "snappy",
+        };
+        f.debug_tuple("Decoder").field(&name).finish()
+    }
+}
+
+impl Decoder {
+    /// Decode `input`, appending the decoded bytes to `out`.
+    pub(crate) fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        match self {
+            Self::Identity => out.extend_from_slice(input),
+            Self::Gzip(d) => {
+                d.write_all(input)?;
+                out.append(d.get_mut());
+            }
+            Self::Deflate(d) => {
+                d.write_all(input)?;
+                out.append(d.get_mut());
+            }
+            Self::Zstd(d) => {
+                d.write_all(input)?;
+                out.append(d.get_mut());
+            }
+            Self::Snappy(d) => d.decode(input, out)?,
+        }
+        Ok(())
+    }
+
+    /// Signal the end of the body, appending any remaining decoded bytes to
+    /// `out`.
+    pub(crate) fn finish(self, out: &mut Vec<u8>) -> io::Result<()> {
+        match self {
+            Self::Identity => {}
+            Self::Gzip(d) => out.append(&mut d.finish()?),
+            Self::Deflate(d) => out.append(&mut d.finish()?),
+            Self::Zstd(mut d) => {
+                d.flush()?;
+                out.append(d.get_mut());
+            }
+            Self::Snappy(d) => d.finish()?,
+        }
+        Ok(())
+    }
+}
+
+/// An incremental decoder of the [snappy framing format].
+///
+/// Chunks are buffered until complete, and then decoded (and checksummed) on
+/// their own.
+///
+/// [snappy framing format]: https://github.com/google/snappy/blob/main/framing_format.txt
+#[derive(Debug, Default)]
+pub(crate) struct SnappyFrames {
+    buf: Vec<u8>,
+}
+
+impl SnappyFrames {
+    fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        self.buf.extend_from_slice(input);
+
+        // Each chunk starts with a 1 byte type and a 3 byte little-endian
+        // length.
+        let mut consumed = 0;
+        while let Some(header) = self.buf.get(consumed..consumed + 4) {
+            let len = u32::from_le_bytes([header[1], header[2], header[3], 0]) as usize;
+            let end = consumed + 4 + len;
+            if self.buf.len() < end {
+                break;
+            }
+
+            let chunk = &self.buf[consumed..end];
+            if chunk != SNAPPY_STREAM_IDENTIFIER {
+                let stream = [SNAPPY_STREAM_IDENTIFIER, chunk].concat();
+                snap::read::FrameDecoder::new(stream.as_slice()).read_to_end(out)?;
+            }
+            consumed = end;
+        }
+
+        self.buf.drain(..consumed);
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self.buf.is_empty() {
+            true => Ok(()),
+            false => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "truncated snappy stream",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(encoding: ContentEncoding, data: &[u8]) -> Vec<u8> {
+        match encoding {
+            ContentEncoding::Identity => data.to_vec(),
+            ContentEncoding::Gzip => {
+                let mut e =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                e.write_all(data).unwrap();
+                e.finish().unwrap()
+            }
+            ContentEncoding::Deflate => {
+                let mut e =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                e.write_all(data).unwrap();
+                e.finish().unwrap()
+            }
+            ContentEncoding::Zstd => zstd::stream::encode_all(data, 0).unwrap(),
+            ContentEncoding::Snappy => {
+                let mut e = snap::write::FrameEncoder::new(Vec::new());
+                e.write_all(data).unwrap();
+                e.into_inner().unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Large enough for multiple snappy chunks
+        let data = "platanos,tag=A val=42i 1\n".repeat(10_000);
+
+        for encoding in ContentEncoding::ALL {
+            assert_eq!(
+                ContentEncoding::parse(Some(encoding.as_str())),
+                Some(encoding)
+            );
+
+            let encoded = encode(encoding, data.as_bytes());
+            let mut decoder = encoding.decoder().unwrap();
+            let mut decoded = vec![];
+            for piece in encoded.chunks(7) {
+                decoder.decode(piece, &mut decoded).unwrap();
+            }
+            decoder.finish(&mut decoded).unwrap();
+
+            assert_eq!(decoded, data.as_bytes(), "{encoding:?}");
+        }
+
+        assert_eq!(
+            ContentEncoding::parse(None),
+            Some(ContentEncoding::Identity)
+        );
+        assert_eq!(ContentEncoding::parse(Some("br")), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for encoding in [
+            ContentEncoding::Gzip,
+            ContentEncoding::Deflate,
+            ContentEncoding::Zstd,
+            ContentEncoding::Snappy,
+        ] {
+            let mut decoder = encoding.decoder().unwrap();
+            let mut decoded = vec![];
+            let res = decoder
+                .decode(b"not compressed at all", &mut decoded)
+                .and_then(|_| decoder.finish(&mut decoded));
+            assert!(res.is_err(), "{encoding:?}");
+        }
+    }
+
+    #[test]
+    fn test_truncated_snappy() {
+        let encoded = encode(ContentEncoding::Snappy, b"platanos val=42i 1\n");
+
+        let mut decoder = ContentEncoding::Snappy.decoder().unwrap();
+        let mut decoded = vec![];
+        decoder
+            .decode(&encoded[..encoded.len() - 1], &mut decoded)
+            .unwrap();
+        assert!(decoded.is_empty());
+        assert!(decoder.finish(&mut decoded).is_err());
+    }
+}
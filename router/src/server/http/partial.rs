@@ -0,0 +1,186 @@
+//! Line-level rejection of schema conflicts for partial line protocol writes.
+
+use data_types::ColumnType;
+use hashbrown::{HashMap, HashSet};
+use influxdb_line_protocol::{FieldValue, ParsedLine};
+use iox_catalog::interface::Error as CatalogError;
+
+use crate::dml_handlers::{DmlError, SchemaError};
+
+/// A schema conflict that causes a line to be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conflict {
+    /// The column exists with a different type.
+    Type(ColumnType),
+    /// The column has been deleted.
+    Deleted,
+}
+
+/// The schema conflicts observed while writing a single partial write
+/// request.
+///
+/// The [`DmlHandler`] rejects a write as a whole, reporting only the first
+/// conflicting column it encounters. A partial write learns the conflicts one
+/// at a time from the returned errors, and retries the write without the lines
+/// that conflict until the [`DmlHandler`] accepts it.
+///
+/// [`DmlHandler`]: crate::dml_handlers::DmlHandler
+#[derive(Debug, Default)]
+pub(crate) struct SchemaConflicts {
+    /// Names of deleted tables.
+    deleted_tables: HashSet<String>,
+    /// Conflicting columns, keyed by table name and then column name.
+    columns: HashMap<String, HashMap<String, Conflict>>,
+}
+
+impl SchemaConflicts {
+    /// Record the schema conflict described by `err`.
+    ///
+    /// Returns false if `err` is not a schema conflict or the conflict is
+    /// already known, in which case retrying the write cannot succeed.
+    pub(crate) fn observe(&mut self, err: &DmlError) -> bool {
+        let DmlError::Schema(SchemaError::Conflict(e)) = err else {
+            return false;
+        };
+
+        let (key, conflict) = match e.err() {
+            CatalogError::TableSoftDeleted { .. } => {
+                return self.deleted_tables.insert(e.table().to_string());
+            }
+            CatalogError::ColumnTypeMismatch { name, existing, .. } => {
+                (name, Conflict::Type(*existing))
+            }
+            CatalogError::ColumnSoftDeleted { name, .. } => (name, Conflict::Deleted),
+            _ => return false,
+        };
+
+        self.columns
+            .entry(e.table().to_string())
+            .or_default()
+            .insert(key.clone(), conflict)
+            .is_none()
+    }
+
+    /// Returns an error describing the conflict if `line` conflicts with the
+    /// observed schema conflicts.
+    pub(crate) fn check(&self, line: &ParsedLine<'_>) -> Result<(), String> {
+        let table = line.series.measurement.as_str();
+        if self.deleted_tables.contains(table) {
+            return Err(format!("table {table} has been deleted"));
+        }
+        let Some(columns) = self.columns.get(table) else {
+            return Ok(());
+        };
+
+        let tags = line
+            .series
+            .tag_set
+            .iter()
+            .flatten()
+            .map(|(name, _)| (name.as_str(), ColumnType::Tag));
+        let fields = line
+            .field_set
+            .iter()
+            .map(|(name, value)| (name.as_str(), field_type(value)));
+
+        for (name, new) in tags.chain(fields) {
+            match columns.get(name) {
+                Some(Conflict::Type(existing)) if *existing != new => {
+                    return Err(format!(
+                        "column {name} is type {existing} but write has type {new}"
+                    ));
+                }
+                Some(Conflict::Deleted) => {
+                    return Err(format!("column {name} in table {table} has been deleted"));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn field_type(value: &FieldValue<'_>) -> ColumnType {
+    match value {
+        FieldValue::I64(_) => ColumnType::I64,
+        FieldValue::U64(_) => ColumnType::U64,
+        FieldValue::F64(_) => ColumnType::F64,
+        FieldValue::String(_) => ColumnType::String,
+        FieldValue::Boolean(_) => ColumnType::Bool,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::{NamespaceId, TableId};
+    use influxdb_line_protocol::parse_lines;
+    use iox_catalog::TableScopedError;
+
+    use super::*;
+
+    fn conflict(table: &str, err: CatalogError) -> DmlError {
+        DmlError::Schema(SchemaError::Conflict(TableScopedError::new(table, err)))
+    }
+
+    fn check(conflicts: &SchemaConflicts, lp: &str) -> Result<(), String> {
+        let line = parse_lines(lp).next().unwrap().unwrap();
+        conflicts.check(&line)
+    }
+
+    #[test]
+    fn test_schema_conflicts() {
+        let mut conflicts = SchemaConflicts::default();
+        assert_eq!(check(&conflicts, "cpu,host=a val=1i"), Ok(()));
+
+        let type_mismatch = conflict(
+            "cpu",
+            CatalogError::ColumnTypeMismatch {
+                name: "val".to_string(),
+                existing: ColumnType::F64,
+                new: ColumnType::I64,
+            },
+        );
+        assert!(conflicts.observe(&type_mismatch));
+        // Observing the same conflict again makes no progress
+        assert!(!conflicts.observe(&type_mismatch));
+
+        assert_eq!(
+            check(&conflicts, "cpu,host=a val=1i"),
+            Err("column val is type f64 but write has type i64".to_string())
+        );
+        assert_eq!(check(&conflicts, "cpu,host=a val=1.0"), Ok(()));
+        assert_eq!(check(&conflicts, "mem,host=a val=1i"), Ok(()));
+        assert_eq!(
+            check(&conflicts, "cpu,val=a host=1i"),
+            Err("column val is type f64 but write has type tag".to_string())
+        );
+
+        assert!(conflicts.observe(&conflict(
+            "cpu",
+            CatalogError::ColumnSoftDeleted {
+                name: "host".to_string(),
+                table_id: TableId::new(1),
+            },
+        )));
+        assert_eq!(
+            check(&conflicts, "cpu,host=a val=1.0"),
+            Err("column host in table cpu has been deleted".to_string())
+        );
+
+        assert!(conflicts.observe(&conflict(
+            "mem",
+            CatalogError::TableSoftDeleted {
+                name: "mem".to_string(),
+                namespace_id: NamespaceId::new(1),
+            },
+        )));
+        assert_eq!(
+            check(&conflicts, "mem val=1i"),
+            Err("table mem has been deleted".to_string())
+        );
+
+        // Other errors cannot be retried
+        assert!(!conflicts.observe(&DmlError::Internal("💣".into())));
+    }
+}
@@ -68,7 +68,7 @@ pub trait WriteRequestUnifier: std::fmt::Debug + Send + Sync {
 #[async_trait]
 impl<T> WriteRequestUnifier for Arc<T>
 where
-    T: WriteRequestUnifier,
+    T: WriteRequestUnifier + ?Sized,
 {
     async fn parse_v1(&self, req: &Request<Body>) -> Result<WriteParams, Error> {
         (**self).parse_v1(req).await
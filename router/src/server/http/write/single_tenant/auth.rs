@@ -5,27 +5,8 @@ use std::sync::Arc;
 use authz::{
     self, http::AuthorizationHeaderExtension, Action, Authorizer, Error, Permission, Resource,
 };
-use base64::{prelude::BASE64_STANDARD, Engine};
 use data_types::NamespaceName;
-use hyper::{header::HeaderValue, Body, Request};
-
-fn extract_header_token(header_value: &'_ HeaderValue) -> Option<Vec<u8>> {
-    let mut parts = header_value.as_bytes().splitn(2, |&v| v == b' ');
-    let token = match parts.next()? {
-        b"Token" | b"Bearer" => parts.next()?.to_vec(),
-        b"Basic" => parts
-            .next()
-            .and_then(|v| BASE64_STANDARD.decode(v).ok())?
-            .splitn(2, |&v| v == b':')
-            .nth(1)?
-            .to_vec(),
-        _ => return None,
-    };
-    if token.is_empty() {
-        return None;
-    }
-    Some(token)
-}
+use hyper::{Body, Request};
 
 pub(crate) async fn authorize(
     authz: &Arc<dyn Authorizer>,
@@ -36,8 +17,7 @@ pub(crate) async fn authorize(
     let token = req
         .extensions()
         .get::<AuthorizationHeaderExtension>()
-        .and_then(|v| v.as_ref())
-        .and_then(extract_header_token)
+        .and_then(|v| v.token())
         .or_else(|| query_param_token.map(|t| t.into_bytes()));
 
     let perms = [Permission::ResourceAction(
@@ -84,6 +64,7 @@ pub mod mock {
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use base64::{prelude::BASE64_STANDARD, Engine};
     use data_types::NamespaceId;
     use hyper::header::HeaderValue;
 
@@ -13,13 +13,13 @@ use object_store::memory::InMemory;
 use router::{
     dml_handlers::{
         client::mock::MockWriteClient, Chain, DmlHandlerChainExt, FanOutAdaptor,
-        InstrumentationDecorator, Partitioned, Partitioner, RetentionValidator, RpcWrite,
-        SchemaValidator,
+        InstrumentationDecorator, Partitioned, Partitioner, RateLimiter, RetentionValidator,
+        RpcWrite, SchemaValidator,
     },
     namespace_cache::{MemoryNamespaceCache, ReadThroughCache, ShardedCache},
     namespace_resolver::{MissingNamespaceAction, NamespaceAutocreation, NamespaceSchemaResolver},
     server::{
-        grpc::RpcWriteGrpcDelegate,
+        grpc::{NamespaceCacheRefresher, RpcWriteGrpcDelegate},
         http::{write::multi_tenant::MultiTenantRequestUnifier, HttpDelegate},
     },
 };
@@ -113,8 +113,13 @@ type HttpDelegateStack = HttpDelegate<
         Chain<
             Chain<
                 Chain<
-                    RetentionValidator<
-                        Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
+                    Chain<
+                        RateLimiter<
+                            Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
+                        >,
+                        RetentionValidator<
+                            Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
+                        >,
                     >,
                     SchemaValidator<
                         Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
@@ -159,6 +164,8 @@ impl TestContext {
 
         let retention_validator = RetentionValidator::new(Arc::clone(&ns_cache));
 
+        let rate_limiter = RateLimiter::new(Arc::clone(&ns_cache), &metrics);
+
         let partitioner = Partitioner::new(PartitionTemplate {
             parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
         });
@@ -175,7 +182,8 @@ impl TestContext {
 
         let parallel_write = FanOutAdaptor::new(rpc_writer);
 
-        let handler_stack = retention_validator
+        let handler_stack = rate_limiter
+            .and_then(retention_validator)
             .and_then(schema_validator)
             .and_then(partitioner)
             .and_then(parallel_write);
@@ -196,6 +204,7 @@ impl TestContext {
         let grpc_delegate = RpcWriteGrpcDelegate::new(
             Arc::clone(&catalog),
             Arc::new(InMemory::default()),
+            Arc::new(NamespaceCacheRefresher::new(Arc::clone(&ns_cache))),
             TopicId::new(TEST_TOPIC_ID),
             QueryPoolId::new(TEST_QUERY_POOL_ID),
         );
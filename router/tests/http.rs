@@ -8,7 +8,7 @@ use hyper::{Body, Request, StatusCode};
 use iox_catalog::interface::SoftDeletedRows;
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{Attributes, DurationHistogram, Metric, U64Counter};
-use router::dml_handlers::{DmlError, RetentionError, SchemaError};
+use router::dml_handlers::{DmlError, RateLimitError, RetentionError, SchemaError};
 use std::sync::Arc;
 
 pub mod common;
@@ -120,6 +120,63 @@ async fn test_write_outside_retention_period() {
     assert_eq!(response.as_status_code(), StatusCode::FORBIDDEN);
 }
 
+#[tokio::test]
+async fn test_write_rate_limited() {
+    let ctx = TestContextBuilder::default()
+        .with_autocreate_namespace(None)
+        .build()
+        .await;
+
+    // Create the namespace with a limit of 1 line per second.
+    let mut repos = ctx.catalog().repositories().await;
+    repos
+        .namespaces()
+        .create(
+            "bananas_test",
+            None,
+            TopicId::new(TEST_TOPIC_ID),
+            QueryPoolId::new(TEST_QUERY_POOL_ID),
+        )
+        .await
+        .expect("failed to create namespace");
+    repos
+        .namespaces()
+        .update_lines_per_second_limit("bananas_test", Some(1))
+        .await
+        .expect("failed to update lines per second limit");
+    drop(repos);
+
+    let now = SystemProvider::default()
+        .now()
+        .timestamp_nanos()
+        .to_string();
+    let lp = format!("platanos,tag1=A val=42i {now}\nplatanos,tag1=B val=42i {now}");
+
+    // The first write is admitted, exhausting the limit...
+    let response = ctx
+        .write_lp("bananas", "test", lp.clone())
+        .await
+        .expect("write should succeed");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // ...causing the next to be rejected.
+    let err = ctx
+        .write_lp("bananas", "test", lp)
+        .await
+        .expect_err("write should be rate limited");
+    assert_matches!(
+        &err,
+        router::server::http::Error::DmlHandler(
+            DmlError::RateLimit(RateLimitError::RateLimited { namespace, .. })
+        ) => {
+            assert_eq!(namespace, "bananas_test");
+        }
+    );
+    assert_eq!(err.as_status_code(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(err.retry_after().is_some());
+    assert_eq!(ctx.write_calls().len(), 1);
+}
+
 #[tokio::test]
 async fn test_schema_conflict() {
     let ctx = TestContextBuilder::default()
@@ -1,5 +1,6 @@
 //! Routines for error handling
 use datafusion::error::DataFusionError;
+use iox_query::exec::cancel::QueryCancelled;
 
 /// Converts a [`DataFusionError`] into the appropriate [`tonic::Code`]
 ///
@@ -25,6 +26,8 @@ pub fn datafusion_error_to_tonic_code(e: &DataFusionError) -> tonic::Code {
 
     match e {
         DataFusionError::ResourcesExhausted(_) => tonic::Code::ResourceExhausted,
+        // the query was stopped by `KILL QUERY` or a Flight cancel action
+        DataFusionError::External(e) if e.is::<QueryCancelled>() => tonic::Code::Cancelled,
         // Map as many as possible back into user visible (non internal) errors
         DataFusionError::SQL(_)
         | DataFusionError::SchemaError(_)
@@ -102,6 +105,11 @@ mod test {
 
         do_transl_test(DataFusionError::Internal(s), tonic::Code::Internal);
 
+        do_transl_test(
+            DataFusionError::External(Box::new(QueryCancelled)),
+            tonic::Code::Cancelled,
+        );
+
         // traversal
         do_transl_test(
             DataFusionError::Context(
@@ -35,30 +35,48 @@ impl Planner {
         }
     }
 
-    /// Plan a SQL query against the data in a namespace, and return a
+    /// Plan a SQL query against the data in `namespace`, and return a
     /// DataFusion physical execution plan.
-    pub async fn sql(&self, query: impl Into<String> + Send) -> Result<Arc<dyn ExecutionPlan>> {
+    ///
+    /// Uses the result cache of the namespace, if enabled.
+    pub async fn sql<N>(
+        &self,
+        namespace: Arc<N>,
+        query: impl Into<String> + Send,
+    ) -> Result<Arc<dyn ExecutionPlan>>
+    where
+        N: QueryNamespace + 'static,
+    {
         let planner = SqlQueryPlanner::new();
         let query = query.into();
         let ctx = self.ctx.child_ctx("planner sql");
 
         self.ctx
-            .run(async move { planner.query(&query, &ctx).await })
+            .run(async move {
+                match namespace.result_cache() {
+                    Some(cache) => planner.query_cached(&query, cache, &ctx).await,
+                    None => planner.query(&query, &ctx).await,
+                }
+            })
             .await
     }
 
-    /// Plan an InfluxQL query against the data in `database`, and return a
+    /// Plan an InfluxQL query against the data in `namespace`, and return a
     /// DataFusion physical execution plan.
-    pub async fn influxql(
+    pub async fn influxql<N>(
         &self,
+        namespace: Arc<N>,
         query: impl Into<String> + Send,
-    ) -> Result<Arc<dyn ExecutionPlan>> {
+    ) -> Result<Arc<dyn ExecutionPlan>>
+    where
+        N: QueryNamespace + 'static,
+    {
         let planner = InfluxQLQueryPlanner::new();
         let query = query.into();
         let ctx = self.ctx.child_ctx("planner influxql");
 
         self.ctx
-            .run(async move { planner.query(&query, &ctx).await })
+            .run(async move { planner.query(&query, namespace, &ctx).await })
             .await
     }
 
@@ -0,0 +1,338 @@
+//! gRPC service for the compactor.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro
+)]
+
+use compactor2::admin::{CompactionAdmin, CompactionJobState};
+use data_types::PartitionId;
+use generated_types::influxdata::iox::compactor::v1::{
+    compaction_job::State, compaction_service_server, CancelCompactionJobRequest,
+    CancelCompactionJobResponse, CompactPartitionsRequest, CompactPartitionsResponse,
+    CompactTableRequest, CompactTableResponse, CompactionJob, DeleteSkippedCompactionsRequest,
+    DeleteSkippedCompactionsResponse, ListCompactionJobsRequest, ListCompactionJobsResponse,
+    ListSkippedCompactionsRequest, ListSkippedCompactionsResponse,
+};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::*;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the compaction gRPC service
+#[derive(Debug)]
+pub struct CompactionService {
+    /// Catalog.
+    catalog: Arc<dyn Catalog>,
+
+    /// Admin state of the running compactor.
+    admin: Arc<CompactionAdmin>,
+}
+
+impl CompactionService {
+    /// Create a new compaction service for the given catalog and compactor.
+    pub fn new(catalog: Arc<dyn Catalog>, admin: Arc<CompactionAdmin>) -> Self {
+        Self { catalog, admin }
+    }
+}
+
+#[tonic::async_trait]
+impl compaction_service_server::CompactionService for CompactionService {
+    async fn list_skipped_compactions(
+        &self,
+        _request: Request<ListSkippedCompactionsRequest>,
+    ) -> Result<Response<ListSkippedCompactionsResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let skipped_compactions = repos
+            .partitions()
+            .list_skipped_compactions()
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(Response::new(ListSkippedCompactionsResponse {
+            skipped_compactions,
+        }))
+    }
+
+    async fn delete_skipped_compactions(
+        &self,
+        request: Request<DeleteSkippedCompactionsRequest>,
+    ) -> Result<Response<DeleteSkippedCompactionsResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        let skipped_compaction = repos
+            .partitions()
+            .delete_skipped_compactions(partition_id)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .map(Into::into);
+
+        Ok(Response::new(DeleteSkippedCompactionsResponse {
+            skipped_compaction,
+        }))
+    }
+
+    async fn compact_partitions(
+        &self,
+        request: Request<CompactPartitionsRequest>,
+    ) -> Result<Response<CompactPartitionsResponse>, Status> {
+        let partition_ids = request.into_inner().partition_ids;
+        if partition_ids.is_empty() {
+            return Err(Status::invalid_argument("no partition IDs given"));
+        }
+
+        let num_queued = self
+            .admin
+            .enqueue(partition_ids.into_iter().map(PartitionId::new));
+        info!(num_queued, "queued partitions for compaction");
+
+        Ok(Response::new(CompactPartitionsResponse {
+            num_queued: num_queued as i64,
+        }))
+    }
+
+    async fn compact_table(
+        &self,
+        request: Request<CompactTableRequest>,
+    ) -> Result<Response<CompactTableResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&req.namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Namespace {} not found", req.namespace_name))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &req.table_name)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("Table {} not found", req.table_name)))?;
+
+        let partition_ids: Vec<_> = repos
+            .partitions()
+            .list_by_table_id(table.id)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+
+        let num_queued = self.admin.enqueue(partition_ids.iter().copied());
+        info!(
+            %req.namespace_name,
+            %req.table_name,
+            num_queued,
+            "queued partitions of table for compaction"
+        );
+
+        Ok(Response::new(CompactTableResponse {
+            partition_ids: partition_ids.into_iter().map(|id| id.get()).collect(),
+            num_queued: num_queued as i64,
+        }))
+    }
+
+    async fn list_compaction_jobs(
+        &self,
+        _request: Request<ListCompactionJobsRequest>,
+    ) -> Result<Response<ListCompactionJobsResponse>, Status> {
+        let jobs = self
+            .admin
+            .jobs()
+            .into_iter()
+            .map(to_compaction_job)
+            .collect();
+
+        Ok(Response::new(ListCompactionJobsResponse { jobs }))
+    }
+
+    async fn cancel_compaction_job(
+        &self,
+        request: Request<CancelCompactionJobRequest>,
+    ) -> Result<Response<CancelCompactionJobResponse>, Status> {
+        let partition_id = request.into_inner().partition_id;
+        let cancelled = self.admin.cancel(PartitionId::new(partition_id));
+        info!(partition_id, cancelled, "cancel compaction job");
+
+        Ok(Response::new(CancelCompactionJobResponse { cancelled }))
+    }
+}
+
+// converts the compactor job status to protobuf
+fn to_compaction_job(job: compactor2::admin::CompactionJob) -> CompactionJob {
+    let state = match job.state {
+        CompactionJobState::Queued => State::Queued,
+        CompactionJobState::Running => State::Running,
+        CompactionJobState::Succeeded => State::Succeeded,
+        CompactionJobState::Failed => State::Failed,
+        CompactionJobState::Cancelled => State::Cancelled,
+    };
+
+    CompactionJob {
+        partition_id: job.partition_id.get(),
+        state: state.into(),
+        requested: job.requested,
+        enqueued_at: job.enqueued_at.map(|t| t.timestamp_nanos()),
+        started_at: job.started_at.map(|t| t.timestamp_nanos()),
+        finished_at: job.finished_at.map(|t| t.timestamp_nanos()),
+        rounds_completed: job.rounds_completed as i64,
+        files_in_round: job.files_in_round as i64,
+        last_error: job.last_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::TRANSITION_SHARD_INDEX;
+    use generated_types::influxdata::iox::compactor::v1::compaction_service_server::CompactionService;
+    use iox_catalog::mem::MemCatalog;
+    use iox_time::{MockProvider, Time};
+
+    fn admin() -> Arc<CompactionAdmin> {
+        Arc::new(CompactionAdmin::new(Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(42),
+        ))))
+    }
+
+    #[tokio::test]
+    async fn compact_table() {
+        let catalog = Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let partition_ids = {
+            let mut repos = catalog.repositories().await;
+            let topic = repos.topics().create_or_get("iox-shared").await.unwrap();
+            let pool = repos
+                .query_pools()
+                .create_or_get("iox-shared")
+                .await
+                .unwrap();
+            let shard = repos
+                .shards()
+                .create_or_get(&topic, TRANSITION_SHARD_INDEX)
+                .await
+                .unwrap();
+            let namespace = repos
+                .namespaces()
+                .create("compaction_test", None, topic.id, pool.id)
+                .await
+                .unwrap();
+            let table = repos
+                .tables()
+                .create_or_get("compaction_test_table", namespace.id)
+                .await
+                .unwrap();
+            let mut ids = vec![];
+            for key in ["foo", "bar"] {
+                let partition = repos
+                    .partitions()
+                    .create_or_get(key.into(), shard.id, table.id)
+                    .await
+                    .unwrap();
+                ids.push(partition.id.get());
+            }
+            ids.sort_unstable();
+            ids
+        };
+
+        let grpc = super::CompactionService::new(catalog, admin());
+
+        let mut response = grpc
+            .compact_table(Request::new(CompactTableRequest {
+                namespace_name: String::from("compaction_test"),
+                table_name: String::from("compaction_test_table"),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        response.partition_ids.sort_unstable();
+        assert_eq!(response.partition_ids, partition_ids);
+        assert_eq!(response.num_queued, 2);
+
+        let jobs = grpc
+            .list_compaction_jobs(Request::new(ListCompactionJobsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .jobs;
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs.iter().all(|job| job.state() == State::Queued
+            && job.requested
+            && job.enqueued_at == Some(42)));
+
+        let status = grpc
+            .compact_table(Request::new(CompactTableRequest {
+                namespace_name: String::from("compaction_test"),
+                table_name: String::from("missing"),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn compact_and_cancel_partitions() {
+        let catalog = Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let grpc = super::CompactionService::new(catalog, admin());
+
+        let status = grpc
+            .compact_partitions(Request::new(CompactPartitionsRequest {
+                partition_ids: vec![],
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let response = grpc
+            .compact_partitions(Request::new(CompactPartitionsRequest {
+                partition_ids: vec![1, 2, 1],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.num_queued, 2);
+
+        let response = grpc
+            .cancel_compaction_job(Request::new(CancelCompactionJobRequest { partition_id: 1 }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.cancelled);
+
+        let response = grpc
+            .cancel_compaction_job(Request::new(CancelCompactionJobRequest { partition_id: 3 }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.cancelled);
+
+        let jobs = grpc
+            .list_compaction_jobs(Request::new(ListCompactionJobsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .jobs;
+        let states: Vec<_> = jobs
+            .iter()
+            .map(|job| (job.partition_id, job.state()))
+            .collect();
+        assert_eq!(states, vec![(1, State::Cancelled), (2, State::Queued)]);
+    }
+}
@@ -17,11 +17,12 @@ use arrow_flight::{
 use arrow_util::flight::prepare_schema_for_flight;
 use authz::Authorizer;
 use bytes::Bytes;
-use data_types::NamespaceNameError;
+use data_types::{NamespaceNameError, QueryPriority};
 use datafusion::{error::DataFusionError, physical_plan::ExecutionPlan};
 use flightsql::FlightSQLCommand;
 use futures::{ready, Stream, StreamExt, TryStreamExt};
 use generated_types::influxdata::iox::querier::v1 as proto;
+use influxdb_influxql_parser::{parse_statements, statement::Statement};
 use iox_query::{
     exec::{ExecutionContextProvider, IOxSessionContext},
     QueryCompletedToken, QueryNamespace,
@@ -30,13 +31,24 @@ use observability_deps::tracing::{debug, info, warn};
 use prost::Message;
 use request::{IoxGetRequest, RunQuery};
 use service_common::{datafusion_error_to_tonic_code, planner::Planner, QueryNamespaceProvider};
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::{fmt::Debug, pin::Pin, sync::Arc, task::Poll, time::Instant};
 use tonic::{metadata::MetadataMap, Request, Response, Streaming};
 use trace::{ctx::SpanContext, span::SpanExt};
 use trace_http::ctx::{RequestLogContext, RequestLogContextExt};
 use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
+/// Type of the `DoAction` request that cancels a running query. The body
+/// of the action is a [`proto::CancelQuery`] message.
+///
+/// Cancelling a query requires write permission on its namespace, as the
+/// query may have been started by another user.
+///
+/// Note: the FlightSQL `CancelFlightInfo` action is not implemented; the
+/// Arrow Flight version in use does not define it, so FlightSQL clients
+/// cannot cancel queries.
+const CANCEL_QUERY_ACTION_TYPE: &str = "CancelQuery";
+
 /// The supported names of the grpc header that contain the target database
 /// for FlightSQL requests.
 ///
@@ -49,6 +61,12 @@ const IOX_FLIGHT_SQL_DATABASE_HEADERS: [&str; 4] = [
     "iox-namespace-name", // deprecated
 ];
 
+/// The name of the grpc header that selects the priority class of a query,
+/// one of `interactive`, `normal` or `batch`.
+///
+/// Queries without this header use the default priority of their namespace.
+const IOX_QUERY_PRIORITY_HEADER: &str = "iox-query-priority";
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -89,6 +107,13 @@ pub enum Error {
         source: tonic::metadata::errors::ToStrError,
     },
 
+    #[snafu(display(
+        "Invalid '{}' header in request: {}",
+        IOX_QUERY_PRIORITY_HEADER,
+        description
+    ))]
+    InvalidQueryPriorityHeader { description: String },
+
     #[snafu(display("Invalid database name: {}", source))]
     InvalidDatabaseName { source: NamespaceNameError },
 
@@ -106,6 +131,16 @@ pub enum Error {
     #[snafu(display("Invalid protobuf: {}", source))]
     Deserialization { source: prost::DecodeError },
 
+    #[snafu(display(
+        "Query {} not found or not running in namespace '{}'",
+        query_id,
+        namespace_name
+    ))]
+    QueryNotFound {
+        namespace_name: String,
+        query_id: u64,
+    },
+
     #[snafu(display("Unsupported message type: {}", description))]
     UnsupportedMessageType { description: String },
 
@@ -129,6 +164,7 @@ impl From<Error> for tonic::Status {
         let msg = "Error handling Flight gRPC request";
         match err {
             Error::DatabaseNotFound { .. }
+            | Error::QueryNotFound { .. }
             | Error::InvalidTicket { .. }
             | Error::InvalidHandshake { .. }
             | Error::Unauthenticated { .. }
@@ -140,6 +176,7 @@ impl From<Error> for tonic::Status {
             | Error::TooManyFlightSQLDatabases { .. }
             | Error::NoFlightSQLDatabase
             | Error::InvalidDatabaseHeader { .. }
+            | Error::InvalidQueryPriorityHeader { .. }
             | Error::Planning { .. }
             | Error::Deserialization { .. }
             | Error::InternalCreatingTicket { .. }
@@ -161,13 +198,14 @@ impl Error {
         let msg = self.to_string();
 
         let code = match self {
-            Self::DatabaseNotFound { .. } => tonic::Code::NotFound,
+            Self::DatabaseNotFound { .. } | Self::QueryNotFound { .. } => tonic::Code::NotFound,
             Self::InvalidTicket { .. }
             | Self::InvalidHandshake { .. }
             | Self::Deserialization { .. }
             | Self::TooManyFlightSQLDatabases { .. }
             | Self::NoFlightSQLDatabase
             | Self::InvalidDatabaseHeader { .. }
+            | Self::InvalidQueryPriorityHeader { .. }
             | Self::InvalidDatabaseName { .. } => tonic::Code::InvalidArgument,
             Self::Planning { source, .. } | Self::Query { source, .. } => {
                 datafusion_error_to_tonic_code(&source)
@@ -396,6 +434,7 @@ where
         permit: InstrumentedAsyncOwnedSemaphorePermit,
         query: &RunQuery,
         namespace: String,
+        priority: Option<QueryPriority>,
     ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
         let db = self
             .server
@@ -405,26 +444,38 @@ where
                 namespace_name: &namespace,
             })?;
 
-        let ctx = db.new_query_context(span_ctx);
+        let ctx = db.new_query_context_with_priority(span_ctx, priority);
         let (query_completed_token, physical_plan) = match query {
             RunQuery::Sql(sql_query) => {
-                let token = db.record_query(&ctx, "sql", Box::new(sql_query.clone()));
+                let token = db
+                    .record_query(&ctx, "sql", Box::new(sql_query.clone()))
+                    .context(QuerySnafu {
+                        namespace_name: &namespace,
+                    })?;
                 let plan = Planner::new(&ctx)
-                    .sql(sql_query)
+                    .sql(Arc::clone(&db), sql_query)
                     .await
                     .context(PlanningSnafu)?;
                 (token, plan)
             }
             RunQuery::InfluxQL(sql_query) => {
-                let token = db.record_query(&ctx, "influxql", Box::new(sql_query.clone()));
+                let token = db
+                    .record_query(&ctx, "influxql", Box::new(sql_query.clone()))
+                    .context(QuerySnafu {
+                        namespace_name: &namespace,
+                    })?;
                 let plan = Planner::new(&ctx)
-                    .influxql(sql_query)
+                    .influxql(Arc::clone(&db), sql_query)
                     .await
                     .context(PlanningSnafu)?;
                 (token, plan)
             }
             RunQuery::FlightSQL(msg) => {
-                let token = db.record_query(&ctx, "flightsql", Box::new(msg.to_string()));
+                let token = db
+                    .record_query(&ctx, "flightsql", Box::new(msg.to_string()))
+                    .context(QuerySnafu {
+                        namespace_name: &namespace,
+                    })?;
                 let plan = Planner::new(&ctx)
                     .flight_sql_do_get(&namespace, db, msg.clone())
                     .await
@@ -438,6 +489,45 @@ where
 
         Ok(Response::new(Box::pin(output) as TonicStream<FlightData>))
     }
+
+    /// Implementation of the `CancelQuery` action
+    async fn run_cancel_query(
+        &self,
+        span_ctx: Option<SpanContext>,
+        authz_token: Option<Vec<u8>>,
+        body: Bytes,
+    ) -> Result<Response<TonicStream<arrow_flight::Result>>, tonic::Status> {
+        let proto::CancelQuery {
+            namespace_name,
+            query_id,
+        } = proto::CancelQuery::decode(body).context(DeserializationSnafu)?;
+
+        info!(%namespace_name, %query_id, "CancelQuery request");
+
+        let perms = cancel_query_permissions(&namespace_name);
+        self.authz
+            .require_any_permission(authz_token.as_deref(), &perms)
+            .await
+            .map_err(Error::from)?;
+
+        let db = self
+            .server
+            .db(&namespace_name, span_ctx.child_span("get namespace"))
+            .await
+            .context(DatabaseNotFoundSnafu {
+                namespace_name: &namespace_name,
+            })?;
+
+        ensure!(
+            db.cancel_query(query_id),
+            QueryNotFoundSnafu {
+                namespace_name,
+                query_id
+            }
+        );
+
+        Ok(Response::new(futures::stream::empty().boxed()))
+    }
 }
 
 #[tonic::async_trait]
@@ -470,6 +560,7 @@ where
         let trace = external_span_ctx.format_jaeger();
         let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
         let authz_token = get_flight_authz(request.metadata());
+        let priority = get_query_priority(request.metadata())?;
         let ticket = request.into_inner();
 
         // attempt to decode ticket
@@ -485,6 +576,7 @@ where
 
         let perms = match query {
             RunQuery::FlightSQL(cmd) => flightsql_permissions(namespace_name, cmd),
+            RunQuery::InfluxQL(q) if is_kill_query(q) => cancel_query_permissions(namespace_name),
             RunQuery::Sql(_) | RunQuery::InfluxQL(_) => vec![authz::Permission::ResourceAction(
                 authz::Resource::Database(namespace_name.to_string()),
                 authz::Action::Read,
@@ -511,7 +603,13 @@ where
         );
 
         let response = self
-            .run_do_get(span_ctx, permit, query, namespace_name.to_string())
+            .run_do_get(
+                span_ctx,
+                permit,
+                query,
+                namespace_name.to_string(),
+                priority,
+            )
             .await;
 
         if let Err(e) = &response {
@@ -648,8 +746,14 @@ where
         let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
         let trace = external_span_ctx.format_jaeger();
 
-        let namespace_name = get_flightsql_namespace(request.metadata())?;
         let authz_token = get_flight_authz(request.metadata());
+
+        if request.get_ref().r#type == CANCEL_QUERY_ACTION_TYPE {
+            let body = request.into_inner().body;
+            return self.run_cancel_query(span_ctx, authz_token, body).await;
+        }
+
+        let namespace_name = get_flightsql_namespace(request.metadata())?;
         let Action {
             r#type: action_type,
             body,
@@ -764,6 +868,19 @@ fn get_flightsql_namespace(metadata: &MetadataMap) -> Result<String> {
     Ok(database_name.context(NoFlightSQLDatabaseSnafu)?.to_string())
 }
 
+/// Retrieve the query priority requested via the [`IOX_QUERY_PRIORITY_HEADER`], if any.
+fn get_query_priority(metadata: &MetadataMap) -> Result<Option<QueryPriority>> {
+    let Some(v) = metadata.get(IOX_QUERY_PRIORITY_HEADER) else {
+        return Ok(None);
+    };
+    let v = v.to_str().map_err(|e| Error::InvalidQueryPriorityHeader {
+        description: e.to_string(),
+    })?;
+    v.parse()
+        .map(Some)
+        .map_err(|description| Error::InvalidQueryPriorityHeader { description })
+}
+
 /// Retrieve the authorization token associated with the request.
 fn get_flight_authz(metadata: &MetadataMap) -> Option<Vec<u8>> {
     let val = metadata.get("authorization")?.as_ref();
@@ -796,6 +913,30 @@ fn flightsql_permissions(namespace_name: &str, cmd: &FlightSQLCommand) -> Vec<au
     vec![authz::Permission::ResourceAction(resource, action)]
 }
 
+/// The permissions required to cancel a query running against
+/// `namespace_name`, either with the `CancelQuery` action or an InfluxQL
+/// `KILL QUERY` statement.
+fn cancel_query_permissions(namespace_name: &str) -> Vec<authz::Permission> {
+    vec![authz::Permission::ResourceAction(
+        authz::Resource::Database(namespace_name.to_string()),
+        authz::Action::Write,
+    )]
+}
+
+/// Returns true if the InfluxQL `query` contains a `KILL QUERY` statement.
+///
+/// Queries that fail to parse are authorized as reads, and rejected by the
+/// planner.
+fn is_kill_query(query: &str) -> bool {
+    parse_statements(query)
+        .map(|statements| {
+            statements
+                .iter()
+                .any(|s| matches!(s, Statement::KillQuery(_)))
+        })
+        .unwrap_or(false)
+}
+
 /// Wrapper over a FlightDataEncodeStream that adds IOx specfic
 /// metadata and records completion
 struct GetStream {
@@ -975,6 +1116,32 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_get_query_priority() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(get_query_priority(&metadata).unwrap(), None);
+
+        metadata.insert(
+            IOX_QUERY_PRIORITY_HEADER,
+            MetadataValue::from_static("Interactive"),
+        );
+        assert_eq!(
+            get_query_priority(&metadata).unwrap(),
+            Some(QueryPriority::Interactive)
+        );
+
+        metadata.insert(
+            IOX_QUERY_PRIORITY_HEADER,
+            MetadataValue::from_static("urgent"),
+        );
+        let err = get_query_priority(&metadata).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid 'iox-query-priority' header in request: invalid query priority 'urgent'"
+        );
+        assert_eq!(err.into_status().code(), tonic::Code::InvalidArgument);
+    }
+
     #[tokio::test]
     async fn test_query_semaphore() {
         let semaphore_size = 2;
@@ -1147,6 +1314,11 @@ mod tests {
             match token {
                 Some(b"GOOD") => Ok(perms.to_vec()),
                 Some(b"BAD") => Ok(vec![]),
+                Some(b"READER") => Ok(perms
+                    .iter()
+                    .filter(|p| matches!(p, Permission::ResourceAction(_, authz::Action::Read)))
+                    .cloned()
+                    .collect()),
                 Some(b"UGLY") => Err(authz::Error::verification("test", "test error")),
                 Some(_) => panic!("unexpected token"),
                 None => Err(authz::Error::NoToken),
@@ -1205,6 +1377,13 @@ mod tests {
             )
         }
 
+        fn kill_query_request(authorization: &'static str) -> tonic::Request<arrow_flight::Ticket> {
+            request(
+                RunQuery::InfluxQL("KILL QUERY 42".to_string()),
+                authorization,
+            )
+        }
+
         fn flightsql_request(authorization: &'static str) -> tonic::Request<arrow_flight::Ticket> {
             request(
                 RunQuery::FlightSQL(FlightSQLCommand::CommandGetCatalogs(
@@ -1240,6 +1419,20 @@ mod tests {
         .await;
         assert_code(&svc, tonic::Code::Internal, influxql_request("Bearer UGLY")).await;
 
+        assert_code(&svc, tonic::Code::Ok, sql_request("Bearer READER")).await;
+        assert_code(
+            &svc,
+            tonic::Code::InvalidArgument, // no such query id
+            kill_query_request("Bearer GOOD"),
+        )
+        .await;
+        assert_code(
+            &svc,
+            tonic::Code::PermissionDenied,
+            kill_query_request("Bearer READER"),
+        )
+        .await;
+
         assert_code(&svc, tonic::Code::Unauthenticated, flightsql_request("")).await;
         assert_code(&svc, tonic::Code::Ok, flightsql_request("Bearer GOOD")).await;
         assert_code(
@@ -1300,4 +1493,50 @@ mod tests {
         assert_code(&svc, tonic::Code::PermissionDenied, request("Bearer BAD")).await;
         assert_code(&svc, tonic::Code::Internal, request("Bearer UGLY")).await;
     }
+
+    #[tokio::test]
+    async fn cancel_query_authz() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.clone().db_or_create("bananas").await;
+
+        let svc = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Some(Arc::new(MockAuthorizer {})),
+        };
+
+        async fn assert_code(
+            svc: &FlightService<TestDatabaseStore>,
+            want: tonic::Code,
+            authorization: &'static str,
+        ) {
+            let body = proto::CancelQuery {
+                namespace_name: "bananas".to_string(),
+                query_id: 42,
+            }
+            .encode_to_vec();
+            let mut req = tonic::Request::new(Action {
+                r#type: CANCEL_QUERY_ACTION_TYPE.to_string(),
+                body: body.into(),
+            });
+            if !authorization.is_empty() {
+                req.metadata_mut().insert(
+                    MetadataKey::from_static("authorization"),
+                    MetadataValue::from_static(authorization),
+                );
+            }
+
+            let got = match svc.do_action(req).await {
+                Ok(_) => tonic::Code::Ok,
+                Err(e) => e.code(),
+            };
+            assert_eq!(want, got);
+        }
+
+        assert_code(&svc, tonic::Code::Unauthenticated, "").await;
+        // The caller may cancel queries, but there is no such query.
+        assert_code(&svc, tonic::Code::NotFound, "Bearer GOOD").await;
+        // Read permission does not allow cancelling queries.
+        assert_code(&svc, tonic::Code::PermissionDenied, "Bearer READER").await;
+        assert_code(&svc, tonic::Code::PermissionDenied, "Bearer BAD").await;
+    }
 }
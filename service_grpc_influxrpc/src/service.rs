@@ -13,7 +13,7 @@ use crate::{
     response_chunking::ChunkReadResponses,
     StorageService,
 };
-use data_types::NamespaceName;
+use data_types::{NamespaceName, QueryPriority};
 use datafusion::error::DataFusionError;
 use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
 use generated_types::{
@@ -59,11 +59,22 @@ use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 /// this size (there's a bit of additional encoding overhead on top of that, but that should be OK).
 const MAX_READ_RESPONSE_SIZE: usize = 4194304 - 100_000; // 4MB - <wiggle room>
 
+/// Priority of metadata queries (tag keys, tag values, measurement names and fields).
+///
+/// These usually back UI elements like query builders and are expected to answer quickly.
+const METADATA_QUERY_PRIORITY: QueryPriority = QueryPriority::Interactive;
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("Namespace not found: {}", db_name))]
     NamespaceNotFound { db_name: String },
 
+    #[snafu(display("Error starting query in namespace '{}': {}", db_name, source))]
+    StartingQuery {
+        db_name: String,
+        source: DataFusionError,
+    },
+
     #[snafu(display("Error listing tables in namespace '{}': {}", db_name, source))]
     ListingTables {
         db_name: String,
@@ -208,7 +219,8 @@ impl Error {
 
         let code = match self {
             Self::NamespaceNotFound { .. } => tonic::Code::NotFound,
-            Self::ListingTables { source, .. }
+            Self::StartingQuery { source, .. }
+            | Self::ListingTables { source, .. }
             | Self::ListingColumns { source, .. }
             | Self::ListingFields { source, .. }
             | Self::PlanningFilteringSeries { source, .. }
@@ -391,7 +403,9 @@ where
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "read_filter", defer_json(&req));
+        let query_completed_token = db
+            .record_query(&ctx, "read_filter", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let frames = read_filter_impl(Arc::clone(&db), db_name, req, &ctx)
             .await?
@@ -439,7 +453,9 @@ where
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "read_group", defer_json(&req));
+        let query_completed_token = db
+            .record_query(&ctx, "read_group", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let ReadGroupRequest {
             read_source: _read_source,
@@ -515,8 +531,9 @@ where
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let query_completed_token =
-            db.record_query(&ctx, "read_window_aggregate", defer_json(&req));
+        let query_completed_token = db
+            .record_query(&ctx, "read_window_aggregate", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let ReadWindowAggregateRequest {
             read_source: _read_source,
@@ -592,8 +609,10 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "tag_keys", defer_json(&req));
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(&ctx, "tag_keys", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let TagKeysRequest {
             tags_source: _tag_source,
@@ -660,8 +679,10 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "tag_values", defer_json(&req));
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(&ctx, "tag_values", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let TagValuesRequest {
             tags_source: _tag_source,
@@ -760,12 +781,14 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(
-            &ctx,
-            "tag_values_grouped_by_measurement_and_tag_key",
-            defer_json(&req),
-        );
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(
+                &ctx,
+                "tag_values_grouped_by_measurement_and_tag_key",
+                defer_json(&req),
+            )
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let results =
             tag_values_grouped_by_measurement_and_tag_key_impl(Arc::clone(&db), db_name, req, &ctx)
@@ -869,8 +892,10 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "measurement_names", defer_json(&req));
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(&ctx, "measurement_names", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let MeasurementNamesRequest {
             source: _source,
@@ -926,8 +951,10 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "measurement_tag_keys", defer_json(&req));
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(&ctx, "measurement_tag_keys", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let MeasurementTagKeysRequest {
             source: _source,
@@ -994,9 +1021,10 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token =
-            db.record_query(&ctx, "measurement_tag_values", defer_json(&req));
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(&ctx, "measurement_tag_values", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let MeasurementTagValuesRequest {
             source: _source,
@@ -1064,8 +1092,10 @@ where
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token = db.record_query(&ctx, "measurement_fields", defer_json(&req));
+        let ctx = db.new_query_context_with_priority(span_ctx, Some(METADATA_QUERY_PRIORITY));
+        let query_completed_token = db
+            .record_query(&ctx, "measurement_fields", defer_json(&req))
+            .context(StartingQuerySnafu { db_name: &db_name })?;
 
         let MeasurementFieldsRequest {
             source: _source,
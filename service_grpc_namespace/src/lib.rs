@@ -1,7 +1,7 @@
 //! Implementation of the namespace gRPC service
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use data_types::{Namespace as CatalogNamespace, NamespaceName, QueryPoolId, TopicId};
+use data_types::{Namespace as CatalogNamespace, NamespaceName, QueryPoolId, Timestamp, TopicId};
 use generated_types::influxdata::iox::namespace::v1::{
     update_namespace_service_protection_limit_request::LimitUpdate, *,
 };
@@ -9,6 +9,9 @@ use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::{debug, info, warn};
 use tonic::{Request, Response, Status};
 
+/// How long a deleted namespace can be undeleted unless configured otherwise.
+pub const DEFAULT_DELETE_GRACE_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
 /// Implementation of the gRPC namespace service
 #[derive(Debug)]
 pub struct NamespaceService {
@@ -16,6 +19,8 @@ pub struct NamespaceService {
     catalog: Arc<dyn Catalog>,
     topic_id: Option<TopicId>,
     query_id: Option<QueryPoolId>,
+    /// How long after their deletion namespaces can be undeleted.
+    delete_grace_period: Duration,
 }
 
 impl NamespaceService {
@@ -28,8 +33,18 @@ impl NamespaceService {
             catalog,
             topic_id,
             query_id,
+            delete_grace_period: DEFAULT_DELETE_GRACE_PERIOD,
         }
     }
+
+    /// Only allow undeleting namespaces that were deleted less than `delete_grace_period` ago.
+    ///
+    /// This should match the grace period of the garbage collector, which purges namespaces
+    /// afterwards.
+    pub fn with_delete_grace_period(mut self, delete_grace_period: Duration) -> Self {
+        self.delete_grace_period = delete_grace_period;
+        self
+    }
 }
 
 #[tonic::async_trait]
@@ -123,6 +138,59 @@ impl namespace_service_server::NamespaceService for NamespaceService {
         Ok(Response::new(Default::default()))
     }
 
+    async fn undelete_namespace(
+        &self,
+        request: Request<UndeleteNamespaceRequest>,
+    ) -> Result<Response<UndeleteNamespaceResponse>, Status> {
+        let namespace_name = request.into_inner().name;
+        let deleted_after =
+            Timestamp::from(self.catalog.time_provider().now() - self.delete_grace_period);
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .undelete(&namespace_name, deleted_after)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %namespace_name, "failed to undelete namespace");
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            "undeleted namespace"
+        );
+
+        Ok(Response::new(UndeleteNamespaceResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
+
+    async fn purge_namespace(
+        &self,
+        request: Request<PurgeNamespaceRequest>,
+    ) -> Result<Response<PurgeNamespaceResponse>, Status> {
+        let namespace_name = request.into_inner().name;
+
+        self.catalog
+            .repositories()
+            .await
+            .namespaces()
+            .purge(&namespace_name)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %namespace_name, "failed to purge namespace");
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(namespace_name, "purged namespace");
+
+        Ok(Response::new(Default::default()))
+    }
+
     async fn update_namespace_retention(
         &self,
         request: Request<UpdateNamespaceRetentionRequest>,
@@ -221,6 +289,70 @@ impl namespace_service_server::NamespaceService for NamespaceService {
                         status_from_catalog_namespace_error(e)
                     })
             }
+            Some(LimitUpdate::MaxLinesPerSecond(n)) => {
+                let n = map_rate_limit(n)?;
+                repos
+                    .namespaces()
+                    .update_lines_per_second_limit(&namespace_name, n)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            lines_per_second_limit = ?n,
+                            "failed to update lines per second limit for namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })
+            }
+            Some(LimitUpdate::MaxBatchBytesPerSecond(n)) => {
+                let n = map_rate_limit(n)?;
+                repos
+                    .namespaces()
+                    .update_batch_bytes_per_second_limit(&namespace_name, n)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            batch_bytes_per_second_limit = ?n,
+                            "failed to update batch bytes per second limit for namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })
+            }
+            Some(LimitUpdate::MaxConcurrentQueries(n)) => {
+                let n = map_query_quota(n.into())?.map(|n| n as i32);
+                repos
+                    .namespaces()
+                    .update_concurrent_queries_limit(&namespace_name, n)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            concurrent_queries_limit = ?n,
+                            "failed to update concurrent queries limit for namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })
+            }
+            Some(LimitUpdate::MaxQueryMemoryBytes(n)) => {
+                let n = map_query_quota(n)?;
+                repos
+                    .namespaces()
+                    .update_query_memory_limit(&namespace_name, n)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            query_memory_limit = ?n,
+                            "failed to update query memory limit for namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })
+            }
             None => Err(Status::invalid_argument(
                 "unsupported service protection limit change requested",
             )),
@@ -231,6 +363,10 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             namespace_id = %namespace.id,
             max_tables = %namespace.max_tables,
             max_columns_per_table = %namespace.max_columns_per_table,
+            max_lines_per_second = ?namespace.max_lines_per_second,
+            max_batch_bytes_per_second = ?namespace.max_batch_bytes_per_second,
+            max_concurrent_queries = ?namespace.max_concurrent_queries,
+            max_query_memory_bytes = ?namespace.max_query_memory_bytes,
             "updated namespace service protection limits",
         );
 
@@ -240,6 +376,92 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             },
         ))
     }
+
+    async fn update_namespace_type_coercion_policy(
+        &self,
+        request: Request<UpdateNamespaceTypeCoercionPolicyRequest>,
+    ) -> Result<Response<UpdateNamespaceTypeCoercionPolicyResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateNamespaceTypeCoercionPolicyRequest {
+            name: namespace_name,
+            policy,
+        } = request.into_inner();
+
+        let policy = TypeCoercionPolicy::from_i32(policy)
+            .and_then(|p| data_types::TypeCoercionPolicy::try_from(p).ok())
+            .ok_or_else(|| Status::invalid_argument("invalid type coercion policy"))?;
+
+        debug!(%namespace_name, %policy, "updating namespace type coercion policy");
+
+        let namespace = repos
+            .namespaces()
+            .update_type_coercion_policy(&namespace_name, policy)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    %policy,
+                    "failed to update type coercion policy for namespace",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            %policy,
+            "updated namespace type coercion policy",
+        );
+
+        Ok(Response::new(UpdateNamespaceTypeCoercionPolicyResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
+
+    async fn update_namespace_default_query_priority(
+        &self,
+        request: Request<UpdateNamespaceDefaultQueryPriorityRequest>,
+    ) -> Result<Response<UpdateNamespaceDefaultQueryPriorityResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateNamespaceDefaultQueryPriorityRequest {
+            name: namespace_name,
+            priority,
+        } = request.into_inner();
+
+        let priority = QueryPriority::from_i32(priority)
+            .and_then(|p| data_types::QueryPriority::try_from(p).ok())
+            .ok_or_else(|| Status::invalid_argument("invalid query priority"))?;
+
+        debug!(%namespace_name, %priority, "updating namespace default query priority");
+
+        let namespace = repos
+            .namespaces()
+            .update_default_query_priority(&namespace_name, priority)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    %priority,
+                    "failed to update default query priority for namespace",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            %priority,
+            "updated namespace default query priority",
+        );
+
+        Ok(Response::new(UpdateNamespaceDefaultQueryPriorityResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
 }
 
 fn namespace_to_proto(namespace: CatalogNamespace) -> Namespace {
@@ -249,6 +471,12 @@ fn namespace_to_proto(namespace: CatalogNamespace) -> Namespace {
         retention_period_ns: namespace.retention_period_ns,
         max_tables: namespace.max_tables,
         max_columns_per_table: namespace.max_columns_per_table,
+        max_lines_per_second: namespace.max_lines_per_second,
+        max_batch_bytes_per_second: namespace.max_batch_bytes_per_second,
+        max_concurrent_queries: namespace.max_concurrent_queries,
+        max_query_memory_bytes: namespace.max_query_memory_bytes,
+        type_coercion_policy: TypeCoercionPolicy::from(namespace.type_coercion_policy).into(),
+        default_query_priority: QueryPriority::from(namespace.default_query_priority).into(),
     }
 }
 
@@ -260,6 +488,13 @@ fn namespace_to_create_response_proto(namespace: CatalogNamespace) -> CreateName
             retention_period_ns: namespace.retention_period_ns,
             max_tables: namespace.max_tables,
             max_columns_per_table: namespace.max_columns_per_table,
+            max_lines_per_second: namespace.max_lines_per_second,
+            max_batch_bytes_per_second: namespace.max_batch_bytes_per_second,
+            max_concurrent_queries: namespace.max_concurrent_queries,
+            max_query_memory_bytes: namespace.max_query_memory_bytes,
+            type_coercion_policy: TypeCoercionPolicy::from(namespace.type_coercion_policy).into(),
+            default_query_priority: QueryPriority::from(namespace.default_query_priority).into(),
+            default_query_priority: QueryPriority::from(namespace.default_query_priority).into(),
         }),
     }
 }
@@ -281,9 +516,37 @@ fn map_retention_period(v: Option<i64>) -> Result<Option<i64>, Status> {
     }
 }
 
+/// Map a user-submitted write rate limit to the correct internal encoding.
+///
+/// 0 is mapped to [`None`], indicating no limit.
+///
+/// Negative rates are rejected with an error.
+fn map_rate_limit(v: i64) -> Result<Option<i64>, Status> {
+    match v {
+        0 => Ok(None),
+        v @ 1.. => Ok(Some(v)),
+        _ => Err(Status::invalid_argument("invalid negative rate limit")),
+    }
+}
+
+/// Map a user-submitted query quota to the correct internal encoding.
+///
+/// 0 is mapped to [`None`], indicating the namespace is only bound by the
+/// global querier limits.
+///
+/// Negative quotas are rejected with an error.
+fn map_query_quota(v: i64) -> Result<Option<i64>, Status> {
+    match v {
+        0 => Ok(None),
+        v @ 1.. => Ok(Some(v)),
+        _ => Err(Status::invalid_argument("invalid negative query quota")),
+    }
+}
+
 fn status_from_catalog_namespace_error(err: iox_catalog::interface::Error) -> Status {
     match err {
-        iox_catalog::interface::Error::NamespaceNotFoundByName { .. } => {
+        iox_catalog::interface::Error::NamespaceNotFoundByName { .. }
+        | iox_catalog::interface::Error::SoftDeletedNamespaceNotFound { .. } => {
             Status::not_found(err.to_string())
         }
         _ => Status::internal(err.to_string()),
@@ -473,6 +736,159 @@ mod tests {
         assert_eq!(updated_ns.id, created_ns.id);
         assert_eq!(updated_ns.max_tables, want_max_tables);
         assert_eq!(updated_ns.max_columns_per_table, want_max_columns_per_table);
+        assert_eq!(updated_ns.max_lines_per_second, None);
+
+        // Set, then remove, the write rate limits
+        let updated_ns = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxLinesPerSecond(1_000)),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_lines_per_second, Some(1_000));
+        assert_eq!(updated_ns.max_batch_bytes_per_second, None);
+        let updated_ns = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxBatchBytesPerSecond(4_096)),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_lines_per_second, Some(1_000));
+        assert_eq!(updated_ns.max_batch_bytes_per_second, Some(4_096));
+        let updated_ns = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxLinesPerSecond(0)),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_lines_per_second, None);
+        assert_eq!(updated_ns.max_batch_bytes_per_second, Some(4_096));
+
+        // Set, then remove, the query quotas
+        let updated_ns = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxConcurrentQueries(4)),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_concurrent_queries, Some(4));
+        assert_eq!(updated_ns.max_query_memory_bytes, None);
+        let updated_ns = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxQueryMemoryBytes(1 << 30)),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_concurrent_queries, Some(4));
+        assert_eq!(updated_ns.max_query_memory_bytes, Some(1 << 30));
+        let updated_ns = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxConcurrentQueries(0)),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_concurrent_queries, None);
+        assert_eq!(updated_ns.max_query_memory_bytes, Some(1 << 30));
+
+        // Change the type coercion policy, rejecting unspecified policies
+        assert_eq!(
+            updated_ns.type_coercion_policy,
+            TypeCoercionPolicy::Strict as i32
+        );
+        let updated_ns = handler
+            .update_namespace_type_coercion_policy(Request::new(
+                UpdateNamespaceTypeCoercionPolicyRequest {
+                    name: NS_NAME.to_string(),
+                    policy: TypeCoercionPolicy::Widen.into(),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(
+            updated_ns.type_coercion_policy,
+            TypeCoercionPolicy::Widen as i32
+        );
+        let status = handler
+            .update_namespace_type_coercion_policy(Request::new(
+                UpdateNamespaceTypeCoercionPolicyRequest {
+                    name: NS_NAME.to_string(),
+                    policy: TypeCoercionPolicy::Unspecified.into(),
+                },
+            ))
+            .await
+            .expect_err("unspecified policy should fail");
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        // Change the default query priority, rejecting unspecified priorities
+        assert_eq!(
+            updated_ns.default_query_priority,
+            QueryPriority::Normal as i32
+        );
+        let updated_ns = handler
+            .update_namespace_default_query_priority(Request::new(
+                UpdateNamespaceDefaultQueryPriorityRequest {
+                    name: NS_NAME.to_string(),
+                    priority: QueryPriority::Batch.into(),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(
+            updated_ns.default_query_priority,
+            QueryPriority::Batch as i32
+        );
+        let status = handler
+            .update_namespace_default_query_priority(Request::new(
+                UpdateNamespaceDefaultQueryPriorityRequest {
+                    name: NS_NAME.to_string(),
+                    priority: QueryPriority::Unspecified.into(),
+                },
+            ))
+            .await
+            .expect_err("unspecified priority should fail");
+        assert_eq!(status.code(), Code::InvalidArgument);
 
         // Deleting the namespace should cause it to disappear
         handler
@@ -492,6 +908,60 @@ mod tests {
                 .namespaces;
             assert_matches!(current.as_slice(), []);
         }
+
+        // Undeleting it within the grace period brings it back
+        let undeleted_ns = handler
+            .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect("must undelete")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(undeleted_ns.id, created_ns.id);
+        {
+            let current = handler
+                .get_namespaces(Request::new(Default::default()))
+                .await
+                .expect("must return namespaces")
+                .into_inner()
+                .namespaces;
+            assert_matches!(current.as_slice(), [ns] => {
+                assert_eq!(ns, &undeleted_ns);
+            })
+        }
+
+        // Only deleted namespaces can be purged
+        let status = handler
+            .purge_namespace(Request::new(PurgeNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect_err("purging a live namespace must fail");
+        assert_eq!(status.code(), Code::NotFound);
+
+        handler
+            .delete_namespace(Request::new(DeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect("must delete");
+        handler
+            .purge_namespace(Request::new(PurgeNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect("must purge");
+
+        // A purged namespace is gone for good
+        let status = handler
+            .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect_err("undeleting a purged namespace must fail");
+        assert_eq!(status.code(), Code::NotFound);
     }
 
     #[tokio::test]
@@ -556,6 +1026,30 @@ mod tests {
                 "invalid namespace update request for max columns per table limit should fail",
             );
         assert_eq!(status.code(), Code::InvalidArgument);
+
+        // Negative rate limits are rejected.
+        let status = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxBatchBytesPerSecond(-1)),
+                },
+            ))
+            .await
+            .expect_err("negative rate limit should fail");
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        // ...as are negative query quotas.
+        let status = handler
+            .update_namespace_service_protection_limit(Request::new(
+                UpdateNamespaceServiceProtectionLimitRequest {
+                    name: NS_NAME.to_string(),
+                    limit_update: Some(LimitUpdate::MaxConcurrentQueries(-1)),
+                },
+            ))
+            .await
+            .expect_err("negative query quota should fail");
+        assert_eq!(status.code(), Code::InvalidArgument);
     }
 
     macro_rules! test_create_namespace_name {
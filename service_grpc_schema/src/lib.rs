@@ -1,22 +1,67 @@
 //! Implementation of the schema gRPC service
 
-use std::{ops::DerefMut, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    ops::DerefMut,
+    sync::Arc,
+};
 
+use data_types::ColumnType;
 use generated_types::influxdata::iox::schema::v1::*;
-use iox_catalog::interface::{get_schema_by_name, Catalog, SoftDeletedRows};
-use observability_deps::tracing::warn;
+use iox_catalog::interface::{
+    get_schema_by_name, Catalog, Error as CatalogError, RepoCollection, SoftDeletedRows,
+};
+use observability_deps::tracing::{info, warn};
+use schema::TIME_COLUMN_NAME;
 use tonic::{Request, Response, Status};
 
+/// Observer that is notified with the new schema of a namespace after a table was created, or a
+/// table or column of it was deleted via the [`SchemaService`].
+pub trait SchemaChangeObserver: Debug + Send + Sync {
+    /// Called with the schema of `namespace` as read from the catalog after the change.
+    fn schema_changed(&self, namespace: &str, schema: data_types::NamespaceSchema);
+}
+
 /// Implementation of the gRPC schema service
 #[derive(Debug)]
 pub struct SchemaService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// Notified about created tables and deleted tables and columns, if any.
+    observer: Option<Arc<dyn SchemaChangeObserver>>,
 }
 
 impl SchemaService {
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            observer: None,
+        }
+    }
+
+    /// Notify `observer` about schema changes made through this service.
+    pub fn with_observer(self, observer: Arc<dyn SchemaChangeObserver>) -> Self {
+        Self {
+            observer: Some(observer),
+            ..self
+        }
+    }
+
+    /// Pass the current schema of `namespace` to the observer, if any.
+    async fn notify_observer(&self, namespace: &str, repos: &mut dyn RepoCollection) {
+        let observer = match &self.observer {
+            Some(observer) => observer,
+            None => return,
+        };
+
+        match get_schema_by_name(namespace, repos, SoftDeletedRows::ExcludeDeleted).await {
+            Ok(schema) => observer.schema_changed(namespace, schema),
+            Err(e) => {
+                warn!(error=%e, %namespace, "failed to retrieve namespace schema after change")
+            }
+        }
     }
 }
 
@@ -42,6 +87,186 @@ impl schema_service_server::SchemaService for SchemaService {
         .map(Arc::new)?;
         Ok(Response::new(schema_to_proto(schema)))
     }
+
+    async fn create_table(
+        &self,
+        request: Request<CreateTableRequest>,
+    ) -> Result<Response<CreateTableResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        if req.table.is_empty() {
+            return Err(Status::invalid_argument("table name must not be empty"));
+        }
+        let primary_key = primary_key_from_tags(req.primary_key)?;
+
+        let namespace = get_namespace(repos.deref_mut(), &req.namespace).await?;
+        let table = repos
+            .tables()
+            .create(&req.table, namespace.id, &primary_key)
+            .await
+            .map_err(|e| match e {
+                CatalogError::NameExists { .. } => Status::already_exists(e.to_string()),
+                CatalogError::TableCreateLimitError { .. } => {
+                    Status::resource_exhausted(e.to_string())
+                }
+                _ => Status::unknown(e.to_string()),
+            })?;
+
+        // Create the primary key columns so that writing them as fields is a
+        // schema conflict.
+        let columns = primary_key
+            .iter()
+            .map(|name| {
+                let column_type = match name.as_str() {
+                    TIME_COLUMN_NAME => ColumnType::Time,
+                    _ => ColumnType::Tag,
+                };
+                (name.as_str(), column_type)
+            })
+            .collect::<HashMap<_, _>>();
+        let columns = repos
+            .columns()
+            .create_or_get_many_unchecked(table.id, columns)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?;
+        info!(
+            %req.namespace,
+            %req.table,
+            table_id=%table.id,
+            primary_key=%primary_key.join(","),
+            "created table"
+        );
+
+        let mut schema = data_types::TableSchema::new_for_table(&table);
+        columns.iter().for_each(|c| schema.add_column(c));
+
+        self.notify_observer(&req.namespace, repos.deref_mut())
+            .await;
+
+        Ok(Response::new(CreateTableResponse {
+            table: Some(table_schema_to_proto(&schema)),
+        }))
+    }
+
+    async fn delete_table(
+        &self,
+        request: Request<DeleteTableRequest>,
+    ) -> Result<Response<DeleteTableResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        let table = get_table(repos.deref_mut(), &req.namespace, &req.table).await?;
+        repos
+            .tables()
+            .soft_delete(table.id)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?;
+        info!(%req.namespace, %req.table, table_id=%table.id, "soft-deleted table");
+
+        self.notify_observer(&req.namespace, repos.deref_mut())
+            .await;
+
+        Ok(Response::new(DeleteTableResponse {}))
+    }
+
+    async fn delete_column(
+        &self,
+        request: Request<DeleteColumnRequest>,
+    ) -> Result<Response<DeleteColumnResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        let table = get_table(repos.deref_mut(), &req.namespace, &req.table).await?;
+        let column = repos
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .into_iter()
+            .find(|c| c.name == req.column)
+            .ok_or_else(|| Status::not_found(format!("Column {} not found", req.column)))?;
+        if column.column_type == ColumnType::Time {
+            return Err(Status::invalid_argument(
+                "the time column cannot be deleted",
+            ));
+        }
+
+        repos
+            .columns()
+            .soft_delete(column.id)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?;
+        info!(
+            %req.namespace,
+            %req.table,
+            %req.column,
+            column_id=%column.id,
+            "soft-deleted column"
+        );
+
+        self.notify_observer(&req.namespace, repos.deref_mut())
+            .await;
+
+        Ok(Response::new(DeleteColumnResponse {}))
+    }
+}
+
+/// Validate the primary key `tags` of a new table, returning them followed by the time column.
+fn primary_key_from_tags(tags: Vec<String>) -> Result<Vec<String>, Status> {
+    if tags.is_empty() {
+        return Err(Status::invalid_argument(
+            "the primary key must contain at least one tag",
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for tag in &tags {
+        if tag.is_empty() || tag == TIME_COLUMN_NAME {
+            return Err(Status::invalid_argument(format!(
+                "invalid primary key column {tag:?}, the time column is always appended"
+            )));
+        }
+        if !seen.insert(tag.as_str()) {
+            return Err(Status::invalid_argument(format!(
+                "duplicate primary key column {tag}"
+            )));
+        }
+    }
+
+    Ok(tags
+        .into_iter()
+        .chain(std::iter::once(TIME_COLUMN_NAME.to_string()))
+        .collect())
+}
+
+/// Look up a namespace that is not deleted by name.
+async fn get_namespace(
+    repos: &mut dyn RepoCollection,
+    namespace_name: &str,
+) -> Result<data_types::Namespace, Status> {
+    repos
+        .namespaces()
+        .get_by_name(namespace_name, SoftDeletedRows::ExcludeDeleted)
+        .await
+        .map_err(|e| Status::unknown(e.to_string()))?
+        .ok_or_else(|| Status::not_found(format!("Namespace {namespace_name} not found")))
+}
+
+/// Look up a table that is not deleted by namespace and table name.
+async fn get_table(
+    repos: &mut dyn RepoCollection,
+    namespace_name: &str,
+    table_name: &str,
+) -> Result<data_types::Table, Status> {
+    let namespace = get_namespace(repos, namespace_name).await?;
+
+    repos
+        .tables()
+        .get_by_namespace_and_name(namespace.id, table_name)
+        .await
+        .map_err(|e| Status::unknown(e.to_string()))?
+        .ok_or_else(|| Status::not_found(format!("Table {table_name} not found")))
 }
 
 fn schema_to_proto(schema: Arc<data_types::NamespaceSchema>) -> GetSchemaResponse {
@@ -53,40 +278,39 @@ fn schema_to_proto(schema: Arc<data_types::NamespaceSchema>) -> GetSchemaRespons
             tables: schema
                 .tables
                 .iter()
-                .map(|(name, t)| {
-                    (
-                        name.clone(),
-                        TableSchema {
-                            id: t.id.get(),
-                            columns: t
-                                .columns
-                                .iter()
-                                .map(|(name, c)| {
-                                    (
-                                        name.clone(),
-                                        ColumnSchema {
-                                            id: c.id.get(),
-                                            column_type: c.column_type as i32,
-                                        },
-                                    )
-                                })
-                                .collect(),
-                        },
-                    )
-                })
+                .map(|(name, t)| (name.clone(), table_schema_to_proto(t)))
                 .collect(),
         }),
     };
     response
 }
 
+fn table_schema_to_proto(schema: &data_types::TableSchema) -> TableSchema {
+    TableSchema {
+        id: schema.id.get(),
+        columns: schema
+            .columns
+            .iter()
+            .map(|(name, c)| {
+                (
+                    name.clone(),
+                    ColumnSchema {
+                        id: c.id.get(),
+                        column_type: c.column_type as i32,
+                    },
+                )
+            })
+            .collect(),
+        primary_key: schema.primary_key.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use data_types::ColumnType;
     use generated_types::influxdata::iox::schema::v1::schema_service_server::SchemaService;
     use iox_catalog::mem::MemCatalog;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
     #[tokio::test]
     async fn test_schema() {
@@ -142,4 +366,206 @@ mod tests {
             vec![&"schema_test_column".to_string()]
         );
     }
+
+    #[derive(Debug, Default)]
+    struct MockObserver {
+        schemas: Mutex<Vec<(String, data_types::NamespaceSchema)>>,
+    }
+
+    impl SchemaChangeObserver for MockObserver {
+        fn schema_changed(&self, namespace: &str, schema: data_types::NamespaceSchema) {
+            self.schemas
+                .lock()
+                .unwrap()
+                .push((namespace.to_string(), schema));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_table_and_column() {
+        let catalog = {
+            let metrics = Arc::new(metric::Registry::default());
+            let catalog = Arc::new(MemCatalog::new(metrics));
+            let mut repos = catalog.repositories().await;
+            let topic = repos.topics().create_or_get("franz").await.unwrap();
+            let pool = repos.query_pools().create_or_get("franz").await.unwrap();
+            let namespace = repos
+                .namespaces()
+                .create("namespace_delete_test", None, topic.id, pool.id)
+                .await
+                .unwrap();
+            for table_name in ["t1", "t2"] {
+                let table = repos
+                    .tables()
+                    .create_or_get(table_name, namespace.id)
+                    .await
+                    .unwrap();
+                repos
+                    .columns()
+                    .create_or_get("tag", table.id, ColumnType::Tag)
+                    .await
+                    .unwrap();
+                repos
+                    .columns()
+                    .create_or_get("time", table.id, ColumnType::Time)
+                    .await
+                    .unwrap();
+            }
+            Arc::clone(&catalog)
+        };
+
+        let observer = Arc::new(MockObserver::default());
+        let grpc = super::SchemaService::new(catalog)
+            .with_observer(Arc::clone(&observer) as Arc<dyn SchemaChangeObserver>);
+
+        grpc.delete_table(Request::new(DeleteTableRequest {
+            namespace: "namespace_delete_test".to_string(),
+            table: "t1".to_string(),
+        }))
+        .await
+        .expect("rpc request should succeed");
+        grpc.delete_column(Request::new(DeleteColumnRequest {
+            namespace: "namespace_delete_test".to_string(),
+            table: "t2".to_string(),
+            column: "tag".to_string(),
+        }))
+        .await
+        .expect("rpc request should succeed");
+
+        // already deleted
+        let status = grpc
+            .delete_table(Request::new(DeleteTableRequest {
+                namespace: "namespace_delete_test".to_string(),
+                table: "t1".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        // the time column must stay
+        let status = grpc
+            .delete_column(Request::new(DeleteColumnRequest {
+                namespace: "namespace_delete_test".to_string(),
+                table: "t2".to_string(),
+                column: "time".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let schema = grpc
+            .get_schema(Request::new(GetSchemaRequest {
+                namespace: "namespace_delete_test".to_string(),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .schema
+            .expect("schema should be Some()");
+        assert_eq!(schema.tables.keys().collect::<Vec<_>>(), vec!["t2"]);
+        assert_eq!(
+            schema.tables["t2"].columns.keys().collect::<Vec<_>>(),
+            vec!["time"]
+        );
+
+        let schemas = observer.schemas.lock().unwrap();
+        assert_eq!(schemas.len(), 2);
+        assert!(schemas
+            .iter()
+            .all(|(namespace, _)| namespace == "namespace_delete_test"));
+        assert_eq!(schemas[1].1.tables.len(), 1);
+        assert_eq!(schemas[1].1.tables["t2"].columns.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_table() {
+        let catalog = {
+            let metrics = Arc::new(metric::Registry::default());
+            let catalog = Arc::new(MemCatalog::new(metrics));
+            let mut repos = catalog.repositories().await;
+            let topic = repos.topics().create_or_get("franz").await.unwrap();
+            let pool = repos.query_pools().create_or_get("franz").await.unwrap();
+            repos
+                .namespaces()
+                .create("namespace_create_test", None, topic.id, pool.id)
+                .await
+                .unwrap();
+            Arc::clone(&catalog)
+        };
+
+        let observer = Arc::new(MockObserver::default());
+        let grpc = super::SchemaService::new(catalog)
+            .with_observer(Arc::clone(&observer) as Arc<dyn SchemaChangeObserver>);
+
+        let request = |table: &str, primary_key: &[&str]| {
+            Request::new(CreateTableRequest {
+                namespace: "namespace_create_test".to_string(),
+                table: table.to_string(),
+                primary_key: primary_key.iter().map(ToString::to_string).collect(),
+            })
+        };
+
+        let table = grpc
+            .create_table(request("cpu", &["region", "host"]))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .table
+            .expect("table should be Some()");
+        assert_eq!(table.primary_key, ["region", "host", "time"]);
+        assert_eq!(
+            table.columns.get("region").unwrap().column_type,
+            ColumnType::Tag as i32
+        );
+        assert_eq!(
+            table.columns.get("time").unwrap().column_type,
+            ColumnType::Time as i32
+        );
+
+        // the schema reflects the primary key, and the observer is notified
+        let schema = grpc
+            .get_schema(Request::new(GetSchemaRequest {
+                namespace: "namespace_create_test".to_string(),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .schema
+            .expect("schema should be Some()");
+        assert_eq!(schema.tables["cpu"].primary_key, ["region", "host", "time"]);
+        {
+            let schemas = observer.schemas.lock().unwrap();
+            assert_eq!(schemas.len(), 1);
+            assert_eq!(
+                schemas[0].1.tables["cpu"].primary_key,
+                ["region", "host", "time"]
+            );
+        }
+
+        // the table exists
+        let status = grpc
+            .create_table(request("cpu", &["region"]))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+
+        // invalid primary keys
+        for primary_key in [&[][..], &["a", "a"], &["a", "time"], &[""]] {
+            let status = grpc
+                .create_table(request("mem", primary_key))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+
+        let status = grpc
+            .create_table(Request::new(CreateTableRequest {
+                namespace: "missing".to_string(),
+                table: "mem".to_string(),
+                primary_key: vec!["host".to_string()],
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
 }
@@ -0,0 +1,627 @@
+//! A Prometheus [remote read] HTTP API implementation for the querier.
+//!
+//! Each remote read [`Query`] is converted into an influxrpc `read_filter`
+//! plan over the requested namespace, mapping the Prometheus data model to
+//! IOx as per the Prometheus remote write conversion in the router:
+//!
+//!   * The metric name (the `__name__` label) is the measurement / table name.
+//!   * All other labels are tags, with an absent tag equivalent to an empty
+//!     label value.
+//!   * Sample values are read from the `value` field, and non-numeric values
+//!     are skipped.
+//!
+//! Both the `SAMPLES` and `STREAMED_XOR_CHUNKS` response types are supported.
+//! `STREAMED_XOR_CHUNKS` responses are streamed to the client one series at a
+//! time as the series are read, in query output order rather than sorted by
+//! label set.
+//!
+//! [remote read]: https://prometheus.io/docs/prometheus/latest/querying/remote_read_api/
+//! [`Query`]: generated_types::prometheus::Query
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro
+)]
+
+mod chunk;
+mod query;
+mod response;
+
+use std::sync::Arc;
+
+use authz::{http::AuthorizationHeaderExtension, Action, Authorizer, Permission, Resource};
+use bytes::{Bytes, BytesMut};
+use data_types::{NamespaceName, OrgBucketMappingError};
+use datafusion::error::DataFusionError;
+use futures::{Stream, StreamExt, TryStreamExt};
+use generated_types::prometheus::{read_request::ResponseType, ReadRequest, TimeSeries};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
+use iox_query::{
+    exec::{seriesset::series::Either, ExecutionContextProvider, IOxSessionContext},
+    QueryNamespace,
+};
+use observability_deps::tracing::{debug, info};
+use predicate::rpc_predicate::InfluxRpcPredicate;
+use prost::Message;
+use serde::Deserialize;
+use service_common::{planner::Planner, QueryNamespaceProvider};
+use snafu::{OptionExt, ResultExt, Snafu};
+use trace::{ctx::SpanContext, span::SpanExt};
+
+/// The label holding the metric name of a Prometheus time series.
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// The name of the field the sample values are read from.
+const VALUE_FIELD_NAME: &str = "value";
+
+/// The path of the remote read endpoint.
+pub const PROM_READ_PATH: &str = "/api/v1/prom/read";
+
+/// Errors returned by the [`PromReadService`].
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("not found"))]
+    NotFound,
+
+    #[snafu(display("failed to deserialize query string: {source}"))]
+    InvalidQueryString { source: serde_urlencoded::de::Error },
+
+    #[snafu(display("invalid namespace: {source}"))]
+    InvalidNamespace { source: OrgBucketMappingError },
+
+    #[snafu(display("namespace {namespace} not found"))]
+    NamespaceNotFound { namespace: String },
+
+    #[snafu(display("authorization failure: {source}"))]
+    Authz { source: authz::Error },
+
+    #[snafu(display("client disconnected: {source}"))]
+    ClientHangup { source: hyper::Error },
+
+    #[snafu(display("max request size ({max_bytes} bytes) exceeded"))]
+    RequestSizeExceeded { max_bytes: usize },
+
+    #[snafu(display("unsupported content encoding: {encoding}"))]
+    InvalidContentEncoding { encoding: String },
+
+    #[snafu(display("error decoding snappy payload: {source}"))]
+    InvalidSnappy { source: snap::Error },
+
+    #[snafu(display("failed to decode protobuf read request: {source}"))]
+    DecodeRequest { source: prost::DecodeError },
+
+    #[snafu(display("no supported response type in accepted_response_types"))]
+    UnsupportedResponseType,
+
+    #[snafu(display("label matcher on reserved label name {name:?}"))]
+    InvalidMatcher { name: String },
+
+    #[snafu(display("label matcher {name:?} has unknown type {value}"))]
+    InvalidMatcherType { name: String, value: i32 },
+
+    #[snafu(display("invalid regular expression {pattern:?}: {source}"))]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+
+    #[snafu(display("error executing query: {source}"))]
+    Query { source: DataFusionError },
+
+    #[snafu(display("error encoding snappy response: {source}"))]
+    EncodeResponse { source: snap::Error },
+}
+
+impl Error {
+    /// Convert the error into an appropriate [`StatusCode`] to be returned to
+    /// the end user.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotFound | Self::NamespaceNotFound { .. } => StatusCode::NOT_FOUND,
+            Self::InvalidQueryString { .. }
+            | Self::InvalidNamespace { .. }
+            | Self::ClientHangup { .. }
+            | Self::InvalidSnappy { .. }
+            | Self::DecodeRequest { .. }
+            | Self::UnsupportedResponseType
+            | Self::InvalidMatcher { .. }
+            | Self::InvalidMatcherType { .. }
+            | Self::InvalidRegex { .. } => StatusCode::BAD_REQUEST,
+            Self::Authz { source } => match source {
+                authz::Error::NoToken => StatusCode::UNAUTHORIZED,
+                authz::Error::Forbidden => StatusCode::FORBIDDEN,
+                authz::Error::Verification { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            Self::RequestSizeExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::InvalidContentEncoding { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            // e.g. the namespace exhausted its query quota
+            Self::Query { source }
+                if matches!(source.find_root(), DataFusionError::ResourcesExhausted(_)) =>
+            {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::Query { .. } | Self::EncodeResponse { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Plan and execute each of `predicates` against `db` in turn, yielding each
+/// Prometheus series as it is read, along with the index of the query that
+/// read it.
+///
+/// Series are yielded in the query output order (by measurement, then tag
+/// values), not sorted by their label sets.
+fn execute<D>(
+    ctx: IOxSessionContext,
+    db: Arc<D>,
+    predicates: Vec<InfluxRpcPredicate>,
+) -> impl Stream<Item = Result<(usize, TimeSeries), Error>> + Send
+where
+    D: QueryNamespace + 'static,
+{
+    futures::stream::iter(predicates.into_iter().enumerate())
+        .then(move |(query_index, predicate)| {
+            let ctx = ctx.child_ctx("prom_read query");
+            let db = Arc::clone(&db);
+            async move {
+                let plans = Planner::new(&ctx)
+                    .read_filter(db, predicate)
+                    .await
+                    .context(QuerySnafu)?;
+
+                let series = ctx
+                    .to_series_and_groups(plans, Arc::clone(&ctx.inner().runtime_env().memory_pool))
+                    .await
+                    .context(QuerySnafu)?
+                    .map_err(|source| Error::Query { source })
+                    .map_ok(move |v| (query_index, v));
+
+                Ok::<_, Error>(series)
+            }
+        })
+        .try_flatten()
+        .try_filter_map(|(query_index, v)| async move {
+            Ok(match v {
+                Either::Series(s) => response::series_to_time_series(s).map(|s| (query_index, s)),
+                // read_filter never produces groups.
+                Either::Group(_) => None,
+            })
+        })
+}
+
+/// The query string parameters of a remote read request.
+#[derive(Debug, Deserialize)]
+struct ReadParams {
+    org: String,
+    bucket: String,
+}
+
+/// Serves the Prometheus remote read API over the namespaces provided by `S`.
+#[derive(Debug)]
+pub struct PromReadService<S> {
+    db_store: Arc<S>,
+    authz: Option<Arc<dyn Authorizer>>,
+    max_request_bytes: usize,
+}
+
+impl<S> PromReadService<S>
+where
+    S: QueryNamespaceProvider,
+{
+    /// Initialise a new [`PromReadService`], rejecting request bodies larger
+    /// than `max_request_bytes` (before and after decompression).
+    pub fn new(
+        db_store: Arc<S>,
+        authz: Option<Arc<dyn Authorizer>>,
+        max_request_bytes: usize,
+    ) -> Self {
+        Self {
+            db_store,
+            authz,
+            max_request_bytes,
+        }
+    }
+
+    /// Serve `req` if it is a remote read request, returning
+    /// [`Error::NotFound`] otherwise.
+    pub async fn route(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        match (req.method(), req.uri().path()) {
+            (&Method::POST, PROM_READ_PATH) => self.read(req).await,
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    async fn read(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        let params: ReadParams = serde_urlencoded::from_str(req.uri().query().unwrap_or_default())
+            .context(InvalidQueryStringSnafu)?;
+        let namespace = NamespaceName::from_org_and_bucket(&params.org, &params.bucket)
+            .context(InvalidNamespaceSnafu)?;
+
+        let token = req
+            .extensions()
+            .get::<AuthorizationHeaderExtension>()
+            .and_then(|v| v.token());
+        let perms = [Permission::ResourceAction(
+            Resource::Database(namespace.to_string()),
+            Action::Read,
+        )];
+        self.authz
+            .require_any_permission(token.as_deref(), &perms)
+            .await
+            .context(AuthzSnafu)?;
+
+        let read_req = self.read_request(req).await?;
+        let response_type = negotiate_response_type(&read_req)?;
+        let predicates = read_req
+            .queries
+            .iter()
+            .map(query::query_to_predicate)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let permit = self
+            .db_store
+            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+            .await;
+
+        info!(
+            %namespace,
+            queries=read_req.queries.len(),
+            ?response_type,
+            "prometheus remote read",
+        );
+
+        let db = self
+            .db_store
+            .db(&namespace, span_ctx.child_span("get namespace"))
+            .await
+            .context(NamespaceNotFoundSnafu {
+                namespace: namespace.as_str(),
+            })?;
+
+        let ctx = db.new_query_context(span_ctx);
+        let mut query_completed_token = db
+            .record_query(&ctx, "prom_read", Box::new(format!("{read_req:?}")))
+            .context(QuerySnafu)?;
+
+        let num_queries = predicates.len();
+        let series = execute(ctx, db, predicates);
+
+        let resp = match response_type {
+            ResponseType::Samples => {
+                let mut results = series
+                    .try_fold(
+                        (0..num_queries).map(|_| Vec::new()).collect::<Vec<_>>(),
+                        |mut results, (query_index, series)| async move {
+                            results[query_index].push(series);
+                            Ok(results)
+                        },
+                    )
+                    .await?;
+                for series in &mut results {
+                    response::sort_series(series);
+                }
+
+                query_completed_token.set_success();
+                debug!(%namespace, "completed prometheus remote read");
+
+                Response::builder()
+                    .header(CONTENT_TYPE, "application/x-protobuf")
+                    .header(CONTENT_ENCODING, "snappy")
+                    .body(Body::from(
+                        response::encode_samples(results).context(EncodeResponseSnafu)?,
+                    ))
+            }
+            ResponseType::StreamedXorChunks => {
+                // Each series is written to the client as soon as it is read,
+                // so the query only completes (and releases its permit) once
+                // the last frame has been produced. An error part way through
+                // aborts the response body.
+                let frames = series
+                    .map_ok(|(query_index, series)| response::encode_frame(query_index, series))
+                    .chain(futures::stream::once(async move {
+                        query_completed_token.set_success();
+                        drop(permit);
+                        debug!(%namespace, "completed prometheus remote read");
+                        Ok(Vec::new())
+                    }));
+
+                Response::builder()
+                    .header(
+                        CONTENT_TYPE,
+                        "application/x-streamed-protobuf; proto=prometheus.ChunkedReadResponse",
+                    )
+                    .body(Body::wrap_stream(frames))
+            }
+        };
+
+        // The header values are static and valid.
+        Ok(resp.unwrap())
+    }
+
+    /// Read, decompress and decode the [`ReadRequest`] in the body of `req`.
+    async fn read_request(&self, req: Request<Body>) -> Result<ReadRequest, Error> {
+        match req
+            .headers()
+            .get(&CONTENT_ENCODING)
+            .map(|v| v.to_str().unwrap_or_default())
+        {
+            // Remote read requests are always snappy compressed.
+            None | Some("snappy") => {}
+            Some(v) => {
+                return InvalidContentEncodingSnafu { encoding: v }.fail();
+            }
+        }
+
+        let mut payload = req.into_body();
+        let mut body = BytesMut::new();
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk.context(ClientHangupSnafu)?;
+            // limit max size of in-memory payload
+            if (body.len() + chunk.len()) > self.max_request_bytes {
+                return RequestSizeExceededSnafu {
+                    max_bytes: self.max_request_bytes,
+                }
+                .fail();
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        // Check the decompressed size before allocating to prevent a
+        // decompression bomb based DoS.
+        let len = snap::raw::decompress_len(&body).context(InvalidSnappySnafu)?;
+        if len > self.max_request_bytes {
+            return RequestSizeExceededSnafu {
+                max_bytes: self.max_request_bytes,
+            }
+            .fail();
+        }
+        let body: Bytes = snap::raw::Decoder::new()
+            .decompress_vec(&body)
+            .context(InvalidSnappySnafu)?
+            .into();
+
+        ReadRequest::decode(body).context(DecodeRequestSnafu)
+    }
+}
+
+/// Select the first supported response type from the client's accepted
+/// response types, defaulting to `SAMPLES` if none are specified.
+fn negotiate_response_type(req: &ReadRequest) -> Result<ResponseType, Error> {
+    if req.accepted_response_types.is_empty() {
+        return Ok(ResponseType::Samples);
+    }
+
+    req.accepted_response_types
+        .iter()
+        .find_map(|v| ResponseType::from_i32(*v))
+        .context(UnsupportedResponseTypeSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use generated_types::prometheus::{
+        label_matcher, ChunkedReadResponse, LabelMatcher, Query, ReadResponse,
+    };
+    use iox_query::test::TestChunk;
+    use service_common::test_util::TestDatabaseStore;
+
+    use super::*;
+
+    const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+
+    async fn new_service() -> PromReadService<TestDatabaseStore> {
+        let db_store = Arc::new(TestDatabaseStore::default());
+
+        let chunk = TestChunk::new("cpu")
+            .with_id(0)
+            .with_tag_column("tag1")
+            .with_time_column()
+            .with_i64_field_column("value")
+            .with_three_rows_of_data();
+        db_store
+            .db_or_create("bananas_test")
+            .await
+            .add_chunk("my_partition_key", Arc::new(chunk));
+
+        PromReadService::new(db_store, None, MAX_REQUEST_BYTES)
+    }
+
+    fn read_request(req: &ReadRequest) -> Request<Body> {
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&req.encode_to_vec())
+            .unwrap();
+
+        Request::builder()
+            .method(Method::POST)
+            .uri("https://bananas.example/api/v1/prom/read?org=bananas&bucket=test")
+            .header(CONTENT_ENCODING, "snappy")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    fn cpu_query(matchers: Vec<LabelMatcher>) -> Query {
+        let mut all = vec![LabelMatcher {
+            r#type: label_matcher::Type::Eq as i32,
+            name: "__name__".to_string(),
+            value: "cpu".to_string(),
+        }];
+        all.extend(matchers);
+
+        Query {
+            start_timestamp_ms: 0,
+            end_timestamp_ms: 1_000,
+            matchers: all,
+            hints: None,
+        }
+    }
+
+    async fn body_bytes(resp: Response<Body>) -> Bytes {
+        hyper::body::to_bytes(resp.into_body()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_samples() {
+        let service = new_service().await;
+
+        let req = ReadRequest {
+            queries: vec![
+                cpu_query(vec![]),
+                cpu_query(vec![LabelMatcher {
+                    r#type: label_matcher::Type::Re as i32,
+                    name: "tag1".to_string(),
+                    value: "W.".to_string(),
+                }]),
+            ],
+            accepted_response_types: vec![],
+        };
+
+        let resp = service.route(read_request(&req)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[CONTENT_ENCODING], "snappy");
+
+        let body = body_bytes(resp).await;
+        let body = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+        let resp = ReadResponse::decode(body.as_slice()).unwrap();
+
+        assert_eq!(resp.results.len(), 2);
+
+        // All three series are returned, sorted by label set.
+        let got = resp.results[0]
+            .timeseries
+            .iter()
+            .map(|s| {
+                let tag = s.labels.iter().find(|l| l.name == "tag1").unwrap();
+                (tag.value.as_str(), s.samples.len())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(got, [("UT", 1), ("VT", 1), ("WA", 1)]);
+
+        // Only the WA series matches the regex.
+        let ts = &resp.results[1].timeseries;
+        assert_eq!(ts.len(), 1);
+        let labels = ts[0]
+            .labels
+            .iter()
+            .map(|l| (l.name.as_str(), l.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [("__name__", "cpu"), ("tag1", "WA")]);
+        assert_eq!(ts[0].samples.len(), 1);
+        assert_eq!(ts[0].samples[0].value, 1000.0);
+        assert_eq!(ts[0].samples[0].timestamp, 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_streamed_chunks() {
+        let service = new_service().await;
+
+        let req = ReadRequest {
+            queries: vec![cpu_query(vec![LabelMatcher {
+                r#type: label_matcher::Type::Eq as i32,
+                name: "tag1".to_string(),
+                value: "VT".to_string(),
+            }])],
+            accepted_response_types: vec![ResponseType::StreamedXorChunks as i32],
+        };
+
+        let resp = service.route(read_request(&req)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[CONTENT_TYPE],
+            "application/x-streamed-protobuf; proto=prometheus.ChunkedReadResponse"
+        );
+
+        let body = body_bytes(resp).await;
+        let mut cursor = &body[..];
+        let len = prost::encoding::decode_varint(&mut cursor).unwrap() as usize;
+        let frame = ChunkedReadResponse::decode(&cursor[4..]).unwrap();
+        assert_eq!(cursor.len(), 4 + len);
+
+        assert_eq!(frame.query_index, 0);
+        assert_eq!(frame.chunked_series.len(), 1);
+        assert_eq!(frame.chunked_series[0].chunks.len(), 1);
+        assert_eq!(frame.chunked_series[0].chunks[0].min_time_ms, 0);
+    }
+
+    #[tokio::test]
+    async fn test_namespace_not_found() {
+        let service = PromReadService::new(
+            Arc::new(TestDatabaseStore::default()),
+            None,
+            MAX_REQUEST_BYTES,
+        );
+
+        let req = ReadRequest {
+            queries: vec![cpu_query(vec![])],
+            accepted_response_types: vec![],
+        };
+
+        let err = service.route(read_request(&req)).await.unwrap_err();
+        assert_matches!(err, Error::NamespaceNotFound { .. });
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests() {
+        let service = new_service().await;
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("https://bananas.example/api/v1/prom/read?org=bananas&bucket=test")
+            .body(Body::empty())
+            .unwrap();
+        assert_matches!(service.route(req).await, Err(Error::NotFound));
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("https://bananas.example/api/v1/prom/read")
+            .body(Body::empty())
+            .unwrap();
+        assert_matches!(
+            service.route(req).await,
+            Err(Error::InvalidQueryString { .. })
+        );
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("https://bananas.example/api/v1/prom/read?org=bananas&bucket=test")
+            .body(Body::from("not snappy"))
+            .unwrap();
+        assert_matches!(service.route(req).await, Err(Error::InvalidSnappy { .. }));
+
+        let req = ReadRequest {
+            queries: vec![cpu_query(vec![LabelMatcher {
+                r#type: label_matcher::Type::Re as i32,
+                name: "tag1".to_string(),
+                value: "(".to_string(),
+            }])],
+            accepted_response_types: vec![],
+        };
+        let err = service.route(read_request(&req)).await.unwrap_err();
+        assert_matches!(err, Error::InvalidRegex { .. });
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+
+        let req = ReadRequest {
+            queries: vec![],
+            accepted_response_types: vec![42],
+        };
+        assert_matches!(
+            service.route(read_request(&req)).await,
+            Err(Error::UnsupportedResponseType)
+        );
+    }
+}
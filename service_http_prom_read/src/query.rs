@@ -0,0 +1,253 @@
+//! Conversion of Prometheus remote read queries into IOx predicates.
+
+use std::collections::BTreeSet;
+
+use datafusion::{
+    common::Column,
+    prelude::{lit, Expr},
+};
+use generated_types::prometheus::{label_matcher, LabelMatcher, Query};
+use predicate::{
+    rpc_predicate::{InfluxRpcPredicate, FIELD_COLUMN_NAME, MEASUREMENT_COLUMN_NAME},
+    Predicate,
+};
+use regex::Regex;
+use snafu::{OptionExt, ResultExt};
+
+use crate::{
+    Error, InvalidMatcherSnafu, InvalidMatcherTypeSnafu, InvalidRegexSnafu, METRIC_NAME_LABEL,
+    VALUE_FIELD_NAME,
+};
+
+/// Prometheus timestamps are in milliseconds.
+const NANOS_PER_MILLI: i64 = 1_000_000;
+
+/// Convert a remote read [`Query`] into an [`InfluxRpcPredicate`] selecting
+/// the `value` field of all the series matched by the label matchers, within
+/// the (inclusive) query time range.
+pub(crate) fn query_to_predicate(query: &Query) -> Result<InfluxRpcPredicate, Error> {
+    let (table_names, predicate) = split_query(query)?;
+    Ok(InfluxRpcPredicate::new(table_names, predicate))
+}
+
+/// Return the optional table name restriction and row [`Predicate`] for
+/// `query`.
+fn split_query(query: &Query) -> Result<(Option<BTreeSet<String>>, Predicate), Error> {
+    // The query range is inclusive at millisecond precision, so include
+    // every nanosecond timestamp that truncates to the end millisecond.
+    let start = query.start_timestamp_ms.saturating_mul(NANOS_PER_MILLI);
+    let end = query
+        .end_timestamp_ms
+        .saturating_add(1)
+        .saturating_mul(NANOS_PER_MILLI);
+
+    let mut exprs = vec![column(FIELD_COLUMN_NAME).eq(lit(VALUE_FIELD_NAME))];
+    let mut table_names = None;
+    for matcher in &query.matchers {
+        // An exact metric name match restricts the query to a single table,
+        // avoiding planning against every table in the namespace.
+        if matcher.name == METRIC_NAME_LABEL
+            && matcher.r#type == label_matcher::Type::Eq as i32
+            && !matcher.value.is_empty()
+            && table_names.is_none()
+        {
+            table_names = Some(BTreeSet::from([matcher.value.clone()]));
+        }
+
+        exprs.push(matcher_to_expr(matcher)?);
+    }
+
+    let predicate = Predicate::new().with_range(start, end).with_exprs(exprs);
+
+    Ok((table_names, predicate))
+}
+
+/// Convert a single label matcher into an [`Expr`], following the Prometheus
+/// matching semantics:
+///
+///   * An absent label is equivalent to a label with an empty value.
+///   * Regular expressions are fully anchored.
+fn matcher_to_expr(matcher: &LabelMatcher) -> Result<Expr, Error> {
+    let name = match matcher.name.as_str() {
+        METRIC_NAME_LABEL => MEASUREMENT_COLUMN_NAME,
+        MEASUREMENT_COLUMN_NAME | FIELD_COLUMN_NAME | "" => {
+            return InvalidMatcherSnafu {
+                name: &matcher.name,
+            }
+            .fail()
+        }
+        v => v,
+    };
+    let col = column(name);
+    let value = matcher.value.as_str();
+
+    let ty = label_matcher::Type::from_i32(matcher.r#type).context(InvalidMatcherTypeSnafu {
+        name: &matcher.name,
+        value: matcher.r#type,
+    })?;
+
+    let expr = match ty {
+        label_matcher::Type::Eq if value.is_empty() => col.clone().is_null().or(col.eq(lit(""))),
+        label_matcher::Type::Eq => col.eq(lit(value)),
+        label_matcher::Type::Neq if value.is_empty() => {
+            col.clone().is_not_null().and(col.not_eq(lit("")))
+        }
+        label_matcher::Type::Neq => col.clone().is_null().or(col.not_eq(lit(value))),
+        label_matcher::Type::Re | label_matcher::Type::Nre => {
+            let pattern = format!("^(?:{value})$");
+            let matches_empty = Regex::new(&pattern)
+                .context(InvalidRegexSnafu { pattern: value })?
+                .is_match("");
+
+            match (ty, matches_empty) {
+                (label_matcher::Type::Re, true) => col
+                    .clone()
+                    .is_null()
+                    .or(query_functions::regex_match_expr(col, pattern)),
+                (label_matcher::Type::Re, false) => query_functions::regex_match_expr(col, pattern),
+                // A NULL (absent label) input to the regex UDF does not match,
+                // which is the desired outcome if the regex matches "".
+                (_, true) => query_functions::regex_not_match_expr(col, pattern),
+                (_, false) => col
+                    .clone()
+                    .is_null()
+                    .or(query_functions::regex_not_match_expr(col, pattern)),
+            }
+        }
+    };
+
+    Ok(expr)
+}
+
+/// Return a column reference for `name`, without parsing it as a qualified
+/// name (label names may contain "." characters).
+fn column(name: &str) -> Expr {
+    Expr::Column(Column::from_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::TimestampRange;
+
+    use super::*;
+
+    fn matcher(ty: label_matcher::Type, name: &str, value: &str) -> LabelMatcher {
+        LabelMatcher {
+            r#type: ty as i32,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matchers() {
+        let tests = [
+            (label_matcher::Type::Eq, "a", "b", r#"a = Utf8("b")"#),
+            (
+                label_matcher::Type::Eq,
+                "a",
+                "",
+                r#"a IS NULL OR a = Utf8("")"#,
+            ),
+            (
+                label_matcher::Type::Neq,
+                "a",
+                "b",
+                r#"a IS NULL OR a != Utf8("b")"#,
+            ),
+            (
+                label_matcher::Type::Neq,
+                "a",
+                "",
+                r#"a IS NOT NULL AND a != Utf8("")"#,
+            ),
+            (
+                label_matcher::Type::Re,
+                "a",
+                "b.+",
+                r#"influx_regex_match(a, Utf8("^(?:b.+)$"))"#,
+            ),
+            (
+                label_matcher::Type::Re,
+                "a",
+                "b.*",
+                r#"a IS NULL OR influx_regex_match(a, Utf8("^(?:b.*)$"))"#,
+            ),
+            (
+                label_matcher::Type::Nre,
+                "a",
+                "b.+",
+                r#"a IS NULL OR influx_regex_not_match(a, Utf8("^(?:b.+)$"))"#,
+            ),
+            (
+                label_matcher::Type::Nre,
+                "a",
+                "b.*",
+                r#"influx_regex_not_match(a, Utf8("^(?:b.*)$"))"#,
+            ),
+            (
+                label_matcher::Type::Eq,
+                "__name__",
+                "cpu",
+                r#"_measurement = Utf8("cpu")"#,
+            ),
+        ];
+
+        for (ty, name, value, want) in tests {
+            let got = matcher_to_expr(&matcher(ty, name, value)).unwrap();
+            assert_eq!(got.to_string(), want, "{ty:?} {name} {value}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_matchers() {
+        assert_matches!(
+            matcher_to_expr(&matcher(label_matcher::Type::Re, "a", "(")),
+            Err(Error::InvalidRegex { .. })
+        );
+        assert_matches!(
+            matcher_to_expr(&matcher(label_matcher::Type::Eq, "_field", "a")),
+            Err(Error::InvalidMatcher { .. })
+        );
+        assert_matches!(
+            matcher_to_expr(&LabelMatcher {
+                r#type: 42,
+                name: "a".to_string(),
+                value: "b".to_string(),
+            }),
+            Err(Error::InvalidMatcherType { value: 42, .. })
+        );
+    }
+
+    #[test]
+    fn test_split_query() {
+        let query = Query {
+            start_timestamp_ms: 1,
+            end_timestamp_ms: 2,
+            matchers: vec![
+                matcher(label_matcher::Type::Eq, "__name__", "cpu"),
+                matcher(label_matcher::Type::Eq, "host", "a"),
+            ],
+            hints: None,
+        };
+
+        let (table_names, got) = split_query(&query).unwrap();
+        assert_eq!(table_names, Some(BTreeSet::from(["cpu".to_string()])));
+
+        assert_eq!(got.range, Some(TimestampRange::new(1_000_000, 3_000_000)));
+        let exprs = got
+            .exprs
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exprs,
+            [
+                r#"_field = Utf8("value")"#,
+                r#"_measurement = Utf8("cpu")"#,
+                r#"host = Utf8("a")"#,
+            ]
+        );
+    }
+}
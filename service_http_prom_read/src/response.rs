@@ -0,0 +1,248 @@
+//! Conversion of IOx series into Prometheus remote read responses.
+
+use generated_types::prometheus::{
+    chunk, Chunk, ChunkedReadResponse, ChunkedSeries, Label, QueryResult, ReadResponse, Sample,
+    TimeSeries,
+};
+use iox_query::exec::seriesset::series::{Data, Series};
+use predicate::rpc_predicate::{FIELD_COLUMN_NAME, MEASUREMENT_COLUMN_NAME};
+use prost::Message;
+
+use crate::{
+    chunk::{XorChunkEncoder, MAX_SAMPLES_PER_CHUNK},
+    METRIC_NAME_LABEL,
+};
+
+/// The CRC32 (Castagnoli) checksum appended to each streamed frame.
+const CASTAGNOLI: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+/// Convert an IOx [`Series`] into a Prometheus [`TimeSeries`].
+///
+/// The `_measurement` tag becomes the `__name__` label, and the `_field` tag
+/// is dropped (only the `value` field is ever selected). Samples are converted
+/// from nanosecond to millisecond precision; if several samples fall within
+/// the same millisecond, the last one is retained.
+///
+/// Returns [`None`] if the series values are not numeric.
+pub(crate) fn series_to_time_series(series: Series) -> Option<TimeSeries> {
+    let (timestamps, values): (_, Vec<f64>) = match series.data {
+        Data::FloatPoints { timestamps, values } => (timestamps, values),
+        Data::IntegerPoints { timestamps, values } => {
+            (timestamps, values.into_iter().map(|v| v as f64).collect())
+        }
+        Data::UnsignedPoints { timestamps, values } => {
+            (timestamps, values.into_iter().map(|v| v as f64).collect())
+        }
+        Data::BooleanPoints { .. } | Data::StringPoints { .. } => return None,
+    };
+
+    let mut labels = series
+        .tags
+        .into_iter()
+        .filter_map(|tag| match tag.key.as_ref() {
+            FIELD_COLUMN_NAME => None,
+            MEASUREMENT_COLUMN_NAME => Some(Label {
+                name: METRIC_NAME_LABEL.to_string(),
+                value: tag.value.to_string(),
+            }),
+            _ => Some(Label {
+                name: tag.key.to_string(),
+                value: tag.value.to_string(),
+            }),
+        })
+        .collect::<Vec<_>>();
+    labels.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let mut samples: Vec<Sample> = Vec::with_capacity(timestamps.len());
+    for (ts, value) in timestamps.into_iter().zip(values) {
+        let timestamp = ts.div_euclid(1_000_000);
+        match samples.last_mut() {
+            Some(last) if last.timestamp == timestamp => last.value = value,
+            _ => samples.push(Sample { value, timestamp }),
+        }
+    }
+
+    Some(TimeSeries { labels, samples })
+}
+
+/// Sort `series` by their label sets, as Prometheus requires.
+pub(crate) fn sort_series(series: &mut [TimeSeries]) {
+    series.sort_unstable_by(|a, b| {
+        let a = a.labels.iter().map(|l| (&l.name, &l.value));
+        let b = b.labels.iter().map(|l| (&l.name, &l.value));
+        a.cmp(b)
+    });
+}
+
+/// Encode the per-query `results` as a snappy-compressed [`ReadResponse`],
+/// for the `SAMPLES` response type.
+pub(crate) fn encode_samples(results: Vec<Vec<TimeSeries>>) -> Result<Vec<u8>, snap::Error> {
+    let resp = ReadResponse {
+        results: results
+            .into_iter()
+            .map(|timeseries| QueryResult { timeseries })
+            .collect(),
+    };
+
+    snap::raw::Encoder::new().compress_vec(&resp.encode_to_vec())
+}
+
+/// Encode `series`, read by the query at `query_index`, as a single
+/// [`ChunkedReadResponse`] frame of the `STREAMED_XOR_CHUNKS` response type.
+///
+/// The frame is prefixed by the uvarint encoded length of the message and the
+/// big-endian CRC32 (Castagnoli) checksum of the message.
+pub(crate) fn encode_frame(query_index: usize, series: TimeSeries) -> Vec<u8> {
+    let msg = ChunkedReadResponse {
+        chunked_series: vec![ChunkedSeries {
+            chunks: encode_chunks(&series.samples),
+            labels: series.labels,
+        }],
+        query_index: query_index as i64,
+    }
+    .encode_to_vec();
+
+    // At most 10 bytes of uvarint length and 4 bytes of checksum.
+    let mut buf = Vec::with_capacity(msg.len() + 14);
+    prost::encoding::encode_varint(msg.len() as u64, &mut buf);
+    buf.extend_from_slice(&CASTAGNOLI.checksum(&msg).to_be_bytes());
+    buf.extend_from_slice(&msg);
+
+    buf
+}
+
+/// Encode `samples` as XOR chunks of at most [`MAX_SAMPLES_PER_CHUNK`]
+/// samples each.
+fn encode_chunks(samples: &[Sample]) -> Vec<Chunk> {
+    samples
+        .chunks(MAX_SAMPLES_PER_CHUNK)
+        .map(|samples| {
+            let mut enc = XorChunkEncoder::default();
+            for s in samples {
+                enc.append(s.timestamp, s.value);
+            }
+            debug_assert_eq!(enc.len(), samples.len());
+
+            Chunk {
+                // chunks() never yields an empty slice.
+                min_time_ms: samples.first().unwrap().timestamp,
+                max_time_ms: samples.last().unwrap().timestamp,
+                r#type: chunk::Encoding::Xor as i32,
+                data: enc.finish(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use iox_query::exec::seriesset::series::Tag;
+
+    use super::*;
+
+    fn tag(key: &str, value: &str) -> Tag {
+        Tag {
+            key: Arc::from(key),
+            value: Arc::from(value),
+        }
+    }
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_series_to_time_series() {
+        let series = Series {
+            tags: vec![
+                tag("_measurement", "cpu"),
+                tag("host", "a"),
+                tag("_field", "value"),
+                tag("az", "z"),
+            ],
+            data: Data::IntegerPoints {
+                timestamps: vec![-1, 1_000_000, 1_500_000, 3_000_000],
+                values: vec![1, 2, 3, 4],
+            },
+        };
+
+        let got = series_to_time_series(series).unwrap();
+        assert_eq!(
+            got.labels,
+            [
+                label("__name__", "cpu"),
+                label("az", "z"),
+                label("host", "a"),
+            ]
+        );
+        assert_eq!(
+            got.samples,
+            [
+                Sample {
+                    value: 1.0,
+                    timestamp: -1
+                },
+                Sample {
+                    value: 3.0,
+                    timestamp: 1
+                },
+                Sample {
+                    value: 4.0,
+                    timestamp: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_series_to_time_series_non_numeric() {
+        let series = Series {
+            tags: vec![tag("_measurement", "cpu")],
+            data: Data::StringPoints {
+                timestamps: vec![1],
+                values: vec!["bananas".to_string()],
+            },
+        };
+
+        assert!(series_to_time_series(series).is_none());
+    }
+
+    #[test]
+    fn test_encode_frame() {
+        let samples = (0..(MAX_SAMPLES_PER_CHUNK as i64 + 1))
+            .map(|i| Sample {
+                value: i as f64,
+                timestamp: i * 1000,
+            })
+            .collect::<Vec<_>>();
+        let series = TimeSeries {
+            labels: vec![label("__name__", "cpu")],
+            samples,
+        };
+
+        let buf = encode_frame(1, series);
+
+        let mut cursor = &buf[..];
+        let len = prost::encoding::decode_varint(&mut cursor).unwrap() as usize;
+        let (crc, frame) = cursor.split_at(4);
+        assert_eq!(frame.len(), len);
+        assert_eq!(crc, CASTAGNOLI.checksum(frame).to_be_bytes());
+
+        let frame = ChunkedReadResponse::decode(frame).unwrap();
+        assert_eq!(frame.query_index, 1);
+        assert_eq!(frame.chunked_series.len(), 1);
+
+        let chunks = &frame.chunked_series[0].chunks;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].min_time_ms, 0);
+        assert_eq!(chunks[0].max_time_ms, 119_000);
+        assert_eq!(chunks[1].min_time_ms, 120_000);
+        assert_eq!(chunks[1].max_time_ms, 120_000);
+        assert_eq!(chunks[1].r#type, chunk::Encoding::Xor as i32);
+    }
+}
@@ -60,6 +60,13 @@ where
         std::mem::swap(&mut t, &mut *pool);
         t
     }
+
+    /// Returns the [`Pool`] currently in use.
+    ///
+    /// The returned pool is not affected by later calls to [`replace`](Self::replace).
+    pub fn current(&self) -> Arc<Pool<DB>> {
+        Arc::clone(&self.pool.read().expect("poisoned"))
+    }
 }
 
 impl<DB> Clone for HotSwapPool<DB>
@@ -57,6 +57,11 @@ pub struct SpanContext {
 
     /// If we should also sample based on this context (i.e. emit child spans).
     pub sampled: bool,
+
+    /// Opaque vendor-specific trace state received from the caller, such as a
+    /// W3C `tracestate` header value, to be propagated as-is to downstream
+    /// services.
+    pub trace_state: Option<Arc<str>>,
 }
 
 impl SpanContext {
@@ -80,6 +85,7 @@ impl SpanContext {
             links: vec![],
             collector,
             sampled: true,
+            trace_state: None,
         }
     }
 
@@ -92,6 +98,7 @@ impl SpanContext {
             links: Vec::with_capacity(0),
             parent_span_id: Some(self.span_id),
             sampled: self.sampled,
+            trace_state: self.trace_state.clone(),
         };
         Span::new(name, ctx)
     }
@@ -106,6 +113,11 @@ impl SpanContext {
                 .iter()
                 .map(|(t_id, s_id)| std::mem::size_of_val(t_id) + std::mem::size_of_val(s_id))
                 .sum::<usize>()
+            + self
+                .trace_state
+                .as_ref()
+                .map(|v| v.len())
+                .unwrap_or_default()
     }
 }
 
@@ -117,6 +129,7 @@ impl PartialEq for SpanContext {
             && self.links == other.links
             && self.collector.is_some() == other.collector.is_some()
             && self.sampled == other.sampled
+            && self.trace_state == other.trace_state
     }
 }
 
@@ -153,6 +166,7 @@ mod tests {
             ],
             collector: Some(collector_1),
             sampled: true,
+            trace_state: Some(Arc::from("a=b")),
         };
 
         let ctx = SpanContext { ..ctx_ref.clone() };
@@ -199,5 +213,11 @@ mod tests {
             ..ctx_ref.clone()
         };
         assert_ne!(ctx_ref, ctx);
+
+        let ctx = SpanContext {
+            trace_state: None,
+            ..ctx_ref.clone()
+        };
+        assert_ne!(ctx_ref, ctx);
     }
 }
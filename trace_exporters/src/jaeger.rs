@@ -360,6 +360,7 @@ mod tests {
             links: vec![],
             collector: None,
             sampled: true,
+            trace_state: None,
         };
         let mut span = ctx.child("foo");
         span.ctx.links = vec![
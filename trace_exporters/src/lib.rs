@@ -16,6 +16,7 @@ use jaeger::JaegerTag;
 use snafu::Snafu;
 use std::num::{NonZeroU16, NonZeroU64};
 use std::sync::Arc;
+use trace_http::ctx::{TraceContextFormat, TraceHeaderFormatter, TraceHeaderParser};
 
 pub mod export;
 
@@ -40,7 +41,7 @@ mod thrift {
     pub mod jaeger;
 }
 
-pub const DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME: &str = "uber-trace-id";
+pub use trace_http::ctx::DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME;
 
 /// CLI config for distributed tracing options
 #[derive(Debug, Clone, clap::Parser)]
@@ -115,6 +116,24 @@ pub struct TracingConfig {
     )]
     pub traces_jaeger_debug_name: String,
 
+    /// Tracing: trace context propagation formats, in order of precedence.
+    ///
+    /// Incoming requests are parsed using the first format with headers
+    /// present, and outgoing requests to other IOx services carry the trace
+    /// context in all of the listed formats.
+    ///
+    /// Use a comma-delimited string to set multiple formats: jaeger,w3c
+    ///
+    /// Can be any of: jaeger, b3, w3c
+    #[clap(
+        long = "traces-context-formats",
+        env = "TRACES_CONTEXT_FORMATS",
+        default_value = "jaeger,b3,w3c",
+        value_delimiter = ',',
+        action
+    )]
+    pub traces_context_formats: Vec<TraceContextFormat>,
+
     /// Tracing: set of key=value pairs to annotate tracing spans with.
     ///
     /// Use a comma-delimited string to set multiple pairs: env=prod,region=eu-1
@@ -147,6 +166,23 @@ impl TracingConfig {
             TracesExporter::Jaeger => Ok(Some(jaeger_exporter(self)?)),
         }
     }
+
+    /// Construct a [`TraceHeaderParser`] for incoming requests, accepting the
+    /// configured trace context formats.
+    pub fn trace_header_parser(&self) -> TraceHeaderParser {
+        TraceHeaderParser::new()
+            .with_jaeger_trace_context_header_name(&self.traces_jaeger_trace_context_header_name)
+            .with_jaeger_debug_name(&self.traces_jaeger_debug_name)
+            .with_formats(self.traces_context_formats.iter().copied())
+    }
+
+    /// Construct a [`TraceHeaderFormatter`] for requests made to other IOx
+    /// services, emitting the configured trace context formats.
+    pub fn trace_header_formatter(&self) -> TraceHeaderFormatter {
+        TraceHeaderFormatter::new()
+            .with_jaeger_trace_context_header_name(&self.traces_jaeger_trace_context_header_name)
+            .with_formats(self.traces_context_formats.iter().copied())
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use std::num::{NonZeroU128, NonZeroU64, ParseIntError};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -15,6 +16,64 @@ const B3_TRACE_ID_HEADER: &str = "X-B3-TraceId";
 const B3_PARENT_SPAN_ID_HEADER: &str = "X-B3-ParentSpanId";
 const B3_SPAN_ID_HEADER: &str = "X-B3-SpanId";
 
+const W3C_TRACEPARENT_HEADER: &str = "traceparent";
+const W3C_TRACESTATE_HEADER: &str = "tracestate";
+
+/// The default header name used to propagate Jaeger trace context.
+pub const DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME: &str = "uber-trace-id";
+
+/// The trace context formats accepted and emitted by default, in order of
+/// precedence.
+pub const DEFAULT_TRACE_CONTEXT_FORMATS: [TraceContextFormat; 3] = [
+    TraceContextFormat::Jaeger,
+    TraceContextFormat::B3,
+    TraceContextFormat::W3C,
+];
+
+/// A format used to propagate trace context across process boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceContextFormat {
+    /// The Jaeger `uber-trace-id` header (or a custom header name).
+    ///
+    /// <https://www.jaegertracing.io/docs/1.21/client-libraries/#propagation-format>
+    Jaeger,
+
+    /// The Zipkin B3 multiple header format.
+    ///
+    /// <https://github.com/openzipkin/b3-propagation#multiple-headers>
+    B3,
+
+    /// The W3C Trace Context `traceparent` and `tracestate` headers.
+    ///
+    /// <https://www.w3.org/TR/trace-context/>
+    W3C,
+}
+
+impl FromStr for TraceContextFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "jaeger" => Ok(Self::Jaeger),
+            "b3" => Ok(Self::B3),
+            "w3c" => Ok(Self::W3C),
+            _ => Err(format!(
+                "Invalid trace context format '{s}'. Valid options: jaeger, b3, w3c"
+            )),
+        }
+    }
+}
+
+impl Display for TraceContextFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Jaeger => f.write_str("jaeger"),
+            Self::B3 => f.write_str("b3"),
+            Self::W3C => f.write_str("w3c"),
+        }
+    }
+}
+
 /// Error decoding SpanContext from transport representation
 #[derive(Debug, Snafu)]
 pub enum ContextError {
@@ -40,6 +99,9 @@ pub enum DecodeError {
     #[snafu(display("Expected \"trace-id:span-id:parent-span-id:flags\""))]
     InvalidJaegerTrace,
 
+    #[snafu(display("Expected \"version-trace-id-parent-id-trace-flags\""))]
+    InvalidW3CTraceParent,
+
     #[snafu(display("value cannot be 0"))]
     ZeroError,
 }
@@ -71,6 +133,9 @@ pub struct TraceHeaderParser {
     jaeger_trace_context_header_name: Option<Arc<str>>,
     /// header that forces sampling
     jaeger_debug_name: Option<Arc<str>>,
+    /// accepted trace context formats in order of precedence, or
+    /// [`DEFAULT_TRACE_CONTEXT_FORMATS`] if not specified
+    formats: Option<Arc<[TraceContextFormat]>>,
 }
 
 impl TraceHeaderParser {
@@ -96,27 +161,58 @@ impl TraceHeaderParser {
         self
     }
 
+    /// specify the accepted trace context formats, in order of precedence
+    ///
+    /// The [`TraceContextFormat::Jaeger`] format is only accepted if a
+    /// jaeger_trace_context_header_name is also specified.
+    pub fn with_formats(mut self, formats: impl IntoIterator<Item = TraceContextFormat>) -> Self {
+        self.formats = Some(formats.into_iter().collect());
+        self
+    }
+
     /// Create a SpanContext for the trace described in the request's
     /// headers, if any
     ///
     /// Currently support the following formats:
     /// * <https://github.com/openzipkin/b3-propagation#multiple-headers>
     /// * <https://www.jaegertracing.io/docs/1.21/client-libraries/#propagation-format>
+    /// * <https://www.w3.org/TR/trace-context/>
+    ///
+    /// If the headers of more than one accepted format are present, the first
+    /// format in the configured order of precedence is used.
     pub fn parse(
         &self,
         collector: Option<&Arc<dyn TraceCollector>>,
         headers: &HeaderMap,
     ) -> Result<Option<SpanContext>, ContextError> {
-        if let Some(trace_header) = self.jaeger_trace_context_header_name.as_ref() {
-            if headers.contains_key(trace_header.as_ref()) {
-                return decode_jaeger(collector, headers, trace_header.as_ref()).map(Some);
+        let formats = self
+            .formats
+            .as_deref()
+            .unwrap_or(&DEFAULT_TRACE_CONTEXT_FORMATS[..]);
+
+        for format in formats {
+            match format {
+                TraceContextFormat::Jaeger => {
+                    if let Some(trace_header) = self.jaeger_trace_context_header_name.as_ref() {
+                        if headers.contains_key(trace_header.as_ref()) {
+                            return decode_jaeger(collector, headers, trace_header.as_ref())
+                                .map(Some);
+                        }
+                    }
+                }
+                TraceContextFormat::B3 => {
+                    if headers.contains_key(B3_TRACE_ID_HEADER) {
+                        return decode_b3(collector, headers).map(Some);
+                    }
+                }
+                TraceContextFormat::W3C => {
+                    if headers.contains_key(W3C_TRACEPARENT_HEADER) {
+                        return decode_w3c(collector, headers).map(Some);
+                    }
+                }
             }
         }
 
-        if headers.contains_key(B3_TRACE_ID_HEADER) {
-            return decode_b3(collector, headers).map(Some);
-        }
-
         if let Some(debug_header_name) = self.jaeger_debug_name.as_ref() {
             if let Some(debug_header_value) = headers.get(debug_header_name.as_ref()) {
                 // create a new trace / span
@@ -161,6 +257,7 @@ fn decode_b3(
         links,
         collector: collector.cloned(),
         sampled,
+        trace_state: None,
     })
 }
 
@@ -219,6 +316,89 @@ fn decode_jaeger(
         links,
         collector: collector.cloned(),
         sampled,
+        trace_state: None,
+    })
+}
+
+struct W3CTraceParent {
+    trace_id: TraceId,
+    span_id: SpanId,
+    flags: u8,
+}
+
+impl FromStr for W3CTraceParent {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('-');
+        let (version, trace_id, span_id, flags) =
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(v), Some(t), Some(s), Some(f)) => (v, t, s, f),
+                _ => return Err(DecodeError::InvalidW3CTraceParent),
+            };
+
+        // Version 0 has exactly four fields, and future versions may only
+        // append fields. Version 0xff is invalid.
+        let extra_fields = parts.next().is_some();
+        let valid_version = match version {
+            "00" => !extra_fields,
+            "ff" => false,
+            v => v.len() == 2,
+        };
+        if !valid_version
+            || trace_id.len() != 32
+            || span_id.len() != 16
+            || flags.len() != 2
+            || !s.trim().bytes().all(|b| b == b'-' || b.is_ascii_hexdigit())
+        {
+            return Err(DecodeError::InvalidW3CTraceParent);
+        }
+
+        Ok(Self {
+            trace_id: parse_trace(trace_id)?,
+            span_id: parse_span(span_id)?,
+            flags: u8::from_str_radix(flags, 16)?,
+        })
+    }
+}
+
+/// Decodes headers in the W3C Trace Context format
+fn decode_w3c(
+    collector: Option<&Arc<dyn TraceCollector>>,
+    headers: &HeaderMap,
+) -> Result<SpanContext, ContextError> {
+    let decoded: W3CTraceParent =
+        required_header(headers, W3C_TRACEPARENT_HEADER, FromStr::from_str)?;
+
+    // Multiple tracestate headers are equivalent to a single, comma
+    // separated header.
+    let trace_state = headers
+        .get_all(W3C_TRACESTATE_HEADER)
+        .iter()
+        .map(|value| {
+            value.to_str().map_err(|source| ContextError::InvalidUtf8 {
+                header: W3C_TRACESTATE_HEADER.to_string(),
+                source,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .join(",");
+    let trace_state = match trace_state.trim() {
+        "" => None,
+        v => Some(Arc::from(v)),
+    };
+
+    // Links cannot be specified via the HTTP header
+    let links = vec![];
+
+    Ok(SpanContext {
+        trace_id: decoded.trace_id,
+        parent_span_id: None,
+        span_id: decoded.span_id,
+        links,
+        collector: collector.cloned(),
+        sampled: decoded.flags & 0x01 == 1,
+        trace_state,
     })
 }
 
@@ -312,6 +492,99 @@ pub fn format_jaeger_trace_context(span_context: &SpanContext) -> String {
     )
 }
 
+/// Format span context as a W3C Trace Context `traceparent` header value.
+///
+/// You may use [`TraceHeaderParser`] to parse the resulting value.
+pub fn format_w3c_traceparent(span_context: &SpanContext) -> String {
+    format!(
+        "00-{:032x}-{:016x}-{:02x}",
+        span_context.trace_id.get(),
+        span_context.span_id.get(),
+        u8::from(span_context.sampled),
+    )
+}
+
+/// Formats a [`SpanContext`] as request headers for propagation to downstream
+/// services, in each of the configured [`TraceContextFormat`]s.
+#[derive(Debug, Clone)]
+pub struct TraceHeaderFormatter {
+    /// header used for the Jaeger trace context
+    jaeger_trace_context_header_name: Arc<str>,
+    /// emitted trace context formats
+    formats: Arc<[TraceContextFormat]>,
+}
+
+impl Default for TraceHeaderFormatter {
+    fn default() -> Self {
+        Self {
+            jaeger_trace_context_header_name: DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME.into(),
+            formats: DEFAULT_TRACE_CONTEXT_FORMATS.into(),
+        }
+    }
+}
+
+impl TraceHeaderFormatter {
+    /// Create a new formatter emitting the [`DEFAULT_TRACE_CONTEXT_FORMATS`],
+    /// using the default Jaeger trace header name.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// specify a header for jaeger_trace_context_header_name
+    ///
+    /// For example, 'uber-trace-id'
+    pub fn with_jaeger_trace_context_header_name(mut self, name: impl AsRef<str>) -> Self {
+        self.jaeger_trace_context_header_name = name.as_ref().into();
+        self
+    }
+
+    /// specify the emitted trace context formats
+    pub fn with_formats(mut self, formats: impl IntoIterator<Item = TraceContextFormat>) -> Self {
+        self.formats = formats.into_iter().collect();
+        self
+    }
+
+    /// Return the (header name, header value) pairs describing
+    /// `span_context` in all the configured formats.
+    pub fn headers(&self, span_context: &SpanContext) -> Vec<(&str, String)> {
+        let mut headers = Vec::with_capacity(self.formats.len());
+
+        for format in self.formats.iter() {
+            match format {
+                TraceContextFormat::Jaeger => headers.push((
+                    self.jaeger_trace_context_header_name.as_ref(),
+                    format_jaeger_trace_context(span_context),
+                )),
+                TraceContextFormat::B3 => {
+                    headers.push((
+                        B3_TRACE_ID_HEADER,
+                        format!("{:032x}", span_context.trace_id.get()),
+                    ));
+                    headers.push((
+                        B3_SPAN_ID_HEADER,
+                        format!("{:016x}", span_context.span_id.get()),
+                    ));
+                    if let Some(parent) = span_context.parent_span_id {
+                        headers.push((B3_PARENT_SPAN_ID_HEADER, format!("{:016x}", parent.get())));
+                    }
+                    headers.push((
+                        B3_SAMPLED_HEADER,
+                        u8::from(span_context.sampled).to_string(),
+                    ));
+                }
+                TraceContextFormat::W3C => {
+                    headers.push((W3C_TRACEPARENT_HEADER, format_w3c_traceparent(span_context)));
+                    if let Some(state) = &span_context.trace_state {
+                        headers.push((W3C_TRACESTATE_HEADER, state.to_string()));
+                    }
+                }
+            }
+        }
+
+        headers
+    }
+}
+
 /// A simple way to format an external span context in a jaeger-like fashion, e.g. for logging.
 pub trait RequestLogContextExt {
     /// Format context.
@@ -561,6 +834,7 @@ mod tests {
             links: vec![],
             collector: Some(Arc::clone(&collector)),
             sampled: true,
+            trace_state: None,
         });
 
         // w/ parent span ID
@@ -571,6 +845,7 @@ mod tests {
             links: vec![],
             collector: Some(Arc::clone(&collector)),
             sampled: true,
+            trace_state: None,
         });
 
         // not sampled
@@ -581,6 +856,178 @@ mod tests {
             links: vec![],
             collector: Some(Arc::clone(&collector)),
             sampled: false,
+            trace_state: None,
         });
     }
+
+    #[test]
+    fn test_decode_w3c() {
+        let parser = TraceHeaderParser::new();
+        let collector: Arc<dyn TraceCollector> = Arc::new(trace::LogTraceCollector::new());
+
+        let mut headers = HeaderMap::new();
+
+        // Sampled
+        headers.insert(
+            W3C_TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        );
+        let span = parser.parse(Some(&collector), &headers).unwrap().unwrap();
+
+        assert_eq!(span.trace_id.get(), 0x0af7651916cd43dd8448eb211c80319c);
+        assert_eq!(span.span_id.get(), 0xb7ad6b7169203331);
+        assert!(span.parent_span_id.is_none());
+        assert!(span.sampled);
+        assert!(span.trace_state.is_none());
+
+        // Not sampled, with trace state split across headers
+        headers.insert(
+            W3C_TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00"),
+        );
+        headers.append(
+            W3C_TRACESTATE_HEADER,
+            HeaderValue::from_static("rojo=00f067aa0ba902b7"),
+        );
+        headers.append(
+            W3C_TRACESTATE_HEADER,
+            HeaderValue::from_static("congo=t61rcWkgMzE"),
+        );
+        let span = parser.parse(Some(&collector), &headers).unwrap().unwrap();
+
+        assert!(!span.sampled);
+        assert_eq!(
+            span.trace_state.as_deref(),
+            Some("rojo=00f067aa0ba902b7,congo=t61rcWkgMzE")
+        );
+
+        // Future versions may append fields
+        headers.insert(
+            W3C_TRACEPARENT_HEADER,
+            HeaderValue::from_static(
+                "cc-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-what-the-future-holds",
+            ),
+        );
+        let span = parser.parse(Some(&collector), &headers).unwrap().unwrap();
+        assert!(span.sampled);
+
+        for invalid in [
+            "invalid",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c8031-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-+1",
+        ] {
+            headers.insert(W3C_TRACEPARENT_HEADER, HeaderValue::from_static(invalid));
+            assert_eq!(
+                parser
+                    .parse(Some(&collector), &headers)
+                    .unwrap_err()
+                    .to_string(),
+                "error decoding header 'traceparent': Expected \"version-trace-id-parent-id-trace-flags\"",
+                "{invalid}"
+            );
+        }
+
+        // All zero IDs are invalid
+        headers.insert(
+            W3C_TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-00000000000000000000000000000000-b7ad6b7169203331-01"),
+        );
+        assert_eq!(
+            parser
+                .parse(Some(&collector), &headers)
+                .unwrap_err()
+                .to_string(),
+            "error decoding header 'traceparent': value cannot be 0"
+        );
+    }
+
+    #[test]
+    fn test_formats_precedence() {
+        let collector: Arc<dyn TraceCollector> = Arc::new(trace::LogTraceCollector::new());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("uber-trace-id", HeaderValue::from_static("1:2:3:1"));
+        headers.insert(
+            W3C_TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-0000000000000000000000000000000a-000000000000000b-01"),
+        );
+
+        // Jaeger takes precedence by default
+        let parser =
+            TraceHeaderParser::new().with_jaeger_trace_context_header_name("uber-trace-id");
+        let span = parser.parse(Some(&collector), &headers).unwrap().unwrap();
+        assert_eq!(span.trace_id.get(), 1);
+
+        // Unless W3C is configured first
+        let parser = parser.with_formats([TraceContextFormat::W3C, TraceContextFormat::Jaeger]);
+        let span = parser.parse(Some(&collector), &headers).unwrap().unwrap();
+        assert_eq!(span.trace_id.get(), 0xa);
+
+        // Formats not configured are ignored
+        let parser = parser.with_formats([TraceContextFormat::B3]);
+        assert!(parser.parse(Some(&collector), &headers).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_trace_context_format_from_str() {
+        assert_eq!(
+            " W3C".parse::<TraceContextFormat>().unwrap(),
+            TraceContextFormat::W3C
+        );
+        for format in DEFAULT_TRACE_CONTEXT_FORMATS {
+            assert_eq!(
+                format.to_string().parse::<TraceContextFormat>().unwrap(),
+                format
+            );
+        }
+        assert!("zipkin".parse::<TraceContextFormat>().is_err());
+    }
+
+    #[test]
+    fn test_trace_header_formatter_roundtrip() {
+        let collector: Arc<dyn TraceCollector> = Arc::new(trace::LogTraceCollector::new());
+
+        let orig = SpanContext {
+            trace_id: TraceId::new(1234).unwrap(),
+            span_id: SpanId::new(5678).unwrap(),
+            parent_span_id: Some(SpanId::new(1357).unwrap()),
+            links: vec![],
+            collector: Some(Arc::clone(&collector)),
+            sampled: true,
+            trace_state: Some(Arc::from("vendor=value")),
+        };
+
+        for format in DEFAULT_TRACE_CONTEXT_FORMATS {
+            let formatter = TraceHeaderFormatter::new().with_formats([format]);
+            let parser = TraceHeaderParser::new()
+                .with_jaeger_trace_context_header_name(DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME)
+                .with_formats([format]);
+
+            let mut headers = HeaderMap::new();
+            for (name, value) in formatter.headers(&orig) {
+                headers.insert(
+                    http::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                    HeaderValue::from_str(&value).unwrap(),
+                );
+            }
+            let parsed = parser.parse(Some(&collector), &headers).unwrap().unwrap();
+
+            assert_eq!(parsed.trace_id, orig.trace_id, "{format}");
+            assert_eq!(parsed.span_id, orig.span_id, "{format}");
+            assert_eq!(parsed.sampled, orig.sampled, "{format}");
+            match format {
+                // The W3C format does not propagate the parent span ID
+                TraceContextFormat::W3C => {
+                    assert_eq!(parsed.parent_span_id, None);
+                    assert_eq!(parsed.trace_state, orig.trace_state);
+                }
+                _ => {
+                    assert_eq!(parsed.parent_span_id, orig.parent_span_id, "{format}");
+                    assert_eq!(parsed.trace_state, None);
+                }
+            }
+        }
+    }
 }